@@ -0,0 +1,92 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// A `System` allocator wrapper that counts allocations for `guardrails bench`
+/// and `scan --timing --memory`.
+///
+/// The binary installs it as the global allocator; library users that don't
+/// simply see zeroed counters.
+pub struct CountingAlloc;
+
+static CURRENT_BYTES: AtomicU64 = AtomicU64::new(0);
+static PEAK_BYTES: AtomicU64 = AtomicU64::new(0);
+static INSTALLED: AtomicU64 = AtomicU64::new(0);
+
+thread_local! {
+    /// (allocation count, allocated bytes) on this thread.
+    static THREAD_COUNTS: Cell<(u64, u64)> = const { Cell::new((0, 0)) };
+}
+
+/// Allocation counters at a point in time, for the current thread.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AllocSnapshot {
+    pub allocations: u64,
+    pub bytes: u64,
+}
+
+impl AllocSnapshot {
+    /// Allocations made between `earlier` and `self`.
+    pub fn since(self, earlier: AllocSnapshot) -> AllocSnapshot {
+        AllocSnapshot {
+            allocations: self.allocations.saturating_sub(earlier.allocations),
+            bytes: self.bytes.saturating_sub(earlier.bytes),
+        }
+    }
+}
+
+fn record_alloc(size: usize) {
+    let size = size as u64;
+    let _ = THREAD_COUNTS.try_with(|c| {
+        let (n, bytes) = c.get();
+        c.set((n + 1, bytes + size));
+    });
+    let current = CURRENT_BYTES.fetch_add(size, Ordering::Relaxed) + size;
+    PEAK_BYTES.fetch_max(current, Ordering::Relaxed);
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        INSTALLED.store(1, Ordering::Relaxed);
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        CURRENT_BYTES.fetch_sub(layout.size() as u64, Ordering::Relaxed);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            CURRENT_BYTES.fetch_sub(layout.size() as u64, Ordering::Relaxed);
+            record_alloc(new_size);
+        }
+        new_ptr
+    }
+}
+
+/// Whether `CountingAlloc` is the active global allocator.
+pub fn is_active() -> bool {
+    INSTALLED.load(Ordering::Relaxed) != 0
+}
+
+/// Allocation counters for the calling thread.
+pub fn thread_snapshot() -> AllocSnapshot {
+    let (allocations, bytes) = THREAD_COUNTS.try_with(|c| c.get()).unwrap_or_default();
+    AllocSnapshot { allocations, bytes }
+}
+
+/// Highest number of live heap bytes seen since start or the last reset.
+pub fn peak_bytes() -> u64 {
+    PEAK_BYTES.load(Ordering::Relaxed)
+}
+
+/// Reset the peak to the current live heap size.
+pub fn reset_peak() {
+    PEAK_BYTES.store(CURRENT_BYTES.load(Ordering::Relaxed), Ordering::Relaxed);
+}
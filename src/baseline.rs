@@ -0,0 +1,103 @@
+use crate::fingerprint;
+use crate::rules::Violation;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Default baseline file written by `guardrails baseline` and read by `scan --baseline`.
+pub const DEFAULT_BASELINE_FILE: &str = ".guardrails-baseline.json";
+
+/// A violation recorded in a baseline, identified by its fingerprint so it
+/// still matches after unrelated edits move it to another line.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BaselineViolation {
+    pub rule_id: String,
+    pub file: PathBuf,
+    pub fingerprint: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct BaselineFile {
+    /// Absent in baselines that only hold ratchet counts.
+    #[serde(default)]
+    violations: Vec<BaselineViolation>,
+}
+
+/// Baseline entries for `violations`, in the same order.
+pub fn entries(violations: &[Violation]) -> Vec<BaselineViolation> {
+    violations
+        .iter()
+        .zip(fingerprint::fingerprints(violations))
+        .map(|(v, fingerprint)| BaselineViolation {
+            rule_id: v.rule_id.clone(),
+            file: v.file.clone(),
+            fingerprint,
+        })
+        .collect()
+}
+
+/// Fingerprints of the violations recorded in the baseline file at `path`.
+pub fn load(path: &Path) -> Result<HashSet<String>, String> {
+    let text = fs::read_to_string(path)
+        .map_err(|e| format!("failed to read baseline {}: {}", path.display(), e))?;
+    let file: BaselineFile = serde_json::from_str(&text)
+        .map_err(|e| format!("failed to parse baseline {}: {}", path.display(), e))?;
+    Ok(file.violations.into_iter().map(|v| v.fingerprint).collect())
+}
+
+/// Drop the violations whose fingerprint is in `baseline`; returns how many were dropped.
+pub fn suppress(violations: &mut Vec<Violation>, baseline: &HashSet<String>) -> usize {
+    take(violations, baseline).len()
+}
+
+/// Remove the violations whose fingerprint is in `baseline` and return them.
+pub fn take(violations: &mut Vec<Violation>, baseline: &HashSet<String>) -> Vec<Violation> {
+    let fingerprints = fingerprint::fingerprints(violations);
+    let (known, new): (Vec<_>, Vec<_>) = violations
+        .drain(..)
+        .zip(fingerprints)
+        .partition(|(_, f)| baseline.contains(f));
+    violations.extend(new.into_iter().map(|(v, _)| v));
+    known.into_iter().map(|(v, _)| v).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn baselined_violations_are_suppressed_after_moving() {
+        let recorded = entries(&[Violation::test("no-console", "src/a.ts").at(3, "console.log(a);"), Violation::test("no-console", "src/a.ts").at(9, "console.log(b);")]);
+        let baseline: HashSet<String> = recorded.into_iter().map(|e| e.fingerprint).collect();
+
+        // Lines shifted by an unrelated edit, and one new violation
+        let mut violations = vec![
+            Violation::test("no-console", "src/a.ts").at(5, "console.log(a);"),
+            Violation::test("no-console", "src/a.ts").at(8, "console.log(c);"),
+            Violation::test("no-console", "src/a.ts").at(11, "console.log(b);"),
+        ];
+        assert_eq!(suppress(&mut violations, &baseline), 2);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].source_line.as_deref(), Some("console.log(c);"));
+    }
+
+    #[test]
+    fn a_second_copy_of_a_baselined_line_is_new() {
+        let baseline: HashSet<String> = entries(&[Violation::test("no-console", "src/a.ts").at(3, "console.log(a);")])
+            .into_iter()
+            .map(|e| e.fingerprint)
+            .collect();
+        let mut violations = vec![Violation::test("no-console", "src/a.ts").at(3, "console.log(a);"), Violation::test("no-console", "src/a.ts").at(7, "console.log(a);")];
+        assert_eq!(suppress(&mut violations, &baseline), 1);
+        assert_eq!(violations[0].line, Some(7));
+    }
+
+    #[test]
+    fn ratchet_only_baselines_load_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(DEFAULT_BASELINE_FILE);
+        fs::write(&path, r#"{"entries": [{"rule_id": "r", "pattern": "p", "count": 2}], "files_scanned": 4}"#).unwrap();
+        assert!(load(&path).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,244 @@
+use crate::scan::{self, ScanError};
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Version of the bundle layout, bumped when it changes incompatibly.
+pub const BUNDLE_FORMAT: u32 = 1;
+
+/// Environment variable holding the signing key, unless `--key-env` names another.
+pub const KEY_ENV: &str = "GUARDRAILS_BUNDLE_KEY";
+
+type HmacSha256 = Hmac<Sha256>;
+
+#[derive(Debug)]
+pub enum BundleError {
+    Scan(ScanError),
+    Read(io::Error),
+    /// The file isn't a policy bundle; the reason.
+    Invalid(String),
+    /// The signature doesn't match the bundle's contents under this key.
+    Signature,
+}
+
+impl fmt::Display for BundleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BundleError::Scan(e) => write!(f, "{}", e),
+            BundleError::Read(e) => write!(f, "failed to read bundle: {}", e),
+            BundleError::Invalid(reason) => write!(f, "not a guardrails policy bundle: {}", reason),
+            BundleError::Signature => write!(
+                f,
+                "bundle signature doesn't match (signed with another key, or modified after signing)"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for BundleError {}
+
+/// Result of `guardrails bundle verify`: the approved policy against the
+/// config the repo resolves to.
+#[derive(Debug, Serialize)]
+pub struct Verification {
+    pub policy_version: Option<String>,
+    /// Fingerprint of the approved policy.
+    pub expected: String,
+    /// Fingerprint of the repo's effective config.
+    pub actual: String,
+    /// Rule ids only the repo's config has.
+    pub added: Vec<String>,
+    /// Rule ids only the approved policy has.
+    pub removed: Vec<String>,
+    /// Rule ids whose settings differ.
+    pub changed: Vec<String>,
+}
+
+impl Verification {
+    pub fn matches(&self) -> bool {
+        self.expected == self.actual
+    }
+}
+
+/// Package the effective config at `config_path`, its fingerprint and the
+/// rules' docs into a bundle signed with `key` (HMAC-SHA256).
+pub fn export(config_path: &Path, policy_version: Option<&str>, key: &[u8]) -> Result<Value, BundleError> {
+    let config = scan::effective_config(config_path).map_err(BundleError::Scan)?;
+    let rules = scan::list_rules(config_path).map_err(BundleError::Scan)?;
+    let payload = json!({
+        "format": BUNDLE_FORMAT,
+        "guardrails_version": env!("CARGO_PKG_VERSION"),
+        "policy_version": policy_version,
+        "fingerprint": fingerprint(&config),
+        "config": config,
+        "rules": rules,
+    });
+    let signature = sign(&payload, key);
+    Ok(json!({ "payload": payload, "signature": signature }))
+}
+
+/// Check the bundle at `bundle_path` against `key`, then compare its policy
+/// with the effective config at `config_path`.
+pub fn verify(bundle_path: &Path, config_path: &Path, key: &[u8]) -> Result<Verification, BundleError> {
+    let text = fs::read_to_string(bundle_path).map_err(BundleError::Read)?;
+    let bundle: Value = serde_json::from_str(&text).map_err(|e| BundleError::Invalid(e.to_string()))?;
+    let payload = bundle
+        .get("payload")
+        .ok_or_else(|| BundleError::Invalid("missing `payload`".into()))?;
+    let signature = bundle
+        .get("signature")
+        .and_then(Value::as_str)
+        .ok_or_else(|| BundleError::Invalid("missing `signature`".into()))?;
+    check_signature(payload, signature, key)?;
+
+    let format = payload.get("format").and_then(Value::as_u64);
+    if format != Some(u64::from(BUNDLE_FORMAT)) {
+        return Err(BundleError::Invalid(format!(
+            "bundle format {} isn't supported by this guardrails (expects {})",
+            format.map_or_else(|| "?".to_string(), |f| f.to_string()),
+            BUNDLE_FORMAT
+        )));
+    }
+    let approved = payload
+        .get("config")
+        .ok_or_else(|| BundleError::Invalid("missing `config`".into()))?;
+    let config = scan::effective_config(config_path).map_err(BundleError::Scan)?;
+
+    let approved_rules = rules_by_id(approved);
+    let rules = rules_by_id(&config);
+    Ok(Verification {
+        policy_version: payload.get("policy_version").and_then(Value::as_str).map(String::from),
+        expected: fingerprint(approved),
+        actual: fingerprint(&config),
+        added: rules.keys().filter(|id| !approved_rules.contains_key(*id)).cloned().collect(),
+        removed: approved_rules.keys().filter(|id| !rules.contains_key(*id)).cloned().collect(),
+        changed: rules
+            .iter()
+            .filter(|(id, rule)| approved_rules.get(*id).is_some_and(|approved| approved != *rule))
+            .map(|(id, _)| id.clone())
+            .collect(),
+    })
+}
+
+/// `sha256:` and the hex SHA-256 of `config`'s canonical JSON.
+pub fn fingerprint(config: &Value) -> String {
+    format!("sha256:{}", hex(&Sha256::digest(canonical(config))))
+}
+
+/// JSON with object keys sorted (`serde_json::Value` keeps them in a
+/// `BTreeMap`) and no whitespace, so equal values give equal bytes.
+fn canonical(value: &Value) -> Vec<u8> {
+    serde_json::to_vec(value).expect("JSON values serialize")
+}
+
+fn sign(payload: &Value, key: &[u8]) -> String {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC takes keys of any length");
+    mac.update(&canonical(payload));
+    format!("hmac-sha256:{}", hex(&mac.finalize().into_bytes()))
+}
+
+fn check_signature(payload: &Value, signature: &str, key: &[u8]) -> Result<(), BundleError> {
+    let bytes = signature
+        .strip_prefix("hmac-sha256:")
+        .and_then(unhex)
+        .ok_or_else(|| BundleError::Invalid("`signature` isn't an hmac-sha256 signature".into()))?;
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC takes keys of any length");
+    mac.update(&canonical(payload));
+    mac.verify_slice(&bytes).map_err(|_| BundleError::Signature)
+}
+
+fn rules_by_id(config: &Value) -> BTreeMap<String, &Value> {
+    config
+        .get("rule")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|rule| Some((rule.get("id")?.as_str()?.to_string(), rule)))
+        .collect()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn unhex(text: &str) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(text.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"
+[guardrails]
+
+[[rule]]
+id = "no-eval"
+type = "banned-pattern"
+pattern = "eval("
+severity = "error"
+rationale = "eval runs arbitrary code"
+
+[[rule]]
+id = "no-console"
+type = "banned-pattern"
+pattern = "console.log("
+"#;
+
+    #[test]
+    fn exported_bundle_verifies_until_the_config_drifts() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = dir.path().join("guardrails.toml");
+        fs::write(&config, CONFIG).unwrap();
+        let bundle_path = dir.path().join("bundle.json");
+
+        let bundle = export(&config, Some("2026.10"), b"secret").unwrap();
+        assert_eq!(bundle["payload"]["rules"][0]["rationale"], "eval runs arbitrary code");
+        fs::write(&bundle_path, serde_json::to_string_pretty(&bundle).unwrap()).unwrap();
+
+        let verification = verify(&bundle_path, &config, b"secret").unwrap();
+        assert!(verification.matches());
+        assert_eq!(verification.policy_version.as_deref(), Some("2026.10"));
+
+        let drifted = CONFIG.replace("console.log(", "console.").replace("severity = \"error\"\n", "")
+            + "\n[[rule]]\nid = \"no-debugger\"\ntype = \"banned-pattern\"\npattern = \"debugger\"\n";
+        fs::write(&config, drifted).unwrap();
+        let verification = verify(&bundle_path, &config, b"secret").unwrap();
+        assert!(!verification.matches());
+        assert_eq!(verification.added, ["no-debugger"]);
+        assert!(verification.removed.is_empty());
+        assert_eq!(verification.changed, ["no-console", "no-eval"]);
+    }
+
+    #[test]
+    fn wrong_key_or_tampered_bundle_fails_the_signature() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = dir.path().join("guardrails.toml");
+        fs::write(&config, CONFIG).unwrap();
+        let bundle_path = dir.path().join("bundle.json");
+
+        let mut bundle = export(&config, None, b"secret").unwrap();
+        fs::write(&bundle_path, bundle.to_string()).unwrap();
+        assert!(matches!(verify(&bundle_path, &config, b"other"), Err(BundleError::Signature)));
+
+        // Loosening the approved policy in place invalidates the signature
+        bundle["payload"]["config"]["rule"][0]["severity"] = json!("warning");
+        fs::write(&bundle_path, bundle.to_string()).unwrap();
+        assert!(matches!(verify(&bundle_path, &config, b"secret"), Err(BundleError::Signature)));
+
+        fs::write(&bundle_path, "{}").unwrap();
+        assert!(matches!(verify(&bundle_path, &config, b"secret"), Err(BundleError::Invalid(_))));
+    }
+}
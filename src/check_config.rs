@@ -0,0 +1,579 @@
+use crate::cli::toml_config::{GuardrailsSection, OutputSection, OverridesTable, PathsSection, RuleOverride, TomlConfig, TomlRule};
+use crate::config::{NearCondition, RatchetBudget, RatchetGroup, RuleExamples};
+use crate::rules::factory::{self, FactoryError};
+use crate::rules::RuleBuildError;
+use crate::scan::{self, ScanError, ScanOptions, Scanner};
+use serde::de::{self, Deserialize, Deserializer, Visitor};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use toml_edit::{ImDocument, Item, Table, TableLike};
+
+/// How serious a config problem is: errors stop a scan, warnings don't.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Level {
+    Error,
+    Warning,
+}
+
+/// One problem in a config file, at the TOML it comes from when there is one.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Diagnostic {
+    pub level: Level,
+    pub message: String,
+    /// 1-based line of the offending TOML.
+    pub line: Option<usize>,
+    /// 1-based column, in characters.
+    pub column: Option<usize>,
+    /// How many characters of the line to underline.
+    #[serde(skip)]
+    pub width: usize,
+}
+
+/// Everything `guardrails check-config` found in one config file.
+#[derive(Debug, Serialize)]
+pub struct Report {
+    pub path: PathBuf,
+    pub diagnostics: Vec<Diagnostic>,
+    pub rules_checked: usize,
+    /// The config's text, to show diagnostics in context.
+    #[serde(skip)]
+    pub source: String,
+}
+
+impl Report {
+    pub fn errors(&self) -> usize {
+        self.diagnostics.iter().filter(|d| d.level == Level::Error).count()
+    }
+
+    pub fn warnings(&self) -> usize {
+        self.diagnostics.iter().filter(|d| d.level == Level::Warning).count()
+    }
+}
+
+/// Validate the config at `path` without scanning: syntax, unknown rule
+/// types, missing and invalid fields, bad regexes and globs, duplicate rule
+/// ids and keys guardrails ignores. Unlike loading the config for a scan,
+/// this reports every problem it finds, each at its line and column.
+pub fn check_config(path: &Path) -> io::Result<Report> {
+    let source = fs::read_to_string(path)?;
+    let (mut diagnostics, rules_checked) = check_text(&source);
+    if !diagnostics.iter().any(|d| d.level == Level::Error) {
+        // Presets, plugins, included rule files and waivers only resolve
+        // from the file itself
+        if let Err(e) = Scanner::from_config(path, ScanOptions::default()) {
+            diagnostics.push(Diagnostic {
+                level: Level::Error,
+                message: e.to_string(),
+                line: None,
+                column: None,
+                width: 0,
+            });
+        }
+    }
+    Ok(Report {
+        path: path.to_path_buf(),
+        diagnostics,
+        rules_checked,
+        source,
+    })
+}
+
+/// Check a config's text on its own, returning the problems found and how
+/// many `[[rule]]`s were checked.
+fn check_text(source: &str) -> (Vec<Diagnostic>, usize) {
+    let mut checker = Checker {
+        source,
+        diagnostics: Vec::new(),
+    };
+    let doc = match ImDocument::parse(source) {
+        Ok(doc) => doc,
+        Err(e) => {
+            checker.push(Level::Error, e.span(), e.message().trim_end().to_string());
+            return (checker.diagnostics, 0);
+        }
+    };
+    let rules: Vec<&Table> = doc
+        .get("rule")
+        .and_then(Item::as_array_of_tables)
+        .map(|rules| rules.iter().collect())
+        .unwrap_or_default();
+    let ranges = rule_ranges(source, doc.as_table(), &rules);
+
+    checker.check_keys(doc.as_table());
+
+    // Parse everything but the rules in one go, then each rule on its own,
+    // so one bad rule doesn't hide the rest
+    let rest = blank(source, |i| !ranges.iter().any(|r| r.contains(&i)));
+    if let Err(e) = toml::from_str::<TomlConfig>(&rest) {
+        checker.push(Level::Error, e.span(), e.message().to_string());
+    }
+    let mut first_seen: HashMap<String, usize> = HashMap::new();
+    for (table, range) in rules.iter().zip(&ranges) {
+        let text = blank(source, |i| range.contains(&i));
+        let rule = match toml::from_str::<RuleOnly>(&text) {
+            Ok(parsed) => match parsed.rule.into_iter().next() {
+                Some(rule) => rule,
+                None => continue,
+            },
+            Err(e) => {
+                checker.push(Level::Error, e.span(), e.message().to_string());
+                continue;
+            }
+        };
+        checker.check_rule(table, rule, &mut first_seen);
+    }
+
+    checker.check_globs(&doc);
+    let mut diagnostics = checker.diagnostics;
+    diagnostics.sort_by_key(|d| (d.line.is_none(), d.line, d.column));
+    (diagnostics, rules.len())
+}
+
+#[derive(serde::Deserialize)]
+struct RuleOnly {
+    #[serde(default)]
+    rule: Vec<TomlRule>,
+}
+
+struct Checker<'a> {
+    source: &'a str,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Checker<'_> {
+    fn push(&mut self, level: Level, span: Option<Range<usize>>, message: String) {
+        let (line, column, width) = match span {
+            Some(span) => {
+                let (line, column, width) = locate(self.source, span);
+                (Some(line), Some(column), width)
+            }
+            None => (None, None, 0),
+        };
+        self.diagnostics.push(Diagnostic {
+            level,
+            message,
+            line,
+            column,
+            width,
+        });
+    }
+
+    /// Warn about keys no guardrails struct reads, which a typo or a key
+    /// in the wrong section would otherwise silently drop.
+    fn check_keys(&mut self, root: &Table) {
+        self.unused_keys(root, &fields::<TomlConfig>(), "the top level");
+        let sections: [(&str, &[&str], &str); 4] = [
+            ("guardrails", &fields::<GuardrailsSection>(), "[guardrails]"),
+            ("paths", &fields::<PathsSection>(), "[paths]"),
+            ("output", &fields::<OutputSection>(), "[output]"),
+            ("overrides", &fields::<OverridesTable>(), "[overrides]"),
+        ];
+        for (key, known, context) in sections {
+            if let Some(table) = root.get(key).and_then(Item::as_table_like) {
+                self.unused_keys(table, known, context);
+            }
+        }
+        for (key, known, context) in [
+            ("override", fields::<RuleOverride>(), "[[override]]"),
+            ("ratchet_group", fields::<RatchetGroup>(), "[[ratchet_group]]"),
+        ] {
+            for table in root.get(key).map(tables).unwrap_or_default() {
+                self.unused_keys(table, &known, context);
+            }
+        }
+        for table in root.get("rule").map(tables).unwrap_or_default() {
+            self.rule_keys(table, &rule_label(table));
+        }
+    }
+
+    fn rule_keys(&mut self, rule: &dyn TableLike, context: &str) {
+        self.unused_keys(rule, &fields::<TomlRule>(), context);
+        for key in ["near", "not_near"] {
+            for table in rule.get(key).map(tables).unwrap_or_default() {
+                self.unused_keys(table, &fields::<NearCondition>(), &format!("`{}` of {}", key, context));
+            }
+        }
+        for table in rule.get("examples").map(tables).unwrap_or_default() {
+            self.unused_keys(table, &fields::<RuleExamples>(), &format!("`examples` of {}", context));
+        }
+        for table in rule.get("budgets").map(tables).unwrap_or_default() {
+            self.unused_keys(table, &fields::<RatchetBudget>(), &format!("`budgets` of {}", context));
+        }
+        for key in ["all_of", "any_of", "none_of"] {
+            for table in rule.get(key).map(tables).unwrap_or_default() {
+                self.rule_keys(table, &format!("`{}` of {}", key, context));
+            }
+        }
+    }
+
+    fn unused_keys(&mut self, table: &dyn TableLike, known: &[&str], context: &str) {
+        for (key, _) in table.iter() {
+            if known.contains(&key) {
+                continue;
+            }
+            let mut message = format!("unused key `{}` in {}", key, context);
+            if let Some(close) = closest(key, known.iter().copied()) {
+                message.push_str(&format!(" (did you mean `{}`?)", close));
+            }
+            let span = table.get_key_value(key).and_then(|(k, _)| k.span());
+            self.push(Level::Warning, span, message);
+        }
+    }
+
+    /// Check one parsed `[[rule]]`: its id, and that it builds.
+    fn check_rule(&mut self, table: &Table, rule: TomlRule, first_seen: &mut HashMap<String, usize>) {
+        if rule.id.is_empty() {
+            self.push(Level::Error, header_span(self.source, table), "rule has no `id`".to_string());
+            return;
+        }
+        let id_span = value_span(table, "id");
+        let line = id_span.clone().map_or(0, |span| locate(self.source, span).0);
+        if let Some(first) = first_seen.get(&rule.id) {
+            let message = format!("duplicate rule id `{}` (first defined on line {})", rule.id, first);
+            self.push(Level::Error, id_span, message);
+        } else {
+            first_seen.insert(rule.id.clone(), line);
+        }
+
+        let err = match Scanner::builder().rule(rule).build() {
+            Ok(_) => return,
+            Err(e) => e,
+        };
+        let (keys, message): (&[&str], String) = match err {
+            ScanError::RuleFactory(FactoryError::UnknownRuleType(ref t)) => {
+                let mut message = format!("unknown rule type `{}`", t);
+                if let Some(close) = closest(t, factory::RULE_TYPES.iter().copied()) {
+                    message.push_str(&format!(" (did you mean `{}`?)", close));
+                }
+                (&["type"], message)
+            }
+            ScanError::RuleFactory(FactoryError::FeatureDisabled(..)) => (&["type"], err.to_string()),
+            ScanError::RuleFactory(FactoryError::BuildError(ref e)) => {
+                let keys: &[&str] = match e {
+                    RuleBuildError::InvalidRegex(..) => REGEX_KEYS,
+                    RuleBuildError::InvalidGlob(..) => GLOB_KEYS,
+                    RuleBuildError::MissingField(..) | RuleBuildError::MissingOneOf(..) => &[],
+                    RuleBuildError::InvalidField(_, field, _) => std::slice::from_ref(field),
+                };
+                (keys, e.to_string())
+            }
+            ScanError::GlobParse(ref e) => (GLOB_KEYS, format!("invalid glob: {}", e)),
+            // Not about this rule; resolving the whole config reports it
+            _ => return,
+        };
+        let span = keys
+            .iter()
+            .find_map(|key| value_span(table, key))
+            .or_else(|| header_span(self.source, table));
+        self.push(Level::Error, span, message);
+    }
+
+    /// Check the config-wide glob lists, each glob at its own position.
+    fn check_globs(&mut self, doc: &ImDocument<&str>) {
+        let lists = [
+            ("guardrails", "include"),
+            ("guardrails", "exclude"),
+            ("guardrails", "test_globs"),
+            ("paths", "third_party"),
+            ("paths", "first_party"),
+        ];
+        for (section, key) in lists {
+            let Some(array) = doc.get(section).and_then(|s| s.get(key)).and_then(Item::as_array) else {
+                continue;
+            };
+            for value in array.iter() {
+                let Some(glob) = value.as_str() else { continue };
+                if let Err(e) = scan::compile_glob_set(&[glob.to_string()]) {
+                    self.push(Level::Error, value.span(), format!("invalid glob in [{}] {}: {}", section, key, e));
+                }
+            }
+        }
+    }
+}
+
+/// Keys whose values a rule compiles as regexes, most likely culprit first.
+const REGEX_KEYS: &[&str] = &[
+    "pattern",
+    "patterns",
+    "condition_pattern",
+    "file_contains",
+    "file_not_contains",
+    "near",
+    "not_near",
+    "allowlist",
+    "bypass_marker",
+    "naming",
+];
+
+/// Keys whose values a rule compiles as globs.
+const GLOB_KEYS: &[&str] = &[
+    "glob",
+    "include",
+    "exclude_glob",
+    "exclude",
+    "basename_glob",
+    "required_files",
+    "critical_files",
+    "allowed_dirs",
+    "budgets",
+];
+
+/// The byte range of each rule, from its `[[rule]]` header to the next
+/// top-level table, so comments and subtables such as `[[rule.patterns]]`
+/// go with the rule above them.
+fn rule_ranges(source: &str, root: &Table, rules: &[&Table]) -> Vec<Range<usize>> {
+    let mut starts = Vec::new();
+    table_starts(root, &mut starts);
+    starts.sort_unstable();
+    rules
+        .iter()
+        .map(|rule| {
+            let start = rule.span().map_or(0, |span| span.start);
+            let end = starts.iter().copied().find(|&s| s > start).unwrap_or(source.len());
+            start..end
+        })
+        .collect()
+}
+
+/// Where each table header below `table` starts, looking through implicit
+/// tables (`a` in `[a.b]`) that have no header of their own.
+fn table_starts(table: &Table, starts: &mut Vec<usize>) {
+    for (_, item) in table.iter() {
+        match item {
+            Item::Table(t) if t.is_implicit() => table_starts(t, starts),
+            Item::Table(t) => starts.extend(t.span().map(|s| s.start)),
+            Item::ArrayOfTables(tables) => starts.extend(tables.iter().filter_map(|t| t.span().map(|s| s.start))),
+            _ => {}
+        }
+    }
+}
+
+/// `source` with every byte outside `keep` turned into a space. Newlines
+/// stay, so parse errors in what's left point at the original positions.
+fn blank(source: &str, keep: impl Fn(usize) -> bool) -> String {
+    let bytes: Vec<u8> = source
+        .bytes()
+        .enumerate()
+        .map(|(i, b)| if b == b'\n' || keep(i) { b } else { b' ' })
+        .collect();
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+/// Tables in an item: a table, an inline table, or an array of either.
+fn tables(item: &Item) -> Vec<&dyn TableLike> {
+    if let Some(aot) = item.as_array_of_tables() {
+        return aot.iter().map(|t| t as &dyn TableLike).collect();
+    }
+    if let Some(array) = item.as_array() {
+        return array
+            .iter()
+            .filter_map(|v| v.as_inline_table())
+            .map(|t| t as &dyn TableLike)
+            .collect();
+    }
+    item.as_table_like().into_iter().collect()
+}
+
+fn rule_label(rule: &dyn TableLike) -> String {
+    match rule.get("id").and_then(Item::as_str) {
+        Some(id) => format!("rule `{}`", id),
+        None => "[[rule]]".to_string(),
+    }
+}
+
+fn value_span(table: &Table, key: &str) -> Option<Range<usize>> {
+    table.get(key).and_then(Item::span)
+}
+
+/// The `[[rule]]` header line of a rule table.
+fn header_span(source: &str, table: &Table) -> Option<Range<usize>> {
+    let start = table.span()?.start;
+    let end = source[start..].find('\n').map_or(source.len(), |n| start + n);
+    Some(start..end.max(start + 1).min(source.len()))
+}
+
+/// 1-based line and column of `span`'s start, and how many characters of
+/// that line it covers.
+fn locate(source: &str, span: Range<usize>) -> (usize, usize, usize) {
+    let start = span.start.min(source.len());
+    let before = &source[..start];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map_or(0, |n| n + 1);
+    let column = source[line_start..start].chars().count() + 1;
+    let line_end = source[start..].find('\n').map_or(source.len(), |n| start + n);
+    let end = span.end.clamp(start, line_end);
+    let width = source[start..end].trim_end_matches('\r').chars().count().max(1);
+    (line, column, width)
+}
+
+/// The candidate within two edits of `word`, if any, for "did you mean".
+fn closest<'a>(word: &str, candidates: impl Iterator<Item = &'a str>) -> Option<&'a str> {
+    candidates
+        .map(|c| (edit_distance(word, c), c))
+        .filter(|&(d, _)| d <= 2 && d < word.len())
+        .min_by_key(|&(d, _)| d)
+        .map(|(_, c)| c)
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut prev = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let cost = if ca == cb { prev } else { prev + 1 };
+            prev = row[j + 1];
+            row[j + 1] = cost.min(row[j] + 1).min(prev + 1);
+        }
+    }
+    row[b.len()]
+}
+
+/// The keys a struct deserializes, renames and aliases included, read from
+/// its serde derive.
+fn fields<'de, T: Deserialize<'de>>() -> Vec<&'static str> {
+    let mut fields = Vec::new();
+    let _ = T::deserialize(FieldProbe(&mut fields));
+    fields
+}
+
+/// A deserializer that records the field names a struct asks for and
+/// deserializes nothing.
+struct FieldProbe<'a>(&'a mut Vec<&'static str>);
+
+impl<'de> Deserializer<'de> for FieldProbe<'_> {
+    type Error = de::value::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+        Err(de::Error::custom("field probe"))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.0.extend_from_slice(fields);
+        Err(de::Error::custom("field probe"))
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map enum identifier ignored_any
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn messages(source: &str) -> Vec<(Level, Option<usize>, String)> {
+        check_text(source)
+            .0
+            .into_iter()
+            .map(|d| (d.level, d.line, d.message))
+            .collect()
+    }
+
+    #[test]
+    fn reports_every_rule_problem_at_its_line() {
+        let source = r#"[guardrails]
+include = ["src/**"]
+
+[[rule]]
+id = "no-todo"
+type = "banned-patern"
+message = "x"
+
+[[rule]]
+id = "bad-regex"
+type = "banned-pattern"
+regex = true
+pattern = "foo("
+message = "x"
+
+[[rule]]
+id = "no-todo"
+type = "banned-pattern"
+pattern = "TODO"
+message = "x"
+mesage = "typo"
+"#;
+        let found = messages(source);
+        assert_eq!(found.len(), 4, "{:?}", found);
+        assert_eq!(found[0].1, Some(6));
+        assert!(found[0].2.contains("unknown rule type `banned-patern` (did you mean `banned-pattern`?)"));
+        assert_eq!(found[1].1, Some(13));
+        assert!(found[1].2.contains("invalid regex"));
+        assert_eq!(found[2].1, Some(17));
+        assert!(found[2].2.contains("duplicate rule id `no-todo` (first defined on line 5)"));
+        assert_eq!(found[3].0, Level::Warning);
+        assert_eq!(found[3].1, Some(21));
+        assert!(found[3].2.contains("unused key `mesage`"));
+        assert!(found[3].2.contains("did you mean `message`"));
+    }
+
+    #[test]
+    fn reports_type_errors_per_rule_and_outside_rules() {
+        let source = r#"[guardrails]
+include = ["src/[**"]
+max_file_size = "big"
+
+[[rule]]
+id = "limits"
+type = "file-limits"
+max_lines = "many"
+
+[[rule]]
+type = "banned-pattern"
+pattern = "x"
+message = "x"
+"#;
+        let found = messages(source);
+        let lines: Vec<Option<usize>> = found.iter().map(|f| f.1).collect();
+        assert_eq!(lines, vec![Some(2), Some(3), Some(8), Some(10)], "{:?}", found);
+        assert!(found[0].2.contains("invalid glob in [guardrails] include"));
+        assert!(found[2].2.contains("invalid type"));
+        assert_eq!(found[3].2, "rule has no `id`");
+    }
+
+    #[test]
+    fn syntax_errors_stop_the_check() {
+        let found = messages("[guardrails]\ninclude = [\"src/**\"\n");
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].0, Level::Error);
+        assert!(found[0].1.is_some());
+    }
+
+    #[test]
+    fn clean_config_has_no_diagnostics() {
+        let source = r#"[guardrails]
+include = ["src/**"]
+
+[[rule]]
+id = "no-todo"
+type = "ratchet"
+pattern = "TODO"
+max_count = 3
+message = "x"
+
+[[rule.budgets]]
+glob = "src/legacy/**"
+max_count = 2
+
+[output]
+link_template = "https://example.com/{path}#L{line}"
+"#;
+        let (diagnostics, rules) = check_text(source);
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+        assert_eq!(rules, 1);
+    }
+}
@@ -1,67 +1,408 @@
-use crate::config::Severity;
-use crate::rules::Violation;
-use crate::scan::ScanResult;
+use crate::bundle::Verification;
+use crate::check_config::{Level, Report as CheckReport};
+use crate::cli::GroupBy;
+use crate::cli::toml_config::OutputSection;
+use crate::codeowners::ReviewerReport;
+use crate::config::{RuleOwner, Severity, Suggestion};
+use crate::config_diff::{ConfigDiff, RuleStatus};
+use crate::doctor::Report as DoctorReport;
+use crate::extends::{self, LockChange};
+use crate::filetypes::FileTypes;
+use crate::fingerprint;
+use crate::i18n::Lang;
+use crate::line_endings;
+use crate::preview::Preview;
+use crate::ratchet_update::RatchetUpdate;
+use crate::result_diff::{self, ResultDiff};
+use crate::ratchet_history::{ascii_sparkline, sparkline};
+use crate::rollup::{self, RatchetGroup, RatchetGroupBy};
+use crate::rule_tests::TestReport;
+use crate::sample::SampleSummary;
+use crate::rules::{Fix, Violation};
+use crate::suppression::SuppressionUse;
+use crate::scan::{BenchReport, FileReport, RuleCount, RuleInfo, ScanProfile, ScanResult};
 use serde_json::json;
 use std::collections::BTreeMap;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::io::Write;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Colors, glyphs and detail lines for pretty output, from `[output]`.
+#[derive(Debug, Clone)]
+pub struct Theme {
+    color: bool,
+    error: &'static str,
+    warning: &'static str,
+    pass: &'static str,
+    pass_glyph: String,
+    fail_glyph: String,
+    show_source: bool,
+    show_suggestions: bool,
+    ascii: bool,
+    lang: Lang,
+    ratchet_group_by: Option<RatchetGroupBy>,
+    limits: DisplayLimits,
+    links: Links,
+}
 
-/// Print violations grouped by file with ANSI colors.
-pub fn print_pretty(result: &ScanResult) {
-    let mut out = std::io::stdout();
-    write_pretty(result, &mut out);
+impl Default for Theme {
+    fn default() -> Self {
+        Self::new(&OutputSection::default()).expect("default colors are valid")
+    }
 }
 
-fn write_pretty(result: &ScanResult, out: &mut dyn Write) {
-    if result.violations.is_empty() {
-        let _ = writeln!(
-            out,
-            "\x1b[32m✓\x1b[0m No violations found ({} files scanned, {} rules loaded)",
-            result.files_scanned, result.rules_loaded
-        );
-        write_ratchet_summary_pretty(&result.ratchet_counts, out);
-        return;
+impl Theme {
+    pub fn new(section: &OutputSection) -> Result<Self, String> {
+        let color = |field: &str, name: &str| {
+            ansi_color(name).ok_or_else(|| {
+                format!(
+                    "[output] {}: unknown color \"{}\" (expected one of {})",
+                    field,
+                    name,
+                    COLOR_NAMES.join(", ")
+                )
+            })
+        };
+        Ok(Self {
+            color: section.color,
+            error: color("error_color", &section.error_color)?,
+            warning: color("warning_color", &section.warning_color)?,
+            pass: color("pass_color", &section.pass_color)?,
+            pass_glyph: section.pass_glyph.clone(),
+            fail_glyph: section.fail_glyph.clone(),
+            show_source: section.show_source,
+            show_suggestions: section.show_suggestions,
+            ascii: false,
+            lang: Lang::default(),
+            ratchet_group_by: None,
+            limits: DisplayLimits::default(),
+            links: Links::default(),
+        })
     }
 
-    // Group violations by file
-    let mut by_file: BTreeMap<String, Vec<&Violation>> = BTreeMap::new();
-    for v in &result.violations {
-        by_file
-            .entry(v.file.display().to_string())
-            .or_default()
-            .push(v);
+    /// Use ASCII stand-ins for every Unicode glyph (`--ascii`).
+    pub fn ascii(mut self) -> Self {
+        self.ascii = true;
+        if !self.pass_glyph.is_ascii() {
+            self.pass_glyph = "[ok]".into();
+        }
+        if !self.fail_glyph.is_ascii() {
+            self.fail_glyph = "[x]".into();
+        }
+        self
     }
 
-    for (file, violations) in &by_file {
-        let _ = writeln!(out, "\n\x1b[4m{}\x1b[0m", file);
-        for v in violations {
-            let severity_str = match v.severity {
-                Severity::Error => "\x1b[31merror\x1b[0m",
-                Severity::Warning => "\x1b[33mwarn \x1b[0m",
-            };
+    /// Language of summaries and headings (`--lang`).
+    pub fn lang(mut self, lang: Lang) -> Self {
+        self.lang = lang;
+        self
+    }
 
-            let location = match (v.line, v.column) {
-                (Some(l), Some(c)) => format!("{}:{}", l, c),
-                (Some(l), None) => format!("{}:1", l),
-                _ => "1:1".to_string(),
-            };
+    /// Group the ratchet summary by tag or owner (`--ratchet-group-by`).
+    pub fn ratchet_group_by(mut self, by: Option<RatchetGroupBy>) -> Self {
+        self.ratchet_group_by = by;
+        self
+    }
 
-            let _ = writeln!(
-                out,
-                "  \x1b[90m{:<8}\x1b[0m {} \x1b[90m{:<25}\x1b[0m {}",
-                location, severity_str, v.rule_id, v.message
-            );
+    /// Which violations are listed, and how (`--max-violations`, `--group-by`,
+    /// `--quiet` and the like).
+    pub fn limits(mut self, limits: DisplayLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Link each violation's location to the code host (`[output] link_template`).
+    pub fn links(mut self, links: Links) -> Self {
+        self.links = links;
+        self
+    }
+
+    fn glyph(&self, unicode: &'static str, ascii: &'static str) -> &'static str {
+        if self.ascii {
+            ascii
+        } else {
+            unicode
+        }
+    }
+
+    fn owner_line(&self, owner: &RuleOwner) -> String {
+        let summary = owner.summary();
+        if self.ascii {
+            summary.replace(" · ", " | ")
+        } else {
+            summary
+        }
+    }
+
+    fn severity_label(&self, severity: Severity) -> String {
+        match severity {
+            Severity::Error => format!("{}error\x1b[0m", self.error),
+            Severity::Warning => format!("{}warn \x1b[0m", self.warning),
+        }
+    }
+
+    fn pass_mark(&self) -> String {
+        format!("{}{}\x1b[0m", self.pass, self.pass_glyph)
+    }
+
+    /// Run `write` and emit its output, with ANSI escapes removed when
+    /// color is off.
+    fn render(&self, out: &mut dyn Write, write: impl FnOnce(&mut dyn Write)) {
+        if self.color {
+            write(out);
+            return;
+        }
+        let mut buf = Vec::new();
+        write(&mut buf);
+        let _ = out.write_all(strip_ansi(&String::from_utf8_lossy(&buf)).as_bytes());
+    }
+}
+
+/// What pretty and JSON output list. Only the listing is cut: summaries,
+/// ratchet counts and the exit code still cover every violation.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DisplayLimits {
+    /// Violations listed in total (`--max-violations`).
+    pub max_violations: Option<usize>,
+    /// Violations listed per file (`--max-per-file`).
+    pub max_per_file: Option<usize>,
+    /// Violations listed per rule (`--max-violations-per-rule`).
+    pub max_per_rule: Option<usize>,
+    /// List violations under each rule or file (`--group-by`). Pretty output
+    /// lists by file without it; JSON lists them flat.
+    pub group_by: Option<GroupBy>,
+    /// List no violations, only the summary (`--quiet`).
+    pub quiet: bool,
+}
+
+/// Which of a scan's violations pretty and JSON output list.
+#[derive(Debug, Default)]
+struct Listing {
+    /// Indices of the violations to list, in order.
+    shown: Vec<usize>,
+    /// Violations left out for repeating an earlier one's rule, location
+    /// and message exactly.
+    duplicates: usize,
+    /// How many more violations each rule cut by `max_per_rule` has.
+    more: BTreeMap<String, usize>,
+}
+
+impl Listing {
+    /// Whether violations other than duplicates were left out.
+    fn truncated(&self, total: usize) -> bool {
+        self.shown.len() + self.duplicates < total
+    }
+}
+
+impl DisplayLimits {
+    /// The violations to list, in order: each distinct one once, the first
+    /// `max_per_file` of each file and `max_per_rule` of each rule, up to
+    /// `max_violations` overall.
+    fn list(&self, violations: &[Violation]) -> Listing {
+        let mut listing = Listing::default();
+        let mut seen = HashSet::new();
+        let mut per_file: HashMap<&Path, usize> = HashMap::new();
+        // Per rule: (distinct violations, listed)
+        let mut per_rule: HashMap<&str, (usize, usize)> = HashMap::new();
+        for (i, v) in violations.iter().enumerate() {
+            if !seen.insert((v.rule_id.as_str(), v.file.as_path(), v.line, v.column, v.message.as_str())) {
+                listing.duplicates += 1;
+                continue;
+            }
+            let rule = per_rule.entry(&v.rule_id).or_default();
+            rule.0 += 1;
+            if self.quiet || self.max_violations.is_some_and(|max| listing.shown.len() >= max) {
+                continue;
+            }
+            if self.max_per_rule.is_some_and(|max| rule.1 >= max) {
+                continue;
+            }
+            let file = per_file.entry(v.file.as_path()).or_default();
+            if self.max_per_file.is_some_and(|max| *file >= max) {
+                continue;
+            }
+            *file += 1;
+            rule.1 += 1;
+            listing.shown.push(i);
+        }
+        if let Some(max) = self.max_per_rule.filter(|_| !self.quiet) {
+            listing.more = per_rule
+                .into_iter()
+                .filter(|(_, (total, _))| *total > max)
+                .map(|(rule, (total, listed))| (rule.to_string(), total - listed))
+                .collect();
+        }
+        listing
+    }
+}
+
+/// Links from violations to their code on the code host, built from
+/// `[output] link_template`. Paths in the links are relative to the
+/// repository root; the commit and branch come from the scan's git metadata.
+#[derive(Debug, Clone, Default)]
+pub struct Links {
+    template: Option<String>,
+    /// Repository root, or where relative violation paths start if unknown.
+    root: PathBuf,
+    /// Directory relative violation paths are relative to.
+    cwd: PathBuf,
+}
+
+impl Links {
+    pub fn new(template: Option<String>, root: PathBuf, cwd: PathBuf) -> Self {
+        Self { template, root, cwd }
+    }
+
+    /// Links for `template` in the current directory's git repository.
+    pub fn detect(template: Option<String>) -> Self {
+        if template.is_none() {
+            return Self::default();
+        }
+        let cwd = std::env::current_dir()
+            .and_then(|dir| dir.canonicalize())
+            .unwrap_or_default();
+        let root = crate::git_diff::repo_root()
+            .ok()
+            .and_then(|root| root.canonicalize().ok())
+            .unwrap_or_else(|| cwd.clone());
+        Self::new(template, root, cwd)
+    }
+
+    /// The code host URL of `v`, if a template is set.
+    fn url(&self, result: &ScanResult, v: &Violation) -> Option<String> {
+        let template = self.template.as_deref()?;
+        let git = result.metadata.as_ref().map(|m| &m.git);
+        let branch = git.and_then(|g| g.branch.as_deref());
+        let sha = git.and_then(|g| g.sha.as_deref()).or(branch).unwrap_or("HEAD");
+        Some(
+            template
+                .replace("{sha}", sha)
+                .replace("{branch}", branch.unwrap_or(sha))
+                .replace("{path}", &url_path(&self.repo_path(&v.file)))
+                .replace("{line}", &v.line.unwrap_or(1).to_string())
+                .replace("{column}", &v.column.unwrap_or(1).to_string()),
+        )
+    }
 
-            if let Some(ref source) = v.source_line {
-                let _ = writeln!(out, "           \x1b[90m│\x1b[0m {}", source.trim());
+    /// `file` relative to the repository root, with `/` separators.
+    fn repo_path(&self, file: &Path) -> String {
+        let mut path = PathBuf::new();
+        for component in self.cwd.join(file).components() {
+            match component {
+                std::path::Component::CurDir => {}
+                std::path::Component::ParentDir => {
+                    path.pop();
+                }
+                c => path.push(c),
             }
+        }
+        let relative = path.strip_prefix(&self.root).unwrap_or(&path);
+        relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+}
 
-            if let Some(ref suggest) = v.suggest {
-                let _ = writeln!(out, "           \x1b[90m└─\x1b[0m \x1b[36m{}\x1b[0m", suggest);
+/// `path` with the characters that would break a URL percent-encoded.
+fn url_path(path: &str) -> String {
+    let mut out = String::with_capacity(path.len());
+    for b in path.bytes() {
+        if b.is_ascii_alphanumeric() || b"/-_.~".contains(&b) {
+            out.push(b as char);
+        } else {
+            out.push_str(&format!("%{:02X}", b));
+        }
+    }
+    out
+}
+
+const COLOR_NAMES: [&str; 16] = [
+    "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white", "gray",
+    "bright-red", "bright-green", "bright-yellow", "bright-blue", "bright-magenta",
+    "bright-cyan", "bright-white",
+];
+
+fn ansi_color(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "black" => "\x1b[30m",
+        "red" => "\x1b[31m",
+        "green" => "\x1b[32m",
+        "yellow" => "\x1b[33m",
+        "blue" => "\x1b[34m",
+        "magenta" => "\x1b[35m",
+        "cyan" => "\x1b[36m",
+        "white" => "\x1b[37m",
+        "gray" | "grey" => "\x1b[90m",
+        "bright-red" => "\x1b[91m",
+        "bright-green" => "\x1b[92m",
+        "bright-yellow" => "\x1b[93m",
+        "bright-blue" => "\x1b[94m",
+        "bright-magenta" => "\x1b[95m",
+        "bright-cyan" => "\x1b[96m",
+        "bright-white" => "\x1b[97m",
+        _ => return None,
+    })
+}
+
+/// Remove ANSI SGR sequences (`ESC [ ... m`) and OSC sequences such as
+/// hyperlinks (`ESC ] ... ESC \`).
+fn strip_ansi(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' && chars.peek() == Some(&'[') {
+            for c in chars.by_ref() {
+                if c == 'm' {
+                    break;
+                }
             }
+        } else if c == '\x1b' && chars.peek() == Some(&']') {
+            while let Some(c) = chars.next() {
+                if c == '\x07' || (c == '\x1b' && chars.next_if_eq(&'\\').is_some()) {
+                    break;
+                }
+            }
+        } else {
+            out.push(c);
         }
     }
+    out
+}
+
+/// Print violations grouped by file with ANSI colors.
+pub fn print_pretty(result: &ScanResult, theme: &Theme) {
+    let mut out = std::io::stdout();
+    write_pretty(result, theme, &mut out);
+}
+
+fn write_pretty(result: &ScanResult, theme: &Theme, out: &mut dyn Write) {
+    theme.render(out, |out| write_pretty_ansi(result, theme, out));
+}
+
+fn write_pretty_ansi(result: &ScanResult, theme: &Theme, out: &mut dyn Write) {
+    if result.violations.is_empty() {
+        let _ = writeln!(
+            out,
+            "{} {} ({}){}",
+            theme.pass_mark(),
+            theme.lang.no_violations(),
+            theme.lang.scanned(result.files_scanned, result.rules_loaded),
+            incomplete_marker(result, theme)
+        );
+        write_stats_pretty(result, 0, theme, out);
+        write_ratchets_pretty(result, theme, out);
+        write_sample_summary_pretty(result.sample.as_ref(), theme, out);
+        write_suppressions_pretty(result.suppressions.as_deref(), out);
+        return;
+    }
+
+    let listing = theme.limits.list(&result.violations);
+    if !theme.limits.quiet {
+        write_listing_pretty(result, &listing, theme, out);
+    }
 
     let errors = result
         .violations
@@ -77,124 +418,739 @@ fn write_pretty(result: &ScanResult, out: &mut dyn Write) {
     let _ = writeln!(out);
     let _ = write!(out, "\x1b[1m");
     if errors > 0 {
-        let _ = write!(out, "\x1b[31m{} error{}\x1b[0m\x1b[1m", errors, if errors == 1 { "" } else { "s" });
+        let _ = write!(out, "{}{}\x1b[0m\x1b[1m", theme.error, theme.lang.errors(errors));
     }
     if errors > 0 && warnings > 0 {
         let _ = write!(out, ", ");
     }
     if warnings > 0 {
-        let _ = write!(out, "\x1b[33m{} warning{}\x1b[0m\x1b[1m", warnings, if warnings == 1 { "" } else { "s" });
+        let _ = write!(out, "{}{}\x1b[0m\x1b[1m", theme.warning, theme.lang.warnings(warnings));
     }
     let _ = writeln!(
         out,
-        " ({} files scanned, {} rules loaded)\x1b[0m",
-        result.files_scanned, result.rules_loaded
+        " ({}){}\x1b[0m",
+        theme.lang.scanned(result.files_scanned, result.rules_loaded),
+        incomplete_marker(result, theme)
     );
+    write_stats_pretty(result, errors + warnings, theme, out);
+    if !theme.limits.quiet && listing.truncated(result.violations.len()) {
+        let _ = writeln!(
+            out,
+            "\x1b[33m{}\x1b[0m",
+            theme.lang.truncated(listing.shown.len(), result.violations.len())
+        );
+    }
+    if !theme.limits.quiet && listing.duplicates > 0 {
+        let _ = writeln!(out, "\x1b[90m{}\x1b[0m", theme.lang.duplicates(listing.duplicates));
+    }
+    if result.third_party_violations > 0 {
+        let _ = writeln!(
+            out,
+            "\x1b[90m{}\x1b[0m",
+            theme.lang.in_third_party(result.third_party_violations)
+        );
+    }
+    if let Some(v) = listing.shown.first().map(|&i| &result.violations[i]) {
+        let _ = writeln!(
+            out,
+            "\x1b[90m{}: {}\x1b[0m",
+            theme.lang.hint_label(),
+            theme.lang.explain_hint(&format!("`guardrails explain {}`", v.rule_id))
+        );
+    }
+
+    write_ratchets_pretty(result, theme, out);
+    write_sample_summary_pretty(result.sample.as_ref(), theme, out);
+    write_suppressions_pretty(result.suppressions.as_deref(), out);
+}
+
+/// The listed violations under a header per file, per rule with
+/// `--group-by rule` or per CODEOWNERS owner with `--group-by owner`, and
+/// how many more each rule cut short has.
+fn write_listing_pretty(result: &ScanResult, listing: &Listing, theme: &Theme, out: &mut dyn Write) {
+    let group_by = theme.limits.group_by;
+    let by_rule = matches!(group_by, Some(GroupBy::Rule));
+    let mut groups: BTreeMap<String, Vec<&Violation>> = BTreeMap::new();
+    if by_rule {
+        for rule in listing.more.keys() {
+            groups.entry(rule.clone()).or_default();
+        }
+    }
+    for v in listing.shown.iter().map(|&i| &result.violations[i]) {
+        for key in group_keys(result, v, group_by) {
+            groups.entry(key).or_default().push(v);
+        }
+    }
+    let more = |n: usize| format!("{}{}", theme.glyph("…", "..."), theme.lang.more(&thousands(n)));
+    let by_owner = matches!(group_by, Some(GroupBy::Owner));
+    let with_file = by_rule || by_owner;
+
+    for (key, violations) in &groups {
+        let _ = writeln!(out, "\n\x1b[4m{}\x1b[0m", key);
+        for v in violations {
+            write_violation_pretty(result, v, with_file, !by_rule, theme, out);
+        }
+        if let Some(&n) = listing.more.get(key).filter(|_| by_rule) {
+            let _ = writeln!(out, "  \x1b[90m{}\x1b[0m", more(n));
+        }
+    }
+    if !by_rule && !listing.more.is_empty() {
+        let _ = writeln!(out);
+        for (rule, &n) in &listing.more {
+            let _ = writeln!(out, "  \x1b[90m{:<25} {}\x1b[0m", rule, more(n));
+        }
+    }
+}
 
-    write_ratchet_summary_pretty(&result.ratchet_counts, out);
+/// Heading a violation is listed under in pretty output, or grouped under
+/// in JSON: its file, its rule with `--group-by rule`, or each CODEOWNERS
+/// owner of its file with `--group-by owner`.
+fn group_keys(result: &ScanResult, v: &Violation, group_by: Option<GroupBy>) -> Vec<String> {
+    match group_by {
+        Some(GroupBy::Rule) => vec![v.rule_id.clone()],
+        Some(GroupBy::Owner) => match result.code_owners.as_ref().and_then(|owners| owners.get(&v.file)) {
+            Some(owners) => owners.clone(),
+            None => vec![UNOWNED.to_string()],
+        },
+        _ => vec![v.file.display().to_string()],
+    }
 }
 
-fn write_ratchet_summary_pretty(
-    ratchet_counts: &HashMap<String, (usize, usize)>,
+/// Group of violations in files no CODEOWNERS entry covers.
+const UNOWNED: &str = "(unowned)";
+
+/// One violation's line, with its source, suggestion and owner beneath.
+/// With `with_file` the line names the file, and with `with_rule` the rule.
+fn write_violation_pretty(
+    result: &ScanResult,
+    v: &Violation,
+    with_file: bool,
+    with_rule: bool,
+    theme: &Theme,
     out: &mut dyn Write,
 ) {
-    if ratchet_counts.is_empty() {
+    let severity_str = theme.severity_label(v.severity);
+
+    let location = match (v.line, v.column) {
+        (Some(l), Some(c)) => format!("{}:{}", l, c),
+        (Some(l), None) => format!("{}:1", l),
+        _ => "1:1".to_string(),
+    };
+    let location = if with_file { format!("{}:{}", v.file.display(), location) } else { location };
+
+    let padding = " ".repeat(8usize.saturating_sub(location.len()));
+    let location = match theme.links.url(result, v) {
+        Some(url) => format!("\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", url, location),
+        None => location,
+    };
+
+    if !with_rule {
+        let _ = writeln!(
+            out,
+            "  \x1b[90m{}{}\x1b[0m {} {}",
+            location, padding, severity_str, v.message
+        );
+    } else {
+        let _ = writeln!(
+            out,
+            "  \x1b[90m{}{}\x1b[0m {} \x1b[90m{}\x1b[0m {}",
+            location,
+            padding,
+            severity_str,
+            rule_link(v, 25),
+            v.message
+        );
+    }
+
+    if let Some(source) = v.source_line.as_ref().filter(|_| theme.show_source) {
+        let _ = writeln!(out, "           \x1b[90m{}\x1b[0m {}", theme.glyph("│", "|"), source.trim());
+    }
+
+    if let Some(suggest) = v.suggest.as_ref().filter(|_| theme.show_suggestions) {
+        let _ = writeln!(
+            out,
+            "           \x1b[90m{}\x1b[0m \x1b[36m{}\x1b[0m",
+            theme.glyph("└─", "`-"),
+            suggest
+        );
+        if let Some(docs) = suggestion_for(result, v).and_then(|s| s.docs.as_ref()) {
+            let _ = writeln!(out, "              \x1b[90m{}\x1b[0m", docs);
+        }
+    }
+
+    if let Some(owner) = result.owners.get(&v.rule_id) {
+        let _ = writeln!(
+            out,
+            "           \x1b[90m{} {}\x1b[0m",
+            theme.glyph("↳", "->"),
+            theme.owner_line(owner)
+        );
+    }
+}
+
+/// The rule id of `v` padded to `width`, as an OSC 8 hyperlink to the
+/// rule's `url` when it has one.
+fn rule_link(v: &Violation, width: usize) -> String {
+    let padding = " ".repeat(width.saturating_sub(v.rule_id.chars().count()));
+    match v.url {
+        Some(ref url) => format!("\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\{}", url, v.rule_id, padding),
+        None => format!("{}{}", v.rule_id, padding),
+    }
+}
+
+fn incomplete_marker(result: &ScanResult, theme: &Theme) -> String {
+    if result.incomplete {
+        format!(" \x1b[33m{}\x1b[0m", theme.lang.incomplete())
+    } else {
+        String::new()
+    }
+}
+
+/// The ratchet summary, per rule or grouped by `--ratchet-group-by`.
+fn write_ratchets_pretty(result: &ScanResult, theme: &Theme, out: &mut dyn Write) {
+    match theme.ratchet_group_by {
+        Some(by) => write_ratchet_groups_pretty(result, &rollup::ratchet_groups(result, by), theme, out),
+        None => write_ratchet_summary_pretty(result, theme, out),
+    }
+}
+
+fn write_ratchet_summary_pretty(result: &ScanResult, theme: &Theme, out: &mut dyn Write) {
+    if result.ratchet_counts.is_empty() {
         return;
     }
 
-    let _ = writeln!(out, "\n\x1b[1mRatchet rules:\x1b[0m");
-    let mut sorted: Vec<_> = ratchet_counts.iter().collect();
+    let _ = writeln!(out, "\n\x1b[1m{}\x1b[0m", theme.lang.ratchet_heading());
+    let mut sorted: Vec<_> = result.ratchet_counts.iter().collect();
     sorted.sort_by_key(|(id, _)| (*id).clone());
 
     for (rule_id, &(found, max)) in &sorted {
-        let status = if found <= max {
-            format!("\x1b[32m✓ pass\x1b[0m ({}/{})", found, max)
-        } else {
-            format!("\x1b[31m✗ OVER\x1b[0m ({}/{})", found, max)
+        let over = result.ratchet_over(rule_id);
+        let trend = result.ratchet_trends.get(*rule_id);
+        let _ = writeln!(out, "  {}", ratchet_line(rule_id, (found, max), over, trend, 30, theme));
+    }
+}
+
+/// One subtotal row per group, with its share of the combined budget in
+/// use, followed by the group's rules.
+fn write_ratchet_groups_pretty(
+    result: &ScanResult,
+    groups: &[RatchetGroup],
+    theme: &Theme,
+    out: &mut dyn Write,
+) {
+    if groups.is_empty() {
+        return;
+    }
+
+    let _ = writeln!(out, "\n\x1b[1m{}\x1b[0m", theme.lang.ratchet_heading());
+    for group in groups {
+        let color = if group.found <= group.max { theme.pass } else { theme.error };
+        let _ = writeln!(
+            out,
+            "  \x1b[1m{:<30}\x1b[0m {}{:.0}%\x1b[0m ({}/{})",
+            group.name.as_deref().unwrap_or(theme.lang.ungrouped()),
+            color,
+            group.utilization(),
+            group.found,
+            group.max
+        );
+        for (rule_id, found, max) in &group.rules {
+            let over = result.ratchet_over(rule_id);
+            let trend = result.ratchet_trends.get(rule_id);
+            let _ = writeln!(out, "    {}", ratchet_line(rule_id, (*found, *max), over, trend, 28, theme));
+        }
+    }
+}
+
+/// A ratchet rule's status, counts and, with history, its trend.
+fn ratchet_line(
+    rule_id: &str,
+    (found, max): (usize, usize),
+    over: bool,
+    trend: Option<&Vec<usize>>,
+    width: usize,
+    theme: &Theme,
+) -> String {
+    let (pass_label, over_label) = theme.lang.ratchet_status();
+    let status = if over {
+        format!("{}{} {}\x1b[0m ({}/{})", theme.error, theme.fail_glyph, over_label, found, max)
+    } else {
+        format!("{}{} {}\x1b[0m ({}/{})", theme.pass, theme.pass_glyph, pass_label, found, max)
+    };
+    match trend.filter(|t| t.len() > 1) {
+        Some(trend) => format!(
+            "{:<width$} {}  \x1b[36m{}\x1b[0m \x1b[90m({} {} {})\x1b[0m",
+            rule_id,
+            status,
+            if theme.ascii { ascii_sparkline(trend) } else { sparkline(trend) },
+            trend[0],
+            theme.glyph("→", "->"),
+            trend[trend.len() - 1],
+            width = width
+        ),
+        None => format!("{:<width$} {}", rule_id, status, width = width),
+    }
+}
+
+fn write_suppressions_pretty(suppressions: Option<&[SuppressionUse]>, out: &mut dyn Write) {
+    let Some(suppressions) = suppressions else {
+        return;
+    };
+
+    let used = suppressions.iter().filter(|u| !u.is_unused()).count();
+    let _ = writeln!(
+        out,
+        "\n\x1b[1mSuppressions:\x1b[0m {} used, {} unused",
+        used,
+        suppressions.len() - used
+    );
+    for u in suppressions.iter().filter(|u| !u.is_unused()) {
+        let location = match (u.line, &u.path) {
+            (Some(line), _) => format!("{}:{}", u.file.display(), line),
+            (None, Some(path)) => format!("{} ({})", u.file.display(), path),
+            (None, None) => u.file.display().to_string(),
         };
-        let _ = writeln!(out, "  {:<30} {}", rule_id, status);
+        let _ = writeln!(
+            out,
+            "  {:<40} {:<25} {:>5} hidden",
+            location, u.rule, u.suppressed
+        );
     }
 }
 
-/// Print violations as structured JSON.
-pub fn print_json(result: &ScanResult) {
-    let mut out = std::io::stdout();
-    write_json(result, &mut out);
+fn write_sample_summary_pretty(sample: Option<&SampleSummary>, theme: &Theme, out: &mut dyn Write) {
+    let Some(sample) = sample else {
+        return;
+    };
+
+    let _ = writeln!(
+        out,
+        "\n\x1b[1mSampled {} of {} files\x1b[0m \x1b[90m(seed {})\x1b[0m",
+        sample.sampled_files, sample.total_files, sample.seed
+    );
+    for e in &sample.estimates {
+        let _ = writeln!(
+            out,
+            "  {:<30} ~{} \x1b[90m(95% range {}{}{}, {} seen)\x1b[0m",
+            e.rule_id,
+            e.estimate,
+            e.low,
+            theme.glyph("–", "-"),
+            e.high,
+            e.observed
+        );
+    }
 }
 
-fn write_json(result: &ScanResult, out: &mut dyn Write) {
-    let violations: Vec<_> = result
-        .violations
-        .iter()
-        .map(|v| {
-            json!({
-                "rule_id": v.rule_id,
-                "severity": match v.severity {
-                    Severity::Error => "error",
-                    Severity::Warning => "warning",
-                },
-                "file": v.file.display().to_string(),
-                "line": v.line,
-                "column": v.column,
-                "message": v.message,
-                "suggest": v.suggest,
-                "source_line": v.source_line,
-                "fix": v.fix.as_ref().map(|f| json!({
-                    "old": f.old,
-                    "new": f.new,
-                })),
-            })
-        })
-        .collect();
+/// Print violations as structured JSON. With `redact_source`, source lines
+/// and fix contents are left out; fingerprints are still computed from them.
+pub fn print_json(result: &ScanResult, redact_source: bool, limits: DisplayLimits) {
+    let mut out = std::io::stdout();
+    write_json(result, redact_source, limits, &mut out);
+}
 
-    let ratchet: serde_json::Map<String, serde_json::Value> = result
-        .ratchet_counts
-        .iter()
-        .map(|(id, &(found, max))| {
-            (
-                id.clone(),
-                json!({ "found": found, "max": max, "pass": found <= max }),
-            )
+fn write_json(result: &ScanResult, redact_source: bool, limits: DisplayLimits, out: &mut dyn Write) {
+    let fingerprints = fingerprint::fingerprints(&result.violations);
+    let listing = limits.list(&result.violations);
+    let violation_json = |i: usize| {
+        let (v, fp) = (&result.violations[i], &fingerprints[i]);
+        json!({
+            "rule_id": v.rule_id,
+            "severity": match v.severity {
+                Severity::Error => "error",
+                Severity::Warning => "warning",
+            },
+            "file": v.file.display().to_string(),
+            "line": v.line,
+            "column": v.column,
+            "message": v.message,
+            "suggest": v.suggest,
+            "suggestion": suggestion_for(result, v),
+            "source_line": v.source_line.as_ref().filter(|_| !redact_source),
+            "fingerprint": fp,
+            "fix": v.fix.as_ref().filter(|_| !redact_source),
+            "owner": result.owners.get(&v.rule_id),
+            "url": v.url,
+            "codeowners": result.code_owners.as_ref().and_then(|owners| owners.get(&v.file)),
         })
-        .collect();
+    };
 
-    let output = json!({
-        "violations": violations,
+    let mut output = json!({
         "summary": {
             "total": result.violations.len(),
+            "shown": listing.shown.len(),
+            "truncated": listing.truncated(result.violations.len()),
+            "duplicates": listing.duplicates,
+            "more": listing.more,
             "errors": result.violations.iter().filter(|v| v.severity == Severity::Error).count(),
             "warnings": result.violations.iter().filter(|v| v.severity == Severity::Warning).count(),
             "files_scanned": result.files_scanned,
             "rules_loaded": result.rules_loaded,
+            "incomplete": result.incomplete,
+            "third_party": result.third_party_violations,
+            "lines_scanned": result.lines_scanned,
+            "bytes_scanned": result.bytes_scanned,
+            "violations_per_kloc": result.per_kloc(result.violations.len()).map(round2),
+            "skipped": result.skipped,
+            "transcoded": result.transcoded,
+            "codeowners": codeowners_json(result),
         },
-        "ratchet": ratchet,
+        "rules": result.rule_stats(),
+        "density": density_json(result),
+        "ratchet": ratchet_json(result),
+        "sample": result.sample,
+        "suppressions": result.suppressions,
+        "metadata": result.metadata,
+        "timing": result.profile.as_ref().map(timing_json),
     });
+    match limits.group_by {
+        Some(group_by @ (GroupBy::Rule | GroupBy::File | GroupBy::Owner)) => {
+            output["groups"] = groups_json(result, &listing, group_by, violation_json)
+        }
+        _ => output["violations"] = listing.shown.iter().map(|&i| violation_json(i)).collect(),
+    }
 
     let _ = writeln!(out, "{}", serde_json::to_string_pretty(&output).unwrap());
 }
 
-/// Print violations in compact one-line-per-violation format.
-/// Violations go to stdout; summary goes to stderr.
-pub fn print_compact(result: &ScanResult) {
-    let mut stdout = std::io::stdout();
-    let mut stderr = std::io::stderr();
-    write_compact(result, &mut stdout, &mut stderr);
+/// Summary violation counts per CODEOWNERS owner, and of violations in
+/// files nobody owns; null without a CODEOWNERS file.
+fn codeowners_json(result: &ScanResult) -> serde_json::Value {
+    let Some(code_owners) = &result.code_owners else {
+        return serde_json::Value::Null;
+    };
+    let mut owners: BTreeMap<&str, usize> = BTreeMap::new();
+    let mut unowned = 0;
+    for v in &result.violations {
+        match code_owners.get(&v.file) {
+            Some(names) => names.iter().for_each(|name| *owners.entry(name).or_default() += 1),
+            None => unowned += 1,
+        }
+    }
+    json!({ "owners": owners, "unowned": unowned })
 }
 
-fn write_compact(result: &ScanResult, out: &mut dyn Write, err: &mut dyn Write) {
+/// `--group-by rule`, `file` or `owner` JSON: per rule (file or CODEOWNERS
+/// owner), its total violations, how many more `--max-violations-per-rule`
+/// left out, and the ones listed.
+fn groups_json(
+    result: &ScanResult,
+    listing: &Listing,
+    group_by: GroupBy,
+    violation_json: impl Fn(usize) -> serde_json::Value,
+) -> serde_json::Value {
+    let by_rule = matches!(group_by, GroupBy::Rule);
+    let keys = |v: &Violation| group_keys(result, v, Some(group_by));
+    let mut totals: HashMap<String, usize> = HashMap::new();
     for v in &result.violations {
-        let severity = match v.severity {
-            Severity::Error => "error",
-            Severity::Warning => "warning",
-        };
-        let line = v.line.unwrap_or(1);
-        let col = v.column.unwrap_or(1);
-
-        let _ = writeln!(
-            out,
-            "{}:{}:{}: {}[{}] {}",
-            v.file.display(),
-            line,
+        for key in keys(v) {
+            *totals.entry(key).or_default() += 1;
+        }
+    }
+    let mut groups: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    if by_rule {
+        for rule in listing.more.keys() {
+            groups.entry(rule.clone()).or_default();
+        }
+    }
+    for &i in &listing.shown {
+        for key in keys(&result.violations[i]) {
+            groups.entry(key).or_default().push(i);
+        }
+    }
+    let name = match group_by {
+        GroupBy::Rule => "rule",
+        GroupBy::Owner => "owner",
+        _ => "file",
+    };
+    groups
+        .into_iter()
+        .map(|(group, shown)| {
+            json!({
+                name: group,
+                "total": totals[&group],
+                "more": listing.more.get(&group).copied().unwrap_or(0),
+                "violations": shown.into_iter().map(&violation_json).collect::<Vec<_>>(),
+            })
+        })
+        .collect()
+}
+
+/// Print a `--counts-only` table of per-rule totals with ANSI colors.
+pub fn print_counts_pretty(result: &ScanResult, theme: &Theme) {
+    let mut out = std::io::stdout();
+    write_counts_pretty(result, theme, &mut out);
+}
+
+fn write_counts_pretty(result: &ScanResult, theme: &Theme, out: &mut dyn Write) {
+    theme.render(out, |out| write_counts_pretty_ansi(result, theme, out));
+}
+
+fn write_counts_pretty_ansi(result: &ScanResult, theme: &Theme, out: &mut dyn Write) {
+    let counts = result.counts.as_deref().unwrap_or_default();
+    let (errors, warnings) = severity_totals(counts);
+
+    if counts.is_empty() {
+        let _ = writeln!(
+            out,
+            "{} {} ({}){}",
+            theme.pass_mark(),
+            theme.lang.no_violations(),
+            theme.lang.scanned(result.files_scanned, result.rules_loaded),
+            incomplete_marker(result, theme)
+        );
+    } else {
+        for c in counts {
+            let severity_str = theme.severity_label(c.severity);
+            let files = format!("{} {}", c.files, if c.files == 1 { "file" } else { "files" });
+            match result.per_kloc(c.count) {
+                Some(per_kloc) => {
+                    let _ = writeln!(
+                        out,
+                        "  {:>8}  {} {:<30} \x1b[90m{:>11}  {:.2}/KLoC\x1b[0m",
+                        c.count, severity_str, c.rule_id, files, per_kloc
+                    );
+                }
+                None => {
+                    let _ = writeln!(
+                        out,
+                        "  {:>8}  {} {:<30} \x1b[90m{:>11}\x1b[0m",
+                        c.count, severity_str, c.rule_id, files
+                    );
+                }
+            }
+        }
+        let _ = writeln!(
+            out,
+            "\n\x1b[1m{}, {}\x1b[0m ({}){}",
+            theme.lang.errors(errors),
+            theme.lang.warnings(warnings),
+            theme.lang.scanned(result.files_scanned, result.rules_loaded),
+            incomplete_marker(result, theme)
+        );
+    }
+    write_stats_pretty(result, errors + warnings, theme, out);
+
+    write_ratchets_pretty(result, theme, out);
+}
+
+/// Print `--counts-only` totals as JSON.
+pub fn print_counts_json(result: &ScanResult) {
+    let mut out = std::io::stdout();
+    write_counts_json(result, &mut out);
+}
+
+fn write_counts_json(result: &ScanResult, out: &mut dyn Write) {
+    let counts = result.counts.as_deref().unwrap_or_default();
+    let (errors, warnings) = severity_totals(counts);
+
+    let output = json!({
+        "counts": counts,
+        "summary": {
+            "total": errors + warnings,
+            "errors": errors,
+            "warnings": warnings,
+            "files_scanned": result.files_scanned,
+            "rules_loaded": result.rules_loaded,
+            "incomplete": result.incomplete,
+            "lines_scanned": result.lines_scanned,
+            "bytes_scanned": result.bytes_scanned,
+            "violations_per_kloc": result.per_kloc(errors + warnings).map(round2),
+            "skipped": result.skipped,
+            "transcoded": result.transcoded,
+        },
+        "density": density_json(result),
+        "ratchet": ratchet_json(result),
+        "metadata": result.metadata,
+    });
+
+    let _ = writeln!(out, "{}", serde_json::to_string_pretty(&output).unwrap());
+}
+
+/// Found and allowed counts of each ratchet rule, with its trend.
+fn ratchet_json(result: &ScanResult) -> serde_json::Map<String, serde_json::Value> {
+    result
+        .ratchet_counts
+        .iter()
+        .map(|(id, &(found, max))| {
+            (
+                id.clone(),
+                json!({
+                    "found": found,
+                    "max": max,
+                    "pass": !result.ratchet_over(id),
+                    "trend": result.ratchet_trends.get(id),
+                }),
+            )
+        })
+        .collect()
+}
+
+/// Print a `--group-by dir` table of counts per directory with ANSI colors.
+pub fn print_rollup_pretty(result: &ScanResult, depth: usize, theme: &Theme) {
+    let mut out = std::io::stdout();
+    write_rollup_pretty(result, depth, theme, &mut out);
+}
+
+fn write_rollup_pretty(result: &ScanResult, depth: usize, theme: &Theme, out: &mut dyn Write) {
+    theme.render(out, |out| write_rollup_pretty_ansi(result, depth, theme, out));
+}
+
+fn write_rollup_pretty_ansi(result: &ScanResult, depth: usize, theme: &Theme, out: &mut dyn Write) {
+    let rollup = rollup::by_directory(result, depth);
+    let errors: usize = rollup.iter().map(|d| d.errors).sum();
+    let warnings: usize = rollup.iter().map(|d| d.warnings).sum();
+
+    if rollup.is_empty() {
+        let _ = writeln!(
+            out,
+            "{} {} ({}){}",
+            theme.pass_mark(),
+            theme.lang.no_violations(),
+            theme.lang.scanned(result.files_scanned, result.rules_loaded),
+            incomplete_marker(result, theme)
+        );
+    } else {
+        let _ = writeln!(
+            out,
+            "  \x1b[90m{:<40} {:>8} {:>8} {:>8}\x1b[0m",
+            "directory", "errors", "warnings", "ratchet"
+        );
+        for dir in &rollup {
+            let _ = writeln!(
+                out,
+                "  {:<40} {:>8} {:>8} {:>8}",
+                dir.directory,
+                dir.errors,
+                dir.warnings,
+                dir.ratchet_total()
+            );
+        }
+        let _ = writeln!(
+            out,
+            "\n\x1b[1m{}, {}\x1b[0m ({}){}",
+            theme.lang.errors(errors),
+            theme.lang.warnings(warnings),
+            theme.lang.scanned(result.files_scanned, result.rules_loaded),
+            incomplete_marker(result, theme)
+        );
+    }
+
+    write_ratchets_pretty(result, theme, out);
+}
+
+/// Print `--group-by dir` counts per directory as JSON.
+pub fn print_rollup_json(result: &ScanResult, depth: usize) {
+    let mut out = std::io::stdout();
+    write_rollup_json(result, depth, &mut out);
+}
+
+fn write_rollup_json(result: &ScanResult, depth: usize, out: &mut dyn Write) {
+    let rollup = rollup::by_directory(result, depth);
+    let errors: usize = rollup.iter().map(|d| d.errors).sum();
+    let warnings: usize = rollup.iter().map(|d| d.warnings).sum();
+
+    let output = json!({
+        "directories": rollup,
+        "depth": depth,
+        "summary": {
+            "errors": errors,
+            "warnings": warnings,
+            "files_scanned": result.files_scanned,
+            "rules_loaded": result.rules_loaded,
+            "incomplete": result.incomplete,
+        },
+        "ratchet": ratchet_json(result),
+        "metadata": result.metadata,
+    });
+
+    let _ = writeln!(out, "{}", serde_json::to_string_pretty(&output).unwrap());
+}
+
+/// Per-rule violation counts and violations per 1,000 scanned lines.
+fn density_json(result: &ScanResult) -> serde_json::Map<String, serde_json::Value> {
+    result
+        .rule_density()
+        .into_iter()
+        .map(|(rule_id, count, per_kloc)| {
+            (rule_id, json!({ "count": count, "per_kloc": round2(per_kloc) }))
+        })
+        .collect()
+}
+
+/// The structured suggestion behind `v.suggest`, unless a per-pattern
+/// `suggest` replaced the rule's own.
+fn suggestion_for<'a>(result: &'a ScanResult, v: &Violation) -> Option<&'a Suggestion> {
+    result
+        .suggestions
+        .get(&v.rule_id)
+        .filter(|s| v.suggest.as_deref() == Some(s.title.as_str()))
+}
+
+fn round2(x: f64) -> f64 {
+    (x * 100.0).round() / 100.0
+}
+
+fn write_stats_pretty(result: &ScanResult, violations: usize, theme: &Theme, out: &mut dyn Write) {
+    if let Some(stats) = stats_line(result, violations, theme) {
+        let _ = writeln!(out, "\x1b[90m{}\x1b[0m", stats);
+    }
+}
+
+/// "12,345 lines, 456.7 KB · 1.23 violations/KLoC", or `None` if nothing was read.
+fn stats_line(result: &ScanResult, violations: usize, theme: &Theme) -> Option<String> {
+    let per_kloc = result.per_kloc(violations)?;
+    Some(format!(
+        "{} lines, {} {} {:.2} violations/KLoC",
+        thousands(result.lines_scanned),
+        format_bytes(result.bytes_scanned),
+        theme.glyph("·", "|"),
+        per_kloc
+    ))
+}
+
+fn thousands(n: usize) -> String {
+    let digits = n.to_string();
+    let mut out = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            out.push(',');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// (errors, warnings) summed over per-rule totals.
+fn severity_totals(counts: &[RuleCount]) -> (usize, usize) {
+    counts.iter().fold((0, 0), |(e, w), c| match c.severity {
+        Severity::Error => (e + c.count, w),
+        Severity::Warning => (e, w + c.count),
+    })
+}
+
+/// Print violations in compact one-line-per-violation format.
+/// Violations go to stdout; summary goes to stderr.
+pub fn print_compact(result: &ScanResult) {
+    let mut stdout = std::io::stdout();
+    let mut stderr = std::io::stderr();
+    write_compact(result, &mut stdout, &mut stderr);
+}
+
+fn write_compact(result: &ScanResult, out: &mut dyn Write, err: &mut dyn Write) {
+    for v in &result.violations {
+        let severity = match v.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        let line = v.line.unwrap_or(1);
+        let col = v.column.unwrap_or(1);
+
+        let _ = writeln!(
+            out,
+            "{}:{}:{}: {}[{}] {}",
+            v.file.display(),
+            line,
             col,
             severity,
             v.rule_id,
@@ -203,24 +1159,58 @@ fn write_compact(result: &ScanResult, out: &mut dyn Write, err: &mut dyn Write)
     }
 
     write_summary_stderr(result, err);
-    write_ratchet_stderr(&result.ratchet_counts, err);
+    write_ratchet_stderr(result, err);
 }
 
 /// Print violations as GitHub Actions workflow commands.
-/// Violations go to stdout; summary goes to stderr.
-pub fn print_github(result: &ScanResult) {
+/// Violations go to stdout; summary goes to stderr. `is_new` tells which
+/// violations are on lines changed in the pull request; they are annotated
+/// before pre-existing ones when the annotation budget runs out.
+pub fn print_github(result: &ScanResult, is_new: &dyn Fn(&Violation) -> bool) {
     let mut stdout = std::io::stdout();
     let mut stderr = std::io::stderr();
-    write_github(result, &mut stdout, &mut stderr);
+    write_github(result, is_new, &mut stdout, &mut stderr);
 }
 
-fn write_github(result: &ScanResult, out: &mut dyn Write, err: &mut dyn Write) {
-    for v in &result.violations {
-        let level = match v.severity {
-            Severity::Error => "error",
-            Severity::Warning => "warning",
-        };
+/// GitHub shows at most this many error and this many warning annotations
+/// per step and silently drops the rest.
+const GITHUB_ANNOTATIONS_PER_LEVEL: usize = 10;
+
+/// One annotation: level, whether it's new in the diff, `file=…` properties
+/// and message.
+struct Annotation {
+    level: Severity,
+    new: bool,
+    props: String,
+    message: String,
+}
+
+fn write_github(
+    result: &ScanResult,
+    is_new: &dyn Fn(&Violation) -> bool,
+    out: &mut dyn Write,
+    err: &mut dyn Write,
+) {
+    let mut annotations = Vec::new();
+
+    // Ratchet failures are project-wide errors, so they rank with new ones
+    let mut sorted: Vec<_> = result.ratchet_counts.iter().collect();
+    sorted.sort_by_key(|(id, _)| (*id).clone());
+    for (rule_id, &(found, max)) in &sorted {
+        if result.ratchet_over(rule_id) {
+            annotations.push(Annotation {
+                level: Severity::Error,
+                new: true,
+                props: format!("title=ratchet-{}", rule_id),
+                message: format!(
+                    "Ratchet rule '{}' exceeded budget: {} found, max {}",
+                    rule_id, found, max
+                ),
+            });
+        }
+    }
 
+    for v in &result.violations {
         let line = v.line.unwrap_or(1);
         let mut props = format!("file={},line={}", v.file.display(), line);
         if let Some(col) = v.column {
@@ -228,19 +1218,54 @@ fn write_github(result: &ScanResult, out: &mut dyn Write, err: &mut dyn Write) {
         }
         props.push_str(&format!(",title={}", v.rule_id));
 
-        let _ = writeln!(out, "::{} {}::{}", level, props, v.message);
+        let mut message = match result.owners.get(&v.rule_id) {
+            Some(owner) => format!("{} ({})", v.message, owner.summary()),
+            None => v.message.clone(),
+        };
+        if let Some(ref url) = v.url {
+            message.push_str(&format!(" (see {})", url));
+        }
+        annotations.push(Annotation {
+            level: v.severity,
+            new: is_new(v),
+            props,
+            message,
+        });
     }
 
-    // Ratchet failures as annotations
-    let mut sorted: Vec<_> = result.ratchet_counts.iter().collect();
-    sorted.sort_by_key(|(id, _)| (*id).clone());
-    for (rule_id, &(found, max)) in &sorted {
-        if found > max {
-            let _ = writeln!(
-                out,
-                "::error title=ratchet-{}::Ratchet rule '{}' exceeded budget: {} found, max {}",
-                rule_id, rule_id, found, max
-            );
+    // Errors before warnings, new-in-diff before pre-existing; the sort is
+    // stable, so scan order holds within each group.
+    annotations.sort_by_key(|a| (a.level == Severity::Warning, !a.new));
+
+    let mut shown = [0usize; 2];
+    let mut overflow = Vec::new();
+    for a in &annotations {
+        let (slot, level) = match a.level {
+            Severity::Error => (0, "error"),
+            Severity::Warning => (1, "warning"),
+        };
+        if shown[slot] < GITHUB_ANNOTATIONS_PER_LEVEL {
+            shown[slot] += 1;
+            let _ = writeln!(out, "::{} {}::{}", level, a.props, a.message);
+        } else {
+            overflow.push((level, a));
+        }
+    }
+
+    if !overflow.is_empty() {
+        let errors = overflow.iter().filter(|(level, _)| *level == "error").count();
+        let warnings = overflow.len() - errors;
+        let _ = writeln!(
+            out,
+            "::notice title=guardrails::{} more error{} and {} more warning{} not annotated (GitHub shows {} of each per step); see the job log for all of them",
+            errors,
+            if errors == 1 { "" } else { "s" },
+            warnings,
+            if warnings == 1 { "" } else { "s" },
+            GITHUB_ANNOTATIONS_PER_LEVEL
+        );
+        for (level, a) in &overflow {
+            let _ = writeln!(out, "{} {}: {}", level, a.props, a.message);
         }
     }
 
@@ -277,45 +1302,172 @@ fn write_summary_stderr(result: &ScanResult, err: &mut dyn Write) {
         }
         let _ = writeln!(
             err,
-            "{} ({} files scanned, {} rules loaded)",
+            "{} ({} files scanned, {} rules loaded){}",
             parts.join(", "),
             result.files_scanned,
-            result.rules_loaded
+            result.rules_loaded,
+            if result.incomplete { " [incomplete]" } else { "" }
         );
     } else {
         let _ = writeln!(
             err,
-            "No violations found ({} files scanned, {} rules loaded)",
+            "No violations found ({} files scanned, {} rules loaded){}",
             result.files_scanned,
-            result.rules_loaded
+            result.rules_loaded,
+            if result.incomplete { " [incomplete]" } else { "" }
         );
     }
 }
 
-fn write_ratchet_stderr(
-    ratchet_counts: &HashMap<String, (usize, usize)>,
-    err: &mut dyn Write,
-) {
-    if ratchet_counts.is_empty() {
+fn write_ratchet_stderr(result: &ScanResult, err: &mut dyn Write) {
+    if result.ratchet_counts.is_empty() {
         return;
     }
 
-    let mut sorted: Vec<_> = ratchet_counts.iter().collect();
+    let mut sorted: Vec<_> = result.ratchet_counts.iter().collect();
     sorted.sort_by_key(|(id, _)| (*id).clone());
 
     for (rule_id, &(found, max)) in &sorted {
-        let status = if found <= max { "pass" } else { "OVER" };
+        let status = if result.ratchet_over(rule_id) { "OVER" } else { "pass" };
         let _ = writeln!(err, "ratchet: {} {} ({}/{})", rule_id, status, found, max);
     }
 }
 
-/// Print violations in SARIF v2.1.0 format for GitHub Code Scanning.
-pub fn print_sarif(result: &ScanResult) {
+/// Print violations as a JUnit XML report for CI test reporters (Jenkins,
+/// GitLab, CircleCI). Each rule in `rule_ids` is a test case, or with
+/// `per_violation` each violation is; errors are failures, and warnings are
+/// listed in the output of passing cases.
+pub fn print_junit(result: &ScanResult, rule_ids: &[String], per_violation: bool) {
+    let mut out = std::io::stdout();
+    write_junit(result, rule_ids, per_violation, &mut out);
+}
+
+fn write_junit(result: &ScanResult, rule_ids: &[String], per_violation: bool, out: &mut dyn Write) {
+    let mut by_rule: BTreeMap<&str, Vec<&Violation>> = rule_ids.iter().map(|id| (id.as_str(), Vec::new())).collect();
+    for v in &result.violations {
+        by_rule.entry(v.rule_id.as_str()).or_default().push(v);
+    }
+    let location = |v: &Violation| {
+        format!(
+            "{}:{}:{}",
+            v.file.display(),
+            v.line.unwrap_or(1),
+            v.column.unwrap_or(1)
+        )
+    };
+
+    let mut cases = Vec::new();
+    for (rule_id, violations) in &by_rule {
+        if per_violation && !violations.is_empty() {
+            for v in violations {
+                let loc = location(v);
+                let mut case = format!(
+                    "    <testcase classname=\"guardrails.{}\" name=\"{}\" file=\"{}\"",
+                    xml_escape(rule_id),
+                    xml_escape(&loc),
+                    xml_escape(&v.file.to_string_lossy())
+                );
+                if let Some(line) = v.line {
+                    case.push_str(&format!(" line=\"{}\"", line));
+                }
+                let mut body = format!("{}: {}\n", loc, v.message);
+                if let Some(ref suggest) = v.suggest {
+                    body.push_str(&format!("{}\n", suggest));
+                }
+                match v.severity {
+                    Severity::Error => case.push_str(&format!(
+                        ">\n      <failure message=\"{}\" type=\"error\">{}</failure>\n    </testcase>",
+                        xml_escape(&v.message),
+                        xml_escape(&body)
+                    )),
+                    Severity::Warning => case.push_str(&format!(
+                        ">\n      <system-out>warning: {}</system-out>\n    </testcase>",
+                        xml_escape(&body)
+                    )),
+                }
+                cases.push((case, v.severity == Severity::Error));
+            }
+            continue;
+        }
+
+        let errors: Vec<&&Violation> = violations.iter().filter(|v| v.severity == Severity::Error).collect();
+        let warnings: Vec<&&Violation> = violations.iter().filter(|v| v.severity == Severity::Warning).collect();
+        let mut case = format!("    <testcase classname=\"guardrails\" name=\"{}\"", xml_escape(rule_id));
+        if errors.is_empty() && warnings.is_empty() {
+            case.push_str("/>");
+        } else {
+            case.push_str(">\n");
+            if !errors.is_empty() {
+                let lines: String = errors
+                    .iter()
+                    .map(|v| format!("{}: {}\n", location(v), v.message))
+                    .collect();
+                case.push_str(&format!(
+                    "      <failure message=\"{} violation{}\" type=\"error\">{}</failure>\n",
+                    errors.len(),
+                    if errors.len() == 1 { "" } else { "s" },
+                    xml_escape(&lines)
+                ));
+            }
+            if !warnings.is_empty() {
+                let lines: String = warnings
+                    .iter()
+                    .map(|v| format!("warning: {}: {}\n", location(v), v.message))
+                    .collect();
+                case.push_str(&format!("      <system-out>{}</system-out>\n", xml_escape(&lines)));
+            }
+            case.push_str("    </testcase>");
+        }
+        cases.push((case, !errors.is_empty()));
+    }
+
+    let failures = cases.iter().filter(|(_, failed)| *failed).count();
+    let _ = writeln!(out, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>");
+    let _ = writeln!(
+        out,
+        "<testsuites name=\"guardrails\" tests=\"{}\" failures=\"{}\" errors=\"0\">",
+        cases.len(),
+        failures
+    );
+    let _ = writeln!(
+        out,
+        "  <testsuite name=\"guardrails\" tests=\"{}\" failures=\"{}\" errors=\"0\" skipped=\"0\">",
+        cases.len(),
+        failures
+    );
+    for (case, _) in &cases {
+        let _ = writeln!(out, "{}", case);
+    }
+    let _ = writeln!(out, "  </testsuite>");
+    let _ = writeln!(out, "</testsuites>");
+}
+
+/// Escape text for XML attributes and element content.
+fn xml_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // Not allowed in XML 1.0 at all
+            c if (c as u32) < 0x20 && !matches!(c, '\t' | '\n' | '\r') => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Print violations in SARIF v2.1.0 format for GitHub Code Scanning. With
+/// `redact_source`, fixes (whose replacement text is source) are left out.
+pub fn print_sarif(result: &ScanResult, redact_source: bool) {
     let mut out = std::io::stdout();
-    write_sarif(result, &mut out);
+    write_sarif(result, redact_source, &mut out);
 }
 
-fn write_sarif(result: &ScanResult, out: &mut dyn Write) {
+fn write_sarif(result: &ScanResult, redact_source: bool, out: &mut dyn Write) {
     // Collect unique rules
     let mut rule_ids: Vec<String> = result
         .violations
@@ -335,17 +1487,29 @@ fn write_sarif(result: &ScanResult, out: &mut dyn Write) {
     let rules: Vec<serde_json::Value> = rule_ids
         .iter()
         .map(|id| {
-            json!({
+            let mut rule = json!({
                 "id": id,
                 "shortDescription": { "text": id },
-            })
+            });
+            if let Some(owner) = result.owners.get(id) {
+                rule["help"] = json!({ "text": owner.summary() });
+            }
+            let docs = result.suggestions.get(id).and_then(|s| s.docs.as_ref());
+            let url = result.violations.iter().find(|v| v.rule_id == *id).and_then(|v| v.url.as_ref());
+            if let Some(url) = url.or(docs) {
+                rule["helpUri"] = json!(url);
+            }
+            rule
         })
         .collect();
 
+    let fingerprints = fingerprint::fingerprints(&result.violations);
+    let fingerprint_key = format!("guardrails/{}", fingerprint::FINGERPRINT_VERSION);
     let results: Vec<serde_json::Value> = result
         .violations
         .iter()
-        .map(|v| {
+        .zip(&fingerprints)
+        .map(|(v, fp)| {
             let level = match v.severity {
                 Severity::Error => "error",
                 Severity::Warning => "warning",
@@ -369,10 +1533,11 @@ fn write_sarif(result: &ScanResult, out: &mut dyn Write) {
                 "level": level,
                 "message": { "text": v.message },
                 "locations": [location],
+                "partialFingerprints": { fingerprint_key.as_str(): fp },
             });
 
             // Add fix if available
-            if let Some(ref fix) = v.fix {
+            if let Some(fix) = v.fix.as_ref().filter(|_| !redact_source) {
                 result_obj["fixes"] = json!([{
                     "description": { "text": v.suggest.as_deref().unwrap_or("Apply fix") },
                     "artifactChanges": [{
@@ -380,9 +1545,15 @@ fn write_sarif(result: &ScanResult, out: &mut dyn Write) {
                             "uri": v.file.display().to_string(),
                         },
                         "replacements": [{
-                            "deletedRegion": {
-                                "startLine": v.line.unwrap_or(1),
-                                "startColumn": v.column.unwrap_or(1),
+                            "deletedRegion": match &fix.range {
+                                Some(range) => json!({
+                                    "byteOffset": range.start,
+                                    "byteLength": range.len(),
+                                }),
+                                None => json!({
+                                    "startLine": v.line.unwrap_or(1),
+                                    "startColumn": v.column.unwrap_or(1),
+                                }),
                             },
                             "insertedContent": { "text": &fix.new }
                         }]
@@ -394,7 +1565,7 @@ fn write_sarif(result: &ScanResult, out: &mut dyn Write) {
         })
         .collect();
 
-    let sarif = json!({
+    let mut sarif = json!({
         "$schema": "https://raw.githubusercontent.com/oasis-tcs/sarif-spec/main/sarif-2.1/schema/sarif-schema-2.1.0.json",
         "version": "2.1.0",
         "runs": [{
@@ -409,17 +1580,63 @@ fn write_sarif(result: &ScanResult, out: &mut dyn Write) {
             "results": results,
         }]
     });
+    if let Some(ref metadata) = result.metadata {
+        let run = &mut sarif["runs"][0];
+        run["invocations"] = json!([{
+            "executionSuccessful": !result.incomplete,
+            "startTimeUtc": metadata.scanned_at,
+        }]);
+        run["properties"] = json!({ "metadata": metadata });
+    }
 
     let _ = writeln!(out, "{}", serde_json::to_string_pretty(&sarif).unwrap());
 }
 
+/// Print violations as a Code Climate issues array, the report format of
+/// GitLab's Code Quality merge request widget.
+pub fn print_codeclimate(result: &ScanResult) {
+    let mut out = std::io::stdout();
+    write_codeclimate(result, &mut out);
+}
+
+fn write_codeclimate(result: &ScanResult, out: &mut dyn Write) {
+    let fingerprints = fingerprint::fingerprints(&result.violations);
+    let issues: Vec<_> = result
+        .violations
+        .iter()
+        .zip(&fingerprints)
+        .map(|(v, fingerprint)| {
+            let path = v.file.to_string_lossy().replace('\\', "/");
+            json!({
+                "type": "issue",
+                "check_name": v.rule_id,
+                "description": v.message,
+                "categories": ["Style"],
+                "fingerprint": fingerprint,
+                "severity": match v.severity {
+                    Severity::Error => "major",
+                    Severity::Warning => "minor",
+                },
+                "location": {
+                    "path": path.strip_prefix("./").unwrap_or(&path),
+                    "positions": {
+                        "begin": { "line": v.line.unwrap_or(1), "column": v.column.unwrap_or(1) },
+                    },
+                },
+            })
+        })
+        .collect();
+
+    let _ = writeln!(out, "{}", serde_json::to_string_pretty(&issues).unwrap());
+}
+
 /// Print violations as a Markdown report (for GitHub PR summaries).
-pub fn print_markdown(result: &ScanResult) {
+pub fn print_markdown(result: &ScanResult, links: &Links) {
     let mut out = std::io::stdout();
-    write_markdown(result, &mut out);
+    write_markdown(result, links, &mut out);
 }
 
-fn write_markdown(result: &ScanResult, out: &mut dyn Write) {
+fn write_markdown(result: &ScanResult, links: &Links, out: &mut dyn Write) {
     let _ = writeln!(out, "## Guardrails Report\n");
 
     let errors = result
@@ -489,10 +1706,41 @@ fn write_markdown(result: &ScanResult, out: &mut dyn Write) {
         .collect();
 
     if !error_violations.is_empty() {
-        write_markdown_severity_section(out, "Errors", &error_violations);
+        write_markdown_severity_section(result, links, out, "Errors", &error_violations);
     }
     if !warning_violations.is_empty() {
-        write_markdown_severity_section(out, "Warnings", &warning_violations);
+        write_markdown_severity_section(result, links, out, "Warnings", &warning_violations);
+    }
+    if let Some(v) = result.violations.first() {
+        let _ = writeln!(
+            out,
+            "> Run `guardrails explain {}` (or any rule id) to see why a rule exists and how to fix it.\n",
+            v.rule_id
+        );
+    }
+
+    // Who to ask about the rules that fired
+    let mut owned: Vec<_> = result
+        .owners
+        .iter()
+        .filter(|(id, _)| result.violations.iter().any(|v| &v.rule_id == *id))
+        .collect();
+    if !owned.is_empty() {
+        owned.sort_by_key(|(id, _)| (*id).clone());
+        let _ = writeln!(out, "### Rule Owners\n");
+        let _ = writeln!(out, "| Rule | Owner | Contact | Escalation |");
+        let _ = writeln!(out, "|------|-------|---------|------------|");
+        for (rule_id, owner) in owned {
+            let _ = writeln!(
+                out,
+                "| `{}` | {} | {} | {} |",
+                rule_id,
+                owner.owner.as_deref().unwrap_or(""),
+                owner.contact.as_deref().unwrap_or(""),
+                owner.escalation.as_deref().unwrap_or("")
+            );
+        }
+        let _ = writeln!(out);
     }
 
     // Ratchet section
@@ -505,60 +1753,1071 @@ fn write_markdown(result: &ScanResult, out: &mut dyn Write) {
         sorted.sort_by_key(|(id, _)| (*id).clone());
 
         for (rule_id, &(found, max)) in &sorted {
-            let status = if found <= max {
-                "\\:white_check_mark: pass"
-            } else {
+            let status = if result.ratchet_over(rule_id) {
                 "\\:x: OVER"
+            } else {
+                "\\:white_check_mark: pass"
             };
             let _ = writeln!(out, "| `{}` | {} | {}/{} |", rule_id, status, found, max);
         }
-        let _ = writeln!(out);
+        let _ = writeln!(out);
+    }
+}
+
+fn write_markdown_severity_section(
+    result: &ScanResult,
+    links: &Links,
+    out: &mut dyn Write,
+    title: &str,
+    violations: &[&Violation],
+) {
+    let _ = writeln!(out, "### {}\n", title);
+
+    // Group by file
+    let mut by_file: BTreeMap<String, Vec<&&Violation>> = BTreeMap::new();
+    for v in violations {
+        by_file
+            .entry(v.file.display().to_string())
+            .or_default()
+            .push(v);
+    }
+
+    for (file, file_violations) in &by_file {
+        let _ = writeln!(out, "**`{}`**\n", file);
+        let _ = writeln!(out, "| Line | Rule | Message | Suggestion |");
+        let _ = writeln!(out, "|------|------|---------|------------|");
+
+        for v in file_violations {
+            let line = v.line.map(|l| l.to_string()).unwrap_or_else(|| "-".to_string());
+            let line = match links.url(result, v) {
+                Some(url) => format!("[{}]({})", line, url),
+                None => line,
+            };
+            let suggest = match (v.suggest.as_deref(), suggestion_for(result, v).and_then(|s| s.docs.as_ref())) {
+                (Some(suggest), Some(docs)) => format!("[{}]({})", suggest, docs),
+                (suggest, _) => suggest.unwrap_or("").to_string(),
+            };
+            let rule = match v.url {
+                Some(ref url) => format!("[`{}`]({})", v.rule_id, url),
+                None => format!("`{}`", v.rule_id),
+            };
+            let _ = writeln!(out, "| {} | {} | {} | {} |", line, rule, v.message, suggest);
+        }
+        let _ = writeln!(out);
+    }
+}
+
+/// Print the configured rules with ANSI colors.
+pub fn print_rules_pretty(rules: &[RuleInfo]) {
+    let mut out = std::io::stdout();
+    write_rules_pretty(rules, &mut out);
+}
+
+fn write_rules_pretty(rules: &[RuleInfo], out: &mut dyn Write) {
+    for rule in rules {
+        let severity_str = match rule.severity {
+            Severity::Error => "\x1b[31merror\x1b[0m",
+            Severity::Warning => "\x1b[33mwarn \x1b[0m",
+        };
+        let _ = write!(
+            out,
+            "{} \x1b[1m{:<30}\x1b[0m \x1b[90m{}",
+            severity_str, rule.id, rule.rule_type
+        );
+        if let Some(ref source) = rule.source {
+            let _ = write!(out, " (from {})", source);
+        }
+        let _ = writeln!(out, "\x1b[0m");
+        if !rule.message.is_empty() {
+            let _ = writeln!(out, "      {}", rule.message);
+        }
+        if let Some(ref owner) = rule.owner {
+            let _ = writeln!(out, "      \x1b[90m↳ {}\x1b[0m", owner.summary());
+        }
+    }
+    let _ = writeln!(
+        out,
+        "\n{} rule{}",
+        rules.len(),
+        if rules.len() == 1 { "" } else { "s" }
+    );
+}
+
+/// Print the configured rules as JSON.
+pub fn print_rules_json(rules: &[RuleInfo]) {
+    let mut out = std::io::stdout();
+    let output = json!({ "rules": rules });
+    let _ = writeln!(out, "{}", serde_json::to_string_pretty(&output).unwrap());
+}
+
+/// Print the configured rules as a markdown document, one section per rule.
+pub fn print_rules_markdown(rules: &[RuleInfo]) {
+    let mut out = std::io::stdout();
+    let _ = writeln!(out, "# Guardrails rules\n");
+    for rule in rules {
+        write_rule_markdown(rule, &mut out);
+    }
+}
+
+/// Print everything known about one rule, examples included.
+pub fn print_explain_pretty(rule: &RuleInfo) {
+    let mut out = std::io::stdout();
+    write_explain_pretty(rule, &mut out);
+}
+
+fn write_explain_pretty(rule: &RuleInfo, out: &mut dyn Write) {
+    let severity_str = match rule.severity {
+        Severity::Error => "\x1b[31merror\x1b[0m",
+        Severity::Warning => "\x1b[33mwarning\x1b[0m",
+    };
+    let _ = writeln!(
+        out,
+        "\x1b[1m{}\x1b[0m  {} \x1b[90m{}\x1b[0m",
+        rule.id, severity_str, rule.rule_type
+    );
+    if !rule.message.is_empty() {
+        let _ = writeln!(out, "  {}", rule.message);
+    }
+    if let Some(ref rationale) = rule.rationale {
+        let _ = writeln!(out, "\n  \x1b[1mWhy\x1b[0m");
+        for line in rationale.trim_end().lines() {
+            let _ = writeln!(out, "  {}", line);
+        }
+        let _ = writeln!(out);
+    }
+    if let Some(ref suggest) = rule.suggest {
+        let _ = writeln!(out, "  \x1b[36m→ {}\x1b[0m", suggest);
+    }
+    if let Some(ref suggestion) = rule.suggestion {
+        if let Some(ref replacement) = suggestion.replacement {
+            for line in replacement.trim_end().lines() {
+                let _ = writeln!(out, "    \x1b[32m{}\x1b[0m", line);
+            }
+        }
+        if let Some(ref docs) = suggestion.docs {
+            let _ = writeln!(out, "  \x1b[90mdocs: {}\x1b[0m", docs);
+        }
+    }
+    if let Some(ref docs) = rule.docs {
+        let _ = writeln!(out, "  \x1b[90mdocs: {}\x1b[0m", docs);
+    }
+    if let Some(ref url) = rule.url {
+        let _ = writeln!(out, "  \x1b[90mconvention: {}\x1b[0m", url);
+    }
+    if let Some(ref glob) = rule.glob {
+        let _ = writeln!(out, "  \x1b[90mapplies to {}\x1b[0m", glob);
+    }
+    if let Some(ref source) = rule.source {
+        let _ = writeln!(out, "  \x1b[90mfrom {}\x1b[0m", source);
+    }
+    if let Some(ref owner) = rule.owner {
+        let _ = writeln!(out, "  \x1b[90m↳ {}\x1b[0m", owner.summary());
+    }
+
+    let Some(ref examples) = rule.examples else {
+        return;
+    };
+    for (label, color, glyph, snippets) in [
+        ("Bad", "\x1b[31m", "✗", &examples.bad),
+        ("Good", "\x1b[32m", "✓", &examples.good),
+    ] {
+        if snippets.is_empty() {
+            continue;
+        }
+        let _ = writeln!(out, "\n  {}{} {}\x1b[0m", color, glyph, label);
+        for snippet in snippets {
+            for line in snippet.trim_end().lines() {
+                let _ = writeln!(out, "    {}{}\x1b[0m", color, line);
+            }
+        }
+    }
+}
+
+/// Print one rule as markdown, e.g. for pasting into a PR comment.
+pub fn print_explain_markdown(rule: &RuleInfo) {
+    let mut out = std::io::stdout();
+    write_rule_markdown(rule, &mut out);
+}
+
+fn write_rule_markdown(rule: &RuleInfo, out: &mut dyn Write) {
+    let severity = match rule.severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+    };
+    let _ = writeln!(out, "## `{}`\n", rule.id);
+    let _ = writeln!(out, "**{}** · `{}`\n", severity, rule.rule_type);
+    if !rule.message.is_empty() {
+        let _ = writeln!(out, "{}\n", rule.message);
+    }
+    if let Some(ref rationale) = rule.rationale {
+        let _ = writeln!(out, "**Why:** {}\n", rationale.trim_end());
+    }
+    if let Some(ref suggest) = rule.suggest {
+        let _ = writeln!(out, "**Suggestion:** {}\n", suggest);
+    }
+    if let Some(ref suggestion) = rule.suggestion {
+        if let Some(ref replacement) = suggestion.replacement {
+            let lang = snippet_language(rule.glob.as_deref());
+            let _ = writeln!(out, "```{}\n{}\n```\n", lang, replacement.trim_end());
+        }
+        if let Some(ref docs) = suggestion.docs {
+            let _ = writeln!(out, "[Docs]({})\n", docs);
+        }
+    }
+    if let Some(ref docs) = rule.docs {
+        let _ = writeln!(out, "[Rule docs]({})\n", docs);
+    }
+    if let Some(ref url) = rule.url {
+        let _ = writeln!(out, "[Convention]({})\n", url);
+    }
+    if let Some(ref glob) = rule.glob {
+        let _ = writeln!(out, "Applies to `{}`.\n", glob);
+    }
+    if let Some(ref owner) = rule.owner {
+        let _ = writeln!(out, "_{}_\n", owner.summary());
+    }
+    if let Some(ref examples) = rule.examples {
+        let lang = snippet_language(rule.glob.as_deref());
+        for (label, snippets) in [("Bad", &examples.bad), ("Good", &examples.good)] {
+            if snippets.is_empty() {
+                continue;
+            }
+            let _ = writeln!(out, "**{}:**\n", label);
+            for snippet in snippets {
+                let _ = writeln!(out, "```{}\n{}\n```\n", lang, snippet.trim_end());
+            }
+        }
+    }
+}
+
+/// Code fence language for a rule's snippets, from the extension its glob
+/// targets (`"**/*.{ts,tsx}"` → `ts`); empty when the glob doesn't say.
+fn snippet_language(glob: Option<&str>) -> &str {
+    let Some((_, ext)) = glob.and_then(|g| g.rsplit_once('.')) else {
+        return "";
+    };
+    let ext = ext.trim_start_matches('{').split([',', '}']).next().unwrap_or("");
+    if !ext.is_empty() && ext.chars().all(|c| c.is_ascii_alphanumeric()) {
+        ext
+    } else {
+        ""
+    }
+}
+
+/// A pending fix: optional 1-indexed line and column, old text, new text, rule id.
+type LineFix<'a> = (Option<usize>, Option<usize>, &'a str, &'a str, &'a str);
+
+/// Print one path per line (`--list-files`), or NUL-terminated with `nul`.
+pub fn print_file_list(files: &[PathBuf], nul: bool) {
+    let mut out = std::io::stdout();
+    write_file_list(files, nul, &mut out);
+}
+
+fn write_file_list(files: &[PathBuf], nul: bool, out: &mut dyn Write) {
+    for file in files {
+        let _ = write!(out, "{}{}", file.display(), if nul { '\0' } else { '\n' });
+    }
+}
+
+/// Print `--list-files --explain`: the files to scan, then each skipped file
+/// with its reason and a count per reason.
+pub fn print_file_report(report: &FileReport) {
+    let mut out = std::io::stdout();
+    write_file_report(report, &mut out);
+}
+
+fn write_file_report(report: &FileReport, out: &mut dyn Write) {
+    write_file_list(&report.files, false, out);
+    if report.skipped.is_empty() {
+        return;
+    }
+    let width = report.skipped.iter().map(|(_, r)| r.as_str().len()).max().unwrap_or(0);
+    let _ = writeln!(out);
+    for (file, reason) in &report.skipped {
+        let _ = writeln!(out, "\x1b[90m{:<width$}\x1b[0m  {}", reason, file.display(), width = width);
+    }
+    let counts: Vec<String> = report
+        .skip_counts()
+        .iter()
+        .map(|(reason, count)| format!("{} {}", count, reason))
+        .collect();
+    let _ = writeln!(
+        out,
+        "\n\x1b[1m{} scanned, {} skipped\x1b[0m ({})",
+        report.files.len(),
+        report.skipped.len(),
+        counts.join(", ")
+    );
+}
+
+/// Print `--list-files --explain` as JSON.
+pub fn print_file_report_json(report: &FileReport) {
+    let mut out = std::io::stdout();
+    write_file_report_json(report, &mut out);
+}
+
+fn write_file_report_json(report: &FileReport, out: &mut dyn Write) {
+    let skipped: Vec<_> = report
+        .skipped
+        .iter()
+        .map(|(file, reason)| json!({ "file": file.display().to_string(), "reason": reason }))
+        .collect();
+    let output = json!({
+        "files": report.files.iter().map(|f| f.display().to_string()).collect::<Vec<_>>(),
+        "skipped": skipped,
+        "summary": {
+            "files": report.files.len(),
+            "skipped": report.skip_counts(),
+        },
+    });
+    let _ = writeln!(out, "{}", serde_json::to_string_pretty(&output).unwrap());
+}
+
+/// Print suggested reviewers for violated files with ANSI colors.
+pub fn print_reviewers_pretty(report: &ReviewerReport) {
+    let mut out = std::io::stdout();
+    write_reviewers_pretty(report, &mut out);
+}
+
+fn write_reviewers_pretty(report: &ReviewerReport, out: &mut dyn Write) {
+    if report.reviewers.is_empty() && report.unowned_files.is_empty() {
+        let _ = writeln!(out, "\x1b[32m✓\x1b[0m No violations — no reviewers to suggest");
+        return;
+    }
+
+    if !report.reviewers.is_empty() {
+        let _ = writeln!(out, "\x1b[1mSuggested reviewers:\x1b[0m");
+        for r in &report.reviewers {
+            let _ = writeln!(
+                out,
+                "  {:<30} \x1b[90m{} violation{} in {} file{}\x1b[0m",
+                r.owner,
+                r.violations,
+                if r.violations == 1 { "" } else { "s" },
+                r.files.len(),
+                if r.files.len() == 1 { "" } else { "s" }
+            );
+        }
+    }
+
+    if !report.unowned_files.is_empty() {
+        let _ = writeln!(out, "\n\x1b[1mNo owner:\x1b[0m");
+        for f in &report.unowned_files {
+            let _ = writeln!(out, "  \x1b[90m{}\x1b[0m", f.display());
+        }
+    }
+}
+
+/// Print suggested reviewers as JSON for bots requesting reviews.
+pub fn print_reviewers_json(report: &ReviewerReport) {
+    let mut out = std::io::stdout();
+    let _ = writeln!(out, "{}", serde_json::to_string_pretty(report).unwrap());
+}
+
+/// Print a config-diff preview with ANSI colors.
+pub fn print_config_diff_pretty(diff: &ConfigDiff) {
+    let mut out = std::io::stdout();
+    write_config_diff_pretty(diff, &mut out);
+}
+
+fn write_config_diff_pretty(diff: &ConfigDiff, out: &mut dyn Write) {
+    let _ = writeln!(out, "\x1b[1mRule changes:\x1b[0m");
+    for r in &diff.rules {
+        let marker = match r.status {
+            RuleStatus::Added => "\x1b[32m+\x1b[0m",
+            RuleStatus::Removed => "\x1b[31m-\x1b[0m",
+            RuleStatus::Kept => " ",
+        };
+        let delta = match r.delta() {
+            d if d > 0 => format!("\x1b[31m+{}\x1b[0m", d),
+            d if d < 0 => format!("\x1b[32m{}\x1b[0m", d),
+            _ => "\x1b[90m±0\x1b[0m".to_string(),
+        };
+        let _ = writeln!(
+            out,
+            "  {} {:<30} {:>5} → {:<5} {}",
+            marker, r.rule_id, r.before, r.after, delta
+        );
+    }
+
+    let _ = writeln!(
+        out,
+        "\n\x1b[1m{} → {} violations\x1b[0m ({} files scanned)",
+        diff.total_before, diff.total_after, diff.files_scanned
+    );
+}
+
+/// Print a config-diff preview as JSON.
+pub fn print_config_diff_json(diff: &ConfigDiff) {
+    let mut out = std::io::stdout();
+    let _ = writeln!(out, "{}", serde_json::to_string_pretty(diff).unwrap());
+}
+
+/// Print `guardrails diff-results` with ANSI colors.
+pub fn print_result_diff_pretty(diff: &ResultDiff) {
+    let mut out = std::io::stdout();
+    write_result_diff_pretty(diff, &mut out);
+}
+
+fn write_result_diff_pretty(diff: &ResultDiff, out: &mut dyn Write) {
+    let location = |file: &Path, line: Option<usize>| match line {
+        Some(l) => format!("{}:{}", file.display(), l),
+        None => file.display().to_string(),
+    };
+    for (heading, marker, violations) in [
+        ("Added", "\x1b[31m+\x1b[0m", &diff.added),
+        ("Resolved", "\x1b[32m-\x1b[0m", &diff.resolved),
+    ] {
+        if violations.is_empty() {
+            continue;
+        }
+        let _ = writeln!(out, "\x1b[1m{} ({}):\x1b[0m", heading, violations.len());
+        for v in violations {
+            let _ = writeln!(
+                out,
+                "  {} {} \x1b[90m{}\x1b[0m {}",
+                marker,
+                location(&v.file, v.line),
+                v.rule_id,
+                v.message
+            );
+        }
+        let _ = writeln!(out);
+    }
+
+    if !diff.ratchets.is_empty() {
+        let _ = writeln!(out, "\x1b[1mRatchets:\x1b[0m");
+        let count = |c: Option<result_diff::RatchetCount>| match c {
+            Some(c) => format!("{}/{}", c.found, c.max),
+            None => "-".to_string(),
+        };
+        for r in &diff.ratchets {
+            let delta = match r.delta() {
+                d if d > 0 => format!("\x1b[31m+{}\x1b[0m", d),
+                d if d < 0 => format!("\x1b[32m{}\x1b[0m", d),
+                _ => "\x1b[90m±0\x1b[0m".to_string(),
+            };
+            let _ = writeln!(
+                out,
+                "  {:<30} {:>7} → {:<7} {}",
+                r.budget,
+                count(r.before),
+                count(r.after),
+                delta
+            );
+        }
+        let _ = writeln!(out);
+    }
+
+    let _ = writeln!(
+        out,
+        "\x1b[1m{} added, {} resolved\x1b[0m ({} unchanged)",
+        diff.added.len(),
+        diff.resolved.len(),
+        diff.unchanged
+    );
+}
+
+/// Print `guardrails diff-results` as JSON.
+pub fn print_result_diff_json(diff: &ResultDiff) {
+    let mut out = std::io::stdout();
+    let _ = writeln!(out, "{}", serde_json::to_string_pretty(diff).unwrap());
+}
+
+/// Print `guardrails doctor` findings with ANSI colors.
+pub fn print_doctor_pretty(report: &DoctorReport) {
+    let mut out = std::io::stdout();
+    write_doctor_pretty(report, &mut out);
+}
+
+fn write_doctor_pretty(report: &DoctorReport, out: &mut dyn Write) {
+    for f in &report.findings {
+        let _ = writeln!(
+            out,
+            "\x1b[33m!\x1b[0m \x1b[1m{}\x1b[0m: {} \x1b[90m[{}]\x1b[0m",
+            f.rule_id,
+            f.message,
+            f.check.label()
+        );
+    }
+    let checked = format!(
+        "{} rule{} checked against {} file{}",
+        report.rules_checked,
+        if report.rules_checked == 1 { "" } else { "s" },
+        report.files_scanned,
+        if report.files_scanned == 1 { "" } else { "s" }
+    );
+    if report.findings.is_empty() {
+        let _ = writeln!(out, "\x1b[32m✓\x1b[0m No config problems ({})", checked);
+    } else {
+        let n = report.findings.len();
+        let _ = writeln!(
+            out,
+            "\n\x1b[1m{} problem{}\x1b[0m ({})",
+            n,
+            if n == 1 { "" } else { "s" },
+            checked
+        );
+    }
+}
+
+/// Print `guardrails doctor` findings as JSON.
+pub fn print_doctor_json(report: &DoctorReport) {
+    let mut out = std::io::stdout();
+    let _ = writeln!(out, "{}", serde_json::to_string_pretty(report).unwrap());
+}
+
+/// Print `guardrails check-config` diagnostics rustc-style, each under the
+/// config line it points at.
+pub fn print_check_config_pretty(report: &CheckReport) {
+    let mut out = std::io::stdout();
+    write_check_config_pretty(report, &mut out);
+}
+
+fn write_check_config_pretty(report: &CheckReport, out: &mut dyn Write) {
+    let path = report.path.display();
+    let lines: Vec<&str> = report.source.lines().collect();
+    for d in &report.diagnostics {
+        let label = match d.level {
+            Level::Error => "\x1b[31merror\x1b[0m",
+            Level::Warning => "\x1b[33mwarning\x1b[0m",
+        };
+        let _ = writeln!(out, "{}\x1b[1m: {}\x1b[0m", label, d.message);
+        let (Some(line), Some(column)) = (d.line, d.column) else {
+            let _ = writeln!(out, "  \x1b[34m-->\x1b[0m {}\n", path);
+            continue;
+        };
+        let gutter = " ".repeat(line.to_string().len());
+        let _ = writeln!(out, "{} \x1b[34m-->\x1b[0m {}:{}:{}", gutter, path, line, column);
+        if let Some(text) = lines.get(line - 1) {
+            let text = text.replace('\t', " ");
+            let _ = writeln!(out, "{} \x1b[34m|\x1b[0m", gutter);
+            let _ = writeln!(out, "\x1b[34m{} |\x1b[0m {}", line, text);
+            let color = if d.level == Level::Error { "31" } else { "33" };
+            let _ = writeln!(
+                out,
+                "{} \x1b[34m|\x1b[0m {}\x1b[{}m{}\x1b[0m",
+                gutter,
+                " ".repeat(column - 1),
+                color,
+                "^".repeat(d.width.max(1))
+            );
+        }
+        let _ = writeln!(out);
+    }
+    let checked = format!(
+        "{} rule{} checked",
+        report.rules_checked,
+        if report.rules_checked == 1 { "" } else { "s" }
+    );
+    let (errors, warnings) = (report.errors(), report.warnings());
+    if errors == 0 && warnings == 0 {
+        let _ = writeln!(out, "\x1b[32m✓\x1b[0m {} is valid ({})", path, checked);
+    } else {
+        let _ = writeln!(
+            out,
+            "\x1b[1m{} error{}, {} warning{}\x1b[0m in {} ({})",
+            errors,
+            if errors == 1 { "" } else { "s" },
+            warnings,
+            if warnings == 1 { "" } else { "s" },
+            path,
+            checked
+        );
+    }
+}
+
+/// Print `guardrails check-config` diagnostics as JSON.
+pub fn print_check_config_json(report: &CheckReport) {
+    let mut out = std::io::stdout();
+    let _ = writeln!(out, "{}", serde_json::to_string_pretty(report).unwrap());
+}
+
+/// Print `guardrails bundle verify` results with ANSI colors.
+pub fn print_bundle_verification_pretty(verification: &Verification) {
+    let mut out = std::io::stdout();
+    write_bundle_verification_pretty(verification, &mut out);
+}
+
+fn write_bundle_verification_pretty(verification: &Verification, out: &mut dyn Write) {
+    let policy = match verification.policy_version {
+        Some(ref version) => format!("policy {}", version),
+        None => "the approved policy".to_string(),
+    };
+    if verification.matches() {
+        let _ = writeln!(
+            out,
+            "\x1b[32m✓\x1b[0m config matches {} \x1b[90m({})\x1b[0m",
+            policy, verification.expected
+        );
+        return;
+    }
+    let _ = writeln!(out, "\x1b[31m✗\x1b[0m \x1b[1mconfig differs from {}\x1b[0m", policy);
+    let _ = writeln!(out, "  expected {}", verification.expected);
+    let _ = writeln!(out, "  found    {}", verification.actual);
+    for (ids, label) in [
+        (&verification.added, "\x1b[33madded\x1b[0m  "),
+        (&verification.removed, "\x1b[31mremoved\x1b[0m"),
+        (&verification.changed, "\x1b[36mchanged\x1b[0m"),
+    ] {
+        for id in ids {
+            let _ = writeln!(out, "  {} {}", label, id);
+        }
+    }
+}
+
+/// Print `guardrails bundle verify` results as JSON.
+pub fn print_bundle_verification_json(verification: &Verification) {
+    let mut out = std::io::stdout();
+    let mut output = serde_json::to_value(verification).unwrap();
+    output["matches"] = json!(verification.matches());
+    let _ = writeln!(out, "{}", serde_json::to_string_pretty(&output).unwrap());
+}
+
+/// Print `guardrails ratchet update` results with ANSI colors.
+pub fn print_ratchet_update_pretty(update: &RatchetUpdate, dry_run: bool) {
+    let mut out = std::io::stdout();
+    write_ratchet_update_pretty(update, dry_run, &mut out);
+}
+
+fn write_ratchet_update_pretty(update: &RatchetUpdate, dry_run: bool, out: &mut dyn Write) {
+    for t in &update.tightened {
+        let _ = writeln!(out, "  \x1b[1m{:<30}\x1b[0m {} → \x1b[32m{}\x1b[0m", t.budget, t.from, t.to);
+    }
+    for o in &update.over_budget {
+        let _ = writeln!(
+            out,
+            "  \x1b[1m{:<30}\x1b[0m \x1b[31mover budget ({}/{}), not tightened\x1b[0m",
+            o.budget, o.found, o.max
+        );
+    }
+    for budget in &update.not_in_config {
+        let _ = writeln!(
+            out,
+            "  \x1b[90m{:<30} can be tightened, but isn't declared in the config file\x1b[0m",
+            budget
+        );
+    }
+    for (rule_id, &scopes) in &update.recorded {
+        let _ = writeln!(
+            out,
+            "  \x1b[1m{:<30}\x1b[0m {} current counts for {} scope{}",
+            rule_id,
+            if dry_run { "would record" } else { "recorded" },
+            scopes,
+            if scopes == 1 { "" } else { "s" }
+        );
+    }
+    let n = update.tightened.len();
+    let scanned = format!(
+        "{} file{} scanned",
+        update.files_scanned,
+        if update.files_scanned == 1 { "" } else { "s" }
+    );
+    if n == 0 && update.recorded.is_empty() && update.over_budget.is_empty() {
+        let _ = writeln!(out, "\x1b[32m✓\x1b[0m Every ratchet is at its current count ({})", scanned);
+    } else if n == 0 {
+        let _ = writeln!(out, "\n({})", scanned);
+    } else {
+        let _ = writeln!(
+            out,
+            "\n\x1b[1m{} ratchet{} {}tightened\x1b[0m ({})",
+            n,
+            if n == 1 { "" } else { "s" },
+            if dry_run { "would be " } else { "" },
+            scanned
+        );
+    }
+}
+
+/// Print `guardrails ratchet update` results as JSON.
+pub fn print_ratchet_update_json(update: &RatchetUpdate) {
+    let mut out = std::io::stdout();
+    let _ = writeln!(out, "{}", serde_json::to_string_pretty(update).unwrap());
+}
+
+/// Print `guardrails update-config` results with ANSI colors.
+pub fn print_lock_update_pretty(changes: &[LockChange]) {
+    let mut out = std::io::stdout();
+    write_lock_update_pretty(changes, &mut out);
+}
+
+fn write_lock_update_pretty(changes: &[LockChange], out: &mut dyn Write) {
+    // Enough of a hash to tell versions apart
+    let short = |digest: &str| digest.chars().take("sha256:".len() + 12).collect::<String>();
+    for change in changes {
+        let _ = match (&change.from, &change.to) {
+            (Some(from), Some(to)) => writeln!(
+                out,
+                "  \x1b[33m~\x1b[0m {} {} → \x1b[32m{}\x1b[0m",
+                change.source,
+                short(from),
+                short(to)
+            ),
+            (None, Some(to)) => writeln!(out, "  \x1b[32m+\x1b[0m {} {}", change.source, short(to)),
+            _ => writeln!(out, "  \x1b[31m-\x1b[0m {} \x1b[90m(no longer extended)\x1b[0m", change.source),
+        };
+    }
+    if changes.is_empty() {
+        let _ = writeln!(out, "\x1b[32m✓\x1b[0m {} is up to date", extends::LOCK_FILE);
+    } else {
+        let _ = writeln!(
+            out,
+            "\n\x1b[32m✓\x1b[0m Updated {} ({} source{} changed)",
+            extends::LOCK_FILE,
+            changes.len(),
+            if changes.len() == 1 { "" } else { "s" }
+        );
+    }
+}
+
+/// Print `guardrails update-config` results as JSON.
+pub fn print_lock_update_json(changes: &[LockChange]) {
+    let mut out = std::io::stdout();
+    let _ = writeln!(out, "{}", serde_json::to_string_pretty(&json!({ "changed": changes })).unwrap());
+}
+
+/// Directories listed per rule in the pretty preview; JSON has them all.
+const PREVIEW_DIRECTORIES: usize = 10;
+
+/// Print a rule preview with ANSI colors.
+pub fn print_preview_pretty(preview: &Preview) {
+    let mut out = std::io::stdout();
+    write_preview_pretty(preview, &mut out);
+}
+
+fn write_preview_pretty(preview: &Preview, out: &mut dyn Write) {
+    for rule in &preview.rules {
+        let budget = match rule.max_count {
+            Some(max) if rule.total <= max => format!(" \x1b[32m(max {})\x1b[0m", max),
+            Some(max) => format!(" \x1b[31m(max {}, {} over)\x1b[0m", max, rule.total - max),
+            None => String::new(),
+        };
+        let _ = writeln!(
+            out,
+            "\x1b[1m{}\x1b[0m: {} match{} in {} file{}{}",
+            rule.rule_id,
+            rule.total,
+            if rule.total == 1 { "" } else { "es" },
+            rule.files,
+            if rule.files == 1 { "" } else { "s" },
+            budget
+        );
+        for dir in rule.directories.iter().take(PREVIEW_DIRECTORIES) {
+            let _ = writeln!(out, "  {:>6}  {}", dir.count, dir.directory);
+        }
+        if rule.directories.len() > PREVIEW_DIRECTORIES {
+            let _ = writeln!(
+                out,
+                "  \x1b[90m… {} more directories\x1b[0m",
+                rule.directories.len() - PREVIEW_DIRECTORIES
+            );
+        }
+    }
+
+    let _ = writeln!(out, "\n\x1b[90m{} files scanned\x1b[0m", preview.files_scanned);
+}
+
+/// Print `guardrails test` results: each case with what it missed or didn't
+/// expect, then the tally.
+pub fn print_test_pretty(report: &TestReport) {
+    let mut out = std::io::stdout();
+    write_test_pretty(report, &mut out);
+}
+
+fn write_test_pretty(report: &TestReport, out: &mut dyn Write) {
+    let at = |line: Option<usize>| line.map_or_else(|| "in the file".to_string(), |line| format!("at line {}", line));
+    for case in &report.cases {
+        if case.passed() {
+            let _ = writeln!(out, "\x1b[32m✓\x1b[0m {}", case.name);
+            continue;
+        }
+        let path = if case.path == case.name {
+            String::new()
+        } else {
+            format!(" \x1b[90m({})\x1b[0m", case.path)
+        };
+        let _ = writeln!(out, "\x1b[31m✗\x1b[0m \x1b[1m{}\x1b[0m{}", case.name, path);
+        for e in &case.missing {
+            let _ = writeln!(out, "    missing     \x1b[1m{}\x1b[0m {}", e.rule, at(e.line));
+        }
+        for u in &case.unexpected {
+            let _ = writeln!(
+                out,
+                "    unexpected  \x1b[1m{}\x1b[0m {}: {}",
+                u.rule,
+                at(u.line),
+                u.message
+            );
+        }
+    }
+    let failed = report.failed();
+    let passed = report.cases.len() - failed;
+    let color = if failed == 0 { "32" } else { "31" };
+    let _ = writeln!(
+        out,
+        "\n\x1b[1;{}m{} passed, {} failed\x1b[0m ({} test{})",
+        color,
+        passed,
+        failed,
+        report.cases.len(),
+        if report.cases.len() == 1 { "" } else { "s" }
+    );
+}
+
+/// Print `guardrails test` results as JSON.
+pub fn print_test_json(report: &TestReport) {
+    let mut out = std::io::stdout();
+    let _ = writeln!(out, "{}", serde_json::to_string_pretty(report).unwrap());
+}
+
+/// Print a rule preview as JSON.
+pub fn print_preview_json(preview: &Preview) {
+    let mut out = std::io::stdout();
+    let _ = writeln!(out, "{}", serde_json::to_string_pretty(preview).unwrap());
+}
+
+/// Print a `--timing` report to stderr, optionally with per-rule memory.
+pub fn print_timing(profile: &ScanProfile, memory: bool) {
+    let mut err = std::io::stderr();
+    write_timing(profile, memory, &mut err);
+}
+
+fn write_timing(profile: &ScanProfile, memory: bool, err: &mut dyn Write) {
+    let _ = writeln!(err, "\n\x1b[1mRule timing\x1b[0m \x1b[90m(scan {:.2?}, summed across threads)\x1b[0m", profile.wall);
+    for (rule_id, p) in &profile.rules {
+        let _ = write!(err, "  {:<30} {:>10.2?}  \x1b[90m{} files\x1b[0m", rule_id, p.time, p.files);
+        if memory {
+            let _ = write!(
+                err,
+                "  \x1b[90m{} allocs, {} total, {} peak/file\x1b[0m",
+                p.allocations,
+                format_bytes(p.alloc_bytes),
+                format_bytes(p.peak_bytes)
+            );
+        }
+        let _ = writeln!(err);
+    }
+
+    let phases = &profile.phases;
+    let _ = writeln!(err, "  \x1b[90mwalk    {:.2?}: listing files\x1b[0m", phases.walk_time);
+    let _ = writeln!(
+        err,
+        "  \x1b[90mread    {:.2?}: reading files, summed across reader threads\x1b[0m",
+        phases.read_time
+    );
+    let _ = writeln!(
+        err,
+        "  \x1b[90mfilters {:.2?}: {} files read, {} with no rule left, {} rule runs skipped by pre-filter{}\x1b[0m",
+        phases.filter_time,
+        phases.files_read,
+        phases.files_filtered,
+        phases.prefiltered,
+        if phases.skipped_large > 0 {
+            format!(", {} files over max_file_size", phases.skipped_large)
+        } else {
+            String::new()
+        }
+    );
+    let _ = writeln!(
+        err,
+        "  \x1b[90mrules   {:.2?}: {} rule runs\x1b[0m",
+        phases.rule_time, phases.rule_runs
+    );
+    match profile.cold_build {
+        Some(cold) => {
+            let _ = writeln!(
+                err,
+                "  \x1b[90mbuild   {:.2?}: warm start from the compile cache, {:.2?} saved (cold build {:.2?})\x1b[0m",
+                profile.build,
+                cold.saturating_sub(profile.build),
+                cold
+            );
+        }
+        None => {
+            let _ = writeln!(err, "  \x1b[90mbuild   {:.2?}: rules built\x1b[0m", profile.build);
+        }
+    }
+    let regexes = &profile.regexes;
+    if regexes.requested > 0 {
+        let _ = writeln!(
+            err,
+            "  \x1b[90mregexes {} requested, {} compiled ({:.1}x dedup)\x1b[0m",
+            regexes.requested,
+            regexes.compiled,
+            regexes.dedup_ratio()
+        );
+    }
+
+    if !profile.slowest_files.is_empty() {
+        let _ = writeln!(err, "\n\x1b[1mSlowest files\x1b[0m \x1b[90m(filters and rules)\x1b[0m");
+        for (file, time) in &profile.slowest_files {
+            let _ = writeln!(err, "  {:>10.2?}  {}", time, file.display());
+        }
+    }
+
+    if memory {
+        match profile.peak_bytes {
+            Some(peak) => {
+                let _ = writeln!(err, "  peak heap {}", format_bytes(peak));
+            }
+            None => {
+                let _ = writeln!(err, "  \x1b[90mallocation tracking unavailable\x1b[0m");
+            }
+        }
     }
 }
 
-fn write_markdown_severity_section(out: &mut dyn Write, title: &str, violations: &[&Violation]) {
-    let _ = writeln!(out, "### {}\n", title);
+/// The `timing` block of JSON output for a scan run with `--timing`.
+fn timing_json(profile: &ScanProfile) -> serde_json::Value {
+    let ms = |d: std::time::Duration| round2(d.as_secs_f64() * 1000.0);
+    let phases = &profile.phases;
+    json!({
+        "wall_ms": ms(profile.wall),
+        "build_ms": ms(profile.build),
+        "phases": {
+            "walk_ms": ms(phases.walk_time),
+            "read_ms": ms(phases.read_time),
+            "filter_ms": ms(phases.filter_time),
+            "match_ms": ms(phases.rule_time),
+            "files_read": phases.files_read,
+            "files_filtered": phases.files_filtered,
+            "prefiltered": phases.prefiltered,
+            "rule_runs": phases.rule_runs,
+        },
+        "rules": profile.rules.iter().map(|(rule_id, p)| json!({
+            "rule_id": rule_id,
+            "ms": ms(p.time),
+            "files": p.files,
+        })).collect::<Vec<_>>(),
+        "slowest_files": profile.slowest_files.iter().map(|(file, time)| json!({
+            "file": file.display().to_string(),
+            "ms": ms(*time),
+        })).collect::<Vec<_>>(),
+    })
+}
 
-    // Group by file
-    let mut by_file: BTreeMap<String, Vec<&&Violation>> = BTreeMap::new();
-    for v in violations {
-        by_file
-            .entry(v.file.display().to_string())
-            .or_default()
-            .push(v);
+/// Print a benchmark report with ANSI colors.
+pub fn print_bench_pretty(report: &BenchReport) {
+    let mut out = std::io::stdout();
+    write_bench_pretty(report, &mut out);
+}
+
+fn write_bench_pretty(report: &BenchReport, out: &mut dyn Write) {
+    let _ = writeln!(
+        out,
+        "\x1b[1m{} files, {:.1} MB, {} run{}\x1b[0m",
+        report.files,
+        report.bytes as f64 / 1_000_000.0,
+        report.iterations,
+        if report.iterations == 1 { "" } else { "s" }
+    );
+    let _ = writeln!(
+        out,
+        "  mean {:.2?}  best {:.2?}  \x1b[36m{:.0} files/sec  {:.1} MB/sec\x1b[0m",
+        report.mean(),
+        report.best(),
+        report.files_per_sec(),
+        report.mb_per_sec()
+    );
+    if let Some(peak) = report.peak_bytes {
+        let _ = writeln!(out, "  peak heap {}", format_bytes(peak));
     }
 
-    for (file, file_violations) in &by_file {
-        let _ = writeln!(out, "**`{}`**\n", file);
-        let _ = writeln!(out, "| Line | Rule | Message | Suggestion |");
-        let _ = writeln!(out, "|------|------|---------|------------|");
+    if report.rules.is_empty() {
+        return;
+    }
 
-        for v in file_violations {
-            let line = v.line.map(|l| l.to_string()).unwrap_or_else(|| "-".to_string());
-            let suggest = v.suggest.as_deref().unwrap_or("");
-            let _ = writeln!(
+    let _ = writeln!(out, "\n\x1b[1mPer-rule cost (per run):\x1b[0m");
+    for (rule_id, p) in &report.rules {
+        let _ = write!(out, "  {:<30} {:>10.2?}  \x1b[90m{} files\x1b[0m", rule_id, p.time, p.files);
+        if report.peak_bytes.is_some() {
+            let _ = write!(
                 out,
-                "| {} | `{}` | {} | {} |",
-                line, v.rule_id, v.message, suggest
+                "  \x1b[90m{} allocs, {}\x1b[0m",
+                p.allocations,
+                format_bytes(p.alloc_bytes)
             );
         }
         let _ = writeln!(out);
     }
 }
 
+/// Print a benchmark report as JSON (durations in microseconds).
+pub fn print_bench_json(report: &BenchReport) {
+    let rules: Vec<_> = report
+        .rules
+        .iter()
+        .map(|(rule_id, p)| {
+            json!({
+                "rule_id": rule_id,
+                "time_us": p.time.as_micros() as u64,
+                "files": p.files,
+                "allocations": p.allocations,
+                "alloc_bytes": p.alloc_bytes,
+            })
+        })
+        .collect();
+
+    let output = json!({
+        "iterations": report.iterations,
+        "files": report.files,
+        "bytes": report.bytes,
+        "mean_us": report.mean().as_micros() as u64,
+        "best_us": report.best().as_micros() as u64,
+        "files_per_sec": report.files_per_sec(),
+        "mb_per_sec": report.mb_per_sec(),
+        "peak_bytes": report.peak_bytes,
+        "rules": rules,
+    });
+
+    let mut out = std::io::stdout();
+    let _ = writeln!(out, "{}", serde_json::to_string_pretty(&output).unwrap());
+}
+
+fn format_bytes(bytes: u64) -> String {
+    match bytes {
+        b if b >= 1_000_000 => format!("{:.1} MB", b as f64 / 1_000_000.0),
+        b if b >= 1_000 => format!("{:.1} KB", b as f64 / 1_000.0),
+        b => format!("{} B", b),
+    }
+}
+
+/// How `apply_fixes` writes its changes.
+#[derive(Debug, Default, Clone)]
+pub struct FixOptions {
+    /// Print the changes to stderr as a unified diff instead of writing them.
+    pub dry_run: bool,
+    /// Copy each file to `<file>.orig` before rewriting it.
+    pub backup: bool,
+    /// When the scan started; files modified after it are skipped, since
+    /// their violations may no longer line up with the text.
+    pub scanned_at: Option<SystemTime>,
+    /// Append a `guardrails:fixed <rule>` comment to every rewritten line.
+    pub marker: bool,
+    /// `[filetypes]` mappings, so markers use the comment syntax of a file's type.
+    pub file_types: FileTypes,
+}
+
 /// Apply fixes from violations to source files. Returns the number of fixes applied.
-/// Fixes are targeted to the specific line where the violation occurred to avoid
-/// accidentally replacing a different occurrence of the same pattern.
-pub fn apply_fixes(result: &ScanResult, dry_run: bool) -> usize {
-    // Group fixable violations by file, keeping line info for targeted replacement
-    let mut fixes_by_file: BTreeMap<String, Vec<(Option<usize>, &str, &str)>> = BTreeMap::new();
+/// Fixes are targeted to the specific line and column where the violation occurred
+/// to avoid accidentally replacing a different occurrence of the same pattern.
+///
+/// Each file is rewritten through a temporary file renamed over it, so an
+/// interrupted run never leaves it half-written; its permissions and line
+/// endings are kept. Files changed since the scan read them are skipped.
+pub fn apply_fixes(result: &ScanResult, options: &FixOptions) -> usize {
+    let mut stderr = std::io::stderr();
+    write_fixes(result, options, &mut stderr)
+}
+
+fn write_fixes(result: &ScanResult, options: &FixOptions, diff_out: &mut dyn Write) -> usize {
+    // Group fixable violations by file, keeping positions for targeted replacement
+    let mut fixes_by_file: BTreeMap<String, Vec<(&Violation, &Fix)>> = BTreeMap::new();
 
     for v in &result.violations {
         if let Some(ref fix) = v.fix {
-            fixes_by_file
-                .entry(v.file.display().to_string())
-                .or_default()
-                .push((v.line, &fix.old, &fix.new));
+            fixes_by_file.entry(v.file.display().to_string()).or_default().push((v, fix));
         }
     }
 
@@ -566,57 +2825,354 @@ pub fn apply_fixes(result: &ScanResult, dry_run: bool) -> usize {
 
     for (file_path, fixes) in &fixes_by_file {
         let path = Path::new(file_path);
+        let Ok(metadata) = std::fs::metadata(path) else {
+            continue;
+        };
         let content = match std::fs::read_to_string(path) {
             Ok(c) => c,
             Err(_) => continue,
         };
-
-        let mut lines: Vec<String> = content.lines().map(|l| l.to_string()).collect();
-        // Preserve trailing newline if present
-        let trailing_newline = content.ends_with('\n');
-        let mut applied = 0;
-
-        for (line_num, old, new) in fixes {
-            if let Some(ln) = line_num {
-                // Line-targeted: only replace within the specific line (1-indexed)
-                if *ln > 0 && *ln <= lines.len() {
-                    let line = &lines[*ln - 1];
-                    if line.contains(*old) {
-                        lines[*ln - 1] = line.replacen(*old, *new, 1);
-                        applied += 1;
-                    }
-                }
-            } else {
-                // No line info — fall back to first-occurrence replacement
-                let joined = lines.join("\n");
-                if joined.contains(*old) {
-                    let modified = joined.replacen(*old, *new, 1);
-                    lines = modified.lines().map(|l| l.to_string()).collect();
-                    applied += 1;
-                }
-            }
-        }
-
-        if applied > 0 && !dry_run {
-            let mut modified = lines.join("\n");
-            if trailing_newline {
-                modified.push('\n');
-            }
-            if let Err(e) = std::fs::write(path, &modified) {
+        let read_at = metadata.modified().ok();
+        if let (Some(modified), Some(scanned_at)) = (read_at, options.scanned_at) {
+            if modified > scanned_at {
                 eprintln!(
-                    "\x1b[31merror\x1b[0m: failed to write {}: {}",
-                    file_path, e
+                    "\x1b[33mwarning\x1b[0m: skipped {}: modified since it was scanned",
+                    file_path
                 );
                 continue;
             }
         }
 
+        let marker = options
+            .marker
+            .then(|| options.file_types.file_type(path).and_then(CommentStyle::for_file_type))
+            .flatten();
+        // A fix's byte range, where it still holds its text, is where it goes
+        let starts = line_endings::starts(&content);
+        let fixes: Vec<LineFix> = fixes
+            .iter()
+            .map(|&(v, fix)| {
+                let at_range = fix.range.as_ref().filter(|range| content.get((*range).clone()) == Some(fix.old.as_str()));
+                let (line, column) = match at_range {
+                    Some(range) => {
+                        let (line, column) = line_endings::position(&starts, range.start);
+                        (Some(line), Some(column))
+                    }
+                    None => (v.line, v.column),
+                };
+                (line, column, fix.old.as_str(), fix.new.as_str(), v.rule_id.as_str())
+            })
+            .collect();
+        let (modified, applied) = fix_content(&content, &fixes, marker);
+        if applied == 0 || modified == content {
+            continue;
+        }
+        if options.dry_run {
+            let _ = write!(diff_out, "{}", unified_diff(file_path, &content, &modified));
+        } else if let Err(e) = replace_file(path, &modified, &metadata, options.backup) {
+            eprintln!(
+                "\x1b[31merror\x1b[0m: failed to write {}: {}",
+                file_path, e
+            );
+            continue;
+        }
+
         total_applied += applied;
     }
 
     total_applied
 }
 
+/// Atomically replace the file at `path`, last read with `metadata`, by `contents`.
+///
+/// Writes a sibling temporary file with the original permissions and renames
+/// it into place. Fails without touching the file if it changed since it was
+/// read. With `backup`, the original is first copied to `<path>.orig`.
+fn replace_file(
+    path: &Path,
+    contents: &str,
+    metadata: &std::fs::Metadata,
+    backup: bool,
+) -> std::io::Result<()> {
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let tmp = path.with_file_name(format!(".{}.guardrails-fix", file_name));
+    let write_tmp = || -> std::io::Result<()> {
+        let mut file = std::fs::File::create(&tmp)?;
+        file.write_all(contents.as_bytes())?;
+        file.sync_all()?;
+        std::fs::set_permissions(&tmp, metadata.permissions())
+    };
+    if let Err(e) = write_tmp() {
+        let _ = std::fs::remove_file(&tmp);
+        return Err(e);
+    }
+
+    let current = std::fs::metadata(path)?;
+    if current.modified().ok() != metadata.modified().ok() || current.len() != metadata.len() {
+        let _ = std::fs::remove_file(&tmp);
+        return Err(std::io::Error::other("file changed while fixes were applied"));
+    }
+    if backup {
+        let mut orig = path.as_os_str().to_owned();
+        orig.push(".orig");
+        if let Err(e) = std::fs::copy(path, &orig) {
+            let _ = std::fs::remove_file(&tmp);
+            return Err(e);
+        }
+    }
+    std::fs::rename(&tmp, path).inspect_err(|_| {
+        let _ = std::fs::remove_file(&tmp);
+    })
+}
+
+/// `content` with `fixes` applied, and how many of them applied.
+///
+/// Each fix replaces its `old` text at the violation's column, or at the first
+/// occurrence on its line not claimed by another fix when the column doesn't
+/// point at it. A fix overlapping one already placed on the same line is
+/// skipped, so conflicting fixes never garble the text. A fix with an empty
+/// `old` inserts `new` at its column. With a `marker` style, each rewritten
+/// line gets a fix marker naming the rules that fixed it.
+fn fix_content(content: &str, fixes: &[LineFix], marker: Option<CommentStyle>) -> (String, usize) {
+    // Each line's text and its own ending, so CRLF and missing final newlines survive
+    let (mut lines, mut endings): (Vec<String>, Vec<&str>) = line_endings::split(content)
+        .map(|(text, ending)| (text.to_string(), ending))
+        .unzip();
+    if lines.is_empty() {
+        // An empty file still has a first line to insert into
+        lines.push(String::new());
+        endings.push("");
+    }
+    let mut applied = 0;
+
+    // Byte ranges to replace, per 0-indexed line
+    let mut edits: BTreeMap<usize, Vec<(usize, usize, &str, &str)>> = BTreeMap::new();
+    let mut unlined = Vec::new();
+    for &(line_num, column, old, new, rule_id) in fixes {
+        let Some(ln) = line_num.filter(|_| !old.is_empty() || column.is_some()) else {
+            if !old.is_empty() {
+                unlined.push((old, new));
+            }
+            continue;
+        };
+        if ln == 0 || ln > lines.len() {
+            continue;
+        }
+        let text = &lines[ln - 1];
+        let line_edits = edits.entry(ln - 1).or_default();
+        let free = |start: usize| {
+            let end = start + old.len();
+            !line_edits.iter().any(|&(s, e, _, _)| start < e && s < end)
+        };
+        let at_column = column
+            .and_then(|c| c.checked_sub(1))
+            .filter(|&start| text.get(start..).is_some_and(|rest| rest.starts_with(old)));
+        let start = match at_column {
+            Some(start) => Some(start).filter(|&start| free(start)),
+            None if old.is_empty() => None,
+            None => text.match_indices(old).map(|(start, _)| start).find(|&start| free(start)),
+        };
+        if let Some(start) = start {
+            line_edits.push((start, start + old.len(), new, rule_id));
+            applied += 1;
+        }
+    }
+    for (ln, mut line_edits) in edits {
+        // Right to left, so earlier ranges stay valid
+        line_edits.sort_by_key(|&(start, _, _, _)| std::cmp::Reverse(start));
+        for &(start, end, new, _) in &line_edits {
+            lines[ln].replace_range(start..end, new);
+        }
+        if let (Some(style), false) = (marker, line_edits.is_empty()) {
+            let rule_ids: Vec<&str> = line_edits.iter().map(|&(_, _, _, rule_id)| rule_id).collect();
+            lines[ln] = mark_line(&lines[ln], &rule_ids, style);
+        }
+    }
+
+    let mut modified: String = lines.iter().zip(&endings).map(|(l, e)| format!("{}{}", l, e)).collect();
+    for (old, new) in unlined {
+        // No line info — fall back to first-occurrence replacement
+        if modified.contains(old) {
+            modified = modified.replacen(old, new, 1);
+            applied += 1;
+        }
+    }
+
+    (modified, applied)
+}
+
+/// Tag in the comments `--fix-marker` leaves on rewritten lines.
+const FIX_MARKER: &str = "guardrails:fixed";
+
+/// Comment syntax a fix marker is written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CommentStyle {
+    /// `/* … */`
+    Block,
+    /// `{/* … */}`, after a JSX tag
+    Jsx,
+    /// `# …`
+    Hash,
+    /// `<!-- … -->`
+    Html,
+}
+
+impl CommentStyle {
+    const ALL: [CommentStyle; 4] = [Self::Jsx, Self::Block, Self::Html, Self::Hash];
+
+    /// The style for a file of type `file_type`, `None` where no marker should go.
+    fn for_file_type(file_type: &str) -> Option<Self> {
+        match file_type {
+            "jsx" | "tsx" => Some(Self::Jsx),
+            "js" | "mjs" | "cjs" | "ts" | "mts" | "cts" | "css" | "scss" | "less" | "rs" | "go"
+            | "java" | "kt" | "swift" | "c" | "h" | "cc" | "cpp" | "hpp" | "cs" => Some(Self::Block),
+            "py" | "rb" | "sh" | "bash" | "yml" | "yaml" | "toml" => Some(Self::Hash),
+            "html" | "htm" | "vue" | "svelte" | "md" | "xml" => Some(Self::Html),
+            _ => None,
+        }
+    }
+
+    fn delimiters(self) -> (&'static str, &'static str) {
+        match self {
+            Self::Block => ("/*", " */"),
+            Self::Jsx => ("{/*", " */}"),
+            Self::Hash => ("#", ""),
+            Self::Html => ("<!--", " -->"),
+        }
+    }
+}
+
+/// `text` without its trailing fix marker, and the rule ids the marker named.
+fn split_fix_marker(text: &str) -> (&str, Vec<&str>) {
+    for style in CommentStyle::ALL {
+        let (open, close) = style.delimiters();
+        let prefix = format!("{} {} ", open, FIX_MARKER);
+        let Some(pos) = text.rfind(&prefix) else {
+            continue;
+        };
+        if let Some(ids) = text[pos + prefix.len()..].strip_suffix(close) {
+            let ids = ids.split(',').map(str::trim).filter(|id| !id.is_empty()).collect();
+            return (text[..pos].trim_end(), ids);
+        }
+    }
+    (text, Vec::new())
+}
+
+/// `text` with a fix marker naming `rule_ids`, merged with any marker it already has.
+///
+/// In JSX files a line ending in a tag gets `{/* … */}` so the marker isn't
+/// rendered as text; other lines get a plain block comment.
+fn mark_line(text: &str, rule_ids: &[&str], style: CommentStyle) -> String {
+    let (code, mut ids) = split_fix_marker(text);
+    ids.extend_from_slice(rule_ids);
+    ids.sort_unstable();
+    ids.dedup();
+    let in_jsx = code.ends_with('>') && !code.ends_with("=>");
+    let style = match style {
+        CommentStyle::Jsx if !in_jsx => CommentStyle::Block,
+        other => other,
+    };
+    let (open, close) = style.delimiters();
+    format!("{} {} {} {}{}", code, open, FIX_MARKER, ids.join(", "), close)
+}
+
+/// `content` with every fix marker removed, and how many were removed.
+fn strip_fix_markers_from(content: &str) -> (String, usize) {
+    let mut stripped = 0;
+    let out = line_endings::split(content)
+        .map(|(text, ending)| {
+            let (code, ids) = split_fix_marker(text);
+            if ids.is_empty() {
+                return format!("{}{}", text, ending);
+            }
+            stripped += 1;
+            format!("{}{}", code, ending)
+        })
+        .collect();
+    (out, stripped)
+}
+
+/// Remove the markers `--fix-marker` left in `files` (`--strip-fix-markers`).
+/// Returns the number of markers removed.
+pub fn strip_fix_markers(files: &[PathBuf]) -> usize {
+    let mut total = 0;
+    for path in files {
+        let Ok(metadata) = std::fs::metadata(path) else {
+            continue;
+        };
+        let Ok(content) = std::fs::read_to_string(path) else {
+            continue;
+        };
+        let (stripped, count) = strip_fix_markers_from(&content);
+        if count == 0 {
+            continue;
+        }
+        if let Err(e) = replace_file(path, &stripped, &metadata, false) {
+            eprintln!(
+                "\x1b[31merror\x1b[0m: failed to write {}: {}",
+                path.display(),
+                e
+            );
+            continue;
+        }
+        total += count;
+    }
+    total
+}
+
+/// Lines of context around each change in `unified_diff`.
+const DIFF_CONTEXT: usize = 3;
+
+/// A unified diff from `before` to `after` of the file at `path`.
+///
+/// Fixes replace text within lines, so lines are compared pairwise; if the
+/// line count changed, the whole file becomes one hunk.
+fn unified_diff(path: &str, before: &str, after: &str) -> String {
+    let old: Vec<&str> = line_endings::lines(before).collect();
+    let new: Vec<&str> = line_endings::lines(after).collect();
+    let path = path.strip_prefix("./").unwrap_or(path);
+    let mut out = if Path::new(path).is_absolute() {
+        format!("--- {0}\n+++ {0}\n", path)
+    } else {
+        format!("--- a/{0}\n+++ b/{0}\n", path)
+    };
+
+    if old.len() != new.len() {
+        out.push_str(&format!("@@ -1,{} +1,{} @@\n", old.len(), new.len()));
+        old.iter().for_each(|l| out.push_str(&format!("-{}\n", l)));
+        new.iter().for_each(|l| out.push_str(&format!("+{}\n", l)));
+        return out;
+    }
+
+    let changed: Vec<usize> = (0..old.len()).filter(|&i| old[i] != new[i]).collect();
+    let mut next = 0;
+    while next < changed.len() {
+        // Extend the hunk while the next change is within reach of its context
+        let mut last = next;
+        while last + 1 < changed.len() && changed[last + 1] - changed[last] <= 2 * DIFF_CONTEXT + 1 {
+            last += 1;
+        }
+        let start = changed[next].saturating_sub(DIFF_CONTEXT);
+        let end = (changed[last] + DIFF_CONTEXT + 1).min(old.len());
+        out.push_str(&format!("@@ -{0},{1} +{0},{1} @@\n", start + 1, end - start));
+
+        let mut i = start;
+        while i < end {
+            if old[i] == new[i] {
+                out.push_str(&format!(" {}\n", old[i]));
+                i += 1;
+                continue;
+            }
+            let run_end = (i..end).find(|&k| old[k] == new[k]).unwrap_or(end);
+            old[i..run_end].iter().for_each(|l| out.push_str(&format!("-{}\n", l)));
+            new[i..run_end].iter().for_each(|l| out.push_str(&format!("+{}\n", l)));
+            i = run_end;
+        }
+        next = last + 1;
+    }
+    out
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -628,9 +3184,7 @@ mod tests {
             violations,
             files_scanned: 5,
             rules_loaded: 2,
-            ratchet_counts: HashMap::new(),
-            changed_files_count: None,
-            base_ref: None,
+            ..Default::default()
         }
     }
 
@@ -652,6 +3206,7 @@ mod tests {
             suggest: None,
             source_line: None,
             fix: None,
+            url: None,
         }
     }
 
@@ -734,7 +3289,7 @@ mod tests {
         )]);
         let mut out = Vec::new();
         let mut err = Vec::new();
-        write_github(&result, &mut out, &mut err);
+        write_github(&result, &|_| false, &mut out, &mut err);
 
         let stdout = String::from_utf8(out).unwrap();
         assert_eq!(
@@ -755,11 +3310,12 @@ mod tests {
             suggest: None,
             source_line: None,
             fix: None,
+            url: None,
         };
         let result = make_result(vec![v]);
         let mut out = Vec::new();
         let mut err = Vec::new();
-        write_github(&result, &mut out, &mut err);
+        write_github(&result, &|_| false, &mut out, &mut err);
 
         let stdout = String::from_utf8(out).unwrap();
         assert_eq!(stdout, "::error file=a.ts,line=3,title=test::msg\n");
@@ -774,7 +3330,7 @@ mod tests {
             .insert("legacy-api".to_string(), (10, 5));
         let mut out = Vec::new();
         let mut err = Vec::new();
-        write_github(&result, &mut out, &mut err);
+        write_github(&result, &|_| false, &mut out, &mut err);
 
         let stdout = String::from_utf8(out).unwrap();
         assert!(stdout.contains("::error title=ratchet-legacy-api"));
@@ -789,19 +3345,86 @@ mod tests {
             .insert("legacy-api".to_string(), (3, 5));
         let mut out = Vec::new();
         let mut err = Vec::new();
-        write_github(&result, &mut out, &mut err);
+        write_github(&result, &|_| false, &mut out, &mut err);
 
         let stdout = String::from_utf8(out).unwrap();
         assert!(stdout.is_empty());
     }
 
+    #[test]
+    fn github_budget_ranks_errors_and_new_lines_first() {
+        let mut violations = Vec::new();
+        for i in 1..=12 {
+            violations.push(make_violation("a.ts", i, 1, Severity::Warning, "warn-rule", "w"));
+        }
+        for i in 1..=11 {
+            violations.push(make_violation("b.ts", i, 1, Severity::Error, "err-rule", "e"));
+        }
+        let result = make_result(violations);
+        let mut out = Vec::new();
+        let mut err = Vec::new();
+        write_github(&result, &|v| v.line == Some(11), &mut out, &mut err);
+
+        let stdout = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = stdout.lines().collect();
+        // The new error comes first, then the other errors, then warnings
+        assert_eq!(lines[0], "::error file=b.ts,line=11,col=1,title=err-rule::e");
+        assert!(lines[1].starts_with("::error file=b.ts,line=1,"));
+        assert_eq!(lines[10], "::warning file=a.ts,line=11,col=1,title=warn-rule::w");
+        assert_eq!(stdout.matches("::error ").count(), 10);
+        assert_eq!(stdout.matches("::warning ").count(), 10);
+        assert!(stdout.contains("::notice title=guardrails::1 more error and 2 more warnings not annotated"));
+        // Overflow stays visible in the log, just not as annotations
+        assert!(stdout.contains("\nerror file=b.ts,line=10,col=1,title=err-rule: e\n"));
+        assert!(stdout.contains("\nwarning file=a.ts,line=12,col=1,title=warn-rule: w\n"));
+    }
+
     // ── write_markdown tests ──
 
+    #[test]
+    fn junit_one_case_per_rule() {
+        let mut v = make_violation("src/a.tsx", 3, 5, Severity::Error, "no-eval", "eval is <unsafe> & slow");
+        v.suggest = Some("use JSON.parse".into());
+        let result = make_result(vec![
+            v,
+            make_violation("src/b.tsx", 7, 1, Severity::Warning, "no-console", "console"),
+        ]);
+        let ids = vec!["clean".to_string(), "no-console".to_string(), "no-eval".to_string()];
+        let mut out = Vec::new();
+        write_junit(&result, &ids, false, &mut out);
+        let output = String::from_utf8(out).unwrap();
+
+        assert!(output.contains(r#"<testsuite name="guardrails" tests="3" failures="1" errors="0" skipped="0">"#));
+        assert!(output.contains(r#"<testcase classname="guardrails" name="clean"/>"#));
+        assert!(output.contains(
+            r#"<failure message="1 violation" type="error">src/a.tsx:3:5: eval is &lt;unsafe&gt; &amp; slow"#
+        ));
+        // Warnings don't fail the case
+        assert!(output.contains("<system-out>warning: src/b.tsx:7:1: console\n</system-out>"));
+    }
+
+    #[test]
+    fn junit_one_case_per_violation() {
+        let result = make_result(vec![
+            make_violation("a.ts", 1, 1, Severity::Error, "no-eval", "no eval"),
+            make_violation("b.ts", 2, 4, Severity::Error, "no-eval", "no eval"),
+        ]);
+        let ids = vec!["clean".to_string(), "no-eval".to_string()];
+        let mut out = Vec::new();
+        write_junit(&result, &ids, true, &mut out);
+        let output = String::from_utf8(out).unwrap();
+
+        assert!(output.contains(r#"tests="3" failures="2""#));
+        assert!(output.contains(r#"<testcase classname="guardrails.no-eval" name="b.ts:2:4" file="b.ts" line="2">"#));
+        assert!(output.contains(r#"<failure message="no eval" type="error">b.ts:2:4: no eval"#));
+        assert!(output.contains(r#"<testcase classname="guardrails" name="clean"/>"#));
+    }
+
     #[test]
     fn markdown_no_violations() {
         let result = make_result(vec![]);
         let mut out = Vec::new();
-        write_markdown(&result, &mut out);
+        write_markdown(&result, &Links::default(), &mut out);
 
         let output = String::from_utf8(out).unwrap();
         assert!(output.contains("## Guardrails Report"));
@@ -817,7 +3440,7 @@ mod tests {
             make_violation("src/b.tsx", 3, 1, Severity::Error, "dark-mode", "missing dark variant"),
         ]);
         let mut out = Vec::new();
-        write_markdown(&result, &mut out);
+        write_markdown(&result, &Links::default(), &mut out);
 
         let output = String::from_utf8(out).unwrap();
         assert!(output.contains("## Guardrails Report"));
@@ -827,6 +3450,7 @@ mod tests {
         assert!(output.contains("`src/a.tsx`"));
         assert!(output.contains("`src/b.tsx`"));
         assert!(output.contains("| Line | Rule | Message | Suggestion |"));
+        assert!(output.contains("> Run `guardrails explain dark-mode` (or any rule id)"));
     }
 
     #[test]
@@ -839,7 +3463,7 @@ mod tests {
             .ratchet_counts
             .insert("old-pattern".to_string(), (10, 5));
         let mut out = Vec::new();
-        write_markdown(&result, &mut out);
+        write_markdown(&result, &Links::default(), &mut out);
 
         let output = String::from_utf8(out).unwrap();
         assert!(output.contains("### Ratchet Rules"));
@@ -858,7 +3482,7 @@ mod tests {
         result.changed_files_count = Some(3);
         result.base_ref = Some("main".into());
         let mut out = Vec::new();
-        write_markdown(&result, &mut out);
+        write_markdown(&result, &Links::default(), &mut out);
 
         let output = String::from_utf8(out).unwrap();
         assert!(output.contains("Scanned 3 changed files against `main`"));
@@ -870,7 +3494,7 @@ mod tests {
         result.changed_files_count = Some(1);
         result.base_ref = Some("develop".into());
         let mut out = Vec::new();
-        write_markdown(&result, &mut out);
+        write_markdown(&result, &Links::default(), &mut out);
 
         let output = String::from_utf8(out).unwrap();
         assert!(output.contains("Scanned 1 changed file against `develop`"));
@@ -882,7 +3506,7 @@ mod tests {
         v.suggest = Some("Use bg-background instead".into());
         let result = make_result(vec![v]);
         let mut out = Vec::new();
-        write_markdown(&result, &mut out);
+        write_markdown(&result, &Links::default(), &mut out);
 
         let output = String::from_utf8(out).unwrap();
         assert!(output.contains("Use bg-background instead"));
@@ -900,10 +3524,11 @@ mod tests {
             suggest: None,
             source_line: None,
             fix: None,
+            url: None,
         };
         let result = make_result(vec![v]);
         let mut out = Vec::new();
-        write_markdown(&result, &mut out);
+        write_markdown(&result, &Links::default(), &mut out);
 
         let output = String::from_utf8(out).unwrap();
         // No line number should show "-"
@@ -970,9 +3595,8 @@ mod tests {
 
     #[test]
     fn ratchet_stderr_empty() {
-        let counts = HashMap::new();
         let mut err = Vec::new();
-        write_ratchet_stderr(&counts, &mut err);
+        write_ratchet_stderr(&ScanResult::default(), &mut err);
 
         let stderr = String::from_utf8(err).unwrap();
         assert!(stderr.is_empty());
@@ -983,8 +3607,12 @@ mod tests {
         let mut counts = HashMap::new();
         counts.insert("a-rule".to_string(), (2usize, 5usize));
         counts.insert("b-rule".to_string(), (10, 3));
+        let result = ScanResult {
+            ratchet_counts: counts,
+            ..Default::default()
+        };
         let mut err = Vec::new();
-        write_ratchet_stderr(&counts, &mut err);
+        write_ratchet_stderr(&result, &mut err);
 
         let stderr = String::from_utf8(err).unwrap();
         assert!(stderr.contains("ratchet: a-rule pass (2/5)"));
@@ -1005,6 +3633,7 @@ mod tests {
             suggest: None,
             source_line: None,
             fix: None,
+            url: None,
         };
         let result = make_result(vec![v]);
         let mut out = Vec::new();
@@ -1029,11 +3658,12 @@ mod tests {
             suggest: None,
             source_line: None,
             fix: None,
+            url: None,
         };
         let result = make_result(vec![v]);
         let mut out = Vec::new();
         let mut err = Vec::new();
-        write_github(&result, &mut out, &mut err);
+        write_github(&result, &|_| false, &mut out, &mut err);
 
         let stdout = String::from_utf8(out).unwrap();
         assert!(stdout.contains("line=1"));
@@ -1061,16 +3691,16 @@ mod tests {
                 fix: Some(crate::rules::Fix {
                     old: "bg-white".into(),
                     new: "bg-background".into(),
+                    range: None,
                 }),
+                url: None,
             }],
             files_scanned: 1,
             rules_loaded: 1,
-            ratchet_counts: HashMap::new(),
-            changed_files_count: None,
-            base_ref: None,
+            ..Default::default()
         };
 
-        let count = apply_fixes(&result, false);
+        let count = apply_fixes(&result, &FixOptions::default());
         assert_eq!(count, 1);
 
         let content = std::fs::read_to_string(&file).unwrap();
@@ -1098,16 +3728,16 @@ mod tests {
                 fix: Some(crate::rules::Fix {
                     old: "bg-white".into(),
                     new: "bg-background".into(),
+                    range: None,
                 }),
+                url: None,
             }],
             files_scanned: 1,
             rules_loaded: 1,
-            ratchet_counts: HashMap::new(),
-            changed_files_count: None,
-            base_ref: None,
+            ..Default::default()
         };
 
-        let count = apply_fixes(&result, false);
+        let count = apply_fixes(&result, &FixOptions::default());
         assert_eq!(count, 1);
 
         let content = std::fs::read_to_string(&file).unwrap();
@@ -1133,29 +3763,135 @@ mod tests {
                 fix: Some(crate::rules::Fix {
                     old: "bg-white".into(),
                     new: "bg-background".into(),
+                    range: None,
                 }),
+                url: None,
             }],
             files_scanned: 1,
             rules_loaded: 1,
-            ratchet_counts: HashMap::new(),
-            changed_files_count: None,
-            base_ref: None,
+            ..Default::default()
         };
 
-        let count = apply_fixes(&result, true);
+        let mut diff = Vec::new();
+        let options = FixOptions {
+            dry_run: true,
+            ..Default::default()
+        };
+        let count = write_fixes(&result, &options, &mut diff);
         assert_eq!(count, 1);
+        let diff = String::from_utf8(diff).unwrap();
+        assert!(diff.ends_with("@@ -1,1 +1,1 @@\n-bg-white\n+bg-background\n"));
 
         // File should not be modified
         let content = std::fs::read_to_string(&file).unwrap();
         assert!(content.contains("bg-white"));
     }
 
+    #[test]
+    fn fixes_apply_at_their_column_and_skip_conflicts() {
+        let content = "a bg-white b bg-white\nbg-white-ish\n";
+        let fixes: Vec<LineFix> = vec![
+            (Some(1), Some(14), "bg-white", "bg-background", "theme"),
+            (Some(1), None, "bg-white", "bg-card", "theme"),
+            // Overlaps the first fix
+            (Some(1), Some(16), "white", "black", "theme"),
+            (Some(2), Some(1), "bg-white-ish", "bg-muted", "theme"),
+        ];
+        let (modified, applied) = fix_content(content, &fixes, None);
+        assert_eq!(applied, 3);
+        assert_eq!(modified, "a bg-card b bg-background\nbg-muted\n");
+    }
+
+    #[test]
+    fn fixes_without_old_text_insert_at_their_column() {
+        let fixes: Vec<LineFix> = vec![
+            (Some(1), Some(10), "", "\n// header", "header"),
+            // Without a position there's nowhere to insert
+            (None, None, "", "lost", "header"),
+            (Some(2), None, "", "lost", "header"),
+        ];
+        let (modified, applied) = fix_content("#!/bin/sh\necho\n", &fixes, None);
+        assert_eq!((modified.as_str(), applied), ("#!/bin/sh\n// header\necho\n", 1));
+
+        let fixes: Vec<LineFix> = vec![(Some(1), Some(1), "", "// header\n", "header")];
+        assert_eq!(fix_content("", &fixes, None), ("// header\n".to_string(), 1));
+        assert_eq!(fix_content("x\n", &fixes, None), ("// header\nx\n".to_string(), 1));
+    }
+
+    #[test]
+    fn fix_markers_name_the_rules_and_strip_cleanly() {
+        let content = "a bg-white text-black\r\n<div className=\"bg-white\">\nok\n";
+        let fixes: Vec<LineFix> = vec![
+            (Some(1), Some(3), "bg-white", "bg-background", "theme"),
+            (Some(1), Some(12), "text-black", "text-foreground", "fg"),
+            (Some(2), None, "bg-white", "bg-background", "theme"),
+        ];
+        let (marked, applied) = fix_content(content, &fixes, Some(CommentStyle::Jsx));
+        assert_eq!(applied, 3);
+        assert_eq!(
+            marked,
+            "a bg-background text-foreground /* guardrails:fixed fg, theme */\r\n\
+             <div className=\"bg-background\"> {/* guardrails:fixed theme */}\nok\n"
+        );
+
+        // A later fix on a marked line merges into its marker
+        let again: Vec<LineFix> = vec![(Some(2), None, "div", "section", "tags")];
+        let (remarked, _) = fix_content(&marked, &again, Some(CommentStyle::Jsx));
+        assert!(remarked.contains("<section className=\"bg-background\"> {/* guardrails:fixed tags, theme */}\n"));
+
+        let (stripped, count) = strip_fix_markers_from(&marked);
+        assert_eq!(count, 2);
+        assert_eq!(
+            stripped,
+            "a bg-background text-foreground\r\n<div className=\"bg-background\">\nok\n"
+        );
+        assert_eq!(CommentStyle::for_file_type("py"), Some(CommentStyle::Hash));
+        assert_eq!(CommentStyle::for_file_type("json"), None);
+    }
+
+    #[test]
+    fn fixes_and_markers_keep_crlf_endings() {
+        let fixes: Vec<LineFix> = vec![
+            (Some(1), Some(1), "bg-white", "bg-background", "theme"),
+            (Some(3), Some(5), "bg-white", "bg-background", "theme"),
+        ];
+        // An unterminated last line ending in a bare CR keeps it after the marker
+        let (fixed, applied) = fix_content("bg-white\r\nok\r\n    bg-white\r", &fixes, Some(CommentStyle::Block));
+        assert_eq!(applied, 2);
+        assert_eq!(
+            fixed,
+            "bg-background /* guardrails:fixed theme */\r\nok\r\n    bg-background /* guardrails:fixed theme */\r"
+        );
+        let (stripped, count) = strip_fix_markers_from(&fixed);
+        assert_eq!(count, 2);
+        assert_eq!(stripped, "bg-background\r\nok\r\n    bg-background\r");
+
+        let diff = unified_diff("a.ts", "ok\r\nbg-white\r\n", "ok\r\nbg-background\r\n");
+        assert!(diff.contains("\n-bg-white\n+bg-background\n"));
+    }
+
+    #[test]
+    fn unified_diff_groups_nearby_changes_into_hunks() {
+        let before: String = (1..=20).map(|i| format!("line {}\n", i)).collect();
+        let after = before
+            .replace("line 2\n", "LINE 2\n")
+            .replace("line 5\n", "LINE 5\n")
+            .replace("line 18\n", "LINE 18\n");
+        let diff = unified_diff("./src/a.ts", &before, &after);
+        let headers: Vec<&str> = diff
+            .lines()
+            .filter(|l| l.starts_with("---") || l.starts_with("+++") || l.starts_with("@@"))
+            .collect();
+        assert_eq!(headers, ["--- a/src/a.ts", "+++ b/src/a.ts", "@@ -1,8 +1,8 @@", "@@ -15,6 +15,6 @@"]);
+        assert!(diff.contains(" line 4\n-line 5\n+LINE 5\n line 6\n"));
+    }
+
     #[test]
     fn apply_fixes_no_fixable_violations() {
         let result = make_result(vec![
             make_violation("a.ts", 1, 1, Severity::Error, "r1", "msg"),
         ]);
-        let count = apply_fixes(&result, false);
+        let count = apply_fixes(&result, &FixOptions::default());
         assert_eq!(count, 0);
     }
 
@@ -1178,20 +3914,125 @@ mod tests {
                 fix: Some(crate::rules::Fix {
                     old: "bg-white".into(),
                     new: "bg-background".into(),
+                    range: None,
                 }),
+                url: None,
             }],
             files_scanned: 1,
             rules_loaded: 1,
-            ratchet_counts: HashMap::new(),
-            changed_files_count: None,
-            base_ref: None,
+            ..Default::default()
         };
 
-        apply_fixes(&result, false);
+        apply_fixes(&result, &FixOptions::default());
         let content = std::fs::read_to_string(&file).unwrap();
         assert!(content.ends_with('\n'));
     }
 
+    fn fixable(file: &Path, line: usize) -> Violation {
+        Violation {
+            fix: Some(crate::rules::Fix {
+                old: "bg-white".into(),
+                new: "bg-background".into(),
+                range: None,
+            }),
+            ..make_violation(&file.display().to_string(), line, 1, Severity::Warning, "theme", "raw color")
+        }
+    }
+
+    #[test]
+    fn apply_fixes_keeps_line_endings_and_permissions() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("test.tsx");
+        std::fs::write(&file, "bg-white\r\nok\nbg-white").unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&file, std::fs::Permissions::from_mode(0o640)).unwrap();
+        }
+
+        let result = make_result(vec![fixable(&file, 1), fixable(&file, 3)]);
+        assert_eq!(apply_fixes(&result, &FixOptions::default()), 2);
+        assert_eq!(
+            std::fs::read_to_string(&file).unwrap(),
+            "bg-background\r\nok\nbg-background"
+        );
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&file).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o640);
+        }
+        // No temporary file left behind, and a second run has nothing to do
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+        assert_eq!(apply_fixes(&result, &FixOptions::default()), 0);
+    }
+
+    #[test]
+    fn apply_fixes_prefer_their_byte_range() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("test.tsx");
+        std::fs::write(&file, "bg-white\r\nx bg-white bg-white\n").unwrap();
+
+        // Without the range, the first occurrence on the line would be fixed
+        let mut v = fixable(&file, 2);
+        v.fix.as_mut().unwrap().range = Some(21..29);
+        assert_eq!(apply_fixes(&make_result(vec![v.clone()]), &FixOptions::default()), 1);
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "bg-white\r\nx bg-white bg-background\n");
+
+        // A range that no longer holds the text falls back to the line and column
+        v.fix.as_mut().unwrap().range = Some(0..3);
+        v.column = Some(3);
+        assert_eq!(apply_fixes(&make_result(vec![v]), &FixOptions::default()), 1);
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "bg-white\r\nx bg-background bg-background\n");
+    }
+
+    #[test]
+    fn fix_markers_follow_filetypes() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("page.gotmpl");
+        std::fs::write(&file, "<p class=\"bg-white\">\n").unwrap();
+        let result = make_result(vec![fixable(&file, 1)]);
+        let section = [("gotmpl".to_string(), "html".to_string())].into_iter().collect();
+        let options = FixOptions {
+            marker: true,
+            file_types: FileTypes::new(&section).unwrap(),
+            ..Default::default()
+        };
+        assert_eq!(apply_fixes(&result, &options), 1);
+        assert_eq!(
+            std::fs::read_to_string(&file).unwrap(),
+            "<p class=\"bg-background\"> <!-- guardrails:fixed theme -->\n"
+        );
+    }
+
+    #[test]
+    fn apply_fixes_backs_up_and_skips_stale_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("test.tsx");
+        std::fs::write(&file, "bg-white\n").unwrap();
+        let result = make_result(vec![fixable(&file, 1)]);
+
+        let before_write = std::time::SystemTime::UNIX_EPOCH;
+        let stale = FixOptions {
+            scanned_at: Some(before_write),
+            ..Default::default()
+        };
+        assert_eq!(apply_fixes(&result, &stale), 0);
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "bg-white\n");
+
+        let backup = FixOptions {
+            backup: true,
+            scanned_at: Some(std::time::SystemTime::now()),
+            ..Default::default()
+        };
+        assert_eq!(apply_fixes(&result, &backup), 1);
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "bg-background\n");
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("test.tsx.orig")).unwrap(),
+            "bg-white\n"
+        );
+    }
+
     #[test]
     fn apply_fixes_nonexistent_file_skipped() {
         let result = ScanResult {
@@ -1207,16 +4048,16 @@ mod tests {
                 fix: Some(crate::rules::Fix {
                     old: "old".into(),
                     new: "new".into(),
+                    range: None,
                 }),
+                url: None,
             }],
             files_scanned: 1,
             rules_loaded: 1,
-            ratchet_counts: HashMap::new(),
-            changed_files_count: None,
-            base_ref: None,
+            ..Default::default()
         };
 
-        let count = apply_fixes(&result, false);
+        let count = apply_fixes(&result, &FixOptions::default());
         assert_eq!(count, 0);
     }
 
@@ -1230,37 +4071,98 @@ mod tests {
         v.fix = Some(crate::rules::Fix {
             old: "bg-white".into(),
             new: "bg-background".into(),
+            range: None,
         });
 
         let mut result = make_result(vec![v]);
         result.ratchet_counts.insert("legacy".into(), (2, 5));
 
         let mut out = Vec::new();
-        write_json(&result, &mut out);
+        write_json(&result, false, DisplayLimits::default(), &mut out);
 
         let output = String::from_utf8(out).unwrap();
         let parsed: serde_json::Value = serde_json::from_str(&output).unwrap();
 
-        assert_eq!(parsed["summary"]["total"], 1);
-        assert_eq!(parsed["summary"]["errors"], 1);
-        assert_eq!(parsed["summary"]["warnings"], 0);
-        assert_eq!(parsed["summary"]["files_scanned"], 5);
-        assert_eq!(parsed["summary"]["rules_loaded"], 2);
-        assert_eq!(parsed["violations"][0]["rule_id"], "dark-mode");
-        assert_eq!(parsed["violations"][0]["severity"], "error");
-        assert_eq!(parsed["violations"][0]["suggest"], "add dark variant");
-        assert_eq!(parsed["violations"][0]["fix"]["old"], "bg-white");
-        assert_eq!(parsed["violations"][0]["fix"]["new"], "bg-background");
-        assert!(parsed["ratchet"]["legacy"]["pass"].as_bool().unwrap());
-        assert_eq!(parsed["ratchet"]["legacy"]["found"], 2);
-        assert_eq!(parsed["ratchet"]["legacy"]["max"], 5);
+        assert_eq!(parsed["summary"]["total"], 1);
+        assert_eq!(parsed["summary"]["errors"], 1);
+        assert_eq!(parsed["summary"]["warnings"], 0);
+        assert_eq!(parsed["summary"]["files_scanned"], 5);
+        assert_eq!(parsed["summary"]["rules_loaded"], 2);
+        assert_eq!(parsed["violations"][0]["rule_id"], "dark-mode");
+        assert_eq!(parsed["violations"][0]["severity"], "error");
+        assert_eq!(parsed["violations"][0]["suggest"], "add dark variant");
+        assert_eq!(parsed["violations"][0]["fix"]["old"], "bg-white");
+        assert_eq!(parsed["violations"][0]["fix"]["new"], "bg-background");
+        assert!(parsed["ratchet"]["legacy"]["pass"].as_bool().unwrap());
+        assert_eq!(parsed["ratchet"]["legacy"]["found"], 2);
+        assert_eq!(parsed["ratchet"]["legacy"]["max"], 5);
+    }
+
+    #[test]
+    fn structured_suggestions_add_replacement_and_docs() {
+        let mut v = make_violation("src/a.ts", 1, 1, Severity::Error, "no-console", "m");
+        v.suggest = Some("Use the logger".into());
+        let mut other = make_violation("src/a.ts", 2, 1, Severity::Error, "no-console", "m");
+        other.suggest = Some("Per-pattern hint".into());
+        let mut result = make_result(vec![v, other]);
+        result.suggestions.insert(
+            "no-console".into(),
+            Suggestion {
+                title: "Use the logger".into(),
+                replacement: Some("logger.info(".into()),
+                docs: Some("https://example.com/logging".into()),
+            },
+        );
+
+        let mut out = Vec::new();
+        write_json(&result, false, DisplayLimits::default(), &mut out);
+        let parsed: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(parsed["violations"][0]["suggestion"]["replacement"], "logger.info(");
+        assert_eq!(parsed["violations"][0]["suggestion"]["docs"], "https://example.com/logging");
+        // A per-pattern suggest isn't the rule's structured one
+        assert!(parsed["violations"][1]["suggestion"].is_null());
+
+        let mut out = Vec::new();
+        write_sarif(&result, false, &mut out);
+        let sarif: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(
+            sarif["runs"][0]["tool"]["driver"]["rules"][0]["helpUri"],
+            "https://example.com/logging"
+        );
+
+        let mut out = Vec::new();
+        write_markdown(&result, &Links::default(), &mut out);
+        let md = String::from_utf8(out).unwrap();
+        assert!(md.contains("[Use the logger](https://example.com/logging)"));
+        assert!(md.contains("| Per-pattern hint |"));
+    }
+
+    #[test]
+    fn json_rules_count_violations_and_files() {
+        let result = make_result(vec![
+            make_violation("src/a.tsx", 1, 1, Severity::Warning, "no-any", "m"),
+            make_violation("src/a.tsx", 2, 1, Severity::Warning, "no-any", "m"),
+            make_violation("src/b.tsx", 1, 1, Severity::Warning, "no-any", "m"),
+            make_violation("src/b.tsx", 3, 1, Severity::Error, "no-eval", "m"),
+        ]);
+        let mut out = Vec::new();
+        write_json(&result, false, DisplayLimits::default(), &mut out);
+        let parsed: serde_json::Value = serde_json::from_slice(&out).unwrap();
+
+        assert_eq!(
+            parsed["rules"],
+            json!([
+                { "rule_id": "no-any", "severity": "warning", "count": 3, "files": 2 },
+                { "rule_id": "no-eval", "severity": "error", "count": 1, "files": 1 },
+            ])
+        );
     }
 
     #[test]
     fn json_empty_violations() {
         let result = make_result(vec![]);
         let mut out = Vec::new();
-        write_json(&result, &mut out);
+        write_json(&result, false, DisplayLimits::default(), &mut out);
 
         let output = String::from_utf8(out).unwrap();
         let parsed: serde_json::Value = serde_json::from_str(&output).unwrap();
@@ -1269,13 +4171,44 @@ mod tests {
         assert!(parsed["violations"].as_array().unwrap().is_empty());
     }
 
+    #[test]
+    fn json_counts_skipped_files_by_reason() {
+        let mut result = make_result(vec![]);
+        result.skipped.insert(crate::scan::SkipReason::TooLarge, 2);
+        let mut out = Vec::new();
+        write_json(&result, false, DisplayLimits::default(), &mut out);
+        let parsed: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(parsed["summary"]["skipped"]["too_large"], 2);
+
+        let report = FileReport {
+            files: vec![PathBuf::from("src/a.ts")],
+            skipped: vec![
+                (PathBuf::from("dist/b.js"), crate::scan::SkipReason::Excluded),
+                (PathBuf::from("logo.png"), crate::scan::SkipReason::Binary),
+            ],
+        };
+        let mut out = Vec::new();
+        write_file_report_json(&report, &mut out);
+        let parsed: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(parsed["files"][0], "src/a.ts");
+        assert_eq!(parsed["skipped"][1]["reason"], "binary");
+        assert_eq!(parsed["summary"]["skipped"]["excluded"], 1);
+
+        let mut out = Vec::new();
+        write_file_report(&report, &mut out);
+        let output = String::from_utf8(out).unwrap();
+        assert!(output.starts_with("src/a.ts\n"));
+        assert!(output.contains("binary  \x1b[0m  logo.png"));
+        assert!(output.contains("1 scanned, 2 skipped"));
+    }
+
     #[test]
     fn json_warning_severity() {
         let result = make_result(vec![
             make_violation("a.ts", 1, 1, Severity::Warning, "r1", "warn msg"),
         ]);
         let mut out = Vec::new();
-        write_json(&result, &mut out);
+        write_json(&result, false, DisplayLimits::default(), &mut out);
 
         let output = String::from_utf8(out).unwrap();
         let parsed: serde_json::Value = serde_json::from_str(&output).unwrap();
@@ -1290,7 +4223,7 @@ mod tests {
             make_violation("a.ts", 1, 1, Severity::Error, "r1", "msg"),
         ]);
         let mut out = Vec::new();
-        write_json(&result, &mut out);
+        write_json(&result, false, DisplayLimits::default(), &mut out);
 
         let output = String::from_utf8(out).unwrap();
         let parsed: serde_json::Value = serde_json::from_str(&output).unwrap();
@@ -1298,6 +4231,32 @@ mod tests {
         assert!(parsed["violations"][0]["fix"].is_null());
     }
 
+    #[test]
+    fn rule_urls_reach_every_format() {
+        let mut v = make_violation("a.ts", 1, 1, Severity::Error, "no-eval", "eval");
+        v.url = Some("https://wiki.example.com/eval".into());
+        let result = make_result(vec![v]);
+
+        let mut out = Vec::new();
+        write_pretty(&result, &Theme::default(), &mut out);
+        let pretty = String::from_utf8(out).unwrap();
+        assert!(pretty.contains("\x1b]8;;https://wiki.example.com/eval\x1b\\no-eval\x1b]8;;\x1b\\ "));
+
+        let mut out = Vec::new();
+        write_json(&result, false, DisplayLimits::default(), &mut out);
+        let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(json["violations"][0]["url"], "https://wiki.example.com/eval");
+
+        let mut out = Vec::new();
+        write_sarif(&result, false, &mut out);
+        let sarif: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(sarif["runs"][0]["tool"]["driver"]["rules"][0]["helpUri"], "https://wiki.example.com/eval");
+
+        let mut out = Vec::new();
+        write_github(&result, &|_| false, &mut out, &mut Vec::new());
+        assert!(String::from_utf8(out).unwrap().ends_with("::eval (see https://wiki.example.com/eval)\n"));
+    }
+
     // ── write_sarif tests ──
 
     #[test]
@@ -1306,6 +4265,7 @@ mod tests {
         v.fix = Some(crate::rules::Fix {
             old: "bg-white".into(),
             new: "bg-background".into(),
+            range: None,
         });
         v.suggest = Some("Use bg-background".into());
 
@@ -1315,7 +4275,7 @@ mod tests {
         ]);
 
         let mut out = Vec::new();
-        write_sarif(&result, &mut out);
+        write_sarif(&result, false, &mut out);
 
         let output = String::from_utf8(out).unwrap();
         let parsed: serde_json::Value = serde_json::from_str(&output).unwrap();
@@ -1342,13 +4302,19 @@ mod tests {
 
         // Second result should not have fixes key set
         assert!(results[1].get("fixes").is_none());
+
+        // Fingerprints come from the shared algorithm
+        assert_eq!(
+            results[1]["partialFingerprints"]["guardrails/v1"],
+            fingerprint::fingerprint(&result.violations[1])
+        );
     }
 
     #[test]
     fn sarif_empty_violations() {
         let result = make_result(vec![]);
         let mut out = Vec::new();
-        write_sarif(&result, &mut out);
+        write_sarif(&result, false, &mut out);
 
         let output = String::from_utf8(out).unwrap();
         let parsed: serde_json::Value = serde_json::from_str(&output).unwrap();
@@ -1357,18 +4323,115 @@ mod tests {
         assert!(parsed["runs"][0]["tool"]["driver"]["rules"].as_array().unwrap().is_empty());
     }
 
+    #[test]
+    fn json_and_sarif_carry_scan_metadata() {
+        let mut result = make_result(vec![]);
+        result.metadata = Some(crate::scan::ScanMetadata {
+            scanned_at: "2026-03-01T09:30:00Z".into(),
+            guardrails_version: "1.2.3",
+            config_fingerprint: Some("00ff00ff00ff00ff".into()),
+            git: crate::upload::Metadata {
+                repo: Some("acme/web".into()),
+                branch: Some("main".into()),
+                sha: Some("abc123".into()),
+            },
+        });
+
+        let mut out = Vec::new();
+        write_json(&result, false, DisplayLimits::default(), &mut out);
+        let parsed: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        let metadata = &parsed["metadata"];
+        assert_eq!(metadata["scanned_at"], "2026-03-01T09:30:00Z");
+        assert_eq!(metadata["config_fingerprint"], "00ff00ff00ff00ff");
+        assert_eq!(metadata["sha"], "abc123");
+        assert_eq!(metadata["branch"], "main");
+
+        let mut out = Vec::new();
+        write_sarif(&result, false, &mut out);
+        let parsed: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        let run = &parsed["runs"][0];
+        assert_eq!(run["invocations"][0]["startTimeUtc"], "2026-03-01T09:30:00Z");
+        assert_eq!(run["invocations"][0]["executionSuccessful"], true);
+        assert_eq!(run["properties"]["metadata"]["guardrails_version"], "1.2.3");
+        assert_eq!(run["properties"]["metadata"]["repo"], "acme/web");
+    }
+
+    #[test]
+    fn redact_source_keeps_locations_and_fingerprints() {
+        let mut v = make_violation("src/a.tsx", 10, 5, Severity::Error, "no-secret", "hardcoded key");
+        v.source_line = Some("const key = \"sk-live-123\";".into());
+        v.fix = Some(crate::rules::Fix {
+            old: "\"sk-live-123\"".into(),
+            new: "process.env.KEY".into(),
+            range: None,
+        });
+        let result = make_result(vec![v]);
+        let expected = fingerprint::fingerprint(&result.violations[0]);
+
+        let mut out = Vec::new();
+        write_json(&result, true, DisplayLimits::default(), &mut out);
+        let output = String::from_utf8(out).unwrap();
+        assert!(!output.contains("sk-live") && !output.contains("process.env"));
+        let parsed: serde_json::Value = serde_json::from_str(&output).unwrap();
+        let json = &parsed["violations"][0];
+        assert_eq!((json["line"].as_u64(), json["column"].as_u64()), (Some(10), Some(5)));
+        assert_eq!(json["message"], "hardcoded key");
+        assert!(json["source_line"].is_null() && json["fix"].is_null());
+        assert_eq!(json["fingerprint"], expected);
+
+        let mut out = Vec::new();
+        write_sarif(&result, true, &mut out);
+        let output = String::from_utf8(out).unwrap();
+        assert!(!output.contains("sk-live") && !output.contains("process.env"));
+        let parsed: serde_json::Value = serde_json::from_str(&output).unwrap();
+        let sarif = &parsed["runs"][0]["results"][0];
+        assert!(sarif.get("fixes").is_none());
+        assert_eq!(sarif["partialFingerprints"]["guardrails/v1"], expected);
+    }
+
+    #[test]
+    fn codeclimate_issues_for_gitlab_code_quality() {
+        let mut first = make_violation("./src/a.tsx", 10, 5, Severity::Error, "no-eval", "eval is unsafe");
+        first.source_line = Some("eval(x);".into());
+        let mut shifted = first.clone();
+        shifted.line = Some(20);
+        let result = make_result(vec![
+            first,
+            shifted,
+            make_violation("src/b.tsx", 3, 1, Severity::Warning, "no-console", "console"),
+        ]);
+
+        let mut out = Vec::new();
+        write_codeclimate(&result, &mut out);
+        let parsed: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        let issues = parsed.as_array().unwrap();
+        assert_eq!(issues.len(), 3);
+        assert_eq!(issues[0]["check_name"], "no-eval");
+        assert_eq!(issues[0]["description"], "eval is unsafe");
+        assert_eq!(issues[0]["severity"], "major");
+        assert_eq!(issues[0]["location"]["path"], "src/a.tsx");
+        assert_eq!(issues[0]["location"]["positions"]["begin"]["line"], 10);
+        assert_eq!(issues[2]["severity"], "minor");
+
+        // The fingerprint ignores the line, and repeats stay unique
+        let expected = fingerprint::fingerprint(&result.violations[0]);
+        assert_eq!(issues[0]["fingerprint"], expected);
+        assert_eq!(issues[1]["fingerprint"], format!("{}:1", expected));
+    }
+
     #[test]
     fn sarif_fix_without_suggest_uses_default() {
         let mut v = make_violation("a.tsx", 1, 1, Severity::Error, "r1", "msg");
         v.fix = Some(crate::rules::Fix {
             old: "old".into(),
             new: "new".into(),
+            range: None,
         });
         // v.suggest is None
 
         let result = make_result(vec![v]);
         let mut out = Vec::new();
-        write_sarif(&result, &mut out);
+        write_sarif(&result, false, &mut out);
 
         let output = String::from_utf8(out).unwrap();
         let parsed: serde_json::Value = serde_json::from_str(&output).unwrap();
@@ -1391,10 +4454,11 @@ mod tests {
             suggest: None,
             source_line: None,
             fix: None,
+            url: None,
         };
         let result = make_result(vec![v]);
         let mut out = Vec::new();
-        write_sarif(&result, &mut out);
+        write_sarif(&result, false, &mut out);
 
         let output = String::from_utf8(out).unwrap();
         let parsed: serde_json::Value = serde_json::from_str(&output).unwrap();
@@ -1430,7 +4494,7 @@ mod tests {
         ]);
         let mut out = Vec::new();
         let mut err = Vec::new();
-        write_github(&result, &mut out, &mut err);
+        write_github(&result, &|_| false, &mut out, &mut err);
 
         let stdout = String::from_utf8(out).unwrap();
         assert!(stdout.contains("::error file=a.ts,line=1,col=1,title=r1::e1"));
@@ -1448,7 +4512,7 @@ mod tests {
             make_violation("src/a.tsx", 1, 1, Severity::Error, "r1", "err"),
         ]);
         let mut out = Vec::new();
-        write_markdown(&result, &mut out);
+        write_markdown(&result, &Links::default(), &mut out);
 
         let output = String::from_utf8(out).unwrap();
         assert!(output.contains("1 error"));
@@ -1465,7 +4529,7 @@ mod tests {
             make_violation("src/a.tsx", 1, 1, Severity::Warning, "r1", "warn"),
         ]);
         let mut out = Vec::new();
-        write_markdown(&result, &mut out);
+        write_markdown(&result, &Links::default(), &mut out);
 
         let output = String::from_utf8(out).unwrap();
         assert!(output.contains("1 warning"));
@@ -1481,7 +4545,7 @@ mod tests {
         let mut result = make_result(vec![]);
         result.ratchet_counts.insert("r1".into(), (1, 5));
         let mut out = Vec::new();
-        write_markdown(&result, &mut out);
+        write_markdown(&result, &Links::default(), &mut out);
 
         let output = String::from_utf8(out).unwrap();
         assert!(output.contains("No violations found"));
@@ -1529,7 +4593,9 @@ mod tests {
                     fix: Some(crate::rules::Fix {
                         old: "bg-white".into(),
                         new: "bg-background".into(),
+                        range: None,
                     }),
+                    url: None,
                 },
                 Violation {
                     rule_id: "theme".into(),
@@ -1543,17 +4609,17 @@ mod tests {
                     fix: Some(crate::rules::Fix {
                         old: "bg-white".into(),
                         new: "bg-background".into(),
+                        range: None,
                     }),
+                    url: None,
                 },
             ],
             files_scanned: 1,
             rules_loaded: 1,
-            ratchet_counts: HashMap::new(),
-            changed_files_count: None,
-            base_ref: None,
+            ..Default::default()
         };
 
-        let count = apply_fixes(&result, false);
+        let count = apply_fixes(&result, &FixOptions::default());
         assert_eq!(count, 2);
 
         let content = std::fs::read_to_string(&file).unwrap();
@@ -1567,7 +4633,7 @@ mod tests {
     fn pretty_no_violations() {
         let result = make_result(vec![]);
         let mut out = Vec::new();
-        write_pretty(&result, &mut out);
+        write_pretty(&result, &Theme::default(), &mut out);
 
         let output = String::from_utf8(out).unwrap();
         assert!(output.contains("No violations found"));
@@ -1582,7 +4648,7 @@ mod tests {
             make_violation("src/a.tsx", 20, 1, Severity::Warning, "theme-tokens", "raw color"),
         ]);
         let mut out = Vec::new();
-        write_pretty(&result, &mut out);
+        write_pretty(&result, &Theme::default(), &mut out);
 
         let output = String::from_utf8(out).unwrap();
         assert!(output.contains("src/a.tsx"));
@@ -1592,6 +4658,7 @@ mod tests {
         assert!(output.contains("warn"));
         assert!(output.contains("1 error"));
         assert!(output.contains("1 warning"));
+        assert!(output.contains("hint: run `guardrails explain dark-mode` to see why a rule exists"));
     }
 
     #[test]
@@ -1600,143 +4667,561 @@ mod tests {
             make_violation("a.ts", 1, 1, Severity::Error, "r1", "e1"),
         ]);
         let mut out = Vec::new();
-        write_pretty(&result, &mut out);
-
-        let output = String::from_utf8(out).unwrap();
-        assert!(output.contains("1 error"));
-        assert!(!output.contains("warning"));
+        write_pretty(&result, &Theme::default(), &mut out);
+
+        let output = String::from_utf8(out).unwrap();
+        assert!(output.contains("1 error"));
+        assert!(!output.contains("warning"));
+    }
+
+    #[test]
+    fn pretty_warnings_only() {
+        let result = make_result(vec![
+            make_violation("a.ts", 1, 1, Severity::Warning, "r1", "w1"),
+            make_violation("a.ts", 2, 1, Severity::Warning, "r2", "w2"),
+        ]);
+        let mut out = Vec::new();
+        write_pretty(&result, &Theme::default(), &mut out);
+
+        let output = String::from_utf8(out).unwrap();
+        assert!(output.contains("2 warnings"));
+        assert!(!output.contains("error"));
+    }
+
+    #[test]
+    fn pretty_with_source_line() {
+        let mut v = make_violation("a.tsx", 5, 1, Severity::Error, "r1", "msg");
+        v.source_line = Some("  <div className=\"bg-white\">".into());
+        let result = make_result(vec![v]);
+        let mut out = Vec::new();
+        write_pretty(&result, &Theme::default(), &mut out);
+
+        let output = String::from_utf8(out).unwrap();
+        assert!(output.contains("<div className=\"bg-white\">"));
+    }
+
+    #[test]
+    fn pretty_with_suggestion() {
+        let mut v = make_violation("a.tsx", 5, 1, Severity::Error, "r1", "msg");
+        v.suggest = Some("Use bg-background instead".into());
+        let result = make_result(vec![v]);
+        let mut out = Vec::new();
+        write_pretty(&result, &Theme::default(), &mut out);
+
+        let output = String::from_utf8(out).unwrap();
+        assert!(output.contains("Use bg-background instead"));
+    }
+
+    fn design_system_owner() -> crate::config::RuleOwner {
+        crate::config::RuleOwner {
+            owner: Some("design-system".into()),
+            contact: Some("#design-system on Slack".into()),
+            escalation: None,
+        }
+    }
+
+    #[test]
+    fn pretty_shows_rule_owner() {
+        let mut result = make_result(vec![make_violation("a.tsx", 5, 1, Severity::Error, "r1", "msg")]);
+        result.owners.insert("r1".into(), design_system_owner());
+        let mut out = Vec::new();
+        write_pretty(&result, &Theme::default(), &mut out);
+
+        let output = String::from_utf8(out).unwrap();
+        assert!(output.contains("owner: design-system · questions? #design-system on Slack"));
+    }
+
+    #[test]
+    fn markdown_and_github_show_rule_owner() {
+        let mut result = make_result(vec![
+            make_violation("a.tsx", 5, 1, Severity::Error, "r1", "msg"),
+            make_violation("a.tsx", 6, 1, Severity::Error, "r2", "other"),
+        ]);
+        result.owners.insert("r1".into(), design_system_owner());
+        result.owners.insert("unfired".into(), design_system_owner());
+
+        let mut out = Vec::new();
+        write_markdown(&result, &Links::default(), &mut out);
+        let output = String::from_utf8(out).unwrap();
+        assert!(output.contains("### Rule Owners"));
+        assert!(output.contains("| `r1` | design-system | #design-system on Slack |  |"));
+        assert!(!output.contains("`unfired`"));
+
+        let mut out = Vec::new();
+        let mut err = Vec::new();
+        write_github(&result, &|_| false, &mut out, &mut err);
+        let output = String::from_utf8(out).unwrap();
+        assert!(output.contains("::msg (owner: design-system · questions? #design-system on Slack)"));
+        assert!(output.contains("::other\n"));
+    }
+
+    #[test]
+    fn pretty_line_only_no_column() {
+        let v = Violation {
+            rule_id: "r1".into(),
+            severity: Severity::Error,
+            file: PathBuf::from("a.ts"),
+            line: Some(7),
+            column: None,
+            message: "msg".into(),
+            suggest: None,
+            source_line: None,
+            fix: None,
+            url: None,
+        };
+        let result = make_result(vec![v]);
+        let mut out = Vec::new();
+        write_pretty(&result, &Theme::default(), &mut out);
+
+        let output = String::from_utf8(out).unwrap();
+        assert!(output.contains("7:1"));
+    }
+
+    #[test]
+    fn link_template_links_violations_to_the_code_host() {
+        let mut result = make_result(vec![make_violation("./web/src/my file.ts", 12, 3, Severity::Error, "r1", "msg")]);
+        result.metadata = Some(crate::scan::ScanMetadata {
+            scanned_at: String::new(),
+            guardrails_version: "1.2.3",
+            config_fingerprint: None,
+            git: crate::upload::Metadata {
+                repo: None,
+                branch: Some("main".into()),
+                sha: Some("abc123".into()),
+            },
+        });
+        let links = Links::new(
+            Some("https://github.com/org/repo/blob/{sha}/{path}#L{line}".into()),
+            PathBuf::from("/repo"),
+            PathBuf::from("/repo/app"),
+        );
+        let url = "https://github.com/org/repo/blob/abc123/app/web/src/my%20file.ts#L12";
+
+        let mut out = Vec::new();
+        write_markdown(&result, &links, &mut out);
+        let output = String::from_utf8(out).unwrap();
+        assert!(output.contains(&format!("| [12]({}) | `r1` |", url)));
+
+        let mut out = Vec::new();
+        write_pretty(&result, &Theme::default().links(links.clone()), &mut out);
+        let output = String::from_utf8(out).unwrap();
+        assert!(output.contains(&format!("\x1b]8;;{}\x1b\\12:3\x1b]8;;\x1b\\    ", url)));
+
+        let plain = Theme::new(&OutputSection {
+            color: false,
+            ..Default::default()
+        })
+        .unwrap()
+        .links(links);
+        let mut out = Vec::new();
+        write_pretty(&result, &plain, &mut out);
+        let output = String::from_utf8(out).unwrap();
+        assert!(output.contains("  12:3     error r1"));
+        assert!(!output.contains('\x1b'));
+    }
+
+    #[test]
+    fn pretty_no_line_no_column() {
+        let v = Violation {
+            rule_id: "r1".into(),
+            severity: Severity::Error,
+            file: PathBuf::from("a.ts"),
+            line: None,
+            column: None,
+            message: "msg".into(),
+            suggest: None,
+            source_line: None,
+            fix: None,
+            url: None,
+        };
+        let result = make_result(vec![v]);
+        let mut out = Vec::new();
+        write_pretty(&result, &Theme::default(), &mut out);
+
+        let output = String::from_utf8(out).unwrap();
+        assert!(output.contains("1:1"));
+    }
+
+    #[test]
+    fn pretty_multiple_files_grouped() {
+        let result = make_result(vec![
+            make_violation("src/a.tsx", 1, 1, Severity::Error, "r1", "m1"),
+            make_violation("src/b.tsx", 2, 1, Severity::Error, "r1", "m2"),
+            make_violation("src/a.tsx", 5, 1, Severity::Warning, "r2", "m3"),
+        ]);
+        let mut out = Vec::new();
+        write_pretty(&result, &Theme::default(), &mut out);
+
+        let output = String::from_utf8(out).unwrap();
+        // Files should appear as group headers
+        assert!(output.contains("src/a.tsx"));
+        assert!(output.contains("src/b.tsx"));
+    }
+
+    #[test]
+    fn pretty_truncates_the_listing_but_not_the_counts() {
+        let result = make_result(vec![
+            make_violation("src/a.tsx", 1, 1, Severity::Error, "r1", "m1"),
+            make_violation("src/a.tsx", 2, 1, Severity::Error, "r1", "m2"),
+            make_violation("src/a.tsx", 3, 1, Severity::Error, "r1", "m3"),
+            make_violation("src/b.tsx", 1, 1, Severity::Warning, "r2", "m4"),
+            make_violation("src/c.tsx", 1, 1, Severity::Warning, "r2", "m5"),
+        ]);
+        let limits = DisplayLimits {
+            max_violations: Some(3),
+            max_per_file: Some(2),
+            ..Default::default()
+        };
+        let mut out = Vec::new();
+        write_pretty(&result, &Theme::default().limits(limits), &mut out);
+
+        let output = strip_ansi(&String::from_utf8(out).unwrap());
+        assert!(output.contains("m2") && output.contains("m4"));
+        assert!(!output.contains("m3") && !output.contains("m5"));
+        assert!(output.contains("3 errors, 2 warnings"));
+        assert!(output.contains("[truncated: listed 3 of 5 violations]"));
+    }
+
+    #[test]
+    fn json_marks_a_truncated_listing() {
+        let result = make_result(vec![
+            make_violation("src/a.tsx", 1, 1, Severity::Error, "r1", "m1"),
+            make_violation("src/a.tsx", 2, 1, Severity::Error, "r1", "m2"),
+        ]);
+        let limits = DisplayLimits {
+            max_violations: Some(1),
+            ..Default::default()
+        };
+        let mut out = Vec::new();
+        write_json(&result, false, limits, &mut out);
+
+        let parsed: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(parsed["violations"].as_array().unwrap().len(), 1);
+        assert_eq!(parsed["summary"]["total"], 2);
+        assert_eq!(parsed["summary"]["shown"], 1);
+        assert_eq!(parsed["summary"]["truncated"], true);
+    }
+
+    #[test]
+    fn per_rule_limits_summarize_what_they_leave_out() {
+        let mut violations: Vec<_> = (1..=1205)
+            .map(|line| make_violation("src/a.tsx", line, 1, Severity::Warning, "no-console", &format!("m{}", line)))
+            .collect();
+        violations.push(make_violation("src/b.tsx", 1, 1, Severity::Error, "no-eval", "eval"));
+        violations.push(make_violation("src/b.tsx", 1, 1, Severity::Error, "no-eval", "eval"));
+        let result = make_result(violations);
+        let limits = DisplayLimits {
+            max_per_rule: Some(2),
+            ..Default::default()
+        };
+        let mut out = Vec::new();
+        write_pretty(&result, &Theme::default().limits(limits), &mut out);
+
+        let output = strip_ansi(&String::from_utf8(out).unwrap());
+        assert!(output.contains("m2") && !output.contains("m3"));
+        assert_eq!(output.matches("no-eval").count(), 1);
+        assert!(output.contains("no-console                …and 1,203 more"));
+        assert!(output.contains("[1 duplicate violations listed once]"));
+
+        let mut out = Vec::new();
+        write_json(&result, false, limits, &mut out);
+        let parsed: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(parsed["violations"].as_array().unwrap().len(), 3);
+        assert_eq!(parsed["summary"]["duplicates"], 1);
+        assert_eq!(parsed["summary"]["more"]["no-console"], 1203);
+        assert_eq!(parsed["summary"]["total"], 1207);
+    }
+
+    #[test]
+    fn group_by_rule_lists_under_each_rule() {
+        let result = make_result(vec![
+            make_violation("src/a.tsx", 1, 1, Severity::Error, "r1", "m1"),
+            make_violation("src/a.tsx", 2, 1, Severity::Warning, "r2", "m2"),
+            make_violation("src/b.tsx", 3, 4, Severity::Error, "r1", "m3"),
+        ]);
+        let limits = DisplayLimits {
+            max_per_rule: Some(1),
+            group_by: Some(GroupBy::Rule),
+            ..Default::default()
+        };
+        let mut out = Vec::new();
+        write_pretty(&result, &Theme::default().ascii().limits(limits), &mut out);
+
+        let output = strip_ansi(&String::from_utf8(out).unwrap());
+        let r1 = output.find("\nr1\n").unwrap();
+        let r2 = output.find("\nr2\n").unwrap();
+        assert!(r1 < r2);
+        assert!(output[r1..r2].contains("src/a.tsx:1:1"));
+        assert!(output[r1..r2].contains("...and 1 more"));
+        assert!(!output.contains("m3"));
+
+        let mut out = Vec::new();
+        write_json(&result, false, limits, &mut out);
+        let parsed: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert!(parsed.get("violations").is_none());
+        let groups = parsed["groups"].as_array().unwrap();
+        assert_eq!(groups[0]["rule"], "r1");
+        assert_eq!((groups[0]["total"].as_u64(), groups[0]["more"].as_u64()), (Some(2), Some(1)));
+        assert_eq!(groups[0]["violations"][0]["message"], "m1");
+        assert_eq!(groups[1]["rule"], "r2");
+
+        let limits = DisplayLimits {
+            group_by: Some(GroupBy::File),
+            ..Default::default()
+        };
+        let mut out = Vec::new();
+        write_json(&result, false, limits, &mut out);
+        let parsed: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(parsed["groups"][1]["file"], "src/b.tsx");
+        assert_eq!(parsed["groups"][0]["violations"].as_array().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn group_by_owner_lists_under_each_codeowner() {
+        let mut result = make_result(vec![
+            make_violation("src/a.tsx", 1, 1, Severity::Error, "r1", "m1"),
+            make_violation("src/b.tsx", 2, 3, Severity::Warning, "r2", "m2"),
+            make_violation("docs/c.md", 4, 1, Severity::Warning, "r1", "m3"),
+        ]);
+        result.code_owners = Some(HashMap::from([
+            (PathBuf::from("src/a.tsx"), vec!["@web".to_string(), "@alice".to_string()]),
+            (PathBuf::from("src/b.tsx"), vec!["@web".to_string()]),
+        ]));
+        let limits = DisplayLimits {
+            group_by: Some(GroupBy::Owner),
+            ..Default::default()
+        };
+        let mut out = Vec::new();
+        write_pretty(&result, &Theme::default().ascii().limits(limits), &mut out);
+
+        let output = strip_ansi(&String::from_utf8(out).unwrap());
+        let unowned = output.find("\n(unowned)\n").unwrap();
+        let alice = output.find("\n@alice\n").unwrap();
+        let web = output.find("\n@web\n").unwrap();
+        assert!(unowned < alice && alice < web);
+        assert!(output[unowned..alice].contains("docs/c.md:4:1"));
+        assert!(output[alice..web].contains("src/a.tsx:1:1") && output[alice..web].contains("r1"));
+        assert!(output[web..].contains("src/a.tsx:1:1") && output[web..].contains("src/b.tsx:2:3"));
+
+        let mut out = Vec::new();
+        write_json(&result, false, limits, &mut out);
+        let parsed: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        let groups = parsed["groups"].as_array().unwrap();
+        assert_eq!(groups[2]["owner"], "@web");
+        assert_eq!(groups[2]["total"], 2);
+        assert_eq!(groups[1]["violations"][0]["codeowners"], json!(["@web", "@alice"]));
+        assert_eq!(groups[0]["violations"][0]["codeowners"], serde_json::Value::Null);
+        assert_eq!(parsed["summary"]["codeowners"], json!({"owners": {"@alice": 1, "@web": 2}, "unowned": 1}));
+
+        result.code_owners = None;
+        let mut out = Vec::new();
+        write_json(&result, false, DisplayLimits::default(), &mut out);
+        let parsed: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(parsed["summary"]["codeowners"], serde_json::Value::Null);
     }
 
     #[test]
-    fn pretty_warnings_only() {
+    fn quiet_prints_only_the_summary() {
         let result = make_result(vec![
-            make_violation("a.ts", 1, 1, Severity::Warning, "r1", "w1"),
-            make_violation("a.ts", 2, 1, Severity::Warning, "r2", "w2"),
+            make_violation("src/a.tsx", 1, 1, Severity::Error, "r1", "m1"),
+            make_violation("src/b.tsx", 1, 1, Severity::Warning, "r2", "m2"),
         ]);
+        let limits = DisplayLimits {
+            quiet: true,
+            ..Default::default()
+        };
         let mut out = Vec::new();
-        write_pretty(&result, &mut out);
+        write_pretty(&result, &Theme::default().limits(limits), &mut out);
 
-        let output = String::from_utf8(out).unwrap();
-        assert!(output.contains("2 warnings"));
-        assert!(!output.contains("error"));
+        let output = strip_ansi(&String::from_utf8(out).unwrap());
+        assert!(!output.contains("src/a.tsx") && !output.contains("m1"));
+        assert!(output.contains("1 error, 1 warning"));
+        assert!(!output.contains("truncated"));
+
+        let mut out = Vec::new();
+        write_json(&result, false, limits, &mut out);
+        let parsed: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert!(parsed["violations"].as_array().unwrap().is_empty());
+        assert_eq!(parsed["summary"]["total"], 2);
+        assert_eq!(parsed["summary"]["shown"], 0);
     }
 
     #[test]
-    fn pretty_with_source_line() {
-        let mut v = make_violation("a.tsx", 5, 1, Severity::Error, "r1", "msg");
-        v.source_line = Some("  <div className=\"bg-white\">".into());
-        let result = make_result(vec![v]);
+    fn pretty_with_ratchet() {
+        let mut result = make_result(vec![
+            make_violation("a.ts", 1, 1, Severity::Error, "r1", "msg"),
+        ]);
+        result.ratchet_counts.insert("legacy".into(), (3, 5));
         let mut out = Vec::new();
-        write_pretty(&result, &mut out);
+        write_pretty(&result, &Theme::default(), &mut out);
 
         let output = String::from_utf8(out).unwrap();
-        assert!(output.contains("<div className=\"bg-white\">"));
+        assert!(output.contains("Ratchet rules:"));
+        assert!(output.contains("legacy"));
+        assert!(output.contains("pass"));
     }
 
     #[test]
-    fn pretty_with_suggestion() {
+    fn pretty_theme_without_color_or_details() {
         let mut v = make_violation("a.tsx", 5, 1, Severity::Error, "r1", "msg");
+        v.source_line = Some("<div className=\"bg-white\">".into());
         v.suggest = Some("Use bg-background instead".into());
-        let result = make_result(vec![v]);
+        let mut result = make_result(vec![v]);
+        result.ratchet_counts.insert("legacy".into(), (3, 2));
+        let theme = Theme::new(&OutputSection {
+            color: false,
+            fail_glyph: "[FAIL]".into(),
+            show_source: false,
+            show_suggestions: false,
+            ..Default::default()
+        })
+        .unwrap();
         let mut out = Vec::new();
-        write_pretty(&result, &mut out);
+        write_pretty(&result, &theme, &mut out);
 
         let output = String::from_utf8(out).unwrap();
-        assert!(output.contains("Use bg-background instead"));
+        assert!(!output.contains('\x1b'));
+        assert!(output.contains("error r1"));
+        assert!(!output.contains("bg-white"));
+        assert!(!output.contains("bg-background"));
+        assert!(output.contains("[FAIL] OVER (3/2)"));
     }
 
     #[test]
-    fn pretty_line_only_no_column() {
-        let v = Violation {
-            rule_id: "r1".into(),
-            severity: Severity::Error,
-            file: PathBuf::from("a.ts"),
-            line: Some(7),
-            column: None,
-            message: "msg".into(),
-            suggest: None,
-            source_line: None,
-            fix: None,
-        };
-        let result = make_result(vec![v]);
+    fn pretty_theme_custom_colors_and_glyphs() {
+        let theme = Theme::new(&OutputSection {
+            error_color: "bright-magenta".into(),
+            pass_color: "blue".into(),
+            pass_glyph: "OK".into(),
+            ..Default::default()
+        })
+        .unwrap();
+
         let mut out = Vec::new();
-        write_pretty(&result, &mut out);
+        write_pretty(&make_result(vec![]), &theme, &mut out);
+        assert!(String::from_utf8(out).unwrap().starts_with("\x1b[34mOK\x1b[0m No violations"));
 
-        let output = String::from_utf8(out).unwrap();
-        assert!(output.contains("7:1"));
+        let mut out = Vec::new();
+        let result = make_result(vec![make_violation("a.ts", 1, 1, Severity::Error, "r1", "e1")]);
+        write_pretty(&result, &theme, &mut out);
+        assert!(String::from_utf8(out).unwrap().contains("\x1b[95merror\x1b[0m"));
+
+        let err = Theme::new(&OutputSection {
+            warning_color: "orange".into(),
+            ..Default::default()
+        })
+        .unwrap_err();
+        assert!(err.contains("warning_color"));
     }
 
     #[test]
-    fn pretty_no_line_no_column() {
-        let v = Violation {
-            rule_id: "r1".into(),
-            severity: Severity::Error,
-            file: PathBuf::from("a.ts"),
-            line: None,
-            column: None,
-            message: "msg".into(),
-            suggest: None,
-            source_line: None,
-            fix: None,
-        };
-        let result = make_result(vec![v]);
+    fn pretty_ascii_has_no_unicode_glyphs() {
+        let mut v = make_violation("a.tsx", 5, 1, Severity::Error, "r1", "msg");
+        v.source_line = Some("<div>".into());
+        v.suggest = Some("use Card".into());
+        let mut result = make_result(vec![v]);
+        result.owners.insert("r1".into(), design_system_owner());
+        result.ratchet_counts.insert("legacy".into(), (120, 100));
+        result.ratchet_trends.insert("legacy".into(), vec![200, 120]);
         let mut out = Vec::new();
-        write_pretty(&result, &mut out);
+        write_pretty(&result, &Theme::default().ascii(), &mut out);
 
         let output = String::from_utf8(out).unwrap();
-        assert!(output.contains("1:1"));
+        assert!(output.is_ascii(), "{}", output);
+        assert!(output.contains("|\x1b[0m <div>"));
+        assert!(output.contains("`-\x1b[0m \x1b[36muse Card"));
+        assert!(output.contains("-> owner: design-system | questions?"));
+        assert!(output.contains("[x] OVER"));
+        assert!(output.contains("#_"));
+        assert!(output.contains("(200 -> 120)"));
     }
 
     #[test]
-    fn pretty_multiple_files_grouped() {
-        let result = make_result(vec![
-            make_violation("src/a.tsx", 1, 1, Severity::Error, "r1", "m1"),
-            make_violation("src/b.tsx", 2, 1, Severity::Error, "r1", "m2"),
-            make_violation("src/a.tsx", 5, 1, Severity::Warning, "r2", "m3"),
+    fn pretty_summary_follows_theme_language() {
+        let mut result = make_result(vec![
+            make_violation("a.ts", 1, 1, Severity::Error, "r1", "rule message"),
+            make_violation("a.ts", 2, 1, Severity::Error, "r1", "rule message"),
+            make_violation("b.ts", 1, 1, Severity::Warning, "r2", "rule message"),
         ]);
+        result.ratchet_counts.insert("legacy".into(), (120, 100));
         let mut out = Vec::new();
-        write_pretty(&result, &mut out);
+        write_pretty(&result, &Theme::default().lang(Lang::De), &mut out);
 
         let output = String::from_utf8(out).unwrap();
-        // Files should appear as group headers
-        assert!(output.contains("src/a.tsx"));
-        assert!(output.contains("src/b.tsx"));
+        assert!(output.contains("2 Fehler"));
+        assert!(output.contains("1 Warnung"));
+        assert!(output.contains("5 Dateien geprüft, 2 Regeln geladen"));
+        assert!(output.contains("Ratchet-Regeln:"));
+        assert!(output.contains("ÜBER"));
+        // Rule messages are the author's and stay as written
+        assert!(output.contains("rule message"));
+
+        let mut out = Vec::new();
+        write_pretty(&make_result(vec![]), &Theme::default().lang(Lang::Es), &mut out);
+        assert!(String::from_utf8(out).unwrap().contains("No se encontraron infracciones"));
     }
 
     #[test]
-    fn pretty_with_ratchet() {
+    fn summary_reports_size_and_density() {
         let mut result = make_result(vec![
-            make_violation("a.ts", 1, 1, Severity::Error, "r1", "msg"),
+            make_violation("a.ts", 1, 1, Severity::Error, "r1", "e1"),
+            make_violation("a.ts", 2, 1, Severity::Error, "r1", "e2"),
+            make_violation("b.ts", 1, 1, Severity::Warning, "r2", "w1"),
         ]);
-        result.ratchet_counts.insert("legacy".into(), (3, 5));
-        let mut out = Vec::new();
-        write_pretty(&result, &mut out);
+        result.lines_scanned = 12_000;
+        result.bytes_scanned = 350_000;
 
+        let mut out = Vec::new();
+        write_pretty(&result, &Theme::default(), &mut out);
         let output = String::from_utf8(out).unwrap();
-        assert!(output.contains("Ratchet rules:"));
-        assert!(output.contains("legacy"));
-        assert!(output.contains("pass"));
+        assert!(output.contains("12,000 lines, 350.0 KB · 0.25 violations/KLoC"));
+
+        let mut out = Vec::new();
+        write_json(&result, false, DisplayLimits::default(), &mut out);
+        let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(json["summary"]["lines_scanned"], 12_000);
+        assert_eq!(json["summary"]["violations_per_kloc"], 0.25);
+        assert_eq!(json["density"]["r1"]["count"], 2);
+        assert_eq!(json["density"]["r1"]["per_kloc"], 0.17);
     }
 
     // ── write_ratchet_summary_pretty tests ──
 
     #[test]
     fn ratchet_summary_pretty_empty() {
-        let counts = HashMap::new();
         let mut out = Vec::new();
-        write_ratchet_summary_pretty(&counts, &mut out);
+        write_ratchet_summary_pretty(&ScanResult::default(), &Theme::default(), &mut out);
 
         let output = String::from_utf8(out).unwrap();
         assert!(output.is_empty());
     }
 
+    #[test]
+    fn ratchet_summary_pretty_shows_trend() {
+        let result = ScanResult {
+            ratchet_counts: HashMap::from([("legacy".to_string(), (120usize, 200usize))]),
+            ratchet_trends: HashMap::from([("legacy".to_string(), vec![200, 180, 150, 120])]),
+            ..Default::default()
+        };
+        let mut out = Vec::new();
+        write_ratchet_summary_pretty(&result, &Theme::default(), &mut out);
+
+        let output = String::from_utf8(out).unwrap();
+        assert!(output.contains("█▆▃▁"));
+        assert!(output.contains("(200 → 120)"));
+    }
+
     #[test]
     fn ratchet_summary_pretty_pass_and_over() {
         let mut counts = HashMap::new();
         counts.insert("a-rule".to_string(), (2usize, 5usize));
         counts.insert("b-rule".to_string(), (10, 3));
+        let result = ScanResult {
+            ratchet_counts: counts,
+            ..Default::default()
+        };
         let mut out = Vec::new();
-        write_ratchet_summary_pretty(&counts, &mut out);
+        write_ratchet_summary_pretty(&result, &Theme::default(), &mut out);
 
         let output = String::from_utf8(out).unwrap();
         assert!(output.contains("Ratchet rules:"));
@@ -1748,12 +5233,91 @@ mod tests {
         assert!(output.contains("(10/3)"));
     }
 
+    #[test]
+    fn excused_ratchets_pass_like_the_exit_code() {
+        let result = ScanResult {
+            ratchet_counts: HashMap::from([("legacy".to_string(), (10usize, 3usize))]),
+            excused_ratchets: HashSet::from(["legacy".to_string()]),
+            ..Default::default()
+        };
+        assert!(!result.fails(crate::config::FailOn::Error, None));
+
+        let mut out = Vec::new();
+        write_ratchet_summary_pretty(&result, &Theme::default(), &mut out);
+        let output = String::from_utf8(out).unwrap();
+        assert!(output.contains("pass") && !output.contains("OVER"));
+        let mut err = Vec::new();
+        write_ratchet_stderr(&result, &mut err);
+        assert_eq!(String::from_utf8(err).unwrap(), "ratchet: legacy pass (10/3)\n");
+        let mut out = Vec::new();
+        write_json(&result, false, DisplayLimits::default(), &mut out);
+        let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(json["ratchet"]["legacy"]["pass"], true);
+    }
+
+    #[test]
+    fn ratchet_update_dry_run_names_over_budget_ratchets() {
+        use crate::ratchet_update::{OverBudget, Tightened};
+        let mut update = RatchetUpdate {
+            tightened: Vec::new(),
+            over_budget: vec![OverBudget { budget: "no-legacy".into(), found: 2, max: 1 }],
+            not_in_config: Vec::new(),
+            recorded: BTreeMap::new(),
+            files_scanned: 1,
+        };
+        let mut out = Vec::new();
+        write_ratchet_update_pretty(&update, true, &mut out);
+        let output = String::from_utf8(out).unwrap();
+        assert!(output.contains("over budget (2/1), not tightened"));
+        assert!(!output.contains("tightened\x1b[0m (") && !output.contains("Every ratchet"));
+
+        update.tightened.push(Tightened { budget: "no-old".into(), from: 3, to: 1 });
+        let mut out = Vec::new();
+        write_ratchet_update_pretty(&update, true, &mut out);
+        let output = String::from_utf8(out).unwrap();
+        assert!(output.contains("1 ratchet would be tightened"));
+        assert!(output.contains("no-legacy") && output.contains("not tightened"));
+    }
+
+    #[test]
+    fn ratchet_summary_grouped_by_tag_and_owner() {
+        let mut result = make_result(vec![]);
+        result.ratchet_counts.insert("raw-colors".into(), (11, 20));
+        result.ratchet_counts.insert("legacy-button".into(), (20, 30));
+        result.ratchet_counts.insert("any-casts".into(), (5, 4));
+        result.tags.insert("raw-colors".into(), vec!["design-system".into()]);
+        result.tags.insert("legacy-button".into(), vec!["design-system".into(), "q3".into()]);
+        result.owners.insert(
+            "any-casts".into(),
+            RuleOwner { owner: Some("platform".into()), ..Default::default() },
+        );
+
+        let groups = rollup::ratchet_groups(&result, RatchetGroupBy::Tag);
+        let names: Vec<_> = groups.iter().map(|g| g.name.as_deref()).collect();
+        assert_eq!(names, vec![Some("design-system"), Some("q3"), None]);
+        assert_eq!((groups[0].found, groups[0].max), (31, 50));
+        assert_eq!(groups[0].utilization().round(), 62.0);
+
+        let theme = Theme::default().ratchet_group_by(Some(RatchetGroupBy::Tag));
+        let mut out = Vec::new();
+        write_ratchets_pretty(&result, &theme, &mut out);
+        let output = strip_ansi(&String::from_utf8(out).unwrap());
+        assert!(output.contains("  design-system                  62% (31/50)\n    legacy-button"));
+        assert!(output.contains("    raw-colors                   ✓ pass (11/20)"));
+        assert!(output.contains("  (none)                         125% (5/4)"));
+
+        let groups = rollup::ratchet_groups(&result, RatchetGroupBy::Owner);
+        let names: Vec<_> = groups.iter().map(|g| g.name.as_deref()).collect();
+        assert_eq!(names, vec![Some("platform"), None]);
+        assert_eq!(groups[1].rules.len(), 2);
+    }
+
     #[test]
     fn pretty_no_violations_with_ratchet() {
         let mut result = make_result(vec![]);
         result.ratchet_counts.insert("legacy".into(), (2, 10));
         let mut out = Vec::new();
-        write_pretty(&result, &mut out);
+        write_pretty(&result, &Theme::default(), &mut out);
 
         let output = String::from_utf8(out).unwrap();
         assert!(output.contains("No violations found"));
@@ -1768,7 +5332,7 @@ mod tests {
             make_violation("a.ts", 2, 1, Severity::Error, "r2", "e2"),
         ]);
         let mut out = Vec::new();
-        write_pretty(&result, &mut out);
+        write_pretty(&result, &Theme::default(), &mut out);
 
         let output = String::from_utf8(out).unwrap();
         assert!(output.contains("2 errors"));
@@ -1781,11 +5345,424 @@ mod tests {
             make_violation("a.ts", 2, 1, Severity::Warning, "r2", "w1"),
         ]);
         let mut out = Vec::new();
-        write_pretty(&result, &mut out);
+        write_pretty(&result, &Theme::default(), &mut out);
 
         let output = String::from_utf8(out).unwrap();
         // Should have comma between error and warning counts
         assert!(output.contains("1 error"));
         assert!(output.contains("1 warning"));
     }
+
+    #[test]
+    fn reviewers_pretty_lists_owners_and_unowned() {
+        let report = ReviewerReport {
+            reviewers: vec![crate::codeowners::ReviewerSuggestion {
+                owner: "@org/frontend".into(),
+                files: vec![PathBuf::from("src/a.tsx")],
+                violations: 2,
+            }],
+            unowned_files: vec![PathBuf::from("scripts/x.sh")],
+        };
+        let mut out = Vec::new();
+        write_reviewers_pretty(&report, &mut out);
+
+        let output = String::from_utf8(out).unwrap();
+        assert!(output.contains("@org/frontend"));
+        assert!(output.contains("2 violations in 1 file"));
+        assert!(output.contains("scripts/x.sh"));
+    }
+
+    #[test]
+    fn rule_markdown_fences_examples_by_glob_language() {
+        let rule = RuleInfo {
+            id: "no-eval".into(),
+            rule_type: "banned-pattern".into(),
+            severity: Severity::Error,
+            message: "eval is unsafe".into(),
+            owner: None,
+            suggestion: None,
+            source: None,
+            suggest: Some("Use JSON.parse".into()),
+            glob: Some("src/**/*.{ts,tsx}".into()),
+            rationale: Some("eval runs arbitrary code\nfrom user input.".into()),
+            docs: Some("https://example.com/no-eval".into()),
+            url: Some("https://wiki.example.com/conventions/eval".into()),
+            examples: Some(crate::config::RuleExamples {
+                bad: vec!["eval(input)".into()],
+                good: vec![],
+            }),
+        };
+        let mut out = Vec::new();
+        write_rule_markdown(&rule, &mut out);
+        let output = String::from_utf8(out).unwrap();
+        assert!(output.contains("## `no-eval`"));
+        assert!(output.contains("**Why:** eval runs arbitrary code\nfrom user input.\n"));
+        assert!(output.contains("[Rule docs](https://example.com/no-eval)"));
+        assert!(output.contains("[Convention](https://wiki.example.com/conventions/eval)"));
+        assert!(output.contains("**Bad:**\n\n```ts\neval(input)\n```"));
+        assert!(!output.contains("**Good:**"));
+
+        let mut out = Vec::new();
+        write_explain_pretty(&rule, &mut out);
+        let output = String::from_utf8(out).unwrap();
+        assert!(output.contains("→ Use JSON.parse"));
+        assert!(output.contains("Why\x1b[0m\n  eval runs arbitrary code\n  from user input.\n"));
+        assert!(output.contains("docs: https://example.com/no-eval"));
+        assert!(output.contains("convention: https://wiki.example.com/conventions/eval"));
+        assert!(output.contains("✗ Bad"));
+
+        assert_eq!(snippet_language(Some("**/*.rs")), "rs");
+        assert_eq!(snippet_language(Some("src/**")), "");
+        assert_eq!(snippet_language(None), "");
+    }
+
+    #[test]
+    fn config_diff_pretty_marks_added_and_removed() {
+        let diff = ConfigDiff {
+            rules: vec![
+                crate::config_diff::RuleDelta {
+                    rule_id: "new-rule".into(),
+                    status: RuleStatus::Added,
+                    before: 0,
+                    after: 4,
+                },
+                crate::config_diff::RuleDelta {
+                    rule_id: "old-rule".into(),
+                    status: RuleStatus::Removed,
+                    before: 2,
+                    after: 0,
+                },
+            ],
+            total_before: 2,
+            total_after: 4,
+            files_scanned: 10,
+        };
+        let mut out = Vec::new();
+        write_config_diff_pretty(&diff, &mut out);
+
+        let output = String::from_utf8(out).unwrap();
+        assert!(output.contains("new-rule"));
+        assert!(output.contains("+4"));
+        assert!(output.contains("-2"));
+        assert!(output.contains("2 → 4 violations"));
+    }
+
+    #[test]
+    fn result_diff_pretty_shows_ratchet_changes() {
+        use crate::result_diff::{RatchetCount, RatchetDelta};
+        let diff = ResultDiff {
+            added: Vec::new(),
+            resolved: Vec::new(),
+            unchanged: 3,
+            ratchets: vec![RatchetDelta {
+                budget: "legacy-fetch".into(),
+                before: Some(RatchetCount { found: 7, max: 10 }),
+                after: Some(RatchetCount { found: 4, max: 10 }),
+            }],
+        };
+        let mut out = Vec::new();
+        write_result_diff_pretty(&diff, &mut out);
+
+        let output = String::from_utf8(out).unwrap();
+        assert!(output.contains("legacy-fetch"));
+        assert!(output.contains("7/10"));
+        assert!(output.contains("-3"));
+        assert!(output.contains("0 added, 0 resolved"));
+        assert!(!output.contains("Added"));
+    }
+
+    #[test]
+    fn preview_pretty_shows_budget_and_caps_directories() {
+        use crate::preview::{DirectoryCount, RulePreview};
+        let preview = Preview {
+            rules: vec![RulePreview {
+                rule_id: "ratchet-any".into(),
+                total: 14,
+                files: 12,
+                max_count: Some(10),
+                directories: (0..12)
+                    .map(|i| DirectoryCount {
+                        directory: format!("src/mod{}", i),
+                        count: if i < 2 { 2 } else { 1 },
+                    })
+                    .collect(),
+            }],
+            files_scanned: 30,
+        };
+        let mut out = Vec::new();
+        write_preview_pretty(&preview, &mut out);
+
+        let output = String::from_utf8(out).unwrap();
+        assert!(output.contains("ratchet-any\x1b[0m: 14 matches in 12 files"));
+        assert!(output.contains("(max 10, 4 over)"));
+        assert!(output.contains("src/mod9"));
+        assert!(!output.contains("src/mod10"));
+        assert!(output.contains("… 2 more directories"));
+    }
+
+    #[test]
+    fn lock_update_pretty_shows_each_change() {
+        let changes = vec![
+            LockChange {
+                source: "github:org/policies/guardrails.toml@v2".into(),
+                from: None,
+                to: Some(format!("sha256:{}", "a".repeat(64))),
+            },
+            LockChange {
+                source: "https://example.com/base.toml".into(),
+                from: Some(format!("sha256:{}", "b".repeat(64))),
+                to: Some(format!("sha256:{}", "c".repeat(64))),
+            },
+        ];
+        let mut out = Vec::new();
+        write_lock_update_pretty(&changes, &mut out);
+
+        let output = String::from_utf8(out).unwrap();
+        assert!(output.contains("+\x1b[0m github:org/policies/guardrails.toml@v2 sha256:aaaaaaaaaaaa\n"));
+        assert!(output.contains("https://example.com/base.toml sha256:bbbbbbbbbbbb → \x1b[32msha256:cccccccccccc"));
+        assert!(output.contains("Updated guardrails.lock (2 sources changed)"));
+
+        let mut out = Vec::new();
+        write_lock_update_pretty(&[], &mut out);
+        assert!(String::from_utf8(out).unwrap().contains("guardrails.lock is up to date"));
+    }
+
+    #[test]
+    fn test_pretty_lists_failures_under_their_case() {
+        use crate::rule_tests::{CaseResult, Expectation, Unexpected};
+        let report = TestReport {
+            cases: vec![
+                CaseResult {
+                    name: "legacy calls".into(),
+                    path: "src/a.ts".into(),
+                    missing: Vec::new(),
+                    unexpected: Vec::new(),
+                },
+                CaseResult {
+                    name: "console".into(),
+                    path: "src/b.ts".into(),
+                    missing: vec![Expectation {
+                        rule: "no-console".into(),
+                        line: Some(3),
+                    }],
+                    unexpected: vec![Unexpected {
+                        rule: "max-lines".into(),
+                        line: None,
+                        message: "too long".into(),
+                    }],
+                },
+            ],
+        };
+        let mut out = Vec::new();
+        write_test_pretty(&report, &mut out);
+
+        let output = String::from_utf8(out).unwrap();
+        assert!(output.contains("✓\x1b[0m legacy calls"));
+        assert!(output.contains("console\x1b[0m \x1b[90m(src/b.ts)"));
+        assert!(output.contains("missing     \x1b[1mno-console\x1b[0m at line 3"));
+        assert!(output.contains("unexpected  \x1b[1mmax-lines\x1b[0m in the file: too long"));
+        assert!(output.contains("1 passed, 1 failed\x1b[0m (2 tests)"));
+    }
+
+    #[test]
+    fn bench_pretty_shows_throughput_and_rules() {
+        let report = BenchReport {
+            iterations: 2,
+            files: 10,
+            bytes: 2_500_000,
+            runs: vec![
+                std::time::Duration::from_millis(10),
+                std::time::Duration::from_millis(30),
+            ],
+            rules: vec![(
+                "no-console".into(),
+                crate::scan::RuleProfile {
+                    time: std::time::Duration::from_millis(5),
+                    files: 10,
+                    allocations: 40,
+                    alloc_bytes: 2048,
+                    peak_bytes: 512,
+                },
+            )],
+            peak_bytes: Some(3_000_000),
+        };
+        let mut out = Vec::new();
+        write_bench_pretty(&report, &mut out);
+
+        let output = String::from_utf8(out).unwrap();
+        assert!(output.contains("10 files, 2.5 MB, 2 runs"));
+        assert!(output.contains("500 files/sec"));
+        assert!(output.contains("peak heap 3.0 MB"));
+        assert!(output.contains("40 allocs, 2.0 KB"));
+    }
+
+    #[test]
+    fn timing_report_memory_columns_optional() {
+        let profile = ScanProfile {
+            wall: std::time::Duration::from_millis(12),
+            rules: vec![(
+                "slow-rule".into(),
+                crate::scan::RuleProfile {
+                    time: std::time::Duration::from_millis(9),
+                    files: 3,
+                    allocations: 12,
+                    alloc_bytes: 4_000_000,
+                    peak_bytes: 3_500_000,
+                },
+            )],
+            phases: crate::scan::PhaseStats {
+                files_read: 40,
+                files_filtered: 30,
+                prefiltered: 55,
+                rule_runs: 25,
+                ..Default::default()
+            },
+            peak_bytes: Some(8_000_000),
+            build: std::time::Duration::from_millis(2),
+            cold_build: None,
+            regexes: Default::default(),
+            slowest_files: vec![("src/big.ts".into(), std::time::Duration::from_millis(7))],
+        };
+
+        let mut err = Vec::new();
+        write_timing(&profile, false, &mut err);
+        let output = String::from_utf8(err).unwrap();
+        assert!(output.contains("slow-rule"));
+        assert!(!output.contains("allocs"));
+        assert!(output.contains("40 files read, 30 with no rule left, 55 rule runs skipped by pre-filter"));
+        assert!(!output.contains("max_file_size"));
+        assert!(output.contains("build   2.00ms: rules built"));
+        assert!(!output.contains("regexes"));
+        assert!(output.contains("Slowest files"));
+        assert!(output.contains("    7.00ms  src/big.ts"));
+
+        let mut err = Vec::new();
+        write_timing(&profile, true, &mut err);
+        let output = String::from_utf8(err).unwrap();
+        assert!(output.contains("12 allocs, 4.0 MB total, 3.5 MB peak/file"));
+        assert!(output.contains("peak heap 8.0 MB"));
+
+        let mut result = make_result(Vec::new());
+        result.profile = Some(profile);
+        let mut out = Vec::new();
+        write_json(&result, false, DisplayLimits::default(), &mut out);
+        let parsed: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(parsed["timing"]["rules"][0]["rule_id"], "slow-rule");
+        assert_eq!(parsed["timing"]["rules"][0]["ms"], 9.0);
+        assert_eq!(parsed["timing"]["phases"]["files_read"], 40);
+        assert_eq!(parsed["timing"]["slowest_files"][0]["file"], "src/big.ts");
+    }
+
+    #[test]
+    fn timing_report_shows_compile_cache_savings() {
+        let profile = ScanProfile {
+            wall: std::time::Duration::from_millis(12),
+            rules: Vec::new(),
+            phases: crate::scan::PhaseStats::default(),
+            peak_bytes: None,
+            build: std::time::Duration::from_millis(3),
+            cold_build: Some(std::time::Duration::from_millis(80)),
+            regexes: crate::rules::regex_pool::RegexPoolStats {
+                requested: 12,
+                compiled: 4,
+            },
+            slowest_files: Vec::new(),
+        };
+
+        let mut err = Vec::new();
+        write_timing(&profile, false, &mut err);
+        let output = String::from_utf8(err).unwrap();
+        assert!(output.contains("build   3.00ms: warm start from the compile cache, 77.00ms saved (cold build 80.00ms)"));
+        assert!(output.contains("regexes 12 requested, 4 compiled (3.0x dedup)"));
+    }
+
+    #[test]
+    fn incomplete_scan_is_marked() {
+        let mut result = make_result(vec![make_violation("a.ts", 1, 1, Severity::Error, "r1", "msg")]);
+        result.incomplete = true;
+
+        let mut out = Vec::new();
+        write_pretty(&result, &Theme::default(), &mut out);
+        assert!(String::from_utf8(out).unwrap().contains("[incomplete: interrupted]"));
+
+        let mut out = Vec::new();
+        write_json(&result, false, DisplayLimits::default(), &mut out);
+        let parsed: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(parsed["summary"]["incomplete"], true);
+
+        let mut out = Vec::new();
+        let mut err = Vec::new();
+        write_compact(&result, &mut out, &mut err);
+        assert!(String::from_utf8(err).unwrap().contains("[incomplete]"));
+    }
+
+    #[test]
+    fn counts_only_table_and_json() {
+        let mut result = make_result(vec![]);
+        result.counts = Some(vec![
+            RuleCount {
+                rule_id: "no-any".into(),
+                severity: Severity::Warning,
+                count: 1200,
+                files: 40,
+            },
+            RuleCount {
+                rule_id: "no-eval".into(),
+                severity: Severity::Error,
+                count: 3,
+                files: 1,
+            },
+        ]);
+
+        let mut out = Vec::new();
+        write_counts_pretty(&result, &Theme::default(), &mut out);
+        let output = String::from_utf8(out).unwrap();
+        assert!(output.contains("1200"));
+        assert!(output.contains("no-eval"));
+        assert!(output.contains("3 errors, 1200 warnings"));
+        assert!(output.contains("40 files"));
+        assert!(output.contains("1 file\x1b"));
+
+        let mut out = Vec::new();
+        write_counts_json(&result, &mut out);
+        let parsed: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(parsed["counts"][0]["rule_id"], "no-any");
+        assert_eq!(parsed["counts"][0]["files"], 40);
+        assert_eq!(parsed["counts"][1]["severity"], "error");
+        assert_eq!(parsed["summary"]["total"], 1203);
+    }
+
+    #[test]
+    fn group_by_dir_rolls_up_violations_and_ratchet_matches() {
+        let mut result = make_result(vec![
+            make_violation("./packages/a/src/x.ts", 1, 1, Severity::Error, "no-eval", "m"),
+            make_violation("packages/a/lib/y.ts", 1, 1, Severity::Warning, "no-any", "m"),
+            make_violation("packages/b/z.ts", 1, 1, Severity::Error, "no-eval", "m"),
+            make_violation("root.ts", 1, 1, Severity::Warning, "no-any", "m"),
+        ]);
+        result.ratchet_counts.insert("legacy".into(), (3, 10));
+        result.ratchet_files.insert(
+            PathBuf::from("packages/b/deep/w.ts"),
+            HashMap::from([("legacy".to_string(), 3)]),
+        );
+
+        let mut out = Vec::new();
+        write_rollup_json(&result, 2, &mut out);
+        let parsed: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        let dirs = parsed["directories"].as_array().unwrap();
+        let rows: Vec<_> = dirs
+            .iter()
+            .map(|d| (d["directory"].as_str().unwrap(), d["errors"].as_u64().unwrap(), d["warnings"].as_u64().unwrap()))
+            .collect();
+        assert_eq!(rows, [("packages/a", 1, 1), ("packages/b", 1, 0), (".", 0, 1)]);
+        assert_eq!(dirs[1]["ratchet"]["legacy"], 3);
+        assert_eq!(parsed["summary"]["errors"], 2);
+
+        let mut out = Vec::new();
+        write_rollup_pretty(&result, 1, &Theme::default(), &mut out);
+        let output = String::from_utf8(out).unwrap();
+        assert!(output.contains("packages"));
+        assert!(!output.contains("packages/a"));
+        assert!(output.contains("2 errors, 2 warnings"));
+    }
 }
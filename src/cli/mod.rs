@@ -1,7 +1,12 @@
 pub mod format;
+pub mod scan_target;
 pub mod toml_config;
 
-use clap::{Parser, Subcommand, ValueEnum};
+use crate::config::FailOn;
+use crate::i18n::Lang;
+use crate::rollup::RatchetGroupBy;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use std::num::NonZeroUsize;
 use std::path::PathBuf;
 
 #[derive(Parser)]
@@ -9,62 +14,538 @@ use std::path::PathBuf;
 pub struct Cli {
     #[command(subcommand)]
     pub command: Commands,
+
+    /// Language of summaries, headings and errors (default: $GUARDRAILS_LANG, else en)
+    #[arg(long, global = true, value_enum)]
+    pub lang: Option<Lang>,
+}
+
+/// The flags of `guardrails scan`.
+#[derive(Args)]
+pub struct ScanArgs {
+    /// Paths to scan (files or directories) [default: the repository root]
+    pub paths: Vec<PathBuf>,
+
+    /// Scan exactly the files listed in FILE, one per line ("-" reads the list from stdin)
+    #[arg(long, value_name = "FILE", conflicts_with = "stdin")]
+    pub files_from: Option<PathBuf>,
+
+    /// File lists are NUL-separated: --files-from input and --list-files output
+    #[arg(short = '0', long)]
+    pub null: bool,
+
+    /// Print the files that would be scanned instead of scanning them
+    #[arg(long, conflicts_with_all = ["stdin", "fix", "counts_only", "suggest_reviewers"])]
+    pub list_files: bool,
+
+    /// With --list-files, also print each skipped file and why it's skipped
+    #[arg(long, requires = "list_files")]
+    pub explain: bool,
+
+    /// Walk files that .gitignore, .ignore and global git excludes would skip
+    #[arg(long, conflicts_with = "stdin")]
+    pub no_ignore: bool,
+
+    /// Walk hidden files and directories (dotfiles); .git is still skipped
+    #[arg(long, conflicts_with = "stdin")]
+    pub hidden: bool,
+
+    /// Skip files that aren't UTF-8 instead of decoding UTF-16 and Latin-1 ones, and report each file skipped for its contents
+    #[arg(long, conflicts_with = "stdin")]
+    pub strict_encoding: bool,
+
+    /// Path to guardrails.toml config file, "-" to read the config from stdin, or a remote config [default: $GUARDRAILS_CONFIG_INLINE, else the one nearest to each path, up to its repository root]
+    #[arg(short, long)]
+    pub config: Option<PathBuf>,
+
+    /// Scan DIR with its own nearest config; results of every --root merge into one report (repeatable)
+    #[arg(
+        long = "root",
+        value_name = "DIR",
+        conflicts_with_all = [
+            "paths", "files_from", "config", "stdin", "list_files", "strip_fix_markers",
+            "sample", "timing", "resume", "incremental"
+        ]
+    )]
+    pub roots: Vec<PathBuf>,
+
+    /// Output format [default: pretty, or json with --stdin]
+    #[arg(short, long, value_enum)]
+    pub format: Option<OutputFormat>,
+
+    /// Read file content from stdin instead of disk
+    #[arg(long)]
+    pub stdin: bool,
+
+    /// Filename to use for glob matching when using --stdin
+    #[arg(long, visible_alias = "stdin-filename", requires = "stdin")]
+    pub filename: Option<String>,
+
+    /// Only scan files changed relative to a base branch (requires git)
+    #[arg(long, conflicts_with = "stdin")]
+    pub changed_only: bool,
+
+    /// Base ref for --changed-only (default: auto-detect from CI env or "main")
+    #[arg(long, requires = "changed_only")]
+    pub base: Option<String>,
+
+    /// Shorthand for --changed-only --base <REF>
+    #[arg(long, value_name = "REF", conflicts_with_all = ["stdin", "base"])]
+    pub diff_base: Option<String>,
+
+    /// Only check the changes staged for commit (`git diff --cached`), as a pre-commit hook does
+    #[arg(long, conflicts_with_all = ["stdin", "changed_only", "diff_base"])]
+    pub staged: bool,
+
+    /// Apply fixes automatically
+    #[arg(long)]
+    pub fix: bool,
+
+    /// Preview fixes without applying (requires --fix)
+    #[arg(long, requires = "fix")]
+    pub dry_run: bool,
+
+    /// Print the changes --fix would make as a unified diff, without applying them
+    #[arg(long, conflicts_with = "stdin")]
+    pub fix_dry_run: bool,
+
+    /// Keep a copy of each file rewritten by --fix as <file>.orig
+    #[arg(long, requires = "fix", conflicts_with_all = ["dry_run", "fix_dry_run"])]
+    pub backup: bool,
+
+    /// Mark each line --fix rewrites with a `guardrails:fixed <rule>` comment
+    #[arg(long, requires = "fix")]
+    pub fix_marker: bool,
+
+    /// Remove the comments left by --fix-marker from the scanned files instead of scanning
+    #[arg(long, conflicts_with_all = ["stdin", "fix", "fix_dry_run", "list_files", "counts_only", "suggest_reviewers"])]
+    pub strip_fix_markers: bool,
+
+    /// Print the CODEOWNERS of violated files instead of the violations
+    #[arg(long, conflicts_with = "stdin")]
+    pub suggest_reviewers: bool,
+
+    /// Scan only N files and extrapolate violation counts
+    #[arg(long, value_name = "N", conflicts_with_all = ["stdin", "changed_only", "diff_base", "staged"])]
+    pub sample: Option<usize>,
+
+    /// How --sample picks files
+    #[arg(long, value_enum, default_value_t = SampleMode::Random, requires = "sample")]
+    pub sample_mode: SampleMode,
+
+    /// Seed for --sample, to reproduce a previous sample
+    #[arg(long, requires = "sample")]
+    pub seed: Option<u64>,
+
+    /// Run rules on at most N files at a time [default: one per CPU]
+    #[arg(short, long, value_name = "N", conflicts_with = "stdin")]
+    pub jobs: Option<NonZeroUsize>,
+
+    /// Print per-rule and per-phase timing and the slowest files to stderr
+    /// after the results (and add a `timing` block to JSON output)
+    #[arg(long, visible_alias = "timings", conflicts_with = "stdin")]
+    pub timing: bool,
+
+    /// Include allocations and peak memory per rule in the timing report
+    #[arg(long, requires = "timing")]
+    pub memory: bool,
+
+    /// Save progress to FILE and skip files an interrupted run already scanned
+    #[arg(
+        long,
+        value_name = "FILE",
+        num_args = 0..=1,
+        default_missing_value = ".guardrails-progress.json",
+        conflicts_with_all = ["stdin", "changed_only", "diff_base", "staged"]
+    )]
+    pub resume: Option<PathBuf>,
+
+    /// Reuse results for files unchanged since the run recorded in FILE
+    #[arg(
+        long,
+        value_name = "FILE",
+        num_args = 0..=1,
+        default_missing_value = ".guardrails-manifest.json",
+        conflicts_with_all = ["stdin", "changed_only", "diff_base", "staged", "sample", "resume"]
+    )]
+    pub incremental: Option<PathBuf>,
+
+    /// Don't read or write the scan result cache in .guardrails-cache/
+    #[arg(long, conflicts_with = "incremental")]
+    pub no_cache: bool,
+
+    /// Trust a plugin file to define external-command / WASM rules (repeatable)
+    #[arg(long, value_name = "PLUGIN", conflicts_with = "stdin")]
+    pub trust: Vec<String>,
+
+    /// Only count violations per rule and print a compact table (faster, low memory)
+    #[arg(long, conflicts_with_all = ["stdin", "changed_only", "diff_base", "staged", "fix", "sample", "suggest_reviewers"])]
+    pub counts_only: bool,
+
+    /// Hide violations below this severity (applies to every output format)
+    #[arg(long, value_enum, value_name = "LEVEL")]
+    pub min_severity: Option<SeverityLevel>,
+
+    /// Exit 1 on violations at this level or above (default: error, or the config's `fail_on`)
+    #[arg(long, value_enum, value_name = "LEVEL")]
+    pub fail_on: Option<FailOn>,
+
+    /// Exit 1 when there are more than N warnings
+    #[arg(long, value_name = "N")]
+    pub max_warnings: Option<usize>,
+
+    /// Suppress violations recorded in a baseline file written by `guardrails baseline`
+    #[arg(
+        long,
+        value_name = "FILE",
+        num_args = 0..=1,
+        default_missing_value = ".guardrails-baseline.json",
+        conflicts_with = "counts_only"
+    )]
+    pub baseline: Option<PathBuf>,
+
+    /// Only report and fail on violations not in FILE, a result saved with `--format json`
+    #[arg(long, value_name = "FILE", conflicts_with = "counts_only")]
+    pub compare_to: Option<PathBuf>,
+
+    /// List violations under each rule, file or CODEOWNERS owner, or print counts per directory
+    #[arg(long, value_enum, value_name = "KEY", conflicts_with_all = ["counts_only", "suggest_reviewers"])]
+    pub group_by: Option<GroupBy>,
+
+    /// Directory levels to roll counts up to with --group-by dir
+    #[arg(long, value_name = "N", default_value_t = 2, requires = "group_by")]
+    pub depth: usize,
+
+    /// Show only what fails the scan: errors and over-budget ratchet rules
+    #[arg(long)]
+    pub only_failures: bool,
+
+    /// Use ASCII instead of Unicode glyphs in pretty output
+    #[arg(long)]
+    pub ascii: bool,
+
+    /// Group the ratchet summary by rule tag or owner, with subtotals and budget use
+    #[arg(long, value_enum, value_name = "KEY")]
+    pub ratchet_group_by: Option<RatchetGroupBy>,
+
+    /// List what each inline suppression and waiver hid, and warn about unused ones
+    #[arg(long, conflicts_with_all = ["stdin", "incremental", "resume"])]
+    pub report_suppressions: bool,
+
+    /// Show up to N baselined or inline-suppressed violations per rule as warnings, a new sample each week
+    #[arg(long, value_name = "N", conflicts_with_all = ["stdin", "incremental", "resume", "counts_only"])]
+    pub audit_sample: Option<usize>,
+
+    /// Create a starter config without prompting if none exists, then scan
+    #[arg(short, long, conflicts_with = "stdin")]
+    pub yes: bool,
+
+    /// Emit machine-readable progress events on stderr
+    #[arg(long, value_enum, value_name = "FORMAT", conflicts_with = "stdin")]
+    pub progress: Option<ProgressFormat>,
+
+    /// Write per-rule counts and scan stats in Prometheus textfile format
+    #[arg(long, value_name = "PATH", conflicts_with = "stdin")]
+    pub metrics_file: Option<PathBuf>,
+
+    /// Leave source lines and fix contents out of JSON and SARIF output
+    #[arg(long)]
+    pub redact_source: bool,
+
+    /// List at most N violations in pretty and JSON output; counts still cover all
+    #[arg(long, value_name = "N")]
+    pub max_violations: Option<usize>,
+
+    /// List at most N violations per file in pretty and JSON output
+    #[arg(long, value_name = "N")]
+    pub max_per_file: Option<usize>,
+
+    /// List at most N violations per rule in pretty and JSON output, noting how many more there are
+    #[arg(long, value_name = "N")]
+    pub max_violations_per_rule: Option<usize>,
+
+    /// Print only the summary in pretty and JSON output, no individual violations
+    #[arg(short, long, conflicts_with_all = ["group_by", "counts_only", "suggest_reviewers"])]
+    pub quiet: bool,
+
+    /// What each JUnit test case covers with --format junit
+    #[arg(long, value_enum, value_name = "CASES", default_value_t = JunitCases::Rule)]
+    pub junit_cases: JunitCases,
 }
 
+// Parsed once per run, so the size of the scan variant doesn't matter
+#[allow(clippy::large_enum_variant)]
 #[derive(Subcommand)]
 pub enum Commands {
     /// Scan files for rule violations
-    Scan {
+    Scan(ScanArgs),
+
+    /// Record current violations and ratchet counts in a baseline JSON file
+    Baseline {
         /// Paths to scan (files or directories)
-        #[arg(required_unless_present = "stdin")]
+        #[arg(required = true)]
         paths: Vec<PathBuf>,
 
         /// Path to guardrails.toml config file
         #[arg(short, long, default_value = "guardrails.toml")]
         config: PathBuf,
 
-        /// Output format
+        /// Output file path for the baseline JSON
+        #[arg(short, long, default_value = ".guardrails-baseline.json")]
+        output: PathBuf,
+    },
+
+    /// Manage ratchet budgets
+    Ratchet {
+        #[command(subcommand)]
+        command: RatchetCommand,
+    },
+
+    /// Fetch the remote configs in `extends` again and re-pin them in guardrails.lock
+    UpdateConfig {
+        /// Path to guardrails.toml config file
+        #[arg(short, long, default_value = "guardrails.toml")]
+        config: PathBuf,
+
+        /// Output format (pretty or json)
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Pretty)]
+        format: OutputFormat,
+    },
+
+    /// Manage the scan result cache
+    Cache {
+        #[command(subcommand)]
+        command: CacheCommand,
+    },
+
+    /// Export or verify a signed bundle of the effective policy
+    Bundle {
+        #[command(subcommand)]
+        command: BundleCommand,
+    },
+
+    /// Manage the git pre-commit hook
+    Hook {
+        #[command(subcommand)]
+        command: HookCommand,
+    },
+
+    /// Preview how switching configs would change the violations on the current tree
+    ConfigDiff {
+        /// Current config
+        old: PathBuf,
+
+        /// Proposed config
+        new: PathBuf,
+
+        /// Paths to scan (files or directories)
+        #[arg(default_value = ".")]
+        paths: Vec<PathBuf>,
+
+        /// Output format (pretty or json)
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Pretty)]
+        format: OutputFormat,
+    },
+
+    /// Compare two saved JSON scan results: added and resolved violations and ratchet changes
+    #[command(visible_alias = "compare")]
+    DiffResults {
+        /// Older result (from `scan --format json`)
+        old: PathBuf,
+
+        /// Newer result
+        new: PathBuf,
+
+        /// Output format (pretty or json)
         #[arg(short, long, value_enum, default_value_t = OutputFormat::Pretty)]
         format: OutputFormat,
 
-        /// Read file content from stdin instead of disk
+        /// Exit 1 when the newer result has violations the older one doesn't
         #[arg(long)]
-        stdin: bool,
+        fail_on_new: bool,
+    },
 
-        /// Filename to use for glob matching when using --stdin
-        #[arg(long, requires = "stdin")]
-        filename: Option<String>,
+    /// Render a saved JSON scan result as a Markdown or HTML report for PR comments
+    Report {
+        /// Result written by `scan --format json` ("-" reads stdin)
+        #[arg(default_value = "-")]
+        file: PathBuf,
 
-        /// Only scan files changed relative to a base branch (requires git)
-        #[arg(long, conflicts_with = "stdin")]
-        changed_only: bool,
+        /// Report format
+        #[arg(short, long, value_enum, default_value_t = ReportFormat::Markdown)]
+        format: ReportFormat,
 
-        /// Base ref for --changed-only (default: auto-detect from CI env or "main")
-        #[arg(long, requires = "changed_only")]
-        base: Option<String>,
+        /// File to write the report to [default: stdout]
+        #[arg(short, long, value_name = "FILE")]
+        out: Option<PathBuf>,
+    },
 
-        /// Apply fixes automatically
+    /// Check the config for duplicate, shadowed and unreachable rules and failing ratchets
+    Doctor {
+        /// Paths to scan (files or directories)
+        #[arg(default_value = ".")]
+        paths: Vec<PathBuf>,
+
+        /// Path to guardrails.toml config file
+        #[arg(short, long, default_value = "guardrails.toml")]
+        config: PathBuf,
+
+        /// Output format (pretty or json)
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Pretty)]
+        format: OutputFormat,
+    },
+
+    /// Validate a config file without scanning, reporting every problem with its line and column
+    CheckConfig {
+        /// Path to the config file to check
+        #[arg(default_value = "guardrails.toml")]
+        path: PathBuf,
+
+        /// Output format (pretty or json)
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Pretty)]
+        format: OutputFormat,
+    },
+
+    /// Check that the rules report exactly the violations annotated in test fixtures
+    Test {
+        /// Tests files (.toml) and fixture files or directories (default:
+        /// guardrails.tests.toml and guardrails-tests/ next to the config)
+        paths: Vec<PathBuf>,
+
+        /// Path to guardrails.toml config file
+        #[arg(short, long, default_value = "guardrails.toml")]
+        config: PathBuf,
+
+        /// Output format (pretty or json)
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Pretty)]
+        format: OutputFormat,
+    },
+
+    /// Run proposed rules against the tree and show where they would match
+    Preview {
+        /// TOML file with the proposed [[rule]] entries
+        #[arg(long, value_name = "FILE")]
+        rule_file: PathBuf,
+
+        /// Paths to scan (files or directories)
+        #[arg(default_value = ".")]
+        paths: Vec<PathBuf>,
+
+        /// Config whose [guardrails] settings (exclude, max_file_size, ...) apply
+        #[arg(short, long, default_value = "guardrails.toml")]
+        config: PathBuf,
+
+        /// Output format (pretty or json)
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Pretty)]
+        format: OutputFormat,
+    },
+
+    /// Build a [[rule]] step by step, preview its matches and append it to the config
+    NewRule {
+        /// Path to guardrails.toml config file
+        #[arg(short, long, default_value = "guardrails.toml")]
+        config: PathBuf,
+
+        /// File to preview the pattern's matches in while it's being written
+        #[arg(long, value_name = "FILE")]
+        sample: Option<PathBuf>,
+    },
+
+    /// Grant a temporary exception to a rule for matching paths
+    Waive {
+        /// ID of the rule to waive
+        rule_id: String,
+
+        /// Glob of the files the waiver covers
         #[arg(long)]
-        fix: bool,
+        path: String,
 
-        /// Preview fixes without applying (requires --fix)
-        #[arg(long, requires = "fix")]
-        dry_run: bool,
+        /// Why the exception is needed (kept for audit)
+        #[arg(long)]
+        reason: String,
+
+        /// Last day the waiver applies (YYYY-MM-DD)
+        #[arg(long)]
+        expires: String,
+
+        /// Path to guardrails.toml config file
+        #[arg(short, long, default_value = "guardrails.toml")]
+        config: PathBuf,
     },
 
-    /// Count current occurrences of ratchet patterns and write a baseline JSON file
-    Baseline {
+    /// List the configured rules with their severity and owners
+    Rules {
+        /// Path to guardrails.toml config file
+        #[arg(short, long, default_value = "guardrails.toml")]
+        config: PathBuf,
+
+        /// Output format (pretty, json, or markdown for rule docs)
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Pretty)]
+        format: OutputFormat,
+    },
+
+    /// Show a rule's message, rationale, suggestion, owner and examples
+    Explain {
+        /// ID of the rule to explain
+        rule_id: String,
+
+        /// Path to guardrails.toml config file
+        #[arg(short, long, default_value = "guardrails.toml")]
+        config: PathBuf,
+
+        /// Output format (pretty, json or markdown)
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Pretty)]
+        format: OutputFormat,
+    },
+
+    /// Run the configured rules repeatedly and report throughput and per-rule cost
+    Bench {
         /// Paths to scan (files or directories)
-        #[arg(required = true)]
+        #[arg(default_value = ".")]
         paths: Vec<PathBuf>,
 
         /// Path to guardrails.toml config file
         #[arg(short, long, default_value = "guardrails.toml")]
         config: PathBuf,
 
-        /// Output file path for the baseline JSON
-        #[arg(short, long, default_value = ".guardrails-baseline.json")]
-        output: PathBuf,
+        /// Number of runs over the files
+        #[arg(short = 'n', long, default_value_t = 5)]
+        iterations: usize,
+
+        /// Output format (pretty or json)
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Pretty)]
+        format: OutputFormat,
+    },
+
+    /// POST a JSON or SARIF scan result to a results service
+    Upload {
+        /// Result written by `scan --format json` or `--format sarif` ("-" reads stdin)
+        #[arg(default_value = "-")]
+        file: PathBuf,
+
+        /// URL to POST the result to
+        #[arg(long, value_name = "URL")]
+        to: String,
+
+        /// Extra request header, e.g. "X-Team: web" (repeatable)
+        #[arg(short = 'H', long = "header", value_name = "HEADER")]
+        headers: Vec<String>,
+
+        /// Send the token in this environment variable as "Authorization: Bearer <token>"
+        #[arg(long, value_name = "VAR")]
+        token_env: Option<String>,
+
+        /// Repository name (default: from CI environment or git remote)
+        #[arg(long)]
+        repo: Option<String>,
+
+        /// Branch (default: from CI environment or git)
+        #[arg(long)]
+        branch: Option<String>,
+
+        /// Commit SHA (default: from CI environment or git)
+        #[arg(long)]
+        sha: Option<String>,
     },
 
     /// Run as an MCP (Model Context Protocol) server over stdio
@@ -74,6 +555,13 @@ pub enum Commands {
         config: PathBuf,
     },
 
+    /// Run as a Language Server Protocol server over stdio, for editor diagnostics
+    Lsp {
+        /// Path to guardrails.toml config file
+        #[arg(short, long, default_value = "guardrails.toml")]
+        config: PathBuf,
+    },
+
     /// Generate a starter guardrails.toml for your project
     Init {
         /// Output file path for the generated config
@@ -86,6 +574,92 @@ pub enum Commands {
     },
 }
 
+#[derive(Subcommand)]
+pub enum RatchetCommand {
+    /// Lower every ratchet max_count in the config to the current count where it's below
+    Update {
+        /// Paths to scan (files or directories)
+        #[arg(default_value = ".")]
+        paths: Vec<PathBuf>,
+
+        /// Path to guardrails.toml config file
+        #[arg(short, long, default_value = "guardrails.toml")]
+        config: PathBuf,
+
+        /// Show what would be tightened without writing the config
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Output format (pretty or json)
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Pretty)]
+        format: OutputFormat,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum BundleCommand {
+    /// Write the resolved config, its fingerprint and rule docs as a signed JSON bundle
+    Export {
+        /// Path to guardrails.toml config file
+        #[arg(short, long, default_value = "guardrails.toml")]
+        config: PathBuf,
+
+        /// Output file for the bundle [default: stdout]
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Version label recorded in the bundle, e.g. "2026.10"
+        #[arg(long, value_name = "VERSION")]
+        policy_version: Option<String>,
+
+        /// Environment variable holding the signing key
+        #[arg(long, value_name = "VAR", default_value = crate::bundle::KEY_ENV)]
+        key_env: String,
+    },
+
+    /// Check the bundle's signature and that the config resolves to its policy
+    Verify {
+        /// Bundle written by `bundle export`
+        bundle: PathBuf,
+
+        /// Path to guardrails.toml config file
+        #[arg(short, long, default_value = "guardrails.toml")]
+        config: PathBuf,
+
+        /// Environment variable holding the signing key
+        #[arg(long, value_name = "VAR", default_value = crate::bundle::KEY_ENV)]
+        key_env: String,
+
+        /// Output format (pretty or json)
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Pretty)]
+        format: OutputFormat,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum HookCommand {
+    /// Write .git/hooks/pre-commit to run `guardrails scan --staged` before each commit
+    Install {
+        /// Replace a pre-commit hook guardrails didn't write
+        #[arg(long)]
+        force: bool,
+
+        /// Print a .pre-commit-config.yaml entry for the pre-commit framework instead
+        #[arg(long, conflicts_with = "force")]
+        pre_commit_framework: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum CacheCommand {
+    /// Delete .guardrails-cache/ next to the config (scan results)
+    Clear {
+        /// Path to guardrails.toml config file
+        #[arg(short, long, default_value = "guardrails.toml")]
+        config: PathBuf,
+    },
+}
+
 #[derive(Clone, ValueEnum)]
 pub enum OutputFormat {
     Pretty,
@@ -93,5 +667,55 @@ pub enum OutputFormat {
     Compact,
     Github,
     Sarif,
+    #[value(alias = "md")]
     Markdown,
+    Junit,
+    Codeclimate,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ReportFormat {
+    #[value(alias = "md")]
+    Markdown,
+    Html,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum JunitCases {
+    /// One test case per configured rule
+    Rule,
+    /// One test case per violation, plus one per rule without violations
+    Violation,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum SeverityLevel {
+    Warning,
+    Error,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ProgressFormat {
+    /// One JSON object per line: scan_started, file_done, rule_timing, scan_finished
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum GroupBy {
+    /// Directory prefixes, --depth levels deep
+    Dir,
+    /// Violations listed under each rule
+    Rule,
+    /// Violations listed under each file
+    File,
+    /// Violations listed under each CODEOWNERS owner of their file
+    Owner,
+}
+
+#[derive(Clone, ValueEnum)]
+pub enum SampleMode {
+    /// Uniformly random files
+    Random,
+    /// Files from every directory, proportional to its size
+    Stratified,
 }
@@ -0,0 +1,224 @@
+use super::ScanArgs;
+use crate::extends;
+use crate::git_diff;
+use crate::scan::{self, ScanRoot};
+use std::fmt;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// What `guardrails scan` covers once the path defaults, config discovery,
+/// `--root`, `--files-from` and the diff flags are applied.
+#[derive(Debug)]
+pub struct ScanTarget {
+    /// The config that shapes the report and the exit code: the first
+    /// root's when there are roots.
+    pub config: PathBuf,
+    /// Config text from `--config -`, a remote `--config` or
+    /// `GUARDRAILS_CONFIG_INLINE`.
+    pub inline: Option<String>,
+    pub paths: Vec<PathBuf>,
+    /// Each scanned with its own config; empty when one config covers the paths.
+    pub roots: Vec<ScanRoot>,
+    /// The ref to diff against with `--changed-only`, `--diff-base` or
+    /// `--staged` ([`git_diff::STAGED`] for the index).
+    pub base_ref: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum ScanTargetError {
+    /// `--config -` and `--stdin` or `--files-from -` both read stdin.
+    SharedStdin,
+    ConfigStdin(std::io::Error),
+    RemoteConfig(PathBuf, String),
+    /// The paths use different configs, with a flag that needs just one.
+    MixedConfigs(Vec<PathBuf>),
+    FileList(PathBuf, std::io::Error),
+}
+
+impl fmt::Display for ScanTargetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScanTargetError::SharedStdin => write!(f, "--config - can't share stdin with --stdin or --files-from -"),
+            ScanTargetError::ConfigStdin(e) => write!(f, "failed to read config from stdin: {}", e),
+            ScanTargetError::RemoteConfig(path, e) => write!(f, "--config {}: {}", path.display(), e),
+            ScanTargetError::MixedConfigs(configs) => {
+                let configs: Vec<String> = configs.iter().map(|c| c.display().to_string()).collect();
+                write!(
+                    f,
+                    "the paths use different configs ({}); pass --config or scan them separately",
+                    configs.join(", ")
+                )
+            }
+            ScanTargetError::FileList(list, e) => write!(f, "failed to read file list '{}': {}", list.display(), e),
+        }
+    }
+}
+
+impl std::error::Error for ScanTargetError {}
+
+impl ScanArgs {
+    /// How the walk treats ignored and hidden files (`--no-ignore`, `--hidden`).
+    pub fn walk(&self) -> scan::WalkOptions {
+        scan::WalkOptions {
+            no_ignore: self.no_ignore,
+            hidden: self.hidden,
+        }
+    }
+
+    /// The ref to diff against, when the scan covers only changed files.
+    pub fn base_ref(&self) -> Option<String> {
+        if self.staged {
+            return Some(git_diff::STAGED.to_string());
+        }
+        let base = self.base.clone().or_else(|| self.diff_base.clone());
+        (self.changed_only || self.diff_base.is_some()).then(|| base.unwrap_or_else(git_diff::detect_base_ref))
+    }
+}
+
+impl ScanTarget {
+    /// Resolve what to scan from the flags, the working directory and the
+    /// repository it's in. Reads stdin for `--config -` and `--files-from -`.
+    pub fn resolve(args: &ScanArgs) -> Result<Self, ScanTargetError> {
+        let mut paths = args.paths.clone();
+        // Without paths or a config, scan the repository this is run in
+        let cwd = std::env::current_dir().and_then(fs::canonicalize).ok();
+        let root = git_diff::repo_root().ok().and_then(|r| fs::canonicalize(r).ok());
+        let explicit_paths = !paths.is_empty();
+        if paths.is_empty() && args.roots.is_empty() && args.files_from.is_none() && !args.stdin {
+            let up = cwd.as_deref().zip(root.as_deref()).and_then(|(cwd, root)| scan::path_up_to(cwd, root));
+            paths.push(up.unwrap_or_else(|| ".".into()));
+        }
+        let inline = inline_config(args)?;
+        let config = match inline {
+            Some(_) => Some(PathBuf::from(scan::INLINE_CONFIG)),
+            None => args.config.clone(),
+        };
+        // Without --config, paths use the config nearest to them: one in
+        // another repository or package is used for those paths instead
+        let targets = if config.is_none() && explicit_paths && args.roots.is_empty() && args.files_from.is_none() && !args.stdin {
+            scan::configs_for_targets(&paths)
+        } else {
+            Vec::new()
+        };
+        let config = config
+            .or_else(|| scan::discover_config(cwd.as_deref()?, root.as_deref()))
+            .unwrap_or_else(|| "guardrails.toml".into());
+        let mut groups: Vec<(PathBuf, Vec<PathBuf>)> = Vec::new();
+        for (found, target_paths) in targets {
+            let found = found.filter(|found| fs::canonicalize(&config).ok().as_ref() != Some(found));
+            let found = found.unwrap_or_else(|| config.clone());
+            match groups.iter_mut().find(|(c, _)| *c == found) {
+                Some((_, group)) => group.extend(target_paths),
+                None => groups.push((found, target_paths)),
+            }
+        }
+        let one_config = args.list_files
+            || args.strip_fix_markers
+            || args.sample.is_some()
+            || args.timing
+            || args.resume.is_some()
+            || args.incremental.is_some();
+        let (config, discovered_roots) = match groups.len() {
+            0 => (config, Vec::new()),
+            1 => (groups.remove(0).0, Vec::new()),
+            _ if one_config => {
+                return Err(ScanTargetError::MixedConfigs(groups.into_iter().map(|(c, _)| c).collect()));
+            }
+            _ => {
+                let roots = groups.into_iter().map(|(config, paths)| {
+                    let dir = scan::config_dir(&config);
+                    let name = cwd.as_deref().and_then(|cwd| dir.strip_prefix(cwd).ok()).unwrap_or(dir);
+                    let name = if name.as_os_str().is_empty() { PathBuf::from(".") } else { name.to_path_buf() };
+                    ScanRoot { name, paths, config }
+                });
+                (config, roots.collect())
+            }
+        };
+        // Each --root is scanned with the config nearest to it; the first
+        // one's config shapes the report and the exit code
+        let mut roots: Vec<ScanRoot> = args
+            .roots
+            .iter()
+            .map(|dir| {
+                let found = fs::canonicalize(dir)
+                    .ok()
+                    .and_then(|start| scan::discover_config(&start, root.as_deref()));
+                let config = dir.join(found.unwrap_or_else(|| "guardrails.toml".into()));
+                ScanRoot::dir(dir.clone(), config)
+            })
+            .collect();
+        roots.extend(discovered_roots);
+        let config = roots.first().map_or(config, |root| root.config.clone());
+        if let Some(ref list) = args.files_from {
+            let text = if list.as_os_str() == "-" {
+                let mut text = String::new();
+                std::io::stdin().read_to_string(&mut text).map(|_| text)
+            } else {
+                fs::read_to_string(list)
+            };
+            let text = text.map_err(|e| ScanTargetError::FileList(list.clone(), e))?;
+            paths.extend(scan::parse_file_list(&text, args.null));
+        }
+        Ok(ScanTarget {
+            config,
+            inline,
+            paths,
+            roots,
+            base_ref: args.base_ref(),
+        })
+    }
+}
+
+/// `--config -` reads the config itself from stdin; without --config,
+/// GUARDRAILS_CONFIG_INLINE can carry it instead.
+fn inline_config(args: &ScanArgs) -> Result<Option<String>, ScanTargetError> {
+    match args.config {
+        Some(ref path) if scan::is_inline_config(path) => {
+            if args.stdin || args.files_from.as_deref().is_some_and(scan::is_inline_config) {
+                return Err(ScanTargetError::SharedStdin);
+            }
+            let mut text = String::new();
+            std::io::stdin().read_to_string(&mut text).map_err(ScanTargetError::ConfigStdin)?;
+            Ok(Some(text))
+        }
+        // A remote config is fetched and pinned like a remote `extends` entry
+        Some(ref path) => match path.to_str().filter(|_| !path.exists()).and_then(extends::remote_config) {
+            Some(source) => source
+                .and_then(|source| extends::fetch_config(&source, Path::new(".")))
+                .map(Some)
+                .map_err(|e| ScanTargetError::RemoteConfig(path.clone(), e)),
+            None => Ok(None),
+        },
+        None if args.roots.is_empty() => {
+            Ok(std::env::var(scan::INLINE_CONFIG_ENV).ok().filter(|t| !t.trim().is_empty()))
+        }
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::{Cli, Commands};
+    use clap::Parser;
+
+    fn scan_args(flags: &[&str]) -> ScanArgs {
+        let argv = ["guardrails", "scan"].iter().chain(flags);
+        match Cli::parse_from(argv).command {
+            Commands::Scan(args) => args,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn diff_flags_pick_the_base_ref() {
+        assert_eq!(scan_args(&[]).base_ref(), None);
+        assert_eq!(scan_args(&["--diff-base", "develop"]).base_ref().as_deref(), Some("develop"));
+        assert_eq!(
+            scan_args(&["--changed-only", "--base", "release"]).base_ref().as_deref(),
+            Some("release")
+        );
+        assert_eq!(scan_args(&["--staged"]).base_ref().as_deref(), Some(git_diff::STAGED));
+    }
+}
@@ -1,16 +1,43 @@
-use crate::config::{RuleConfig, Severity};
-use serde::Deserialize;
+use crate::config::{
+    FailOn, ImportLayer, NearCondition, RatchetBudget, RatchetGroup, RuleConfig, RuleExamples, RuleOwner, RulePattern,
+    Severity, Suggestion,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 /// Top-level TOML config file structure.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 pub struct TomlConfig {
     pub guardrails: GuardrailsSection,
     #[serde(default)]
+    pub paths: PathsSection,
+    #[serde(default)]
+    pub output: OutputSection,
+    /// Custom extensions mapped to a known file type, e.g. `mjsx = "jsx"`.
+    #[serde(default)]
+    pub filetypes: BTreeMap<String, String>,
+    #[serde(default)]
     pub rule: Vec<TomlRule>,
+    #[serde(default)]
+    pub ratchet_group: Vec<RatchetGroup>,
+    /// Changes to inherited rules, by id or by path.
+    #[serde(default, rename = "override")]
+    pub overrides: Vec<RuleOverride>,
+    /// The `[overrides]` table.
+    #[serde(default, rename = "overrides")]
+    pub overrides_table: OverridesTable,
+}
+
+impl TomlConfig {
+    /// Every override in the config: the `[overrides]` table's, then the
+    /// `[[override]]` entries.
+    pub fn rule_overrides(&self) -> Vec<RuleOverride> {
+        self.overrides_table.with(&self.overrides)
+    }
 }
 
 /// The `[guardrails]` section.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct GuardrailsSection {
     #[allow(dead_code)]
     pub name: Option<String>,
@@ -23,11 +50,143 @@ pub struct GuardrailsSection {
     /// Paths to plugin TOML files containing additional rules
     #[serde(default)]
     pub plugins: Vec<String>,
+    /// Also load the rule packs (`guardrails-plugin-*`) the project depends on
+    #[serde(default)]
+    pub discover_plugins: bool,
+    /// Rule files (globs allowed) merged into this config, relative to it
+    #[serde(default)]
+    pub include_rules: Vec<String>,
+    /// JSON file that accumulates ratchet counts across scans (enables trends)
+    pub history: Option<String>,
+    /// TOML file of temporary waivers (default: `.guardrails-waivers.toml`)
+    pub waivers: Option<String>,
+    /// TOML file of per-directory and per-file ratchet budgets (default: `.guardrails-budgets.toml`)
+    pub budgets: Option<String>,
+    /// Skip files larger than this many bytes without reading them
+    pub max_file_size: Option<u64>,
+    /// Walk into symlinked directories and scan symlinked files
+    #[serde(default)]
+    pub follow_symlinks: bool,
+    /// Directory levels below each scan path to descend
+    pub max_depth: Option<usize>,
+    /// Files a walk may find before the scan stops with an error (default: 1,000,000)
+    pub max_files: Option<usize>,
+    /// Threads reading files ahead of rule evaluation (default: 8)
+    pub io_threads: Option<usize>,
+    /// Globs of test files, where rules' `in_tests` setting applies
+    #[serde(default)]
+    pub test_globs: Vec<String>,
+    /// Plugin files allowed to define privileged (external-command, WASM) rules
+    #[serde(default)]
+    pub trust: Vec<String>,
+    /// Which violations fail the scan (default: errors)
+    pub fail_on: Option<FailOn>,
+    /// New violations per rule a `--changed-only` scan tolerates (default: 0)
+    pub max_new_violations: Option<usize>,
 }
 
-/// A single `[[rule]]` entry.
+/// The `[paths]` section: first-party vs third-party classification.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct PathsSection {
+    /// Globs for vendored / third-party code.
+    #[serde(default)]
+    pub third_party: Vec<String>,
+    /// Globs that stay first-party even inside a third-party path.
+    #[serde(default)]
+    pub first_party: Vec<String>,
+}
+
+/// The `[output]` section: how pretty output looks.
 #[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct OutputSection {
+    /// Emit ANSI colors at all.
+    pub color: bool,
+    /// Color names for error and warning labels and the pass/fail glyphs.
+    pub error_color: String,
+    pub warning_color: String,
+    pub pass_color: String,
+    /// Glyphs for "no violations" / passing ratchets and failing ratchets.
+    pub pass_glyph: String,
+    pub fail_glyph: String,
+    /// Show the offending source line under each violation.
+    pub show_source: bool,
+    /// Show the rule's suggestion under each violation.
+    pub show_suggestions: bool,
+    /// URL of a violation on the code host, with `{sha}`, `{branch}`,
+    /// `{path}`, `{line}` and `{column}` filled in.
+    pub link_template: Option<String>,
+}
+
+impl Default for OutputSection {
+    fn default() -> Self {
+        Self {
+            color: true,
+            error_color: "red".into(),
+            warning_color: "yellow".into(),
+            pass_color: "green".into(),
+            pass_glyph: "✓".into(),
+            fail_glyph: "✗".into(),
+            show_source: true,
+            show_suggestions: true,
+            link_template: None,
+        }
+    }
+}
+
+/// An `[[override]]` entry: adjusts a rule inherited through `extends`
+/// without redeclaring it, or turns rules off (in `files` only, if given).
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RuleOverride {
+    /// The rule `severity` and `max_count` apply to.
+    pub id: Option<String>,
+    pub severity: Option<String>,
+    pub max_count: Option<usize>,
+    /// Ids of rules to turn off.
+    #[serde(default)]
+    pub disable: Vec<String>,
+    /// Globs, relative to the config, that `disable` is limited to.
+    #[serde(default, deserialize_with = "crate::config::one_or_many")]
+    pub files: Vec<String>,
+}
+
+/// The `[overrides]` table: rules turned off, and new severities, by id.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct OverridesTable {
+    #[serde(default)]
+    pub disable: Vec<String>,
+    #[serde(default)]
+    pub severity: BTreeMap<String, String>,
+}
+
+impl OverridesTable {
+    /// The table as `[[override]]` entries, followed by `entries`.
+    pub fn with(&self, entries: &[RuleOverride]) -> Vec<RuleOverride> {
+        let mut overrides: Vec<RuleOverride> = self
+            .severity
+            .iter()
+            .map(|(id, severity)| RuleOverride {
+                id: Some(id.clone()),
+                severity: Some(severity.clone()),
+                ..Default::default()
+            })
+            .collect();
+        if !self.disable.is_empty() {
+            overrides.push(RuleOverride {
+                disable: self.disable.clone(),
+                ..Default::default()
+            });
+        }
+        overrides.extend_from_slice(entries);
+        overrides
+    }
+}
+
+/// A single `[[rule]]` entry.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TomlRule {
+    /// Required for top-level rules; composite components inherit the parent's.
+    #[serde(default)]
     pub id: String,
     #[serde(rename = "type")]
     pub rule_type: String,
@@ -36,25 +195,179 @@ pub struct TomlRule {
     pub glob: Option<String>,
     #[serde(default)]
     pub message: String,
-    pub suggest: Option<String>,
+    /// A string, or a `{ title, replacement, docs }` table.
+    pub suggest: Option<Suggestion>,
     #[serde(default)]
     pub allowed_classes: Vec<String>,
     #[serde(default)]
     pub token_map: Vec<String>,
     pub pattern: Option<String>,
+    /// Ratchet and banned-pattern rules: more patterns, as strings or
+    /// `{ pattern, message, suggest, regex }` tables.
+    #[serde(default)]
+    pub patterns: Vec<RulePattern>,
     pub max_count: Option<usize>,
+    /// Ratchet rules: extra budgets for subsets of files.
+    #[serde(default)]
+    pub budgets: Vec<RatchetBudget>,
+    /// Ratchet rules: `"match"` (default), `"line"` or `"file"`: what counts toward `max_count`.
+    pub count_by: Option<String>,
+    /// Ratchet rules: also count matches that overlap an earlier one.
+    #[serde(default)]
+    pub overlapping: bool,
+    /// Ratchet and banned-pattern rules: match patterns regardless of case.
+    #[serde(default)]
+    pub case_insensitive: bool,
+    /// Ratchet and banned-pattern rules: search the whole file, so patterns can span lines.
+    #[serde(default)]
+    pub multiline: bool,
+    /// Ratchet and banned-pattern rules: only match whole words.
+    #[serde(default)]
+    pub word_boundary: bool,
+    /// Ratchet and banned-pattern rules: ignore matches inside comments.
+    #[serde(default)]
+    pub skip_comments: bool,
+    /// Ratchet and banned-pattern rules: ignore matches inside string literals.
+    #[serde(default)]
+    pub skip_strings: bool,
     #[serde(default)]
     pub packages: Vec<String>,
     #[serde(default)]
     pub regex: bool,
     pub manifest: Option<String>,
+    /// Globs selecting more files, in addition to `glob`.
     #[serde(default)]
+    pub include: Vec<String>,
+    #[serde(default, alias = "exclude")]
     pub exclude_glob: Vec<String>,
+    /// Glob matched against the file name alone, e.g. `"[A-Z]*.tsx"`.
+    pub basename_glob: Option<String>,
     pub file_contains: Option<String>,
     pub file_not_contains: Option<String>,
+    /// Only count a match when this pattern appears within a few lines of it.
+    pub near: Option<NearCondition>,
+    /// Drop a match when this pattern appears within a few lines of it.
+    pub not_near: Option<NearCondition>,
+    /// `"first-party"` (default), `"third-party"` or `"all"` (see `[paths]`).
+    pub applies_to: Option<String>,
+    /// `"off"`, `"warning"` or `"error"` in files matching `test_globs`.
+    pub in_tests: Option<String>,
+    /// Team or person responsible for the rule.
+    pub owner: Option<String>,
+    /// Where to ask questions, e.g. `"#design-system on Slack"`.
+    pub contact: Option<String>,
+    /// Who to go to when the contact can't help (e.g. to request an exception).
+    pub escalation: Option<String>,
+    /// Labels for grouping rules, e.g. in the ratchet summary (`--ratchet-group-by tag`).
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Why the rule exists, shown by `explain` and rule docs.
+    pub rationale: Option<String>,
+    /// Link to longer documentation of the rule.
+    pub docs: Option<String>,
+    /// Page on the convention the rule enforces, linked from each of its
+    /// violations.
+    pub url: Option<String>,
+    /// `examples.bad` / `examples.good` snippets shown by `explain` and rule docs.
+    pub examples: Option<RuleExamples>,
     #[serde(default)]
     pub required_files: Vec<String>,
     pub condition_pattern: Option<String>,
+    /// Text `--fix` adds at the top of files missing the pattern (used by required-pattern).
+    pub insert: Option<String>,
+    pub max_files: Option<usize>,
+    pub max_lines: Option<usize>,
+    /// Maximum file size in bytes (used by file-limits).
+    pub max_bytes: Option<usize>,
+    /// Maximum line length in characters (used by file-limits).
+    pub max_line_length: Option<usize>,
+    /// Required encoding, "utf-8" or "ascii" (used by file-encoding).
+    pub encoding: Option<String>,
+    /// Regexes of matched secrets to ignore (used by secrets).
+    #[serde(default)]
+    pub allowlist: Vec<String>,
+    /// Bits per character from which a generic secret counts (used by secrets, default 3.5).
+    pub min_entropy: Option<f64>,
+    /// Tree-sitter query to match (used by syntax-query).
+    pub query: Option<String>,
+    /// `"javascript"`, `"typescript"` or `"tsx"` (used by syntax-query).
+    pub language: Option<String>,
+    /// Age in days at which a TODO becomes an error (used by todo-age).
+    pub max_age_days: Option<u64>,
+    /// Globs of files that need an acknowledgement for large changes (used by risky-change).
+    #[serde(default)]
+    pub critical_files: Vec<String>,
+    pub max_changed_percent: Option<u32>,
+    #[serde(default)]
+    pub freeze_windows: Vec<String>,
+    pub bypass_marker: Option<String>,
+    /// Globs of directories matching files must live in (used by file-structure).
+    #[serde(default)]
+    pub allowed_dirs: Vec<String>,
+    /// `"kebab-case"`, `"snake_case"`, `"camelCase"` or `"PascalCase"` (used by file-structure).
+    pub naming: Option<String>,
+    /// Keys the frontmatter must set (used by frontmatter).
+    #[serde(default)]
+    pub required_keys: Vec<String>,
+    /// Values each key may take, by key (used by frontmatter).
+    #[serde(default)]
+    pub allowed_values: BTreeMap<String, Vec<String>>,
+    /// Keys the frontmatter must not set (used by frontmatter).
+    #[serde(default)]
+    pub banned_keys: Vec<String>,
+    /// Values each key must not take, by key (used by frontmatter).
+    #[serde(default)]
+    pub banned_values: BTreeMap<String, Vec<String>>,
+    /// Named layers and the imports allowed between them (used by import-boundary).
+    #[serde(default)]
+    pub layers: Vec<ImportLayer>,
+    /// Program and arguments to run (used by external-command).
+    #[serde(default)]
+    pub command: Vec<String>,
+    /// Run `command` once over every matching file (used by external-command).
+    #[serde(default)]
+    pub batch: bool,
+    /// Seconds `command` may run before it's killed (used by external-command, default 30).
+    pub timeout_secs: Option<u64>,
+    /// `"error"` (default), `"warning"` or `"ignore"`: how a failed `command`
+    /// or trapping `module` is reported (used by external-command and wasm).
+    pub on_failure: Option<String>,
+    /// Path of the WebAssembly module implementing the rule, relative to the
+    /// file defining the rule (used by wasm).
+    pub module: Option<String>,
+    /// New violations a `--changed-only` scan tolerates for this rule,
+    /// overriding `[guardrails] max_new_violations`.
+    pub max_new_violations: Option<usize>,
+    /// Only report violations on lines changed relative to the base branch,
+    /// even in a full scan.
+    #[serde(default)]
+    pub only_changed: bool,
+    /// Still runs, but emits a one-time deprecation notice.
+    #[serde(default)]
+    pub deprecated: bool,
+    /// ID of the rule that supersedes this one; implies `deprecated`.
+    /// Suppressions using this rule's id also apply to the replacement.
+    pub replaced_by: Option<String>,
+    /// Day (`YYYY-MM-DD`, UTC) the rule was added, for `grace_period_days`.
+    pub introduced: Option<String>,
+    /// Days after `introduced` during which the rule's errors are reported
+    /// as warnings.
+    pub grace_period_days: Option<u32>,
+    /// Composite rules: `"line"` (default) or `"file"`. Ratchet rules:
+    /// `"repo"` (default) or `"new"`, counting only matches on changed lines.
+    pub scope: Option<String>,
+    /// Composite rules: components that must all match.
+    #[serde(default)]
+    pub all_of: Vec<TomlRule>,
+    /// Composite rules: components of which at least one must match.
+    #[serde(default)]
+    pub any_of: Vec<TomlRule>,
+    /// Composite rules: components that must not match.
+    #[serde(default)]
+    pub none_of: Vec<TomlRule>,
+    /// Plugin file the rule was loaded from; `None` for the main config and presets.
+    #[serde(skip)]
+    pub source: Option<String>,
 }
 
 fn default_severity() -> String {
@@ -73,20 +386,93 @@ impl Default for TomlRule {
             allowed_classes: Vec::new(),
             token_map: Vec::new(),
             pattern: None,
+            patterns: Vec::new(),
             max_count: None,
+            budgets: Vec::new(),
+            count_by: None,
+            overlapping: false,
+            case_insensitive: false,
+            multiline: false,
+            word_boundary: false,
+            skip_comments: false,
+            skip_strings: false,
             packages: Vec::new(),
             regex: false,
             manifest: None,
+            include: Vec::new(),
             exclude_glob: Vec::new(),
+            basename_glob: None,
             file_contains: None,
             file_not_contains: None,
+            near: None,
+            not_near: None,
+            applies_to: None,
+            in_tests: None,
+            owner: None,
+            contact: None,
+            escalation: None,
+            tags: Vec::new(),
+            rationale: None,
+            docs: None,
+            url: None,
+            examples: None,
             required_files: Vec::new(),
             condition_pattern: None,
+            insert: None,
+            max_files: None,
+            max_lines: None,
+            max_bytes: None,
+            max_line_length: None,
+            encoding: None,
+            allowlist: Vec::new(),
+            min_entropy: None,
+            query: None,
+            language: None,
+            max_age_days: None,
+            critical_files: Vec::new(),
+            max_changed_percent: None,
+            freeze_windows: Vec::new(),
+            bypass_marker: None,
+            allowed_dirs: Vec::new(),
+            naming: None,
+            required_keys: Vec::new(),
+            allowed_values: BTreeMap::new(),
+            banned_keys: Vec::new(),
+            banned_values: BTreeMap::new(),
+            layers: Vec::new(),
+            command: Vec::new(),
+            batch: false,
+            timeout_secs: None,
+            on_failure: None,
+            module: None,
+            max_new_violations: None,
+            only_changed: false,
+            deprecated: false,
+            replaced_by: None,
+            introduced: None,
+            grace_period_days: None,
+            scope: None,
+            all_of: Vec::new(),
+            any_of: Vec::new(),
+            none_of: Vec::new(),
+            source: None,
         }
     }
 }
 
 impl TomlRule {
+    /// Ownership metadata, when any of `owner`, `contact` or `escalation` is set.
+    pub fn ownership(&self) -> Option<RuleOwner> {
+        if self.owner.is_none() && self.contact.is_none() && self.escalation.is_none() {
+            return None;
+        }
+        Some(RuleOwner {
+            owner: self.owner.clone(),
+            contact: self.contact.clone(),
+            escalation: self.escalation.clone(),
+        })
+    }
+
     /// Convert to the core `RuleConfig` type.
     pub fn to_rule_config(&self) -> RuleConfig {
         let severity = match self.severity.to_lowercase().as_str() {
@@ -98,20 +484,76 @@ impl TomlRule {
             id: self.id.clone(),
             severity,
             message: self.message.clone(),
-            suggest: self.suggest.clone(),
+            suggest: self.suggest.as_ref().map(|s| s.title.clone()),
             glob: self.glob.clone(),
             allowed_classes: self.allowed_classes.clone(),
             token_map: self.token_map.clone(),
             pattern: self.pattern.clone(),
+            patterns: self.patterns.clone(),
             max_count: self.max_count,
+            count_by: self.count_by.clone(),
+            overlapping: self.overlapping,
+            case_insensitive: self.case_insensitive,
+            multiline: self.multiline,
+            word_boundary: self.word_boundary,
+            skip_comments: self.skip_comments,
+            skip_strings: self.skip_strings,
             packages: self.packages.clone(),
             regex: self.regex,
             manifest: self.manifest.clone(),
+            include: self.include.clone(),
             exclude_glob: self.exclude_glob.clone(),
             file_contains: self.file_contains.clone(),
             file_not_contains: self.file_not_contains.clone(),
             required_files: self.required_files.clone(),
             condition_pattern: self.condition_pattern.clone(),
+            insert: self.insert.clone(),
+            max_files: self.max_files,
+            max_lines: self.max_lines,
+            max_bytes: self.max_bytes,
+            max_line_length: self.max_line_length,
+            encoding: self.encoding.clone(),
+            allowlist: self.allowlist.clone(),
+            min_entropy: self.min_entropy,
+            query: self.query.clone(),
+            language: self.language.clone(),
+            max_age_days: self.max_age_days,
+            critical_files: self.critical_files.clone(),
+            max_changed_percent: self.max_changed_percent,
+            freeze_windows: self.freeze_windows.clone(),
+            bypass_marker: self.bypass_marker.clone(),
+            allowed_dirs: self.allowed_dirs.clone(),
+            naming: self.naming.clone(),
+            required_keys: self.required_keys.clone(),
+            allowed_values: self.allowed_values.clone(),
+            banned_keys: self.banned_keys.clone(),
+            banned_values: self.banned_values.clone(),
+            layers: self.layers.clone(),
+            command: self.command.clone(),
+            batch: self.batch,
+            timeout_secs: self.timeout_secs,
+            on_failure: self.on_failure.clone(),
+            module: self.module.clone(),
+            scope: self.scope.clone(),
+            all_of: self.components(&self.all_of),
+            any_of: self.components(&self.any_of),
+            none_of: self.components(&self.none_of),
+            defer_regex: false,
+            regex_pool: None,
         }
     }
+
+    /// Composite components as `(type, config)`, reporting under this rule's id.
+    fn components(&self, rules: &[TomlRule]) -> Vec<(String, RuleConfig)> {
+        rules
+            .iter()
+            .map(|c| {
+                let config = RuleConfig {
+                    id: self.id.clone(),
+                    ..c.to_rule_config()
+                };
+                (c.rule_type.clone(), config)
+            })
+            .collect()
+    }
 }
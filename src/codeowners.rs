@@ -0,0 +1,379 @@
+use crate::rules::Violation;
+use globset::{GlobBuilder, GlobMatcher};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Locations GitHub checks for a CODEOWNERS file, in priority order.
+const CODEOWNERS_PATHS: &[&str] = &[".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+
+/// Parsed CODEOWNERS rules. Later entries take precedence, as on GitHub.
+#[derive(Debug, Default)]
+pub struct CodeOwners {
+    entries: Vec<OwnerEntry>,
+}
+
+#[derive(Debug)]
+struct OwnerEntry {
+    matchers: Vec<GlobMatcher>,
+    owners: Vec<String>,
+}
+
+/// Owners whose paths tripped guardrails, with the files involved.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ReviewerSuggestion {
+    pub owner: String,
+    pub files: Vec<PathBuf>,
+    pub violations: usize,
+}
+
+/// The CODEOWNERS of the git repositories a scan covered, each matched
+/// against paths relative to its repository's root.
+#[derive(Debug, Default)]
+pub struct RepoCodeOwners {
+    /// Repository roots (canonical) with their CODEOWNERS, deepest first.
+    repos: Vec<(PathBuf, CodeOwners)>,
+    cwd: PathBuf,
+}
+
+/// Reviewer suggestions for a scan, plus files no CODEOWNERS entry covers.
+#[derive(Debug, Default, Serialize)]
+pub struct ReviewerReport {
+    pub reviewers: Vec<ReviewerSuggestion>,
+    pub unowned_files: Vec<PathBuf>,
+}
+
+impl CodeOwners {
+    /// Load the first CODEOWNERS file found under `root`, if any.
+    pub fn load(root: &Path) -> Option<Self> {
+        CODEOWNERS_PATHS
+            .iter()
+            .find_map(|p| fs::read_to_string(root.join(p)).ok())
+            .map(|text| Self::parse(&text))
+    }
+
+    /// Parse CODEOWNERS text. Lines with invalid patterns are skipped.
+    pub fn parse(text: &str) -> Self {
+        let entries = text
+            .lines()
+            .filter_map(|line| {
+                let line = line.trim();
+                if line.is_empty() || line.starts_with('#') {
+                    return None;
+                }
+                let mut parts = line.split_whitespace();
+                let pattern = parts.next()?;
+                let owners: Vec<String> = parts
+                    .take_while(|p| !p.starts_with('#'))
+                    .map(|p| p.to_string())
+                    .collect();
+                let matchers = pattern_to_globs(pattern)
+                    .iter()
+                    .map(|g| {
+                        GlobBuilder::new(g)
+                            .literal_separator(true)
+                            .build()
+                            .map(|g| g.compile_matcher())
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+                    .ok()?;
+                Some(OwnerEntry { matchers, owners })
+            })
+            .collect();
+
+        Self { entries }
+    }
+
+    /// Owners of a repo-relative path. Empty when unowned (or explicitly unassigned).
+    pub fn owners_of(&self, path: &Path) -> &[String] {
+        self.entries
+            .iter()
+            .rev()
+            .find(|e| e.matchers.iter().any(|m| m.is_match(path)))
+            .map(|e| e.owners.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// The owners of each violated file that has any, keyed by the file as
+    /// the violations name it.
+    ///
+    /// Violation paths are made relative to `root` before matching.
+    pub fn attribute(&self, violations: &[Violation], root: &Path) -> HashMap<PathBuf, Vec<String>> {
+        let mut owned = HashMap::new();
+        for v in violations {
+            if !owned.contains_key(&v.file) {
+                let owners = self.owners_of(&relative_path(&v.file, root));
+                if !owners.is_empty() {
+                    owned.insert(v.file.clone(), owners.to_vec());
+                }
+            }
+        }
+        owned
+    }
+
+    /// Map violated files to owners, deduplicated and ordered by violation count.
+    ///
+    /// Violation paths are made relative to `root` before matching.
+    pub fn suggest_reviewers(&self, violations: &[Violation], root: &Path) -> ReviewerReport {
+        let mut per_file: BTreeMap<PathBuf, usize> = BTreeMap::new();
+        for v in violations {
+            *per_file.entry(relative_path(&v.file, root)).or_insert(0) += 1;
+        }
+
+        let mut by_owner: BTreeMap<&str, ReviewerSuggestion> = BTreeMap::new();
+        let mut unowned_files = Vec::new();
+        for (file, count) in per_file {
+            let owners = self.owners_of(&file);
+            if owners.is_empty() {
+                unowned_files.push(file);
+                continue;
+            }
+            for owner in owners {
+                let entry = by_owner.entry(owner).or_insert_with(|| ReviewerSuggestion {
+                    owner: owner.clone(),
+                    files: Vec::new(),
+                    violations: 0,
+                });
+                entry.files.push(file.clone());
+                entry.violations += count;
+            }
+        }
+
+        let mut reviewers: Vec<ReviewerSuggestion> = by_owner.into_values().collect();
+        reviewers.sort_by(|a, b| b.violations.cmp(&a.violations).then_with(|| a.owner.cmp(&b.owner)));
+
+        ReviewerReport {
+            reviewers,
+            unowned_files,
+        }
+    }
+}
+
+impl RepoCodeOwners {
+    /// Load the CODEOWNERS of the repository each of `scanned` is in, from
+    /// its root; paths outside a repository use the one in `cwd`. `None`
+    /// when none of them has one.
+    pub fn load(scanned: &[PathBuf], cwd: &Path) -> Option<Self> {
+        let mut roots: Vec<PathBuf> = Vec::new();
+        for path in scanned.iter().map(PathBuf::as_path).chain(scanned.is_empty().then_some(cwd)) {
+            let root = repo_root(&canonical(&cwd.join(path))).unwrap_or_else(|| canonical(cwd));
+            if !roots.contains(&root) {
+                roots.push(root);
+            }
+        }
+        let mut repos: Vec<(PathBuf, CodeOwners)> = roots
+            .into_iter()
+            .filter_map(|root| CodeOwners::load(&root).map(|owners| (root, owners)))
+            .collect();
+        if repos.is_empty() {
+            return None;
+        }
+        repos.sort_by_key(|(root, _)| std::cmp::Reverse(root.components().count()));
+        Some(Self {
+            repos,
+            cwd: canonical(cwd),
+        })
+    }
+
+    /// The owners of each violated file that has any, keyed by the file as
+    /// the violations name it.
+    pub fn attribute(&self, violations: &[Violation]) -> HashMap<PathBuf, Vec<String>> {
+        let mut owned = HashMap::new();
+        for v in violations {
+            if !owned.contains_key(&v.file) {
+                if let Some((i, file)) = self.locate(&v.file) {
+                    let owners = self.repos[i].1.owners_of(&file);
+                    if !owners.is_empty() {
+                        owned.insert(v.file.clone(), owners.to_vec());
+                    }
+                }
+            }
+        }
+        owned
+    }
+
+    /// Like [`CodeOwners::suggest_reviewers`], with each file matched
+    /// against the CODEOWNERS of its repository and named relative to it.
+    pub fn suggest_reviewers(&self, violations: &[Violation]) -> ReviewerReport {
+        let mut per_repo: Vec<Vec<Violation>> = vec![Vec::new(); self.repos.len()];
+        for v in violations {
+            if let Some((i, file)) = self.locate(&v.file) {
+                per_repo[i].push(Violation { file, ..v.clone() });
+            }
+        }
+
+        let mut report = ReviewerReport::default();
+        for ((_, owners), own) in self.repos.iter().zip(per_repo) {
+            let part = owners.suggest_reviewers(&own, Path::new(""));
+            report.unowned_files.extend(part.unowned_files);
+            for suggestion in part.reviewers {
+                match report.reviewers.iter_mut().find(|r| r.owner == suggestion.owner) {
+                    Some(r) => {
+                        r.files.extend(suggestion.files);
+                        r.violations += suggestion.violations;
+                    }
+                    None => report.reviewers.push(suggestion),
+                }
+            }
+        }
+        report.reviewers.sort_by(|a, b| b.violations.cmp(&a.violations).then_with(|| a.owner.cmp(&b.owner)));
+        report
+    }
+
+    /// The index of the repository `file` is in, and `file` relative to its root.
+    fn locate(&self, file: &Path) -> Option<(usize, PathBuf)> {
+        let file = canonical(&self.cwd.join(file));
+        self.repos
+            .iter()
+            .position(|(root, _)| file.starts_with(root))
+            .map(|i| (i, relative_path(&file, &self.repos[i].0)))
+    }
+}
+
+/// The root of the git repository `path` is in: its nearest ancestor with a `.git`.
+fn repo_root(path: &Path) -> Option<PathBuf> {
+    path.ancestors().find(|dir| dir.join(".git").exists()).map(Path::to_path_buf)
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Translate a gitignore-style CODEOWNERS pattern into globs.
+fn pattern_to_globs(pattern: &str) -> Vec<String> {
+    let anchored = pattern.starts_with('/');
+    let trimmed = pattern.trim_start_matches('/');
+    let dir_only = trimmed.ends_with('/');
+    let trimmed = trimmed.trim_end_matches('/');
+
+    if trimmed.is_empty() {
+        return vec!["**".to_string()];
+    }
+
+    // Patterns without an inner slash match at any depth
+    let base = if anchored || trimmed.contains('/') {
+        trimmed.to_string()
+    } else {
+        format!("**/{}", trimmed)
+    };
+
+    // A trailing wildcard segment (`docs/*`) matches files only, not nested paths
+    let last_segment = base.rsplit('/').next().unwrap_or_default();
+    if dir_only {
+        vec![format!("{}/**", base)]
+    } else if last_segment.contains('*') {
+        vec![base]
+    } else {
+        vec![base.clone(), format!("{}/**", base)]
+    }
+}
+
+fn relative_path(file: &Path, root: &Path) -> PathBuf {
+    let rel = file.strip_prefix(root).unwrap_or(file);
+    rel.strip_prefix(".").unwrap_or(rel).to_path_buf()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "\
+# Default owners
+*               @org/core
+/infra/         @org/platform   # infra team
+*.tsx           @org/frontend @alice
+/docs/generated/
+";
+
+    #[test]
+    fn last_match_wins() {
+        let owners = CodeOwners::parse(SAMPLE);
+        assert_eq!(owners.owners_of(Path::new("src/lib.rs")), ["@org/core"]);
+        assert_eq!(owners.owners_of(Path::new("infra/main.tf")), ["@org/platform"]);
+        assert_eq!(
+            owners.owners_of(Path::new("src/ui/Button.tsx")),
+            ["@org/frontend", "@alice"]
+        );
+    }
+
+    #[test]
+    fn single_star_does_not_cross_directories() {
+        let owners = CodeOwners::parse("/src/* @org/src\n");
+        assert_eq!(owners.owners_of(Path::new("src/main.rs")), ["@org/src"]);
+        assert!(owners.owners_of(Path::new("src/a/b.rs")).is_empty());
+    }
+
+    #[test]
+    fn entry_without_owners_unassigns() {
+        let owners = CodeOwners::parse(SAMPLE);
+        assert!(owners.owners_of(Path::new("docs/generated/api.md")).is_empty());
+    }
+
+    #[test]
+    fn anchored_pattern_only_matches_root() {
+        let owners = CodeOwners::parse("/infra/ @org/platform\n");
+        assert!(owners.owners_of(Path::new("infra/a.tf")).len() == 1);
+        assert!(owners.owners_of(Path::new("src/infra/a.tf")).is_empty());
+    }
+
+    #[test]
+    fn suggest_reviewers_dedupes_and_orders() {
+        let owners = CodeOwners::parse(SAMPLE);
+        let violations = vec![
+            Violation::test("r", "./src/ui/Button.tsx"),
+            Violation::test("r", "./src/ui/Button.tsx"),
+            Violation::test("r", "./src/ui/Card.tsx"),
+            Violation::test("r", "./infra/main.tf"),
+            Violation::test("r", "./docs/generated/api.md"),
+        ];
+        let report = owners.suggest_reviewers(&violations, Path::new("."));
+
+        let names: Vec<&str> = report.reviewers.iter().map(|r| r.owner.as_str()).collect();
+        assert_eq!(names, ["@alice", "@org/frontend", "@org/platform"]);
+        assert_eq!(report.reviewers[0].violations, 3);
+        assert_eq!(report.reviewers[0].files.len(), 2);
+        assert_eq!(report.unowned_files, [PathBuf::from("docs/generated/api.md")]);
+
+        let owned = owners.attribute(&violations, Path::new("."));
+        assert_eq!(owned.len(), 3);
+        assert_eq!(owned[Path::new("./src/ui/Card.tsx")], ["@org/frontend", "@alice"]);
+        assert!(!owned.contains_key(Path::new("./docs/generated/api.md")));
+    }
+
+    #[test]
+    fn each_repository_uses_its_own_codeowners() {
+        let dir = tempfile::tempdir().unwrap();
+        for (repo, owner) in [("web", "@web"), ("api", "@api")] {
+            fs::create_dir_all(dir.path().join(repo).join(".git")).unwrap();
+            fs::create_dir_all(dir.path().join(repo).join("src")).unwrap();
+            fs::write(dir.path().join(repo).join("CODEOWNERS"), format!("/src/ {}\n", owner)).unwrap();
+            fs::write(dir.path().join(repo).join("src/a.ts"), "").unwrap();
+        }
+        // CODEOWNERS in the directory this runs in isn't read for repositories under it
+        fs::write(dir.path().join("CODEOWNERS"), "* @cwd\n").unwrap();
+
+        let scanned = [PathBuf::from("web/src"), PathBuf::from("api")];
+        let owners = RepoCodeOwners::load(&scanned, dir.path()).unwrap();
+        let violations = vec![
+            Violation::test("r", "web/src/a.ts"),
+            Violation::test("r", "api/src/a.ts"),
+            Violation::test("r", "api/src/a.ts"),
+        ];
+        let owned = owners.attribute(&violations);
+        assert_eq!(owned[Path::new("web/src/a.ts")], ["@web"]);
+        assert_eq!(owned[Path::new("api/src/a.ts")], ["@api"]);
+
+        let report = owners.suggest_reviewers(&violations);
+        let names: Vec<(&str, usize)> = report.reviewers.iter().map(|r| (r.owner.as_str(), r.violations)).collect();
+        assert_eq!(names, [("@api", 2), ("@web", 1)]);
+        assert_eq!(report.reviewers[0].files, [PathBuf::from("src/a.ts")]);
+
+        // Outside a repository, the directory this runs in is used
+        let plain = tempfile::tempdir().unwrap();
+        assert!(RepoCodeOwners::load(&[PathBuf::from(".")], plain.path()).is_none());
+        fs::write(plain.path().join("CODEOWNERS"), "* @cwd\n").unwrap();
+        let owners = RepoCodeOwners::load(&[], plain.path()).unwrap();
+        assert_eq!(owners.attribute(&[Violation::test("r", "x.ts")])[Path::new("x.ts")], ["@cwd"]);
+    }
+}
@@ -0,0 +1,117 @@
+use crate::cli::toml_config::TomlRule;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Compile cache used by `scan` unless `--no-cache` is given, relative to
+/// the config's directory.
+pub const COMPILE_CACHE_FILE: &str = ".guardrails-cache/compile.json";
+
+/// A config's rules as the last cold build resolved and validated them.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CompiledRules {
+    /// The config fingerprint the rules were built for.
+    fingerprint: String,
+    /// `exclude` globs, with those of nested configs scoped to their directory.
+    pub exclude: Vec<String>,
+    /// The rules after presets, `extends`, plugins, overrides and nested
+    /// configs are applied.
+    pub rules: Vec<TomlRule>,
+    /// Regexes the rules use that parsed, so they needn't be checked again.
+    pub regexes: Vec<String>,
+    /// How long the cold build took, in microseconds.
+    build_micros: u64,
+}
+
+impl CompiledRules {
+    /// How long the cold build took.
+    pub fn cold_build(&self) -> Duration {
+        Duration::from_micros(self.build_micros)
+    }
+}
+
+/// Saves the resolved rules and validated regexes of a config, so the next
+/// run with the same config skips resolving presets and shared configs and
+/// re-checking regex syntax.
+///
+/// Compiled regex programs can't be serialized, so regexes are still compiled
+/// the first time a rule runs on a file. The cache is keyed by the config
+/// fingerprint, which covers the config, its plugins, included rule files,
+/// nested configs, shared configs and the tool version.
+#[derive(Debug)]
+pub struct CompileCache {
+    path: PathBuf,
+    fingerprint: String,
+    compiled: Option<CompiledRules>,
+}
+
+impl CompileCache {
+    /// Open the cache at `path` for the config with `fingerprint`. It's warm
+    /// only if it was saved for the same fingerprint.
+    pub fn open(path: &Path, fingerprint: String) -> Self {
+        let compiled = fs::read_to_string(path)
+            .ok()
+            .and_then(|text| serde_json::from_str::<CompiledRules>(&text).ok())
+            .filter(|compiled| compiled.fingerprint == fingerprint);
+        Self {
+            path: path.to_path_buf(),
+            fingerprint,
+            compiled,
+        }
+    }
+
+    /// The saved rules, on a warm start.
+    pub fn warm(&self) -> Option<&CompiledRules> {
+        self.compiled.as_ref()
+    }
+
+    /// Save the outcome of a cold build that took `build_time`.
+    pub fn save(&self, exclude: &[String], rules: &[TomlRule], regexes: Vec<String>, build_time: Duration) -> io::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let compiled = CompiledRules {
+            fingerprint: self.fingerprint.clone(),
+            exclude: exclude.to_vec(),
+            rules: rules.to_vec(),
+            regexes,
+            build_micros: build_time.as_micros() as u64,
+        };
+        fs::write(&self.path, serde_json::to_string(&compiled).map_err(io::Error::other)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(pattern: &str) -> TomlRule {
+        TomlRule {
+            id: "r".into(),
+            rule_type: "banned-pattern".into(),
+            pattern: Some(pattern.into()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn warm_only_for_the_config_it_was_saved_for() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(COMPILE_CACHE_FILE);
+
+        let cold = CompileCache::open(&path, "a".into());
+        assert!(cold.warm().is_none());
+        cold.save(&["dist/**".into()], &[rule("eval")], vec![r"eval\(".into()], Duration::from_millis(80))
+            .unwrap();
+
+        let warm = CompileCache::open(&path, "a".into());
+        let compiled = warm.warm().unwrap();
+        assert_eq!(compiled.cold_build(), Duration::from_millis(80));
+        assert_eq!(compiled.exclude, ["dist/**"]);
+        assert_eq!(compiled.rules[0].pattern.as_deref(), Some("eval"));
+        assert_eq!(compiled.regexes, [r"eval\("]);
+        assert!(CompileCache::open(&path, "b".into()).warm().is_none());
+    }
+}
@@ -1,10 +1,225 @@
 /// Severity level for a rule violation.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Severity {
     Error,
     Warning,
 }
 
+/// Which violations fail a scan (`--fail-on`, `[guardrails] fail_on`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum FailOn {
+    /// Errors and warnings
+    Warning,
+    /// Errors only
+    #[default]
+    Error,
+    /// Nothing; only --max-warnings can fail the scan
+    Never,
+}
+
+/// A pattern that must (`near`) or must not (`not_near`) appear within
+/// `within_lines` lines of a match for it to count.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct NearCondition {
+    pub pattern: String,
+    #[serde(default = "default_within_lines")]
+    pub within_lines: usize,
+    /// Treat `pattern` as a regex (default: literal).
+    #[serde(default)]
+    pub regex: bool,
+}
+
+fn default_within_lines() -> usize {
+    5
+}
+
+/// One of a rule's `patterns`: a bare string, or a table that can give the
+/// pattern its own `message`, `suggest` and `regex` setting.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(from = "PatternEntry")]
+pub struct RulePattern {
+    pub pattern: String,
+    pub message: Option<String>,
+    pub suggest: Option<String>,
+    /// Overrides the rule's `regex` for this pattern.
+    pub regex: Option<bool>,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum PatternEntry {
+    Plain(String),
+    Table {
+        pattern: String,
+        message: Option<String>,
+        suggest: Option<String>,
+        regex: Option<bool>,
+    },
+}
+
+impl From<PatternEntry> for RulePattern {
+    fn from(entry: PatternEntry) -> Self {
+        match entry {
+            PatternEntry::Plain(pattern) => Self {
+                pattern,
+                ..Default::default()
+            },
+            PatternEntry::Table {
+                pattern,
+                message,
+                suggest,
+                regex,
+            } => Self {
+                pattern,
+                message,
+                suggest,
+                regex,
+            },
+        }
+    }
+}
+
+/// A rule's `suggest`: a bare string, or a table that adds a replacement
+/// snippet and a docs link for outputs that can show more than text.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(from = "SuggestionEntry")]
+pub struct Suggestion {
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub replacement: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub docs: Option<String>,
+}
+
+impl Suggestion {
+    /// Whether there is more to show than the title.
+    pub fn is_structured(&self) -> bool {
+        self.replacement.is_some() || self.docs.is_some()
+    }
+}
+
+impl From<&str> for Suggestion {
+    fn from(title: &str) -> Self {
+        Self {
+            title: title.to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum SuggestionEntry {
+    Plain(String),
+    Table {
+        title: String,
+        replacement: Option<String>,
+        docs: Option<String>,
+    },
+}
+
+impl From<SuggestionEntry> for Suggestion {
+    fn from(entry: SuggestionEntry) -> Self {
+        match entry {
+            SuggestionEntry::Plain(title) => Self {
+                title,
+                ..Default::default()
+            },
+            SuggestionEntry::Table {
+                title,
+                replacement,
+                docs,
+            } => Self {
+                title,
+                replacement,
+                docs,
+            },
+        }
+    }
+}
+
+/// A ratchet budget for the files under `glob`, on top of the rule-wide
+/// `max_count`. Matches in those files take `severity` when it is set.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct RatchetBudget {
+    pub glob: String,
+    pub max_count: usize,
+    pub severity: Option<Severity>,
+}
+
+/// A `[[ratchet_group]]`: ratchet rules whose matches share one budget on
+/// top of their own `max_count`s.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct RatchetGroup {
+    pub id: String,
+    pub rules: Vec<String>,
+    pub max_count: usize,
+}
+
+/// A layer of an import-boundary rule: the files matching `glob`, and the
+/// layers they may import from besides their own.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ImportLayer {
+    pub name: String,
+    #[serde(deserialize_with = "one_or_many")]
+    pub glob: Vec<String>,
+    #[serde(default)]
+    pub may_import: Vec<String>,
+}
+
+/// Who to ask about a rule, from its `owner`, `contact` and `escalation` fields.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize)]
+pub struct RuleOwner {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contact: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub escalation: Option<String>,
+}
+
+impl RuleOwner {
+    /// One-line form, e.g. `owner: design-system · questions? #design-system on Slack`.
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(ref owner) = self.owner {
+            parts.push(format!("owner: {}", owner));
+        }
+        if let Some(ref contact) = self.contact {
+            parts.push(format!("questions? {}", contact));
+        }
+        if let Some(ref escalation) = self.escalation {
+            parts.push(format!("escalation: {}", escalation));
+        }
+        parts.join(" · ")
+    }
+}
+
+/// Code snippets that show what a rule rejects (`bad`) and accepts (`good`).
+/// Each side takes one snippet or a list of them.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct RuleExamples {
+    #[serde(default, deserialize_with = "one_or_many")]
+    pub bad: Vec<String>,
+    #[serde(default, deserialize_with = "one_or_many")]
+    pub good: Vec<String>,
+}
+
+pub(crate) fn one_or_many<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    #[derive(serde::Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+    Ok(match serde::Deserialize::deserialize(deserializer)? {
+        OneOrMany::One(value) => vec![value],
+        OneOrMany::Many(values) => values,
+    })
+}
+
 /// Parsed rule configuration from `guardrails.toml`.
 #[derive(Debug, Clone)]
 pub struct RuleConfig {
@@ -19,14 +234,33 @@ pub struct RuleConfig {
     pub token_map: Vec<String>,
     /// Literal pattern to search for (used by ratchet and banned-pattern rules).
     pub pattern: Option<String>,
+    /// More patterns searched for alongside `pattern` (used by ratchet and banned-pattern rules).
+    pub patterns: Vec<RulePattern>,
     /// Maximum allowed occurrences (used by ratchet rules).
     pub max_count: Option<usize>,
+    /// `"match"` (default), `"line"` or `"file"`: what counts toward `max_count` (used by ratchet rules).
+    pub count_by: Option<String>,
+    /// Whether overlapping matches each count (used by ratchet rules, default: false).
+    pub overlapping: bool,
+    /// Whether patterns match regardless of case (used by ratchet and banned-pattern rules).
+    pub case_insensitive: bool,
+    /// Whether patterns are searched for in the whole file rather than line by
+    /// line, so they can span lines (used by ratchet and banned-pattern rules).
+    pub multiline: bool,
+    /// Whether patterns only match whole words (used by ratchet and banned-pattern rules).
+    pub word_boundary: bool,
+    /// Whether matches inside comments are ignored (used by ratchet and banned-pattern rules).
+    pub skip_comments: bool,
+    /// Whether matches inside string literals are ignored (used by ratchet and banned-pattern rules).
+    pub skip_strings: bool,
     /// Banned package names (used by banned-import and banned-dependency rules).
     pub packages: Vec<String>,
     /// Whether `pattern` should be interpreted as a regex (default: false).
     pub regex: bool,
     /// Manifest filename to check (used by banned-dependency, defaults to `package.json`).
     pub manifest: Option<String>,
+    /// Glob patterns for files to include in this rule, besides `glob`.
+    pub include: Vec<String>,
     /// Glob patterns for files to exclude from this rule.
     pub exclude_glob: Vec<String>,
     /// Only run rule if file contains this string.
@@ -37,6 +271,80 @@ pub struct RuleConfig {
     pub required_files: Vec<String>,
     /// Condition pattern: only enforce required-pattern if this pattern is present.
     pub condition_pattern: Option<String>,
+    /// Text `--fix` adds at the top of files missing the pattern (used by required-pattern).
+    pub insert: Option<String>,
+    /// Maximum number of changed files in a diff (used by pr-size).
+    pub max_files: Option<usize>,
+    /// Maximum number of added/modified lines in a diff (used by pr-size),
+    /// or lines in a file (used by file-limits).
+    pub max_lines: Option<usize>,
+    /// Maximum file size in bytes (used by file-limits).
+    pub max_bytes: Option<usize>,
+    /// Maximum line length in characters (used by file-limits).
+    pub max_line_length: Option<usize>,
+    /// Required encoding, "utf-8" or "ascii" (used by file-encoding).
+    pub encoding: Option<String>,
+    /// Regexes of matched secrets to ignore (used by secrets).
+    pub allowlist: Vec<String>,
+    /// Bits per character from which a generic secret counts (used by secrets, default 3.5).
+    pub min_entropy: Option<f64>,
+    /// Tree-sitter query to match (used by syntax-query).
+    pub query: Option<String>,
+    /// `"javascript"`, `"typescript"` or `"tsx"` (used by syntax-query).
+    pub language: Option<String>,
+    /// Age in days at which a TODO becomes an error (used by todo-age).
+    pub max_age_days: Option<u64>,
+    /// Globs of critical files (used by risky-change).
+    pub critical_files: Vec<String>,
+    /// Share of a critical file's lines a diff may change, in percent (used by risky-change).
+    pub max_changed_percent: Option<u32>,
+    /// UTC time windows (`start..end`) during which protected paths are frozen; none freezes them
+    /// permanently (used by freeze-window).
+    pub freeze_windows: Vec<String>,
+    /// Commit-message marker that bypasses a freeze or acknowledges a risky change
+    /// (used by freeze-window and risky-change).
+    pub bypass_marker: Option<String>,
+    /// Globs of directories matching files must live in (used by file-structure).
+    pub allowed_dirs: Vec<String>,
+    /// Naming convention for file names, e.g. `"kebab-case"` (used by file-structure).
+    pub naming: Option<String>,
+    /// Keys the frontmatter must set (used by frontmatter).
+    pub required_keys: Vec<String>,
+    /// Values each key may take, by key (used by frontmatter).
+    pub allowed_values: std::collections::BTreeMap<String, Vec<String>>,
+    /// Keys the frontmatter must not set (used by frontmatter).
+    pub banned_keys: Vec<String>,
+    /// Values each key must not take, by key (used by frontmatter).
+    pub banned_values: std::collections::BTreeMap<String, Vec<String>>,
+    /// Named layers and the imports allowed between them (used by import-boundary).
+    pub layers: Vec<ImportLayer>,
+    /// Program and arguments to run (used by external-command).
+    pub command: Vec<String>,
+    /// Run `command` once over every matching file instead of once per file
+    /// (used by external-command).
+    pub batch: bool,
+    /// Seconds `command` may run before it's killed (used by external-command, default 30).
+    pub timeout_secs: Option<u64>,
+    /// `"error"` (default), `"warning"` or `"ignore"`: how a failed `command`
+    /// or trapping `module` is reported (used by external-command and wasm).
+    pub on_failure: Option<String>,
+    /// Path of the WebAssembly module implementing the rule (used by wasm).
+    pub module: Option<String>,
+    /// `"line"` (default) or `"file"`: where composite components must coincide;
+    /// `"repo"` (default) or `"new"`: which matches a ratchet counts.
+    pub scope: Option<String>,
+    /// Components that must all match, as `(rule type, config)` (used by composite).
+    pub all_of: Vec<(String, RuleConfig)>,
+    /// Components of which at least one must match (used by composite).
+    pub any_of: Vec<(String, RuleConfig)>,
+    /// Components that must not match (used by composite).
+    pub none_of: Vec<(String, RuleConfig)>,
+    /// Compile `pattern` regexes the first time they're searched instead of
+    /// when the rule is built; they're still parsed up front, so syntax errors
+    /// fail the build (used by ratchet and banned-pattern rules). Set by scans.
+    pub defer_regex: bool,
+    /// Compiled regexes shared with the other rules of the scan.
+    pub regex_pool: Option<std::sync::Arc<crate::rules::regex_pool::RegexPool>>,
 }
 
 impl Default for RuleConfig {
@@ -50,15 +358,57 @@ impl Default for RuleConfig {
             allowed_classes: Vec::new(),
             token_map: Vec::new(),
             pattern: None,
+            patterns: Vec::new(),
             max_count: None,
+            count_by: None,
+            overlapping: false,
+            case_insensitive: false,
+            multiline: false,
+            word_boundary: false,
+            skip_comments: false,
+            skip_strings: false,
             packages: Vec::new(),
             regex: false,
             manifest: None,
+            include: Vec::new(),
             exclude_glob: Vec::new(),
             file_contains: None,
             file_not_contains: None,
             required_files: Vec::new(),
             condition_pattern: None,
+            insert: None,
+            max_files: None,
+            max_lines: None,
+            max_bytes: None,
+            max_line_length: None,
+            encoding: None,
+            allowlist: Vec::new(),
+            min_entropy: None,
+            query: None,
+            language: None,
+            max_age_days: None,
+            critical_files: Vec::new(),
+            max_changed_percent: None,
+            freeze_windows: Vec::new(),
+            bypass_marker: None,
+            allowed_dirs: Vec::new(),
+            naming: None,
+            required_keys: Vec::new(),
+            allowed_values: std::collections::BTreeMap::new(),
+            banned_keys: Vec::new(),
+            banned_values: std::collections::BTreeMap::new(),
+            layers: Vec::new(),
+            command: Vec::new(),
+            batch: false,
+            timeout_secs: None,
+            on_failure: None,
+            module: None,
+            scope: None,
+            all_of: Vec::new(),
+            any_of: Vec::new(),
+            none_of: Vec::new(),
+            defer_regex: false,
+            regex_pool: None,
         }
     }
 }
@@ -0,0 +1,159 @@
+use crate::rules::Violation;
+use crate::scan::{self, ScanError};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+/// How a rule's presence changed between two configs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleStatus {
+    Added,
+    Removed,
+    Kept,
+}
+
+/// Violation counts for one rule under the old and new config.
+#[derive(Debug, Serialize)]
+pub struct RuleDelta {
+    pub rule_id: String,
+    pub status: RuleStatus,
+    pub before: usize,
+    pub after: usize,
+}
+
+impl RuleDelta {
+    /// Change in violation count (new minus old).
+    pub fn delta(&self) -> i64 {
+        self.after as i64 - self.before as i64
+    }
+}
+
+/// Preview of how a policy change would affect the violation set on a tree.
+#[derive(Debug, Serialize)]
+pub struct ConfigDiff {
+    /// Every rule in either config, sorted by id.
+    pub rules: Vec<RuleDelta>,
+    pub total_before: usize,
+    pub total_after: usize,
+    pub files_scanned: usize,
+}
+
+/// Scan `target_paths` under both configs and compare the results per rule.
+pub fn run_config_diff(
+    old_config: &Path,
+    new_config: &Path,
+    target_paths: &[PathBuf],
+) -> Result<ConfigDiff, ScanError> {
+    let old_ids = scan::rule_ids(old_config, None)?;
+    let new_ids = scan::rule_ids(new_config, None)?;
+
+    let before = scan::run_scan(old_config, target_paths)?;
+    let after = scan::run_scan(new_config, target_paths)?;
+
+    Ok(diff_results(
+        &old_ids,
+        &new_ids,
+        &before.violations,
+        &after.violations,
+        before.files_scanned.max(after.files_scanned),
+    ))
+}
+
+fn diff_results(
+    old_ids: &[String],
+    new_ids: &[String],
+    before: &[Violation],
+    after: &[Violation],
+    files_scanned: usize,
+) -> ConfigDiff {
+    let count = |violations: &[Violation]| {
+        let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+        for v in violations {
+            *counts.entry(v.rule_id.clone()).or_insert(0) += 1;
+        }
+        counts
+    };
+    let before_counts = count(before);
+    let after_counts = count(after);
+
+    let old_set: BTreeSet<&String> = old_ids.iter().collect();
+    let new_set: BTreeSet<&String> = new_ids.iter().collect();
+
+    let rules = old_set
+        .union(&new_set)
+        .map(|id| RuleDelta {
+            rule_id: (*id).clone(),
+            status: match (old_set.contains(id), new_set.contains(id)) {
+                (false, true) => RuleStatus::Added,
+                (true, false) => RuleStatus::Removed,
+                _ => RuleStatus::Kept,
+            },
+            before: before_counts.get(*id).copied().unwrap_or(0),
+            after: after_counts.get(*id).copied().unwrap_or(0),
+        })
+        .collect();
+
+    ConfigDiff {
+        rules,
+        total_before: before.len(),
+        total_after: after.len(),
+        files_scanned,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn classifies_added_removed_and_kept() {
+        let diff = diff_results(
+            &ids(&["kept", "gone"]),
+            &ids(&["kept", "new"]),
+            &[Violation::test("kept", "a.ts"), Violation::test("gone", "a.ts")],
+            &[Violation::test("kept", "a.ts"), Violation::test("kept", "a.ts"), Violation::test("new", "a.ts")],
+            3,
+        );
+
+        let by_id: BTreeMap<&str, &RuleDelta> =
+            diff.rules.iter().map(|r| (r.rule_id.as_str(), r)).collect();
+        assert_eq!(by_id["gone"].status, RuleStatus::Removed);
+        assert_eq!(by_id["gone"].delta(), -1);
+        assert_eq!(by_id["new"].status, RuleStatus::Added);
+        assert_eq!(by_id["new"].after, 1);
+        assert_eq!(by_id["kept"].status, RuleStatus::Kept);
+        assert_eq!(by_id["kept"].delta(), 1);
+        assert_eq!((diff.total_before, diff.total_after), (2, 3));
+    }
+
+    #[test]
+    fn run_config_diff_on_tree() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.ts"), "console.log(1);\nalert(2);\n").unwrap();
+
+        let old = dir.path().join("old.toml");
+        std::fs::write(
+            &old,
+            "[guardrails]\n[[rule]]\nid = \"no-console\"\ntype = \"banned-pattern\"\npattern = \"console.log\"\n",
+        )
+        .unwrap();
+        let new = dir.path().join("new.toml");
+        std::fs::write(
+            &new,
+            "[guardrails]\n[[rule]]\nid = \"no-alert\"\ntype = \"banned-pattern\"\npattern = \"alert(\"\nglob = \"**/*.ts\"\n",
+        )
+        .unwrap();
+
+        let diff = run_config_diff(&old, &new, &[dir.path().to_path_buf()]).unwrap();
+        assert_eq!(diff.rules.len(), 2);
+        assert_eq!(diff.rules[0].rule_id, "no-alert");
+        assert_eq!(diff.rules[0].status, RuleStatus::Added);
+        assert_eq!(diff.rules[0].after, 1);
+        assert_eq!(diff.rules[1].status, RuleStatus::Removed);
+    }
+}
@@ -0,0 +1,268 @@
+use crate::presets;
+use crate::scan::{self, ScanError, ScanOptions};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+/// A kind of config problem `guardrails doctor` looks for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Check {
+    /// Two of the config's own rules share an id.
+    DuplicateId,
+    /// A preset rule replaced by the same id in a later preset.
+    ShadowedPresetRule,
+    /// A content rule that can't match any scanned file.
+    UnreachableRule,
+    /// A ratchet whose `max_count` is already below the current count.
+    RatchetOverBudget,
+    /// Two rules searching for the same pattern.
+    DuplicatePattern,
+}
+
+impl Check {
+    pub fn label(self) -> &'static str {
+        match self {
+            Check::DuplicateId => "duplicate-id",
+            Check::ShadowedPresetRule => "shadowed-preset-rule",
+            Check::UnreachableRule => "unreachable-rule",
+            Check::RatchetOverBudget => "ratchet-over-budget",
+            Check::DuplicatePattern => "duplicate-pattern",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Finding {
+    pub check: Check,
+    pub rule_id: String,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Report {
+    pub findings: Vec<Finding>,
+    pub files_scanned: usize,
+    pub rules_checked: usize,
+}
+
+/// Lint the config at `config_path` against the files under `paths`.
+pub fn run_doctor(config_path: &Path, paths: &[PathBuf]) -> Result<Report, ScanError> {
+    let (toml_config, own_rules) = scan::load_config_rules(config_path, None)?;
+    let extends = &toml_config.guardrails.extends;
+    let base_dir = scan::config_dir(config_path);
+    let resolved = presets::resolve_rules(base_dir, extends, &own_rules, &toml_config.rule_overrides())
+        .map_err(ScanError::Preset)?;
+    let mut findings = Vec::new();
+
+    // Own rules with the same id: against a preset only the last one applies,
+    // otherwise both run and their violations can't be told apart.
+    let mut declared: BTreeMap<&str, usize> = BTreeMap::new();
+    for rule in &own_rules {
+        *declared.entry(rule.id.as_str()).or_insert(0) += 1;
+    }
+    for (id, &count) in &declared {
+        if count > 1 {
+            findings.push(Finding {
+                check: Check::DuplicateId,
+                rule_id: id.to_string(),
+                message: format!("declared {} times", count),
+            });
+        }
+    }
+
+    // Preset rules a later preset replaces
+    let mut provided_by: HashMap<String, &str> = HashMap::new();
+    for name in extends {
+        let rules = presets::resolve_rules(base_dir, std::slice::from_ref(name), &[], &[]).map_err(ScanError::Preset)?;
+        for rule in rules {
+            if let Some(earlier) = provided_by.insert(rule.id.clone(), name) {
+                findings.push(Finding {
+                    check: Check::ShadowedPresetRule,
+                    rule_id: rule.id,
+                    message: format!("preset \"{}\" is replaced by preset \"{}\"", earlier, name),
+                });
+            }
+        }
+    }
+
+    // Content rules whose globs leave nothing to scan
+    let mut seen_reach = HashMap::new();
+    for reach in scan::rule_reach(config_path, paths)? {
+        if seen_reach.insert(reach.rule_id.clone(), ()).is_some() {
+            continue;
+        }
+        let message = if reach.selected == 0 {
+            "its globs match no files"
+        } else if reach.scanned == 0 {
+            "every file its globs match is in [guardrails] exclude"
+        } else if reach.active == 0 {
+            "every file its globs match is a test file, where in_tests turns it off"
+        } else {
+            continue;
+        };
+        findings.push(Finding {
+            check: Check::UnreachableRule,
+            rule_id: reach.rule_id,
+            message: message.to_string(),
+        });
+    }
+
+    // Ratchets that fail on the current tree
+    let options = ScanOptions {
+        counts_only: true,
+        ..Default::default()
+    };
+    let result = scan::run_scan_with_options(config_path, paths, &options)?;
+    let mut over: Vec<_> = result
+        .ratchet_counts
+        .iter()
+        .filter(|(_, &(found, max))| found > max)
+        .collect();
+    over.sort();
+    for (rule_id, &(found, max)) in over {
+        findings.push(Finding {
+            check: Check::RatchetOverBudget,
+            rule_id: rule_id.clone(),
+            message: format!("max_count is {} but {} matches exist, so every scan fails", max, found),
+        });
+    }
+
+    // Rules searching for the same pattern
+    let mut first_with: HashMap<(&str, bool), &str> = HashMap::new();
+    for rule in &resolved {
+        let patterns = rule
+            .pattern
+            .iter()
+            .map(|p| (p, rule.regex))
+            .chain(rule.patterns.iter().map(|p| (&p.pattern, p.regex.unwrap_or(rule.regex))));
+        for (pattern, regex) in patterns {
+            match first_with.get(&(pattern.as_str(), regex)) {
+                Some(&first) if first != rule.id => findings.push(Finding {
+                    check: Check::DuplicatePattern,
+                    rule_id: rule.id.clone(),
+                    message: format!("pattern \"{}\" is also used by \"{}\"", pattern, first),
+                }),
+                Some(_) => {}
+                None => {
+                    first_with.insert((pattern.as_str(), regex), &rule.id);
+                }
+            }
+        }
+    }
+
+    Ok(Report {
+        findings,
+        files_scanned: result.files_scanned,
+        rules_checked: resolved.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn checks(report: &Report) -> Vec<(Check, &str)> {
+        report
+            .findings
+            .iter()
+            .map(|f| (f.check, f.rule_id.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn clean_config_has_no_findings() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("a.ts"), "console.log(1);\n").unwrap();
+        let config = dir.path().join("guardrails.toml");
+        fs::write(
+            &config,
+            r#"
+[guardrails]
+
+[[rule]]
+id = "no-console"
+type = "banned-pattern"
+pattern = "console.log("
+glob = "**/*.ts"
+"#,
+        )
+        .unwrap();
+
+        let report = run_doctor(&config, &[dir.path().to_path_buf()]).unwrap();
+        assert_eq!(checks(&report), vec![]);
+        assert_eq!(report.rules_checked, 1);
+    }
+
+    #[test]
+    fn finds_shadowed_unreachable_and_duplicate_rules() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("gen")).unwrap();
+        fs::write(dir.path().join("gen/a.ts"), "legacy();\n").unwrap();
+        fs::write(dir.path().join("a.test.ts"), "x\n").unwrap();
+        fs::write(dir.path().join("b.ts"), "legacy();\nlegacy();\n").unwrap();
+        let config = dir.path().join("guardrails.toml");
+        fs::write(
+            &config,
+            r#"
+[guardrails]
+exclude = ["**/gen/**"]
+test_globs = ["**/*.test.ts"]
+
+[[rule]]
+id = "no-legacy"
+type = "ratchet"
+pattern = "legacy("
+glob = "**/*.ts"
+max_count = 1
+
+[[rule]]
+id = "legacy-again"
+type = "banned-pattern"
+pattern = "legacy("
+glob = "**/*.ts"
+
+[[rule]]
+id = "no-py"
+type = "banned-pattern"
+pattern = "print("
+glob = "**/*.py"
+
+[[rule]]
+id = "gen-only"
+type = "banned-pattern"
+pattern = "TODO"
+glob = "**/gen/**"
+
+[[rule]]
+id = "tests-only"
+type = "banned-pattern"
+pattern = "FIXME"
+glob = "**/*.test.ts"
+in_tests = "off"
+
+[[rule]]
+id = "no-py"
+type = "banned-pattern"
+pattern = "exec("
+glob = "**/*.py"
+"#,
+        )
+        .unwrap();
+
+        let report = run_doctor(&config, &[dir.path().to_path_buf()]).unwrap();
+        assert_eq!(
+            checks(&report),
+            vec![
+                (Check::DuplicateId, "no-py"),
+                (Check::UnreachableRule, "no-py"),
+                (Check::UnreachableRule, "gen-only"),
+                (Check::UnreachableRule, "tests-only"),
+                (Check::RatchetOverBudget, "no-legacy"),
+                (Check::DuplicatePattern, "legacy-again"),
+            ]
+        );
+        assert!(report.findings[4].message.contains("max_count is 1 but 2"));
+    }
+}
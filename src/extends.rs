@@ -0,0 +1,488 @@
+use crate::cli::toml_config::{OverridesTable, RuleOverride, TomlRule};
+use crate::interpolate;
+use crate::presets;
+use crate::scan;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Where fetched configs are kept, relative to the config's directory, when
+/// there's no user cache directory.
+pub const CACHE_DIR: &str = ".guardrails-cache/extends";
+
+/// The lockfile next to a config, pinning the content hash of every remote
+/// config it extends.
+pub const LOCK_FILE: &str = "guardrails.lock";
+
+/// The config file an `org/repo@ref` entry stands for.
+const DEFAULT_REMOTE_PATH: &str = "guardrails.toml";
+
+/// One entry of `[guardrails] extends`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    /// A built-in preset, e.g. `"shadcn-strict"`.
+    Preset(String),
+    /// A shared config file, e.g. `"./shared/guardrails-base.toml"`.
+    File(PathBuf),
+    /// A config file in a GitHub repository at a pinned ref,
+    /// e.g. `"github:org/repo/path.toml@v1"` or `"org/repo@v1"`.
+    GitHub {
+        owner: String,
+        repo: String,
+        path: String,
+        git_ref: String,
+    },
+    /// A config file served over HTTPS, e.g. `"https://example.com/base.toml"`.
+    Url(String),
+}
+
+impl Source {
+    /// Parse `spec` as written in the config at `parent` (`None` for the
+    /// config being scanned, which lives in `base_dir`). Relative paths
+    /// resolve against the extending file: its directory, its directory in
+    /// the same repository and ref for a `github:` file, or its URL's
+    /// directory for a URL.
+    pub fn parse(spec: &str, parent: Option<&Source>, base_dir: &Path) -> Result<Self, String> {
+        if let Some(rest) = spec.strip_prefix("github:") {
+            let (location, git_ref) = rest
+                .rsplit_once('@')
+                .filter(|(_, r)| !r.is_empty())
+                .ok_or("pin a ref, e.g. github:org/repo/path.toml@v1")?;
+            let mut parts = location.splitn(3, '/');
+            let (Some(owner), Some(repo), Some(path)) = (parts.next(), parts.next(), parts.next()) else {
+                return Err("expected github:owner/repo/path.toml@ref".to_string());
+            };
+            return github(owner, repo, path, git_ref, "expected github:owner/repo/path.toml@ref");
+        }
+        if spec.starts_with("https://") {
+            return Ok(Source::Url(spec.to_string()));
+        }
+        if spec.starts_with("http://") {
+            return Err("fetch configs over https".to_string());
+        }
+        if let Some(source) = shorthand(spec) {
+            return source;
+        }
+
+        let is_path = spec.contains(['/', '\\']) || spec.ends_with(".toml");
+        match parent {
+            _ if !is_path => Ok(Source::Preset(spec.to_string())),
+            Some(Source::GitHub {
+                owner,
+                repo,
+                path,
+                git_ref,
+            }) => {
+                let dir = path.rsplit_once('/').map_or("", |(dir, _)| dir);
+                Ok(Source::GitHub {
+                    owner: owner.clone(),
+                    repo: repo.clone(),
+                    path: normalize(&format!("{}/{}", dir, spec))?,
+                    git_ref: git_ref.clone(),
+                })
+            }
+            Some(Source::Url(url)) => {
+                let (origin, path) = split_url(url);
+                let dir = path.rsplit_once('/').map_or("", |(dir, _)| dir);
+                Ok(Source::Url(format!("{}/{}", origin, normalize(&format!("{}/{}", dir, spec))?)))
+            }
+            Some(Source::File(file)) => Ok(Source::File(file.parent().unwrap_or(Path::new("")).join(spec))),
+            _ => Ok(Source::File(base_dir.join(spec))),
+        }
+    }
+
+    /// Identifies the source for cycle detection, in [`LOCK_FILE`], and as
+    /// the `source` of remote rules (which `trust` refers to).
+    pub fn key(&self) -> String {
+        match self {
+            Source::Preset(name) => name.clone(),
+            Source::File(path) => fs::canonicalize(path)
+                .unwrap_or_else(|_| path.clone())
+                .display()
+                .to_string(),
+            Source::GitHub {
+                owner,
+                repo,
+                path,
+                git_ref,
+            } => format!("github:{}/{}/{}@{}", owner, repo, path, git_ref),
+            Source::Url(url) => url.clone(),
+        }
+    }
+
+    /// Whether it's fetched over the network.
+    pub fn is_remote(&self) -> bool {
+        matches!(self, Source::GitHub { .. } | Source::Url(_))
+    }
+
+    fn url(&self) -> Option<String> {
+        match self {
+            Source::GitHub {
+                owner,
+                repo,
+                path,
+                git_ref,
+            } => Some(format!(
+                "https://raw.githubusercontent.com/{}/{}/{}/{}",
+                owner, repo, git_ref, path
+            )),
+            Source::Url(url) => Some(url.clone()),
+            _ => None,
+        }
+    }
+}
+
+fn github(owner: &str, repo: &str, path: &str, git_ref: &str, usage: &str) -> Result<Source, String> {
+    if owner.is_empty() || repo.is_empty() || git_ref.split('/').any(|c| c.is_empty() || c == "..") {
+        return Err(usage.to_string());
+    }
+    Ok(Source::GitHub {
+        owner: owner.to_string(),
+        repo: repo.to_string(),
+        path: normalize(path)?,
+        git_ref: git_ref.to_string(),
+    })
+}
+
+/// `org/repo@ref`, for the repository's `guardrails.toml`.
+fn shorthand(spec: &str) -> Option<Result<Source, String>> {
+    let (location, git_ref) = spec.split_once('@')?;
+    let (owner, repo) = location.split_once('/')?;
+    if spec.starts_with('.') || spec.ends_with(".toml") || repo.contains(['/', '\\']) {
+        return None;
+    }
+    Some(github(owner, repo, DEFAULT_REMOTE_PATH, git_ref, "expected org/repo@ref"))
+}
+
+/// `url` split into its scheme and host, and its path.
+fn split_url(url: &str) -> (&str, &str) {
+    let after_scheme = url.find("://").map_or(0, |p| p + 3);
+    match url[after_scheme..].find('/') {
+        Some(p) => url.split_at(after_scheme + p),
+        None => (url, ""),
+    }
+}
+
+/// Resolve `.` and `..` in a repository path, which must stay inside the repository.
+fn normalize(path: &str) -> Result<String, String> {
+    let mut parts: Vec<&str> = Vec::new();
+    for part in path.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop().ok_or_else(|| format!("'{}' leaves the repository", path))?;
+            }
+            part => parts.push(part),
+        }
+    }
+    if parts.is_empty() {
+        return Err("missing a file path".to_string());
+    }
+    Ok(parts.join("/"))
+}
+
+/// The parts of an extended config that are inherited: its rules, its
+/// overrides and what it extends itself. Other sections are ignored, so a
+/// whole `guardrails.toml` can be shared as-is.
+#[derive(Debug, Default, Deserialize)]
+pub struct SharedConfig {
+    #[serde(default)]
+    pub guardrails: SharedSection,
+    #[serde(default)]
+    pub rule: Vec<TomlRule>,
+    #[serde(default, rename = "override")]
+    pub overrides: Vec<RuleOverride>,
+    #[serde(default, rename = "overrides")]
+    pub overrides_table: OverridesTable,
+}
+
+impl SharedConfig {
+    /// Its `[overrides]` table and `[[override]]` entries.
+    pub fn rule_overrides(&self) -> Vec<RuleOverride> {
+        self.overrides_table.with(&self.overrides)
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct SharedSection {
+    #[serde(default)]
+    pub extends: Vec<String>,
+}
+
+/// Read and parse the config at `source`. Remote files are fetched into
+/// `cache` once, pinned in the [`LOCK_FILE`] next to the config in
+/// `base_dir`, and read from the cache afterwards.
+pub fn load(source: &Source, base_dir: &Path, cache: &Path) -> Result<SharedConfig, String> {
+    let text = match source {
+        Source::Preset(name) => return Err(format!("'{}' is a preset, not a file", name)),
+        Source::File(path) => fs::read_to_string(path).map_err(|e| format!("failed to read {}: {}", path.display(), e))?,
+        remote => fetch_locked(remote, base_dir, cache)?,
+    };
+    let name = || match source {
+        Source::File(path) => path.display().to_string(),
+        remote => remote.key(),
+    };
+    // Variables are those of the extending config, so a shared config can
+    // name paths that differ per repo
+    let text = interpolate::interpolate(&text, base_dir).map_err(|e| format!("{}: {}", name(), e))?;
+    toml::from_str(&text).map_err(|e| format!("failed to parse {}: {}", name(), e))
+}
+
+/// The directory fetched configs are cached in: `guardrails` in the user's
+/// cache directory, shared by every checkout, or [`CACHE_DIR`] under
+/// `base_dir` when there's none.
+pub fn cache_dir(base_dir: &Path) -> PathBuf {
+    let var = |name: &str| std::env::var_os(name).filter(|v| !v.is_empty()).map(PathBuf::from);
+    let user = var("XDG_CACHE_HOME")
+        .or_else(|| var("HOME").map(|home| home.join(".cache")))
+        .or_else(|| var("LOCALAPPDATA"));
+    match user {
+        Some(dir) => dir.join("guardrails"),
+        None => base_dir.join(CACHE_DIR),
+    }
+}
+
+/// The spec of `--config`, if it names a remote config rather than a file:
+/// an HTTPS URL, a `github:` file or `org/repo@ref`.
+pub fn remote_config(spec: &str) -> Option<Result<Source, String>> {
+    let remote = spec.starts_with("https://") || spec.starts_with("github:") || shorthand(spec).is_some();
+    remote.then(|| Source::parse(spec, None, Path::new(".")))
+}
+
+/// The text of the remote config at `source`, as [`load`] gets it.
+pub fn fetch_config(source: &Source, base_dir: &Path) -> Result<String, String> {
+    fetch_locked(source, base_dir, &cache_dir(base_dir))
+}
+
+/// `sha256:` and the hex SHA-256 of `text`, as [`LOCK_FILE`] pins it.
+pub fn digest(text: &str) -> String {
+    let hash = Sha256::digest(text.as_bytes());
+    format!("sha256:{}", hash.iter().map(|b| format!("{:02x}", b)).collect::<String>())
+}
+
+/// The text of a remote `source`, checked against the hash the lockfile in
+/// `base_dir` pins for it. A source that isn't pinned yet is pinned to what
+/// was fetched.
+fn fetch_locked(source: &Source, base_dir: &Path, cache: &Path) -> Result<String, String> {
+    let key = source.key();
+    let url = source.url().ok_or_else(|| format!("'{}' isn't remote", key))?;
+    let lock_path = base_dir.join(LOCK_FILE);
+    let mut pins = read_lock(&lock_path)?;
+    if let Some(pinned) = pins.get(&key) {
+        let cached = cache.join(cache_name(pinned));
+        if let Ok(text) = fs::read_to_string(&cached) {
+            if digest(&text) == *pinned {
+                return Ok(text);
+            }
+        }
+        let text = fetch(&url)?;
+        if digest(&text) != *pinned {
+            return Err(format!(
+                "{} no longer matches the hash in {}; run `guardrails update-config` to accept the change",
+                url, LOCK_FILE
+            ));
+        }
+        store(cache, &text)?;
+        return Ok(text);
+    }
+
+    let text = fetch(&url)?;
+    store(cache, &text)?;
+    pins.insert(key, digest(&text));
+    write_lock(&lock_path, &pins)?;
+    Ok(text)
+}
+
+/// The cache file holding the text with `digest`.
+fn cache_name(digest: &str) -> String {
+    format!("{}.toml", digest.strip_prefix("sha256:").unwrap_or(digest))
+}
+
+/// Save `text` in `cache` under its hash, through a temporary file so a
+/// failed write never leaves a partial config behind.
+fn store(cache: &Path, text: &str) -> Result<(), String> {
+    fs::create_dir_all(cache).map_err(|e| format!("failed to create {}: {}", cache.display(), e))?;
+    let dest = cache.join(cache_name(&digest(text)));
+    let mut tmp = dest.clone();
+    tmp.as_mut_os_string().push(format!(".{}.tmp", std::process::id()));
+    fs::write(&tmp, text)
+        .and_then(|_| fs::rename(&tmp, &dest))
+        .map_err(|e| format!("failed to write {}: {}", dest.display(), e))
+}
+
+/// Download `url` with curl.
+fn fetch(url: &str) -> Result<String, String> {
+    let output = Command::new("curl")
+        .args(["--silent", "--show-error", "--fail", "--location"])
+        .arg(url)
+        .output()
+        .map_err(|_| "curl is not installed or not in PATH".to_string())?;
+    if !output.status.success() {
+        return Err(format!(
+            "failed to fetch {}: {}",
+            url,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    String::from_utf8(output.stdout).map_err(|_| format!("{} is not UTF-8 text", url))
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Lockfile {
+    #[serde(default)]
+    sources: BTreeMap<String, String>,
+}
+
+/// The hashes the lockfile at `path` pins, by source key; none if it doesn't exist.
+fn read_lock(path: &Path) -> Result<BTreeMap<String, String>, String> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(BTreeMap::new()),
+        Err(e) => return Err(format!("failed to read {}: {}", path.display(), e)),
+    };
+    let lock: Lockfile = toml::from_str(&text).map_err(|e| format!("failed to parse {}: {}", path.display(), e))?;
+    Ok(lock.sources)
+}
+
+/// Write `pins` to the lockfile at `path`, or remove it when there are none.
+fn write_lock(path: &Path, pins: &BTreeMap<String, String>) -> Result<(), String> {
+    if pins.is_empty() {
+        return match fs::remove_file(path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(format!("failed to remove {}: {}", path.display(), e))
+            }
+            _ => Ok(()),
+        };
+    }
+    let lock = Lockfile { sources: pins.clone() };
+    let body = toml::to_string(&lock).map_err(|e| e.to_string())?;
+    let text = format!(
+        "# Content hashes of the remote configs in `extends`.\n# Refresh with `guardrails update-config`.\n\n{}",
+        body
+    );
+    fs::write(path, text).map_err(|e| format!("failed to write {}: {}", path.display(), e))
+}
+
+/// A source whose pin `guardrails update-config` added, changed or dropped.
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct LockChange {
+    pub source: String,
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+/// Fetch every remote config the config at `config_path` extends again and
+/// rewrite its lockfile with their current hashes (`guardrails
+/// update-config`). The old lockfile is kept if anything fails.
+pub fn update_lock(config_path: &Path) -> Result<Vec<LockChange>, String> {
+    let base_dir = scan::config_dir(config_path);
+    let lock_path = base_dir.join(LOCK_FILE);
+    let old = read_lock(&lock_path)?;
+    let (toml_config, own_rules) = scan::load_config_rules(config_path, None).map_err(|e| e.to_string())?;
+
+    write_lock(&lock_path, &BTreeMap::new())?;
+    let resolved = presets::resolve_rules(
+        base_dir,
+        &toml_config.guardrails.extends,
+        &own_rules,
+        &toml_config.rule_overrides(),
+    );
+    if let Err(e) = resolved {
+        write_lock(&lock_path, &old)?;
+        return Err(e.to_string());
+    }
+    let new = read_lock(&lock_path)?;
+
+    let mut sources: Vec<&String> = old.keys().chain(new.keys()).collect();
+    sources.sort();
+    sources.dedup();
+    Ok(sources
+        .into_iter()
+        .filter(|source| old.get(*source) != new.get(*source))
+        .map(|source| LockChange {
+            source: source.clone(),
+            from: old.get(source).cloned(),
+            to: new.get(source).cloned(),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_presets_files_and_github_specs() {
+        let base = Path::new("/repo");
+        assert_eq!(Source::parse("ai-safety", None, base), Ok(Source::Preset("ai-safety".into())));
+        assert_eq!(
+            Source::parse("./shared/base.toml", None, base),
+            Ok(Source::File(PathBuf::from("/repo/./shared/base.toml")))
+        );
+        let remote = Source::parse("github:org/policies/web/base.toml@v1", None, base).unwrap();
+        assert_eq!(remote.key(), "github:org/policies/web/base.toml@v1");
+
+        // Relative to the extending file
+        let nested = Source::parse("../common.toml", Some(&remote), base).unwrap();
+        assert_eq!(nested.key(), "github:org/policies/common.toml@v1");
+        let file = Source::File(PathBuf::from("/repo/shared/base.toml"));
+        assert_eq!(
+            Source::parse("more.toml", Some(&file), base),
+            Ok(Source::File(PathBuf::from("/repo/shared/more.toml")))
+        );
+    }
+
+    #[test]
+    fn rejects_unpinned_and_escaping_github_specs() {
+        let base = Path::new(".");
+        assert!(Source::parse("github:org/repo/base.toml", None, base).unwrap_err().contains("pin a ref"));
+        assert!(Source::parse("github:org/repo@v1", None, base).is_err());
+        assert!(Source::parse("github:org/repo/../x.toml@v1", None, base).is_err());
+        assert!(Source::parse("github:org/repo/x.toml@../v1", None, base).is_err());
+    }
+
+    #[test]
+    fn parses_urls_and_repo_shorthands() {
+        let base = Path::new(".");
+        let url = Source::parse("https://example.com/policies/web/base.toml", None, base).unwrap();
+        assert_eq!(
+            Source::parse("../common.toml", Some(&url), base),
+            Ok(Source::Url("https://example.com/policies/common.toml".into()))
+        );
+        assert!(Source::parse("http://example.com/base.toml", None, base).is_err());
+
+        let repo = Source::parse("org/policies@v2", None, base).unwrap();
+        assert_eq!(repo.key(), "github:org/policies/guardrails.toml@v2");
+        assert!(repo.is_remote());
+        assert_eq!(
+            Source::parse("shared/base.toml", None, base),
+            Ok(Source::File(PathBuf::from("./shared/base.toml")))
+        );
+        assert!(remote_config("org/policies@v2").is_some());
+        assert!(remote_config("configs/guardrails.toml").is_none());
+    }
+
+    #[test]
+    fn remote_files_are_read_from_the_cache_by_their_pinned_hash() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = dir.path().join("cache");
+        let text = "[guardrails]\nextends = [\"ai-safety\"]\n\n[[rule]]\nid = \"no-todo\"\ntype = \"banned-pattern\"\npattern = \"TODO\"\n";
+        store(&cache, text).unwrap();
+        let source = Source::parse("github:org/policies/base.toml@v1", None, dir.path()).unwrap();
+        let pins = BTreeMap::from([(source.key(), digest(text))]);
+        write_lock(&dir.path().join(LOCK_FILE), &pins).unwrap();
+        assert_eq!(read_lock(&dir.path().join(LOCK_FILE)).unwrap(), pins);
+
+        let shared = load(&source, dir.path(), &cache).unwrap();
+        assert_eq!(shared.guardrails.extends, ["ai-safety"]);
+        assert_eq!(shared.rule[0].id, "no-todo");
+
+        // An empty lockfile is removed
+        write_lock(&dir.path().join(LOCK_FILE), &BTreeMap::new()).unwrap();
+        assert!(!dir.path().join(LOCK_FILE).exists());
+    }
+}
@@ -0,0 +1,78 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+/// File types the built-in rules understand, named by their usual extension.
+pub const FILE_TYPES: &[&str] = &[
+    "js", "jsx", "mjs", "cjs", "ts", "tsx", "mts", "cts", "html", "htm", "vue", "svelte", "css",
+    "scss", "less", "py", "pyi", "rs", "go", "java", "kt", "swift", "c", "h", "cc", "cpp", "hpp",
+    "cs", "rb", "sh", "bash", "yml", "yaml", "toml", "json", "md", "xml",
+];
+
+/// Resolves a file's type using the `[filetypes]` config section, which maps
+/// nonstandard extensions to a known type (`mjsx = "jsx"`, `gotmpl = "html"`).
+#[derive(Debug, Default, Clone)]
+pub struct FileTypes {
+    aliases: HashMap<String, String>,
+}
+
+impl FileTypes {
+    pub fn new(section: &BTreeMap<String, String>) -> Result<Self, String> {
+        let mut aliases = HashMap::new();
+        for (extension, file_type) in section {
+            let extension = extension.trim_start_matches('.');
+            if extension.is_empty() || extension.contains(['.', '/', '*']) {
+                return Err(format!(
+                    "\"{}\" is not a file extension (write it like \"mjsx\" or \".mjsx\")",
+                    extension
+                ));
+            }
+            let file_type = file_type.trim_start_matches('.');
+            if !FILE_TYPES.contains(&file_type) {
+                return Err(format!(
+                    "unknown file type \"{}\" for \"{}\" (expected one of: {})",
+                    file_type,
+                    extension,
+                    FILE_TYPES.join(", ")
+                ));
+            }
+            aliases.insert(extension.to_string(), file_type.to_string());
+        }
+        Ok(Self { aliases })
+    }
+
+    /// The type of the file at `path`: what `[filetypes]` maps its extension
+    /// to, or else the extension itself.
+    pub fn file_type<'a>(&'a self, path: &'a Path) -> Option<&'a str> {
+        let extension = path.extension()?.to_str()?;
+        Some(self.aliases.get(extension).map_or(extension, String::as_str))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file_types(entries: &[(&str, &str)]) -> Result<FileTypes, String> {
+        let section = entries
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        FileTypes::new(&section)
+    }
+
+    #[test]
+    fn maps_custom_extensions() {
+        let types = file_types(&[("mjsx", "jsx"), (".gotmpl", ".html")]).unwrap();
+        assert_eq!(types.file_type(Path::new("src/App.mjsx")), Some("jsx"));
+        assert_eq!(types.file_type(Path::new("templates/page.gotmpl")), Some("html"));
+        assert_eq!(types.file_type(Path::new("src/a.ts")), Some("ts"));
+        assert_eq!(types.file_type(Path::new("Makefile")), None);
+    }
+
+    #[test]
+    fn rejects_unknown_types_and_bad_extensions() {
+        assert!(file_types(&[("mjsx", "jsxx")]).unwrap_err().contains("unknown file type \"jsxx\""));
+        assert!(file_types(&[("d.ts", "ts")]).is_err());
+        assert!(file_types(&[("", "ts")]).is_err());
+    }
+}
@@ -0,0 +1,121 @@
+use crate::rules::Violation;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Version of the fingerprint algorithm, also the SARIF `partialFingerprints` key suffix.
+pub const FINGERPRINT_VERSION: &str = "v1";
+
+/// The fingerprint of one violation: 16 lowercase hex digits that identify
+/// "the same violation" across scans, commits and machines. It hashes the
+/// rule id, the file path and the normalized source line but not the line or
+/// column, so edits that only shift code around keep it. SARIF
+/// `partialFingerprints`, JSON output and anything else that compares runs
+/// use this function, so they always agree.
+///
+/// Stability: for a given `FINGERPRINT_VERSION` the result depends only on
+/// those inputs — not on the guardrails or Rust version, the platform or the
+/// scan order. Any change to the algorithm comes with a new version.
+///
+/// The path is used as given apart from `\` becoming `/` and a leading `./`
+/// being dropped, so scan with repo-relative paths for fingerprints that
+/// match across checkouts. The source line is trimmed and runs of whitespace
+/// collapse to one space; file-level violations have no context.
+pub fn fingerprint(v: &Violation) -> String {
+    let mut hash = Fnv1a::new();
+    hash.write(FINGERPRINT_VERSION.as_bytes());
+    hash.write(&[0]);
+    hash.write(v.rule_id.as_bytes());
+    hash.write(&[0]);
+    hash.write(normalize_path(&v.file).as_bytes());
+    hash.write(&[0]);
+    if let Some(ref line) = v.source_line {
+        hash.write(normalize_context(line).as_bytes());
+    }
+    format!("{:016x}", hash.finish())
+}
+
+/// Fingerprints for a list of violations, in the same order. Identical
+/// violations (same rule, file and normalized line) get `:1`, `:2`, … suffixes
+/// after the first, in line order, so every fingerprint in a result is unique.
+pub fn fingerprints(violations: &[Violation]) -> Vec<String> {
+    let base: Vec<String> = violations.iter().map(fingerprint).collect();
+
+    let mut order: Vec<usize> = (0..violations.len()).collect();
+    order.sort_by_key(|&i| (&base[i], violations[i].line, violations[i].column, i));
+
+    let mut seen: HashMap<&str, usize> = HashMap::new();
+    let mut out = base.clone();
+    for i in order {
+        let n = seen.entry(base[i].as_str()).or_insert(0);
+        if *n > 0 {
+            out[i] = format!("{}:{}", base[i], n);
+        }
+        *n += 1;
+    }
+    out
+}
+
+fn normalize_path(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    path.strip_prefix("./").unwrap_or(&path).to_string()
+}
+
+fn normalize_context(line: &str) -> String {
+    line.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// 64-bit FNV-1a: tiny, fast and fixed by its spec, unlike `DefaultHasher`.
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    fn new() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.0 ^= u64::from(b);
+            self.0 = self.0.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fingerprint_is_pinned() {
+        // Changing this value breaks every stored baseline; bump FINGERPRINT_VERSION instead.
+        let v = Violation::test("no-console", "src/a.ts").at(3, "console.log(x);");
+        assert_eq!(fingerprint(&v), "bd3a64fbd35fa3a1");
+    }
+
+    #[test]
+    fn fingerprint_ignores_position_and_spacing() {
+        let a = Violation::test("no-console", "./src/a.ts").at(3, "  console.log(x);");
+        let b = Violation::test("no-console", "src/a.ts").at(40, "console.log(x);\t");
+        let c = Violation::test("no-console", "src\\a.ts").at(7, "console.log(x);");
+        assert_eq!(fingerprint(&a), fingerprint(&b));
+        assert_eq!(fingerprint(&a), fingerprint(&c));
+        assert_ne!(fingerprint(&a), fingerprint(&Violation::test("no-console", "src/a.ts").at(3, "console.log(y);")));
+        assert_ne!(fingerprint(&a), fingerprint(&Violation::test("no-console", "src/b.ts").at(3, "console.log(x);")));
+    }
+
+    #[test]
+    fn fingerprints_disambiguate_duplicates_in_line_order() {
+        let vs = vec![
+            Violation::test("no-console", "a.ts").at(9, "console.log(x);"),
+            Violation::test("no-console", "a.ts").at(2, "console.log(x);"),
+            Violation::test("no-console", "a.ts").at(5, "console.log(y);"),
+        ];
+        let fps = fingerprints(&vs);
+        assert_eq!(fps[1], fingerprint(&vs[1]));
+        assert_eq!(fps[0], format!("{}:1", fingerprint(&vs[0])));
+        assert_eq!(fps[2], fingerprint(&vs[2]));
+    }
+}
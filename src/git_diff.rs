@@ -1,7 +1,8 @@
-use std::collections::HashMap;
+use crate::line_endings;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
 use std::ops::RangeInclusive;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 #[derive(Debug)]
@@ -28,10 +29,19 @@ impl fmt::Display for GitDiffError {
 impl std::error::Error for GitDiffError {}
 
 /// Changed files and line ranges from a git diff.
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct DiffInfo {
     /// Map of relative file path to list of changed line ranges.
     pub changed_lines: HashMap<PathBuf, Vec<RangeInclusive<usize>>>,
+    /// Files the diff creates, including rename targets.
+    pub added_files: HashSet<PathBuf>,
+    /// Full messages of the commits in `base..HEAD`, newest first.
+    pub commit_messages: Vec<String>,
+    /// Description of the pull/merge request being checked, when CI provides it.
+    pub pr_description: Option<String>,
+    /// Whether this is the diff of the index (`scan --staged`), whose files
+    /// are scanned as staged rather than as in the working tree.
+    pub staged: bool,
 }
 
 impl DiffInfo {
@@ -39,6 +49,19 @@ impl DiffInfo {
         self.changed_lines.contains_key(path)
     }
 
+    /// Whether the diff creates the file, as opposed to modifying it.
+    pub fn is_added(&self, path: &Path) -> bool {
+        self.added_files.contains(path)
+    }
+
+    /// Number of added or modified lines in a file.
+    pub fn changed_line_count(&self, path: &PathBuf) -> usize {
+        match self.changed_lines.get(path) {
+            Some(ranges) => ranges.iter().map(|r| r.end() - r.start() + 1).sum(),
+            None => 0,
+        }
+    }
+
     /// Check if a specific line in a file is within a changed range.
     pub fn has_line(&self, path: &PathBuf, line: usize) -> bool {
         match self.changed_lines.get(path) {
@@ -86,16 +109,45 @@ pub fn repo_root() -> Result<PathBuf, GitDiffError> {
     Ok(PathBuf::from(root))
 }
 
+/// Short hash of `HEAD`, or `None` outside a git repository.
+pub fn head_commit() -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())?;
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Base ref standing for the changes staged for the next commit
+/// (`scan --staged`): the index against `HEAD`. No real ref contains a `:`.
+pub const STAGED: &str = ":staged";
+
 /// Parse a git diff to extract changed files and their changed line ranges.
 ///
-/// Uses triple-dot diff (`base...HEAD`) for correct merge-base comparison.
+/// Uses triple-dot diff (`base...HEAD`) for correct merge-base comparison,
+/// or `git diff --cached` for [`STAGED`].
 /// Only includes Added, Copied, Modified, Renamed files (`--diff-filter=ACMR`).
+/// A base ref missing from a shallow clone is fetched first.
 pub fn diff_info(base_ref: &str) -> Result<DiffInfo, GitDiffError> {
+    diff_info_fetching(base_ref, true)
+}
+
+/// [`diff_info`], without fetching a base ref that isn't available locally.
+pub fn local_diff_info(base_ref: &str) -> Result<DiffInfo, GitDiffError> {
+    diff_info_fetching(base_ref, false)
+}
+
+fn diff_info_fetching(base_ref: &str, fetch: bool) -> Result<DiffInfo, GitDiffError> {
     // Ensure we're in a git repo
     repo_root()?;
 
+    if base_ref == STAGED {
+        return staged_diff_info();
+    }
+
     // Try the base ref directly, then with origin/ prefix
-    let effective_base = resolve_base_ref(base_ref)?;
+    let effective_base = resolve_base_ref(base_ref, fetch)?;
 
     let output = Command::new("git")
         .args([
@@ -113,12 +165,117 @@ pub fn diff_info(base_ref: &str) -> Result<DiffInfo, GitDiffError> {
     }
 
     let diff_text = String::from_utf8_lossy(&output.stdout);
-    Ok(parse_diff(&diff_text))
+    let mut info = parse_diff(&diff_text);
+    info.commit_messages = commit_messages(&effective_base);
+    info.pr_description = pr_description();
+    Ok(info)
+}
+
+/// The changes staged in the index. There are no commits yet, so no
+/// commit messages or pull request description.
+fn staged_diff_info() -> Result<DiffInfo, GitDiffError> {
+    let output = Command::new("git")
+        .args(["diff", "--cached", "-U0", "--diff-filter=ACMR"])
+        .output()
+        .map_err(|_| GitDiffError::GitNotFound)?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+        return Err(GitDiffError::CommandFailed(stderr));
+    }
+
+    Ok(DiffInfo {
+        staged: true,
+        ..parse_diff(&String::from_utf8_lossy(&output.stdout))
+    })
+}
+
+/// The pull/merge request description from `GUARDRAILS_PR_DESCRIPTION`,
+/// GitLab's `CI_MERGE_REQUEST_DESCRIPTION` or the GitHub Actions event payload.
+fn pr_description() -> Option<String> {
+    let env = |name: &str| std::env::var(name).ok().filter(|v| !v.is_empty());
+    if let Some(description) = env("GUARDRAILS_PR_DESCRIPTION").or_else(|| env("CI_MERGE_REQUEST_DESCRIPTION")) {
+        return Some(description);
+    }
+    let event = std::fs::read_to_string(env("GITHUB_EVENT_PATH")?).ok()?;
+    github_pr_body(&event)
+}
+
+/// `pull_request.body` of a GitHub Actions event payload.
+fn github_pr_body(event: &str) -> Option<String> {
+    let event: serde_json::Value = serde_json::from_str(event).ok()?;
+    let body = event.get("pull_request")?.get("body")?.as_str()?;
+    (!body.is_empty()).then(|| body.to_string())
+}
+
+/// The commit `base...HEAD` diffs against: the merge base of the base ref and
+/// `HEAD`, or `HEAD` itself for staged changes.
+pub fn merge_base(base_ref: &str) -> Result<String, GitDiffError> {
+    if base_ref == STAGED {
+        return Ok("HEAD".to_string());
+    }
+    let effective_base = resolve_base_ref(base_ref, true)?;
+    let output = Command::new("git")
+        .args(["merge-base", &effective_base, "HEAD"])
+        .output()
+        .map_err(|_| GitDiffError::GitNotFound)?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+        return Err(GitDiffError::CommandFailed(stderr));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Content of a repo-relative file at a commit, or `None` if it didn't exist there.
+pub fn file_at(rev: &str, path: &Path) -> Option<String> {
+    let spec = format!("{}:{}", rev, path.to_string_lossy().replace('\\', "/"));
+    let output = Command::new("git").args(["show", &spec]).output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Content of a file of the repository at `repo_root`, by its repo-relative
+/// path, as staged in the index, or `None` if it isn't in there.
+pub fn staged_file(repo_root: &Path, path: &Path) -> Option<Vec<u8>> {
+    let spec = format!(":{}", path.to_string_lossy().replace('\\', "/"));
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo_root)
+        .args(["show", &spec])
+        .output()
+        .ok()?;
+    output.status.success().then_some(output.stdout)
+}
+
+/// Collect full commit messages for `base..HEAD`. Best-effort: returns an
+/// empty list if git log fails (e.g. in a shallow clone without the base).
+fn commit_messages(base_ref: &str) -> Vec<String> {
+    let output = match Command::new("git")
+        .args(["log", "--format=%B%x00", &format!("{}..HEAD", base_ref)])
+        .output()
+    {
+        Ok(o) if o.status.success() => o,
+        _ => return Vec::new(),
+    };
+
+    parse_commit_messages(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Split NUL-terminated `git log --format=%B%x00` output into messages.
+fn parse_commit_messages(log_text: &str) -> Vec<String> {
+    log_text
+        .split('\0')
+        .map(|m| m.trim())
+        .filter(|m| !m.is_empty())
+        .map(|m| m.to_string())
+        .collect()
 }
 
 /// Resolve a base ref, trying the ref directly then with origin/ prefix.
-/// For shallow clones, attempts a fetch first.
-fn resolve_base_ref(base_ref: &str) -> Result<String, GitDiffError> {
+/// For shallow clones, attempts a fetch first when `fetch` is set.
+fn resolve_base_ref(base_ref: &str, fetch: bool) -> Result<String, GitDiffError> {
     // Try the ref directly
     if ref_exists(base_ref) {
         return Ok(base_ref.to_string());
@@ -130,6 +287,10 @@ fn resolve_base_ref(base_ref: &str) -> Result<String, GitDiffError> {
         return Ok(with_origin);
     }
 
+    if !fetch {
+        return Err(GitDiffError::BaseRefNotFound(base_ref.to_string()));
+    }
+
     // Attempt shallow fetch and retry
     let _ = Command::new("git")
         .args(["fetch", "--depth=1", "origin", base_ref])
@@ -157,15 +318,35 @@ fn ref_exists(r: &str) -> bool {
 /// Parse unified diff output into a DiffInfo.
 fn parse_diff(diff_text: &str) -> DiffInfo {
     let mut changed_lines: HashMap<PathBuf, Vec<RangeInclusive<usize>>> = HashMap::new();
+    let mut added_files: HashSet<PathBuf> = HashSet::new();
     let mut current_file: Option<PathBuf> = None;
+    let mut new_file = false;
+
+    for line in line_endings::lines(diff_text) {
+        if line.starts_with("diff --git ") {
+            new_file = false;
+            continue;
+        }
+        if line == "--- /dev/null" {
+            new_file = true;
+            continue;
+        }
+        // A pure rename has no +++ line
+        if let Some(path) = line.strip_prefix("rename to ") {
+            changed_lines.entry(PathBuf::from(path)).or_default();
+            added_files.insert(PathBuf::from(path));
+            continue;
+        }
 
-    for line in diff_text.lines() {
         // Detect file path from +++ line
         if let Some(path) = line.strip_prefix("+++ b/") {
             current_file = Some(PathBuf::from(path));
             changed_lines
                 .entry(PathBuf::from(path))
-                .or_insert_with(Vec::new);
+                .or_default();
+            if new_file {
+                added_files.insert(PathBuf::from(path));
+            }
             continue;
         }
 
@@ -179,7 +360,11 @@ fn parse_diff(diff_text: &str) -> DiffInfo {
         }
     }
 
-    DiffInfo { changed_lines }
+    DiffInfo {
+        changed_lines,
+        added_files,
+        ..Default::default()
+    }
 }
 
 /// Parse a hunk header like `@@ -10,3 +15,4 @@` and return the new-side line range.
@@ -194,7 +379,7 @@ fn parse_hunk_header(line: &str) -> Option<RangeInclusive<usize>> {
 
     // Find the end of the numbers (next space or @@)
     let end = after_plus
-        .find(|c: char| c == ' ' || c == '@')
+        .find([' ', '@'])
         .unwrap_or(after_plus.len());
     let range_str = &after_plus[..end];
 
@@ -271,6 +456,40 @@ new file mode 100644
         assert_eq!(bar_ranges[0], 1..=10);
     }
 
+    #[test]
+    fn parse_diff_records_added_and_renamed_files() {
+        let diff = "\
+diff --git a/src/foo.rs b/src/foo.rs
+--- a/src/foo.rs
++++ b/src/foo.rs
+@@ -1 +1 @@
+-old
++new
+diff --git a/src/bar.rs b/src/bar.rs
+new file mode 100644
+--- /dev/null
++++ b/src/bar.rs
+@@ -0,0 +1 @@
++new
+diff --git a/src/old.rs b/src/moved.rs
+similarity index 100%
+rename from src/old.rs
+rename to src/moved.rs
+";
+        let info = parse_diff(diff);
+        assert!(!info.is_added(Path::new("src/foo.rs")));
+        assert!(info.is_added(Path::new("src/bar.rs")));
+        assert!(info.is_added(Path::new("src/moved.rs")));
+        assert!(info.has_file(&PathBuf::from("src/moved.rs")));
+    }
+
+    #[test]
+    fn parse_diff_crlf() {
+        let diff = "diff --git a/src/foo.rs b/src/foo.rs\r\n--- a/src/foo.rs\r\n+++ b/src/foo.rs\r\n@@ -1,3 +1,5 @@\r\n+new\r\n";
+        let info = parse_diff(diff);
+        assert_eq!(info.changed_lines[&PathBuf::from("src/foo.rs")], vec![1..=5]);
+    }
+
     #[test]
     fn diff_info_has_file_and_line() {
         let mut changed_lines = HashMap::new();
@@ -278,7 +497,10 @@ new file mode 100644
             PathBuf::from("src/main.rs"),
             vec![5..=10, 20..=25],
         );
-        let info = DiffInfo { changed_lines };
+        let info = DiffInfo {
+            changed_lines,
+            ..Default::default()
+        };
 
         assert!(info.has_file(&PathBuf::from("src/main.rs")));
         assert!(!info.has_file(&PathBuf::from("src/other.rs")));
@@ -288,6 +510,36 @@ new file mode 100644
         assert!(!info.has_line(&PathBuf::from("src/main.rs"), 15));
     }
 
+    #[test]
+    fn diff_info_changed_line_count() {
+        let mut changed_lines = HashMap::new();
+        changed_lines.insert(PathBuf::from("src/main.rs"), vec![5..=10, 20..=20]);
+        let info = DiffInfo {
+            changed_lines,
+            ..Default::default()
+        };
+
+        assert_eq!(info.changed_line_count(&PathBuf::from("src/main.rs")), 7);
+        assert_eq!(info.changed_line_count(&PathBuf::from("src/other.rs")), 0);
+    }
+
+    #[test]
+    fn parse_commit_messages_splits_on_nul() {
+        let log = "feat: add thing\n\nbody [freeze-bypass]\n\0\nfix: other\n\0\n";
+        let messages = parse_commit_messages(log);
+        assert_eq!(messages.len(), 2);
+        assert!(messages[0].contains("[freeze-bypass]"));
+        assert_eq!(messages[1], "fix: other");
+    }
+
+    #[test]
+    fn github_pr_body_reads_the_pull_request() {
+        let event = r#"{"action": "opened", "pull_request": {"number": 7, "body": "risk-ack: reviewed with payments"}}"#;
+        assert_eq!(github_pr_body(event).as_deref(), Some("risk-ack: reviewed with payments"));
+        assert_eq!(github_pr_body(r#"{"pull_request": {"body": null}}"#), None);
+        assert_eq!(github_pr_body(r#"{"ref": "refs/heads/main"}"#), None);
+    }
+
     #[test]
     fn detect_base_ref_defaults_to_main() {
         // When no CI env vars are set, should default to "main"
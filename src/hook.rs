@@ -0,0 +1,130 @@
+use crate::git_diff::GitDiffError;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Marks a hook `guardrails hook install` wrote, so reinstalling may replace it.
+const MARKER: &str = "# Installed by `guardrails hook install`";
+
+/// The `pre-commit` hook: scan the staged changes, and let commits through
+/// where guardrails isn't installed rather than blocking them.
+pub const PRE_COMMIT_HOOK: &str = "#!/bin/sh
+# Installed by `guardrails hook install`: checks staged changes before each commit.
+# Skip it once with `git commit --no-verify`.
+if ! command -v guardrails >/dev/null 2>&1; then
+    echo \"guardrails: not found in PATH, skipping the pre-commit check\" >&2
+    exit 0
+fi
+exec guardrails scan --staged
+";
+
+/// An entry for the pre-commit framework's `.pre-commit-config.yaml`.
+pub const PRE_COMMIT_CONFIG: &str = "repos:
+  - repo: local
+    hooks:
+      - id: guardrails
+        name: guardrails
+        entry: guardrails scan --staged
+        language: system
+        pass_filenames: false
+";
+
+#[derive(Debug)]
+pub enum HookError {
+    Git(GitDiffError),
+    /// A pre-commit hook guardrails didn't write is already installed.
+    Exists(PathBuf),
+    Io(io::Error),
+}
+
+impl fmt::Display for HookError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HookError::Git(e) => write!(f, "{}", e),
+            HookError::Exists(path) => write!(
+                f,
+                "{} already exists and wasn't installed by guardrails (use --force to replace it)",
+                path.display()
+            ),
+            HookError::Io(e) => write!(f, "failed to write hook: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for HookError {}
+
+/// Install the pre-commit hook in the current repository's hooks directory
+/// (`core.hooksPath` when set). Returns the hook's path.
+pub fn install(force: bool) -> Result<PathBuf, HookError> {
+    install_in(&hooks_dir()?, force)
+}
+
+/// Write the pre-commit hook into `hooks_dir`. A hook from an earlier
+/// install is updated; any other hook is only replaced with `force`.
+pub fn install_in(hooks_dir: &Path, force: bool) -> Result<PathBuf, HookError> {
+    let path = hooks_dir.join("pre-commit");
+    if !force {
+        if let Ok(existing) = fs::read_to_string(&path) {
+            if !existing.contains(MARKER) {
+                return Err(HookError::Exists(path));
+            }
+        }
+    }
+    fs::create_dir_all(hooks_dir).map_err(HookError::Io)?;
+    fs::write(&path, PRE_COMMIT_HOOK).map_err(HookError::Io)?;
+    make_executable(&path).map_err(HookError::Io)?;
+    Ok(path)
+}
+
+fn hooks_dir() -> Result<PathBuf, HookError> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--git-path", "hooks"])
+        .output()
+        .map_err(|_| HookError::Git(GitDiffError::GitNotFound))?;
+    if !output.status.success() {
+        return Err(HookError::Git(GitDiffError::NotARepo));
+    }
+    Ok(PathBuf::from(String::from_utf8_lossy(&output.stdout).trim()))
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755))
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn install_writes_an_executable_hook_and_keeps_foreign_ones() {
+        let dir = tempfile::tempdir().unwrap();
+        let hooks = dir.path().join("hooks");
+
+        let path = install_in(&hooks, false).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), PRE_COMMIT_HOOK);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o111, 0o111);
+        }
+        // Reinstalling updates guardrails' own hook
+        install_in(&hooks, false).unwrap();
+
+        fs::write(&path, "#!/bin/sh\nnpm test\n").unwrap();
+        let err = install_in(&hooks, false).err().unwrap();
+        assert!(matches!(err, HookError::Exists(_)));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "#!/bin/sh\nnpm test\n");
+
+        install_in(&hooks, true).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), PRE_COMMIT_HOOK);
+    }
+}
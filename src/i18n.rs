@@ -0,0 +1,227 @@
+use clap::ValueEnum;
+
+/// Environment variable read when `--lang` isn't given.
+pub const LANG_ENV: &str = "GUARDRAILS_LANG";
+
+/// Language of guardrails' own output (summaries, headings, CLI errors).
+/// Rule messages are written by rule authors and shown as they are.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum Lang {
+    #[default]
+    En,
+    Es,
+    De,
+}
+
+impl Lang {
+    /// Parse a language tag such as `de`, `es-MX` or `de_DE.UTF-8`.
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let primary = tag.split(['-', '_', '.']).next()?.to_ascii_lowercase();
+        Some(match primary.as_str() {
+            "en" => Lang::En,
+            "es" => Lang::Es,
+            "de" => Lang::De,
+            _ => return None,
+        })
+    }
+
+    /// The language named by `GUARDRAILS_LANG`, if it is set and known.
+    pub fn from_env() -> Option<Self> {
+        Self::from_tag(&std::env::var(LANG_ENV).ok()?)
+    }
+
+    pub fn error_label(self) -> &'static str {
+        match self {
+            Lang::En => "error",
+            Lang::Es => "error",
+            Lang::De => "Fehler",
+        }
+    }
+
+    pub fn warning_label(self) -> &'static str {
+        match self {
+            Lang::En => "warning",
+            Lang::Es => "advertencia",
+            Lang::De => "Warnung",
+        }
+    }
+
+    pub fn hint_label(self) -> &'static str {
+        match self {
+            Lang::En => "hint",
+            Lang::Es => "sugerencia",
+            Lang::De => "Tipp",
+        }
+    }
+
+    pub fn notice_label(self) -> &'static str {
+        match self {
+            Lang::En => "notice",
+            Lang::Es => "aviso",
+            Lang::De => "Hinweis",
+        }
+    }
+
+    pub fn config_not_found(self, path: &str) -> String {
+        match self {
+            Lang::En => format!("config file '{}' not found", path),
+            Lang::Es => format!("no se encontró el archivo de configuración '{}'", path),
+            Lang::De => format!("Konfigurationsdatei '{}' nicht gefunden", path),
+        }
+    }
+
+    /// `command` is already formatted (e.g. in bold).
+    pub fn run_init_hint(self, command: &str) -> String {
+        match self {
+            Lang::En => format!("run {} to generate a starter config", command),
+            Lang::Es => format!("ejecuta {} para generar una configuración inicial", command),
+            Lang::De => format!("führe {} aus, um eine Startkonfiguration zu erzeugen", command),
+        }
+    }
+
+    pub fn scan_interrupted(self) -> &'static str {
+        match self {
+            Lang::En => "scan was interrupted — results above are incomplete",
+            Lang::Es => "el análisis se interrumpió — los resultados anteriores están incompletos",
+            Lang::De => "der Scan wurde unterbrochen — die Ergebnisse oben sind unvollständig",
+        }
+    }
+
+    pub fn no_violations(self) -> &'static str {
+        match self {
+            Lang::En => "No violations found",
+            Lang::Es => "No se encontraron infracciones",
+            Lang::De => "Keine Verstöße gefunden",
+        }
+    }
+
+    /// E.g. `12 files scanned, 3 rules loaded`.
+    pub fn scanned(self, files: usize, rules: usize) -> String {
+        match self {
+            Lang::En => format!("{} files scanned, {} rules loaded", files, rules),
+            Lang::Es => format!("{} archivos analizados, {} reglas cargadas", files, rules),
+            Lang::De => format!("{} Dateien geprüft, {} Regeln geladen", files, rules),
+        }
+    }
+
+    pub fn errors(self, n: usize) -> String {
+        let (one, many) = match self {
+            Lang::En => ("error", "errors"),
+            Lang::Es => ("error", "errores"),
+            Lang::De => ("Fehler", "Fehler"),
+        };
+        format!("{} {}", n, if n == 1 { one } else { many })
+    }
+
+    pub fn warnings(self, n: usize) -> String {
+        let (one, many) = match self {
+            Lang::En => ("warning", "warnings"),
+            Lang::Es => ("advertencia", "advertencias"),
+            Lang::De => ("Warnung", "Warnungen"),
+        };
+        format!("{} {}", n, if n == 1 { one } else { many })
+    }
+
+    pub fn in_third_party(self, n: usize) -> String {
+        match self {
+            Lang::En => format!("{} in third-party paths", n),
+            Lang::Es => format!("{} en rutas de terceros", n),
+            Lang::De => format!("{} in Drittanbieter-Pfaden", n),
+        }
+    }
+
+    /// Note under the summary when `--max-violations` or `--max-per-file`
+    /// cut the listing.
+    pub fn truncated(self, shown: usize, total: usize) -> String {
+        match self {
+            Lang::En => format!("[truncated: listed {} of {} violations]", shown, total),
+            Lang::Es => format!("[truncado: se listan {} de {} infracciones]", shown, total),
+            Lang::De => format!("[gekürzt: {} von {} Verstößen aufgeführt]", shown, total),
+        }
+    }
+
+    /// Note under a rule's listing when `--max-violations-per-rule` cut it.
+    pub fn more(self, n: &str) -> String {
+        match self {
+            Lang::En => format!("and {} more", n),
+            Lang::Es => format!("y {} más", n),
+            Lang::De => format!("und {} weitere", n),
+        }
+    }
+
+    /// Note under the summary when identical violations were listed once.
+    pub fn duplicates(self, n: usize) -> String {
+        match self {
+            Lang::En => format!("[{} duplicate violations listed once]", n),
+            Lang::Es => format!("[{} infracciones duplicadas listadas una vez]", n),
+            Lang::De => format!("[{} doppelte Verstöße einmal aufgeführt]", n),
+        }
+    }
+
+    /// Pointer under the summary to `guardrails explain`, shown with the
+    /// command for one of the rules that fired.
+    pub fn explain_hint(self, command: &str) -> String {
+        match self {
+            Lang::En => format!("run {} to see why a rule exists and how to fix it", command),
+            Lang::Es => format!("ejecuta {} para ver por qué existe una regla y cómo corregirla", command),
+            Lang::De => format!("führe {} aus, um zu sehen, warum es eine Regel gibt und wie man sie behebt", command),
+        }
+    }
+
+    pub fn incomplete(self) -> &'static str {
+        match self {
+            Lang::En => "[incomplete: interrupted]",
+            Lang::Es => "[incompleto: interrumpido]",
+            Lang::De => "[unvollständig: abgebrochen]",
+        }
+    }
+
+    pub fn ratchet_heading(self) -> &'static str {
+        match self {
+            Lang::En => "Ratchet rules:",
+            Lang::Es => "Reglas ratchet:",
+            Lang::De => "Ratchet-Regeln:",
+        }
+    }
+
+    /// Label of the ratchet group for rules without a tag or owner.
+    pub fn ungrouped(self) -> &'static str {
+        match self {
+            Lang::En => "(none)",
+            Lang::Es => "(ninguno)",
+            Lang::De => "(keine)",
+        }
+    }
+
+    /// Ratchet status words: within budget, over budget.
+    pub fn ratchet_status(self) -> (&'static str, &'static str) {
+        match self {
+            Lang::En => ("pass", "OVER"),
+            Lang::Es => ("ok", "EXCEDIDO"),
+            Lang::De => ("ok", "ÜBER"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tags_pick_the_primary_language() {
+        assert_eq!(Lang::from_tag("de"), Some(Lang::De));
+        assert_eq!(Lang::from_tag("es-MX"), Some(Lang::Es));
+        assert_eq!(Lang::from_tag("de_DE.UTF-8"), Some(Lang::De));
+        assert_eq!(Lang::from_tag("EN"), Some(Lang::En));
+        assert_eq!(Lang::from_tag("fr"), None);
+    }
+
+    #[test]
+    fn counts_are_pluralized_per_language() {
+        assert_eq!(Lang::En.errors(1), "1 error");
+        assert_eq!(Lang::En.errors(2), "2 errors");
+        assert_eq!(Lang::Es.warnings(3), "3 advertencias");
+        assert_eq!(Lang::De.errors(2), "2 Fehler");
+        assert_eq!(Lang::De.warnings(1), "1 Warnung");
+    }
+}
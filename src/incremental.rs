@@ -0,0 +1,274 @@
+use crate::rules::Violation;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+/// Default manifest file for `--incremental`.
+pub const DEFAULT_MANIFEST_FILE: &str = ".guardrails-manifest.json";
+
+/// Directory for everything guardrails caches, next to the config. The walk
+/// never enters it.
+pub const CACHE_DIR: &str = ".guardrails-cache";
+
+/// Scan result cache used by `scan` unless `--no-cache` is given, relative
+/// to the config's directory.
+pub const SCAN_CACHE_FILE: &str = ".guardrails-cache/scan.json";
+
+/// Per-file results of the last run, keyed by content hash.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ManifestData {
+    /// Identifies the config (and tool version) the results were produced by.
+    fingerprint: String,
+    files: HashMap<PathBuf, FileEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct FileEntry {
+    hash: String,
+    /// Hash of the rules that apply to the file, so editing one rule only
+    /// re-checks the files it covers.
+    #[serde(default)]
+    rules: String,
+    /// Violations before waivers, ratchet budgets and diff filtering.
+    violations: Vec<Violation>,
+}
+
+/// Results of a previous run that can be reused for files whose content is
+/// unchanged. A manifest written under a different fingerprint is discarded.
+#[derive(Debug)]
+pub struct Manifest {
+    /// `None` for a manifest that only lives in memory (`ScanSession`).
+    path: Option<PathBuf>,
+    fingerprint: String,
+    previous: HashMap<PathBuf, FileEntry>,
+    next: Mutex<HashMap<PathBuf, FileEntry>>,
+    reused: AtomicUsize,
+}
+
+impl Manifest {
+    /// Open the manifest at `path`, keeping its entries only if they were
+    /// recorded under `fingerprint`.
+    pub fn open(path: &Path, fingerprint: String) -> Self {
+        let previous = fs::read_to_string(path)
+            .ok()
+            .and_then(|text| serde_json::from_str::<ManifestData>(&text).ok())
+            .filter(|m| m.fingerprint == fingerprint)
+            .map(|m| m.files)
+            .unwrap_or_default();
+
+        Self {
+            path: Some(path.to_path_buf()),
+            fingerprint,
+            previous,
+            next: Mutex::new(HashMap::new()),
+            reused: AtomicUsize::new(0),
+        }
+    }
+
+    /// An empty manifest that is never written to disk.
+    pub fn in_memory(fingerprint: String) -> Self {
+        Self {
+            path: None,
+            fingerprint,
+            previous: HashMap::new(),
+            next: Mutex::new(HashMap::new()),
+            reused: AtomicUsize::new(0),
+        }
+    }
+
+    /// The manifest for the next run: this run's entries on top of the
+    /// previous ones, so files a run didn't touch stay cached.
+    pub fn advance(self) -> Self {
+        let mut previous = self.previous;
+        previous.extend(self.next.into_inner().unwrap());
+        Self {
+            path: self.path,
+            fingerprint: self.fingerprint,
+            previous,
+            next: Mutex::new(HashMap::new()),
+            reused: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn fingerprint(&self) -> &str {
+        &self.fingerprint
+    }
+
+    /// Number of files with cached results.
+    pub fn len(&self) -> usize {
+        self.previous.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.previous.is_empty()
+    }
+
+    /// Whether `file` is the manifest itself, which may sit inside the scanned tree.
+    pub fn is_manifest_file(&self, file: &Path) -> bool {
+        self.path
+            .as_deref()
+            .is_some_and(|path| file.file_name().is_some() && file.file_name() == path.file_name())
+    }
+
+    /// Cached violations for `file`, if its content hash and the hash of the
+    /// rules that apply to it are unchanged.
+    pub fn lookup(&self, file: &Path, hash: &str, rules: &str) -> Option<Vec<Violation>> {
+        let entry = self.previous.get(file).filter(|e| e.hash == hash && e.rules == rules)?;
+        self.reused.fetch_add(1, Ordering::Relaxed);
+        Some(entry.violations.clone())
+    }
+
+    /// Record this run's result for `file`.
+    pub fn record(&self, file: &Path, hash: String, rules: String, violations: &[Violation]) {
+        self.next.lock().unwrap().insert(
+            file.to_path_buf(),
+            FileEntry {
+                hash,
+                rules,
+                violations: violations.to_vec(),
+            },
+        );
+    }
+
+    /// Number of files whose results were reused.
+    pub fn reused(&self) -> usize {
+        self.reused.load(Ordering::Relaxed)
+    }
+
+    /// Write this run's entries, replacing the previous manifest.
+    pub fn save(&self) -> io::Result<()> {
+        let Some(ref path) = self.path else {
+            return Ok(());
+        };
+        let data = ManifestData {
+            fingerprint: self.fingerprint.clone(),
+            files: self.next.lock().unwrap().clone(),
+        };
+        let json = serde_json::to_string(&data).map_err(io::Error::other)?;
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, json)?;
+        fs::rename(&tmp, path)
+    }
+}
+
+/// Hash of a file's content.
+pub fn content_hash(content: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+/// Hash of one rule's configuration.
+pub fn rule_hash(rule_config: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    rule_config.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Combined hash of the rules (from [`rule_hash`]) that apply to a file.
+pub fn rules_hash(rule_hashes: &[u64]) -> String {
+    let mut hasher = DefaultHasher::new();
+    rule_hashes.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+/// Delete the cache directory under `base_dir`. Returns whether there was one.
+pub fn clear_cache(base_dir: &Path) -> io::Result<bool> {
+    match fs::remove_dir_all(base_dir.join(CACHE_DIR)) {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+/// Fingerprint for cached results: tool version, config and plugin contents.
+pub fn fingerprint(config_text: &str, plugin_texts: &[String]) -> String {
+    let mut hasher = DefaultHasher::new();
+    env!("CARGO_PKG_VERSION").hash(&mut hasher);
+    config_text.hash(&mut hasher);
+    plugin_texts.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unchanged_content_is_reused() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("manifest.json");
+        let hash = content_hash("eval(x);\n");
+
+        let first = Manifest::open(&path, "cfg".into());
+        assert!(first.lookup(Path::new("a.js"), &hash, "").is_none());
+        first.record(Path::new("a.js"), hash.clone(), String::new(), &[Violation::test("r", "a.js")]);
+        first.save().unwrap();
+
+        let second = Manifest::open(&path, "cfg".into());
+        assert_eq!(second.lookup(Path::new("a.js"), &hash, "").unwrap().len(), 1);
+        // Same content, but the rules that apply to it changed
+        assert!(second.lookup(Path::new("a.js"), &hash, "edited").is_none());
+        assert!(second
+            .lookup(Path::new("a.js"), &content_hash("eval(y);\n"), "")
+            .is_none());
+        assert_eq!(second.reused(), 1);
+    }
+
+    #[test]
+    fn changed_fingerprint_discards_results() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("manifest.json");
+        let hash = content_hash("x");
+
+        let first = Manifest::open(&path, "old".into());
+        first.record(Path::new("a.js"), hash.clone(), String::new(), &[]);
+        first.save().unwrap();
+
+        let second = Manifest::open(&path, "new".into());
+        assert!(second.lookup(Path::new("a.js"), &hash, "").is_none());
+    }
+
+    #[test]
+    fn in_memory_manifest_carries_results_forward() {
+        let hash = content_hash("eval(x);\n");
+        let first = Manifest::in_memory("cfg".into());
+        first.record(Path::new("a.js"), hash.clone(), String::new(), &[Violation::test("r", "a.js")]);
+        first.save().unwrap();
+
+        let second = first.advance();
+        second.record(Path::new("b.js"), content_hash("ok();\n"), String::new(), &[]);
+        let third = second.advance();
+        assert_eq!(third.len(), 2);
+        assert_eq!(third.lookup(Path::new("a.js"), &hash, "").unwrap().len(), 1);
+        assert!(!third.is_manifest_file(Path::new(DEFAULT_MANIFEST_FILE)));
+    }
+
+    #[test]
+    fn clear_cache_removes_the_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest = Manifest::open(&dir.path().join(SCAN_CACHE_FILE), "cfg".into());
+        manifest.record(Path::new("a.js"), content_hash("x"), String::new(), &[]);
+        manifest.save().unwrap();
+        assert!(dir.path().join(SCAN_CACHE_FILE).is_file());
+
+        assert!(clear_cache(dir.path()).unwrap());
+        assert!(!dir.path().join(CACHE_DIR).exists());
+        assert!(!clear_cache(dir.path()).unwrap());
+    }
+
+    #[test]
+    fn fingerprint_covers_plugins() {
+        assert_eq!(fingerprint("a", &[]), fingerprint("a", &[]));
+        assert_ne!(fingerprint("a", &[]), fingerprint("a", &["p".into()]));
+    }
+}
@@ -1,3 +1,4 @@
+use crate::presets;
 use std::path::Path;
 
 /// Detected project type based on config files present.
@@ -9,6 +10,8 @@ pub enum ProjectType {
     TailwindOnly,
     /// Generic JS/TS project (package.json but no Tailwind)
     Generic,
+    /// Rust crate or workspace (Cargo.toml but no package.json)
+    Rust,
     /// Unknown project type
     Unknown,
 }
@@ -21,11 +24,13 @@ pub fn detect_project(dir: &Path) -> ProjectType {
         || dir.join("tailwind.config.mjs").exists()
         || dir.join("tailwind.config.cjs").exists();
     let has_package_json = dir.join("package.json").exists();
+    let has_cargo_toml = dir.join("Cargo.toml").exists();
 
     match (has_shadcn, has_tailwind, has_package_json) {
         (true, _, _) => ProjectType::ShadcnTailwind,
         (false, true, _) => ProjectType::TailwindOnly,
         (false, false, true) => ProjectType::Generic,
+        _ if has_cargo_toml => ProjectType::Rust,
         _ => ProjectType::Unknown,
     }
 }
@@ -36,10 +41,57 @@ pub fn generate_config(project_type: &ProjectType) -> String {
         ProjectType::ShadcnTailwind => generate_shadcn_config(),
         ProjectType::TailwindOnly => generate_tailwind_config(),
         ProjectType::Generic => generate_generic_config(),
+        ProjectType::Rust => generate_rust_config(),
         ProjectType::Unknown => generate_generic_config(),
     }
 }
 
+/// Presets the generated config for `project_type` extends.
+pub fn default_presets(project_type: &ProjectType) -> Vec<String> {
+    match project_type {
+        ProjectType::ShadcnTailwind => vec!["shadcn-strict".to_string()],
+        _ => Vec::new(),
+    }
+}
+
+/// Parse a comma- or space-separated list of preset names; `none` selects none.
+pub fn parse_presets(input: &str) -> Result<Vec<String>, String> {
+    let mut selected = Vec::new();
+    for name in input.split([',', ' ']).map(str::trim).filter(|n| !n.is_empty()) {
+        if name == "none" {
+            continue;
+        }
+        if !presets::available_presets().contains(&name) {
+            return Err(format!(
+                "unknown preset '{}' (available: {})",
+                name,
+                presets::available_presets().join(", ")
+            ));
+        }
+        if !selected.iter().any(|s| s == name) {
+            selected.push(name.to_string());
+        }
+    }
+    Ok(selected)
+}
+
+/// `config` (as generated) extending `presets` instead of its own choice.
+pub fn set_presets(config: &str, presets: &[String]) -> String {
+    let mut out = String::with_capacity(config.len());
+    for line in config.lines() {
+        if line.starts_with("extends = ") {
+            continue;
+        }
+        out.push_str(line);
+        out.push('\n');
+        if line.starts_with("name = ") && !presets.is_empty() {
+            let quoted: Vec<String> = presets.iter().map(|p| format!("\"{}\"", p)).collect();
+            out.push_str(&format!("extends = [{}]\n", quoted.join(", ")));
+        }
+    }
+    out
+}
+
 fn generate_shadcn_config() -> String {
     r#"# guardrails.toml — Guardrails for shadcn/Tailwind
 # Generated by `guardrails init` (shadcn + Tailwind detected)
@@ -115,6 +167,22 @@ severity = "error"
 packages = ["bootstrap", "bulma", "@mui/material", "antd"]
 message = "Competing CSS framework detected — this project uses Tailwind"
 
+# ──────────────────────────────────────────────
+# Ratchet: Phase Out Arbitrary Values
+# Existing w-[123px]-style classes are tolerated up to max_count;
+# lower it as they're migrated so new ones can't creep in.
+# ──────────────────────────────────────────────
+
+# [[rule]]
+# id = "fewer-arbitrary-values"
+# type = "ratchet"
+# severity = "warning"
+# pattern = "-\\[[0-9]+px\\]"
+# regex = true
+# max_count = 50
+# glob = "**/*.{tsx,jsx}"
+# message = "Arbitrary pixel value — use the Tailwind spacing scale"
+
 # ──────────────────────────────────────────────
 # Uncomment these rules if you add shadcn/ui:
 # ──────────────────────────────────────────────
@@ -181,6 +249,75 @@ exclude = ["**/node_modules/**", "**/dist/**", "**/build/**"]
 # severity = "error"
 # packages = ["request"]
 # message = "The 'request' package is deprecated — use 'node-fetch'"
+
+# ──────────────────────────────────────────────
+# Example: Ratchet down a legacy pattern
+# Existing uses are tolerated up to max_count; lower it as they're
+# removed so new ones can't be added.
+# ──────────────────────────────────────────────
+
+# [[rule]]
+# id = "fewer-any-types"
+# type = "ratchet"
+# severity = "warning"
+# pattern = ": any"
+# max_count = 100
+# glob = "src/**/*.{ts,tsx}"
+# message = "Avoid `any` — give the value a real type"
+"#
+    .to_string()
+}
+
+fn generate_rust_config() -> String {
+    r#"# guardrails.toml — Guardrails for Rust
+# Generated by `guardrails init` (Cargo.toml detected)
+
+[guardrails]
+name = "my-project"
+include = ["src/**/*", "crates/**/*"]
+exclude = ["**/target/**"]
+
+# ──────────────────────────────────────────────
+# Ban Debug Macros
+# dbg! output shouldn't reach a commit.
+# ──────────────────────────────────────────────
+
+[[rule]]
+id = "no-dbg"
+type = "banned-pattern"
+severity = "error"
+pattern = "dbg!("
+glob = "**/*.rs"
+message = "Remove dbg! before committing"
+
+# ──────────────────────────────────────────────
+# Ratchet: Phase Out unwrap()
+# Existing calls are tolerated up to max_count; lower it as they're
+# replaced with error handling so new ones can't be added.
+# Set max_count to the current count from `guardrails scan`.
+# ──────────────────────────────────────────────
+
+# [[rule]]
+# id = "fewer-unwraps"
+# type = "ratchet"
+# severity = "warning"
+# pattern = ".unwrap()"
+# max_count = 100
+# glob = "src/**/*.rs"
+# message = "Prefer ? or expect() with a reason over unwrap()"
+
+# ──────────────────────────────────────────────
+# Example: Ratchet down todo!() placeholders
+# ──────────────────────────────────────────────
+
+# [[rule]]
+# id = "fewer-todos"
+# type = "ratchet"
+# severity = "warning"
+# pattern = "todo!("
+# max_count = 10
+# glob = "**/*.rs"
+# message = "Unimplemented code path"
 "#
     .to_string()
 }
@@ -230,6 +367,17 @@ mod tests {
         assert_eq!(detect_project(dir.path()), ProjectType::Generic);
     }
 
+    #[test]
+    fn detect_rust_project() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("Cargo.toml"), "").unwrap();
+        assert_eq!(detect_project(dir.path()), ProjectType::Rust);
+
+        // A JS frontend in a Rust repo still gets the JS config
+        fs::write(dir.path().join("package.json"), "{}").unwrap();
+        assert_eq!(detect_project(dir.path()), ProjectType::Generic);
+    }
+
     #[test]
     fn detect_unknown() {
         let dir = tempfile::tempdir().unwrap();
@@ -244,6 +392,26 @@ mod tests {
         assert!(!config.contains("\n[[rule]]"));
     }
 
+    #[test]
+    fn presets_can_be_chosen_for_a_generated_config() {
+        assert_eq!(
+            parse_presets("ai-safety, shadcn-migrate ai-safety").unwrap(),
+            ["ai-safety", "shadcn-migrate"]
+        );
+        assert!(parse_presets("none").unwrap().is_empty());
+        assert!(parse_presets("strict").unwrap_err().contains("available: shadcn-strict"));
+
+        let shadcn = generate_config(&ProjectType::ShadcnTailwind);
+        let config = set_presets(&shadcn, &["ai-safety".to_string()]);
+        assert!(config.contains("name = \"my-project\"\nextends = [\"ai-safety\"]\n"));
+        assert!(!config.contains("extends = [\"shadcn-strict\"]"));
+        assert!(!set_presets(&shadcn, &[]).contains("extends = "));
+
+        let generic = set_presets(&generate_config(&ProjectType::Generic), &["ai-safety".to_string()]);
+        let parsed: toml::Value = toml::from_str(&generic).unwrap();
+        assert_eq!(parsed["guardrails"]["extends"][0].as_str(), Some("ai-safety"));
+    }
+
     #[test]
     fn tailwind_config_has_migration_rules() {
         let config = generate_config(&ProjectType::TailwindOnly);
@@ -260,5 +428,15 @@ mod tests {
         assert!(config.contains("banned-pattern"));
         assert!(config.contains("banned-import"));
         assert!(config.contains("banned-dependency"));
+        assert!(config.contains("# type = \"ratchet\""));
+    }
+
+    #[test]
+    fn rust_config_excludes_target_and_parses() {
+        let config = generate_config(&ProjectType::Rust);
+        assert!(config.contains("**/target/**"));
+        assert!(config.contains("# type = \"ratchet\""));
+        let parsed: crate::cli::toml_config::TomlConfig = toml::from_str(&config).unwrap();
+        assert_eq!(parsed.rule.len(), 1);
     }
 }
@@ -0,0 +1,162 @@
+use std::fs;
+use std::path::Path;
+use toml_edit::{DocumentMut, Formatted, Item, Value};
+
+/// Variables every config can use, which take precedence over the
+/// environment: the absolute directory of the config, and the root of the
+/// git repository it's in (the config's directory outside one).
+pub const BUILTINS: [&str; 2] = ["CONFIG_DIR", "REPO_ROOT"];
+
+/// The config `text` with `${VAR}` and `${VAR:-default}` in its string
+/// values replaced by built-in variables (see [`BUILTINS`]) or environment
+/// variables, for the config in `config_dir`.
+///
+/// Keys and comments are left alone, as is text that isn't valid TOML so the
+/// parser can report it. Errors name the value with the undefined variable.
+pub fn interpolate(text: &str, config_dir: &Path) -> Result<String, String> {
+    if !text.contains("${") {
+        return Ok(text.to_string());
+    }
+    let dir = fs::canonicalize(config_dir).unwrap_or_else(|_| config_dir.to_path_buf());
+    let repo = dir.ancestors().find(|d| d.join(".git").exists()).unwrap_or(&dir);
+    let builtins = [dir.display().to_string(), repo.display().to_string()];
+    interpolate_with(text, &|name: &str| match BUILTINS.iter().position(|&b| b == name) {
+        Some(i) => Some(builtins[i].clone()),
+        None => std::env::var(name).ok(),
+    })
+}
+
+fn interpolate_with(text: &str, lookup: &dyn Fn(&str) -> Option<String>) -> Result<String, String> {
+    let Ok(mut doc) = text.parse::<DocumentMut>() else {
+        return Ok(text.to_string());
+    };
+    for (key, item) in doc.iter_mut() {
+        interpolate_item(item, key.get().to_string(), lookup)?;
+    }
+    Ok(doc.to_string())
+}
+
+fn interpolate_item(item: &mut Item, path: String, lookup: &dyn Fn(&str) -> Option<String>) -> Result<(), String> {
+    match item {
+        Item::Value(value) => interpolate_value(value, path, lookup),
+        Item::Table(table) => table
+            .iter_mut()
+            .try_for_each(|(key, item)| interpolate_item(item, format!("{}.{}", path, key.get()), lookup)),
+        Item::ArrayOfTables(tables) => tables.iter_mut().enumerate().try_for_each(|(i, table)| {
+            table
+                .iter_mut()
+                .try_for_each(|(key, item)| interpolate_item(item, format!("{}[{}].{}", path, i, key.get()), lookup))
+        }),
+        Item::None => Ok(()),
+    }
+}
+
+fn interpolate_value(value: &mut Value, path: String, lookup: &dyn Fn(&str) -> Option<String>) -> Result<(), String> {
+    match value {
+        Value::String(string) => {
+            let expanded = expand(string.value(), lookup).map_err(|e| format!("{}: {}", path, e))?;
+            if expanded != *string.value() {
+                let mut replaced = Formatted::new(expanded);
+                *replaced.decor_mut() = string.decor().clone();
+                *string = replaced;
+            }
+            Ok(())
+        }
+        Value::Array(array) => array
+            .iter_mut()
+            .enumerate()
+            .try_for_each(|(i, value)| interpolate_value(value, format!("{}[{}]", path, i), lookup)),
+        Value::InlineTable(table) => table
+            .iter_mut()
+            .try_for_each(|(key, value)| interpolate_value(value, format!("{}.{}", path, key.get()), lookup)),
+        _ => Ok(()),
+    }
+}
+
+/// `text` with each `${NAME}` replaced by `lookup(NAME)`. `${NAME:-default}`
+/// falls back to `default` when the variable is unset or empty, and `$${`
+/// stands for a literal `${`.
+pub fn expand(text: &str, lookup: &dyn Fn(&str) -> Option<String>) -> Result<String, String> {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(at) = rest.find("${") {
+        let after = &rest[at + 2..];
+        if rest[..at].ends_with('$') {
+            out.push_str(&rest[..at - 1]);
+            out.push_str("${");
+            rest = after;
+            continue;
+        }
+        out.push_str(&rest[..at]);
+        let end = after
+            .find('}')
+            .ok_or_else(|| format!("unterminated `${{` in \"{}\"", text))?;
+        let body = &after[..end];
+        let (name, default) = match body.split_once(":-") {
+            Some((name, default)) => (name, Some(default)),
+            None => (body, None),
+        };
+        if name.is_empty()
+            || name.starts_with(|c: char| c.is_ascii_digit())
+            || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            return Err(format!("`${{{}}}` is not a variable; write `$${{` for a literal `${{`", body));
+        }
+        match (lookup(name), default) {
+            (Some(value), Some(default)) if value.is_empty() => out.push_str(default),
+            (Some(value), _) => out.push_str(&value),
+            (None, Some(default)) => out.push_str(default),
+            (None, None) => {
+                return Err(format!(
+                    "undefined variable `{}`; set it, or give a default with `${{{}:-default}}`",
+                    name, name
+                ))
+            }
+        }
+        rest = &after[end + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lookup(name: &str) -> Option<String> {
+        match name {
+            "TEAM" => Some("web".to_string()),
+            "EMPTY" => Some(String::new()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn expands_variables_defaults_and_escapes() {
+        assert_eq!(expand("apps/${TEAM}/**", &lookup).unwrap(), "apps/web/**");
+        assert_eq!(expand("${MISSING:-src}/${EMPTY:-lib}", &lookup).unwrap(), "src/lib");
+        assert_eq!(expand("x${EMPTY}y", &lookup).unwrap(), "xy");
+        assert_eq!(expand("Use `$${theme.x}`", &lookup).unwrap(), "Use `${theme.x}`");
+        assert!(expand("${MISSING}", &lookup).unwrap_err().contains("undefined variable `MISSING`"));
+        assert!(expand("Use `${theme.x}`", &lookup).unwrap_err().contains("$${"));
+        assert!(expand("${TEAM", &lookup).unwrap_err().contains("unterminated"));
+    }
+
+    #[test]
+    fn interpolates_string_values_and_names_the_failing_one() {
+        let text = "# ${TEAM} stays\n[guardrails]\nexclude = [\"apps/${TEAM}/**\"]\n\n[[rule]]\nid = \"a\"\nmessage = 'Ask ${TEAM}' # owner\nmeta = { team = \"${TEAM}\" }\n";
+        assert_eq!(
+            interpolate_with(text, &lookup).unwrap(),
+            "# ${TEAM} stays\n[guardrails]\nexclude = [\"apps/web/**\"]\n\n[[rule]]\nid = \"a\"\nmessage = \"Ask web\" # owner\nmeta = { team = \"web\" }\n"
+        );
+        let err = interpolate_with("[[rule]]\nid = \"a\"\n[[rule]]\nmessage = \"${NOPE}\"\n", &lookup).unwrap_err();
+        assert!(err.starts_with("rule[1].message: undefined variable `NOPE`"), "{}", err);
+
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join(".git")).unwrap();
+        fs::create_dir_all(dir.path().join("web")).unwrap();
+        let root = fs::canonicalize(dir.path()).unwrap();
+        let text = interpolate("a = \"${REPO_ROOT}|${CONFIG_DIR}\"\n", &dir.path().join("web")).unwrap();
+        assert_eq!(text, format!("a = \"{}|{}\"\n", root.display(), root.join("web").display()));
+    }
+}
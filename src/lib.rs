@@ -1,8 +1,46 @@
+pub mod alloc_stats;
+pub mod baseline;
+pub mod bundle;
+pub mod check_config;
 pub mod cli;
+pub mod codeowners;
+pub mod compile_cache;
 pub mod config;
+pub mod config_diff;
+pub mod doctor;
+pub mod extends;
+pub mod filetypes;
+pub mod fingerprint;
 pub mod git_diff;
+pub mod hook;
+pub mod i18n;
+pub mod incremental;
 pub mod init;
+pub mod interpolate;
+pub mod line_endings;
+pub mod lsp;
 pub mod mcp;
+pub mod metrics;
+pub mod new_rule;
+pub mod path_class;
+pub mod plugin_discovery;
 pub mod presets;
+pub mod preview;
+pub mod progress;
+pub mod ratchet_history;
+pub mod ratchet_scopes;
+pub mod ratchet_update;
+pub mod report;
+pub mod result_diff;
+pub mod resume;
+pub mod rollup;
+pub mod rule_tests;
 pub mod rules;
+pub mod sample;
 pub mod scan;
+pub mod source_text;
+pub mod suppression;
+pub mod upload;
+pub mod waivers;
+
+pub use scan::{Scanner, ScannerBuilder};
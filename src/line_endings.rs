@@ -0,0 +1,66 @@
+/// Each line of `content` paired with its ending (`"\n"`, `"\r\n"`, or `""`
+/// for an unterminated last line).
+///
+/// Every line number, column, and fix is counted against these lines, so a
+/// CRLF file reports the same positions as its LF copy. Unlike [`str::lines`],
+/// the `\r` of an unterminated last line is treated as part of its ending too.
+pub fn split(content: &str) -> impl Iterator<Item = (&str, &str)> {
+    content.split_inclusive('\n').map(|l| {
+        let body = l.strip_suffix('\n').unwrap_or(l);
+        let text = body.strip_suffix('\r').unwrap_or(body);
+        (text, &l[text.len()..])
+    })
+}
+
+/// The lines of `content` without their endings.
+pub fn lines(content: &str) -> impl Iterator<Item = &str> {
+    split(content).map(|(text, _)| text)
+}
+
+/// The byte offset in `content` at which each of its [`lines`] starts.
+pub fn starts(content: &str) -> Vec<usize> {
+    split(content)
+        .scan(0, |offset, (text, ending)| {
+            let start = *offset;
+            *offset += text.len() + ending.len();
+            Some(start)
+        })
+        .collect()
+}
+
+/// The 1-based line and byte column of byte `offset`, given the line
+/// [`starts`] of its content.
+pub fn position(starts: &[usize], offset: usize) -> (usize, usize) {
+    let line = starts.partition_point(|&start| start <= offset).max(1);
+    (line, offset - starts.get(line - 1).copied().unwrap_or(0) + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crlf_and_lf_lines_match() {
+        let lf: Vec<&str> = lines("a\nb\n\nc").collect();
+        assert_eq!(lf, vec!["a", "b", "", "c"]);
+        assert_eq!(lines("a\r\nb\r\n\r\nc\r").collect::<Vec<_>>(), lf);
+        assert_eq!(lines("a\r\nb\n\nc\r\n").collect::<Vec<_>>(), lf);
+    }
+
+    #[test]
+    fn endings_round_trip() {
+        let content = "a\r\nb\n\r\nc\r";
+        let split: Vec<_> = split(content).collect();
+        assert_eq!(split, vec![("a", "\r\n"), ("b", "\n"), ("", "\r\n"), ("c", "\r")]);
+        let joined: String = split.iter().map(|(text, ending)| format!("{}{}", text, ending)).collect();
+        assert_eq!(joined, content);
+        assert_eq!(starts(content), vec![0, 3, 5, 7]);
+        assert_eq!(position(&starts(content), 4), (2, 2));
+    }
+
+    #[test]
+    fn inner_carriage_returns_stay_in_the_line() {
+        assert_eq!(lines("a\rb\r\n").collect::<Vec<_>>(), vec!["a\rb"]);
+        assert_eq!(lines("").count(), 0);
+    }
+}
@@ -0,0 +1,468 @@
+use crate::config::Severity;
+use crate::line_endings;
+use crate::rules::Violation;
+use crate::scan::{ScanResult, ScanSession};
+use serde_json::json;
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+
+/// Run a minimal Language Server Protocol server over stdio.
+///
+/// Open documents are scanned as unsaved buffers on open, change and save,
+/// and their violations published as diagnostics. Violations with fix data
+/// become quick fixes. The `ScanSession` re-reads the config before every
+/// scan, so saving the config (or a watched-files notification) re-publishes
+/// every open document under the new rules.
+pub fn run_lsp_server(config_path: &Path) {
+    let mut server = Server::new(config_path);
+    let stdin = io::stdin();
+    let mut reader = stdin.lock();
+    let mut stdout = io::stdout();
+
+    while let Ok(Some(message)) = read_message(&mut reader) {
+        if message.get("method").and_then(|m| m.as_str()) == Some("exit") {
+            break;
+        }
+        for outgoing in server.handle(&message) {
+            if write_message(&mut stdout, &outgoing).is_err() {
+                return;
+            }
+        }
+    }
+}
+
+/// Read one `Content-Length` framed message. `None` at the end of input.
+fn read_message(reader: &mut impl BufRead) -> io::Result<Option<serde_json::Value>> {
+    let mut length: Option<usize> = None;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                length = value.trim().parse().ok();
+            }
+        }
+    }
+    let length = length.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "missing Content-Length"))?;
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+    serde_json::from_slice(&body)
+        .map(Some)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Write `message` with its `Content-Length` header.
+fn write_message(writer: &mut impl Write, message: &serde_json::Value) -> io::Result<()> {
+    let body = message.to_string();
+    write!(writer, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+    writer.flush()
+}
+
+/// Server state: the warm scan session and the text of every open document.
+struct Server {
+    config_path: PathBuf,
+    session: ScanSession,
+    /// Open documents by URI.
+    documents: HashMap<String, String>,
+}
+
+impl Server {
+    fn new(config_path: &Path) -> Self {
+        Self {
+            config_path: config_path.to_path_buf(),
+            session: ScanSession::new(config_path),
+            documents: HashMap::new(),
+        }
+    }
+
+    /// Handle one request or notification, returning the messages to send.
+    fn handle(&mut self, message: &serde_json::Value) -> Vec<serde_json::Value> {
+        let id = message.get("id").cloned();
+        let method = message.get("method").and_then(|m| m.as_str()).unwrap_or("");
+        let params = message.get("params").cloned().unwrap_or(json!({}));
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or("").to_string();
+
+        match method {
+            "initialize" => vec![response(id, initialize_result())],
+            "shutdown" => vec![response(id, serde_json::Value::Null)],
+            "textDocument/didOpen" => {
+                let text = params["textDocument"]["text"].as_str().unwrap_or("").to_string();
+                self.documents.insert(uri.clone(), text);
+                self.publish(&uri)
+            }
+            "textDocument/didChange" => {
+                // Full sync: the last change holds the whole document
+                if let Some(text) = params["contentChanges"]
+                    .as_array()
+                    .and_then(|changes| changes.last())
+                    .and_then(|change| change["text"].as_str())
+                {
+                    self.documents.insert(uri.clone(), text.to_string());
+                }
+                self.publish(&uri)
+            }
+            "textDocument/didSave" => {
+                if let Some(text) = params["text"].as_str() {
+                    self.documents.insert(uri.clone(), text.to_string());
+                }
+                if self.is_config(&uri) {
+                    self.publish_all()
+                } else {
+                    self.publish(&uri)
+                }
+            }
+            "textDocument/didClose" => {
+                self.documents.remove(&uri);
+                vec![diagnostics_notification(&uri, Vec::new())]
+            }
+            "workspace/didChangeWatchedFiles" => self.publish_all(),
+            "textDocument/codeAction" => {
+                let actions = self.code_actions(&uri, &params["range"]);
+                vec![response(id, json!(actions))]
+            }
+            _ => match id {
+                // Unknown notifications are ignored; unknown requests get an error
+                Some(id) => vec![json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "error": { "code": -32601, "message": format!("Unknown method: {}", method) }
+                })],
+                None => Vec::new(),
+            },
+        }
+    }
+
+    /// Whether `uri` is the config file.
+    fn is_config(&self, uri: &str) -> bool {
+        let Some(path) = uri_to_path(uri) else {
+            return false;
+        };
+        match (path.canonicalize(), self.config_path.canonicalize()) {
+            (Ok(path), Ok(config)) => path == config,
+            _ => false,
+        }
+    }
+
+    /// Scan the open document at `uri`. Errors become a message for the log.
+    fn scan(&mut self, uri: &str) -> Option<Result<ScanResult, String>> {
+        let path = uri_to_path(uri)?;
+        let text = self.documents.get(uri)?;
+        Some(
+            self.session
+                .scan_content(text, &path.to_string_lossy())
+                .map_err(|e| format!("guardrails: {}", e)),
+        )
+    }
+
+    /// Diagnostics for the document at `uri`.
+    fn publish(&mut self, uri: &str) -> Vec<serde_json::Value> {
+        let result = match self.scan(uri) {
+            Some(Ok(result)) => result,
+            Some(Err(message)) => return vec![log_message(&message)],
+            None => return Vec::new(),
+        };
+        let text = self.documents.get(uri).map(String::as_str).unwrap_or("");
+        let lines: Vec<&str> = line_endings::lines(text).collect();
+        let diagnostics = result
+            .violations
+            .iter()
+            .map(|v| {
+                let mut diagnostic = json!({
+                    "range": violation_range(v, &lines),
+                    "severity": match v.severity {
+                        Severity::Error => 1,
+                        Severity::Warning => 2,
+                    },
+                    "code": v.rule_id,
+                    "source": "guardrails",
+                    "message": match v.suggest {
+                        Some(ref suggest) => format!("{}\n{}", v.message, suggest),
+                        None => v.message.clone(),
+                    },
+                });
+                let docs = result.suggestions.get(&v.rule_id).and_then(|s| s.docs.as_ref());
+                if let Some(href) = v.url.as_ref().or(docs) {
+                    diagnostic["codeDescription"] = json!({ "href": href });
+                }
+                diagnostic
+            })
+            .collect();
+        vec![diagnostics_notification(uri, diagnostics)]
+    }
+
+    /// Diagnostics for every open document, e.g. after the config changed.
+    fn publish_all(&mut self) -> Vec<serde_json::Value> {
+        let mut uris: Vec<String> = self.documents.keys().cloned().collect();
+        uris.sort();
+        uris.iter().flat_map(|uri| self.publish(uri)).collect()
+    }
+
+    /// Quick fixes for the violations with fix data on the lines of `range`.
+    fn code_actions(&mut self, uri: &str, range: &serde_json::Value) -> Vec<serde_json::Value> {
+        let Some(Ok(result)) = self.scan(uri) else {
+            return Vec::new();
+        };
+        let text = self.documents.get(uri).map(String::as_str).unwrap_or("");
+        let lines: Vec<&str> = line_endings::lines(text).collect();
+        let first = range["start"]["line"].as_u64().unwrap_or(0) as usize;
+        let last = range["end"]["line"].as_u64().unwrap_or(first as u64) as usize;
+
+        result
+            .violations
+            .iter()
+            .filter(|v| v.line.is_some_and(|line| (first..=last).contains(&(line - 1))))
+            .filter_map(|v| {
+                let fix = v.fix.as_ref()?;
+                let line = lines.get(v.line? - 1)?;
+                // The fix's text at the violation's column, else its first occurrence on the line
+                let start = v
+                    .column
+                    .map(|c| c - 1)
+                    .filter(|&c| line.get(c..).is_some_and(|rest| rest.starts_with(fix.old.as_str())))
+                    .or_else(|| line.find(fix.old.as_str()))?;
+                let row = v.line? - 1;
+                Some(json!({
+                    "title": format!("Replace '{}' with '{}' ({})", fix.old, fix.new, v.rule_id),
+                    "kind": "quickfix",
+                    "edit": {
+                        "changes": {
+                            uri: [{
+                                "range": {
+                                    "start": { "line": row, "character": utf16_column(line, start) },
+                                    "end": { "line": row, "character": utf16_column(line, start + fix.old.len()) },
+                                },
+                                "newText": fix.new,
+                            }]
+                        }
+                    }
+                }))
+            })
+            .collect()
+    }
+}
+
+fn initialize_result() -> serde_json::Value {
+    json!({
+        "capabilities": {
+            "textDocumentSync": {
+                "openClose": true,
+                "change": 1,
+                "save": { "includeText": true }
+            },
+            "codeActionProvider": { "codeActionKinds": ["quickfix"] }
+        },
+        "serverInfo": {
+            "name": "guardrails",
+            "version": env!("CARGO_PKG_VERSION")
+        }
+    })
+}
+
+fn response(id: Option<serde_json::Value>, result: serde_json::Value) -> serde_json::Value {
+    json!({ "jsonrpc": "2.0", "id": id, "result": result })
+}
+
+fn diagnostics_notification(uri: &str, diagnostics: Vec<serde_json::Value>) -> serde_json::Value {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/publishDiagnostics",
+        "params": { "uri": uri, "diagnostics": diagnostics }
+    })
+}
+
+fn log_message(message: &str) -> serde_json::Value {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "window/logMessage",
+        "params": { "type": 1, "message": message }
+    })
+}
+
+/// Where `v` is underlined: from its column to the end of the fix's text, or
+/// else to the end of the line. Violations without a line mark the file start.
+fn violation_range(v: &Violation, lines: &[&str]) -> serde_json::Value {
+    let (row, line) = match v.line {
+        Some(line) => (line - 1, lines.get(line - 1).copied().unwrap_or("")),
+        None => (0, ""),
+    };
+    let start = v.column.map_or(0, |c| c - 1).min(line.len());
+    let end = match v.fix {
+        Some(ref fix) if line.get(start..).is_some_and(|rest| rest.starts_with(fix.old.as_str())) => {
+            start + fix.old.len()
+        }
+        _ => line.trim_end().len().max(start),
+    };
+    json!({
+        "start": { "line": row, "character": utf16_column(line, start) },
+        "end": { "line": row, "character": utf16_column(line, end) },
+    })
+}
+
+/// The UTF-16 offset LSP positions use for byte offset `byte` of `line`.
+fn utf16_column(line: &str, byte: usize) -> usize {
+    let mut byte = byte.min(line.len());
+    while !line.is_char_boundary(byte) {
+        byte -= 1;
+    }
+    line[..byte].encode_utf16().count()
+}
+
+/// The path of a `file://` URI, with percent-escapes decoded.
+fn uri_to_path(uri: &str) -> Option<PathBuf> {
+    let rest = uri.strip_prefix("file://")?;
+    let bytes = rest.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            if let Some(byte) = rest.get(i + 1..i + 3).and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                decoded.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+    let path = String::from_utf8(decoded).ok()?;
+    // Windows URIs look like file:///C:/dir
+    let path = match path.strip_prefix('/') {
+        Some(windows) if windows.get(1..2) == Some(":") => windows.to_string(),
+        _ => path,
+    };
+    Some(PathBuf::from(path))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn server(dir: &Path) -> Server {
+        let config = dir.join("guardrails.toml");
+        fs::write(
+            &config,
+            "[guardrails]\n[[rule]]\nid = \"tokens\"\ntype = \"tailwind-theme-tokens\"\nseverity = \"error\"\nglob = \"**/*.tsx\"\n",
+        )
+        .unwrap();
+        Server::new(&config)
+    }
+
+    fn uri(dir: &Path, file: &str) -> String {
+        format!("file://{}", dir.join(file).display()).replace(' ', "%20")
+    }
+
+    fn open(server: &mut Server, uri: &str, text: &str) -> Vec<serde_json::Value> {
+        server.handle(&json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/didOpen",
+            "params": { "textDocument": { "uri": uri, "languageId": "typescriptreact", "version": 1, "text": text } }
+        }))
+    }
+
+    #[test]
+    fn messages_round_trip_through_content_length_framing() {
+        let mut out = Vec::new();
+        write_message(&mut out, &json!({ "id": 1, "method": "initialize" })).unwrap();
+        assert!(out.starts_with(b"Content-Length: 30\r\n\r\n"));
+
+        let mut reader = io::Cursor::new(out);
+        assert_eq!(read_message(&mut reader).unwrap(), Some(json!({ "id": 1, "method": "initialize" })));
+        assert_eq!(read_message(&mut reader).unwrap(), None);
+    }
+
+    #[test]
+    fn open_and_change_publish_diagnostics() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut server = server(dir.path());
+        let uri = uri(dir.path(), "app view.tsx");
+
+        let out = open(&mut server, &uri, "const é = <div className=\"bg-white\" />;\n");
+        assert_eq!(out[0]["method"], "textDocument/publishDiagnostics");
+        let diagnostic = &out[0]["params"]["diagnostics"][0];
+        assert_eq!(diagnostic["code"], "tokens");
+        assert_eq!(diagnostic["severity"], 1);
+        // "é" is two bytes but one UTF-16 unit
+        assert_eq!(diagnostic["range"]["start"], json!({ "line": 0, "character": 26 }));
+        assert_eq!(diagnostic["range"]["end"], json!({ "line": 0, "character": 34 }));
+
+        let out = server.handle(&json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/didChange",
+            "params": { "textDocument": { "uri": uri, "version": 2 }, "contentChanges": [{ "text": "ok();\n" }] }
+        }));
+        assert_eq!(out[0]["params"]["diagnostics"], json!([]));
+
+        // Files outside the rule's glob get no diagnostics
+        let out = open(&mut server, &self::uri(dir.path(), "app.css"), ".x { bg-white }\n");
+        assert_eq!(out[0]["params"]["diagnostics"], json!([]));
+    }
+
+    #[test]
+    fn code_actions_apply_fix_data() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut server = server(dir.path());
+        let uri = uri(dir.path(), "a.tsx");
+        open(&mut server, &uri, "ok();\n<div className=\"p-2 bg-white\" />\n");
+
+        let out = server.handle(&json!({
+            "jsonrpc": "2.0",
+            "id": 7,
+            "method": "textDocument/codeAction",
+            "params": {
+                "textDocument": { "uri": uri },
+                "range": { "start": { "line": 1, "character": 0 }, "end": { "line": 1, "character": 0 } },
+                "context": { "diagnostics": [] }
+            }
+        }));
+        assert_eq!(out[0]["id"], 7);
+        let action = &out[0]["result"][0];
+        assert_eq!(action["kind"], "quickfix");
+        let edit = &action["edit"]["changes"][uri.as_str()][0];
+        assert_eq!(edit["newText"], "bg-background");
+        assert_eq!(edit["range"]["start"], json!({ "line": 1, "character": 20 }));
+        assert_eq!(edit["range"]["end"], json!({ "line": 1, "character": 28 }));
+    }
+
+    #[test]
+    fn saving_the_config_republishes_open_documents() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut server = server(dir.path());
+        let uri = uri(dir.path(), "a.tsx");
+        assert_eq!(open(&mut server, &uri, "<div className=\"bg-white\" />\n")[0]["params"]["diagnostics"].as_array().unwrap().len(), 1);
+
+        fs::write(dir.path().join("guardrails.toml"), "[guardrails]\n").unwrap();
+        let out = server.handle(&json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/didSave",
+            "params": { "textDocument": { "uri": self::uri(dir.path(), "guardrails.toml") } }
+        }));
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0]["params"]["uri"], uri.as_str());
+        assert_eq!(out[0]["params"]["diagnostics"], json!([]));
+    }
+
+    #[test]
+    fn unknown_requests_error_and_notifications_are_ignored() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut server = server(dir.path());
+        let out = server.handle(&json!({ "jsonrpc": "2.0", "id": 3, "method": "textDocument/hover" }));
+        assert_eq!(out[0]["error"]["code"], -32601);
+        assert!(server.handle(&json!({ "jsonrpc": "2.0", "method": "$/setTrace" })).is_empty());
+    }
+
+    #[test]
+    fn file_uris_decode_to_paths() {
+        assert_eq!(uri_to_path("file:///home/me/my%20app/a.ts"), Some(PathBuf::from("/home/me/my app/a.ts")));
+        assert_eq!(uri_to_path("file:///C%3A/src/a.ts"), Some(PathBuf::from("C:/src/a.ts")));
+        assert_eq!(uri_to_path("untitled:Untitled-1"), None);
+    }
+}
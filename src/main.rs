@@ -1,177 +1,524 @@
 use clap::Parser;
+use guardrails::alloc_stats::CountingAlloc;
 use guardrails::cli::format;
-use guardrails::cli::{Cli, Commands, OutputFormat};
+use guardrails::cli::scan_target::ScanTarget;
+use guardrails::cli::{
+    BundleCommand, CacheCommand, Cli, Commands, GroupBy, HookCommand, JunitCases, OutputFormat, ProgressFormat, RatchetCommand,
+    ReportFormat, SampleMode, ScanArgs, SeverityLevel,
+};
+use guardrails::bundle::{self, BundleError};
+use guardrails::check_config;
+use guardrails::codeowners::RepoCodeOwners;
+use guardrails::compile_cache;
 use guardrails::config::Severity;
-use guardrails::git_diff;
+use guardrails::config_diff;
+use guardrails::doctor;
+use guardrails::extends;
+use guardrails::hook;
+use guardrails::i18n::Lang;
+use guardrails::incremental;
 use guardrails::init;
+use guardrails::lsp;
 use guardrails::mcp;
+use guardrails::metrics;
+use guardrails::new_rule::{self, RuleDraft};
+use guardrails::presets;
+use guardrails::preview;
+use guardrails::ratchet_update;
+use guardrails::report::{self, SavedResult};
+use guardrails::result_diff;
+use guardrails::rule_tests;
+use guardrails::progress::ProgressSink;
+use guardrails::rules::Violation;
+use guardrails::sample::{self, SampleOptions, SampleStrategy};
 use guardrails::scan;
+use guardrails::upload::{self, Metadata};
+use guardrails::waivers::Waiver;
 use std::fs;
-use std::io::Read;
+use std::io::{IsTerminal, Read, Write};
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
 use std::process;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+#[global_allocator]
+static ALLOC: CountingAlloc = CountingAlloc;
 
 fn main() {
     let cli = Cli::parse();
+    let lang = cli.lang.or_else(Lang::from_env).unwrap_or_default();
 
     match cli.command {
-        Commands::Scan {
+        Commands::Scan(args) => scan_command(args, lang),
+
+        Commands::Baseline {
             paths,
+            config,
+            output,
+        } => {
+            let result = match scan::run_baseline(&config, &paths) {
+                Ok(r) => r,
+                Err(e) => {
+                    eprintln!("\x1b[31m{}\x1b[0m: {}", lang.error_label(), e);
+                    process::exit(2);
+                }
+            };
+
+            let json = serde_json::to_string_pretty(&result).unwrap();
+            if let Err(e) = fs::write(&output, &json) {
+                eprintln!("\x1b[31m{}\x1b[0m: failed to write baseline: {}", lang.error_label(), e);
+                process::exit(2);
+            }
+
+            eprintln!(
+                "\x1b[32m✓\x1b[0m Baseline written to {} ({} violation{}, {} ratchet rule{}, {} files scanned)",
+                output.display(),
+                result.violations.len(),
+                if result.violations.len() == 1 { "" } else { "s" },
+                result.entries.len(),
+                if result.entries.len() == 1 { "" } else { "s" },
+                result.files_scanned
+            );
+
+            for entry in &result.entries {
+                eprintln!(
+                    "  {:<30} {} occurrence{}",
+                    entry.rule_id,
+                    entry.count,
+                    if entry.count == 1 { "" } else { "s" }
+                );
+            }
+        }
+
+        Commands::Ratchet {
+            command:
+                RatchetCommand::Update {
+                    paths,
+                    config,
+                    dry_run,
+                    format: output_format,
+                },
+        } => {
+            let update = match ratchet_update::run_ratchet_update(&config, &paths, dry_run) {
+                Ok(u) => u,
+                Err(e) => {
+                    eprintln!("\x1b[31m{}\x1b[0m: {}", lang.error_label(), e);
+                    process::exit(2);
+                }
+            };
+
+            match output_format {
+                OutputFormat::Json => format::print_ratchet_update_json(&update),
+                _ => format::print_ratchet_update_pretty(&update, dry_run),
+            }
+        }
+
+        Commands::UpdateConfig {
             config,
             format: output_format,
-            stdin,
-            filename,
-            changed_only,
-            base,
-            fix,
-            dry_run,
         } => {
-            let result = if stdin {
-                // Read from stdin
-                let mut content = String::new();
-                std::io::stdin().read_to_string(&mut content).unwrap_or_else(|e| {
-                    eprintln!("\x1b[31merror\x1b[0m: failed to read stdin: {}", e);
+            let changes = match extends::update_lock(&config) {
+                Ok(changes) => changes,
+                Err(e) => {
+                    eprintln!("\x1b[31m{}\x1b[0m: {}", lang.error_label(), e);
                     process::exit(2);
-                });
-                let fname = filename.as_deref().unwrap_or("stdin.tsx");
-                match scan::run_scan_stdin(&config, &content, fname) {
-                    Ok(r) => r,
-                    Err(e) => {
-                        eprintln!("\x1b[31merror\x1b[0m: {}", e);
-                        process::exit(2);
-                    }
                 }
-            } else if changed_only {
-                let base_ref = base.unwrap_or_else(|| git_diff::detect_base_ref());
-                match scan::run_scan_changed(&config, &paths, &base_ref) {
-                    Ok(r) => r,
-                    Err(scan::ScanError::GitDiff(ref msg)) => {
-                        eprintln!("\x1b[31merror\x1b[0m: {}", msg);
-                        eprintln!(
-                            "\x1b[90mhint\x1b[0m: --changed-only requires a git repository with the base branch available"
-                        );
-                        process::exit(2);
-                    }
-                    Err(scan::ScanError::ConfigRead(ref e))
-                        if e.kind() == std::io::ErrorKind::NotFound =>
-                    {
-                        eprintln!(
-                            "\x1b[31merror\x1b[0m: config file '{}' not found",
-                            config.display()
-                        );
-                        eprintln!(
-                            "\x1b[90mhint\x1b[0m: run \x1b[1mguardrails init\x1b[0m to generate a starter config"
-                        );
-                        process::exit(2);
-                    }
-                    Err(e) => {
-                        eprintln!("\x1b[31merror\x1b[0m: {}", e);
+            };
+
+            match output_format {
+                OutputFormat::Json => format::print_lock_update_json(&changes),
+                _ => format::print_lock_update_pretty(&changes),
+            }
+        }
+
+        Commands::Cache {
+            command: CacheCommand::Clear { config },
+        } => match incremental::clear_cache(scan::config_dir(&config)) {
+            Ok(true) => eprintln!("\x1b[32m✓\x1b[0m Cleared {}", incremental::CACHE_DIR),
+            Ok(false) => eprintln!("No {} to clear", incremental::CACHE_DIR),
+            Err(e) => {
+                eprintln!("\x1b[31m{}\x1b[0m: failed to clear {}: {}", lang.error_label(), incremental::CACHE_DIR, e);
+                process::exit(2);
+            }
+        },
+
+        Commands::Hook {
+            command: HookCommand::Install {
+                pre_commit_framework: true,
+                ..
+            },
+        } => print!("{}", hook::PRE_COMMIT_CONFIG),
+
+        Commands::Hook {
+            command: HookCommand::Install { force, .. },
+        } => match hook::install(force) {
+            Ok(path) => eprintln!(
+                "\x1b[32m✓\x1b[0m Installed {} (runs `guardrails scan --staged` before each commit)",
+                path.display()
+            ),
+            Err(e) => {
+                eprintln!("\x1b[31m{}\x1b[0m: {}", lang.error_label(), e);
+                process::exit(2);
+            }
+        },
+
+        Commands::Bundle {
+            command:
+                BundleCommand::Export {
+                    config,
+                    output,
+                    policy_version,
+                    key_env,
+                },
+        } => {
+            let key = bundle_key(&key_env, lang);
+            let bundle = bundle::export(&config, policy_version.as_deref(), &key).unwrap_or_else(|e| {
+                eprintln!("\x1b[31m{}\x1b[0m: {}", lang.error_label(), e);
+                process::exit(2);
+            });
+            let text = serde_json::to_string_pretty(&bundle).unwrap();
+            match output {
+                Some(path) => {
+                    if let Err(e) = fs::write(&path, format!("{}\n", text)) {
+                        eprintln!("\x1b[31m{}\x1b[0m: failed to write {}: {}", lang.error_label(), path.display(), e);
                         process::exit(2);
                     }
+                    eprintln!(
+                        "\x1b[32m✓\x1b[0m Wrote {} ({})",
+                        path.display(),
+                        bundle["payload"]["fingerprint"].as_str().unwrap_or_default()
+                    );
                 }
-            } else {
-                match scan::run_scan(&config, &paths) {
-                    Ok(r) => r,
-                    Err(scan::ScanError::ConfigRead(ref e))
-                        if e.kind() == std::io::ErrorKind::NotFound =>
-                    {
-                        eprintln!(
-                            "\x1b[31merror\x1b[0m: config file '{}' not found",
-                            config.display()
-                        );
-                        eprintln!(
-                            "\x1b[90mhint\x1b[0m: run \x1b[1mguardrails init\x1b[0m to generate a starter config"
-                        );
-                        process::exit(2);
-                    }
-                    Err(e) => {
-                        eprintln!("\x1b[31merror\x1b[0m: {}", e);
-                        process::exit(2);
-                    }
+                None => println!("{}", text),
+            }
+        }
+
+        Commands::Bundle {
+            command:
+                BundleCommand::Verify {
+                    bundle: bundle_path,
+                    config,
+                    key_env,
+                    format: output_format,
+                },
+        } => {
+            let key = bundle_key(&key_env, lang);
+            let verification = match bundle::verify(&bundle_path, &config, &key) {
+                Ok(v) => v,
+                Err(e) => {
+                    eprintln!("\x1b[31m{}\x1b[0m: {}", lang.error_label(), e);
+                    process::exit(if matches!(e, BundleError::Signature) { 1 } else { 2 });
                 }
             };
 
-            // Apply fixes if requested
-            if fix && !stdin {
-                let applied = format::apply_fixes(&result, dry_run);
-                if applied > 0 {
-                    if dry_run {
-                        eprintln!(
-                            "\x1b[36m(dry run)\x1b[0m {} fix{} would be applied",
-                            applied,
-                            if applied == 1 { "" } else { "es" }
-                        );
-                    } else {
-                        eprintln!(
-                            "\x1b[32m✓\x1b[0m Applied {} fix{}",
-                            applied,
-                            if applied == 1 { "" } else { "es" }
-                        );
-                    }
+            match output_format {
+                OutputFormat::Json => format::print_bundle_verification_json(&verification),
+                _ => format::print_bundle_verification_pretty(&verification),
+            }
+            if !verification.matches() {
+                process::exit(1);
+            }
+        }
+
+        Commands::ConfigDiff {
+            old,
+            new,
+            paths,
+            format: output_format,
+        } => {
+            let diff = match config_diff::run_config_diff(&old, &new, &paths) {
+                Ok(d) => d,
+                Err(e) => {
+                    eprintln!("\x1b[31m{}\x1b[0m: {}", lang.error_label(), e);
+                    process::exit(2);
                 }
+            };
+
+            match output_format {
+                OutputFormat::Json => format::print_config_diff_json(&diff),
+                _ => format::print_config_diff_pretty(&diff),
             }
+        }
+
+        Commands::DiffResults {
+            old,
+            new,
+            format: output_format,
+            fail_on_new,
+        } => {
+            let diff = match result_diff::run_result_diff(&old, &new) {
+                Ok(d) => d,
+                Err(e) => {
+                    eprintln!("\x1b[31m{}\x1b[0m: {}", lang.error_label(), e);
+                    process::exit(2);
+                }
+            };
 
             match output_format {
-                OutputFormat::Pretty => format::print_pretty(&result),
-                OutputFormat::Json => format::print_json(&result),
-                OutputFormat::Compact => format::print_compact(&result),
-                OutputFormat::Github => format::print_github(&result),
-                OutputFormat::Sarif => format::print_sarif(&result),
-                OutputFormat::Markdown => format::print_markdown(&result),
+                OutputFormat::Json => format::print_result_diff_json(&diff),
+                _ => format::print_result_diff_pretty(&diff),
+            }
+            if fail_on_new && !diff.added.is_empty() {
+                process::exit(1);
+            }
+        }
+
+        Commands::Report { file, format, out } => {
+            let result = SavedResult::load(&file).unwrap_or_else(|e| {
+                eprintln!("\x1b[31m{}\x1b[0m: {}", lang.error_label(), e);
+                process::exit(2);
+            });
+            let mut buf = Vec::new();
+            let _ = match format {
+                ReportFormat::Markdown => report::write_markdown(&result, &mut buf),
+                ReportFormat::Html => report::write_html(&result, &mut buf),
+            };
+            let written = match out {
+                Some(ref path) => fs::write(path, &buf),
+                None => std::io::stdout().write_all(&buf),
+            };
+            if let Err(e) = written {
+                eprintln!("\x1b[31m{}\x1b[0m: failed to write report: {}", lang.error_label(), e);
+                process::exit(2);
             }
+        }
 
-            let has_errors = result
-                .violations
-                .iter()
-                .any(|v| v.severity == Severity::Error);
+        Commands::Doctor {
+            paths,
+            config,
+            format: output_format,
+        } => {
+            let report = match doctor::run_doctor(&config, &paths) {
+                Ok(r) => r,
+                Err(e) => {
+                    eprintln!("\x1b[31m{}\x1b[0m: {}", lang.error_label(), e);
+                    process::exit(2);
+                }
+            };
 
-            process::exit(if has_errors { 1 } else { 0 });
+            match output_format {
+                OutputFormat::Json => format::print_doctor_json(&report),
+                _ => format::print_doctor_pretty(&report),
+            }
+            if !report.findings.is_empty() {
+                process::exit(1);
+            }
         }
 
-        Commands::Baseline {
+        Commands::CheckConfig {
+            path,
+            format: output_format,
+        } => {
+            let report = match check_config::check_config(&path) {
+                Ok(r) => r,
+                Err(e) => {
+                    eprintln!("\x1b[31m{}\x1b[0m: {}: {}", lang.error_label(), path.display(), e);
+                    process::exit(2);
+                }
+            };
+
+            match output_format {
+                OutputFormat::Json => format::print_check_config_json(&report),
+                _ => format::print_check_config_pretty(&report),
+            }
+            if report.errors() > 0 {
+                process::exit(1);
+            }
+        }
+
+        Commands::Test {
             paths,
             config,
-            output,
+            format: output_format,
         } => {
-            let result = match scan::run_baseline(&config, &paths) {
+            let report = match rule_tests::run_tests(&config, &paths) {
                 Ok(r) => r,
                 Err(e) => {
-                    eprintln!("\x1b[31merror\x1b[0m: {}", e);
+                    eprintln!("\x1b[31m{}\x1b[0m: {}", lang.error_label(), e);
                     process::exit(2);
                 }
             };
 
-            let json = serde_json::to_string_pretty(&result).unwrap();
-            if let Err(e) = fs::write(&output, &json) {
-                eprintln!("\x1b[31merror\x1b[0m: failed to write baseline: {}", e);
+            match output_format {
+                OutputFormat::Json => format::print_test_json(&report),
+                _ => format::print_test_pretty(&report),
+            }
+            if report.failed() > 0 {
+                process::exit(1);
+            }
+        }
+
+        Commands::Preview {
+            rule_file,
+            paths,
+            config,
+            format: output_format,
+        } => {
+            let preview = match preview::run_preview(&config, &rule_file, &paths) {
+                Ok(p) => p,
+                Err(e) => {
+                    eprintln!("\x1b[31m{}\x1b[0m: {}", lang.error_label(), e);
+                    process::exit(2);
+                }
+            };
+
+            match output_format {
+                OutputFormat::Json => format::print_preview_json(&preview),
+                _ => format::print_preview_pretty(&preview),
+            }
+        }
+
+        Commands::NewRule { config, sample } => new_rule(&config, sample, lang),
+
+        Commands::Waive {
+            rule_id,
+            path,
+            reason,
+            expires,
+            config,
+        } => {
+            let waiver = Waiver {
+                rule: rule_id,
+                path,
+                reason,
+                expires,
+            };
+            match scan::grant_waiver(&config, &waiver) {
+                Ok(file) => eprintln!(
+                    "\x1b[32m✓\x1b[0m Waived '{}' for {} until {} ({})",
+                    waiver.rule,
+                    waiver.path,
+                    waiver.expires,
+                    file.display()
+                ),
+                Err(e) => {
+                    eprintln!("\x1b[31m{}\x1b[0m: {}", lang.error_label(), e);
+                    process::exit(2);
+                }
+            }
+        }
+
+        Commands::Rules {
+            config,
+            format: output_format,
+        } => {
+            let rules = match scan::list_rules(&config) {
+                Ok(r) => r,
+                Err(e) => {
+                    eprintln!("\x1b[31m{}\x1b[0m: {}", lang.error_label(), e);
+                    process::exit(2);
+                }
+            };
+
+            match output_format {
+                OutputFormat::Json => format::print_rules_json(&rules),
+                OutputFormat::Markdown => format::print_rules_markdown(&rules),
+                _ => format::print_rules_pretty(&rules),
+            }
+        }
+
+        Commands::Explain {
+            rule_id,
+            config,
+            format: output_format,
+        } => {
+            let rule = match scan::explain_rule(&config, &rule_id) {
+                Ok(r) => r,
+                Err(e) => {
+                    eprintln!("\x1b[31m{}\x1b[0m: {}", lang.error_label(), e);
+                    process::exit(2);
+                }
+            };
+
+            match output_format {
+                OutputFormat::Json => format::print_rules_json(std::slice::from_ref(&rule)),
+                OutputFormat::Markdown => format::print_explain_markdown(&rule),
+                _ => format::print_explain_pretty(&rule),
+            }
+        }
+
+        Commands::Bench {
+            paths,
+            config,
+            iterations,
+            format: output_format,
+        } => {
+            let report = match scan::run_bench(&config, &paths, iterations) {
+                Ok(r) => r,
+                Err(e) => {
+                    eprintln!("\x1b[31m{}\x1b[0m: {}", lang.error_label(), e);
+                    process::exit(2);
+                }
+            };
+
+            match output_format {
+                OutputFormat::Json => format::print_bench_json(&report),
+                _ => format::print_bench_pretty(&report),
+            }
+        }
+
+        Commands::Upload {
+            file,
+            to,
+            mut headers,
+            token_env,
+            repo,
+            branch,
+            sha,
+        } => {
+            let text = if file.as_os_str() == "-" {
+                let mut text = String::new();
+                std::io::stdin().read_to_string(&mut text).map(|_| text)
+            } else {
+                fs::read_to_string(&file)
+            };
+            let text = text.unwrap_or_else(|e| {
+                eprintln!("\x1b[31m{}\x1b[0m: {}", lang.error_label(), upload::UploadError::Read(e));
                 process::exit(2);
+            });
+
+            if let Some(var) = token_env {
+                match std::env::var(&var) {
+                    Ok(token) if !token.is_empty() => {
+                        headers.push(format!("Authorization: Bearer {}", token));
+                    }
+                    _ => {
+                        eprintln!("\x1b[31m{}\x1b[0m: environment variable {} is not set", lang.error_label(), var);
+                        process::exit(2);
+                    }
+                }
             }
 
-            eprintln!(
-                "\x1b[32m✓\x1b[0m Baseline written to {} ({} ratchet rule{}, {} files scanned)",
-                output.display(),
-                result.entries.len(),
-                if result.entries.len() == 1 { "" } else { "s" },
-                result.files_scanned
-            );
+            let detected = Metadata::detect();
+            let metadata = Metadata {
+                repo: repo.or(detected.repo),
+                branch: branch.or(detected.branch),
+                sha: sha.or(detected.sha),
+            };
 
-            for entry in &result.entries {
-                eprintln!(
-                    "  {:<30} {} occurrence{}",
-                    entry.rule_id,
-                    entry.count,
-                    if entry.count == 1 { "" } else { "s" }
-                );
+            let uploaded = upload::envelope(&text, &metadata)
+                .and_then(|body| upload::post(&to, &headers, &body));
+            if let Err(e) = uploaded {
+                eprintln!("\x1b[31m{}\x1b[0m: {}", lang.error_label(), e);
+                process::exit(2);
             }
+            eprintln!("\x1b[32m✓\x1b[0m Uploaded result to {}", to);
         }
 
         Commands::Mcp { config } => {
             mcp::run_mcp_server(&config);
         }
 
+        Commands::Lsp { config } => {
+            lsp::run_lsp_server(&config);
+        }
+
         Commands::Init { output, force } => {
             if output.exists() && !force {
                 eprintln!(
-                    "\x1b[31merror\x1b[0m: '{}' already exists (use --force to overwrite)",
+                    "\x1b[31m{}\x1b[0m: '{}' already exists (use --force to overwrite)",
+                    lang.error_label(),
                     output.display()
                 );
                 process::exit(2);
@@ -182,7 +529,7 @@ fn main() {
             let config = init::generate_config(&project_type);
 
             if let Err(e) = fs::write(&output, &config) {
-                eprintln!("\x1b[31merror\x1b[0m: failed to write config: {}", e);
+                eprintln!("\x1b[31m{}\x1b[0m: failed to write config: {}", lang.error_label(), e);
                 process::exit(2);
             }
 
@@ -190,6 +537,7 @@ fn main() {
                 init::ProjectType::ShadcnTailwind => "shadcn + Tailwind",
                 init::ProjectType::TailwindOnly => "Tailwind CSS",
                 init::ProjectType::Generic => "generic",
+                init::ProjectType::Rust => "Rust",
                 init::ProjectType::Unknown => "generic",
             };
 
@@ -199,8 +547,640 @@ fn main() {
                 type_label
             );
             eprintln!(
-                "\x1b[90mhint\x1b[0m: run \x1b[1mguardrails scan .\x1b[0m to find violations"
+                "\x1b[90m{}\x1b[0m: run \x1b[1mguardrails scan .\x1b[0m to find violations",
+                lang.hint_label()
+            );
+        }
+    }
+}
+
+/// Offer to create a starter config when `guardrails scan` finds none, so a
+/// first run ends in a scan rather than an IO error. Prompts on a terminal,
+/// creates the detected default with `--yes`, and exits otherwise.
+/// `guardrails scan`: resolve what to scan, scan it, report, and exit with
+/// the scan's status.
+fn scan_command(args: ScanArgs, lang: Lang) {
+    let (fix, dry_run) = (args.fix || args.fix_dry_run, args.dry_run || args.fix_dry_run);
+    // Editors reading a single buffer from stdin want machine-readable output
+    let output_format = args.format.clone().unwrap_or(if args.stdin { OutputFormat::Json } else { OutputFormat::Pretty });
+    let target = ScanTarget::resolve(&args).unwrap_or_else(|e| {
+        eprintln!("\x1b[31m{}\x1b[0m: {}", lang.error_label(), e);
+        process::exit(2);
+    });
+    let (config, inline) = (&target.config, target.inline.as_deref());
+
+    if !args.stdin && target.roots.is_empty() && !scan::is_inline_config(config) && !config.exists() {
+        first_run(config, args.yes, lang);
+    }
+
+    if args.explain || args.list_files || args.strip_fix_markers {
+        let base_ref = target.base_ref.as_deref();
+        let (paths, trust, walk) = (&target.paths, &args.trust, args.walk());
+        let listed = if args.explain {
+            scan::explain_files(config, inline, paths, base_ref, trust, walk).map(|report| match output_format {
+                OutputFormat::Json => format::print_file_report_json(&report),
+                _ => format::print_file_report(&report),
+            })
+        } else {
+            scan::list_files(config, inline, paths, base_ref, trust, walk).map(|files| {
+                if args.list_files {
+                    format::print_file_list(&files, args.null);
+                } else {
+                    let stripped = format::strip_fix_markers(&files);
+                    eprintln!(
+                        "\x1b[32m✓\x1b[0m Removed {} fix marker{}",
+                        stripped,
+                        if stripped == 1 { "" } else { "s" }
+                    );
+                }
+            })
+        };
+        if let Err(e) = listed {
+            eprintln!("\x1b[31m{}\x1b[0m: {}", lang.error_label(), e);
+            process::exit(2);
+        }
+        return;
+    }
+
+    let options = scan_options(&args, &target);
+    let metadata = scan::ScanMetadata::collect(config, inline);
+    let scanned_at = std::time::SystemTime::now();
+    let mut result = run_scan(&args, &target, &options, lang);
+
+    let limits = format::DisplayLimits {
+        max_violations: args.max_violations,
+        max_per_file: args.max_per_file,
+        max_per_rule: args.max_violations_per_rule,
+        group_by: args.group_by,
+        quiet: args.quiet,
+    };
+    let section = scan::output_settings(config, inline).unwrap_or_else(|e| {
+        eprintln!("\x1b[31m{}\x1b[0m: {}", lang.error_label(), e);
+        process::exit(2);
+    });
+    let links = format::Links::detect(section.link_template.clone());
+    let theme = format::Theme::new(&section)
+        .map(|theme| if args.ascii { theme.ascii() } else { theme })
+        .map(|theme| {
+            theme
+                .lang(lang)
+                .ratchet_group_by(args.ratchet_group_by)
+                .limits(limits)
+                .links(links.clone())
+        })
+        .unwrap_or_else(|e| {
+            eprintln!("\x1b[31m{}\x1b[0m: {}", lang.error_label(), e);
+            process::exit(2);
+        });
+
+    result.metadata = Some(metadata);
+
+    let mut baselined = Vec::new();
+    if let Some(ref path) = args.baseline {
+        let known = guardrails::baseline::load(path).unwrap_or_else(|e| {
+            eprintln!("\x1b[31m{}\x1b[0m: {}", lang.error_label(), e);
+            process::exit(2);
+        });
+        baselined = guardrails::baseline::take(&mut result.violations, &known);
+        let suppressed = baselined.len();
+        if suppressed > 0 {
+            result.notices.push(format!(
+                "{} violation{} already in baseline {}",
+                suppressed,
+                if suppressed == 1 { "" } else { "s" },
+                path.display()
+            ));
+        }
+    }
+
+    // Gate on what changed since a saved result rather than on lines
+    if let Some(ref path) = args.compare_to {
+        let comparison = result_diff::compare_to(path, &mut result.violations).unwrap_or_else(|e| {
+            eprintln!("\x1b[31m{}\x1b[0m: {}", lang.error_label(), e);
+            process::exit(2);
+        });
+        result.notices.push(format!(
+            "compared to {}: {} new violation{}, {} fixed, {} unchanged",
+            path.display(),
+            comparison.new,
+            if comparison.new == 1 { "" } else { "s" },
+            comparison.fixed,
+            comparison.unchanged
+        ));
+    }
+
+    // Re-surface a weekly sample of what was hidden, so "temporarily
+    // ignored" debt stays visible without failing the build
+    if let Some(per_rule) = args.audit_sample {
+        let hidden: Vec<(Violation, &str)> = std::mem::take(&mut result.suppressed)
+            .into_iter()
+            .map(|v| (v, "suppressed inline"))
+            .chain(baselined.into_iter().map(|v| (v, "in the baseline")))
+            .collect();
+        let total = hidden.len();
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        let picked = sample::audit(hidden, per_rule, sample::audit_seed(now));
+        if !picked.is_empty() {
+            result.notices.push(format!(
+                "audit sample: showing {} of {} hidden violation{} as warnings (a new sample each week)",
+                picked.len(),
+                total,
+                if total == 1 { "" } else { "s" }
+            ));
+        }
+        result.audited = picked.len();
+        result.violations.extend(picked);
+    }
+
+    if let Some(ref path) = args.metrics_file {
+        let duration = scanned_at.elapsed().unwrap_or_default();
+        let rule_ids = scan::rule_ids(config, inline).unwrap_or_default();
+        let text = metrics::render(&result, &rule_ids, duration, std::time::SystemTime::now());
+        if let Err(e) = metrics::write(path, &text) {
+            eprintln!(
+                "\x1b[31m{}\x1b[0m: failed to write metrics to {}: {}",
+                lang.error_label(),
+                path.display(),
+                e
             );
+            process::exit(2);
         }
     }
+
+    for notice in &result.notices {
+        eprintln!("\x1b[33m{}\x1b[0m: {}", lang.notice_label(), notice);
+    }
+
+    if let Some(reused) = result.reused_files.filter(|&n| n > 0) {
+        eprintln!(
+            "\x1b[90mcache: reused results for {} of {} files\x1b[0m",
+            reused, result.files_scanned
+        );
+    }
+
+    if let Some(ref profile) = result.profile {
+        format::print_timing(profile, args.memory);
+    }
+
+    // Apply fixes if requested
+    if fix && !args.stdin {
+        let fix_options = format::FixOptions {
+            dry_run,
+            backup: args.backup,
+            scanned_at: Some(scanned_at),
+            marker: args.fix_marker,
+            file_types: scan::file_types(config, inline).unwrap_or_else(|e| {
+                eprintln!("\x1b[31m{}\x1b[0m: {}", lang.error_label(), e);
+                process::exit(2);
+            }),
+        };
+        let applied = format::apply_fixes(&result, &fix_options);
+        if applied > 0 {
+            if dry_run {
+                eprintln!(
+                    "\x1b[36m(dry run)\x1b[0m {} fix{} would be applied",
+                    applied,
+                    if applied == 1 { "" } else { "es" }
+                );
+            } else {
+                eprintln!(
+                    "\x1b[32m✓\x1b[0m Applied {} fix{}",
+                    applied,
+                    if applied == 1 { "" } else { "es" }
+                );
+            }
+        }
+    }
+
+    let min_severity = match args.min_severity {
+        Some(SeverityLevel::Error) => Some(Severity::Error),
+        Some(SeverityLevel::Warning) => Some(Severity::Warning),
+        None if args.only_failures => Some(Severity::Error),
+        None => None,
+    };
+    if let Some(min) = min_severity {
+        result.retain_min_severity(min);
+    }
+    if args.only_failures {
+        result.drop_passing_ratchets();
+    }
+
+    let needs_owners = if args.suggest_reviewers {
+        Some("--suggest-reviewers")
+    } else {
+        matches!(args.group_by, Some(GroupBy::Owner)).then_some("--group-by owner")
+    };
+    // Only the outputs that show owners read CODEOWNERS, from the
+    // root of each repository scanned
+    let code_owners = if needs_owners.is_some() || matches!(output_format, OutputFormat::Json) {
+        let scanned: Vec<PathBuf> = if target.roots.is_empty() {
+            target.paths.clone()
+        } else {
+            target.roots.iter().flat_map(|root| root.paths.clone()).collect()
+        };
+        RepoCodeOwners::load(&scanned, &std::env::current_dir().unwrap_or_default())
+    } else {
+        None
+    };
+    if let (Some(flag), None) = (needs_owners, &code_owners) {
+        eprintln!("\x1b[31m{}\x1b[0m: no CODEOWNERS file found", lang.error_label());
+        eprintln!(
+            "\x1b[90m{}\x1b[0m: {} reads .github/CODEOWNERS, CODEOWNERS or docs/CODEOWNERS at the repository root",
+            lang.hint_label(),
+            flag
+        );
+        process::exit(2);
+    }
+    result.code_owners = code_owners.as_ref().map(|owners| owners.attribute(&result.violations));
+
+    if let Some(owners) = code_owners.as_ref().filter(|_| args.suggest_reviewers) {
+        let report = owners.suggest_reviewers(&result.violations);
+        match output_format {
+            OutputFormat::Json => format::print_reviewers_json(&report),
+            _ => format::print_reviewers_pretty(&report),
+        }
+    } else if args.counts_only {
+        match output_format {
+            OutputFormat::Json => format::print_counts_json(&result),
+            _ => format::print_counts_pretty(&result, &theme),
+        }
+    } else if let Some(GroupBy::Dir) = args.group_by {
+        match output_format {
+            OutputFormat::Json => format::print_rollup_json(&result, args.depth),
+            _ => format::print_rollup_pretty(&result, args.depth, &theme),
+        }
+    } else {
+        match output_format {
+            OutputFormat::Pretty => format::print_pretty(&result, &theme),
+            OutputFormat::Json => format::print_json(&result, args.redact_source, limits),
+            OutputFormat::Compact => format::print_compact(&result),
+            OutputFormat::Github => format::print_github(&result, &*scan::new_in_diff(&result)),
+            OutputFormat::Sarif => format::print_sarif(&result, args.redact_source),
+            OutputFormat::Markdown => format::print_markdown(&result, &links),
+            OutputFormat::Junit => format::print_junit(
+                &result,
+                &scan::rule_ids(config, inline).unwrap_or_default(),
+                matches!(args.junit_cases, JunitCases::Violation),
+            ),
+            OutputFormat::Codeclimate => format::print_codeclimate(&result),
+        }
+    }
+
+    if result.incomplete {
+        eprintln!(
+            "\x1b[33m{}\x1b[0m: {}",
+            lang.warning_label(),
+            lang.scan_interrupted()
+        );
+        if let Some(ref progress) = args.resume {
+            eprintln!(
+                "\x1b[90m{}\x1b[0m: progress saved to {}; rerun with --resume to continue",
+                lang.hint_label(),
+                progress.display()
+            );
+        }
+        process::exit(130);
+    }
+
+    let fail_on = match args.fail_on {
+        Some(level) => level,
+        None => scan::fail_on(config, inline).ok().flatten().unwrap_or_default(),
+    };
+    if let Some(max) = args.max_warnings {
+        let warnings = result.totals().1.saturating_sub(result.audited);
+        if warnings > max {
+            eprintln!(
+                "\x1b[31m{}\x1b[0m: {} warnings exceed --max-warnings {}",
+                lang.error_label(),
+                warnings,
+                max
+            );
+        }
+    }
+
+    process::exit(if result.fails(fail_on, args.max_warnings) { 1 } else { 0 });
+}
+
+/// The options `guardrails scan` runs the scan with.
+fn scan_options(args: &ScanArgs, target: &ScanTarget) -> scan::ScanOptions {
+    // The scan cache is on wherever --incremental is allowed; timings
+    // would only measure the cache
+    let cacheable = !args.no_cache
+        && !args.stdin
+        && target.base_ref.is_none()
+        && args.sample.is_none()
+        && args.resume.is_none()
+        && !args.report_suppressions
+        && args.audit_sample.is_none()
+        && !args.timing;
+    let incremental = args.incremental.clone().or_else(|| {
+        cacheable.then(|| scan::config_dir(&target.config).join(incremental::SCAN_CACHE_FILE))
+    });
+    scan::ScanOptions {
+        sample: args.sample.map(|size| SampleOptions {
+            size,
+            strategy: match args.sample_mode {
+                SampleMode::Random => SampleStrategy::Random,
+                SampleMode::Stratified => SampleStrategy::Stratified,
+            },
+            seed: args.seed.unwrap_or_else(|| {
+                std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map(|d| d.as_nanos() as u64)
+                    .unwrap_or_default()
+            }),
+        }),
+        timing: args.timing,
+        jobs: args.jobs.map(NonZeroUsize::get),
+        cancel: (!args.stdin).then(install_cancel_handler),
+        resume: args.resume.clone(),
+        counts_only: args.counts_only,
+        incremental,
+        trust: args.trust.clone(),
+        report_suppressions: args.report_suppressions,
+        keep_suppressed: args.audit_sample.is_some(),
+        progress: args.progress.map(|ProgressFormat::Json| ProgressSink::json_lines()),
+        walk: args.walk(),
+        violations: None,
+        strict_encoding: args.strict_encoding,
+        config_text: target.inline.clone(),
+        compile_cache: (!args.no_cache).then(|| scan::config_dir(&target.config).join(compile_cache::COMPILE_CACHE_FILE)),
+    }
+}
+
+/// Scan stdin, the roots, the changes against the base ref, or the paths,
+/// exiting with a hint when the config or the diff can't be read.
+fn run_scan(args: &ScanArgs, target: &ScanTarget, options: &scan::ScanOptions, lang: Lang) -> scan::ScanResult {
+    let (config, inline) = (&target.config, target.inline.as_deref());
+    let scanned = if args.stdin {
+        let mut content = String::new();
+        std::io::stdin().read_to_string(&mut content).unwrap_or_else(|e| {
+            eprintln!("\x1b[31m{}\x1b[0m: failed to read stdin: {}", lang.error_label(), e);
+            process::exit(2);
+        });
+        let fname = args.filename.as_deref().unwrap_or("stdin.tsx");
+        scan::run_scan_stdin(config, inline, &content, fname)
+    } else if !target.roots.is_empty() {
+        scan::run_scan_roots(&target.roots, options, |config, paths, options| match target.base_ref {
+            Some(ref base_ref) => scan::run_scan_changed(config, paths, base_ref, options),
+            None => scan::run_scan_with_options(config, paths, options),
+        })
+    } else {
+        match target.base_ref {
+            Some(ref base_ref) => scan::run_scan_changed(config, &target.paths, base_ref, options),
+            None => scan::run_scan_with_options(config, &target.paths, options),
+        }
+    };
+    match scanned {
+        Ok(r) => r,
+        Err(scan::ScanError::GitDiff(ref msg)) if !args.stdin && target.base_ref.is_some() => {
+            eprintln!("\x1b[31m{}\x1b[0m: {}", lang.error_label(), msg);
+            eprintln!(
+                "\x1b[90m{}\x1b[0m: --changed-only requires a git repository with the base branch available",
+                lang.hint_label()
+            );
+            process::exit(2);
+        }
+        Err(scan::ScanError::ConfigRead(ref e)) if !args.stdin && e.kind() == std::io::ErrorKind::NotFound => {
+            eprintln!(
+                "\x1b[31m{}\x1b[0m: {}",
+                lang.error_label(),
+                lang.config_not_found(&config.display().to_string())
+            );
+            eprintln!(
+                "\x1b[90m{}\x1b[0m: {}",
+                lang.hint_label(),
+                lang.run_init_hint("\x1b[1mguardrails init\x1b[0m")
+            );
+            process::exit(2);
+        }
+        Err(e) => {
+            eprintln!("\x1b[31m{}\x1b[0m: {}", lang.error_label(), e);
+            process::exit(2);
+        }
+    }
+}
+
+fn first_run(config: &Path, yes: bool, lang: Lang) {
+    let interactive = !yes && std::io::stdin().is_terminal();
+    if !yes && !interactive {
+        eprintln!(
+            "\x1b[31m{}\x1b[0m: no config found at {}",
+            lang.error_label(),
+            config.display()
+        );
+        eprintln!(
+            "\x1b[90m{}\x1b[0m: run \x1b[1mguardrails init\x1b[0m to create one, or pass --yes to create one and scan",
+            lang.hint_label()
+        );
+        process::exit(2);
+    }
+
+    let project_dir = std::env::current_dir().unwrap_or_default();
+    let project_type = init::detect_project(&project_dir);
+    let mut presets = init::default_presets(&project_type);
+
+    if interactive {
+        eprintln!("No {} found — guardrails needs a config to know what to enforce.", config.display());
+        let answer = prompt("Create a starter config now? [Y/n] ");
+        if answer.eq_ignore_ascii_case("n") || answer.eq_ignore_ascii_case("no") {
+            eprintln!(
+                "\x1b[90m{}\x1b[0m: run \x1b[1mguardrails init\x1b[0m when you're ready",
+                lang.hint_label()
+            );
+            process::exit(2);
+        }
+        let default = if presets.is_empty() { "none".to_string() } else { presets.join(", ") };
+        loop {
+            let answer = prompt(&format!(
+                "Presets to extend ({}, or none) [{}]: ",
+                presets::available_presets().join(", "),
+                default
+            ));
+            if answer.is_empty() {
+                break;
+            }
+            match init::parse_presets(&answer) {
+                Ok(chosen) => {
+                    presets = chosen;
+                    break;
+                }
+                Err(e) => eprintln!("\x1b[31m{}\x1b[0m: {}", lang.error_label(), e),
+            }
+        }
+    }
+
+    let text = init::set_presets(&init::generate_config(&project_type), &presets);
+    if let Err(e) = fs::write(config, text) {
+        eprintln!("\x1b[31m{}\x1b[0m: failed to write config: {}", lang.error_label(), e);
+        process::exit(2);
+    }
+    eprintln!("\x1b[32m✓\x1b[0m Created {}; scanning with it now\n", config.display());
+}
+
+/// `guardrails new-rule`: ask for each part of a pattern rule, previewing the
+/// pattern's matches in a sample file, then validate the rule and append it.
+fn new_rule(config: &Path, sample: Option<PathBuf>, lang: Lang) {
+    // Every question is asked again until it gets an answer, so stop at EOF
+    let ask = |question: &str| {
+        prompt_line(question).unwrap_or_else(|| {
+            eprintln!("\n\x1b[31m{}\x1b[0m: no rule added (input ended)", lang.error_label());
+            process::exit(2);
+        })
+    };
+    let rule_type = loop {
+        let answer = ask(&format!("Rule type ({}) [banned-pattern]: ", new_rule::RULE_TYPES.join(", ")));
+        let answer = if answer.is_empty() { "banned-pattern".to_string() } else { answer };
+        if new_rule::RULE_TYPES.contains(&answer.as_str()) {
+            break answer;
+        }
+        eprintln!("\x1b[31m{}\x1b[0m: unsupported rule type '{}'", lang.error_label(), answer);
+    };
+    let mut draft = RuleDraft {
+        rule_type,
+        ..Default::default()
+    };
+    let existing = scan::rule_ids(config, None).unwrap_or_default();
+    draft.id = loop {
+        let answer = ask("Rule id (e.g. no-moment): ");
+        if answer.is_empty() {
+            continue;
+        }
+        if !existing.contains(&answer) {
+            break answer;
+        }
+        eprintln!("\x1b[31m{}\x1b[0m: {} already has a rule '{}'", lang.error_label(), config.display(), answer);
+    };
+
+    let sample = sample.or_else(|| {
+        let answer = ask("Sample file to preview matches in (empty to skip): ");
+        (!answer.is_empty()).then(|| PathBuf::from(answer))
+    });
+    let sample_text = sample.as_ref().and_then(|path| match fs::read_to_string(path) {
+        Ok(text) => Some(text),
+        Err(e) => {
+            eprintln!("\x1b[33mwarning\x1b[0m: can't preview in {}: {}", path.display(), e);
+            None
+        }
+    });
+    loop {
+        draft.pattern = ask("Pattern: ");
+        if draft.pattern.is_empty() {
+            continue;
+        }
+        draft.regex = ask("Is it a regular expression? [y/N]: ").eq_ignore_ascii_case("y");
+        if let (Some(path), Some(text)) = (sample.as_ref(), sample_text.as_ref()) {
+            match new_rule::preview(&draft, path, text) {
+                Ok(matches) if matches.is_empty() => eprintln!("\x1b[90mno matches in {}\x1b[0m", path.display()),
+                Ok(matches) => {
+                    for v in &matches {
+                        eprintln!(
+                            "  \x1b[90m{}:{}\x1b[0m  {}",
+                            v.line.unwrap_or(0),
+                            v.column.unwrap_or(0),
+                            v.source_line.as_deref().unwrap_or("").trim()
+                        );
+                    }
+                    eprintln!("\x1b[90m{} match(es) in {}\x1b[0m", matches.len(), path.display());
+                }
+                Err(e) => {
+                    eprintln!("\x1b[31m{}\x1b[0m: {}", lang.error_label(), e);
+                    continue;
+                }
+            }
+        }
+        if !ask("Keep this pattern? [Y/n]: ").eq_ignore_ascii_case("n") {
+            break;
+        }
+    }
+
+    let glob = ask("Files it applies to, as a glob relative to the config (empty for all): ");
+    draft.glob = (!glob.is_empty()).then_some(glob);
+    if draft.rule_type == "ratchet" {
+        let current = new_rule::count_matches(config, &draft, &[scan::config_dir(config).to_path_buf()]).unwrap_or(0);
+        draft.max_count = Some(loop {
+            let answer = ask(&format!("Budget (max_count, found today: {}) [{}]: ", current, current));
+            if answer.is_empty() {
+                break current;
+            }
+            match answer.parse() {
+                Ok(n) => break n,
+                Err(_) => eprintln!("\x1b[31m{}\x1b[0m: '{}' isn't a number", lang.error_label(), answer),
+            }
+        });
+    }
+    draft.severity = loop {
+        let answer = ask("Severity (warning, error) [warning]: ");
+        match answer.as_str() {
+            "" => break "warning".to_string(),
+            "warning" | "error" => break answer,
+            _ => eprintln!("\x1b[31m{}\x1b[0m: expected warning or error", lang.error_label()),
+        }
+    };
+    draft.message = ask("Message shown with each violation: ");
+
+    if let Err(e) = new_rule::validate(config, &draft) {
+        eprintln!("\x1b[31m{}\x1b[0m: {}", lang.error_label(), e);
+        process::exit(2);
+    }
+    eprintln!("\n{}", draft.to_toml());
+    if ask(&format!("Append to {}? [Y/n]: ", config.display())).eq_ignore_ascii_case("n") {
+        return;
+    }
+    if let Err(e) = new_rule::append(config, &draft) {
+        eprintln!("\x1b[31m{}\x1b[0m: failed to write {}: {}", lang.error_label(), config.display(), e);
+        process::exit(2);
+    }
+    eprintln!("\x1b[32m✓\x1b[0m Added rule '{}' to {}", draft.id, config.display());
+}
+
+/// The policy bundle signing key from environment variable `var`; exits
+/// when it isn't set.
+fn bundle_key(var: &str, lang: Lang) -> Vec<u8> {
+    match std::env::var(var) {
+        Ok(key) if !key.is_empty() => key.into_bytes(),
+        _ => {
+            eprintln!(
+                "\x1b[31m{}\x1b[0m: environment variable {} with the bundle signing key is not set",
+                lang.error_label(),
+                var
+            );
+            process::exit(2);
+        }
+    }
+}
+
+/// Ask `question` on stderr and read one trimmed line of stdin (empty at EOF).
+fn prompt(question: &str) -> String {
+    prompt_line(question).unwrap_or_default()
+}
+
+/// Ask `question` on stderr and read one trimmed line of stdin, or `None` at EOF.
+fn prompt_line(question: &str) -> Option<String> {
+    eprint!("{}", question);
+    let _ = std::io::stderr().flush();
+    let mut answer = String::new();
+    match std::io::stdin().read_line(&mut answer) {
+        Ok(0) | Err(_) => None,
+        Ok(_) => Some(answer.trim().to_string()),
+    }
+}
+
+/// Install a Ctrl-C handler that asks the scan to stop early so partial
+/// results can still be printed. A second Ctrl-C aborts immediately.
+fn install_cancel_handler() -> Arc<AtomicBool> {
+    let cancel = Arc::new(AtomicBool::new(false));
+    let flag = Arc::clone(&cancel);
+    let _ = ctrlc::set_handler(move || {
+        if flag.swap(true, Ordering::SeqCst) {
+            process::exit(130);
+        }
+        eprintln!(
+            "\n\x1b[33minterrupted\x1b[0m: finishing up with partial results (Ctrl-C again to abort)"
+        );
+    });
+    cancel
 }
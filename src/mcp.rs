@@ -1,6 +1,6 @@
 use crate::cli::toml_config::TomlConfig;
 use crate::presets;
-use crate::scan;
+use crate::scan::{self, ScanSession};
 use serde_json::json;
 use std::io::{self, BufRead, Write};
 use std::path::{Path, PathBuf};
@@ -9,8 +9,10 @@ use std::path::{Path, PathBuf};
 ///
 /// Reads JSON-RPC requests from stdin, processes them, and writes
 /// JSON-RPC responses to stdout. Supports the MCP protocol for
-/// tool discovery and execution.
+/// tool discovery and execution. Compiled rules and per-file results are
+/// kept warm across requests in a `ScanSession`.
 pub fn run_mcp_server(config_path: &Path) {
+    let mut session = ScanSession::new(config_path);
     let stdin = io::stdin();
     let mut stdout = io::stdout();
 
@@ -46,7 +48,7 @@ pub fn run_mcp_server(config_path: &Path) {
         let response = match method {
             "initialize" => handle_initialize(id.clone()),
             "tools/list" => handle_tools_list(id.clone()),
-            "tools/call" => handle_tools_call(id.clone(), &params, config_path),
+            "tools/call" => handle_tools_call(id.clone(), &params, config_path, &mut session),
             "notifications/initialized" | "notifications/cancelled" => continue,
             _ => json!({
                 "jsonrpc": "2.0",
@@ -122,6 +124,7 @@ fn handle_tools_call(
     id: Option<serde_json::Value>,
     params: &serde_json::Value,
     config_path: &Path,
+    session: &mut ScanSession,
 ) -> serde_json::Value {
     let tool_name = params
         .get("name")
@@ -131,7 +134,7 @@ fn handle_tools_call(
     let arguments = params.get("arguments").cloned().unwrap_or(json!({}));
 
     match tool_name {
-        "guardrails_scan" => handle_scan(&id, &arguments, config_path),
+        "guardrails_scan" => handle_scan(&id, &arguments, session),
         "guardrails_list_rules" => handle_list_rules(&id, config_path),
         _ => json!({
             "jsonrpc": "2.0",
@@ -144,7 +147,7 @@ fn handle_tools_call(
 fn handle_scan(
     id: &Option<serde_json::Value>,
     arguments: &serde_json::Value,
-    config_path: &Path,
+    session: &mut ScanSession,
 ) -> serde_json::Value {
     // Check for inline content mode
     if let Some(content) = arguments.get("content").and_then(|c| c.as_str()) {
@@ -153,7 +156,7 @@ fn handle_scan(
             .and_then(|f| f.as_str())
             .unwrap_or("stdin.tsx");
 
-        match scan::run_scan_stdin(config_path, content, filename) {
+        match session.scan_content(content, filename) {
             Ok(result) => {
                 let violations = format_violations_json(&result);
                 json!({
@@ -185,7 +188,7 @@ fn handle_scan(
             })
             .unwrap_or_else(|| vec![PathBuf::from(".")]);
 
-        match scan::run_scan(config_path, &paths) {
+        match session.scan_paths(&paths) {
             Ok(result) => {
                 let violations = format_violations_json(&result);
                 json!({
@@ -212,7 +215,7 @@ fn handle_list_rules(
     id: &Option<serde_json::Value>,
     config_path: &Path,
 ) -> serde_json::Value {
-    let config_text = match std::fs::read_to_string(config_path) {
+    let config_text = match scan::interpolated_config(config_path, None) {
         Ok(c) => c,
         Err(e) => {
             return json!({
@@ -240,7 +243,12 @@ fn handle_list_rules(
         }
     };
 
-    let resolved = match presets::resolve_rules(&toml_config.guardrails.extends, &toml_config.rule) {
+    let resolved = match presets::resolve_rules(
+        scan::config_dir(config_path),
+        &toml_config.guardrails.extends,
+        &toml_config.rule,
+        &toml_config.rule_overrides(),
+    ) {
         Ok(r) => r,
         Err(e) => {
             return json!({
@@ -263,6 +271,7 @@ fn handle_list_rules(
                 "severity": r.severity,
                 "glob": r.glob,
                 "message": r.message,
+                "owner": r.ownership(),
             })
         })
         .collect();
@@ -299,7 +308,23 @@ fn format_violations_json(result: &scan::ScanResult) -> serde_json::Value {
             });
 
             if let Some(ref fix) = v.fix {
-                obj["fix"] = json!({ "old": fix.old, "new": fix.new });
+                obj["fix"] = json!(fix);
+            }
+
+            if let Some(owner) = result.owners.get(&v.rule_id) {
+                obj["owner"] = json!(owner);
+            }
+
+            if let Some(ref url) = v.url {
+                obj["url"] = json!(url);
+            }
+
+            if let Some(suggestion) = result
+                .suggestions
+                .get(&v.rule_id)
+                .filter(|s| v.suggest.as_deref() == Some(s.title.as_str()))
+            {
+                obj["suggestion"] = json!(suggestion);
             }
 
             obj
@@ -322,7 +347,6 @@ mod tests {
     use super::*;
     use crate::config::Severity;
     use crate::rules::Violation;
-    use std::collections::HashMap;
     use std::path::PathBuf;
 
     #[test]
@@ -350,9 +374,7 @@ mod tests {
             violations: vec![],
             files_scanned: 3,
             rules_loaded: 2,
-            ratchet_counts: HashMap::new(),
-            changed_files_count: None,
-            base_ref: None,
+            ..Default::default()
         };
         let json = format_violations_json(&result);
         assert_eq!(json["summary"]["total"], 0);
@@ -375,13 +397,13 @@ mod tests {
                 fix: Some(crate::rules::Fix {
                     old: "bg-red-500".into(),
                     new: "bg-destructive".into(),
+                    range: None,
                 }),
+                url: None,
             }],
             files_scanned: 1,
             rules_loaded: 1,
-            ratchet_counts: HashMap::new(),
-            changed_files_count: None,
-            base_ref: None,
+            ..Default::default()
         };
         let json = format_violations_json(&result);
         assert_eq!(json["summary"]["total"], 1);
@@ -406,6 +428,7 @@ mod tests {
                     suggest: None,
                     source_line: None,
                     fix: None,
+                    url: None,
                 },
                 Violation {
                     rule_id: "r2".into(),
@@ -417,13 +440,12 @@ mod tests {
                     suggest: None,
                     source_line: None,
                     fix: None,
+                    url: None,
                 },
             ],
             files_scanned: 2,
             rules_loaded: 2,
-            ratchet_counts: HashMap::new(),
-            changed_files_count: None,
-            base_ref: None,
+            ..Default::default()
         };
         let json = format_violations_json(&result);
         assert_eq!(json["summary"]["errors"], 1);
@@ -437,6 +459,7 @@ mod tests {
             Some(json!(3)),
             &json!({ "name": "nonexistent_tool", "arguments": {} }),
             std::path::Path::new("guardrails.toml"),
+            &mut ScanSession::new(std::path::Path::new("guardrails.toml")),
         );
         assert!(resp["error"].is_object());
         assert_eq!(resp["error"]["code"], -32602);
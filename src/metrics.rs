@@ -0,0 +1,145 @@
+use crate::config::Severity;
+use crate::scan::ScanResult;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Render `result` in the Prometheus text exposition format, for the node
+/// exporter's textfile collector (`--metrics-file`).
+///
+/// Every rule in `rule_ids` gets a violation series, at 0 if it found
+/// nothing, so a rule that was fixed doesn't just disappear from dashboards.
+pub fn render(result: &ScanResult, rule_ids: &[String], duration: Duration, finished_at: SystemTime) -> String {
+    let mut per_rule: BTreeMap<&str, (usize, usize)> = rule_ids.iter().map(|id| (id.as_str(), (0, 0))).collect();
+    let stats = result.rule_stats();
+    for s in &stats {
+        per_rule.insert(&s.rule_id, (s.count, s.files));
+    }
+    let (errors, warnings) = stats.iter().fold((0, 0), |(e, w), s| match s.severity {
+        Severity::Error => (e + s.count, w),
+        Severity::Warning => (e, w + s.count),
+    });
+
+    let mut out = String::new();
+    header(&mut out, "guardrails_rule_violations", "Violations reported per rule.");
+    for (rule, (count, _)) in &per_rule {
+        let _ = writeln!(out, "guardrails_rule_violations{{rule=\"{}\"}} {}", escape(rule), count);
+    }
+    header(&mut out, "guardrails_rule_violation_files", "Files with at least one violation per rule.");
+    for (rule, (_, files)) in &per_rule {
+        let _ = writeln!(out, "guardrails_rule_violation_files{{rule=\"{}\"}} {}", escape(rule), files);
+    }
+    header(&mut out, "guardrails_violations", "Violations reported per severity.");
+    let _ = writeln!(out, "guardrails_violations{{severity=\"error\"}} {}", errors);
+    let _ = writeln!(out, "guardrails_violations{{severity=\"warning\"}} {}", warnings);
+
+    let mut budgets: Vec<_> = result.ratchet_counts.iter().collect();
+    budgets.sort();
+    header(&mut out, "guardrails_ratchet_found", "Current matches per ratchet budget.");
+    for (budget, (found, _)) in &budgets {
+        let _ = writeln!(out, "guardrails_ratchet_found{{budget=\"{}\"}} {}", escape(budget), found);
+    }
+    header(&mut out, "guardrails_ratchet_max", "max_count per ratchet budget.");
+    for (budget, (_, max)) in &budgets {
+        let _ = writeln!(out, "guardrails_ratchet_max{{budget=\"{}\"}} {}", escape(budget), max);
+    }
+
+    for (name, help, value) in [
+        ("guardrails_files_scanned", "Files scanned.", result.files_scanned.to_string()),
+        ("guardrails_lines_scanned", "Lines read.", result.lines_scanned.to_string()),
+        ("guardrails_rules_loaded", "Rules loaded from the config.", result.rules_loaded.to_string()),
+        (
+            "guardrails_scan_duration_seconds",
+            "Wall-clock time of the scan.",
+            format!("{:.3}", duration.as_secs_f64()),
+        ),
+        (
+            "guardrails_scan_incomplete",
+            "1 if the scan was cancelled before it covered every file.",
+            u8::from(result.incomplete).to_string(),
+        ),
+        (
+            "guardrails_last_run_timestamp_seconds",
+            "Unix time the scan finished.",
+            finished_at.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs().to_string(),
+        ),
+    ] {
+        header(&mut out, name, help);
+        let _ = writeln!(out, "{} {}", name, value);
+    }
+    out
+}
+
+fn header(out: &mut String, name: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} gauge", name);
+}
+
+/// Escape a label value: backslash, double quote and newline.
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+/// Write `metrics` to `path` through a temporary file renamed into place, so
+/// the collector never reads a half-written file.
+pub fn write(path: &Path, metrics: &str) -> io::Result<()> {
+    let mut tmp = PathBuf::from(path);
+    tmp.as_mut_os_string().push(".tmp");
+    fs::write(&tmp, metrics)?;
+    fs::rename(&tmp, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::Violation;
+
+    #[test]
+    fn renders_rules_ratchets_and_scan_stats() {
+        let mut result = ScanResult {
+            violations: vec![
+                Violation::test("no-console", "a.ts"),
+                Violation::test("no-console", "a.ts"),
+                Violation { severity: Severity::Warning, ..Violation::test("no-\"quoted\"", "b.ts") },
+            ],
+            files_scanned: 12,
+            rules_loaded: 3,
+            ..Default::default()
+        };
+        result.ratchet_counts.insert("legacy".into(), (4, 10));
+        let ids = vec!["fixed-rule".to_string(), "no-console".to_string()];
+        let at = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let text = render(&result, &ids, Duration::from_millis(1500), at);
+
+        for line in [
+            "# TYPE guardrails_rule_violations gauge",
+            "guardrails_rule_violations{rule=\"fixed-rule\"} 0",
+            "guardrails_rule_violations{rule=\"no-console\"} 2",
+            "guardrails_rule_violations{rule=\"no-\\\"quoted\\\"\"} 1",
+            "guardrails_rule_violation_files{rule=\"no-console\"} 1",
+            "guardrails_violations{severity=\"error\"} 2",
+            "guardrails_violations{severity=\"warning\"} 1",
+            "guardrails_ratchet_found{budget=\"legacy\"} 4",
+            "guardrails_ratchet_max{budget=\"legacy\"} 10",
+            "guardrails_files_scanned 12",
+            "guardrails_scan_duration_seconds 1.500",
+            "guardrails_scan_incomplete 0",
+            "guardrails_last_run_timestamp_seconds 1700000000",
+        ] {
+            assert!(text.lines().any(|l| l == line), "missing {:?} in\n{}", line, text);
+        }
+    }
+
+    #[test]
+    fn write_replaces_the_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("guardrails.prom");
+        fs::write(&path, "old").unwrap();
+        write(&path, "new\n").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new\n");
+        assert!(!dir.path().join("guardrails.prom.tmp").exists());
+    }
+}
@@ -0,0 +1,225 @@
+use crate::cli::toml_config::TomlRule;
+use crate::rules::Violation;
+use crate::scan::{self, ScanError, Scanner};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use toml_edit::{value, ArrayOfTables, DocumentMut, Item, Table};
+
+/// Rule types `guardrails new-rule` can build: the ones driven by a pattern.
+pub const RULE_TYPES: &[&str] = &["banned-pattern", "ratchet"];
+
+/// A `[[rule]]` being put together by `guardrails new-rule`.
+#[derive(Debug, Clone, Default)]
+pub struct RuleDraft {
+    pub id: String,
+    /// One of [`RULE_TYPES`].
+    pub rule_type: String,
+    pub pattern: String,
+    pub regex: bool,
+    pub glob: Option<String>,
+    /// Budget of a ratchet rule.
+    pub max_count: Option<usize>,
+    /// `"warning"` or `"error"`.
+    pub severity: String,
+    pub message: String,
+}
+
+impl RuleDraft {
+    /// The draft as a `[[rule]]` block, ready to append to a config.
+    pub fn to_toml(&self) -> String {
+        let mut table = Table::new();
+        table["id"] = value(&self.id);
+        table["type"] = value(&self.rule_type);
+        table["severity"] = value(&self.severity);
+        table["pattern"] = value(&self.pattern);
+        if self.regex {
+            table["regex"] = value(true);
+        }
+        if let Some(ref glob) = self.glob {
+            table["glob"] = value(glob);
+        }
+        if let Some(max_count) = self.max_count {
+            table["max_count"] = value(max_count as i64);
+        }
+        if !self.message.is_empty() {
+            table["message"] = value(&self.message);
+        }
+        let mut rules = ArrayOfTables::new();
+        rules.push(table);
+        let mut doc = DocumentMut::new();
+        doc["rule"] = Item::ArrayOfTables(rules);
+        doc.to_string()
+    }
+
+    /// The draft parsed back the way a config would load it.
+    pub fn to_rule(&self) -> Result<TomlRule, String> {
+        #[derive(serde::Deserialize)]
+        struct Block {
+            rule: Vec<TomlRule>,
+        }
+        let block: Block = toml::from_str(&self.to_toml()).map_err(|e| e.to_string())?;
+        block.rule.into_iter().next().ok_or_else(|| "no rule".to_string())
+    }
+}
+
+/// What the draft's pattern matches in `sample`, scanned as the file
+/// `sample_path`, for the live preview. The draft's glob is ignored here so
+/// the pattern can be tried on any file, and a ratchet reports every match.
+pub fn preview(draft: &RuleDraft, sample_path: &Path, sample: &str) -> Result<Vec<Violation>, String> {
+    let mut rule = draft.to_rule()?;
+    rule.glob = None;
+    if rule.rule_type == "ratchet" {
+        rule.max_count = Some(0);
+    }
+    let scanner = Scanner::builder().rule(rule).build().map_err(|e| e.to_string())?;
+    Ok(scanner.scan_content(sample, &sample_path.to_string_lossy()).violations)
+}
+
+/// Why the draft can't be added to the config at `config_path`, if it can't:
+/// a missing or taken id, an empty pattern, or a rule that doesn't build.
+pub fn validate(config_path: &Path, draft: &RuleDraft) -> Result<(), String> {
+    if draft.id.is_empty() {
+        return Err("the rule needs an id".to_string());
+    }
+    if !RULE_TYPES.contains(&draft.rule_type.as_str()) {
+        return Err(format!(
+            "unsupported rule type '{}' (expected one of: {})",
+            draft.rule_type,
+            RULE_TYPES.join(", ")
+        ));
+    }
+    if draft.pattern.is_empty() {
+        return Err("the rule needs a pattern".to_string());
+    }
+    match scan::rule_ids(config_path, None) {
+        Ok(ids) if ids.contains(&draft.id) => {
+            return Err(format!("{} already has a rule '{}'", config_path.display(), draft.id));
+        }
+        Ok(_) => {}
+        Err(ScanError::ConfigRead(ref e)) if e.kind() == io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e.to_string()),
+    }
+    let rule = draft.to_rule()?;
+    Scanner::builder().rule(rule).build().map(|_| ()).map_err(|e| e.to_string())
+}
+
+/// How many times the draft matches in `paths` today, under the config's
+/// `[guardrails]` settings — where a new ratchet's budget starts.
+pub fn count_matches(config_path: &Path, draft: &RuleDraft, paths: &[PathBuf]) -> Result<usize, String> {
+    let rule = draft.to_rule()?;
+    let result = scan::run_scan_rules(config_path, std::slice::from_ref(&rule), paths).map_err(|e| e.to_string())?;
+    Ok(result.violations.iter().filter(|v| v.rule_id == draft.id).count())
+}
+
+/// Append the draft to the end of the config at `config_path`, leaving the
+/// rest of the file as it is.
+pub fn append(config_path: &Path, draft: &RuleDraft) -> io::Result<()> {
+    let mut text = fs::read_to_string(config_path)?;
+    if !text.is_empty() && !text.ends_with('\n') {
+        text.push('\n');
+    }
+    if !text.is_empty() {
+        text.push('\n');
+    }
+    text.push_str(&draft.to_toml());
+    fs::write(config_path, text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn draft() -> RuleDraft {
+        RuleDraft {
+            id: "no-moment".into(),
+            rule_type: "banned-pattern".into(),
+            pattern: "from \"moment\"".into(),
+            severity: "error".into(),
+            message: "Use date-fns instead of moment".into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn renders_a_rule_block_with_escaped_values() {
+        let draft = RuleDraft {
+            glob: Some("src/**/*.ts".into()),
+            ..draft()
+        };
+        assert_eq!(
+            draft.to_toml(),
+            "[[rule]]\nid = \"no-moment\"\ntype = \"banned-pattern\"\nseverity = \"error\"\npattern = 'from \"moment\"'\nglob = \"src/**/*.ts\"\nmessage = \"Use date-fns instead of moment\"\n"
+        );
+        assert_eq!(draft.to_rule().unwrap().pattern.as_deref(), Some("from \"moment\""));
+    }
+
+    #[test]
+    fn previews_matches_in_a_sample_regardless_of_glob() {
+        let draft = RuleDraft {
+            pattern: r"console\.(log|debug)\(".into(),
+            regex: true,
+            glob: Some("lib/**".into()),
+            rule_type: "ratchet".into(),
+            max_count: Some(5),
+            ..draft()
+        };
+        let violations = preview(&draft, Path::new("sample.ts"), "ok();\nconsole.debug(x);\nconsole.log(y);\n").unwrap();
+        let lines: Vec<_> = violations.iter().map(|v| v.line).collect();
+        assert_eq!(lines, [Some(2), Some(3)]);
+
+        let broken = RuleDraft {
+            pattern: "(".into(),
+            ..draft
+        };
+        assert!(preview(&broken, Path::new("sample.ts"), "").unwrap_err().contains("invalid regex"));
+    }
+
+    #[test]
+    fn validation_rejects_taken_ids_and_bad_rules() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = dir.path().join("guardrails.toml");
+        fs::write(&config, "[guardrails]\n\n[[rule]]\nid = \"no-moment\"\ntype = \"banned-pattern\"\npattern = \"x\"\n").unwrap();
+
+        assert!(validate(&config, &draft()).unwrap_err().contains("already has a rule 'no-moment'"));
+        let fresh = RuleDraft {
+            id: "no-lodash".into(),
+            ..draft()
+        };
+        assert!(validate(&config, &fresh).is_ok());
+        let bad_glob = RuleDraft {
+            glob: Some("src/[".into()),
+            ..fresh.clone()
+        };
+        assert!(validate(&config, &bad_glob).is_err());
+        let empty = RuleDraft {
+            pattern: String::new(),
+            ..fresh
+        };
+        assert_eq!(validate(&config, &empty).unwrap_err(), "the rule needs a pattern");
+    }
+
+    #[test]
+    fn counts_current_matches_and_appends_to_the_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = dir.path().join("guardrails.toml");
+        fs::write(&config, "[guardrails]\n# keep me").unwrap();
+        fs::write(dir.path().join("a.ts"), "import x from \"moment\";\nimport y from \"moment\";\n").unwrap();
+
+        let draft = RuleDraft {
+            rule_type: "ratchet".into(),
+            ..draft()
+        };
+        assert_eq!(count_matches(&config, &draft, &[dir.path().to_path_buf()]).unwrap(), 2);
+
+        let draft = RuleDraft {
+            max_count: Some(2),
+            ..draft
+        };
+        append(&config, &draft).unwrap();
+        let text = fs::read_to_string(&config).unwrap();
+        assert!(text.starts_with("[guardrails]\n# keep me\n\n[[rule]]\nid = \"no-moment\"\ntype = \"ratchet\""));
+        assert!(text.ends_with("max_count = 2\nmessage = \"Use date-fns instead of moment\"\n"));
+        assert_eq!(scan::rule_ids(&config, None).unwrap(), ["no-moment"]);
+    }
+}
@@ -0,0 +1,99 @@
+use crate::cli::toml_config::PathsSection;
+use crate::scan::compile_glob_set;
+use globset::GlobSet;
+use serde::Serialize;
+
+/// Whether a path holds the project's own code or vendored/third-party code.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PathClass {
+    #[default]
+    FirstParty,
+    ThirdParty,
+}
+
+impl PathClass {
+    /// Parse a rule's `applies_to` value. `"all"` means no restriction (`None`).
+    pub fn parse_applies_to(value: &str) -> Result<Option<Self>, String> {
+        match value {
+            "first-party" => Ok(Some(PathClass::FirstParty)),
+            "third-party" => Ok(Some(PathClass::ThirdParty)),
+            "all" => Ok(None),
+            other => Err(format!(
+                "expected \"first-party\", \"third-party\" or \"all\", got \"{}\"",
+                other
+            )),
+        }
+    }
+}
+
+/// Classifies paths using the `[paths]` config section.
+///
+/// Paths matching `third_party` are third-party unless they also match
+/// `first_party` (for carve-outs like a patched fork inside `vendor/`).
+#[derive(Debug, Default)]
+pub struct PathClassifier {
+    third_party: Option<GlobSet>,
+    first_party: Option<GlobSet>,
+}
+
+impl PathClassifier {
+    pub fn new(section: &PathsSection) -> Result<Self, globset::Error> {
+        let compile = |patterns: &[String]| -> Result<Option<GlobSet>, globset::Error> {
+            if patterns.is_empty() {
+                Ok(None)
+            } else {
+                compile_glob_set(patterns).map(Some)
+            }
+        };
+        Ok(Self {
+            third_party: compile(&section.third_party)?,
+            first_party: compile(&section.first_party)?,
+        })
+    }
+
+    pub fn classify(&self, path: &str) -> PathClass {
+        let third_party = self.third_party.as_ref().is_some_and(|g| g.is_match(path))
+            && !self.first_party.as_ref().is_some_and(|g| g.is_match(path));
+        if third_party {
+            PathClass::ThirdParty
+        } else {
+            PathClass::FirstParty
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_with_first_party_carve_outs() {
+        let classifier = PathClassifier::new(&PathsSection {
+            third_party: vec!["vendor/**".into(), "**/generated/**".into()],
+            first_party: vec!["vendor/our-fork/**".into()],
+        })
+        .unwrap();
+
+        assert_eq!(classifier.classify("src/app.ts"), PathClass::FirstParty);
+        assert_eq!(classifier.classify("./vendor/lodash/index.js"), PathClass::ThirdParty);
+        assert_eq!(classifier.classify("src/api/generated/client.ts"), PathClass::ThirdParty);
+        assert_eq!(classifier.classify("vendor/our-fork/lib.js"), PathClass::FirstParty);
+    }
+
+    #[test]
+    fn empty_section_is_all_first_party() {
+        let classifier = PathClassifier::default();
+        assert_eq!(classifier.classify("vendor/x.js"), PathClass::FirstParty);
+    }
+
+    #[test]
+    fn parses_applies_to() {
+        assert_eq!(PathClass::parse_applies_to("all"), Ok(None));
+        assert_eq!(
+            PathClass::parse_applies_to("third-party"),
+            Ok(Some(PathClass::ThirdParty))
+        );
+        assert!(PathClass::parse_applies_to("vendor").is_err());
+    }
+}
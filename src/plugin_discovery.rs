@@ -0,0 +1,170 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Package name prefix that marks a rule pack, on npm and crates.io alike.
+pub const PLUGIN_PREFIX: &str = "guardrails-plugin-";
+
+/// File a rule pack ships its `[[rule]]` entries in, at the package root.
+pub const RULES_FILE: &str = "rules.toml";
+
+/// Rule files of the rule packs a project depends on: npm packages named
+/// `guardrails-plugin-*` (or `@scope/guardrails-plugin-*`) in `package.json`,
+/// and crates named `guardrails-plugin-*` in `Cargo.toml`. Only installed
+/// packages that ship a `rules.toml` are returned, in name order.
+pub fn discover(root: &Path) -> Vec<PathBuf> {
+    let mut found = npm_plugins(root);
+    found.extend(cargo_plugins(root));
+    found
+}
+
+fn is_plugin_name(name: &str) -> bool {
+    let bare = match name.strip_prefix('@') {
+        Some(scoped) => scoped.split_once('/').map_or("", |(_, n)| n),
+        None => name,
+    };
+    bare.starts_with(PLUGIN_PREFIX)
+}
+
+/// `node_modules/<name>/rules.toml` for each plugin in `dependencies` or
+/// `devDependencies`.
+fn npm_plugins(root: &Path) -> Vec<PathBuf> {
+    let Ok(text) = fs::read_to_string(root.join("package.json")) else {
+        return Vec::new();
+    };
+    let Ok(manifest) = serde_json::from_str::<serde_json::Value>(&text) else {
+        return Vec::new();
+    };
+    let mut names: Vec<&str> = ["dependencies", "devDependencies"]
+        .iter()
+        .filter_map(|section| manifest.get(section)?.as_object())
+        .flat_map(|deps| deps.keys().map(String::as_str))
+        .filter(|name| is_plugin_name(name))
+        .collect();
+    names.sort_unstable();
+    names.dedup();
+    names
+        .into_iter()
+        .map(|name| root.join("node_modules").join(name).join(RULES_FILE))
+        .filter(|path| path.is_file())
+        .collect()
+}
+
+/// `rules.toml` of each plugin crate in `dependencies` or `dev-dependencies`:
+/// from its `path` for path dependencies, otherwise from the Cargo registry
+/// at the version locked in `Cargo.lock`.
+fn cargo_plugins(root: &Path) -> Vec<PathBuf> {
+    let Ok(text) = fs::read_to_string(root.join("Cargo.toml")) else {
+        return Vec::new();
+    };
+    let Ok(manifest) = toml::from_str::<toml::Table>(&text) else {
+        return Vec::new();
+    };
+    let mut deps: Vec<(&String, &toml::Value)> = ["dependencies", "dev-dependencies"]
+        .iter()
+        .filter_map(|section| manifest.get(*section)?.as_table())
+        .flat_map(|deps| deps.iter())
+        .filter(|(name, _)| name.starts_with(PLUGIN_PREFIX))
+        .collect();
+    deps.sort_by_key(|(name, _)| *name);
+    deps.dedup_by_key(|(name, _)| *name);
+
+    let registry_dirs = registry_source_dirs();
+    let lock = fs::read_to_string(root.join("Cargo.lock"))
+        .ok()
+        .and_then(|text| toml::from_str::<toml::Table>(&text).ok());
+
+    let mut found = Vec::new();
+    for (name, spec) in deps {
+        if let Some(path) = spec.get("path").and_then(toml::Value::as_str) {
+            found.push(root.join(path).join(RULES_FILE));
+            continue;
+        }
+        let Some(version) = lock.as_ref().and_then(|lock| locked_version(lock, name)) else {
+            continue;
+        };
+        let dir_name = format!("{}-{}", name, version);
+        found.extend(
+            registry_dirs
+                .iter()
+                .map(|dir| dir.join(&dir_name).join(RULES_FILE))
+                .find(|path| path.is_file()),
+        );
+    }
+    found.retain(|path| path.is_file());
+    found
+}
+
+fn locked_version<'a>(lock: &'a toml::Table, name: &str) -> Option<&'a str> {
+    lock.get("package")?
+        .as_array()?
+        .iter()
+        .find(|pkg| pkg.get("name").and_then(toml::Value::as_str) == Some(name))?
+        .get("version")?
+        .as_str()
+}
+
+/// `$CARGO_HOME/registry/src/*`, one directory per registry index.
+fn registry_source_dirs() -> Vec<PathBuf> {
+    let cargo_home = std::env::var_os("CARGO_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cargo")));
+    let Some(cargo_home) = cargo_home else {
+        return Vec::new();
+    };
+    let Ok(entries) = fs::read_dir(cargo_home.join("registry").join("src")) else {
+        return Vec::new();
+    };
+    let mut dirs: Vec<PathBuf> = entries.filter_map(|e| Some(e.ok()?.path())).collect();
+    dirs.sort();
+    dirs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn discovers_npm_and_path_crate_rule_packs() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::write(
+            root.join("package.json"),
+            r#"{
+  "dependencies": { "react": "^18", "@acme/guardrails-plugin-security": "1.0.0" },
+  "devDependencies": { "guardrails-plugin-react": "2.0.0", "guardrails-plugin-missing": "1.0.0" }
+}"#,
+        )
+        .unwrap();
+        for pkg in ["@acme/guardrails-plugin-security", "guardrails-plugin-react", "react"] {
+            let pkg_dir = root.join("node_modules").join(pkg);
+            fs::create_dir_all(&pkg_dir).unwrap();
+            fs::write(pkg_dir.join(RULES_FILE), "").unwrap();
+        }
+        fs::write(
+            root.join("Cargo.toml"),
+            "[dependencies]\nserde = \"1\"\nguardrails-plugin-unsafe = { path = \"packs/unsafe\" }\n",
+        )
+        .unwrap();
+        fs::create_dir_all(root.join("packs/unsafe")).unwrap();
+        fs::write(root.join("packs/unsafe").join(RULES_FILE), "").unwrap();
+
+        let found = discover(root);
+        let rel: Vec<_> = found.iter().map(|p| p.strip_prefix(root).unwrap()).collect();
+        assert_eq!(
+            rel,
+            vec![
+                Path::new("node_modules/@acme/guardrails-plugin-security/rules.toml"),
+                Path::new("node_modules/guardrails-plugin-react/rules.toml"),
+                Path::new("packs/unsafe/rules.toml"),
+            ]
+        );
+    }
+
+    #[test]
+    fn plugin_names_allow_npm_scopes() {
+        assert!(is_plugin_name("guardrails-plugin-x"));
+        assert!(is_plugin_name("@org/guardrails-plugin-x"));
+        assert!(!is_plugin_name("@guardrails-plugin-x"));
+        assert!(!is_plugin_name("eslint-plugin-x"));
+    }
+}
@@ -1,6 +1,8 @@
-use crate::cli::toml_config::TomlRule;
+use crate::cli::toml_config::{RuleOverride, TomlRule};
+use crate::extends::{self, Source};
 use std::collections::HashMap;
 use std::fmt;
+use std::path::Path;
 
 #[derive(Debug)]
 pub enum PresetError {
@@ -8,6 +10,17 @@ pub enum PresetError {
         name: String,
         available: Vec<&'static str>,
     },
+    /// An extended config couldn't be fetched, read or parsed.
+    Extends { spec: String, message: String },
+    /// Configs that extend each other, in order, ending with the repeat.
+    Cycle(Vec<String>),
+    /// An `[[override]]` names no inherited or declared rule.
+    UnknownOverride(String),
+    /// A `disable` list names no inherited or declared rule.
+    UnknownDisable(String),
+    /// An `[[override]]` with `files` but nothing to disable there, or with
+    /// neither an `id` nor `disable`.
+    InvalidOverride(String),
 }
 
 impl fmt::Display for PresetError {
@@ -21,6 +34,11 @@ impl fmt::Display for PresetError {
                     available.join(", ")
                 )
             }
+            PresetError::Extends { spec, message } => write!(f, "extends '{}': {}", spec, message),
+            PresetError::Cycle(chain) => write!(f, "extends cycle: {}", chain.join(" → ")),
+            PresetError::UnknownOverride(id) => write!(f, "[[override]] for unknown rule '{}'", id),
+            PresetError::UnknownDisable(id) => write!(f, "disable names unknown rule '{}'", id),
+            PresetError::InvalidOverride(message) => write!(f, "[[override]]: {}", message),
         }
     }
 }
@@ -187,38 +205,134 @@ fn merge_rules(preset_rules: Vec<TomlRule>, user_rules: &[TomlRule]) -> Vec<Toml
     merged
 }
 
-/// Resolve all `extends` presets and merge with user-defined rules.
+/// Resolve all `extends` entries and merge them with user-defined rules.
 /// Returns the final list of `TomlRule` entries ready for the build pipeline.
+///
+/// Entries are presets, shared config files (relative to `base_dir`) or
+/// remote files (`github:`, `org/repo@ref` or HTTPS URLs), which can extend
+/// further configs themselves. Later entries replace earlier rules with the
+/// same id, user rules replace inherited ones, and `overrides` then adjust
+/// the result by id.
 pub fn resolve_rules(
+    base_dir: &Path,
     extends: &[String],
     user_rules: &[TomlRule],
+    overrides: &[RuleOverride],
 ) -> Result<Vec<TomlRule>, PresetError> {
-    if extends.is_empty() {
+    if extends.is_empty() && overrides.is_empty() {
         return Ok(user_rules.to_vec());
     }
 
-    // Collect all preset rules in order, later presets override earlier ones
-    let mut all_preset_rules: Vec<TomlRule> = Vec::new();
+    let inherited = inherited_rules(base_dir, &extends::cache_dir(base_dir), None, extends, &mut Vec::new())?;
+    let mut merged = merge_rules(inherited, user_rules);
+    apply_overrides(&mut merged, overrides)?;
+    Ok(merged)
+}
+
+/// The rules `extends` brings in, for the config at `parent`, with remote
+/// configs cached in `cache`. `stack` holds the configs being resolved, to
+/// catch cycles.
+fn inherited_rules(
+    base_dir: &Path,
+    cache: &Path,
+    parent: Option<&Source>,
+    extends: &[String],
+    stack: &mut Vec<String>,
+) -> Result<Vec<TomlRule>, PresetError> {
+    // Collect all inherited rules in order, later entries override earlier ones
+    let mut all_rules: Vec<TomlRule> = Vec::new();
     let mut seen: HashMap<String, usize> = HashMap::new();
 
-    for preset_name in extends {
-        let preset = resolve_preset(preset_name).ok_or_else(|| PresetError::UnknownPreset {
-            name: preset_name.clone(),
-            available: available_presets().to_vec(),
-        })?;
+    for spec in extends {
+        let error = |message: String| PresetError::Extends {
+            spec: spec.clone(),
+            message,
+        };
+        let rules = match Source::parse(spec, parent, base_dir).map_err(error)? {
+            Source::Preset(name) => {
+                let preset = resolve_preset(&name).ok_or_else(|| PresetError::UnknownPreset {
+                    name: name.clone(),
+                    available: available_presets().to_vec(),
+                })?;
+                preset_rules(preset)
+            }
+            source => {
+                let key = source.key();
+                if let Some(start) = stack.iter().position(|k| *k == key) {
+                    let mut chain = stack[start..].to_vec();
+                    chain.push(key);
+                    return Err(PresetError::Cycle(chain));
+                }
+                let shared = extends::load(&source, base_dir, cache).map_err(error)?;
+                stack.push(key.clone());
+                let inherited = inherited_rules(base_dir, cache, Some(&source), &shared.guardrails.extends, stack)?;
+                stack.pop();
+
+                let mut rules = merge_rules(inherited, &shared.rule);
+                apply_overrides(&mut rules, &shared.rule_overrides())?;
+                // Fetched rules count as plugin rules, so privileged ones need `trust`
+                if source.is_remote() {
+                    for rule in &mut rules {
+                        rule.source.get_or_insert_with(|| key.clone());
+                    }
+                }
+                rules
+            }
+        };
 
-        for rule in preset_rules(preset) {
+        for rule in rules {
             if let Some(&idx) = seen.get(&rule.id) {
-                // Later preset overrides earlier for same id
-                all_preset_rules[idx] = rule;
+                all_rules[idx] = rule;
             } else {
-                seen.insert(rule.id.clone(), all_preset_rules.len());
-                all_preset_rules.push(rule);
+                seen.insert(rule.id.clone(), all_rules.len());
+                all_rules.push(rule);
             }
         }
     }
 
-    Ok(merge_rules(all_preset_rules, user_rules))
+    Ok(all_rules)
+}
+
+/// Apply overrides to the rules with their ids. Disabled rules are removed
+/// after every override was applied; a `disable` limited to `files` adds
+/// them to the rule's `exclude_glob` instead.
+fn apply_overrides(rules: &mut Vec<TomlRule>, overrides: &[RuleOverride]) -> Result<(), PresetError> {
+    let mut disabled: Vec<&str> = Vec::new();
+    for o in overrides {
+        if o.id.is_none() && o.disable.is_empty() {
+            return Err(PresetError::InvalidOverride("needs an id or a disable list".to_string()));
+        }
+        if !o.files.is_empty() && o.disable.is_empty() {
+            return Err(PresetError::InvalidOverride(
+                "files only limits disable; use in_tests or the rule's glob for per-path severity".to_string(),
+            ));
+        }
+        if let Some(ref id) = o.id {
+            let rule = rules
+                .iter_mut()
+                .find(|r| r.id == *id)
+                .ok_or_else(|| PresetError::UnknownOverride(id.clone()))?;
+            if let Some(ref severity) = o.severity {
+                rule.severity = severity.clone();
+            }
+            if let Some(max_count) = o.max_count {
+                rule.max_count = Some(max_count);
+            }
+        }
+        for id in &o.disable {
+            let rule = rules
+                .iter_mut()
+                .find(|r| r.id == *id)
+                .ok_or_else(|| PresetError::UnknownDisable(id.clone()))?;
+            if o.files.is_empty() {
+                disabled.push(id);
+            } else {
+                rule.exclude_glob.extend(o.files.iter().cloned());
+            }
+        }
+    }
+    rules.retain(|r| !disabled.contains(&r.id.as_str()));
+    Ok(())
 }
 
 #[cfg(test)]
@@ -259,7 +373,7 @@ mod tests {
 
     #[test]
     fn resolve_unknown_preset_errors() {
-        let result = resolve_rules(&["unknown-preset".to_string()], &[]);
+        let result = resolve_rules(Path::new("."), &["unknown-preset".to_string()], &[], &[]);
         assert!(result.is_err());
         let err = result.unwrap_err();
         let msg = format!("{}", err);
@@ -276,7 +390,7 @@ mod tests {
             message: "No TODOs".into(),
             ..Default::default()
         }];
-        let result = resolve_rules(&[], &user_rules).unwrap();
+        let result = resolve_rules(Path::new("."), &[], &user_rules, &[]).unwrap();
         assert_eq!(result.len(), 1);
         assert_eq!(result[0].id, "custom-rule");
     }
@@ -291,7 +405,7 @@ mod tests {
             message: "Custom message".into(),
             ..Default::default()
         }];
-        let result = resolve_rules(&["shadcn-strict".to_string()], &user_rules).unwrap();
+        let result = resolve_rules(Path::new("."), &["shadcn-strict".to_string()], &user_rules, &[]).unwrap();
         assert_eq!(result.len(), 5);
         let token_rule = result.iter().find(|r| r.id == "use-theme-tokens").unwrap();
         assert_eq!(token_rule.severity, "warning");
@@ -307,7 +421,7 @@ mod tests {
             message: "no foo".into(),
             ..Default::default()
         }];
-        let result = resolve_rules(&["shadcn-strict".to_string()], &user_rules).unwrap();
+        let result = resolve_rules(Path::new("."), &["shadcn-strict".to_string()], &user_rules, &[]).unwrap();
         assert_eq!(result.len(), 6);
         assert_eq!(result[5].id, "my-custom");
     }
@@ -317,8 +431,10 @@ mod tests {
         // shadcn-strict sets use-theme-tokens severity to "error"
         // shadcn-migrate sets it to "warning"
         let result = resolve_rules(
+            Path::new("."),
             &["shadcn-strict".to_string(), "shadcn-migrate".to_string()],
             &[],
+            &[],
         )
         .unwrap();
         let token_rule = result.iter().find(|r| r.id == "use-theme-tokens").unwrap();
@@ -330,8 +446,10 @@ mod tests {
     #[test]
     fn multiple_presets_combine() {
         let result = resolve_rules(
+            Path::new("."),
             &["shadcn-migrate".to_string(), "ai-safety".to_string()],
             &[],
+            &[],
         )
         .unwrap();
         // 2 from migrate + 3 from ai-safety = 5
@@ -348,4 +466,130 @@ mod tests {
             );
         }
     }
+
+    fn write(path: &Path, text: &str) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, text).unwrap();
+    }
+
+    #[test]
+    fn shared_files_extend_and_override_by_id() {
+        let dir = tempfile::tempdir().unwrap();
+        write(
+            &dir.path().join("shared/base.toml"),
+            r#"
+[guardrails]
+extends = ["ai-safety", "ratchets.toml"]
+
+[[rule]]
+id = "no-todo"
+type = "banned-pattern"
+pattern = "TODO"
+
+[[override]]
+id = "no-lodash"
+severity = "warning"
+"#,
+        );
+        write(
+            &dir.path().join("shared/ratchets.toml"),
+            "[[rule]]\nid = \"legacy\"\ntype = \"ratchet\"\npattern = \"legacy(\"\nmax_count = 50\n",
+        );
+
+        let overrides = vec![
+            RuleOverride {
+                id: Some("legacy".into()),
+                severity: Some("error".into()),
+                max_count: Some(20),
+                ..Default::default()
+            },
+            RuleOverride {
+                id: Some("no-todo".into()),
+                severity: Some("error".into()),
+                ..Default::default()
+            },
+        ];
+        let result = resolve_rules(dir.path(), &["./shared/base.toml".to_string()], &[], &overrides).unwrap();
+        let ids: Vec<&str> = result.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, ["no-moment", "no-lodash", "no-deprecated-request", "legacy", "no-todo"]);
+        let rule = |id: &str| result.iter().find(|r| r.id == id).unwrap();
+        assert_eq!(rule("no-lodash").severity, "warning");
+        assert_eq!((rule("legacy").severity.as_str(), rule("legacy").max_count), ("error", Some(20)));
+        assert_eq!(rule("no-todo").severity, "error");
+        assert!(rule("no-todo").source.is_none());
+    }
+
+    #[test]
+    fn overrides_table_and_scoped_overrides_disable_and_regrade() {
+        let config: crate::cli::toml_config::TomlConfig = toml::from_str(
+            r#"
+[guardrails]
+
+[overrides]
+disable = ["no-moment"]
+severity."no-lodash" = "warning"
+
+[[override]]
+files = ["tests/**", "**/*.spec.ts"]
+disable = ["no-deprecated-request"]
+"#,
+        )
+        .unwrap();
+        let result = resolve_rules(Path::new("."), &["ai-safety".to_string()], &[], &config.rule_overrides()).unwrap();
+        let ids: Vec<&str> = result.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, ["no-lodash", "no-deprecated-request"]);
+        assert_eq!(result[0].severity, "warning");
+        assert_eq!(result[1].exclude_glob, ["tests/**", "**/*.spec.ts"]);
+
+        let disable = |disable: &str, files: &[&str]| RuleOverride {
+            disable: vec![disable.into()],
+            files: files.iter().map(|f| f.to_string()).collect(),
+            ..Default::default()
+        };
+        let err = resolve_rules(Path::new("."), &["ai-safety".to_string()], &[], &[disable("no-such-rule", &[])]).unwrap_err();
+        assert_eq!(err.to_string(), "disable names unknown rule 'no-such-rule'");
+        let scoped_severity = RuleOverride {
+            id: Some("no-lodash".into()),
+            severity: Some("warning".into()),
+            files: vec!["tests/**".into()],
+            ..Default::default()
+        };
+        let err = resolve_rules(Path::new("."), &["ai-safety".to_string()], &[], &[scoped_severity]).unwrap_err();
+        assert!(matches!(err, PresetError::InvalidOverride(_)), "{}", err);
+    }
+
+    #[test]
+    fn extends_cycles_and_unknown_overrides_error() {
+        let dir = tempfile::tempdir().unwrap();
+        write(&dir.path().join("a.toml"), "[guardrails]\nextends = [\"b.toml\"]\n");
+        write(&dir.path().join("b.toml"), "[guardrails]\nextends = [\"./a.toml\"]\n");
+        let err = resolve_rules(dir.path(), &["a.toml".to_string()], &[], &[]).unwrap_err();
+        assert!(matches!(err, PresetError::Cycle(ref chain) if chain.len() == 3), "{}", err);
+
+        let overrides = vec![RuleOverride {
+            id: Some("no-such-rule".into()),
+            severity: Some("error".into()),
+            ..Default::default()
+        }];
+        let err = resolve_rules(dir.path(), &["ai-safety".to_string()], &[], &overrides).unwrap_err();
+        assert_eq!(err.to_string(), "[[override]] for unknown rule 'no-such-rule'");
+
+        let err = resolve_rules(dir.path(), &["missing.toml".to_string()], &[], &[]).unwrap_err();
+        assert!(err.to_string().starts_with("extends 'missing.toml': failed to read"));
+    }
+
+    #[test]
+    fn github_rules_are_marked_with_their_source() {
+        let dir = tempfile::tempdir().unwrap();
+        let text = "[[rule]]\nid = \"no-todo\"\ntype = \"banned-pattern\"\npattern = \"TODO\"\n";
+        let digest = extends::digest(text);
+        write(&dir.path().join("cache").join(format!("{}.toml", &digest["sha256:".len()..])), text);
+        write(
+            &dir.path().join(extends::LOCK_FILE),
+            &format!("[sources]\n\"github:org/policies/base.toml@v1\" = {:?}\n", digest),
+        );
+        let extends = ["github:org/policies/base.toml@v1".to_string()];
+        let result = inherited_rules(dir.path(), &dir.path().join("cache"), None, &extends, &mut Vec::new()).unwrap();
+        assert_eq!(result[0].source.as_deref(), Some("github:org/policies/base.toml@v1"));
+    }
 }
@@ -0,0 +1,132 @@
+use crate::rules::Violation;
+use crate::scan::{self, ScanError};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Matches of a proposed rule in one directory.
+#[derive(Debug, Serialize)]
+pub struct DirectoryCount {
+    pub directory: String,
+    pub count: usize,
+}
+
+/// Impact of one proposed rule on the tree.
+#[derive(Debug, Serialize)]
+pub struct RulePreview {
+    pub rule_id: String,
+    pub total: usize,
+    pub files: usize,
+    /// Configured `max_count` of a ratchet rule, to compare `total` against.
+    pub max_count: Option<usize>,
+    /// Directories with matches, most first.
+    pub directories: Vec<DirectoryCount>,
+}
+
+/// What adding a rule file to the config would report, before adding it.
+#[derive(Debug, Serialize)]
+pub struct Preview {
+    /// Every rule in the rule file, in file order.
+    pub rules: Vec<RulePreview>,
+    pub files_scanned: usize,
+}
+
+/// Run only the rules in `rule_file` over `target_paths` and count their
+/// matches per directory.
+pub fn run_preview(
+    config_path: &Path,
+    rule_file: &Path,
+    target_paths: &[PathBuf],
+) -> Result<Preview, ScanError> {
+    let (result, rules) = scan::run_scan_rule_file(config_path, rule_file, target_paths)?;
+    let rule_ids: Vec<(String, Option<usize>)> = rules
+        .into_iter()
+        .map(|rule| {
+            let max_count = (rule.rule_type == "ratchet").then_some(rule.max_count).flatten();
+            (rule.id, max_count)
+        })
+        .collect();
+    Ok(summarize(&rule_ids, &result.violations, result.files_scanned))
+}
+
+fn summarize(
+    rule_ids: &[(String, Option<usize>)],
+    violations: &[Violation],
+    files_scanned: usize,
+) -> Preview {
+    let rules = rule_ids
+        .iter()
+        .map(|(rule_id, max_count)| {
+            let mut by_dir: BTreeMap<String, usize> = BTreeMap::new();
+            let mut files: Vec<&Path> = Vec::new();
+            for v in violations.iter().filter(|v| &v.rule_id == rule_id) {
+                *by_dir.entry(directory_of(&v.file)).or_insert(0) += 1;
+                files.push(&v.file);
+            }
+            files.sort_unstable();
+            files.dedup();
+
+            let mut directories: Vec<DirectoryCount> = by_dir
+                .into_iter()
+                .map(|(directory, count)| DirectoryCount { directory, count })
+                .collect();
+            directories.sort_by_key(|d| std::cmp::Reverse(d.count));
+            RulePreview {
+                rule_id: rule_id.clone(),
+                total: directories.iter().map(|d| d.count).sum(),
+                files: files.len(),
+                max_count: *max_count,
+                directories,
+            }
+        })
+        .collect();
+
+    Preview {
+        rules,
+        files_scanned,
+    }
+}
+
+/// The file's parent directory, `.` for files at the top level.
+fn directory_of(file: &Path) -> String {
+    let parent = file.parent().unwrap_or(Path::new(""));
+    let dir = parent.to_string_lossy().replace('\\', "/");
+    match dir.strip_prefix("./").unwrap_or(&dir) {
+        "" | "." => ".".to_string(),
+        d => d.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_matches_per_directory_most_first() {
+        let violations = vec![
+            Violation::test("no-any", "./src/a.ts"),
+            Violation::test("no-any", "src/legacy/b.ts"),
+            Violation::test("no-any", "src/legacy/b.ts"),
+            Violation::test("no-any", "src/legacy/c.ts"),
+            Violation::test("no-any", "top.ts"),
+            Violation::test("other", "src/a.ts"),
+        ];
+        let rule_ids = vec![("no-any".to_string(), Some(10)), ("unused".to_string(), None)];
+        let preview = summarize(&rule_ids, &violations, 7);
+
+        let no_any = &preview.rules[0];
+        assert_eq!(no_any.total, 5);
+        assert_eq!(no_any.files, 4);
+        assert_eq!(no_any.max_count, Some(10));
+        let dirs: Vec<(&str, usize)> = no_any
+            .directories
+            .iter()
+            .map(|d| (d.directory.as_str(), d.count))
+            .collect();
+        assert_eq!(dirs, vec![("src/legacy", 3), (".", 1), ("src", 1)]);
+
+        assert_eq!(preview.rules[1].total, 0);
+        assert!(preview.rules[1].directories.is_empty());
+        assert_eq!(preview.files_scanned, 7);
+    }
+}
@@ -0,0 +1,99 @@
+use crate::rules::Violation;
+use serde::Serialize;
+use std::io::Write;
+use std::path::Path;
+use std::sync::Arc;
+
+/// A machine-readable progress event for `scan --progress json`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum ProgressEvent<'a> {
+    /// The file list is known and rules are about to run.
+    ScanStarted { files: usize, rules: usize },
+    /// One file was handled (scanned, reused from a cache or skipped).
+    FileDone {
+        file: &'a Path,
+        violations: usize,
+        done: usize,
+        total: usize,
+    },
+    /// Time one rule spent across all files, sent once the files are done.
+    RuleTiming { rule: &'a str, files: usize, ms: f64 },
+    /// The scan is over; `incomplete` when it was interrupted.
+    ScanFinished {
+        files_scanned: usize,
+        violations: usize,
+        elapsed_ms: f64,
+        incomplete: bool,
+    },
+}
+
+/// Receives progress events from any scan thread.
+#[derive(Clone)]
+pub struct ProgressSink(Arc<dyn Fn(&ProgressEvent) + Send + Sync>);
+
+impl std::fmt::Debug for ProgressSink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ProgressSink")
+    }
+}
+
+impl ProgressSink {
+    pub fn new(sink: impl Fn(&ProgressEvent) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(sink))
+    }
+
+    /// One JSON object per line on stderr.
+    pub fn json_lines() -> Self {
+        Self::new(|event| {
+            if let Ok(line) = serde_json::to_string(event) {
+                let _ = writeln!(std::io::stderr().lock(), "{}", line);
+            }
+        })
+    }
+
+    pub fn emit(&self, event: &ProgressEvent) {
+        (self.0)(event)
+    }
+}
+
+type OnFile = dyn Fn(&Path, &[Violation]) + Send + Sync;
+
+/// Receives each file's violations from any scan thread (`scan_streaming`).
+#[derive(Clone)]
+pub struct ViolationSink(Arc<OnFile>);
+
+impl std::fmt::Debug for ViolationSink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ViolationSink")
+    }
+}
+
+impl ViolationSink {
+    pub fn new(sink: impl Fn(&Path, &[Violation]) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(sink))
+    }
+
+    pub fn emit(&self, file: &Path, violations: &[Violation]) {
+        (self.0)(file, violations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_serialize_with_a_tag() {
+        let event = ProgressEvent::FileDone {
+            file: Path::new("src/a.ts"),
+            violations: 2,
+            done: 1,
+            total: 10,
+        };
+        assert_eq!(
+            serde_json::to_string(&event).unwrap(),
+            r#"{"event":"file_done","file":"src/a.ts","violations":2,"done":1,"total":10}"#
+        );
+    }
+}
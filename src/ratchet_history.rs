@@ -0,0 +1,146 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Oldest points are dropped once a rule has this many.
+const MAX_POINTS: usize = 200;
+
+/// How many recent counts a sparkline shows.
+pub const TREND_POINTS: usize = 12;
+
+/// One recorded ratchet count.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HistoryPoint {
+    /// Unix seconds.
+    pub timestamp: u64,
+    /// Short `HEAD` hash at scan time, when run inside a git repository.
+    pub commit: Option<String>,
+    pub count: usize,
+}
+
+/// Count history per ratchet rule, stored as JSON next to the config.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct RatchetHistory {
+    pub rules: BTreeMap<String, Vec<HistoryPoint>>,
+}
+
+impl RatchetHistory {
+    /// Load history from `path`; a missing file is an empty history.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(text) => serde_json::from_str(&text).map_err(io::Error::other),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let json = serde_json::to_string_pretty(self).map_err(io::Error::other)?;
+        fs::write(path, json + "\n")
+    }
+
+    /// Append the current count of each ratchet rule. A rescan of the same
+    /// commit with an unchanged count adds nothing.
+    pub fn record(
+        &mut self,
+        ratchet_counts: &HashMap<String, (usize, usize)>,
+        timestamp: u64,
+        commit: Option<&str>,
+    ) {
+        for (rule_id, &(found, _)) in ratchet_counts {
+            let points = self.rules.entry(rule_id.clone()).or_default();
+            if points
+                .last()
+                .is_some_and(|p| p.count == found && p.commit.as_deref() == commit)
+            {
+                continue;
+            }
+            points.push(HistoryPoint {
+                timestamp,
+                commit: commit.map(str::to_string),
+                count: found,
+            });
+            if points.len() > MAX_POINTS {
+                points.drain(..points.len() - MAX_POINTS);
+            }
+        }
+    }
+
+    /// The last `TREND_POINTS` counts per rule, oldest first.
+    pub fn trends(&self) -> HashMap<String, Vec<usize>> {
+        self.rules
+            .iter()
+            .map(|(rule_id, points)| {
+                let start = points.len().saturating_sub(TREND_POINTS);
+                (rule_id.clone(), points[start..].iter().map(|p| p.count).collect())
+            })
+            .collect()
+    }
+}
+
+/// Render counts as a block sparkline scaled between their min and max.
+pub fn sparkline(counts: &[usize]) -> String {
+    render_sparkline(counts, &['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'])
+}
+
+/// `sparkline` drawn with ASCII characters only.
+pub fn ascii_sparkline(counts: &[usize]) -> String {
+    render_sparkline(counts, &['_', '.', '-', '~', '=', '+', '*', '#'])
+}
+
+fn render_sparkline(counts: &[usize], bars: &[char]) -> String {
+    let (Some(&min), Some(&max)) = (counts.iter().min(), counts.iter().max()) else {
+        return String::new();
+    };
+    let range = (max - min).max(1);
+    counts
+        .iter()
+        .map(|&c| bars[(c - min) * (bars.len() - 1) / range])
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counts(found: usize) -> HashMap<String, (usize, usize)> {
+        HashMap::from([("ratchet-old-api".to_string(), (found, 200))])
+    }
+
+    #[test]
+    fn record_skips_unchanged_rescan() {
+        let mut history = RatchetHistory::default();
+        history.record(&counts(180), 1, Some("abc"));
+        history.record(&counts(180), 2, Some("abc"));
+        history.record(&counts(150), 3, Some("def"));
+
+        let points = &history.rules["ratchet-old-api"];
+        assert_eq!(points.len(), 2);
+        assert_eq!(points[1].commit.as_deref(), Some("def"));
+        assert_eq!(history.trends()["ratchet-old-api"], [180, 150]);
+    }
+
+    #[test]
+    fn history_round_trips_and_missing_file_is_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history.json");
+        assert!(RatchetHistory::load(&path).unwrap().rules.is_empty());
+
+        let mut history = RatchetHistory::default();
+        history.record(&counts(42), 7, None);
+        history.save(&path).unwrap();
+
+        let loaded = RatchetHistory::load(&path).unwrap();
+        assert_eq!(loaded.rules["ratchet-old-api"][0].count, 42);
+    }
+
+    #[test]
+    fn sparkline_scales_to_range() {
+        assert_eq!(sparkline(&[200, 150, 100, 50, 0]), "█▆▄▂▁");
+        assert_eq!(sparkline(&[5, 5]), "▁▁");
+        assert_eq!(sparkline(&[]), "");
+        assert_eq!(ascii_sparkline(&[200, 150, 100, 50, 0]), "#+~._");
+    }
+}
@@ -0,0 +1,98 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Default budgets file, used when `[guardrails] budgets` is not set.
+pub const DEFAULT_BUDGETS_FILE: &str = ".guardrails-budgets.toml";
+
+/// Per-scope budgets by ratchet rule id, then by directory or file.
+pub type Budgets = BTreeMap<String, BTreeMap<String, usize>>;
+
+/// What each budget of a ratchet with `scope = "directory"` or `"file"`
+/// covers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scope {
+    /// The files directly in one directory.
+    Directory,
+    /// A single file.
+    File,
+}
+
+impl Scope {
+    /// The scope a ratchet's `scope` setting budgets by, if it's one of
+    /// `"directory"` or `"file"`.
+    pub fn parse(scope: Option<&str>) -> Option<Self> {
+        match scope {
+            Some("directory") => Some(Scope::Directory),
+            Some("file") => Some(Scope::File),
+            _ => None,
+        }
+    }
+
+    /// The budget a match in `path` (relative to the config, with `/`
+    /// separators) counts toward: its directory (`.` at the top), or the
+    /// path itself.
+    pub fn key(self, path: &str) -> String {
+        match self {
+            Scope::Directory => path.rsplit_once('/').map_or(".", |(dir, _)| dir).to_string(),
+            Scope::File => path.to_string(),
+        }
+    }
+}
+
+/// Read the budgets file at `path`; a missing file has no budgets.
+pub fn load(path: &Path) -> Result<Budgets, String> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Budgets::new()),
+        Err(e) => return Err(format!("failed to read {}: {}", path.display(), e)),
+    };
+    toml::from_str(&text).map_err(|e| format!("failed to parse {}: {}", path.display(), e))
+}
+
+/// Write `budgets` to `path`, one table per rule, leaving out rules with no
+/// budgets left.
+pub fn save(path: &Path, budgets: &Budgets) -> io::Result<()> {
+    let budgets: Budgets = budgets
+        .iter()
+        .filter(|(_, scopes)| !scopes.is_empty())
+        .map(|(rule, scopes)| (rule.clone(), scopes.clone()))
+        .collect();
+    let text = toml::to_string(&budgets).map_err(io::Error::other)?;
+    fs::write(
+        path,
+        format!(
+            "# Per-directory and per-file ratchet budgets. Commit this file; lower them with\n\
+             # `guardrails ratchet update`.\n\n{}",
+            text
+        ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn budgets_round_trip_through_the_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("budgets.toml");
+        assert!(load(&path).unwrap().is_empty());
+
+        let mut budgets = Budgets::new();
+        budgets.entry("legacy-fetch".into()).or_default().insert("packages/web/src".into(), 12);
+        budgets.entry("legacy-fetch".into()).or_default().insert(".".into(), 1);
+        budgets.entry("gone".into()).or_default();
+        save(&path, &budgets).unwrap();
+
+        let text = fs::read_to_string(&path).unwrap();
+        assert!(text.contains("[legacy-fetch]\n\".\" = 1\n\"packages/web/src\" = 12\n"));
+        assert!(!text.contains("gone"));
+        assert_eq!(load(&path).unwrap()["legacy-fetch"]["packages/web/src"], 12);
+
+        assert_eq!(Scope::Directory.key("packages/web/src/a.ts"), "packages/web/src");
+        assert_eq!(Scope::Directory.key("a.ts"), ".");
+        assert_eq!(Scope::File.key("packages/web/src/a.ts"), "packages/web/src/a.ts");
+    }
+}
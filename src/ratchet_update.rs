@@ -0,0 +1,345 @@
+use crate::ratchet_scopes::{self, Scope};
+use crate::scan::{self, ScanError, ScanOptions};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml_edit::{DocumentMut, Item, Value};
+
+/// A ratchet budget lowered to the current count.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Tightened {
+    /// Rule id, `rule (glob)` for a per-glob budget, `rule [scope]` for a
+    /// per-directory or per-file budget, or a ratchet group id.
+    pub budget: String,
+    pub from: usize,
+    pub to: usize,
+}
+
+/// A ratchet budget its current count exceeds, which is left as it is.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OverBudget {
+    pub budget: String,
+    pub found: usize,
+    pub max: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RatchetUpdate {
+    pub tightened: Vec<Tightened>,
+    /// Budgets already over their count, so there's nothing to tighten.
+    pub over_budget: Vec<OverBudget>,
+    /// Budgets with room to tighten that aren't declared in the config file
+    /// itself (presets, plugins, included rule files).
+    pub not_in_config: Vec<String>,
+    /// Ratchets budgeted per directory or file that the budgets file had no
+    /// budgets for, and how many scopes' current counts were recorded.
+    pub recorded: BTreeMap<String, usize>,
+    pub files_scanned: usize,
+}
+
+/// Re-scan `paths` and lower every ratchet budget in the config file at
+/// `config_path` that is above its current count (`guardrails ratchet update`).
+///
+/// Comments and formatting of the config are kept. Per-directory and
+/// per-file budgets are lowered in the budgets file, and recorded there at
+/// the current counts for ratchets it has none for yet. With `dry_run`
+/// neither file is written.
+pub fn run_ratchet_update(config_path: &Path, paths: &[PathBuf], dry_run: bool) -> Result<RatchetUpdate, ScanError> {
+    let options = ScanOptions {
+        counts_only: true,
+        ..Default::default()
+    };
+    let result = scan::run_scan_with_options(config_path, paths, &options)?;
+    if result.incomplete {
+        return Err(ScanError::RatchetUpdate(
+            "the scan didn't complete, so the counts can't be trusted".to_string(),
+        ));
+    }
+    let mut over_budget: Vec<OverBudget> = result
+        .ratchet_counts
+        .iter()
+        .filter(|(_, &(found, max))| found > max)
+        .map(|(budget, &(found, max))| OverBudget { budget: budget.clone(), found, max })
+        .collect();
+    over_budget.sort_by(|a, b| a.budget.cmp(&b.budget));
+    let mut room: HashMap<&str, (usize, usize)> = result
+        .ratchet_counts
+        .iter()
+        .filter(|(_, &(found, max))| found < max)
+        .map(|(key, &counts)| (key.as_str(), counts))
+        .collect();
+
+    let text = fs::read_to_string(config_path).map_err(ScanError::ConfigRead)?;
+    let mut doc: DocumentMut = text
+        .parse()
+        .map_err(|e| ScanError::RatchetUpdate(format!("failed to parse {}: {}", config_path.display(), e)))?;
+
+    // The total of a ratchet budgeted only per scope is its scopes' sum, not a budget
+    if let Some(rules) = doc.get("rule").and_then(Item::as_array_of_tables) {
+        for rule in rules.iter().filter(|rule| rule.get("max_count").is_none()) {
+            if Scope::parse(rule.get("scope").and_then(Item::as_str)).is_some() {
+                if let Some(id) = rule.get("id").and_then(Item::as_str) {
+                    room.remove(id);
+                }
+            }
+        }
+    }
+
+    let mut tightened = Vec::new();
+    let mut tighten = |budget: String, max_count: &mut Item| {
+        if let Some((found, max)) = room.remove(budget.as_str()) {
+            if set_count(max_count, found) {
+                tightened.push(Tightened { budget, from: max, to: found });
+            }
+        }
+    };
+    if let Some(rules) = doc.get_mut("rule").and_then(Item::as_array_of_tables_mut) {
+        for rule in rules.iter_mut() {
+            if rule.get("type").and_then(Item::as_str) != Some("ratchet") {
+                continue;
+            }
+            let Some(id) = rule.get("id").and_then(Item::as_str).map(str::to_string) else {
+                continue;
+            };
+            if let Some(max_count) = rule.get_mut("max_count") {
+                tighten(id.clone(), max_count);
+            }
+            if let Some(budgets) = rule.get_mut("budgets").and_then(Item::as_array_of_tables_mut) {
+                for budget in budgets.iter_mut() {
+                    let Some(glob) = budget.get("glob").and_then(Item::as_str).map(str::to_string) else {
+                        continue;
+                    };
+                    if let Some(max_count) = budget.get_mut("max_count") {
+                        tighten(format!("{} ({})", id, glob), max_count);
+                    }
+                }
+            }
+        }
+    }
+    if let Some(groups) = doc.get_mut("ratchet_group").and_then(Item::as_array_of_tables_mut) {
+        for group in groups.iter_mut() {
+            let Some(id) = group.get("id").and_then(Item::as_str).map(str::to_string) else {
+                continue;
+            };
+            if let Some(max_count) = group.get_mut("max_count") {
+                tighten(id, max_count);
+            }
+        }
+    }
+
+    if !dry_run && !tightened.is_empty() {
+        fs::write(config_path, doc.to_string()).map_err(|e| {
+            ScanError::RatchetUpdate(format!("failed to write {}: {}", config_path.display(), e))
+        })?;
+    }
+
+    let recorded = if result.ratchet_scopes.is_empty() {
+        BTreeMap::new()
+    } else {
+        update_scope_budgets(config_path, &result.ratchet_scopes, &mut tightened, dry_run)?
+    };
+
+    tightened.sort_by(|a, b| a.budget.cmp(&b.budget));
+    let mut not_in_config: Vec<String> = room.into_keys().map(str::to_string).collect();
+    not_in_config.sort();
+    Ok(RatchetUpdate {
+        tightened,
+        over_budget,
+        not_in_config,
+        recorded,
+        files_scanned: result.files_scanned,
+    })
+}
+
+/// Lower the per-scope budgets in the config's budgets file to `scopes`'
+/// current counts, adding what's lowered to `tightened`, and record the
+/// counts of ratchets the file has no budgets for. Returns those ratchets
+/// with how many scopes were recorded.
+fn update_scope_budgets(
+    config_path: &Path,
+    scopes: &BTreeMap<String, BTreeMap<String, (usize, usize)>>,
+    tightened: &mut Vec<Tightened>,
+    dry_run: bool,
+) -> Result<BTreeMap<String, usize>, ScanError> {
+    let path = scan::scope_budgets_path(config_path)?;
+    let mut budgets = ratchet_scopes::load(&path).map_err(ScanError::Budgets)?;
+    let mut recorded = BTreeMap::new();
+    let mut changed = false;
+    for (rule_id, counts) in scopes {
+        let Some(rule_budgets) = budgets.get_mut(rule_id) else {
+            let current: BTreeMap<String, usize> = counts
+                .iter()
+                .filter(|(_, &(found, _))| found > 0)
+                .map(|(scope, &(found, _))| (scope.clone(), found))
+                .collect();
+            recorded.insert(rule_id.clone(), current.len());
+            budgets.insert(rule_id.clone(), current);
+            changed = true;
+            continue;
+        };
+        for (scope, &(found, budget)) in counts.iter().filter(|(_, &(found, budget))| found < budget) {
+            tightened.push(Tightened {
+                budget: format!("{} [{}]", rule_id, scope),
+                from: budget,
+                to: found,
+            });
+            if found == 0 {
+                rule_budgets.remove(scope);
+            } else {
+                rule_budgets.insert(scope.clone(), found);
+            }
+            changed = true;
+        }
+    }
+
+    if !dry_run && changed {
+        ratchet_scopes::save(&path, &budgets)
+            .map_err(|e| ScanError::RatchetUpdate(format!("failed to write {}: {}", path.display(), e)))?;
+    }
+    Ok(recorded)
+}
+
+/// Replace the integer in `item` with `count`, keeping its surrounding
+/// whitespace and trailing comment. Returns false if `item` isn't an integer.
+fn set_count(item: &mut Item, count: usize) -> bool {
+    let Some(value) = item.as_value_mut().filter(|v| v.is_integer()) else {
+        return false;
+    };
+    let decor = value.decor().clone();
+    *value = Value::from(count as i64);
+    *value.decor_mut() = decor;
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lowers_budgets_with_room_and_keeps_formatting() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("legacy")).unwrap();
+        fs::write(dir.path().join("a.ts"), "legacy();\nold();\n").unwrap();
+        fs::write(dir.path().join("legacy/b.ts"), "legacy();\nlegacy();\n").unwrap();
+        let config = dir.path().join("guardrails.toml");
+        let text = r#"# Project guardrails
+[guardrails]
+
+[[rule]]
+id = "no-legacy"
+type = "ratchet"
+pattern = "legacy("
+glob = "**/*.ts"
+max_count   = 10 # shrink me
+
+[[rule.budgets]]
+glob = "**/legacy/**"
+max_count = 2
+
+[[rule]]
+id = "no-old"
+type = "ratchet"
+pattern = "old("
+glob = "**/*.ts"
+max_count = 1
+
+[[ratchet_group]]
+id = "migration"
+rules = ["no-legacy", "no-old"]
+max_count = 5
+"#;
+        fs::write(&config, text).unwrap();
+
+        let preview = run_ratchet_update(&config, &[dir.path().to_path_buf()], true).unwrap();
+        assert_eq!(
+            preview.tightened,
+            vec![
+                Tightened { budget: "migration".into(), from: 5, to: 4 },
+                Tightened { budget: "no-legacy".into(), from: 10, to: 3 },
+            ]
+        );
+        assert_eq!(fs::read_to_string(&config).unwrap(), text);
+
+        run_ratchet_update(&config, &[dir.path().to_path_buf()], false).unwrap();
+        let expected = text
+            .replace("max_count   = 10 # shrink me", "max_count   = 3 # shrink me")
+            .replace("max_count = 5\n", "max_count = 4\n");
+        assert_eq!(fs::read_to_string(&config).unwrap(), expected);
+
+        // Nothing left to tighten
+        let again = run_ratchet_update(&config, &[dir.path().to_path_buf()], false).unwrap();
+        assert!(again.tightened.is_empty());
+    }
+
+    #[test]
+    fn records_then_lowers_per_file_budgets() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("a.ts"), "legacy();\nlegacy();\n").unwrap();
+        fs::write(dir.path().join("b.ts"), "legacy();\n").unwrap();
+        let budgets = dir.path().join("budgets.toml");
+        let config = dir.path().join("guardrails.toml");
+        fs::write(
+            &config,
+            format!(
+                "[guardrails]\nbudgets = {:?}\n\n[[rule]]\nid = \"no-legacy\"\ntype = \"ratchet\"\npattern = \"legacy(\"\nglob = \"**/*.ts\"\nscope = \"file\"\n",
+                budgets.display().to_string()
+            ),
+        )
+        .unwrap();
+        let paths = [dir.path().to_path_buf()];
+
+        let update = run_ratchet_update(&config, &paths, false).unwrap();
+        assert_eq!(update.recorded["no-legacy"], 2);
+        assert!(update.tightened.is_empty() && update.not_in_config.is_empty());
+        assert_eq!(fs::read_to_string(&budgets).unwrap().lines().last(), Some("\"b.ts\" = 1"));
+
+        fs::write(dir.path().join("a.ts"), "legacy();\n").unwrap();
+        fs::write(dir.path().join("b.ts"), "").unwrap();
+        let update = run_ratchet_update(&config, &paths, false).unwrap();
+        assert!(update.recorded.is_empty());
+        assert_eq!(
+            update.tightened,
+            vec![
+                Tightened { budget: "no-legacy [a.ts]".into(), from: 2, to: 1 },
+                Tightened { budget: "no-legacy [b.ts]".into(), from: 1, to: 0 },
+            ]
+        );
+        assert_eq!(ratchet_scopes::load(&budgets).unwrap()["no-legacy"].len(), 1);
+    }
+
+    #[test]
+    fn reports_budgets_outside_the_config() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("a.ts"), "legacy();\n").unwrap();
+        fs::write(
+            dir.path().join("plugin.toml"),
+            "[[rule]]\nid = \"plugin-legacy\"\ntype = \"ratchet\"\npattern = \"legacy(\"\nglob = \"**/*.ts\"\nmax_count = 4\n",
+        )
+        .unwrap();
+        let config = dir.path().join("guardrails.toml");
+        let plugin = dir.path().join("plugin.toml");
+        fs::write(&config, format!("[guardrails]\nplugins = [{:?}]\n", plugin.display().to_string())).unwrap();
+
+        let update = run_ratchet_update(&config, &[dir.path().to_path_buf()], false).unwrap();
+        assert!(update.tightened.is_empty());
+        assert_eq!(update.not_in_config, vec!["plugin-legacy".to_string()]);
+    }
+
+    #[test]
+    fn leaves_over_budget_ratchets_alone() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("a.ts"), "legacy();\nlegacy();\nold();\n").unwrap();
+        let config = dir.path().join("guardrails.toml");
+        let text = "[guardrails]\n\n[[rule]]\nid = \"no-legacy\"\ntype = \"ratchet\"\npattern = \"legacy(\"\nglob = \"**/*.ts\"\nmax_count = 1\n\n[[rule]]\nid = \"no-old\"\ntype = \"ratchet\"\npattern = \"old(\"\nglob = \"**/*.ts\"\nmax_count = 3\n";
+        fs::write(&config, text).unwrap();
+
+        let preview = run_ratchet_update(&config, &[dir.path().to_path_buf()], true).unwrap();
+        assert_eq!(preview.tightened, vec![Tightened { budget: "no-old".into(), from: 3, to: 1 }]);
+        assert_eq!(
+            preview.over_budget,
+            vec![OverBudget { budget: "no-legacy".into(), found: 2, max: 1 }]
+        );
+        assert_eq!(fs::read_to_string(&config).unwrap(), text);
+    }
+}
@@ -0,0 +1,330 @@
+use crate::result_diff::{RatchetCount, ReportedViolation};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::io::{self, Read, Write};
+use std::path::Path;
+
+/// A JSON scan result (from `scan --format json`), as `guardrails report`
+/// renders it.
+#[derive(Debug, Deserialize)]
+pub struct SavedResult {
+    pub violations: Vec<ReportedViolation>,
+    #[serde(default)]
+    pub summary: Summary,
+    #[serde(default)]
+    pub ratchet: BTreeMap<String, RatchetCount>,
+}
+
+/// The `summary` section of a saved result.
+#[derive(Debug, Default, Deserialize)]
+pub struct Summary {
+    #[serde(default)]
+    pub total: usize,
+    #[serde(default)]
+    pub files_scanned: usize,
+    #[serde(default)]
+    pub rules_loaded: usize,
+}
+
+/// Violations of one rule, for the per-rule table.
+struct RuleRow<'a> {
+    rule_id: &'a str,
+    severity: &'a str,
+    count: usize,
+    files: usize,
+}
+
+impl SavedResult {
+    /// Read a saved result from `path`, or from stdin when it's `-`.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = if path.as_os_str() == "-" {
+            let mut text = String::new();
+            io::stdin().read_to_string(&mut text).map(|_| text)
+        } else {
+            std::fs::read_to_string(path)
+        }
+        .map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+        Self::parse(&text).map_err(|e| {
+            format!("{} is not a JSON scan result (from `scan --format json`): {}", path.display(), e)
+        })
+    }
+
+    pub fn parse(text: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(text)
+    }
+
+    fn errors(&self) -> usize {
+        self.violations.iter().filter(|v| v.severity == "error").count()
+    }
+
+    fn warnings(&self) -> usize {
+        self.violations.len() - self.errors()
+    }
+
+    /// Violations per rule, most first; errors sort before warnings on ties.
+    fn by_rule(&self) -> Vec<RuleRow<'_>> {
+        let mut rules: BTreeMap<&str, (&str, usize, Vec<&Path>)> = BTreeMap::new();
+        for v in &self.violations {
+            let entry = rules.entry(&v.rule_id).or_insert((&v.severity, 0, Vec::new()));
+            if v.severity == "error" {
+                entry.0 = &v.severity;
+            }
+            entry.1 += 1;
+            if !entry.2.contains(&v.file.as_path()) {
+                entry.2.push(&v.file);
+            }
+        }
+        let mut rows: Vec<RuleRow> = rules
+            .into_iter()
+            .map(|(rule_id, (severity, count, files))| RuleRow {
+                rule_id,
+                severity,
+                count,
+                files: files.len(),
+            })
+            .collect();
+        rows.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| (a.severity != "error").cmp(&(b.severity != "error"))));
+        rows
+    }
+
+    /// Violations grouped by file, in path order.
+    fn by_file(&self) -> BTreeMap<String, Vec<&ReportedViolation>> {
+        let mut files: BTreeMap<String, Vec<&ReportedViolation>> = BTreeMap::new();
+        for v in &self.violations {
+            files.entry(v.file.display().to_string()).or_default().push(v);
+        }
+        for violations in files.values_mut() {
+            violations.sort_by_key(|v| (v.line, v.column));
+        }
+        files
+    }
+
+    /// One-line summary: the counts, or that the scan was clean.
+    fn headline(&self) -> String {
+        let plural = |n: usize, word: &str| format!("{} {}{}", n, word, if n == 1 { "" } else { "s" });
+        let (errors, warnings) = (self.errors(), self.warnings());
+        let counts = match (errors, warnings) {
+            (0, 0) => "No violations found".to_string(),
+            (0, w) => plural(w, "warning"),
+            (e, 0) => plural(e, "error"),
+            (e, w) => format!("{}, {}", plural(e, "error"), plural(w, "warning")),
+        };
+        format!(
+            "{} in {} ({} loaded)",
+            counts,
+            plural(self.summary.files_scanned, "file"),
+            plural(self.summary.rules_loaded, "rule")
+        )
+    }
+
+    /// Set when the scan capped the violations it wrote (`--max-violations`).
+    fn truncation_note(&self) -> Option<String> {
+        (self.summary.total > self.violations.len()).then(|| {
+            format!(
+                "Showing {} of {} violations; the saved result was truncated.",
+                self.violations.len(),
+                self.summary.total
+            )
+        })
+    }
+}
+
+/// Render `result` as Markdown for a PR comment: a per-rule table, the
+/// ratchet table and a collapsible section per file.
+pub fn write_markdown(result: &SavedResult, out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "## Guardrails Report\n")?;
+    writeln!(out, "**{}**\n", result.headline())?;
+    if let Some(note) = result.truncation_note() {
+        writeln!(out, "> {}\n", note)?;
+    }
+
+    if !result.violations.is_empty() {
+        writeln!(out, "### Violations by rule\n")?;
+        writeln!(out, "| Rule | Severity | Violations | Files |")?;
+        writeln!(out, "|------|----------|------------|-------|")?;
+        for row in result.by_rule() {
+            writeln!(out, "| `{}` | {} | {} | {} |", row.rule_id, row.severity, row.count, row.files)?;
+        }
+        writeln!(out)?;
+    }
+
+    if !result.ratchet.is_empty() {
+        writeln!(out, "### Ratchet rules\n")?;
+        writeln!(out, "| Rule | Status | Count |")?;
+        writeln!(out, "|------|--------|-------|")?;
+        for (rule_id, count) in &result.ratchet {
+            let status = if count.found <= count.max { "pass" } else { "**over**" };
+            writeln!(out, "| `{}` | {} | {}/{} |", rule_id, status, count.found, count.max)?;
+        }
+        writeln!(out)?;
+    }
+
+    let files = result.by_file();
+    if !files.is_empty() {
+        writeln!(out, "### Files\n")?;
+    }
+    for (file, violations) in &files {
+        // GitHub only renders Markdown inside <details> after a blank line
+        writeln!(out, "<details>")?;
+        writeln!(
+            out,
+            "<summary><code>{}</code> ({})</summary>\n",
+            escape_html(file),
+            violations.len()
+        )?;
+        writeln!(out, "| Line | Severity | Rule | Message |")?;
+        writeln!(out, "|------|----------|------|---------|")?;
+        for v in violations {
+            writeln!(
+                out,
+                "| {} | {} | `{}` | {} |",
+                v.line.map_or_else(|| "-".to_string(), |l| l.to_string()),
+                v.severity,
+                v.rule_id,
+                escape_table_cell(&v.message)
+            )?;
+        }
+        writeln!(out, "\n</details>\n")?;
+    }
+    Ok(())
+}
+
+/// Render `result` as an HTML fragment with the same sections as
+/// [`write_markdown`], using only tags PR comments allow.
+pub fn write_html(result: &SavedResult, out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "<h2>Guardrails Report</h2>")?;
+    writeln!(out, "<p><strong>{}</strong></p>", escape_html(&result.headline()))?;
+    if let Some(note) = result.truncation_note() {
+        writeln!(out, "<blockquote>{}</blockquote>", escape_html(&note))?;
+    }
+
+    if !result.violations.is_empty() {
+        writeln!(out, "<h3>Violations by rule</h3>")?;
+        writeln!(out, "<table>")?;
+        writeln!(out, "<tr><th>Rule</th><th>Severity</th><th>Violations</th><th>Files</th></tr>")?;
+        for row in result.by_rule() {
+            writeln!(
+                out,
+                "<tr><td><code>{}</code></td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                escape_html(row.rule_id),
+                escape_html(row.severity),
+                row.count,
+                row.files
+            )?;
+        }
+        writeln!(out, "</table>")?;
+    }
+
+    if !result.ratchet.is_empty() {
+        writeln!(out, "<h3>Ratchet rules</h3>")?;
+        writeln!(out, "<table>")?;
+        writeln!(out, "<tr><th>Rule</th><th>Status</th><th>Count</th></tr>")?;
+        for (rule_id, count) in &result.ratchet {
+            let status = if count.found <= count.max { "pass" } else { "<strong>over</strong>" };
+            writeln!(
+                out,
+                "<tr><td><code>{}</code></td><td>{}</td><td>{}/{}</td></tr>",
+                escape_html(rule_id),
+                status,
+                count.found,
+                count.max
+            )?;
+        }
+        writeln!(out, "</table>")?;
+    }
+
+    let files = result.by_file();
+    if !files.is_empty() {
+        writeln!(out, "<h3>Files</h3>")?;
+    }
+    for (file, violations) in &files {
+        writeln!(out, "<details>")?;
+        writeln!(
+            out,
+            "<summary><code>{}</code> ({})</summary>",
+            escape_html(file),
+            violations.len()
+        )?;
+        writeln!(out, "<table>")?;
+        writeln!(out, "<tr><th>Line</th><th>Severity</th><th>Rule</th><th>Message</th></tr>")?;
+        for v in violations {
+            writeln!(
+                out,
+                "<tr><td>{}</td><td>{}</td><td><code>{}</code></td><td>{}</td></tr>",
+                v.line.map_or_else(|| "-".to_string(), |l| l.to_string()),
+                escape_html(&v.severity),
+                escape_html(&v.rule_id),
+                escape_html(&v.message)
+            )?;
+        }
+        writeln!(out, "</table>")?;
+        writeln!(out, "</details>")?;
+    }
+    Ok(())
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// A message fit for one Markdown table cell: pipes escaped, newlines
+/// folded, and HTML escaped so it can't open tags.
+fn escape_table_cell(text: &str) -> String {
+    escape_html(text).replace('|', "\\|").replace('\n', " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RESULT: &str = r#"{
+        "violations": [
+            {"rule_id": "no-eval", "severity": "error", "file": "src/b.ts", "line": 9, "column": 1, "message": "no eval", "fingerprint": "1"},
+            {"rule_id": "no-eval", "severity": "error", "file": "src/a.ts", "line": 3, "column": 1, "message": "no eval", "fingerprint": "2"},
+            {"rule_id": "no-console", "severity": "warning", "file": "src/a.ts", "line": 1, "column": 1, "message": "a | b <script>", "fingerprint": "3"}
+        ],
+        "summary": {"total": 3, "files_scanned": 12, "rules_loaded": 4},
+        "ratchet": {"legacy-api": {"found": 7, "max": 5, "pass": false}}
+    }"#;
+
+    #[test]
+    fn markdown_groups_by_rule_and_file() {
+        let result = SavedResult::parse(RESULT).unwrap();
+        let mut out = Vec::new();
+        write_markdown(&result, &mut out).unwrap();
+        let md = String::from_utf8(out).unwrap();
+
+        assert!(md.contains("**2 errors, 1 warning in 12 files (4 rules loaded)**"));
+        assert!(md.contains("| `no-eval` | error | 2 | 2 |\n| `no-console` | warning | 1 | 1 |"));
+        assert!(md.contains("| `legacy-api` | **over** | 7/5 |"));
+        assert!(md.contains("<summary><code>src/a.ts</code> (2)</summary>\n\n"));
+        // Files in path order, lines in order within each file
+        assert!(md.find("src/a.ts").unwrap() < md.find("src/b.ts").unwrap());
+        assert!(md.contains("| 1 | warning | `no-console` | a \\| b &lt;script&gt; |\n| 3 | error |"));
+        assert!(!md.contains("truncated"));
+    }
+
+    #[test]
+    fn html_escapes_and_notes_truncation() {
+        let mut result = SavedResult::parse(RESULT).unwrap();
+        result.summary.total = 10;
+        let mut out = Vec::new();
+        write_html(&result, &mut out).unwrap();
+        let html = String::from_utf8(out).unwrap();
+
+        assert!(html.contains("<td>a | b &lt;script&gt;</td>"));
+        assert!(html.contains("<summary><code>src/b.ts</code> (1)</summary>"));
+        assert!(html.contains("Showing 3 of 10 violations"));
+        assert_eq!(html.matches("<details>").count(), html.matches("</details>").count());
+
+        let clean = SavedResult::parse(r#"{"violations": [], "summary": {"files_scanned": 1, "rules_loaded": 1}}"#).unwrap();
+        let mut out = Vec::new();
+        write_html(&clean, &mut out).unwrap();
+        let html = String::from_utf8(out).unwrap();
+        assert!(html.contains("No violations found in 1 file (1 rule loaded)"));
+        assert!(!html.contains("<table>"));
+    }
+}
@@ -0,0 +1,297 @@
+use crate::config::Severity;
+use crate::fingerprint;
+use crate::rules::Violation;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A violation as written by `scan --format json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportedViolation {
+    pub rule_id: String,
+    pub severity: String,
+    pub file: PathBuf,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub message: String,
+    #[serde(default, skip_serializing)]
+    source_line: Option<String>,
+    /// Missing from reports written before fingerprints were added; it's
+    /// recomputed from the rule, file and source line then.
+    #[serde(default)]
+    pub fingerprint: String,
+}
+
+/// A ratchet count from a report's `ratchet` section.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RatchetCount {
+    pub found: usize,
+    pub max: usize,
+}
+
+/// A ratchet budget whose count or limit differs between the two reports.
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct RatchetDelta {
+    /// Rule id, `rule (glob)` for a per-glob budget, or a ratchet group id.
+    pub budget: String,
+    pub before: Option<RatchetCount>,
+    pub after: Option<RatchetCount>,
+}
+
+impl RatchetDelta {
+    /// Change in the count (new minus old); a missing side counts as zero.
+    pub fn delta(&self) -> i64 {
+        let found = |c: Option<RatchetCount>| c.map_or(0, |c| c.found as i64);
+        found(self.after) - found(self.before)
+    }
+}
+
+/// What changed between two saved scan results (`guardrails diff-results`).
+#[derive(Debug, Serialize)]
+pub struct ResultDiff {
+    /// Violations in the new report only, in report order.
+    pub added: Vec<ReportedViolation>,
+    /// Violations in the old report only, in report order.
+    pub resolved: Vec<ReportedViolation>,
+    /// Violations in both reports.
+    pub unchanged: usize,
+    /// Budgets whose count or limit changed, sorted by name.
+    pub ratchets: Vec<RatchetDelta>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Report {
+    violations: Vec<ReportedViolation>,
+    #[serde(default)]
+    ratchet: BTreeMap<String, RatchetCount>,
+}
+
+/// Compare the JSON scan results at `old` and `new`. Violations are matched
+/// by fingerprint, so ones that only moved to another line aren't reported.
+pub fn run_result_diff(old: &Path, new: &Path) -> Result<ResultDiff, String> {
+    Ok(diff_reports(load(old)?, load(new)?))
+}
+
+/// How a scan compares to a saved result (`scan --compare-to`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Comparison {
+    /// Violations the saved result doesn't have.
+    pub new: usize,
+    /// Violations of the saved result the scan no longer finds.
+    pub fixed: usize,
+    pub unchanged: usize,
+}
+
+/// Compare `violations` to the JSON scan result at `old`, matching by
+/// fingerprint as [`run_result_diff`] does, and remove the ones it already
+/// had so only new violations are left to report.
+pub fn compare_to(old: &Path, violations: &mut Vec<Violation>) -> Result<Comparison, String> {
+    let old = load(old)?;
+    let mut remaining: HashMap<String, usize> = HashMap::new();
+    for v in old.violations {
+        *remaining.entry(v.fingerprint).or_insert(0) += 1;
+    }
+    let fingerprints = fingerprint::fingerprints(violations);
+    let before = violations.len();
+    let mut fingerprints = fingerprints.into_iter();
+    violations.retain(|_| {
+        let fp = fingerprints.next().unwrap_or_default();
+        match remaining.get_mut(&fp) {
+            Some(n) if *n > 0 => {
+                *n -= 1;
+                false
+            }
+            _ => true,
+        }
+    });
+    let unchanged = before - violations.len();
+    Ok(Comparison {
+        new: violations.len(),
+        fixed: remaining.values().sum(),
+        unchanged,
+    })
+}
+
+fn load(path: &Path) -> Result<Report, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+    let mut report: Report = serde_json::from_str(&text).map_err(|e| {
+        format!("{} is not a JSON scan result (from `scan --format json`): {}", path.display(), e)
+    })?;
+
+    if report.violations.iter().any(|v| v.fingerprint.is_empty()) {
+        let violations: Vec<Violation> = report.violations.iter().map(to_violation).collect();
+        for (v, fp) in report.violations.iter_mut().zip(fingerprint::fingerprints(&violations)) {
+            v.fingerprint = fp;
+        }
+    }
+    Ok(report)
+}
+
+fn to_violation(v: &ReportedViolation) -> Violation {
+    Violation {
+        rule_id: v.rule_id.clone(),
+        severity: if v.severity == "error" { Severity::Error } else { Severity::Warning },
+        file: v.file.clone(),
+        line: v.line,
+        column: v.column,
+        message: v.message.clone(),
+        suggest: None,
+        source_line: v.source_line.clone(),
+        fix: None,
+        url: None,
+    }
+}
+
+fn diff_reports(old: Report, new: Report) -> ResultDiff {
+    let mut remaining: HashMap<&str, usize> = HashMap::new();
+    for v in &old.violations {
+        *remaining.entry(v.fingerprint.as_str()).or_insert(0) += 1;
+    }
+    let mut added = Vec::new();
+    let mut unchanged = 0;
+    for v in &new.violations {
+        match remaining.get_mut(v.fingerprint.as_str()) {
+            Some(n) if *n > 0 => {
+                *n -= 1;
+                unchanged += 1;
+            }
+            _ => added.push(v.clone()),
+        }
+    }
+
+    let mut still_present: HashMap<&str, usize> = HashMap::new();
+    for v in &new.violations {
+        *still_present.entry(v.fingerprint.as_str()).or_insert(0) += 1;
+    }
+    let mut resolved = Vec::new();
+    for v in &old.violations {
+        match still_present.get_mut(v.fingerprint.as_str()) {
+            Some(n) if *n > 0 => *n -= 1,
+            _ => resolved.push(v.clone()),
+        }
+    }
+
+    let mut budgets: Vec<&String> = old.ratchet.keys().chain(new.ratchet.keys()).collect();
+    budgets.sort();
+    budgets.dedup();
+    let ratchets = budgets
+        .into_iter()
+        .map(|budget| RatchetDelta {
+            budget: budget.clone(),
+            before: old.ratchet.get(budget).copied(),
+            after: new.ratchet.get(budget).copied(),
+        })
+        .filter(|d| d.before != d.after)
+        .collect();
+
+    ResultDiff {
+        added,
+        resolved,
+        unchanged,
+        ratchets,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(json: &str) -> Report {
+        serde_json::from_str(json).unwrap()
+    }
+
+    fn violation(rule: &str, line: usize, fingerprint: &str) -> String {
+        format!(
+            r#"{{"rule_id":"{}","severity":"error","file":"a.ts","line":{},"column":1,"message":"m","fingerprint":"{}"}}"#,
+            rule, line, fingerprint
+        )
+    }
+
+    #[test]
+    fn matches_violations_by_fingerprint() {
+        let old = report(&format!(
+            r#"{{"violations":[{},{},{}],"ratchet":{{"legacy":{{"found":5,"max":10}},"gone":{{"found":1,"max":1}}}}}}"#,
+            violation("no-console", 3, "aaaa"),
+            violation("no-console", 7, "bbbb"),
+            violation("no-eval", 9, "cccc"),
+        ));
+        let new = report(&format!(
+            r#"{{"violations":[{},{}],"ratchet":{{"legacy":{{"found":3,"max":10}},"new":{{"found":0,"max":0}}}}}}"#,
+            violation("no-console", 12, "aaaa"),
+            violation("no-alert", 1, "dddd"),
+        ));
+
+        let diff = diff_reports(old, new);
+        assert_eq!(diff.unchanged, 1);
+        assert_eq!(diff.added.iter().map(|v| v.fingerprint.as_str()).collect::<Vec<_>>(), ["dddd"]);
+        assert_eq!(diff.resolved.iter().map(|v| v.fingerprint.as_str()).collect::<Vec<_>>(), ["bbbb", "cccc"]);
+
+        let budgets: Vec<_> = diff.ratchets.iter().map(|d| (d.budget.as_str(), d.delta())).collect();
+        assert_eq!(budgets, [("gone", -1), ("legacy", -2), ("new", 0)]);
+    }
+
+    #[test]
+    fn repeated_fingerprints_are_counted() {
+        let old = report(&format!(r#"{{"violations":[{}]}}"#, violation("r", 1, "aaaa")));
+        let new = report(&format!(
+            r#"{{"violations":[{},{}]}}"#,
+            violation("r", 1, "aaaa"),
+            violation("r", 2, "aaaa"),
+        ));
+        let diff = diff_reports(old, new);
+        assert_eq!((diff.added.len(), diff.resolved.len(), diff.unchanged), (1, 0, 1));
+    }
+
+    #[test]
+    fn loads_reports_without_fingerprints() {
+        let dir = tempfile::tempdir().unwrap();
+        let old = dir.path().join("old.json");
+        let new = dir.path().join("new.json");
+        let v = |line: usize| {
+            format!(
+                r#"{{"rule_id":"r","severity":"warning","file":"a.ts","line":{},"column":1,"message":"m","source_line":"  x();"}}"#,
+                line
+            )
+        };
+        fs::write(&old, format!(r#"{{"violations":[{}]}}"#, v(1))).unwrap();
+        fs::write(&new, format!(r#"{{"violations":[{}]}}"#, v(4))).unwrap();
+
+        let diff = run_result_diff(&old, &new).unwrap();
+        assert_eq!((diff.added.len(), diff.resolved.len(), diff.unchanged), (0, 0, 1));
+
+        fs::write(&new, "{}").unwrap();
+        assert!(run_result_diff(&old, &new).unwrap_err().contains("not a JSON scan result"));
+    }
+
+    #[test]
+    fn compare_to_keeps_only_new_violations() {
+        let make = |line: usize, source: &str| Violation {
+            rule_id: "r".into(),
+            severity: Severity::Error,
+            file: PathBuf::from("a.ts"),
+            line: Some(line),
+            column: Some(1),
+            message: "m".into(),
+            suggest: None,
+            source_line: Some(source.into()),
+            fix: None,
+            url: None,
+        };
+        let saved = [make(1, "a();"), make(2, "a();"), make(3, "b();")];
+        let entries: Vec<String> = fingerprint::fingerprints(&saved)
+            .iter()
+            .enumerate()
+            .map(|(i, fp)| violation("r", i + 1, fp))
+            .collect();
+        let dir = tempfile::tempdir().unwrap();
+        let old = dir.path().join("old.json");
+        fs::write(&old, format!(r#"{{"violations":[{}]}}"#, entries.join(","))).unwrap();
+
+        // One `a();` moved, the other and `b();` were fixed, `c();` is new
+        let mut violations = vec![make(10, "a();"), make(11, "c();")];
+        let comparison = compare_to(&old, &mut violations).unwrap();
+        assert_eq!(comparison, Comparison { new: 1, fixed: 2, unchanged: 1 });
+        assert_eq!(violations[0].source_line.as_deref(), Some("c();"));
+    }
+}
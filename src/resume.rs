@@ -0,0 +1,173 @@
+use crate::rules::Violation;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+/// Default progress file for `--resume`.
+pub const DEFAULT_PROGRESS_FILE: &str = ".guardrails-progress.json";
+
+/// How many newly scanned files trigger a checkpoint write.
+const SAVE_EVERY: usize = 500;
+
+/// On-disk progress of an interrupted scan.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Progress {
+    /// Identifies the config + targets the progress belongs to.
+    fingerprint: String,
+    completed: Vec<PathBuf>,
+    violations: Vec<Violation>,
+}
+
+/// Scan progress persisted to disk so an interrupted scan can pick up where
+/// it stopped. Progress from a different config or target set is ignored.
+#[derive(Debug)]
+pub struct Checkpoint {
+    path: PathBuf,
+    resumed: HashSet<PathBuf>,
+    state: Mutex<Progress>,
+    unsaved: AtomicUsize,
+}
+
+impl Checkpoint {
+    /// Open the progress file at `path`, keeping its contents only if they
+    /// match `fingerprint`.
+    pub fn open(path: &Path, fingerprint: String) -> Self {
+        let progress = fs::read_to_string(path)
+            .ok()
+            .and_then(|text| serde_json::from_str::<Progress>(&text).ok())
+            .filter(|p| p.fingerprint == fingerprint)
+            .unwrap_or(Progress {
+                fingerprint,
+                ..Default::default()
+            });
+
+        Self {
+            path: path.to_path_buf(),
+            resumed: progress.completed.iter().cloned().collect(),
+            state: Mutex::new(progress),
+            unsaved: AtomicUsize::new(0),
+        }
+    }
+
+    /// Number of files already completed by an earlier run.
+    pub fn resumed_files(&self) -> usize {
+        self.resumed.len()
+    }
+
+    /// Whether an earlier run already scanned `file`.
+    pub fn is_done(&self, file: &Path) -> bool {
+        self.resumed.contains(file)
+    }
+
+    /// Whether `file` is this checkpoint's own progress (or temp) file, which
+    /// may sit inside the scanned tree.
+    pub fn is_progress_file(&self, file: &Path) -> bool {
+        let name = file.file_name();
+        name.is_some() && (name == self.path.file_name() || name == self.tmp_path().file_name())
+    }
+
+    /// Violations found by earlier runs.
+    pub fn resumed_violations(&self) -> Vec<Violation> {
+        let state = self.state.lock().unwrap();
+        state
+            .violations
+            .iter()
+            .filter(|v| self.resumed.contains(&v.file))
+            .cloned()
+            .collect()
+    }
+
+    /// Record a finished file, writing the progress file every few hundred files.
+    pub fn record(&self, file: &Path, violations: &[Violation]) {
+        {
+            let mut state = self.state.lock().unwrap();
+            state.completed.push(file.to_path_buf());
+            state.violations.extend_from_slice(violations);
+        }
+        if self.unsaved.fetch_add(1, Ordering::Relaxed) + 1 >= SAVE_EVERY {
+            self.unsaved.store(0, Ordering::Relaxed);
+            let _ = self.save();
+        }
+    }
+
+    /// Write the progress file (via a temp file, so a kill mid-write can't corrupt it).
+    pub fn save(&self) -> io::Result<()> {
+        let json = {
+            let state = self.state.lock().unwrap();
+            serde_json::to_string(&*state).map_err(io::Error::other)?
+        };
+        let tmp = self.tmp_path();
+        fs::write(&tmp, json)?;
+        fs::rename(&tmp, &self.path)
+    }
+
+    /// The scan completed: remove the progress file.
+    pub fn finish(&self) {
+        let _ = fs::remove_file(&self.path);
+    }
+
+    fn tmp_path(&self) -> PathBuf {
+        self.path.with_extension("json.tmp")
+    }
+}
+
+/// Fingerprint for a scan: config contents, target paths and tool version.
+pub fn fingerprint(config_text: &str, target_paths: &[PathBuf]) -> String {
+    let mut hasher = DefaultHasher::new();
+    env!("CARGO_PKG_VERSION").hash(&mut hasher);
+    config_text.hash(&mut hasher);
+    target_paths.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saved_progress_is_resumed() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("progress.json");
+
+        let first = Checkpoint::open(&path, "abc".into());
+        first.record(Path::new("a.ts"), &[Violation::test("r", "a.ts")]);
+        first.record(Path::new("b.ts"), &[]);
+        first.save().unwrap();
+
+        let second = Checkpoint::open(&path, "abc".into());
+        assert_eq!(second.resumed_files(), 2);
+        assert!(second.is_done(Path::new("a.ts")));
+        assert!(!second.is_done(Path::new("c.ts")));
+        assert_eq!(second.resumed_violations().len(), 1);
+
+        second.finish();
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn mismatched_fingerprint_starts_over() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("progress.json");
+
+        let first = Checkpoint::open(&path, "old".into());
+        first.record(Path::new("a.ts"), &[]);
+        first.save().unwrap();
+
+        let second = Checkpoint::open(&path, "new".into());
+        assert_eq!(second.resumed_files(), 0);
+    }
+
+    #[test]
+    fn fingerprint_depends_on_config_and_targets() {
+        let paths = vec![PathBuf::from("src")];
+        assert_eq!(fingerprint("a", &paths), fingerprint("a", &paths));
+        assert_ne!(fingerprint("a", &paths), fingerprint("b", &paths));
+        assert_ne!(fingerprint("a", &paths), fingerprint("a", &[PathBuf::from("lib")]));
+    }
+}
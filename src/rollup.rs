@@ -0,0 +1,150 @@
+use crate::config::Severity;
+use crate::scan::ScanResult;
+use clap::ValueEnum;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::{Component, Path};
+
+/// Violation and ratchet counts under one directory prefix.
+#[derive(Debug, Default, PartialEq, Eq, Serialize)]
+pub struct DirectoryRollup {
+    pub directory: String,
+    pub errors: usize,
+    pub warnings: usize,
+    /// Matches per ratchet rule, whether or not the rule is within budget.
+    pub ratchet: BTreeMap<String, usize>,
+}
+
+impl DirectoryRollup {
+    pub fn ratchet_total(&self) -> usize {
+        self.ratchet.values().sum()
+    }
+}
+
+/// Roll the scan's counts up to directory prefixes of at most `depth`
+/// components (`--group-by dir --depth N`), most errors first.
+///
+/// Ratchet rules are counted by their matches, not their reported
+/// violations, so directories still show up while the rule is within budget.
+pub fn by_directory(result: &ScanResult, depth: usize) -> Vec<DirectoryRollup> {
+    let mut dirs: BTreeMap<String, DirectoryRollup> = BTreeMap::new();
+    for v in &result.violations {
+        if result.ratchet_counts.contains_key(&v.rule_id) {
+            continue;
+        }
+        let dir = entry(&mut dirs, &v.file, depth);
+        match v.severity {
+            Severity::Error => dir.errors += 1,
+            Severity::Warning => dir.warnings += 1,
+        }
+    }
+    for (file, rules) in &result.ratchet_files {
+        let dir = entry(&mut dirs, file, depth);
+        for (rule_id, count) in rules {
+            *dir.ratchet.entry(rule_id.clone()).or_insert(0) += count;
+        }
+    }
+
+    let mut rollup: Vec<DirectoryRollup> = dirs.into_values().collect();
+    rollup.sort_by(|a, b| {
+        (b.errors, b.warnings, b.ratchet_total()).cmp(&(a.errors, a.warnings, a.ratchet_total()))
+    });
+    rollup
+}
+
+fn entry<'a>(
+    dirs: &'a mut BTreeMap<String, DirectoryRollup>,
+    file: &Path,
+    depth: usize,
+) -> &'a mut DirectoryRollup {
+    let directory = directory_prefix(file, depth);
+    dirs.entry(directory.clone()).or_insert_with(|| DirectoryRollup {
+        directory,
+        ..Default::default()
+    })
+}
+
+/// The first `depth` directories of `file`'s parent, `.` for files at the top level.
+fn directory_prefix(file: &Path, depth: usize) -> String {
+    let parent = file.parent().unwrap_or(Path::new(""));
+    let names: Vec<_> = parent
+        .components()
+        .filter_map(|c| match c {
+            Component::Normal(name) => Some(name.to_string_lossy()),
+            _ => None,
+        })
+        .take(depth)
+        .collect();
+    let dir = names.join("/");
+    match (parent.has_root(), dir.as_str()) {
+        (true, _) => format!("/{}", dir),
+        (false, "") => ".".to_string(),
+        (false, _) => dir,
+    }
+}
+
+/// What `--ratchet-group-by` groups ratchet rules by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum RatchetGroupBy {
+    /// The rule's `tags`; a rule with several tags counts toward each
+    Tag,
+    /// The rule's `owner`
+    Owner,
+}
+
+/// Ratchet rules sharing a tag or owner, with their combined counts.
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct RatchetGroup {
+    /// The tag or owner, `None` for rules without one.
+    pub name: Option<String>,
+    pub found: usize,
+    pub max: usize,
+    /// `(rule id, found, max)`, sorted by id.
+    pub rules: Vec<(String, usize, usize)>,
+}
+
+impl RatchetGroup {
+    /// Share of the group's combined budget in use, in percent.
+    pub fn utilization(&self) -> f64 {
+        if self.max == 0 {
+            if self.found == 0 { 0.0 } else { 100.0 }
+        } else {
+            self.found as f64 * 100.0 / self.max as f64
+        }
+    }
+}
+
+/// Group the scan's ratchet rules by tag or owner, named groups first in
+/// name order and rules without a tag or owner last.
+pub fn ratchet_groups(result: &ScanResult, by: RatchetGroupBy) -> Vec<RatchetGroup> {
+    let mut groups: BTreeMap<Option<&str>, RatchetGroup> = BTreeMap::new();
+    for (rule_id, &(found, max)) in &result.ratchet_counts {
+        let names: Vec<Option<&str>> = match by {
+            RatchetGroupBy::Tag => match result.tags.get(rule_id) {
+                Some(tags) => tags.iter().map(|t| Some(t.as_str())).collect(),
+                None => vec![None],
+            },
+            RatchetGroupBy::Owner => {
+                vec![result.owners.get(rule_id).and_then(|o| o.owner.as_deref())]
+            }
+        };
+        for name in names {
+            let group = groups.entry(name).or_insert_with(|| RatchetGroup {
+                name: name.map(String::from),
+                found: 0,
+                max: 0,
+                rules: Vec::new(),
+            });
+            group.found += found;
+            group.max += max;
+            group.rules.push((rule_id.clone(), found, max));
+        }
+    }
+
+    let mut groups: Vec<RatchetGroup> = groups.into_values().collect();
+    for group in &mut groups {
+        group.rules.sort();
+    }
+    groups.sort_by(|a, b| (a.name.is_none(), &a.name).cmp(&(b.name.is_none(), &b.name)));
+    groups
+}
@@ -0,0 +1,357 @@
+use crate::line_endings;
+use crate::rules::Violation;
+use crate::scan::{self, ScanError, ScanOptions, Scanner};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Test cases run when `guardrails test` is given no paths, if it exists
+/// next to the config.
+pub const DEFAULT_TESTS_FILE: &str = "guardrails.tests.toml";
+
+/// Fixture directory run when `guardrails test` is given no paths, if it
+/// exists next to the config.
+pub const DEFAULT_FIXTURES_DIR: &str = "guardrails-tests";
+
+/// Annotation expecting a violation of the named rules on its line.
+const EXPECT: &str = "expect:";
+
+/// Annotation expecting a violation of the named rules anywhere in the file.
+const EXPECT_FILE: &str = "expect-file:";
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TestsFile {
+    #[serde(default)]
+    test: Vec<TestCase>,
+}
+
+/// One `[[test]]` of a tests file.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TestCase {
+    name: Option<String>,
+    /// Where the content pretends to live, relative to the config.
+    path: String,
+    content: String,
+    /// Expectations besides the content's annotations.
+    #[serde(default)]
+    expect: Vec<Expectation>,
+}
+
+/// A violation a test expects.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Expectation {
+    pub rule: String,
+    /// The line it's on; `None` for anywhere in the file, or no line at all.
+    pub line: Option<usize>,
+}
+
+impl Expectation {
+    fn covers(&self, v: &Violation) -> bool {
+        v.rule_id == self.rule && self.line.is_none_or(|line| v.line == Some(line))
+    }
+}
+
+/// A violation no expectation covered.
+#[derive(Debug, Serialize)]
+pub struct Unexpected {
+    pub rule: String,
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CaseResult {
+    pub name: String,
+    /// The path the content was checked as, relative to the config.
+    pub path: String,
+    /// Expectations no violation met.
+    pub missing: Vec<Expectation>,
+    pub unexpected: Vec<Unexpected>,
+}
+
+impl CaseResult {
+    pub fn passed(&self) -> bool {
+        self.missing.is_empty() && self.unexpected.is_empty()
+    }
+}
+
+/// Results of `guardrails test`, one per case in the order they were found.
+#[derive(Debug, Serialize)]
+pub struct TestReport {
+    pub cases: Vec<CaseResult>,
+}
+
+impl TestReport {
+    pub fn failed(&self) -> usize {
+        self.cases.iter().filter(|c| !c.passed()).count()
+    }
+}
+
+#[derive(Debug)]
+pub enum TestError {
+    Scan(ScanError),
+    Read(PathBuf, std::io::Error),
+    Parse(PathBuf, String),
+    /// No paths were given and neither default exists.
+    NoTests(PathBuf),
+}
+
+impl fmt::Display for TestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TestError::Scan(e) => write!(f, "{}", e),
+            TestError::Read(path, e) => write!(f, "failed to read {}: {}", path.display(), e),
+            TestError::Parse(path, e) => write!(f, "failed to parse {}: {}", path.display(), e),
+            TestError::NoTests(dir) => write!(
+                f,
+                "no tests found: add {} or a {}/ directory of fixtures in {}, or pass their paths",
+                DEFAULT_TESTS_FILE,
+                DEFAULT_FIXTURES_DIR,
+                dir.display()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TestError {}
+
+/// Run the rule tests in `paths` against the config at `config_path`
+/// (`guardrails test`).
+///
+/// A `.toml` path is a tests file of `[[test]]` cases, each checking
+/// `content` as if it were the file at `path`. Any other file, or each file
+/// under a directory, is a fixture checked as if it were at its path
+/// relative to that directory. Both expect the violations their content
+/// annotates: `expect: rule-id` (more ids separated by commas) on a line
+/// expects each rule to report that line, and `expect-file: rule-id`
+/// anywhere expects it somewhere in the file. A case fails when an
+/// expectation isn't met or a violation isn't expected. Ratchet matches
+/// count whatever the budget, and waivers don't apply.
+pub fn run_tests(config_path: &Path, paths: &[PathBuf]) -> Result<TestReport, TestError> {
+    let config_dir = scan::config_dir(config_path);
+    let paths: Vec<PathBuf> = if paths.is_empty() {
+        let defaults: Vec<PathBuf> = [DEFAULT_TESTS_FILE, DEFAULT_FIXTURES_DIR]
+            .iter()
+            .map(|name| config_dir.join(name))
+            .filter(|path| path.exists())
+            .collect();
+        if defaults.is_empty() {
+            return Err(TestError::NoTests(config_dir.to_path_buf()));
+        }
+        defaults
+    } else {
+        paths.to_vec()
+    };
+
+    let mut cases = Vec::new();
+    for path in &paths {
+        if path.is_dir() {
+            let mut files = Vec::new();
+            fixture_files(path, &mut files)?;
+            for file in files {
+                let relative = file.strip_prefix(path).unwrap_or(&file);
+                let relative = relative.to_string_lossy().replace('\\', "/");
+                let content = fs::read_to_string(&file).map_err(|e| TestError::Read(file.clone(), e))?;
+                cases.push(TestCase {
+                    name: None,
+                    path: relative,
+                    content,
+                    expect: Vec::new(),
+                });
+            }
+        } else if path.extension().is_some_and(|ext| ext == "toml") {
+            let text = fs::read_to_string(path).map_err(|e| TestError::Read(path.clone(), e))?;
+            let file: TestsFile = toml::from_str(&text).map_err(|e| TestError::Parse(path.clone(), e.to_string()))?;
+            cases.extend(file.test);
+        } else {
+            let content = fs::read_to_string(path).map_err(|e| TestError::Read(path.clone(), e))?;
+            cases.push(TestCase {
+                name: None,
+                path: path.file_name().unwrap_or_default().to_string_lossy().into_owned(),
+                content,
+                expect: Vec::new(),
+            });
+        }
+    }
+
+    let scanner = Scanner::from_config(config_path, ScanOptions::default()).map_err(TestError::Scan)?;
+    let cases = cases
+        .into_iter()
+        .map(|case| {
+            let violations = scanner.check_content(&case.content, &config_dir.join(&case.path).to_string_lossy());
+            let mut expected = annotations(&case.content);
+            expected.extend(case.expect);
+            check_case(case.name.unwrap_or_else(|| case.path.clone()), case.path, &expected, &violations)
+        })
+        .collect();
+    Ok(TestReport { cases })
+}
+
+/// The files under `dir`, sorted, skipping hidden ones.
+fn fixture_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), TestError> {
+    let mut entries: Vec<PathBuf> = fs::read_dir(dir)
+        .and_then(|entries| entries.map(|entry| entry.map(|e| e.path())).collect())
+        .map_err(|e| TestError::Read(dir.to_path_buf(), e))?;
+    entries.sort();
+    for entry in entries {
+        if entry.file_name().is_some_and(|name| name.to_string_lossy().starts_with('.')) {
+            continue;
+        }
+        if entry.is_dir() {
+            fixture_files(&entry, files)?;
+        } else {
+            files.push(entry);
+        }
+    }
+    Ok(())
+}
+
+/// The expectations `expect:` and `expect-file:` annotations in `content`
+/// declare.
+fn annotations(content: &str) -> Vec<Expectation> {
+    let mut expected = Vec::new();
+    for (idx, text) in line_endings::lines(content).enumerate() {
+        for (marker, line) in [(EXPECT, Some(idx + 1)), (EXPECT_FILE, None)] {
+            let Some(rest) = after_marker(text, marker) else {
+                continue;
+            };
+            let ids = rest.split(',').map(|part| {
+                part.trim_start()
+                    .split(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '_'))
+                    .next()
+                    .unwrap_or_default()
+            });
+            for rule in ids.take_while(|id| !id.is_empty()) {
+                expected.push(Expectation {
+                    rule: rule.to_string(),
+                    line,
+                });
+            }
+        }
+    }
+    expected
+}
+
+/// The text after `marker` on `line`, if it's there and not the tail of a
+/// longer word (`my-expect:`).
+fn after_marker<'a>(line: &'a str, marker: &str) -> Option<&'a str> {
+    line.match_indices(marker).find_map(|(pos, _)| {
+        let before = line[..pos].chars().next_back();
+        let standalone = before.is_none_or(|c| !(c.is_alphanumeric() || c == '-' || c == '_'));
+        standalone.then(|| &line[pos + marker.len()..])
+    })
+}
+
+fn check_case(name: String, path: String, expected: &[Expectation], violations: &[Violation]) -> CaseResult {
+    let mut missing: Vec<Expectation> = expected
+        .iter()
+        .filter(|e| !violations.iter().any(|v| e.covers(v)))
+        .cloned()
+        .collect();
+    missing.dedup();
+    let unexpected = violations
+        .iter()
+        .filter(|v| !expected.iter().any(|e| e.covers(v)))
+        .map(|v| Unexpected {
+            rule: v.rule_id.clone(),
+            line: v.line,
+            message: v.message.clone(),
+        })
+        .collect();
+    CaseResult {
+        name,
+        path,
+        missing,
+        unexpected,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn annotations_name_rules_by_line_or_file() {
+        let content = "a(); // expect: no-a, no-b\n# expect-file: max-lines\nb(); /* expect:no-c */\nmy-expect: x\n";
+        let expected: Vec<_> = annotations(content).into_iter().map(|e| (e.rule, e.line)).collect();
+        assert_eq!(
+            expected,
+            [
+                ("no-a".to_string(), Some(1)),
+                ("no-b".to_string(), Some(1)),
+                ("max-lines".to_string(), None),
+                ("no-c".to_string(), Some(3)),
+            ]
+        );
+    }
+
+    #[test]
+    fn cases_fail_on_missing_and_unexpected_violations() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = dir.path().join("guardrails.toml");
+        fs::write(
+            &config,
+            r#"[guardrails]
+
+[[rule]]
+id = "no-legacy"
+type = "ratchet"
+pattern = "legacyFetch("
+glob = "src/**/*.ts"
+max_count = 100
+
+[[rule]]
+id = "no-console"
+type = "banned-pattern"
+pattern = "console.log("
+glob = "src/**"
+"#,
+        )
+        .unwrap();
+        fs::create_dir_all(dir.path().join("guardrails-tests/src/api")).unwrap();
+        fs::write(
+            dir.path().join("guardrails-tests/src/api/client.ts"),
+            "legacyFetch(a); // expect: no-legacy\nconsole.log(b);\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("guardrails.tests.toml"),
+            r#"[[test]]
+name = "outside src"
+path = "scripts/build.ts"
+content = "legacyFetch(a);\n"
+
+[[test]]
+path = "src/app.ts"
+content = "console.log(a);\n"
+expect = [{ rule = "no-console", line = 1 }, { rule = "no-legacy" }]
+"#,
+        )
+        .unwrap();
+
+        let report = run_tests(&config, &[]).unwrap();
+        let results: Vec<_> = report
+            .cases
+            .iter()
+            .map(|c| (c.name.as_str(), c.passed(), c.missing.len(), c.unexpected.len()))
+            .collect();
+        assert_eq!(
+            results,
+            [
+                ("outside src", true, 0, 0),
+                ("src/app.ts", false, 1, 0),
+                ("src/api/client.ts", false, 0, 1),
+            ]
+        );
+        assert_eq!(report.cases[2].unexpected[0].line, Some(2));
+        assert_eq!(report.failed(), 2);
+
+        let empty = tempfile::tempdir().unwrap();
+        let err = run_tests(&empty.path().join("guardrails.toml"), &[]).unwrap_err();
+        assert!(matches!(err, TestError::NoTests(_)));
+    }
+}
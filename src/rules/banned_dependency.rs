@@ -1,12 +1,16 @@
 use crate::config::{RuleConfig, Severity};
+use crate::line_endings;
 use crate::rules::{Rule, RuleBuildError, ScanContext, Violation};
-use std::collections::HashSet;
+use std::cmp::Ordering;
 
-/// Checks `package.json` (or other manifest) files for banned packages
-/// in dependency sections.
+/// Checks manifest files for banned packages in dependency sections.
 ///
-/// Scans `dependencies`, `devDependencies`, `peerDependencies`, and
-/// `optionalDependencies` for packages that should not be used.
+/// Understands `package.json` (`dependencies`, `devDependencies`,
+/// `peerDependencies`, `optionalDependencies`), `Cargo.toml` (`dependencies`,
+/// `dev-dependencies`, `build-dependencies`, including target and workspace
+/// tables), `requirements*.txt`, `pyproject.toml` (PEP 621 and Poetry) and
+/// `go.mod`. A banned package may carry version constraints, e.g.
+/// `react <18`, to ban only the versions a manifest can resolve to.
 #[derive(Debug)]
 pub struct BannedDependencyRule {
     id: String,
@@ -14,8 +18,9 @@ pub struct BannedDependencyRule {
     message: String,
     suggest: Option<String>,
     glob: Option<String>,
-    packages: HashSet<String>,
-    manifest: String,
+    packages: Vec<BannedPackage>,
+    /// Only files with this name are checked; `None` checks every recognized manifest.
+    manifest: Option<String>,
 }
 
 /// JSON dependency sections to check.
@@ -26,6 +31,143 @@ const DEP_SECTIONS: &[&str] = &[
     "optionalDependencies",
 ];
 
+/// `Cargo.toml` dependency tables, at the top level, under `target.*` and `workspace`.
+const CARGO_SECTIONS: &[&str] = &["dependencies", "dev-dependencies", "build-dependencies"];
+
+/// Manifests checked when the rule doesn't name one.
+const DEFAULT_GLOB: &str = "**/{package.json,Cargo.toml,requirements*.txt,pyproject.toml,go.mod}";
+
+/// A `packages` entry: a name and the version constraints that must all hold
+/// for a declared version to be banned (none bans every version).
+#[derive(Debug, Clone, PartialEq)]
+struct BannedPackage {
+    name: String,
+    constraints: Vec<(Ordering, bool, Vec<u64>)>,
+    spec: String,
+}
+
+impl BannedPackage {
+    /// Parse `name [op version]...`, e.g. `moment`, `react <18` or `lodash >=4, <4.17.21`.
+    fn parse(entry: &str) -> Option<Self> {
+        let entry = entry.trim();
+        let split = entry.find([' ', '<', '>', '=']).unwrap_or(entry.len());
+        let (name, spec) = entry.split_at(split);
+        if name.is_empty() {
+            return None;
+        }
+        let mut constraints = Vec::new();
+        for token in spec.split([' ', ',']).filter(|t| !t.is_empty()) {
+            let (ordering, inclusive, version) = if let Some(v) = token.strip_prefix("<=") {
+                (Ordering::Less, true, v)
+            } else if let Some(v) = token.strip_prefix(">=") {
+                (Ordering::Greater, true, v)
+            } else if let Some(v) = token.strip_prefix('<') {
+                (Ordering::Less, false, v)
+            } else if let Some(v) = token.strip_prefix('>') {
+                (Ordering::Greater, false, v)
+            } else if let Some(v) = token.strip_prefix("==").or_else(|| token.strip_prefix('=')) {
+                (Ordering::Equal, true, v)
+            } else {
+                return None;
+            };
+            let version = parse_version(version).filter(|_| version.starts_with(|c: char| c.is_ascii_digit()))?;
+            constraints.push((ordering, inclusive, version));
+        }
+        Some(Self {
+            name: name.to_string(),
+            constraints,
+            spec: spec.trim().to_string(),
+        })
+    }
+
+    /// Whether a dependency declared as `declared` (a version requirement
+    /// from the manifest) is banned. The lowest version it allows is
+    /// compared; requirements without a readable version (`*`, git or path
+    /// dependencies) only match packages banned outright.
+    fn bans(&self, declared: Option<&str>) -> bool {
+        if self.constraints.is_empty() {
+            return true;
+        }
+        let Some(version) = declared.and_then(parse_version) else {
+            return false;
+        };
+        self.constraints.iter().all(|(ordering, inclusive, bound)| {
+            let cmp = compare_versions(&version, bound);
+            cmp == *ordering || (*inclusive && cmp == Ordering::Equal)
+        })
+    }
+}
+
+/// The first version number in `text`, e.g. `[17, 0, 2]` for `^17.0.2`.
+fn parse_version(text: &str) -> Option<Vec<u64>> {
+    let start = text.find(|c: char| c.is_ascii_digit())?;
+    let digits: String = text[start..]
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || *c == '.')
+        .collect();
+    let parts: Vec<u64> = digits
+        .split('.')
+        .take_while(|p| !p.is_empty())
+        .map_while(|p| p.parse().ok())
+        .collect();
+    (!parts.is_empty()).then_some(parts)
+}
+
+/// Compare versions component by component, missing components counting as 0.
+fn compare_versions(a: &[u64], b: &[u64]) -> Ordering {
+    let len = a.len().max(b.len());
+    (0..len)
+        .map(|i| a.get(i).unwrap_or(&0).cmp(b.get(i).unwrap_or(&0)))
+        .find(|o| o.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+/// Python package names compare case-insensitively with `-`, `_` and `.` equivalent.
+fn normalize_python(name: &str) -> String {
+    name.to_ascii_lowercase().replace(['_', '.'], "-")
+}
+
+/// A dependency declared in a manifest.
+#[derive(Debug, PartialEq)]
+struct Dependency {
+    name: String,
+    version: Option<String>,
+    section: String,
+    line: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ManifestKind {
+    Json,
+    Cargo,
+    Requirements,
+    PyProject,
+    GoMod,
+}
+
+impl ManifestKind {
+    fn of(file_name: &str) -> Option<Self> {
+        match file_name {
+            "Cargo.toml" => Some(Self::Cargo),
+            "pyproject.toml" => Some(Self::PyProject),
+            "go.mod" => Some(Self::GoMod),
+            n if n.ends_with(".json") => Some(Self::Json),
+            n if n.starts_with("requirements") && n.ends_with(".txt") => Some(Self::Requirements),
+            _ => None,
+        }
+    }
+
+    fn dependencies(self, content: &str) -> Vec<Dependency> {
+        match self {
+            Self::Json => json_dependencies(content),
+            Self::Cargo => cargo_dependencies(content),
+            Self::Requirements => requirements_dependencies(content),
+            Self::PyProject => pyproject_dependencies(content),
+            Self::GoMod => go_mod_dependencies(content),
+        }
+    }
+}
+
 impl BannedDependencyRule {
     pub fn new(config: &RuleConfig) -> Result<Self, RuleBuildError> {
         if config.packages.is_empty() {
@@ -35,18 +177,31 @@ impl BannedDependencyRule {
             ));
         }
 
-        let packages: HashSet<String> = config.packages.iter().cloned().collect();
-        let manifest = config
-            .manifest
-            .as_deref()
-            .unwrap_or("package.json")
-            .to_string();
+        let packages = config
+            .packages
+            .iter()
+            .map(|entry| {
+                BannedPackage::parse(entry).ok_or_else(|| {
+                    RuleBuildError::InvalidField(
+                        config.id.clone(),
+                        "packages",
+                        format!(
+                            "expected a package name with optional version constraints like \"react <18\", got \"{}\"",
+                            entry
+                        ),
+                    )
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let manifest = config.manifest.clone();
 
         // Build a glob that only matches the manifest filename
-        let glob = config
-            .glob
-            .clone()
-            .or_else(|| Some(format!("**/{}", manifest)));
+        let glob = config.glob.clone().or_else(|| {
+            Some(match manifest {
+                Some(ref name) => format!("**/{}", name),
+                None => DEFAULT_GLOB.to_string(),
+            })
+        });
 
         Ok(Self {
             id: config.id.clone(),
@@ -58,6 +213,18 @@ impl BannedDependencyRule {
             manifest,
         })
     }
+
+    fn banned(&self, kind: ManifestKind, dep: &Dependency) -> Option<&BannedPackage> {
+        self.packages.iter().find(|p| {
+            let same_name = match kind {
+                ManifestKind::Requirements | ManifestKind::PyProject => {
+                    normalize_python(&p.name) == normalize_python(&dep.name)
+                }
+                _ => p.name == dep.name,
+            };
+            same_name && p.bans(dep.version.as_deref())
+        })
+    }
 }
 
 impl Rule for BannedDependencyRule {
@@ -81,49 +248,65 @@ impl Rule for BannedDependencyRule {
             .and_then(|n| n.to_str())
             .unwrap_or("");
 
-        if file_name != self.manifest {
+        if self.manifest.as_deref().is_some_and(|m| m != file_name) {
             return Vec::new();
         }
-
-        let json: serde_json::Value = match serde_json::from_str(ctx.content) {
-            Ok(v) => v,
-            Err(_) => return Vec::new(), // skip malformed JSON
+        let Some(kind) = ManifestKind::of(file_name) else {
+            return Vec::new();
         };
 
         let mut violations = Vec::new();
 
-        for section in DEP_SECTIONS {
-            if let Some(deps) = json.get(section).and_then(|v| v.as_object()) {
-                for pkg_name in deps.keys() {
-                    if self.packages.contains(pkg_name) {
-                        // Find the line number by searching for the package name in the raw text
-                        let line_num = find_line_number(ctx.content, pkg_name, section);
-
-                        violations.push(Violation {
-                            rule_id: self.id.clone(),
-                            severity: self.severity,
-                            file: ctx.file_path.to_path_buf(),
-                            line: line_num,
-                            column: None,
-                            message: format!(
-                                "{}: '{}' in {}",
-                                self.message, pkg_name, section
-                            ),
-                            suggest: self.suggest.clone(),
-                            source_line: line_num.and_then(|n| {
-                                ctx.content.lines().nth(n - 1).map(|l| l.to_string())
-                            }),
-                            fix: None,
-                        });
-                    }
-                }
-            }
+        for dep in kind.dependencies(ctx.content) {
+            let Some(banned) = self.banned(kind, &dep) else {
+                continue;
+            };
+            let package = match (banned.constraints.is_empty(), &dep.version) {
+                (false, Some(version)) => format!("'{}' {} (banned: {})", dep.name, version, banned.spec),
+                _ => format!("'{}'", dep.name),
+            };
+            violations.push(Violation {
+                rule_id: self.id.clone(),
+                severity: self.severity,
+                file: ctx.file_path.to_path_buf(),
+                line: dep.line,
+                column: None,
+                message: format!("{}: {} in {}", self.message, package, dep.section),
+                suggest: self.suggest.clone(),
+                source_line: dep
+                    .line
+                    .and_then(|n| line_endings::lines(ctx.content).nth(n - 1).map(|l| l.to_string())),
+                fix: None,
+                url: None,
+            });
         }
 
         violations
     }
 }
 
+fn json_dependencies(content: &str) -> Vec<Dependency> {
+    let json: serde_json::Value = match serde_json::from_str(content) {
+        Ok(v) => v,
+        Err(_) => return Vec::new(), // skip malformed JSON
+    };
+    let mut deps = Vec::new();
+    for section in DEP_SECTIONS {
+        if let Some(entries) = json.get(section).and_then(|v| v.as_object()) {
+            for (name, version) in entries {
+                deps.push(Dependency {
+                    name: name.clone(),
+                    version: version.as_str().map(str::to_string),
+                    section: section.to_string(),
+                    // Find the line number by searching for the package name in the raw text
+                    line: find_line_number(content, name, section),
+                });
+            }
+        }
+    }
+    deps
+}
+
 /// Find the line number of a package name within a specific dependency section.
 fn find_line_number(content: &str, pkg_name: &str, section: &str) -> Option<usize> {
     let needle = format!(r#""{}""#, pkg_name);
@@ -132,7 +315,7 @@ fn find_line_number(content: &str, pkg_name: &str, section: &str) -> Option<usiz
     let mut in_section = false;
     let mut brace_depth = 0;
 
-    for (idx, line) in content.lines().enumerate() {
+    for (idx, line) in line_endings::lines(content).enumerate() {
         if line.contains(&section_needle) {
             in_section = true;
             brace_depth = 0;
@@ -155,7 +338,7 @@ fn find_line_number(content: &str, pkg_name: &str, section: &str) -> Option<usiz
     }
 
     // Fallback: search anywhere in the file
-    for (idx, line) in content.lines().enumerate() {
+    for (idx, line) in line_endings::lines(content).enumerate() {
         if line.contains(&needle) {
             return Some(idx + 1);
         }
@@ -164,9 +347,229 @@ fn find_line_number(content: &str, pkg_name: &str, section: &str) -> Option<usiz
     None
 }
 
+fn cargo_dependencies(content: &str) -> Vec<Dependency> {
+    let Ok(doc) = content.parse::<toml::Table>() else {
+        return Vec::new();
+    };
+    let mut parents: Vec<(String, &toml::Table)> = vec![(String::new(), &doc)];
+    if let Some(workspace) = doc.get("workspace").and_then(|v| v.as_table()) {
+        parents.push(("workspace.".to_string(), workspace));
+    }
+    if let Some(targets) = doc.get("target").and_then(|v| v.as_table()) {
+        for (target, table) in targets {
+            if let Some(table) = table.as_table() {
+                parents.push((format!("target.{}.", target), table));
+            }
+        }
+    }
+    let mut tables: Vec<(String, &toml::Table)> = Vec::new();
+    for (prefix, parent) in parents {
+        for section in CARGO_SECTIONS {
+            if let Some(table) = parent.get(*section).and_then(|v| v.as_table()) {
+                tables.push((format!("{}{}", prefix, section), table));
+            }
+        }
+    }
+
+    let mut deps = Vec::new();
+    for (section, table) in tables {
+        for (key, value) in table {
+            let (name, version) = match value {
+                toml::Value::String(version) => (key.as_str(), Some(version.clone())),
+                toml::Value::Table(t) => (
+                    t.get("package").and_then(|p| p.as_str()).unwrap_or(key),
+                    t.get("version").and_then(|v| v.as_str()).map(str::to_string),
+                ),
+                _ => (key.as_str(), None),
+            };
+            deps.push(Dependency {
+                name: name.to_string(),
+                version,
+                line: find_toml_key_line(content, &section, key),
+                section: section.clone(),
+            });
+        }
+    }
+    deps
+}
+
+/// Line of `key` in the TOML table `section`: a `key = ...` line under the
+/// section's header, or a `[section.key]` header of its own.
+fn find_toml_key_line(content: &str, section: &str, key: &str) -> Option<usize> {
+    // Quotes in headers (`target.'cfg(unix)'.dependencies`) don't matter for matching
+    let unquote = |s: &str| s.replace(['\'', '"', ' '], "");
+    let section = unquote(section);
+    let own_header = format!("{}.{}", section, key);
+    let mut current = String::new();
+    for (idx, line) in line_endings::lines(content).enumerate() {
+        let trimmed = line.trim();
+        if let Some(header) = trimmed.strip_prefix('[').and_then(|h| h.split(']').next()) {
+            current = unquote(header);
+            if current == own_header {
+                return Some(idx + 1);
+            }
+            continue;
+        }
+        if current == section {
+            let rest = trimmed
+                .strip_prefix(key)
+                .or_else(|| trimmed.strip_prefix(&format!("\"{}\"", key)));
+            if rest.is_some_and(|r| r.trim_start().starts_with(['=', '.'])) {
+                return Some(idx + 1);
+            }
+        }
+    }
+    None
+}
+
+/// Split a PEP 508 requirement like `requests[socks]>=2.0; python_version<"3.8"`
+/// into its name and version specifier.
+fn split_requirement(requirement: &str) -> Option<(String, Option<String>)> {
+    let requirement = requirement.split(';').next()?.trim();
+    let end = requirement
+        .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')))
+        .unwrap_or(requirement.len());
+    let name = &requirement[..end];
+    if name.is_empty() {
+        return None;
+    }
+    let rest = requirement[end..].trim_start();
+    let rest = match rest.strip_prefix('[') {
+        Some(extras) => extras.split_once(']').map_or("", |(_, r)| r).trim(),
+        None => rest,
+    };
+    let version = (!rest.is_empty()).then(|| rest.trim_matches(['(', ')']).to_string());
+    Some((name.to_string(), version))
+}
+
+fn requirements_dependencies(content: &str) -> Vec<Dependency> {
+    line_endings::lines(content)
+        .enumerate()
+        .filter_map(|(idx, line)| {
+            let line = line.split(" #").next().unwrap_or_default().trim();
+            if line.is_empty() || line.starts_with(['#', '-']) {
+                return None;
+            }
+            let (name, version) = split_requirement(line)?;
+            Some(Dependency {
+                name,
+                version,
+                section: "requirements".to_string(),
+                line: Some(idx + 1),
+            })
+        })
+        .collect()
+}
+
+fn pyproject_dependencies(content: &str) -> Vec<Dependency> {
+    let Ok(doc) = content.parse::<toml::Table>() else {
+        return Vec::new();
+    };
+    let mut deps = Vec::new();
+    let line_of = |name: &str| {
+        line_endings::lines(content).position(|l| {
+            let l = l.trim_start().trim_start_matches(['"', '\'']);
+            l.strip_prefix(name)
+                .is_some_and(|rest| !rest.starts_with(|c: char| c.is_ascii_alphanumeric() || c == '-' || c == '_'))
+        })
+        .map(|i| i + 1)
+    };
+    let mut push_requirements = |section: &str, list: Option<&toml::Value>| {
+        for requirement in list.and_then(|v| v.as_array()).into_iter().flatten() {
+            if let Some((name, version)) = requirement.as_str().and_then(split_requirement) {
+                deps.push(Dependency {
+                    line: line_of(&name),
+                    name,
+                    version,
+                    section: section.to_string(),
+                });
+            }
+        }
+    };
+
+    let project = doc.get("project");
+    push_requirements("project.dependencies", project.and_then(|p| p.get("dependencies")));
+    if let Some(optional) = project
+        .and_then(|p| p.get("optional-dependencies"))
+        .and_then(|v| v.as_table())
+    {
+        for (extra, list) in optional {
+            push_requirements(&format!("project.optional-dependencies.{}", extra), Some(list));
+        }
+    }
+
+    let poetry = doc.get("tool").and_then(|t| t.get("poetry"));
+    let mut poetry_tables: Vec<(String, &toml::Value)> = Vec::new();
+    for section in ["dependencies", "dev-dependencies"] {
+        if let Some(table) = poetry.and_then(|p| p.get(section)) {
+            poetry_tables.push((format!("tool.poetry.{}", section), table));
+        }
+    }
+    if let Some(groups) = poetry.and_then(|p| p.get("group")).and_then(|g| g.as_table()) {
+        for (group, value) in groups {
+            if let Some(table) = value.get("dependencies") {
+                poetry_tables.push((format!("tool.poetry.group.{}.dependencies", group), table));
+            }
+        }
+    }
+    for (section, table) in poetry_tables {
+        for (name, value) in table.as_table().into_iter().flatten() {
+            if name == "python" {
+                continue;
+            }
+            let version = match value {
+                toml::Value::String(v) => Some(v.clone()),
+                other => other.get("version").and_then(|v| v.as_str()).map(str::to_string),
+            };
+            deps.push(Dependency {
+                name: name.clone(),
+                version,
+                line: find_toml_key_line(content, &section, name),
+                section: section.clone(),
+            });
+        }
+    }
+    deps
+}
+
+fn go_mod_dependencies(content: &str) -> Vec<Dependency> {
+    let mut deps = Vec::new();
+    let mut in_block = false;
+    for (idx, line) in line_endings::lines(content).enumerate() {
+        let line = line.split("//").next().unwrap_or_default().trim();
+        let spec = if in_block {
+            if line == ")" {
+                in_block = false;
+                continue;
+            }
+            line
+        } else if let Some(rest) = line.strip_prefix("require") {
+            let rest = rest.trim();
+            if rest == "(" {
+                in_block = true;
+                continue;
+            }
+            rest
+        } else {
+            continue;
+        };
+        let mut parts = spec.split_whitespace();
+        if let Some(module) = parts.next() {
+            deps.push(Dependency {
+                name: module.to_string(),
+                version: parts.next().map(str::to_string),
+                section: "require".to_string(),
+                line: Some(idx + 1),
+            });
+        }
+    }
+    deps
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::path_class::PathClass;
     use std::path::Path;
 
     fn make_rule(packages: Vec<&str>) -> BannedDependencyRule {
@@ -182,13 +585,24 @@ mod tests {
     }
 
     fn check(rule: &BannedDependencyRule, content: &str) -> Vec<Violation> {
+        check_in(rule, "package.json", content)
+    }
+
+    fn check_in(rule: &BannedDependencyRule, path: &str, content: &str) -> Vec<Violation> {
         let ctx = ScanContext {
-            file_path: Path::new("package.json"),
+            file_path: Path::new(path),
             content,
+            class: PathClass::FirstParty,
+            file_type: None,
+            changed_ranges: None,
         };
         rule.check_file(&ctx)
     }
 
+    fn lines(violations: &[Violation]) -> Vec<usize> {
+        violations.iter().filter_map(|v| v.line).collect()
+    }
+
     #[test]
     fn detects_dependency() {
         let rule = make_rule(vec!["bootstrap"]);
@@ -250,6 +664,9 @@ mod tests {
         let ctx = ScanContext {
             file_path: Path::new("src/component.tsx"),
             content: r#"{"dependencies": {"bootstrap": "^5.0.0"}}"#,
+            class: PathClass::FirstParty,
+            file_type: None,
+            changed_ranges: None,
         };
         let violations = rule.check_file(&ctx);
         assert!(violations.is_empty());
@@ -304,6 +721,9 @@ mod tests {
         let ctx = ScanContext {
             file_path: Path::new("package.json"),
             content: r#"{"dependencies": {"bootstrap": "^5.0.0"}}"#,
+            class: PathClass::FirstParty,
+            file_type: None,
+            changed_ranges: None,
         };
         assert!(rule.check_file(&ctx).is_empty());
 
@@ -311,7 +731,98 @@ mod tests {
         let ctx = ScanContext {
             file_path: Path::new("bower.json"),
             content: r#"{"dependencies": {"bootstrap": "^5.0.0"}}"#,
+            class: PathClass::FirstParty,
+            file_type: None,
+            changed_ranges: None,
         };
         assert_eq!(rule.check_file(&ctx).len(), 1);
     }
+
+    #[test]
+    fn version_constraints_only_ban_matching_versions() {
+        let rule = make_rule(vec!["react <18", "lodash >=4, <4.17.21"]);
+        let content = r#"{
+  "dependencies": {
+    "react": "^17.0.2",
+    "lodash": "~4.17.21"
+  },
+  "devDependencies": {
+    "react": "18.2.0",
+    "lodash": "^4.17.4"
+  }
+}"#;
+        let violations = check(&rule, content);
+        assert_eq!(lines(&violations), vec![3, 8]);
+        assert!(violations[0].message.contains("'react' ^17.0.2 (banned: <18)"));
+    }
+
+    #[test]
+    fn constraints_skip_unversioned_dependencies() {
+        let rule = make_rule(vec!["react <18", "moment"]);
+        let content = r#"{"dependencies": {"react": "github:facebook/react", "moment": "*"}}"#;
+        let violations = check(&rule, content);
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].message.contains("'moment'"));
+    }
+
+    #[test]
+    fn invalid_package_spec_errors() {
+        let config = RuleConfig {
+            id: "test".into(),
+            packages: vec!["react ~18".to_string()],
+            ..Default::default()
+        };
+        let err = BannedDependencyRule::new(&config).unwrap_err();
+        assert!(matches!(err, RuleBuildError::InvalidField(_, "packages", _)));
+    }
+
+    #[test]
+    fn detects_cargo_dependencies() {
+        let rule = make_rule(vec!["openssl", "time <0.3"]);
+        let content = r#"[package]
+name = "app"
+
+[dependencies]
+serde = "1"
+time = { version = "0.2.27", features = ["std"] }
+tls = { package = "openssl", version = "0.10" }
+
+[dev-dependencies]
+time = "0.3"
+
+[target.'cfg(unix)'.build-dependencies.openssl]
+version = "0.10"
+"#;
+        let violations = check_in(&rule, "crates/app/Cargo.toml", content);
+        assert_eq!(lines(&violations), vec![6, 7, 12]);
+        assert!(violations[1].message.contains("in dependencies"));
+        assert!(violations[2].message.contains("target.cfg(unix).build-dependencies"));
+    }
+
+    #[test]
+    fn detects_requirements() {
+        let rule = make_rule(vec!["PyYAML <5.4", "requests"]);
+        let content = "# pinned\n-r base.txt\npyyaml==5.3.1  # CVE\nRequests[socks]>=2.0 ; python_version > '3'\nflask\n";
+        let violations = check_in(&rule, "requirements-dev.txt", content);
+        assert_eq!(lines(&violations), vec![3, 4]);
+        assert!(violations[0].message.contains("'pyyaml' ==5.3.1"));
+    }
+
+    #[test]
+    fn detects_pyproject_and_go_mod() {
+        let rule = make_rule(vec!["requests", "github.com/pkg/errors", "django <4"]);
+        let pyproject = r#"[project]
+name = "app"
+dependencies = [
+  "requests>=2",
+]
+
+[tool.poetry.group.dev.dependencies]
+django = "^3.2"
+"#;
+        assert_eq!(lines(&check_in(&rule, "pyproject.toml", pyproject)), vec![4, 8]);
+
+        let go_mod = "module example.com/app\n\nrequire github.com/pkg/errors v0.9.1\n\nrequire (\n\tgolang.org/x/sync v0.1.0\n\tgithub.com/pkg/errors v0.9.1 // indirect\n)\n";
+        assert_eq!(lines(&check_in(&rule, "go.mod", go_mod)), vec![3, 7]);
+    }
 }
@@ -1,17 +1,31 @@
 use crate::config::{RuleConfig, Severity};
-use crate::rules::{Rule, RuleBuildError, ScanContext, Violation};
+use crate::line_endings;
+use crate::rules::{regex_pool, Rule, RuleBuildError, ScanContext, Violation};
 use regex::Regex;
+use std::sync::Arc;
 
 /// Scans source files for import/require statements referencing banned packages.
 ///
 /// Detects patterns like:
-/// - `import ... from 'pkg'`
-/// - `import 'pkg'`
+/// - `import ... from 'pkg'`, `import 'pkg'`, `export ... from 'pkg'`
 /// - `require('pkg')`
+/// - Python `import pkg` and `from pkg import ...` (in `.py` files)
+/// - Rust `use pkg::...` and `extern crate pkg` (in `.rs` files)
 /// - Subpath imports like `import ... from 'lodash/debounce'`
 ///
+/// A `*` in a package matches one path segment, so `lodash/*` bans every
+/// subpath of lodash but not `lodash` itself, and `@scope/*` bans every
+/// package in the scope. Rust and Python spell `-` as `_` and subpaths with
+/// `::` and `.`, which the patterns account for.
+///
 /// Uses word-boundary matching to avoid false positives (e.g., `moment` won't
 /// match `momentum`).
+///
+/// Without a `glob`, the rule checks files whose type (see `[filetypes]`) is
+/// one of `FILE_TYPES`.
+/// File types checked when no `glob` is configured.
+const FILE_TYPES: &[&str] = &["ts", "tsx", "js", "jsx", "mjs", "cjs", "py", "pyi", "rs"];
+
 #[derive(Debug)]
 pub struct BannedImportRule {
     id: String,
@@ -19,9 +33,46 @@ pub struct BannedImportRule {
     message: String,
     suggest: Option<String>,
     glob: Option<String>,
-    #[allow(dead_code)]
-    packages: Vec<String>,
-    import_re: Regex,
+    /// Literals one of which every flaggable file contains; `None` when a
+    /// package starts with a wildcard.
+    prefilter: Option<Vec<String>>,
+    import_re: Arc<Regex>,
+    python_re: Arc<Regex>,
+    rust_re: Arc<Regex>,
+}
+
+/// `package` as a regex, with `-`, `/` and `*` spelled as in the target language.
+fn package_pattern(package: &str, separator: &str, wildcard: &str, snake_case: bool) -> String {
+    package
+        .split('/')
+        .map(|segment| {
+            segment
+                .split('*')
+                .map(|literal| {
+                    let literal = if snake_case { literal.replace('-', "_") } else { literal.to_string() };
+                    regex::escape(&literal)
+                })
+                .collect::<Vec<_>>()
+                .join(wildcard)
+        })
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+/// The literal leading name of each package, in both spellings of `-`.
+fn prefilter_literals(packages: &[String]) -> Option<Vec<String>> {
+    let mut literals = Vec::new();
+    for package in packages {
+        let head = package.split(['/', '*']).next().unwrap_or_default();
+        if head.is_empty() {
+            return None;
+        }
+        literals.push(head.to_string());
+        if head.contains('-') {
+            literals.push(head.replace('-', "_"));
+        }
+    }
+    Some(literals)
 }
 
 impl BannedImportRule {
@@ -33,14 +84,16 @@ impl BannedImportRule {
             ));
         }
 
-        // Build a regex that matches import/require of any banned package.
-        // Escaped package names joined with | to form alternatives.
-        let escaped: Vec<String> = config
-            .packages
-            .iter()
-            .map(|p| regex::escape(p))
-            .collect();
-        let pkg_group = escaped.join("|");
+        // Each language's spelling of the banned packages, joined with | to form alternatives.
+        let group = |separator: &str, wildcard: &str, snake_case: bool| {
+            config
+                .packages
+                .iter()
+                .map(|p| package_pattern(p, separator, wildcard, snake_case))
+                .collect::<Vec<_>>()
+                .join("|")
+        };
+        let build = |pattern: String| regex_pool::compile(config, &pattern);
 
         // Match:
         //   import ... from ['"]pkg['"]      (named/default import)
@@ -48,26 +101,45 @@ impl BannedImportRule {
         //   require\(['"]pkg['"]\)           (CommonJS require)
         //   export ... from ['"]pkg['"]      (re-exports)
         // Also match subpath imports: pkg/subpath
-        let pattern = format!(
-            r#"(?:import\s+.*?\s+from\s+|import\s+|export\s+.*?\s+from\s+|require\s*\(\s*)['"]({})(?:/[^'"]*)?['"]"#,
-            pkg_group
-        );
+        let import_re = build(format!(
+            r#"(?:import\s+.*?\s+from\s+|import\s+|export\s+.*?\s+from\s+|require\s*\(\s*)['"]((?:{})(?:/[^'"]*)?)['"]"#,
+            group("/", r#"[^/'"]+"#, false)
+        ))?;
 
-        let import_re = Regex::new(&pattern)
-            .map_err(|e| RuleBuildError::InvalidRegex(config.id.clone(), e))?;
+        // Python: import pkg[.sub][, ...] / from pkg[.sub] import ...
+        let python = group(".", r"\w+", true);
+        let python_re = build(format!(
+            r"^\s*(?:from\s+((?:{0})(?:\.[\w.]+)?)\s+import\b|import\s+(?:[\w.]+\s*,\s*)*((?:{0})(?:\.[\w.]+)?)\b)",
+            python
+        ))?;
 
-        let default_glob = "**/*.{ts,tsx,js,jsx,mjs,cjs}".to_string();
+        // Rust: use pkg::... / use pkg; / use pkg as x; / extern crate pkg
+        let rust_re = build(format!(
+            r"\b(?:use|extern\s+crate)\s+(?:::)?((?:{}))(?:::|\s*;|\s+as\b)",
+            group("::", r"\w+", true)
+        ))?;
 
         Ok(Self {
             id: config.id.clone(),
             severity: config.severity,
             message: config.message.clone(),
             suggest: config.suggest.clone(),
-            glob: config.glob.clone().or(Some(default_glob)),
-            packages: config.packages.clone(),
+            glob: config.glob.clone(),
+            prefilter: prefilter_literals(&config.packages),
             import_re,
+            python_re,
+            rust_re,
         })
     }
+
+    /// The import pattern for a file of type `file_type`.
+    fn pattern_for(&self, file_type: Option<&str>) -> &Regex {
+        match file_type {
+            Some("py" | "pyi") => &self.python_re,
+            Some("rs") => &self.rust_re,
+            _ => &self.import_re,
+        }
+    }
 }
 
 impl Rule for BannedImportRule {
@@ -83,12 +155,20 @@ impl Rule for BannedImportRule {
         self.glob.as_deref()
     }
 
+    fn prefilter(&self) -> Option<&[String]> {
+        self.prefilter.as_deref()
+    }
+
     fn check_file(&self, ctx: &ScanContext) -> Vec<Violation> {
+        if self.glob.is_none() && !ctx.file_type.is_some_and(|t| FILE_TYPES.contains(&t)) {
+            return Vec::new();
+        }
         let mut violations = Vec::new();
+        let import_re = self.pattern_for(ctx.file_type);
 
-        for (line_idx, line) in ctx.content.lines().enumerate() {
-            for cap in self.import_re.captures_iter(line) {
-                let matched_pkg = cap.get(1).unwrap().as_str();
+        for (line_idx, line) in line_endings::lines(ctx.content).enumerate() {
+            for cap in import_re.captures_iter(line) {
+                let matched_pkg = cap.iter().skip(1).flatten().next().unwrap().as_str();
                 let full_match = cap.get(0).unwrap();
 
                 violations.push(Violation {
@@ -101,6 +181,7 @@ impl Rule for BannedImportRule {
                     suggest: self.suggest.clone(),
                     source_line: Some(line.to_string()),
                     fix: None,
+                    url: None,
                 });
             }
         }
@@ -112,6 +193,7 @@ impl Rule for BannedImportRule {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::path_class::PathClass;
     use std::path::Path;
 
     fn make_rule(packages: Vec<&str>) -> BannedImportRule {
@@ -127,9 +209,16 @@ mod tests {
     }
 
     fn check(rule: &BannedImportRule, content: &str) -> Vec<Violation> {
+        check_in(rule, "test.ts", content)
+    }
+
+    fn check_in(rule: &BannedImportRule, file: &str, content: &str) -> Vec<Violation> {
         let ctx = ScanContext {
-            file_path: Path::new("test.ts"),
+            file_path: Path::new(file),
             content,
+            class: PathClass::FirstParty,
+            file_type: Path::new(file).extension().and_then(|e| e.to_str()),
+            changed_ranges: None,
         };
         rule.check_file(&ctx)
     }
@@ -222,8 +311,60 @@ import { jsx } from '@emotion/react';"#;
     }
 
     #[test]
-    fn default_glob_set() {
+    fn checks_known_file_types_without_glob() {
+        let rule = make_rule(vec!["moment"]);
+        assert_eq!(rule.file_glob(), None);
+        let content = "import moment from 'moment';";
+        assert!(check_in(&rule, "notes.md", content).is_empty());
+
+        // A `[filetypes]` alias is checked like the type it maps to
+        let ctx = ScanContext {
+            file_path: Path::new("App.mjsx"),
+            content,
+            class: PathClass::FirstParty,
+            file_type: Some("jsx"),
+            changed_ranges: None,
+        };
+        assert_eq!(rule.check_file(&ctx).len(), 1);
+    }
+
+    #[test]
+    fn wildcards_match_one_segment() {
+        let rule = make_rule(vec!["lodash/*", "@legacy/*"]);
+        let content = r#"import _ from 'lodash';
+import debounce from 'lodash/debounce';
+import x from 'lodash/fp/map';
+import { Button } from '@legacy/ui';
+import { Card } from '@modern/ui';"#;
+        let violations = check(&rule, content);
+        let lines: Vec<_> = violations.iter().map(|v| v.line.unwrap()).collect();
+        assert_eq!(lines, vec![2, 3, 4]);
+        assert!(violations[0].message.ends_with("'lodash/debounce'"));
+        assert_eq!(rule.prefilter(), Some(&["lodash".to_string(), "@legacy".to_string()][..]));
+    }
+
+    #[test]
+    fn detects_rust_use_and_extern_crate() {
+        let rule = make_rule(vec!["lazy-static", "failure"]);
+        let content = "use lazy_static::lazy_static;\nuse failure;\nextern crate failure as f;\nuse failure_derive::Fail;\nlet failure = 1;";
+        let violations = check_in(&rule, "src/lib.rs", content);
+        let lines: Vec<_> = violations.iter().map(|v| v.line.unwrap()).collect();
+        assert_eq!(lines, vec![1, 2, 3]);
+        assert!(violations[0].message.ends_with("'lazy_static'"));
+        assert!(rule.prefilter().unwrap().contains(&"lazy_static".to_string()));
+
+        let std_rule = make_rule(vec!["std/sync/mpsc"]);
+        assert_eq!(check_in(&std_rule, "a.rs", "use std::sync::mpsc::channel;").len(), 1);
+        assert!(check_in(&std_rule, "a.rs", "use std::sync::Mutex;").is_empty());
+    }
+
+    #[test]
+    fn detects_python_imports() {
         let rule = make_rule(vec!["moment"]);
-        assert_eq!(rule.file_glob(), Some("**/*.{ts,tsx,js,jsx,mjs,cjs}"));
+        let content = "import moment\nfrom moment import format\nimport os, moment.tz\nimport momentum\n# import moment";
+        let lines: Vec<_> = check_in(&rule, "app.py", content).iter().map(|v| v.line.unwrap()).collect();
+        assert_eq!(lines, vec![1, 2, 3]);
+        // JS syntax in a Python file isn't Python
+        assert!(check_in(&rule, "app.py", "const m = require('moment');").is_empty());
     }
 }
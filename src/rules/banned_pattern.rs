@@ -1,11 +1,17 @@
 use crate::config::{RuleConfig, Severity};
+use crate::line_endings;
+use crate::rules::patterns::PatternSet;
 use crate::rules::{Rule, RuleBuildError, ScanContext, Violation};
-use regex::Regex;
 
-/// Scans files line-by-line for a literal string or regex match.
+/// Scans files line-by-line for literal string or regex matches.
 ///
 /// Useful for banning code patterns like `style={{`, `console.log(`, `// @ts-ignore`, etc.
 /// When `regex` is true in the config, the pattern is treated as a regular expression.
+/// A family of related patterns can be banned together with `patterns`.
+/// `case_insensitive`, `word_boundary` and `multiline` (search the whole
+/// file, so a pattern can span lines) change how every pattern matches, and
+/// `skip_comments` / `skip_strings` ignore matches in comments and string
+/// literals, as the file's type writes them.
 #[derive(Debug)]
 pub struct BannedPatternRule {
     id: String,
@@ -13,26 +19,12 @@ pub struct BannedPatternRule {
     message: String,
     suggest: Option<String>,
     glob: Option<String>,
-    pattern: String,
-    compiled_regex: Option<Regex>,
+    patterns: PatternSet,
 }
 
 impl BannedPatternRule {
     pub fn new(config: &RuleConfig) -> Result<Self, RuleBuildError> {
-        let pattern = config
-            .pattern
-            .as_ref()
-            .filter(|p| !p.is_empty())
-            .ok_or_else(|| RuleBuildError::MissingField(config.id.clone(), "pattern"))?
-            .clone();
-
-        let compiled_regex = if config.regex {
-            let re = Regex::new(&pattern)
-                .map_err(|e| RuleBuildError::InvalidRegex(config.id.clone(), e))?;
-            Some(re)
-        } else {
-            None
-        };
+        let patterns = PatternSet::new(config)?;
 
         Ok(Self {
             id: config.id.clone(),
@@ -40,10 +32,32 @@ impl BannedPatternRule {
             message: config.message.clone(),
             suggest: config.suggest.clone(),
             glob: config.glob.clone(),
-            pattern,
-            compiled_regex,
+            patterns,
         })
     }
+
+    /// Report each non-overlapping match on the 0-based line `line_idx`.
+    fn check_line(&self, ctx: &ScanContext, line_idx: usize, line: &str, violations: &mut Vec<Violation>) {
+        for (start, _, index) in self.patterns.find_all(line, false) {
+            violations.push(self.violation(ctx, line_idx, line, start, index));
+        }
+    }
+
+    /// A match of pattern `index` at byte `column` of the 0-based line `line_idx`.
+    fn violation(&self, ctx: &ScanContext, line_idx: usize, line: &str, column: usize, index: usize) -> Violation {
+        Violation {
+            rule_id: self.id.clone(),
+            severity: self.severity,
+            file: ctx.file_path.to_path_buf(),
+            line: Some(line_idx + 1),
+            column: Some(column + 1),
+            message: self.patterns.message(index, &self.message),
+            suggest: self.patterns.suggest(index, self.suggest.as_ref()),
+            source_line: Some(line.to_string()),
+            fix: None,
+            url: None,
+        }
+    }
 }
 
 impl Rule for BannedPatternRule {
@@ -59,48 +73,35 @@ impl Rule for BannedPatternRule {
         self.glob.as_deref()
     }
 
+    fn prefilter(&self) -> Option<&[String]> {
+        self.patterns.prefilter()
+    }
+
+    fn line_patterns(&self) -> Option<&PatternSet> {
+        (!self.patterns.whole_file()).then_some(&self.patterns)
+    }
+
     fn check_file(&self, ctx: &ScanContext) -> Vec<Violation> {
+        if self.patterns.whole_file() {
+            return self
+                .patterns
+                .find_in_content(ctx.content, ctx.file_type, false)
+                .into_iter()
+                .map(|(line_idx, line, column, index)| self.violation(ctx, line_idx, line, column, index))
+                .collect();
+        }
         let mut violations = Vec::new();
-
-        for (line_idx, line) in ctx.content.lines().enumerate() {
-            if let Some(ref re) = self.compiled_regex {
-                // Regex mode: report each match
-                for m in re.find_iter(line) {
-                    violations.push(Violation {
-                        rule_id: self.id.clone(),
-                        severity: self.severity,
-                        file: ctx.file_path.to_path_buf(),
-                        line: Some(line_idx + 1),
-                        column: Some(m.start() + 1),
-                        message: self.message.clone(),
-                        suggest: self.suggest.clone(),
-                        source_line: Some(line.to_string()),
-                        fix: None,
-                    });
-                }
-            } else {
-                // Literal mode: find all occurrences
-                let pat = self.pattern.as_str();
-                let pat_len = pat.len();
-                let mut search_start = 0;
-                while let Some(pos) = line[search_start..].find(pat) {
-                    let col = search_start + pos;
-                    violations.push(Violation {
-                        rule_id: self.id.clone(),
-                        severity: self.severity,
-                        file: ctx.file_path.to_path_buf(),
-                        line: Some(line_idx + 1),
-                        column: Some(col + 1),
-                        message: self.message.clone(),
-                        suggest: self.suggest.clone(),
-                        source_line: Some(line.to_string()),
-                        fix: None,
-                    });
-                    search_start = col + pat_len;
-                }
-            }
+        for (line_idx, line) in line_endings::lines(ctx.content).enumerate() {
+            self.check_line(ctx, line_idx, line, &mut violations);
         }
+        violations
+    }
 
+    fn check_lines(&self, ctx: &ScanContext, lines: &[&str], candidates: &[usize]) -> Vec<Violation> {
+        let mut violations = Vec::new();
+        for &line_idx in candidates {
+            self.check_line(ctx, line_idx, lines[line_idx], &mut violations);
+        }
         violations
     }
 }
@@ -108,6 +109,8 @@ impl Rule for BannedPatternRule {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::RulePattern;
+    use crate::path_class::PathClass;
     use std::path::Path;
 
     fn make_config(pattern: &str, regex: bool) -> RuleConfig {
@@ -126,6 +129,9 @@ mod tests {
         let ctx = ScanContext {
             file_path: Path::new("test.tsx"),
             content,
+            class: PathClass::FirstParty,
+            file_type: None,
+            changed_ranges: None,
         };
         rule.check_file(&ctx)
     }
@@ -225,4 +231,110 @@ mod tests {
         assert_eq!(violations[0].suggest.as_deref(), Some("remove this pattern"));
         assert!(violations[0].source_line.is_some());
     }
+
+    #[test]
+    fn case_insensitive_and_word_boundary_literals() {
+        let mut config = make_config("todo", false);
+        config.case_insensitive = true;
+        config.word_boundary = true;
+        let rule = BannedPatternRule::new(&config).unwrap();
+        assert!(rule.prefilter().is_none());
+        let violations = check(&rule, "// TODO: x
+const todos = 1; // Todo");
+        let found: Vec<_> = violations.iter().map(|v| (v.line, v.column)).collect();
+        assert_eq!(found, [(Some(1), Some(4)), (Some(2), Some(21))]);
+
+        // Only the ends that are word characters get a boundary
+        let mut config = make_config("eval(", false);
+        config.word_boundary = true;
+        let rule = BannedPatternRule::new(&config).unwrap();
+        assert_eq!(check(&rule, "eval(a); safeEval(b); eval (c)").len(), 1);
+
+        let mut config = make_config(r"is(Nan|Finite)", true);
+        config.word_boundary = true;
+        let rule = BannedPatternRule::new(&config).unwrap();
+        assert_eq!(check(&rule, "isNan(x) || isFinite(y) || thisNan").len(), 2);
+    }
+
+    #[test]
+    fn multiline_patterns_span_lines() {
+        let mut config = make_config(r"catch \(\w+\) \{\s*\}", true);
+        config.multiline = true;
+        let rule = BannedPatternRule::new(&config).unwrap();
+        assert!(rule.line_patterns().is_none());
+        let content = "try {\r\n  run();\r\n} catch (e) {\r\n}\r\nfoo();\r\n} catch (e) { log(e); }\r\n";
+        let violations = check(&rule, content);
+        assert_eq!(violations.len(), 1);
+        assert_eq!((violations[0].line, violations[0].column), (Some(3), Some(3)));
+        assert_eq!(violations[0].source_line.as_deref(), Some("} catch (e) {"));
+
+        // A newline in a literal matches CRLF too, and anchors match per line
+        let mut config = make_config("});\n});", false);
+        config.multiline = true;
+        let rule = BannedPatternRule::new(&config).unwrap();
+        let found: Vec<_> = check(&rule, "a\r\n  });\r\n});\n").iter().map(|v| (v.line, v.column)).collect();
+        assert_eq!(found, [(Some(2), Some(3))]);
+        let mut config = make_config(r"^\}$", true);
+        config.multiline = true;
+        let rule = BannedPatternRule::new(&config).unwrap();
+        assert_eq!(check(&rule, "{\r\n}\r\n  }\r\n").len(), 1);
+    }
+
+    #[test]
+    fn skips_comments_and_strings_by_file_type() {
+        let mut config = make_config("legacyFetch(", false);
+        config.skip_comments = true;
+        config.skip_strings = true;
+        let rule = BannedPatternRule::new(&config).unwrap();
+        assert!(rule.line_patterns().is_none());
+        let content = "// TODO: remove legacyFetch(\nlegacyFetch(url); /* legacyFetch( */\nalert(\"legacyFetch( failed\");\n";
+        let check_as = |path: &str, file_type: &str| {
+            let ctx = ScanContext {
+                file_path: Path::new(path),
+                content,
+                class: PathClass::FirstParty,
+                file_type: Some(file_type),
+                changed_ranges: None,
+            };
+            rule.check_file(&ctx)
+        };
+        let violations = check_as("a.ts", "ts");
+        assert_eq!(violations.len(), 1);
+        assert_eq!((violations[0].line, violations[0].column), (Some(2), Some(1)));
+        assert_eq!(violations[0].source_line.as_deref(), Some("legacyFetch(url); /* legacyFetch( */"));
+        // Types without a known syntax are searched as they are
+        assert_eq!(check_as("a.txt", "txt").len(), 4);
+    }
+
+    #[test]
+    fn multiple_patterns_report_which_matched() {
+        let mut config = make_config("eval(", false);
+        config.patterns = vec![
+            RulePattern {
+                pattern: "new Function(".into(),
+                ..Default::default()
+            },
+            RulePattern {
+                pattern: r#"setTimeout\(\s*['"]"#.into(),
+                message: Some("setTimeout with a string evaluates code".into()),
+                suggest: Some("pass a function".into()),
+                regex: Some(true),
+            },
+        ];
+        let rule = BannedPatternRule::new(&config).unwrap();
+        assert!(rule.prefilter().is_none());
+        let violations = check(&rule, "eval(a); new Function(b);\nsetTimeout('c()', 1);");
+        let found: Vec<_> = violations
+            .iter()
+            .map(|v| (v.line, v.column, v.message.as_str(), v.suggest.as_deref()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (Some(1), Some(1), "banned pattern found: 'eval('", Some("remove this pattern")),
+                (Some(1), Some(10), "banned pattern found: 'new Function('", Some("remove this pattern")),
+                (Some(2), Some(1), "setTimeout with a string evaluates code", Some("pass a function")),
+            ]
+        );
+    }
 }
@@ -0,0 +1,289 @@
+use crate::config::{RuleConfig, Severity};
+use crate::rules::factory;
+use crate::rules::{Rule, RuleBuildError, ScanContext, Violation};
+use std::collections::BTreeMap;
+
+/// Where component matches must coincide.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Scope {
+    /// Components must (not) match on the same line.
+    Line,
+    /// Components must (not) match anywhere in the same file.
+    File,
+}
+
+/// Combines other rule types with AND/OR/NOT semantics.
+///
+/// A component "matches" wherever it would report a violation. The composite
+/// fires where every `all_of` component matches, at least one `any_of`
+/// component matches (when any are given), and no `none_of` component
+/// matches — on the same line, or anywhere in the same file with
+/// `scope = "file"`. Violations are reported at the lines of the positive
+/// component matches. Component globs are ignored; the composite's own
+/// `glob` selects files.
+pub struct CompositeRule {
+    id: String,
+    severity: Severity,
+    message: String,
+    suggest: Option<String>,
+    glob: Option<String>,
+    scope: Scope,
+    all_of: Vec<Box<dyn Rule>>,
+    any_of: Vec<Box<dyn Rule>>,
+    none_of: Vec<Box<dyn Rule>>,
+}
+
+impl std::fmt::Debug for CompositeRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CompositeRule")
+            .field("id", &self.id)
+            .field("scope", &self.scope)
+            .field("all_of", &self.all_of.len())
+            .field("any_of", &self.any_of.len())
+            .field("none_of", &self.none_of.len())
+            .finish()
+    }
+}
+
+impl CompositeRule {
+    pub fn new(config: &RuleConfig) -> Result<Self, RuleBuildError> {
+        if config.all_of.is_empty() && config.any_of.is_empty() {
+            return Err(RuleBuildError::MissingField(config.id.clone(), "all_of"));
+        }
+
+        let scope = match config.scope.as_deref() {
+            None | Some("line") => Scope::Line,
+            Some("file") => Scope::File,
+            Some(other) => {
+                return Err(RuleBuildError::InvalidField(
+                    config.id.clone(),
+                    "scope",
+                    format!("expected \"line\" or \"file\", got \"{}\"", other),
+                ))
+            }
+        };
+
+        let build = |components: &[(String, RuleConfig)], field: &'static str| {
+            components
+                .iter()
+                .map(|(rule_type, component)| {
+                    factory::build_rule(rule_type, component).map_err(|e| {
+                        RuleBuildError::InvalidField(config.id.clone(), field, e.to_string())
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()
+        };
+
+        Ok(Self {
+            id: config.id.clone(),
+            severity: config.severity,
+            message: config.message.clone(),
+            suggest: config.suggest.clone(),
+            glob: config.glob.clone(),
+            scope,
+            all_of: build(&config.all_of, "all_of")?,
+            any_of: build(&config.any_of, "any_of")?,
+            none_of: build(&config.none_of, "none_of")?,
+        })
+    }
+}
+
+/// One representative violation per line for a component, keyed by line
+/// (`0` for violations without a line).
+fn matches(rule: &dyn Rule, ctx: &ScanContext) -> BTreeMap<usize, Violation> {
+    let mut by_line = BTreeMap::new();
+    for v in rule.check_file(ctx) {
+        by_line.entry(v.line.unwrap_or(0)).or_insert(v);
+    }
+    by_line
+}
+
+impl Rule for CompositeRule {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    fn file_glob(&self) -> Option<&str> {
+        self.glob.as_deref()
+    }
+
+    /// Only when every component is.
+    fn cacheable(&self) -> bool {
+        self.all_of.iter().chain(&self.any_of).chain(&self.none_of).all(|r| r.cacheable())
+    }
+
+    fn check_file(&self, ctx: &ScanContext) -> Vec<Violation> {
+        let all: Vec<_> = self.all_of.iter().map(|r| matches(r.as_ref(), ctx)).collect();
+        let any: Vec<_> = self.any_of.iter().map(|r| matches(r.as_ref(), ctx)).collect();
+        let none: Vec<_> = self.none_of.iter().map(|r| matches(r.as_ref(), ctx)).collect();
+
+        // Candidate hits come from the first positive component list
+        let candidates: BTreeMap<usize, &Violation> = match all.first() {
+            Some(first) => first.iter().map(|(l, v)| (*l, v)).collect(),
+            None => {
+                let mut union = BTreeMap::new();
+                for m in &any {
+                    for (l, v) in m {
+                        union.entry(*l).or_insert(v);
+                    }
+                }
+                union
+            }
+        };
+
+        let hits: Vec<&Violation> = match self.scope {
+            Scope::Line => candidates
+                .into_iter()
+                .filter(|(line, _)| {
+                    all.iter().all(|m| m.contains_key(line))
+                        && (any.is_empty() || any.iter().any(|m| m.contains_key(line)))
+                        && !none.iter().any(|m| m.contains_key(line))
+                })
+                .map(|(_, v)| v)
+                .collect(),
+            Scope::File => {
+                let fires = all.iter().all(|m| !m.is_empty())
+                    && (any.is_empty() || any.iter().any(|m| !m.is_empty()))
+                    && none.iter().all(|m| m.is_empty());
+                if fires {
+                    candidates.into_values().collect()
+                } else {
+                    Vec::new()
+                }
+            }
+        };
+
+        hits.into_iter()
+            .map(|v| Violation {
+                rule_id: self.id.clone(),
+                severity: self.severity,
+                file: ctx.file_path.to_path_buf(),
+                line: v.line,
+                column: v.column,
+                message: if self.message.is_empty() {
+                    v.message.clone()
+                } else {
+                    self.message.clone()
+                },
+                suggest: self.suggest.clone().or_else(|| v.suggest.clone()),
+                source_line: v.source_line.clone(),
+                fix: None,
+                url: None,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path_class::PathClass;
+    use std::path::Path;
+
+    fn pattern(p: &str) -> (String, RuleConfig) {
+        (
+            "banned-pattern".into(),
+            RuleConfig {
+                id: "composite".into(),
+                pattern: Some(p.into()),
+                ..Default::default()
+            },
+        )
+    }
+
+    fn make_config(
+        scope: &str,
+        all_of: Vec<(String, RuleConfig)>,
+        any_of: Vec<(String, RuleConfig)>,
+        none_of: Vec<(String, RuleConfig)>,
+    ) -> RuleConfig {
+        RuleConfig {
+            id: "composite".into(),
+            severity: Severity::Error,
+            message: "use apiClient".into(),
+            scope: Some(scope.into()),
+            all_of,
+            any_of,
+            none_of,
+            ..Default::default()
+        }
+    }
+
+    fn check(config: &RuleConfig, content: &str) -> Vec<Violation> {
+        let rule = CompositeRule::new(config).unwrap();
+        let ctx = ScanContext {
+            file_path: Path::new("a.ts"),
+            content,
+            class: PathClass::FirstParty,
+            file_type: None,
+            changed_ranges: None,
+        };
+        rule.check_file(&ctx)
+    }
+
+    #[test]
+    fn file_scope_none_of_suppresses_whole_file() {
+        let config = make_config(
+            "file",
+            vec![pattern("fetch(")],
+            vec![],
+            vec![pattern("from './apiClient'")],
+        );
+
+        let raw = check(&config, "const r = fetch('/x');\n");
+        assert_eq!(raw.len(), 1);
+        assert_eq!(raw[0].rule_id, "composite");
+        assert_eq!(raw[0].line, Some(1));
+        assert_eq!(raw[0].message, "use apiClient");
+
+        let wrapped = check(
+            &config,
+            "import { api } from './apiClient';\nconst r = fetch('/x');\n",
+        );
+        assert!(wrapped.is_empty());
+    }
+
+    #[test]
+    fn line_scope_requires_all_on_same_line() {
+        let config = make_config(
+            "line",
+            vec![pattern("useEffect"), pattern("fetch(")],
+            vec![],
+            vec![],
+        );
+        let v = check(&config, "useEffect(() => {\n  fetch('/x');\n});\nuseEffect(() => fetch('/y'));\n");
+        assert_eq!(v.len(), 1);
+        assert_eq!(v[0].line, Some(4));
+    }
+
+    #[test]
+    fn any_of_is_a_union() {
+        let config = make_config("line", vec![], vec![pattern("alert("), pattern("confirm(")], vec![]);
+        let v = check(&config, "alert(1);\nok();\nconfirm(2);\n");
+        let lines: Vec<_> = v.iter().map(|v| v.line).collect();
+        assert_eq!(lines, [Some(1), Some(3)]);
+    }
+
+    #[test]
+    fn requires_a_positive_component() {
+        let config = make_config("line", vec![], vec![], vec![pattern("x")]);
+        assert!(matches!(
+            CompositeRule::new(&config),
+            Err(RuleBuildError::MissingField(_, "all_of"))
+        ));
+    }
+
+    #[test]
+    fn rejects_unknown_scope_and_component_type() {
+        let config = make_config("block", vec![pattern("x")], vec![], vec![]);
+        assert!(CompositeRule::new(&config).is_err());
+
+        let bad = make_config("line", vec![("nope".into(), RuleConfig::default())], vec![], vec![]);
+        let err = CompositeRule::new(&bad).unwrap_err().to_string();
+        assert!(err.contains("unknown rule type"));
+    }
+}
@@ -0,0 +1,382 @@
+use crate::config::{RuleConfig, Severity};
+use crate::line_endings;
+use crate::rules::{RepoContext, RepoRule, Rule, RuleBuildError, ScanContext, Violation};
+use crate::scan::compile_glob_set;
+use globset::GlobSet;
+use serde::Deserialize;
+use serde_json::json;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Seconds a command may run when the rule sets no `timeout_secs`.
+const DEFAULT_TIMEOUT_SECS: u64 = 30;
+
+/// Runs a team's own checker and reports what it finds.
+///
+/// By default `command` runs once per matching file, with the file's path
+/// as its last argument and its contents on stdin. With `batch`, it runs
+/// once per scan and gets `{"files": [{"path", "content"}]}` on stdin
+/// instead. Either way it prints its findings to stdout as JSON: a list of
+/// `{file, line, column, message, severity, suggest}` objects (or an object
+/// with that list under `violations`), where only `message` is required, and
+/// `file` only in batch mode. No output means no findings.
+///
+/// Exit status 0 or 1 counts as a clean run, so linters that exit 1 on
+/// findings work as is. Any other status, output that isn't the JSON above,
+/// or running past `timeout_secs` is a failure, reported per `on_failure`.
+#[derive(Debug)]
+pub struct ExternalCommandRule {
+    id: String,
+    severity: Severity,
+    message: String,
+    suggest: Option<String>,
+    glob: Option<String>,
+    command: Vec<String>,
+    batch: bool,
+    timeout: Duration,
+    /// Severity of the violation reporting a failed run; `None` ignores failures.
+    on_failure: Option<Severity>,
+    /// Files a batch run covers (`glob` and `include`); `None` for all.
+    files: Option<GlobSet>,
+    exclude: Option<GlobSet>,
+}
+
+/// A finding as the command reports it.
+#[derive(Debug, Deserialize)]
+struct Finding {
+    file: Option<PathBuf>,
+    line: Option<usize>,
+    column: Option<usize>,
+    message: Option<String>,
+    severity: Option<Severity>,
+    suggest: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Output {
+    List(Vec<Finding>),
+    Object { violations: Vec<Finding> },
+}
+
+impl ExternalCommandRule {
+    pub fn new(config: &RuleConfig) -> Result<Self, RuleBuildError> {
+        if config.command.is_empty() {
+            return Err(RuleBuildError::MissingField(config.id.clone(), "command"));
+        }
+        let on_failure = match config.on_failure.as_deref() {
+            None | Some("error") => Some(Severity::Error),
+            Some("warning") => Some(Severity::Warning),
+            Some("ignore") => None,
+            Some(other) => {
+                return Err(RuleBuildError::InvalidField(
+                    config.id.clone(),
+                    "on_failure",
+                    format!("expected \"error\", \"warning\" or \"ignore\", got \"{}\"", other),
+                ))
+            }
+        };
+        let globs = |patterns: Vec<String>| {
+            (!patterns.is_empty())
+                .then(|| compile_glob_set(&patterns))
+                .transpose()
+                .map_err(|e| RuleBuildError::InvalidGlob(config.id.clone(), e))
+        };
+
+        Ok(Self {
+            id: config.id.clone(),
+            severity: config.severity,
+            message: config.message.clone(),
+            suggest: config.suggest.clone(),
+            glob: config.glob.clone(),
+            command: config.command.clone(),
+            batch: config.batch,
+            timeout: Duration::from_secs(config.timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS)),
+            on_failure,
+            files: globs(config.glob.iter().chain(&config.include).cloned().collect())?,
+            exclude: globs(config.exclude_glob.clone())?,
+        })
+    }
+
+    /// Run the command with `args` appended and `input` on stdin, returning
+    /// its stdout or why the run failed.
+    fn run(&self, args: &[&str], input: Vec<u8>) -> Result<Vec<u8>, String> {
+        let mut child = Command::new(&self.command[0])
+            .args(&self.command[1..])
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("couldn't start: {}", e))?;
+
+        // Pipes are fed and drained on their own threads, so a command that
+        // writes before it has read all its input can't deadlock
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        thread::spawn(move || stdin.write_all(&input));
+        let drain = |mut pipe: Box<dyn Read + Send>| {
+            thread::spawn(move || {
+                let mut buf = Vec::new();
+                let _ = pipe.read_to_end(&mut buf);
+                buf
+            })
+        };
+        let stdout = drain(Box::new(child.stdout.take().expect("stdout is piped")));
+        let stderr = drain(Box::new(child.stderr.take().expect("stderr is piped")));
+
+        let deadline = Instant::now() + self.timeout;
+        let status = loop {
+            match child.try_wait().map_err(|e| e.to_string())? {
+                Some(status) => break status,
+                None if Instant::now() >= deadline => {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    return Err(format!("timed out after {}s", self.timeout.as_secs()));
+                }
+                None => thread::sleep(Duration::from_millis(5)),
+            }
+        };
+        let stdout = stdout.join().unwrap_or_default();
+        if !matches!(status.code(), Some(0 | 1)) {
+            let stderr = String::from_utf8_lossy(&stderr.join().unwrap_or_default()).trim().to_string();
+            let status = status.code().map_or_else(|| "a signal".to_string(), |code| format!("status {}", code));
+            return Err(match stderr.lines().next() {
+                Some(line) => format!("exited with {}: {}", status, line),
+                None => format!("exited with {}", status),
+            });
+        }
+        Ok(stdout)
+    }
+
+    /// The command's findings in `stdout`.
+    fn parse(stdout: &[u8]) -> Result<Vec<Finding>, String> {
+        if stdout.iter().all(u8::is_ascii_whitespace) {
+            return Ok(Vec::new());
+        }
+        match serde_json::from_slice(stdout) {
+            Ok(Output::List(findings) | Output::Object { violations: findings }) => Ok(findings),
+            Err(e) => Err(format!("printed invalid output: {}", e)),
+        }
+    }
+
+    fn violation(&self, file: PathBuf, content: Option<&str>, finding: Finding) -> Violation {
+        let source_line = finding
+            .line
+            .zip(content)
+            .and_then(|(line, content)| line_endings::lines(content).nth(line.checked_sub(1)?))
+            .map(String::from);
+        Violation {
+            rule_id: self.id.clone(),
+            severity: finding.severity.unwrap_or(self.severity),
+            file,
+            line: finding.line,
+            column: finding.column,
+            message: finding.message.unwrap_or_else(|| self.message.clone()),
+            suggest: finding.suggest.or_else(|| self.suggest.clone()),
+            source_line,
+            fix: None,
+            url: None,
+        }
+    }
+
+    /// The violation reporting a failed run on `file`, unless failures are ignored.
+    fn failure(&self, file: PathBuf, reason: String) -> Option<Violation> {
+        Some(Violation {
+            rule_id: self.id.clone(),
+            severity: self.on_failure?,
+            file,
+            line: None,
+            column: None,
+            message: format!("external command '{}' {}", self.command[0], reason),
+            suggest: None,
+            source_line: None,
+            fix: None,
+            url: None,
+        })
+    }
+
+    fn covers(&self, path: &Path) -> bool {
+        let normalized = path.to_string_lossy().replace('\\', "/");
+        let path = normalized.trim_start_matches("./");
+        self.files.as_ref().is_none_or(|files| files.is_match(path))
+            && !self.exclude.as_ref().is_some_and(|exclude| exclude.is_match(path))
+    }
+}
+
+impl Rule for ExternalCommandRule {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    fn file_glob(&self) -> Option<&str> {
+        self.glob.as_deref()
+    }
+
+    /// The command may read anything.
+    fn cacheable(&self) -> bool {
+        false
+    }
+
+    fn check_file(&self, ctx: &ScanContext) -> Vec<Violation> {
+        if self.batch {
+            // Batch runs cover every file at once, via check_repo
+            return Vec::new();
+        }
+        let path = ctx.file_path.to_string_lossy();
+        let findings = self
+            .run(&[&path], ctx.content.as_bytes().to_vec())
+            .and_then(|stdout| Self::parse(&stdout));
+        match findings {
+            Ok(findings) => findings
+                .into_iter()
+                .map(|finding| self.violation(ctx.file_path.to_path_buf(), Some(ctx.content), finding))
+                .collect(),
+            Err(reason) => self.failure(ctx.file_path.to_path_buf(), reason).into_iter().collect(),
+        }
+    }
+}
+
+impl RepoRule for ExternalCommandRule {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    fn check_repo(&self, repo: &RepoContext) -> Vec<Violation> {
+        let files: Vec<(&PathBuf, String)> = repo
+            .files
+            .iter()
+            .filter(|path| self.covers(path))
+            .filter_map(|path| Some((path, fs::read_to_string(path).ok()?)))
+            .collect();
+        if files.is_empty() {
+            return Vec::new();
+        }
+        let input = json!({
+            "files": files
+                .iter()
+                .map(|(path, content)| json!({ "path": path, "content": content }))
+                .collect::<Vec<_>>(),
+        });
+        let findings = self
+            .run(&[], input.to_string().into_bytes())
+            .and_then(|stdout| Self::parse(&stdout));
+        let findings = match findings {
+            Ok(findings) => findings,
+            Err(reason) => {
+                let file = repo.roots.first().cloned().unwrap_or_else(|| PathBuf::from("."));
+                return self.failure(file, reason).into_iter().collect();
+            }
+        };
+        findings
+            .into_iter()
+            .filter_map(|mut finding| {
+                let file = finding.file.take()?;
+                let content = files.iter().find(|(path, _)| **path == file).map(|(_, c)| c.as_str());
+                Some(self.violation(file, content, finding))
+            })
+            .collect()
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::path_class::PathClass;
+
+    fn make_rule(script: &str, batch: bool) -> ExternalCommandRule {
+        let config = RuleConfig {
+            id: "sql-lint".into(),
+            severity: Severity::Warning,
+            message: "sql lint".into(),
+            command: vec!["sh".into(), "-c".into(), script.into(), "sh".into()],
+            batch,
+            timeout_secs: Some(1),
+            ..Default::default()
+        };
+        ExternalCommandRule::new(&config).unwrap()
+    }
+
+    fn check(rule: &ExternalCommandRule, content: &str) -> Vec<Violation> {
+        let ctx = ScanContext {
+            file_path: Path::new("db/query.sql"),
+            content,
+            class: PathClass::FirstParty,
+            file_type: Some("sql"),
+            changed_ranges: None,
+        };
+        rule.check_file(&ctx)
+    }
+
+    #[test]
+    fn per_file_runs_report_findings_and_failures() {
+        // The path arrives as $1 and the contents on stdin
+        let rule = make_rule(
+            r#"grep -q 'SELECT \*' && echo "[{\"line\": 2, \"column\": 1, \"message\": \"no SELECT * in $1\", \"severity\": \"error\"}]"; exit 1"#,
+            false,
+        );
+        let violations = check(&rule, "-- users\nSELECT * FROM users;\n");
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].message, "no SELECT * in db/query.sql");
+        assert_eq!(violations[0].severity, Severity::Error);
+        assert_eq!(violations[0].source_line.as_deref(), Some("SELECT * FROM users;"));
+        assert!(check(&rule, "SELECT id FROM users;\n").is_empty());
+
+        let violations = check(&make_rule("echo oops >&2; exit 3", false), "");
+        assert_eq!(violations[0].message, "external command 'sh' exited with status 3: oops");
+        assert_eq!(violations[0].severity, Severity::Error);
+        let violations = check(&make_rule("echo not json", false), "");
+        assert!(violations[0].message.contains("printed invalid output"));
+        let violations = check(&make_rule("sleep 5", false), "");
+        assert_eq!(violations[0].message, "external command 'sh' timed out after 1s");
+    }
+
+    #[test]
+    fn batch_runs_get_every_covered_file_at_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = dir.path().join("a.sql");
+        let b = dir.path().join("b.sql");
+        fs::write(&a, "SELECT 1;\n").unwrap();
+        fs::write(&b, "SELECT 2;\n").unwrap();
+        let readme = dir.path().join("README.md");
+        fs::write(&readme, "docs\n").unwrap();
+
+        // Report how many files arrived, against the last one
+        let script = format!(
+            r#"n=$(grep -o '"path"' | wc -l | tr -d ' '); echo "{{\"violations\": [{{\"file\": \"{}\", \"line\": 1, \"message\": \"$n files\"}}]}}""#,
+            b.display()
+        );
+        let config = RuleConfig {
+            id: "sql-lint".into(),
+            glob: Some("*.sql".into()),
+            command: vec!["sh".into(), "-c".into(), script],
+            batch: true,
+            ..Default::default()
+        };
+        let rule = ExternalCommandRule::new(&config).unwrap();
+        assert!(check(&rule, "SELECT 1;").is_empty());
+
+        let files = [a, b.clone(), readme];
+        let repo = RepoContext {
+            roots: &[dir.path().to_path_buf()],
+            files: &files,
+            violations: &[],
+        };
+        let violations = rule.check_repo(&repo);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].file, b);
+        assert_eq!(violations[0].message, "2 files");
+        assert_eq!(violations[0].source_line.as_deref(), Some("SELECT 2;"));
+    }
+}
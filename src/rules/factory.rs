@@ -2,18 +2,39 @@ use crate::config::RuleConfig;
 use crate::rules::banned_dependency::BannedDependencyRule;
 use crate::rules::banned_import::BannedImportRule;
 use crate::rules::banned_pattern::BannedPatternRule;
+use crate::rules::composite::CompositeRule;
+use crate::rules::env_hygiene::EnvHygieneRule;
+use crate::rules::external_command::ExternalCommandRule;
+use crate::rules::file_encoding::FileEncodingRule;
+use crate::rules::file_limits::FileLimitsRule;
 use crate::rules::file_presence::FilePresenceRule;
+use crate::rules::file_structure::FileStructureRule;
+use crate::rules::freeze_window::FreezeWindowRule;
+use crate::rules::frontmatter::FrontmatterRule;
+use crate::rules::import_boundary::ImportBoundaryRule;
+use crate::rules::paired_change::PairedChangeRule;
+use crate::rules::path_pattern::PathPatternRule;
+use crate::rules::pr_size::PrSizeRule;
 use crate::rules::ratchet::RatchetRule;
 use crate::rules::required_pattern::RequiredPatternRule;
+use crate::rules::risky_change::RiskyChangeRule;
+use crate::rules::secrets::SecretsRule;
+#[cfg(feature = "syntax")]
+use crate::rules::syntax_query::SyntaxQueryRule;
 use crate::rules::tailwind_dark_mode::TailwindDarkModeRule;
 use crate::rules::tailwind_theme_tokens::TailwindThemeTokensRule;
+use crate::rules::todo_age::TodoAgeRule;
+#[cfg(feature = "wasm")]
+use crate::rules::wasm::WasmRule;
 use crate::rules::window_pattern::WindowPatternRule;
-use crate::rules::{Rule, RuleBuildError};
+use crate::rules::{DiffRule, PathRule, RepoRule, Rule, RuleBuildError};
 use std::fmt;
 
 #[derive(Debug)]
 pub enum FactoryError {
     UnknownRuleType(String),
+    /// A rule type that needs a cargo feature this build lacks: (type, feature).
+    FeatureDisabled(String, &'static str),
     BuildError(RuleBuildError),
 }
 
@@ -21,6 +42,11 @@ impl fmt::Display for FactoryError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             FactoryError::UnknownRuleType(t) => write!(f, "unknown rule type: '{}'", t),
+            FactoryError::FeatureDisabled(t, feature) => write!(
+                f,
+                "rule type '{}' needs guardrails built with the '{}' feature (cargo install guardrails --features {})",
+                t, feature, feature
+            ),
             FactoryError::BuildError(e) => write!(f, "{}", e),
         }
     }
@@ -34,6 +60,37 @@ impl From<RuleBuildError> for FactoryError {
     }
 }
 
+/// Every rule type a config can name.
+pub const RULE_TYPES: &[&str] = &[
+    "tailwind-dark-mode",
+    "tailwind-theme-tokens",
+    "ratchet",
+    "banned-pattern",
+    "banned-import",
+    "import-boundary",
+    "banned-dependency",
+    "required-pattern",
+    "file-presence",
+    "path-pattern",
+    "file-structure",
+    "window-pattern",
+    "todo-age",
+    "file-limits",
+    "file-encoding",
+    "env-hygiene",
+    "frontmatter",
+    "secrets",
+    "composite",
+    "syntax-query",
+    "paired-change",
+    "schema-change",
+    "pr-size",
+    "freeze-window",
+    "risky-change",
+    "external-command",
+    "wasm",
+];
+
 /// Build a rule instance from a type string and config.
 pub fn build_rule(rule_type: &str, config: &RuleConfig) -> Result<Box<dyn Rule>, FactoryError> {
     match rule_type {
@@ -42,10 +99,110 @@ pub fn build_rule(rule_type: &str, config: &RuleConfig) -> Result<Box<dyn Rule>,
         "ratchet" => Ok(Box::new(RatchetRule::new(config)?)),
         "banned-pattern" => Ok(Box::new(BannedPatternRule::new(config)?)),
         "banned-import" => Ok(Box::new(BannedImportRule::new(config)?)),
+        "import-boundary" => Ok(Box::new(ImportBoundaryRule::new(config)?)),
         "banned-dependency" => Ok(Box::new(BannedDependencyRule::new(config)?)),
         "required-pattern" => Ok(Box::new(RequiredPatternRule::new(config)?)),
         "file-presence" => Ok(Box::new(FilePresenceRule::new(config)?)),
+        "path-pattern" => Ok(Box::new(PathPatternRule::new(config)?)),
+        "file-structure" => Ok(Box::new(FileStructureRule::new(config)?)),
         "window-pattern" => Ok(Box::new(WindowPatternRule::new(config)?)),
+        "todo-age" => Ok(Box::new(TodoAgeRule::new(config)?)),
+        "file-limits" => Ok(Box::new(FileLimitsRule::new(config)?)),
+        "file-encoding" => Ok(Box::new(FileEncodingRule::new(config)?)),
+        "env-hygiene" => Ok(Box::new(EnvHygieneRule::new(config)?)),
+        "frontmatter" => Ok(Box::new(FrontmatterRule::new(config)?)),
+        "secrets" => Ok(Box::new(SecretsRule::new(config)?)),
+        "composite" => Ok(Box::new(CompositeRule::new(config)?)),
+        "external-command" | "external" => Ok(Box::new(ExternalCommandRule::new(config)?)),
+        #[cfg(feature = "syntax")]
+        "syntax-query" => Ok(Box::new(SyntaxQueryRule::new(config)?)),
+        #[cfg(not(feature = "syntax"))]
+        "syntax-query" => Err(FactoryError::FeatureDisabled(rule_type.to_string(), "syntax")),
+        #[cfg(feature = "wasm")]
+        "wasm" => Ok(Box::new(WasmRule::new(config)?)),
+        #[cfg(not(feature = "wasm"))]
+        "wasm" => Err(FactoryError::FeatureDisabled(rule_type.to_string(), "wasm")),
+        _ => Err(FactoryError::UnknownRuleType(rule_type.to_string())),
+    }
+}
+
+/// Whether a rule type runs code outside guardrails (external commands,
+/// WASM modules). Such rules only load from trusted sources.
+pub fn is_privileged_rule_type(rule_type: &str) -> bool {
+    matches!(rule_type, "external-command" | "external" | "wasm")
+}
+
+/// Whether a rule type is evaluated against the diff instead of file contents.
+pub fn is_diff_rule_type(rule_type: &str) -> bool {
+    matches!(
+        rule_type,
+        "paired-change" | "schema-change" | "pr-size" | "freeze-window" | "risky-change"
+    )
+}
+
+/// Whether a rule type checks file paths during the walk instead of contents.
+pub fn is_path_rule_type(rule_type: &str) -> bool {
+    matches!(rule_type, "path-pattern" | "file-structure" | "file-encoding")
+}
+
+/// Build a path-level rule instance from a type string and config.
+pub fn build_path_rule(rule_type: &str, config: &RuleConfig) -> Result<Box<dyn PathRule>, FactoryError> {
+    match rule_type {
+        "path-pattern" => Ok(Box::new(PathPatternRule::new(config)?)),
+        "file-structure" => Ok(Box::new(FileStructureRule::new(config)?)),
+        "file-encoding" => Ok(Box::new(FileEncodingRule::new(config)?)),
+        _ => Err(FactoryError::UnknownRuleType(rule_type.to_string())),
+    }
+}
+
+/// Whether a rule type only checks the scan as a whole, with nothing to run
+/// per file.
+pub fn is_repo_rule_type(rule_type: &str) -> bool {
+    matches!(rule_type, "file-presence")
+}
+
+/// Build the whole-scan check of a rule type, if it has one. Batch
+/// external-command rules have one in addition to their per-file check.
+pub fn build_repo_rule(
+    rule_type: &str,
+    config: &RuleConfig,
+) -> Result<Option<Box<dyn RepoRule>>, FactoryError> {
+    match rule_type {
+        "file-presence" => Ok(Some(Box::new(FilePresenceRule::new(config)?))),
+        "external-command" | "external" if config.batch => Ok(Some(Box::new(ExternalCommandRule::new(config)?))),
+        _ => Ok(None),
+    }
+}
+
+type RuleWithRepoRule = (Box<dyn Rule>, Option<Box<dyn RepoRule>>);
+
+/// Build a rule's per-file check and its whole-scan check, if it has one.
+/// Env-hygiene rules are built once and do both, so they share what they
+/// read from `.env` templates.
+pub fn build_rule_with_repo_rule(
+    rule_type: &str,
+    config: &RuleConfig,
+) -> Result<RuleWithRepoRule, FactoryError> {
+    match rule_type {
+        "env-hygiene" => {
+            let rule = EnvHygieneRule::new(config)?;
+            Ok((Box::new(rule.clone()), Some(Box::new(rule))))
+        }
+        _ => Ok((build_rule(rule_type, config)?, build_repo_rule(rule_type, config)?)),
+    }
+}
+
+/// Build a diff-level rule instance from a type string and config.
+pub fn build_diff_rule(
+    rule_type: &str,
+    config: &RuleConfig,
+) -> Result<Box<dyn DiffRule>, FactoryError> {
+    match rule_type {
+        "paired-change" => Ok(Box::new(PairedChangeRule::new(config)?)),
+        "schema-change" => Ok(Box::new(PairedChangeRule::schema_change(config)?)),
+        "pr-size" => Ok(Box::new(PrSizeRule::new(config)?)),
+        "freeze-window" => Ok(Box::new(FreezeWindowRule::new(config)?)),
+        "risky-change" => Ok(Box::new(RiskyChangeRule::new(config)?)),
         _ => Err(FactoryError::UnknownRuleType(rule_type.to_string())),
     }
 }
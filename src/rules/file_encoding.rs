@@ -0,0 +1,188 @@
+use crate::config::{RuleConfig, Severity};
+use crate::rules::{PathRule, Rule, RuleBuildError, ScanContext, Violation};
+use std::fs;
+use std::path::Path;
+
+/// Requires files to be UTF-8 (the default) or plain ASCII, without a byte
+/// order mark.
+///
+/// Files that aren't valid UTF-8 are skipped by every content rule, so this
+/// runs during the walk as a path rule and reads the raw bytes itself. One
+/// violation per file, at the first offending byte.
+#[derive(Debug)]
+pub struct FileEncodingRule {
+    id: String,
+    severity: Severity,
+    message: String,
+    suggest: Option<String>,
+    glob: Option<String>,
+    ascii: bool,
+}
+
+impl FileEncodingRule {
+    pub fn new(config: &RuleConfig) -> Result<Self, RuleBuildError> {
+        let ascii = match config.encoding.as_deref() {
+            None | Some("utf-8") => false,
+            Some("ascii") => true,
+            Some(other) => {
+                return Err(RuleBuildError::InvalidField(
+                    config.id.clone(),
+                    "encoding",
+                    format!("expected \"utf-8\" or \"ascii\", got \"{}\"", other),
+                ))
+            }
+        };
+
+        Ok(Self {
+            id: config.id.clone(),
+            severity: config.severity,
+            message: config.message.clone(),
+            suggest: config.suggest.clone(),
+            glob: config.glob.clone(),
+            ascii,
+        })
+    }
+
+    /// Where `bytes` break the rule, as a byte offset, and why.
+    fn problem(&self, bytes: &[u8]) -> Option<(usize, String)> {
+        if bytes.starts_with(b"\xEF\xBB\xBF") {
+            return Some((0, "file starts with a UTF-8 byte order mark".to_string()));
+        }
+        if bytes.starts_with(b"\xFF\xFE") || bytes.starts_with(b"\xFE\xFF") {
+            return Some((0, "file starts with a UTF-16 byte order mark".to_string()));
+        }
+        if let Err(e) = std::str::from_utf8(bytes) {
+            let offset = e.valid_up_to();
+            return Some((offset, format!("file is not valid UTF-8 (byte 0x{:02X})", bytes[offset])));
+        }
+        if self.ascii {
+            let offset = bytes.iter().position(|b| !b.is_ascii())?;
+            let c = std::str::from_utf8(&bytes[offset..]).ok()?.chars().next()?;
+            return Some((offset, format!("file is not ASCII ('{}', U+{:04X})", c, c as u32)));
+        }
+        None
+    }
+}
+
+impl Rule for FileEncodingRule {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    fn file_glob(&self) -> Option<&str> {
+        self.glob.as_deref()
+    }
+
+    fn check_file(&self, _ctx: &ScanContext) -> Vec<Violation> {
+        // Files are checked during the walk via check_path, including
+        // the ones that never reach content rules
+        Vec::new()
+    }
+}
+
+impl PathRule for FileEncodingRule {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    fn check_path(&self, path: &Path) -> Option<Violation> {
+        let bytes = fs::read(path).ok()?;
+        let (offset, problem) = self.problem(&bytes)?;
+        let line_start = bytes[..offset].iter().rposition(|&b| b == b'\n').map_or(0, |i| i + 1);
+        let line = bytes[..offset].iter().filter(|&&b| b == b'\n').count() + 1;
+        let column = String::from_utf8_lossy(&bytes[line_start..offset]).chars().count() + 1;
+
+        Some(Violation {
+            rule_id: self.id.clone(),
+            severity: self.severity,
+            file: path.to_path_buf(),
+            line: Some(line),
+            column: Some(column),
+            message: if self.message.is_empty() {
+                problem
+            } else {
+                format!("{} ({})", self.message, problem)
+            },
+            suggest: self.suggest.clone(),
+            source_line: None,
+            fix: None,
+            url: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_rule(encoding: Option<&str>) -> FileEncodingRule {
+        let config = RuleConfig {
+            id: "encoding".into(),
+            severity: Severity::Error,
+            encoding: encoding.map(String::from),
+            ..Default::default()
+        };
+        FileEncodingRule::new(&config).unwrap()
+    }
+
+    fn check(rule: &FileEncodingRule, bytes: &[u8]) -> Option<Violation> {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a.ts");
+        fs::write(&path, bytes).unwrap();
+        rule.check_path(&path)
+    }
+
+    #[test]
+    fn flags_invalid_utf8_at_the_first_bad_byte() {
+        let rule = make_rule(None);
+        assert!(check(&rule, "ok\ncafé\n".as_bytes()).is_none());
+
+        let v = check(&rule, b"ok\ncaf\xE9\n").unwrap();
+        assert_eq!(v.line, Some(2));
+        assert_eq!(v.column, Some(4));
+        assert_eq!(v.message, "file is not valid UTF-8 (byte 0xE9)");
+    }
+
+    #[test]
+    fn flags_byte_order_marks() {
+        let rule = make_rule(Some("utf-8"));
+        let v = check(&rule, b"\xEF\xBB\xBFexport {};\n").unwrap();
+        assert_eq!((v.line, v.column), (Some(1), Some(1)));
+        assert_eq!(v.message, "file starts with a UTF-8 byte order mark");
+        assert_eq!(
+            check(&rule, b"\xFF\xFEa\x00").unwrap().message,
+            "file starts with a UTF-16 byte order mark"
+        );
+    }
+
+    #[test]
+    fn ascii_rejects_other_characters() {
+        let rule = make_rule(Some("ascii"));
+        assert!(check(&rule, b"plain\n").is_none());
+
+        let v = check(&rule, "a\n  \u{2014} b\n".as_bytes()).unwrap();
+        assert_eq!((v.line, v.column), (Some(2), Some(3)));
+        assert_eq!(v.message, "file is not ASCII ('\u{2014}', U+2014)");
+    }
+
+    #[test]
+    fn unknown_encoding_is_rejected() {
+        let config = RuleConfig {
+            id: "encoding".into(),
+            encoding: Some("latin-1".into()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            FileEncodingRule::new(&config),
+            Err(RuleBuildError::InvalidField(_, "encoding", _))
+        ));
+    }
+}
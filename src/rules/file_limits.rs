@@ -0,0 +1,220 @@
+use crate::config::{RuleConfig, Severity};
+use crate::line_endings;
+use crate::rules::{Rule, RuleBuildError, ScanContext, Violation};
+
+/// Caps how large files may grow: `max_lines` lines, `max_bytes` bytes and
+/// `max_line_length` characters per line.
+///
+/// Each limit that's exceeded is reported once, at the first offending line:
+/// line `max_lines + 1`, the line that crosses `max_bytes`, or the first line
+/// that's too long. Pair with `glob` to hold e.g. `src/pages/**` under 800
+/// lines.
+#[derive(Debug)]
+pub struct FileLimitsRule {
+    id: String,
+    severity: Severity,
+    message: String,
+    suggest: Option<String>,
+    glob: Option<String>,
+    max_lines: Option<usize>,
+    max_bytes: Option<usize>,
+    max_line_length: Option<usize>,
+}
+
+impl FileLimitsRule {
+    pub fn new(config: &RuleConfig) -> Result<Self, RuleBuildError> {
+        if config.max_lines.is_none() && config.max_bytes.is_none() && config.max_line_length.is_none() {
+            return Err(RuleBuildError::MissingField(config.id.clone(), "max_lines"));
+        }
+
+        Ok(Self {
+            id: config.id.clone(),
+            severity: config.severity,
+            message: config.message.clone(),
+            suggest: config.suggest.clone(),
+            glob: config.glob.clone(),
+            max_lines: config.max_lines,
+            max_bytes: config.max_bytes,
+            max_line_length: config.max_line_length,
+        })
+    }
+
+    fn violation(&self, ctx: &ScanContext, line: usize, column: usize, source: &str, problem: String) -> Violation {
+        Violation {
+            rule_id: self.id.clone(),
+            severity: self.severity,
+            file: ctx.file_path.to_path_buf(),
+            line: Some(line),
+            column: Some(column),
+            message: if self.message.is_empty() {
+                problem
+            } else {
+                format!("{} ({})", self.message, problem)
+            },
+            suggest: self.suggest.clone(),
+            source_line: Some(source.to_string()),
+            fix: None,
+            url: None,
+        }
+    }
+}
+
+impl Rule for FileLimitsRule {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    fn file_glob(&self) -> Option<&str> {
+        self.glob.as_deref()
+    }
+
+    fn check_file(&self, ctx: &ScanContext) -> Vec<Violation> {
+        let mut violations = Vec::new();
+        let mut lines_reported = false;
+        let mut bytes_reported = false;
+        let mut length_reported = false;
+        let mut bytes = 0;
+
+        for (idx, (text, ending)) in line_endings::split(ctx.content).enumerate() {
+            let line = idx + 1;
+            bytes += text.len() + ending.len();
+
+            if let Some(max) = self.max_lines.filter(|&max| !lines_reported && line > max) {
+                lines_reported = true;
+                let total = line_endings::split(ctx.content).count();
+                violations.push(self.violation(
+                    ctx,
+                    line,
+                    1,
+                    text,
+                    format!("file has {} lines, limit {}", total, max),
+                ));
+            }
+
+            if let Some(max) = self.max_bytes.filter(|&max| !bytes_reported && bytes > max) {
+                bytes_reported = true;
+                violations.push(self.violation(
+                    ctx,
+                    line,
+                    1,
+                    text,
+                    format!("file is {} bytes, limit {}", ctx.content.len(), max),
+                ));
+            }
+
+            if let Some(max) = self.max_line_length.filter(|_| !length_reported) {
+                let length = text.chars().count();
+                if length > max {
+                    length_reported = true;
+                    violations.push(self.violation(
+                        ctx,
+                        line,
+                        max + 1,
+                        text,
+                        format!("line is {} characters, limit {}", length, max),
+                    ));
+                }
+            }
+
+            let done = (self.max_lines.is_none() || lines_reported)
+                && (self.max_bytes.is_none() || bytes_reported)
+                && (self.max_line_length.is_none() || length_reported);
+            if done {
+                break;
+            }
+        }
+
+        violations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path_class::PathClass;
+    use std::path::Path;
+
+    fn make_rule(max_lines: Option<usize>, max_bytes: Option<usize>, max_line_length: Option<usize>) -> FileLimitsRule {
+        let config = RuleConfig {
+            id: "file-limits".into(),
+            severity: Severity::Error,
+            max_lines,
+            max_bytes,
+            max_line_length,
+            ..Default::default()
+        };
+        FileLimitsRule::new(&config).unwrap()
+    }
+
+    fn check(rule: &FileLimitsRule, content: &str) -> Vec<Violation> {
+        let ctx = ScanContext {
+            file_path: Path::new("src/pages/home.tsx"),
+            content,
+            class: PathClass::FirstParty,
+            file_type: None,
+            changed_ranges: None,
+        };
+        rule.check_file(&ctx)
+    }
+
+    #[test]
+    fn reports_the_first_line_over_max_lines() {
+        let rule = make_rule(Some(2), None, None);
+        assert!(check(&rule, "a\nb\n").is_empty());
+
+        let violations = check(&rule, "a\nb\nc\nd\n");
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].line, Some(3));
+        assert_eq!(violations[0].message, "file has 4 lines, limit 2");
+        assert_eq!(violations[0].source_line.as_deref(), Some("c"));
+    }
+
+    #[test]
+    fn reports_the_line_crossing_max_bytes() {
+        let rule = make_rule(None, Some(5), None);
+        assert!(check(&rule, "ab\ncd").is_empty());
+
+        let violations = check(&rule, "ab\ncd\nef\n");
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].line, Some(2));
+        assert_eq!(violations[0].message, "file is 9 bytes, limit 5");
+    }
+
+    #[test]
+    fn reports_the_first_long_line_once() {
+        let rule = make_rule(None, None, Some(3));
+        let violations = check(&rule, "abc\nabcdé\nabcdef\n");
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].line, Some(2));
+        assert_eq!(violations[0].column, Some(4));
+        assert_eq!(violations[0].message, "line is 5 characters, limit 3");
+    }
+
+    #[test]
+    fn custom_message_keeps_the_details() {
+        let config = RuleConfig {
+            id: "page-size".into(),
+            message: "Split large pages".into(),
+            max_lines: Some(1),
+            ..Default::default()
+        };
+        let rule = FileLimitsRule::new(&config).unwrap();
+        assert_eq!(check(&rule, "a\nb\n")[0].message, "Split large pages (file has 2 lines, limit 1)");
+    }
+
+    #[test]
+    fn requires_a_limit() {
+        let config = RuleConfig {
+            id: "file-limits".into(),
+            ..Default::default()
+        };
+        assert!(matches!(
+            FileLimitsRule::new(&config),
+            Err(RuleBuildError::MissingField(_, "max_lines"))
+        ));
+    }
+}
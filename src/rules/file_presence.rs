@@ -1,5 +1,5 @@
 use crate::config::{RuleConfig, Severity};
-use crate::rules::{Rule, RuleBuildError, ScanContext, Violation};
+use crate::rules::{RepoContext, RepoRule, Rule, RuleBuildError, ScanContext, Violation};
 use std::path::PathBuf;
 
 /// Ensures that specific files exist in the project.
@@ -72,6 +72,7 @@ impl FilePresenceRule {
                     suggest: self.suggest.clone(),
                     source_line: None,
                     fix: None,
+                    url: None,
                 });
             }
         }
@@ -100,6 +101,20 @@ impl Rule for FilePresenceRule {
     }
 }
 
+impl RepoRule for FilePresenceRule {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    fn check_repo(&self, repo: &RepoContext) -> Vec<Violation> {
+        self.check_paths(repo.roots)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
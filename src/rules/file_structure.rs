@@ -0,0 +1,246 @@
+use crate::config::{RuleConfig, Severity};
+use crate::rules::{PathRule, Rule, RuleBuildError, ScanContext, Violation};
+use crate::scan::compile_glob_set;
+use globset::GlobSet;
+use regex::Regex;
+use std::path::Path;
+
+/// Checks where files live and how they're named, never what they contain.
+///
+/// Files matching `glob` must sit inside a directory matching one of
+/// `allowed_dirs` (e.g. `*.test.ts` only under `__tests__/`), and their
+/// names must follow `naming` (e.g. kebab-case in `components/`). With
+/// neither set, every matching file is a violation, which keeps new files
+/// out of a directory when combined with `only_changed`.
+#[derive(Debug)]
+pub struct FileStructureRule {
+    id: String,
+    severity: Severity,
+    message: String,
+    suggest: Option<String>,
+    glob: Option<String>,
+    allowed_dirs: Vec<String>,
+    allowed: Option<GlobSet>,
+    naming: Option<(String, Regex)>,
+}
+
+impl FileStructureRule {
+    pub fn new(config: &RuleConfig) -> Result<Self, RuleBuildError> {
+        let allowed = if config.allowed_dirs.is_empty() {
+            None
+        } else {
+            Some(
+                compile_glob_set(&config.allowed_dirs)
+                    .map_err(|e| RuleBuildError::InvalidGlob(config.id.clone(), e))?,
+            )
+        };
+
+        let naming = match config.naming.as_deref() {
+            None => None,
+            Some(style) => {
+                let pattern = match style {
+                    "kebab-case" => r"^[a-z0-9]+(-[a-z0-9]+)*$",
+                    "snake_case" => r"^[a-z0-9]+(_[a-z0-9]+)*$",
+                    "camelCase" => r"^[a-z][a-zA-Z0-9]*$",
+                    "PascalCase" => r"^[A-Z][a-zA-Z0-9]*$",
+                    other => {
+                        return Err(RuleBuildError::InvalidField(
+                            config.id.clone(),
+                            "naming",
+                            format!(
+                                "expected \"kebab-case\", \"snake_case\", \"camelCase\" or \"PascalCase\", got \"{}\"",
+                                other
+                            ),
+                        ))
+                    }
+                };
+                Some((style.to_string(), Regex::new(pattern).expect("valid naming regex")))
+            }
+        };
+
+        if allowed.is_none() && naming.is_none() && config.glob.is_none() {
+            return Err(RuleBuildError::MissingField(config.id.clone(), "glob"));
+        }
+
+        Ok(Self {
+            id: config.id.clone(),
+            severity: config.severity,
+            message: config.message.clone(),
+            suggest: config.suggest.clone(),
+            glob: config.glob.clone(),
+            allowed_dirs: config.allowed_dirs.clone(),
+            allowed,
+            naming,
+        })
+    }
+
+    /// Why `path` breaks the rule, or `None` if it doesn't.
+    fn problem(&self, path: &str) -> Option<String> {
+        let (dir, name) = path.rsplit_once('/').unwrap_or(("", path));
+
+        if let Some(ref allowed) = self.allowed {
+            // Any enclosing directory counts, by path or by name, as does
+            // a `dir/**` glob matching the file itself
+            let mut inside = allowed.is_match(path);
+            let mut current = dir;
+            while !current.is_empty() {
+                let dir_name = current.rsplit_once('/').map_or(current, |(_, n)| n);
+                if allowed.is_match(current) || allowed.is_match(dir_name) {
+                    inside = true;
+                    break;
+                }
+                current = current.rsplit_once('/').map_or("", |(parent, _)| parent);
+            }
+            if !inside {
+                return Some(format!("File must be in {}", self.allowed_dirs.join(" or ")));
+            }
+        }
+
+        if let Some((ref style, ref regex)) = self.naming {
+            // `user-card.test.tsx` is named `user-card`; dotfiles are skipped
+            let stem = name.split('.').next().unwrap_or("");
+            if !stem.is_empty() && !regex.is_match(stem) {
+                return Some(format!("File name '{}' is not {}", stem, style));
+            }
+            return None;
+        }
+
+        if self.allowed.is_none() {
+            return Some("File is not allowed here".to_string());
+        }
+        None
+    }
+}
+
+impl Rule for FileStructureRule {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    fn file_glob(&self) -> Option<&str> {
+        self.glob.as_deref()
+    }
+
+    fn check_file(&self, _ctx: &ScanContext) -> Vec<Violation> {
+        // Paths are checked during the walk via check_path, not per file
+        Vec::new()
+    }
+}
+
+impl PathRule for FileStructureRule {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    fn check_path(&self, path: &Path) -> Option<Violation> {
+        let normalized = path.to_string_lossy().replace('\\', "/");
+        let problem = self.problem(normalized.trim_start_matches("./"))?;
+
+        Some(Violation {
+            rule_id: self.id.clone(),
+            severity: self.severity,
+            file: path.to_path_buf(),
+            line: None,
+            column: None,
+            message: if self.message.is_empty() { problem } else { self.message.clone() },
+            suggest: self.suggest.clone(),
+            source_line: None,
+            fix: None,
+            url: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_rule(allowed_dirs: &[&str], naming: Option<&str>) -> FileStructureRule {
+        let config = RuleConfig {
+            id: "layout".into(),
+            severity: Severity::Error,
+            glob: Some("**/*.ts".into()),
+            allowed_dirs: allowed_dirs.iter().map(|d| d.to_string()).collect(),
+            naming: naming.map(String::from),
+            ..Default::default()
+        };
+        FileStructureRule::new(&config).unwrap()
+    }
+
+    #[test]
+    fn files_must_live_in_allowed_dirs() {
+        let rule = make_rule(&["__tests__"], None);
+        assert!(rule.check_path(Path::new("./src/__tests__/a.test.ts")).is_none());
+        assert!(rule.check_path(Path::new("src/__tests__/nested/a.test.ts")).is_none());
+
+        let v = rule.check_path(Path::new("src/a.test.ts")).unwrap();
+        assert_eq!(v.message, "File must be in __tests__");
+        assert_eq!(v.file, Path::new("src/a.test.ts"));
+
+        let rule = make_rule(&["src/legacy/**", "tests"], None);
+        assert!(rule.check_path(Path::new("src/legacy/a.ts")).is_none());
+        assert!(rule.check_path(Path::new("src/legacy/old/a.ts")).is_none());
+        assert_eq!(
+            rule.check_path(Path::new("src/a.ts")).unwrap().message,
+            "File must be in src/legacy/** or tests"
+        );
+    }
+
+    #[test]
+    fn file_names_follow_the_naming_style() {
+        let rule = make_rule(&[], Some("kebab-case"));
+        assert!(rule.check_path(Path::new("components/user-card.ts")).is_none());
+        assert!(rule.check_path(Path::new("components/user-card.test.ts")).is_none());
+        assert!(rule.check_path(Path::new("components/.eslintrc.ts")).is_none());
+        // Directory names are free
+        assert!(rule.check_path(Path::new("UserCard/user-card.ts")).is_none());
+        assert_eq!(
+            rule.check_path(Path::new("components/UserCard.ts")).unwrap().message,
+            "File name 'UserCard' is not kebab-case"
+        );
+
+        let rule = make_rule(&[], Some("PascalCase"));
+        assert!(rule.check_path(Path::new("UserCard.ts")).is_none());
+        assert!(rule.check_path(Path::new("user_card.ts")).is_some());
+    }
+
+    #[test]
+    fn without_constraints_every_matching_file_is_flagged() {
+        let config = RuleConfig {
+            id: "frozen".into(),
+            glob: Some("src/legacy/**".into()),
+            message: "src/legacy is frozen".into(),
+            ..Default::default()
+        };
+        let rule = FileStructureRule::new(&config).unwrap();
+        assert_eq!(rule.check_path(Path::new("src/legacy/a.ts")).unwrap().message, "src/legacy is frozen");
+
+        let missing = RuleConfig {
+            id: "frozen".into(),
+            ..Default::default()
+        };
+        assert!(matches!(
+            FileStructureRule::new(&missing),
+            Err(RuleBuildError::MissingField(_, "glob"))
+        ));
+    }
+
+    #[test]
+    fn unknown_naming_style_is_rejected() {
+        let config = RuleConfig {
+            id: "names".into(),
+            naming: Some("SCREAMING".into()),
+            ..Default::default()
+        };
+        let err = FileStructureRule::new(&config).unwrap_err();
+        assert!(matches!(err, RuleBuildError::InvalidField(_, "naming", _)));
+    }
+}
@@ -0,0 +1,335 @@
+use crate::config::{RuleConfig, Severity};
+use crate::git_diff::DiffInfo;
+use crate::rules::{DiffRule, RuleBuildError, Violation};
+use crate::scan::compile_glob_set;
+use globset::GlobSet;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Blocks changes to protected paths during release freezes.
+///
+/// Evaluated against the diff in `--changed-only` mode. Every changed file
+/// matching `glob` is reported while the scan time falls inside one of the
+/// `freeze_windows`, unless a commit in the range carries the
+/// `bypass_marker` in its message.
+///
+/// Without windows (`freeze_windows` omitted or empty) the paths are frozen
+/// permanently: every change to them needs the bypass marker, as for
+/// directories a team has handed off or archived.
+///
+/// Windows are `start..end` in UTC. Each bound is either a date
+/// (`2025-12-20`) or a date and time (`2025-12-20T18:00`, optional `Z`).
+/// A date-only end bound covers the whole day.
+#[derive(Debug)]
+pub struct FreezeWindowRule {
+    id: String,
+    severity: Severity,
+    message: String,
+    suggest: Option<String>,
+    protected: GlobSet,
+    /// Half-open `[start, end)` ranges in Unix seconds.
+    windows: Vec<(i64, i64)>,
+    bypass_marker: String,
+}
+
+const DEFAULT_BYPASS_MARKER: &str = "[freeze-bypass]";
+
+impl FreezeWindowRule {
+    /// Build a `freeze-window` rule; `glob` is required.
+    pub fn new(config: &RuleConfig) -> Result<Self, RuleBuildError> {
+        let glob = config
+            .glob
+            .as_ref()
+            .filter(|g| !g.is_empty())
+            .ok_or_else(|| RuleBuildError::MissingField(config.id.clone(), "glob"))?;
+        let protected = compile_glob_set(std::slice::from_ref(glob))
+            .map_err(|e| RuleBuildError::InvalidGlob(config.id.clone(), e))?;
+
+        let windows = config
+            .freeze_windows
+            .iter()
+            .map(|w| {
+                parse_window(w).ok_or_else(|| {
+                    RuleBuildError::InvalidField(
+                        config.id.clone(),
+                        "freeze_windows",
+                        format!("expected 'YYYY-MM-DD[THH:MM]..YYYY-MM-DD[THH:MM]', got '{}'", w),
+                    )
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            id: config.id.clone(),
+            severity: config.severity,
+            message: config.message.clone(),
+            suggest: config.suggest.clone(),
+            protected,
+            windows,
+            bypass_marker: config
+                .bypass_marker
+                .clone()
+                .unwrap_or_else(|| DEFAULT_BYPASS_MARKER.to_string()),
+        })
+    }
+
+    fn check_at(&self, diff: &DiffInfo, now: i64) -> Vec<Violation> {
+        let frozen = self.windows.is_empty()
+            || self.windows.iter().any(|&(start, end)| now >= start && now < end);
+        if !frozen {
+            return Vec::new();
+        }
+
+        if diff
+            .commit_messages
+            .iter()
+            .any(|m| m.contains(&self.bypass_marker))
+        {
+            return Vec::new();
+        }
+
+        let mut files: Vec<&PathBuf> = diff
+            .changed_lines
+            .keys()
+            .filter(|p| self.protected.is_match(p))
+            .collect();
+        files.sort();
+
+        let msg = if self.message.is_empty() {
+            "Protected path changed during a freeze window".to_string()
+        } else {
+            self.message.clone()
+        };
+        let suggest = self.suggest.clone().or_else(|| {
+            Some(format!(
+                "Wait for the freeze to end or add '{}' to a commit message",
+                self.bypass_marker
+            ))
+        });
+
+        files
+            .into_iter()
+            .map(|file| Violation {
+                rule_id: self.id.clone(),
+                severity: self.severity,
+                file: file.clone(),
+                line: None,
+                column: None,
+                message: msg.clone(),
+                suggest: suggest.clone(),
+                source_line: None,
+                fix: None,
+                url: None,
+            })
+            .collect()
+    }
+}
+
+impl DiffRule for FreezeWindowRule {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    fn check_diff(&self, diff: &DiffInfo) -> Vec<Violation> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0);
+        self.check_at(diff, now)
+    }
+}
+
+/// Parse `start..end` into a half-open range of Unix seconds.
+fn parse_window(window: &str) -> Option<(i64, i64)> {
+    let (start, end) = window.split_once("..")?;
+    let start = parse_timestamp(start.trim(), false)?;
+    let end = parse_timestamp(end.trim(), true)?;
+    if end <= start {
+        return None;
+    }
+    Some((start, end))
+}
+
+/// Parse `YYYY-MM-DD` or `YYYY-MM-DDTHH:MM[Z]` as UTC. A date-only value used
+/// as an end bound resolves to the following midnight.
+pub(crate) fn parse_timestamp(s: &str, is_end: bool) -> Option<i64> {
+    let s = s.strip_suffix('Z').unwrap_or(s);
+    let (date, time) = match s.split_once('T') {
+        Some((d, t)) => (d, Some(t)),
+        None => (s, None),
+    };
+
+    let mut parts = date.splitn(3, '-');
+    let year: i64 = parts.next()?.parse().ok()?;
+    let month: u32 = parts.next()?.parse().ok()?;
+    let day: u32 = parts.next()?.parse().ok()?;
+    if !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month) {
+        return None;
+    }
+    let days = days_from_civil(year, month, day);
+
+    let seconds = match time {
+        Some(t) => {
+            let (h, m) = t.split_once(':')?;
+            let h: i64 = h.parse().ok()?;
+            let m: i64 = m.parse().ok()?;
+            if !(0..24).contains(&h) || !(0..60).contains(&m) {
+                return None;
+            }
+            h * 3600 + m * 60
+        }
+        None if is_end => 86_400,
+        None => 0,
+    };
+
+    Some(days * 86_400 + seconds)
+}
+
+/// Format Unix seconds as an RFC 3339 UTC timestamp (`2025-12-24T12:00:00Z`).
+pub(crate) fn format_timestamp(secs: i64) -> String {
+    let (days, rem) = (secs.div_euclid(86_400), secs.rem_euclid(86_400));
+    let (year, month, day) = civil_from_days(days);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60
+    )
+}
+
+fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if (year % 4 == 0 && year % 100 != 0) || year % 400 == 0 => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Days since 1970-01-01 for a proleptic Gregorian date.
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let m = month as i64;
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// Inverse of `days_from_civil`.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn make_diff(files: &[&str], commits: &[&str]) -> DiffInfo {
+        let mut changed_lines = HashMap::new();
+        for f in files {
+            changed_lines.insert(PathBuf::from(f), vec![1..=1]);
+        }
+        DiffInfo {
+            changed_lines,
+            commit_messages: commits.iter().map(|s| s.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    fn make_rule(windows: Vec<&str>) -> FreezeWindowRule {
+        let config = RuleConfig {
+            id: "release-freeze".into(),
+            severity: Severity::Error,
+            glob: Some("infra/**".into()),
+            freeze_windows: windows.into_iter().map(|s| s.to_string()).collect(),
+            ..Default::default()
+        };
+        FreezeWindowRule::new(&config).unwrap()
+    }
+
+    #[test]
+    fn days_from_civil_epoch() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(days_from_civil(2000, 3, 1), 11_017);
+    }
+
+    #[test]
+    fn format_timestamp_round_trips() {
+        let t = parse_timestamp("2024-02-29T23:59", false).unwrap();
+        assert_eq!(format_timestamp(t), "2024-02-29T23:59:00Z");
+        assert_eq!(format_timestamp(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_timestamp(951_782_400 + 61), "2000-02-29T00:01:01Z");
+    }
+
+    #[test]
+    fn date_end_covers_whole_day() {
+        let (start, end) = parse_window("2025-12-20..2025-12-20").unwrap();
+        assert_eq!(end - start, 86_400);
+    }
+
+    #[test]
+    fn inside_window_flags_protected_files() {
+        let rule = make_rule(vec!["2025-12-20..2026-01-02"]);
+        let now = parse_timestamp("2025-12-24T12:00", false).unwrap();
+        let diff = make_diff(&["infra/main.tf", "src/app.ts"], &["chore: bump"]);
+        let violations = rule.check_at(&diff, now);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].file, PathBuf::from("infra/main.tf"));
+    }
+
+    #[test]
+    fn outside_window_passes() {
+        let rule = make_rule(vec!["2025-12-20T18:00Z..2026-01-02T09:00Z"]);
+        let now = parse_timestamp("2026-01-02T09:00", false).unwrap();
+        let diff = make_diff(&["infra/main.tf"], &[]);
+        assert!(rule.check_at(&diff, now).is_empty());
+    }
+
+    #[test]
+    fn no_windows_freeze_permanently() {
+        let rule = make_rule(vec![]);
+        let diff = make_diff(&["infra/main.tf", "src/app.ts"], &["chore: bump"]);
+        for now in ["1970-01-01", "2025-06-15T12:00", "2099-12-31T23:59"] {
+            let now = parse_timestamp(now, false).unwrap();
+            assert_eq!(rule.check_at(&diff, now).len(), 1);
+        }
+    }
+
+    #[test]
+    fn bypass_marker_lifts_freeze() {
+        let rule = make_rule(vec![]);
+        let diff = make_diff(&["infra/main.tf"], &["hotfix: rotate certs\n\n[freeze-bypass]"]);
+        assert!(rule.check_at(&diff, 0).is_empty());
+    }
+
+    #[test]
+    fn invalid_window_errors() {
+        let config = RuleConfig {
+            id: "release-freeze".into(),
+            glob: Some("infra/**".into()),
+            freeze_windows: vec!["2025-13-01..2025-12-31".into()],
+            ..Default::default()
+        };
+        let err = FreezeWindowRule::new(&config).unwrap_err();
+        assert!(matches!(err, RuleBuildError::InvalidField(_, "freeze_windows", _)));
+    }
+}
@@ -0,0 +1,336 @@
+use crate::config::{RuleConfig, Severity};
+use crate::line_endings;
+use crate::rules::{Rule, RuleBuildError, ScanContext, Violation};
+use std::collections::BTreeMap;
+
+/// Files checked when the rule sets no `glob`.
+const DEFAULT_GLOB: &str = "**/*.{md,mdx,markdown,yml,yaml}";
+
+/// Checks the YAML frontmatter of Markdown files — the block between `---`
+/// lines at the top — and the top-level keys of YAML files.
+///
+/// Only top-level keys are read. Their values are scalars or lists of
+/// scalars (`[a, b]` or `- a` items); nested mappings and block scalars
+/// count as set but have no values to check.
+///
+/// Config fields:
+/// - `required_keys` — keys that must be set
+/// - `allowed_values` — values each key may take, by key; each item of a list must be allowed
+/// - `banned_keys` — keys that must not be set
+/// - `banned_values` — values each key must not take, by key
+#[derive(Debug)]
+pub struct FrontmatterRule {
+    id: String,
+    severity: Severity,
+    message: String,
+    suggest: Option<String>,
+    glob: String,
+    required_keys: Vec<String>,
+    allowed_values: BTreeMap<String, Vec<String>>,
+    banned_keys: Vec<String>,
+    banned_values: BTreeMap<String, Vec<String>>,
+}
+
+impl FrontmatterRule {
+    pub fn new(config: &RuleConfig) -> Result<Self, RuleBuildError> {
+        if config.required_keys.is_empty()
+            && config.allowed_values.is_empty()
+            && config.banned_keys.is_empty()
+            && config.banned_values.is_empty()
+        {
+            return Err(RuleBuildError::MissingField(config.id.clone(), "required_keys"));
+        }
+
+        Ok(Self {
+            id: config.id.clone(),
+            severity: config.severity,
+            message: config.message.clone(),
+            suggest: config.suggest.clone(),
+            glob: config.glob.clone().unwrap_or_else(|| DEFAULT_GLOB.to_string()),
+            required_keys: config.required_keys.clone(),
+            allowed_values: config.allowed_values.clone(),
+            banned_keys: config.banned_keys.clone(),
+            banned_values: config.banned_values.clone(),
+        })
+    }
+
+    fn violation(&self, ctx: &ScanContext, line: usize, column: usize, source: &str, problem: String) -> Violation {
+        Violation {
+            rule_id: self.id.clone(),
+            severity: self.severity,
+            file: ctx.file_path.to_path_buf(),
+            line: Some(line),
+            column: Some(column),
+            message: if self.message.is_empty() {
+                problem
+            } else {
+                format!("{} ({})", self.message, problem)
+            },
+            suggest: self.suggest.clone(),
+            source_line: Some(source.to_string()),
+            fix: None,
+            url: None,
+        }
+    }
+}
+
+impl Rule for FrontmatterRule {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    fn file_glob(&self) -> Option<&str> {
+        Some(&self.glob)
+    }
+
+    fn check_file(&self, ctx: &ScanContext) -> Vec<Violation> {
+        let lines: Vec<&str> = line_endings::lines(ctx.content).collect();
+        let source = |line: usize| lines.get(line - 1).copied().unwrap_or("");
+        let yaml = matches!(ctx.file_type, Some("yml" | "yaml"));
+        let Some(entries) = frontmatter(&lines, yaml) else {
+            if self.required_keys.is_empty() {
+                return Vec::new();
+            }
+            let problem = format!("missing frontmatter with {}", self.required_keys.join(", "));
+            return vec![self.violation(ctx, 1, 1, source(1), problem)];
+        };
+
+        let mut violations = Vec::new();
+        for key in &self.required_keys {
+            if !entries.iter().any(|entry| entry.key == key) {
+                violations.push(self.violation(ctx, 1, 1, source(1), format!("frontmatter is missing `{}`", key)));
+            }
+        }
+        for entry in &entries {
+            if self.banned_keys.iter().any(|key| key == entry.key) {
+                let problem = format!("`{}` is not allowed in frontmatter", entry.key);
+                violations.push(self.violation(ctx, entry.line, 1, source(entry.line), problem));
+            }
+            let allowed = self.allowed_values.get(entry.key);
+            let banned = self.banned_values.get(entry.key);
+            for value in &entry.values {
+                let problem = match (allowed, banned) {
+                    (Some(allowed), _) if !allowed.iter().any(|a| a == value.text) => {
+                        format!("`{}: {}` is not one of {}", entry.key, value.text, allowed.join(", "))
+                    }
+                    (_, Some(banned)) if banned.iter().any(|b| b == value.text) => {
+                        format!("`{}: {}` is not allowed", entry.key, value.text)
+                    }
+                    _ => continue,
+                };
+                violations.push(self.violation(ctx, value.line, value.column, source(value.line), problem));
+            }
+        }
+        violations
+    }
+}
+
+/// A top-level key, the line it's on and its scalar values.
+struct Entry<'a> {
+    key: &'a str,
+    line: usize,
+    values: Vec<Value<'a>>,
+}
+
+/// One scalar value (or list item), unquoted, and where it is.
+struct Value<'a> {
+    text: &'a str,
+    line: usize,
+    column: usize,
+}
+
+/// The top-level entries of the frontmatter of `lines`, or of the whole
+/// document for a YAML file. `None` when a Markdown file has none.
+fn frontmatter<'a>(lines: &[&'a str], yaml: bool) -> Option<Vec<Entry<'a>>> {
+    let opened = lines.first().is_some_and(|l| l.trim_start_matches('\u{feff}').trim_end() == "---");
+    if !opened && !yaml {
+        return None;
+    }
+    let from = usize::from(opened);
+    let end = lines[from..].iter().position(|l| matches!(l.trim_end(), "---" | "..."));
+    if end.is_none() && !yaml {
+        return None;
+    }
+    let to = end.map_or(lines.len(), |end| from + end);
+
+    let mut entries: Vec<Entry> = Vec::new();
+    // Whether `- item` lines are the list of the last key, which had no
+    // value of its own on its line
+    let mut open = false;
+    for (i, &text) in lines[from..to].iter().enumerate() {
+        let line = from + i + 1;
+        let trimmed = text.trim_start();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if let Some(item) = trimmed.strip_prefix('-').filter(|rest| rest.is_empty() || rest.starts_with(' ')) {
+            if let Some(entry) = entries.last_mut().filter(|_| open) {
+                if let Some(value) = scalar(text, item) {
+                    entry.values.push(Value { line, ..value });
+                }
+            }
+            continue;
+        }
+        if trimmed.len() != text.len() {
+            // A nested mapping: the last key has no list
+            open = false;
+            continue;
+        }
+        let Some(colon) = text.find(": ").or_else(|| text.strip_suffix(':').map(|key| key.len())) else {
+            continue;
+        };
+        let key = unquote(text[..colon].trim_end());
+        let rest = strip_comment(&text[colon + 1..]);
+        let mut values = Vec::new();
+        let value = rest.trim();
+        open = value.is_empty();
+        if let Some(items) = value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+            values.extend(items.split(',').filter_map(|item| scalar(text, item)).map(|v| Value { line, ..v }));
+        } else if !value.starts_with(['|', '>', '{', '&', '*', '!']) {
+            values.extend(scalar(text, value).map(|v| Value { line, ..v }));
+        }
+        entries.push(Entry { key, line, values });
+    }
+    Some(entries)
+}
+
+/// The unquoted scalar in `part`, a slice of the line `text`, with its
+/// column in `text` (its line is filled in by the caller).
+fn scalar<'a>(text: &str, part: &'a str) -> Option<Value<'a>> {
+    let value = unquote(strip_comment(part).trim());
+    if value.is_empty() {
+        return None;
+    }
+    Some(Value {
+        text: value,
+        line: 0,
+        column: value.as_ptr() as usize - text.as_ptr() as usize + 1,
+    })
+}
+
+fn unquote(value: &str) -> &str {
+    for quote in ['"', '\''] {
+        if let Some(inner) = value.strip_prefix(quote).and_then(|v| v.strip_suffix(quote)) {
+            return inner;
+        }
+    }
+    value
+}
+
+/// `value` without a trailing ` # comment`, keeping quoted `#`s.
+fn strip_comment(value: &str) -> &str {
+    let trimmed = value.trim_start();
+    if let Some(quote) = trimmed.chars().next().filter(|c| matches!(c, '"' | '\'')) {
+        let start = value.len() - trimmed.len();
+        return match trimmed[1..].find(quote) {
+            Some(close) => &value[..start + close + 2],
+            None => value,
+        };
+    }
+    match value.find(" #") {
+        Some(at) => &value[..at],
+        None => value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path_class::PathClass;
+    use std::path::Path;
+
+    fn adr_rule() -> FrontmatterRule {
+        let config = RuleConfig {
+            id: "adr-frontmatter".into(),
+            severity: Severity::Error,
+            required_keys: vec!["status".into(), "owners".into()],
+            allowed_values: BTreeMap::from([
+                ("status".into(), vec!["proposed".into(), "accepted".into()]),
+                ("owners".into(), vec!["@web".into(), "@platform".into()]),
+            ]),
+            banned_keys: vec!["reviewers".into()],
+            banned_values: BTreeMap::from([("draft".into(), vec!["true".into()])]),
+            ..Default::default()
+        };
+        FrontmatterRule::new(&config).unwrap()
+    }
+
+    fn check(rule: &FrontmatterRule, file: &str, content: &str) -> Vec<(usize, usize, String)> {
+        let path = Path::new(file);
+        let ctx = ScanContext {
+            file_path: path,
+            content,
+            class: PathClass::FirstParty,
+            file_type: path.extension().and_then(|e| e.to_str()),
+            changed_ranges: None,
+        };
+        rule.check_file(&ctx)
+            .into_iter()
+            .map(|v| (v.line.unwrap(), v.column.unwrap(), v.message))
+            .collect()
+    }
+
+    #[test]
+    fn checks_markdown_frontmatter_keys_and_values() {
+        let rule = adr_rule();
+        let ok = "---\nstatus: accepted # since May\nowners: [\"@web\", '@platform']\n---\n# Use Rust\n";
+        assert!(check(&rule, "docs/adr/1.md", ok).is_empty());
+
+        let content = "---\nstatus: \"draft\"\nowners:\n  - '@web'\n  - \"@alice\"\nreviewers: []\ndraft: true\nlinks:\n  docs: x\n---\nstatus: body text\n";
+        assert_eq!(
+            check(&rule, "docs/adr/2.md", content),
+            [
+                (2, 10, "`status: draft` is not one of proposed, accepted".to_string()),
+                (5, 6, "`owners: @alice` is not one of @web, @platform".to_string()),
+                (6, 1, "`reviewers` is not allowed in frontmatter".to_string()),
+                (7, 8, "`draft: true` is not allowed".to_string()),
+            ]
+        );
+
+        assert_eq!(
+            check(&rule, "docs/adr/3.md", "---\ndraft: false\n---\n"),
+            [
+                (1, 1, "frontmatter is missing `status`".to_string()),
+                (1, 1, "frontmatter is missing `owners`".to_string()),
+            ]
+        );
+        // A thematic break further down isn't frontmatter, nor is an unclosed block
+        assert_eq!(
+            check(&rule, "docs/adr/4.md", "# Title\n---\nstatus: accepted\n---\n"),
+            [(1, 1, "missing frontmatter with status, owners".to_string())]
+        );
+        assert_eq!(check(&rule, "docs/adr/5.md", "---\nstatus: accepted\n").len(), 1);
+    }
+
+    #[test]
+    fn yaml_files_are_checked_whole() {
+        let config = RuleConfig {
+            id: "no-draft".into(),
+            message: "Publish before merging".into(),
+            banned_values: BTreeMap::from([("draft".into(), vec!["true".into()])]),
+            ..Default::default()
+        };
+        let rule = FrontmatterRule::new(&config).unwrap();
+        assert_eq!(rule.file_glob(), Some(DEFAULT_GLOB));
+        assert_eq!(
+            check(&rule, "content/post.yaml", "title: Hi\ndraft: true\n"),
+            [(2, 8, "Publish before merging (`draft: true` is not allowed)".to_string())]
+        );
+        // Without frontmatter, a Markdown file has nothing to ban
+        assert!(check(&rule, "README.md", "draft: true\n").is_empty());
+    }
+
+    #[test]
+    fn needs_something_to_check() {
+        let config = RuleConfig {
+            id: "empty".into(),
+            ..Default::default()
+        };
+        let err = FrontmatterRule::new(&config).unwrap_err();
+        assert!(matches!(err, RuleBuildError::MissingField(_, "required_keys")));
+    }
+}
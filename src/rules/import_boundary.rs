@@ -0,0 +1,342 @@
+use crate::config::{RuleConfig, Severity};
+use crate::rules::{regex_pool, Rule, RuleBuildError, ScanContext, Violation};
+use crate::scan::compile_glob_set;
+use globset::GlobSet;
+use regex::Regex;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// Keeps imports flowing in the directions a layered architecture allows.
+///
+/// Each of `layers` names the files matching its globs and the layers it
+/// `may_import` from. An import from a file in one layer that resolves into
+/// another layer it doesn't list is a violation, e.g. `domain` importing from
+/// `ui`. Imports within a layer are always allowed, as are imports from or
+/// into files no layer claims.
+///
+/// Relative specifiers (`../ui/button`, Python's `from ..ui import x`) are
+/// resolved against the importing file; bare ones (`@app/ui`, `app.ui`) are
+/// matched as written, so path aliases can be added to a layer's globs. A
+/// file in more than one layer belongs to the first.
+///
+/// Without a `glob`, the rule checks files whose type (see `[filetypes]`) is
+/// one of `FILE_TYPES`.
+/// File types checked when no `glob` is configured.
+const FILE_TYPES: &[&str] = &["ts", "tsx", "js", "jsx", "mjs", "cjs", "mts", "cts", "py", "pyi"];
+
+#[derive(Debug)]
+pub struct ImportBoundaryRule {
+    id: String,
+    severity: Severity,
+    message: String,
+    suggest: Option<String>,
+    glob: Option<String>,
+    layers: Vec<Layer>,
+    prefilter: Vec<String>,
+    import_re: Arc<Regex>,
+    python_re: Arc<Regex>,
+}
+
+#[derive(Debug)]
+struct Layer {
+    name: String,
+    globs: GlobSet,
+    /// Indices of the layers this one may import from.
+    may_import: Vec<usize>,
+}
+
+impl ImportBoundaryRule {
+    pub fn new(config: &RuleConfig) -> Result<Self, RuleBuildError> {
+        if config.layers.is_empty() {
+            return Err(RuleBuildError::MissingField(config.id.clone(), "layers"));
+        }
+        let invalid = |reason: String| RuleBuildError::InvalidField(config.id.clone(), "layers", reason);
+
+        let mut seen = HashSet::new();
+        for layer in &config.layers {
+            if !seen.insert(layer.name.as_str()) {
+                return Err(invalid(format!("layer '{}' is defined twice", layer.name)));
+            }
+            if layer.glob.is_empty() {
+                return Err(invalid(format!("layer '{}' has no globs", layer.name)));
+            }
+        }
+
+        let mut layers = Vec::new();
+        for layer in &config.layers {
+            let mut may_import = Vec::new();
+            for name in &layer.may_import {
+                let index = config
+                    .layers
+                    .iter()
+                    .position(|l| &l.name == name)
+                    .ok_or_else(|| invalid(format!("layer '{}' may import unknown layer '{}'", layer.name, name)))?;
+                may_import.push(index);
+            }
+            layers.push(Layer {
+                name: layer.name.clone(),
+                globs: compile_glob_set(&layer.glob).map_err(|e| RuleBuildError::InvalidGlob(config.id.clone(), e))?,
+                may_import,
+            });
+        }
+
+        // Match the specifier of:
+        //   import ... from 'x' / import 'x'    (including multi-line named imports)
+        //   export ... from 'x'                 (re-exports)
+        //   require('x') / import('x')          (CommonJS and dynamic imports)
+        let import_re = regex_pool::compile(
+            config,
+            r#"(?:\bimport\s+(?:[\w*${}\s,]+?\s+from\s+)?|\bexport\s+[\w*${}\s,]+?\s+from\s+|\b(?:require|import)\s*\(\s*)['"]([^'"\n]+)['"]"#,
+        )?;
+        // Python: from x.y import ... / import x.y[, z]
+        let python_re = regex_pool::compile(
+            config,
+            r"(?m)^[ \t]*(?:from[ \t]+(\.*[\w.]*)[ \t]+import\b|import[ \t]+([\w.]+(?:[ \t]*,[ \t]*[\w.]+)*))",
+        )?;
+
+        Ok(Self {
+            id: config.id.clone(),
+            severity: config.severity,
+            message: config.message.clone(),
+            suggest: config.suggest.clone(),
+            glob: config.glob.clone(),
+            layers,
+            prefilter: vec!["import".into(), "require".into(), "from".into()],
+            import_re,
+            python_re,
+        })
+    }
+
+    /// Index of the first layer claiming `path` (or the package `path`/index).
+    fn layer_of(&self, path: &str) -> Option<usize> {
+        let index = format!("{}/index", path);
+        self.layers
+            .iter()
+            .position(|layer| layer.globs.is_match(path) || layer.globs.is_match(&index))
+    }
+
+    /// `(byte offset, specifier, resolved path)` of each import in `ctx`.
+    fn imports(&self, ctx: &ScanContext, file: &str) -> Vec<(usize, String, String)> {
+        let dir = file.rsplit_once('/').map_or("", |(dir, _)| dir);
+        let mut imports = Vec::new();
+
+        if matches!(ctx.file_type, Some("py" | "pyi")) {
+            for cap in self.python_re.captures_iter(ctx.content) {
+                let start = cap.get(0).unwrap().start();
+                if let Some(module) = cap.get(1) {
+                    let module = module.as_str();
+                    imports.push((start, module.to_string(), python_module_path(dir, module)));
+                } else if let Some(modules) = cap.get(2) {
+                    for module in modules.as_str().split(',').map(str::trim) {
+                        imports.push((start, module.to_string(), python_module_path(dir, module)));
+                    }
+                }
+            }
+        } else {
+            for cap in self.import_re.captures_iter(ctx.content) {
+                let specifier = cap.get(1).unwrap().as_str();
+                let resolved = if specifier.starts_with("./") || specifier.starts_with("../") {
+                    normalize(&format!("{}/{}", dir, specifier))
+                } else {
+                    specifier.to_string()
+                };
+                imports.push((cap.get(0).unwrap().start(), specifier.to_string(), resolved));
+            }
+        }
+        imports
+    }
+}
+
+/// `module` (dotted, with leading dots for relative imports) as a path.
+fn python_module_path(dir: &str, module: &str) -> String {
+    let dots = module.len() - module.trim_start_matches('.').len();
+    let path = module[dots..].replace('.', "/");
+    if dots == 0 {
+        return path;
+    }
+    // `.x` is a sibling, and each further dot goes up a package
+    let mut base = dir.to_string();
+    for _ in 1..dots {
+        base.push_str("/..");
+    }
+    normalize(&format!("{}/{}", base, path))
+}
+
+/// `path` with `.` and `..` segments folded away.
+fn normalize(path: &str) -> String {
+    let mut segments: Vec<&str> = Vec::new();
+    for segment in path.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." if segments.last().is_some_and(|s| *s != "..") => {
+                segments.pop();
+            }
+            _ => segments.push(segment),
+        }
+    }
+    segments.join("/")
+}
+
+impl Rule for ImportBoundaryRule {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    fn file_glob(&self) -> Option<&str> {
+        self.glob.as_deref()
+    }
+
+    fn prefilter(&self) -> Option<&[String]> {
+        Some(&self.prefilter)
+    }
+
+    fn check_file(&self, ctx: &ScanContext) -> Vec<Violation> {
+        if self.glob.is_none() && !ctx.file_type.is_some_and(|t| FILE_TYPES.contains(&t)) {
+            return Vec::new();
+        }
+        let normalized = ctx.file_path.to_string_lossy().replace('\\', "/");
+        let file = normalized.trim_start_matches("./");
+        let Some(from) = self.layer_of(file) else {
+            return Vec::new();
+        };
+        let layer = &self.layers[from];
+
+        let mut violations = Vec::new();
+        for (offset, specifier, resolved) in self.imports(ctx, file) {
+            let Some(to) = self.layer_of(&resolved) else {
+                continue;
+            };
+            if to == from || layer.may_import.contains(&to) {
+                continue;
+            }
+            let line_start = ctx.content[..offset].rfind('\n').map_or(0, |i| i + 1);
+            let line_end = ctx.content[offset..].find('\n').map_or(ctx.content.len(), |i| offset + i);
+            let detail = format!(
+                "layer '{}' may not import from '{}' ('{}')",
+                layer.name, self.layers[to].name, specifier
+            );
+            violations.push(Violation {
+                rule_id: self.id.clone(),
+                severity: self.severity,
+                file: ctx.file_path.to_path_buf(),
+                line: Some(ctx.content[..offset].matches('\n').count() + 1),
+                column: Some(offset - line_start + 1),
+                message: if self.message.is_empty() {
+                    detail
+                } else {
+                    format!("{}: {}", self.message, detail)
+                },
+                suggest: self.suggest.clone(),
+                source_line: Some(ctx.content[line_start..line_end].trim_end_matches('\r').to_string()),
+                fix: None,
+                url: None,
+            });
+        }
+        violations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ImportLayer;
+    use crate::path_class::PathClass;
+    use std::path::Path;
+
+    fn layer(name: &str, glob: &str, may_import: &[&str]) -> ImportLayer {
+        ImportLayer {
+            name: name.into(),
+            glob: vec![glob.into()],
+            may_import: may_import.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    fn make_rule() -> ImportBoundaryRule {
+        let config = RuleConfig {
+            id: "layers".into(),
+            severity: Severity::Error,
+            layers: vec![
+                layer("ui", "src/ui/**", &["domain"]),
+                layer("domain", "src/domain/**", &[]),
+                layer("infra", "src/infra/**", &["domain"]),
+            ],
+            ..Default::default()
+        };
+        ImportBoundaryRule::new(&config).unwrap()
+    }
+
+    fn check(rule: &ImportBoundaryRule, file: &str, content: &str) -> Vec<Violation> {
+        let ctx = ScanContext {
+            file_path: Path::new(file),
+            content,
+            class: PathClass::FirstParty,
+            file_type: Path::new(file).extension().and_then(|e| e.to_str()),
+            changed_ranges: None,
+        };
+        rule.check_file(&ctx)
+    }
+
+    #[test]
+    fn flags_imports_against_the_layer_matrix() {
+        let rule = make_rule();
+        let content = "import { Button } from '../ui/button';\n\
+                       import {\n  Db,\n} from \"../infra/db\";\n\
+                       import { User } from './user';\n\
+                       const fs = require('fs');\n";
+        let violations = check(&rule, "./src/domain/order.ts", content);
+        assert_eq!(violations.len(), 2);
+        assert_eq!(violations[0].message, "layer 'domain' may not import from 'ui' ('../ui/button')");
+        assert_eq!((violations[0].line, violations[0].column), (Some(1), Some(1)));
+        assert_eq!(violations[1].message, "layer 'domain' may not import from 'infra' ('../infra/db')");
+        assert_eq!(violations[1].line, Some(2));
+
+        // ui may import domain, and re-exports and dynamic imports count too
+        let content = "import { User } from '../domain/user';\nexport * from '../infra';\nawait import('../infra/db');\n";
+        let violations = check(&rule, "src/ui/page.tsx", content);
+        assert_eq!(violations.len(), 2);
+        assert_eq!(violations[0].source_line.as_deref(), Some("export * from '../infra';"));
+        assert_eq!(violations[1].line, Some(3));
+
+        // Files outside every layer are unrestricted
+        assert!(check(&rule, "scripts/seed.ts", "import '../src/ui/button';").is_empty());
+    }
+
+    #[test]
+    fn resolves_python_modules() {
+        let rule = make_rule();
+        let content = "from ..ui.views import render\nimport src.infra.db, os\nfrom . import user\n";
+        let violations = check(&rule, "src/domain/order.py", content);
+        let messages: Vec<_> = violations.iter().map(|v| v.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            [
+                "layer 'domain' may not import from 'ui' ('..ui.views')",
+                "layer 'domain' may not import from 'infra' ('src.infra.db')",
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_or_missing_layers() {
+        let config = RuleConfig {
+            id: "layers".into(),
+            layers: vec![layer("ui", "src/ui/**", &["domian"])],
+            ..Default::default()
+        };
+        let err = ImportBoundaryRule::new(&config).unwrap_err();
+        assert!(err.to_string().contains("unknown layer 'domian'"));
+
+        let config = RuleConfig {
+            id: "layers".into(),
+            ..Default::default()
+        };
+        assert!(matches!(
+            ImportBoundaryRule::new(&config),
+            Err(RuleBuildError::MissingField(_, "layers"))
+        ));
+    }
+}
@@ -0,0 +1,249 @@
+use std::borrow::Cow;
+
+/// Which parts of a file pattern rules ignore (`skip_comments`,
+/// `skip_strings`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Masking {
+    pub comments: bool,
+    pub strings: bool,
+}
+
+/// How comments and string literals are written in one family of file
+/// types. Lightweight on purpose: no nesting, no raw-string prefixes, no
+/// interpolation.
+struct Syntax {
+    line_comments: &'static [&'static str],
+    /// Whether a line comment only starts at the beginning of a line or
+    /// after whitespace, as in shell and YAML (`$#`, `color: #fff`).
+    spaced_line_comments: bool,
+    block_comment: Option<(&'static str, &'static str)>,
+    quotes: &'static [u8],
+    /// The quote whose strings may span lines (template literals, raw strings).
+    multiline_quote: Option<u8>,
+    /// Whether three quotes in a row open a string that may span lines.
+    triple_quotes: bool,
+}
+
+const C_LIKE: Syntax = Syntax {
+    line_comments: &["//"],
+    spaced_line_comments: false,
+    block_comment: Some(("/*", "*/")),
+    quotes: b"\"'",
+    multiline_quote: None,
+    triple_quotes: false,
+};
+
+const JVM: Syntax = Syntax {
+    triple_quotes: true,
+    ..C_LIKE
+};
+
+const JS: Syntax = Syntax {
+    quotes: b"\"'`",
+    multiline_quote: Some(b'`'),
+    ..C_LIKE
+};
+
+const GO: Syntax = Syntax {
+    quotes: b"\"'`",
+    multiline_quote: Some(b'`'),
+    ..C_LIKE
+};
+
+/// Single quotes are left alone: they also start lifetimes.
+const RUST: Syntax = Syntax {
+    quotes: b"\"",
+    ..C_LIKE
+};
+
+const CSS: Syntax = Syntax {
+    line_comments: &[],
+    ..C_LIKE
+};
+
+const HASH: Syntax = Syntax {
+    line_comments: &["#"],
+    spaced_line_comments: false,
+    block_comment: None,
+    quotes: b"\"'",
+    multiline_quote: None,
+    triple_quotes: false,
+};
+
+const PYTHON: Syntax = Syntax {
+    triple_quotes: true,
+    ..HASH
+};
+
+const SHELL: Syntax = Syntax {
+    spaced_line_comments: true,
+    ..HASH
+};
+
+const JSON: Syntax = Syntax {
+    line_comments: &[],
+    spaced_line_comments: false,
+    block_comment: None,
+    quotes: b"\"",
+    multiline_quote: None,
+    triple_quotes: false,
+};
+
+const MARKUP: Syntax = Syntax {
+    line_comments: &[],
+    spaced_line_comments: false,
+    block_comment: Some(("<!--", "-->")),
+    quotes: b"",
+    multiline_quote: None,
+    triple_quotes: false,
+};
+
+fn syntax(file_type: &str) -> Option<&'static Syntax> {
+    Some(match file_type {
+        "js" | "jsx" | "mjs" | "cjs" | "ts" | "tsx" | "mts" | "cts" => &JS,
+        "c" | "h" | "cc" | "cpp" | "hpp" | "cs" | "scss" | "less" => &C_LIKE,
+        "java" | "kt" | "swift" => &JVM,
+        "go" => &GO,
+        "rs" => &RUST,
+        "css" => &CSS,
+        "py" | "pyi" | "toml" => &PYTHON,
+        "rb" => &HASH,
+        "sh" | "bash" | "yml" | "yaml" => &SHELL,
+        "json" => &JSON,
+        "html" | "htm" | "xml" | "md" | "vue" | "svelte" => &MARKUP,
+        _ => return None,
+    })
+}
+
+impl Masking {
+    pub fn is_empty(&self) -> bool {
+        !self.comments && !self.strings
+    }
+
+    /// `content` with the comments and the insides of string literals this
+    /// masking skips blanked out, as the syntax of `file_type` has them.
+    ///
+    /// Every blanked byte becomes a space except line endings, so lines,
+    /// columns and byte offsets stay where they were. A string's quotes are
+    /// kept. Files of types without a known syntax are returned as they are.
+    pub fn apply<'a>(&self, content: &'a str, file_type: Option<&str>) -> Cow<'a, str> {
+        let Some(syntax) = file_type.and_then(syntax).filter(|_| !self.is_empty()) else {
+            return Cow::Borrowed(content);
+        };
+        let bytes = content.as_bytes();
+        let mut masked = bytes.to_vec();
+        let mut blank = |from: usize, to: usize| {
+            for b in &mut masked[from..to] {
+                if *b != b'\n' && *b != b'\r' {
+                    *b = b' ';
+                }
+            }
+        };
+        let line_end = |from: usize| bytes[from..].iter().position(|&b| b == b'\n').map_or(bytes.len(), |p| from + p);
+
+        let mut i = 0;
+        while i < bytes.len() {
+            let rest = &bytes[i..];
+            let spaced = !syntax.spaced_line_comments || i == 0 || bytes[i - 1].is_ascii_whitespace();
+            if spaced && syntax.line_comments.iter().any(|c| rest.starts_with(c.as_bytes())) {
+                let end = line_end(i);
+                if self.comments {
+                    blank(i, end);
+                }
+                i = end;
+            } else if let Some((open, close)) = syntax.block_comment.filter(|(open, _)| rest.starts_with(open.as_bytes())) {
+                let end = find(bytes, i + open.len(), close.as_bytes()).map_or(bytes.len(), |p| p + close.len());
+                if self.comments {
+                    blank(i, end);
+                }
+                i = end;
+            } else if syntax.quotes.contains(&rest[0]) {
+                let quote = rest[0];
+                let len = if syntax.triple_quotes && rest.starts_with(&[quote; 3]) { 3 } else { 1 };
+                let multiline = len == 3 || syntax.multiline_quote == Some(quote);
+                let open = i + len;
+                let close = if len == 3 {
+                    find(bytes, open, &rest[..3])
+                } else {
+                    string_end(bytes, open, quote, multiline)
+                };
+                let end = close.unwrap_or_else(|| if multiline { bytes.len() } else { line_end(open) });
+                if self.strings {
+                    blank(open, end);
+                }
+                i = close.map_or(end, |close| close + len);
+            } else {
+                i += 1;
+            }
+        }
+        Cow::Owned(String::from_utf8(masked).expect("only ASCII delimiters and whole characters are blanked"))
+    }
+}
+
+/// The offset of the first `needle` in `bytes` at or after `from`.
+fn find(bytes: &[u8], from: usize, needle: &[u8]) -> Option<usize> {
+    bytes
+        .get(from..)?
+        .windows(needle.len())
+        .position(|w| w == needle)
+        .map(|p| from + p)
+}
+
+/// The offset of the quote closing a string whose contents start at `from`,
+/// skipping backslash escapes. A string that isn't `multiline` ends
+/// unterminated at the end of its line.
+fn string_end(bytes: &[u8], from: usize, quote: u8, multiline: bool) -> Option<usize> {
+    let mut i = from;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b if b == quote => return Some(i),
+            b'\n' if !multiline => return None,
+            _ => i += 1,
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BOTH: Masking = Masking {
+        comments: true,
+        strings: true,
+    };
+
+    #[test]
+    fn blanks_comments_and_strings_keeping_positions() {
+        let content = "legacyFetch(\"legacyFetch\"); // legacyFetch\r\n/* legacyFetch\n */ a('b\\'c', `x\ny`);\n";
+        let masked = BOTH.apply(content, Some("ts"));
+        assert_eq!(
+            masked,
+            "legacyFetch(\"           \");               \r\n              \n    a('    ', ` \n `);\n"
+        );
+        assert_eq!(masked.len(), content.len());
+
+        // Comments alone leave strings, and a `//` inside a string isn't a comment
+        let comments = Masking {
+            comments: true,
+            strings: false,
+        };
+        assert_eq!(comments.apply("f(\"http://x\"); // é", Some("js")), "f(\"http://x\");      ");
+        assert_eq!(comments.apply("f(\"é\") // x", None), "f(\"é\") // x");
+    }
+
+    #[test]
+    fn syntax_follows_the_file_type() {
+        let python = BOTH.apply("x = \"\"\"doc\n# no\"\"\" # yes\n", Some("py"));
+        assert_eq!(python, "x = \"\"\"   \n    \"\"\"      \n");
+        let shell = BOTH.apply("echo $# # note\n", Some("sh"));
+        assert_eq!(shell, "echo $#       \n");
+        let rust = BOTH.apply("fn f<'a>(s: &'a str) { \"x\" } // y", Some("rs"));
+        assert_eq!(rust, "fn f<'a>(s: &'a str) { \" \" }     ");
+        let html = BOTH.apply("<p>a</p><!-- b\n--> \"c\"", Some("html"));
+        assert_eq!(html, "<p>a</p>      \n    \"c\"");
+        // An unterminated string ends with its line
+        assert_eq!(BOTH.apply("a('b\nc // d", Some("js")), "a(' \nc     ");
+    }
+}
@@ -1,17 +1,48 @@
 pub mod banned_dependency;
 pub mod banned_import;
 pub mod banned_pattern;
+pub mod composite;
+pub mod env_hygiene;
+pub mod external_command;
 pub mod factory;
+pub mod file_encoding;
+pub mod file_limits;
 pub mod file_presence;
+pub mod file_structure;
+pub mod freeze_window;
+pub mod frontmatter;
+pub mod import_boundary;
+pub mod masking;
+pub mod paired_change;
+pub mod path_pattern;
+pub mod patterns;
+pub mod pr_size;
 pub mod ratchet;
+pub mod regex_pool;
 pub mod required_pattern;
+pub mod risky_change;
+pub mod secrets;
+#[cfg(feature = "syntax")]
+pub mod syntax_query;
+pub mod tailwind_classes;
 pub mod tailwind_dark_mode;
 pub mod tailwind_theme_tokens;
+pub mod todo_age;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 pub mod window_pattern;
 
 use crate::config::Severity;
+use crate::git_diff::DiffInfo;
+use crate::path_class::PathClass;
+use crate::rules::patterns::PatternSet;
+use serde::{Deserialize, Serialize};
+use std::ops::{Range, RangeInclusive};
 use std::path::{Path, PathBuf};
 
+/// File types the Tailwind rules check when no `glob` is configured.
+pub(crate) const TAILWIND_FILE_TYPES: &[&str] = &["tsx", "jsx", "html"];
+
 /// A lint rule that checks source files for violations.
 pub trait Rule: Send + Sync {
     /// Unique identifier for this rule (e.g. `"tailwind-dark-mode"`).
@@ -23,25 +54,127 @@ pub trait Rule: Send + Sync {
     /// Optional glob pattern restricting which files are scanned.
     fn file_glob(&self) -> Option<&str>;
 
+    /// Literals of which at least one appears in every file the rule can
+    /// flag. Files containing none are skipped without calling `check_file`.
+    fn prefilter(&self) -> Option<&[String]> {
+        None
+    }
+
+    /// Patterns one of which matches on every line the rule flags. The scan
+    /// searches for the patterns of all such rules in a glob group together
+    /// and calls [`Rule::check_lines`] with the lines that matched.
+    fn line_patterns(&self) -> Option<&PatternSet> {
+        None
+    }
+
     /// Scan a single file and return any violations found.
     fn check_file(&self, ctx: &ScanContext) -> Vec<Violation>;
+
+    /// Like [`Rule::check_file`], but only looking at `candidates`: indices
+    /// into `lines` (the file's lines) where one of the rule's
+    /// [`line_patterns`](Rule::line_patterns) matched, in order.
+    fn check_lines(&self, ctx: &ScanContext, _lines: &[&str], _candidates: &[usize]) -> Vec<Violation> {
+        self.check_file(ctx)
+    }
+
+    /// Whether the rule's violations for a file depend only on its content,
+    /// its path and the rule's config, so the scan cache may reuse them.
+    /// Rules that also read other files, git or the clock return `false`.
+    fn cacheable(&self) -> bool {
+        true
+    }
+}
+
+/// A rule evaluated once against the whole change set rather than per file.
+///
+/// Diff rules only run in diff-aware mode (`--changed-only`); without a diff
+/// there is nothing for them to check.
+pub trait DiffRule: Send + Sync {
+    /// Unique identifier for this rule.
+    fn id(&self) -> &str;
+
+    /// Severity level reported when the rule fires.
+    fn severity(&self) -> Severity;
+
+    /// Inspect the diff and return any diff-level violations.
+    fn check_diff(&self, diff: &DiffInfo) -> Vec<Violation>;
+}
+
+/// A rule that checks file paths (names and placement) instead of contents.
+///
+/// Path rules see every walked file before any file is read, and only within
+/// their `glob`.
+pub trait PathRule: Send + Sync {
+    /// Unique identifier for this rule.
+    fn id(&self) -> &str;
+
+    /// Severity level reported when the rule fires.
+    fn severity(&self) -> Severity;
+
+    /// Check one walked path and return its violation, if any.
+    fn check_path(&self, path: &Path) -> Option<Violation>;
+}
+
+/// A rule evaluated once over the whole scan, after every file was checked.
+///
+/// For checks that need global state: files that must or must not exist,
+/// relationships between files, or totals over what the file pass found.
+pub trait RepoRule: Send + Sync {
+    /// Unique identifier for this rule.
+    fn id(&self) -> &str;
+
+    /// Severity level reported when the rule fires.
+    fn severity(&self) -> Severity;
+
+    /// Inspect the scan as a whole and return any violations.
+    fn check_repo(&self, repo: &RepoContext) -> Vec<Violation>;
+}
+
+/// What a [`RepoRule`] sees of the scan.
+pub struct RepoContext<'a> {
+    /// The paths the scan was started on.
+    pub roots: &'a [PathBuf],
+    /// Every file the walk found, in walk order.
+    pub files: &'a [PathBuf],
+    /// Violations from the file pass, before ratchet budgets are applied.
+    /// Empty in `--counts-only` scans, which don't keep them.
+    pub violations: &'a [Violation],
 }
 
 /// The file currently being scanned.
 pub struct ScanContext<'a> {
     pub file_path: &'a Path,
     pub content: &'a str,
+    /// First-party or vendored, per the `[paths]` config section.
+    pub class: PathClass,
+    /// The file's extension, or the file type `[filetypes]` maps it to.
+    pub file_type: Option<&'a str>,
+    /// Line ranges (1-based) the diff adds or modifies in this file, in
+    /// `--changed-only` scans; empty for unchanged files, `None` otherwise.
+    pub changed_ranges: Option<&'a [RangeInclusive<usize>]>,
+}
+
+impl ScanContext<'_> {
+    /// Whether the diff adds or modifies `line` (1-based). Every line is in
+    /// the diff of a scan without one.
+    pub fn in_diff(&self, line: usize) -> bool {
+        self.changed_ranges
+            .is_none_or(|ranges| ranges.iter().any(|range| range.contains(&line)))
+    }
 }
 
 /// Machine-actionable fix data for a violation.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Fix {
     pub old: String,
     pub new: String,
+    /// Byte range of `old` in the file, when the rule knows exactly where it is.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub range: Option<Range<usize>>,
 }
 
 /// A single violation emitted by a rule.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Violation {
     pub rule_id: String,
     pub severity: Severity,
@@ -52,13 +185,48 @@ pub struct Violation {
     pub suggest: Option<String>,
     pub source_line: Option<String>,
     pub fix: Option<Fix>,
+    /// The rule's `url`, filled in by the scan for rules that declare one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+#[cfg(test)]
+impl Violation {
+    /// An error from `rule_id` at line 1, column 1 of `file`, for tests.
+    pub(crate) fn test(rule_id: &str, file: &str) -> Self {
+        Violation {
+            rule_id: rule_id.into(),
+            severity: Severity::Error,
+            file: PathBuf::from(file),
+            line: Some(1),
+            column: Some(1),
+            message: "m".into(),
+            suggest: None,
+            source_line: None,
+            fix: None,
+            url: None,
+        }
+    }
+
+    /// This violation on `line`, whose text is `source`.
+    pub(crate) fn at(self, line: usize, source: &str) -> Self {
+        Violation {
+            line: Some(line),
+            source_line: Some(source.into()),
+            ..self
+        }
+    }
 }
 
 /// Errors that can occur when constructing a rule from config.
 #[derive(Debug)]
 pub enum RuleBuildError {
     InvalidRegex(String, regex::Error),
+    InvalidGlob(String, globset::Error),
     MissingField(String, &'static str),
+    /// None of several fields, any one of which would do, is set.
+    MissingOneOf(String, &'static [&'static str]),
+    InvalidField(String, &'static str, String),
 }
 
 impl std::fmt::Display for RuleBuildError {
@@ -67,9 +235,19 @@ impl std::fmt::Display for RuleBuildError {
             RuleBuildError::InvalidRegex(id, err) => {
                 write!(f, "rule '{}': invalid regex: {}", id, err)
             }
+            RuleBuildError::InvalidGlob(id, err) => {
+                write!(f, "rule '{}': invalid glob: {}", id, err)
+            }
             RuleBuildError::MissingField(id, field) => {
                 write!(f, "rule '{}': missing required field '{}'", id, field)
             }
+            RuleBuildError::MissingOneOf(id, fields) => {
+                let fields: Vec<String> = fields.iter().map(|field| format!("'{}'", field)).collect();
+                write!(f, "rule '{}': needs at least one of {}", id, fields.join(", "))
+            }
+            RuleBuildError::InvalidField(id, field, reason) => {
+                write!(f, "rule '{}': invalid value for '{}': {}", id, field, reason)
+            }
         }
     }
 }
@@ -0,0 +1,235 @@
+use crate::config::{RuleConfig, Severity};
+use crate::git_diff::DiffInfo;
+use crate::rules::{DiffRule, RuleBuildError, Violation};
+use crate::scan::compile_glob_set;
+use globset::GlobSet;
+use std::path::PathBuf;
+
+/// Requires that changes to one set of files come with changes to another.
+///
+/// Evaluated against the diff in `--changed-only` mode: if any changed file
+/// matches `glob`, at least one changed file must match one of the globs in
+/// `required_files` (e.g. changing `api/**` requires updating
+/// `api/CHANGELOG.md` or `openapi.yaml`). Reported once per diff.
+///
+/// `schema-change` is the same rule with schema-oriented defaults.
+#[derive(Debug)]
+pub struct PairedChangeRule {
+    id: String,
+    severity: Severity,
+    message: String,
+    suggest: Option<String>,
+    /// Noun used in messages for trigger files ("file", "schema file").
+    subject: &'static str,
+    trigger_globs: GlobSet,
+    trigger_pattern: String,
+    required_patterns: Vec<String>,
+    required_globs: GlobSet,
+}
+
+const DEFAULT_SCHEMA_GLOBS: &[&str] = &["**/*.graphql", "**/*.gql", "**/*.sql", "**/migrations/**"];
+const DEFAULT_NOTE_GLOBS: &[&str] = &["**/CHANGELOG.md"];
+
+impl PairedChangeRule {
+    /// Build a `paired-change` rule; both `glob` and `required_files` are required.
+    pub fn new(config: &RuleConfig) -> Result<Self, RuleBuildError> {
+        let trigger = config
+            .glob
+            .as_ref()
+            .filter(|g| !g.is_empty())
+            .ok_or_else(|| RuleBuildError::MissingField(config.id.clone(), "glob"))?;
+        if config.required_files.is_empty() {
+            return Err(RuleBuildError::MissingField(
+                config.id.clone(),
+                "required_files",
+            ));
+        }
+
+        Self::build(config, "file", vec![trigger.clone()], config.required_files.clone())
+    }
+
+    /// Build a `schema-change` rule: schema globs and changelog notes by default.
+    pub fn schema_change(config: &RuleConfig) -> Result<Self, RuleBuildError> {
+        let schema_patterns: Vec<String> = match config.glob {
+            Some(ref g) => vec![g.clone()],
+            None => DEFAULT_SCHEMA_GLOBS.iter().map(|s| s.to_string()).collect(),
+        };
+        let note_patterns: Vec<String> = if config.required_files.is_empty() {
+            DEFAULT_NOTE_GLOBS.iter().map(|s| s.to_string()).collect()
+        } else {
+            config.required_files.clone()
+        };
+
+        Self::build(config, "schema file", schema_patterns, note_patterns)
+    }
+
+    fn build(
+        config: &RuleConfig,
+        subject: &'static str,
+        trigger_patterns: Vec<String>,
+        required_patterns: Vec<String>,
+    ) -> Result<Self, RuleBuildError> {
+        let trigger_globs = compile_glob_set(&trigger_patterns)
+            .map_err(|e| RuleBuildError::InvalidGlob(config.id.clone(), e))?;
+        let required_globs = compile_glob_set(&required_patterns)
+            .map_err(|e| RuleBuildError::InvalidGlob(config.id.clone(), e))?;
+
+        Ok(Self {
+            id: config.id.clone(),
+            severity: config.severity,
+            message: config.message.clone(),
+            suggest: config.suggest.clone(),
+            subject,
+            trigger_globs,
+            trigger_pattern: trigger_patterns.join(", "),
+            required_patterns,
+            required_globs,
+        })
+    }
+}
+
+impl DiffRule for PairedChangeRule {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    fn check_diff(&self, diff: &DiffInfo) -> Vec<Violation> {
+        let mut changed: Vec<&PathBuf> = diff.changed_lines.keys().collect();
+        changed.sort();
+
+        let triggered: Vec<&PathBuf> = changed
+            .iter()
+            .copied()
+            .filter(|p| self.trigger_globs.is_match(p))
+            .collect();
+        if triggered.is_empty() {
+            return Vec::new();
+        }
+
+        if changed.iter().any(|p| self.required_globs.is_match(p)) {
+            return Vec::new();
+        }
+
+        let detail = format!(
+            "{} {}{} matching {} changed without updating {}",
+            triggered.len(),
+            self.subject,
+            if triggered.len() == 1 { "" } else { "s" },
+            self.trigger_pattern,
+            self.required_patterns.join(" or ")
+        );
+        let msg = if self.message.is_empty() {
+            detail
+        } else {
+            format!("{}: {}", self.message, detail)
+        };
+
+        vec![Violation {
+            rule_id: self.id.clone(),
+            severity: self.severity,
+            file: triggered[0].clone(),
+            line: None,
+            column: None,
+            message: msg,
+            suggest: self.suggest.clone(),
+            source_line: None,
+            fix: None,
+            url: None,
+        }]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn make_diff(files: &[&str]) -> DiffInfo {
+        let mut changed_lines = HashMap::new();
+        for f in files {
+            changed_lines.insert(PathBuf::from(f), vec![1..=1]);
+        }
+        DiffInfo {
+            changed_lines,
+            ..Default::default()
+        }
+    }
+
+    fn make_config(glob: Option<&str>, required: Vec<&str>) -> RuleConfig {
+        RuleConfig {
+            id: "paired".into(),
+            severity: Severity::Error,
+            message: "paired change missing".into(),
+            glob: glob.map(|s| s.to_string()),
+            required_files: required.into_iter().map(|s| s.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn paired_change_missing_flagged() {
+        let rule =
+            PairedChangeRule::new(&make_config(Some("api/**"), vec!["api/CHANGELOG.md", "openapi.yaml"]))
+                .unwrap();
+        let violations = rule.check_diff(&make_diff(&["api/users.ts", "api/orders.ts", "src/app.ts"]));
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].file, PathBuf::from("api/orders.ts"));
+        assert!(violations[0].line.is_none());
+        assert!(violations[0].message.contains("2 files matching api/** changed"));
+        assert!(violations[0].message.contains("api/CHANGELOG.md or openapi.yaml"));
+    }
+
+    #[test]
+    fn any_required_file_satisfies() {
+        let rule =
+            PairedChangeRule::new(&make_config(Some("api/**"), vec!["api/CHANGELOG.md", "openapi.yaml"]))
+                .unwrap();
+        assert!(rule.check_diff(&make_diff(&["api/users.ts", "openapi.yaml"])).is_empty());
+        assert!(rule.check_diff(&make_diff(&["api/users.ts", "api/CHANGELOG.md"])).is_empty());
+    }
+
+    #[test]
+    fn untriggered_passes() {
+        let rule = PairedChangeRule::new(&make_config(Some("api/**"), vec!["openapi.yaml"])).unwrap();
+        assert!(rule.check_diff(&make_diff(&["src/app.ts"])).is_empty());
+    }
+
+    #[test]
+    fn paired_change_requires_fields() {
+        let err = PairedChangeRule::new(&make_config(None, vec!["x"])).unwrap_err();
+        assert!(matches!(err, RuleBuildError::MissingField(_, "glob")));
+        let err = PairedChangeRule::new(&make_config(Some("api/**"), vec![])).unwrap_err();
+        assert!(matches!(err, RuleBuildError::MissingField(_, "required_files")));
+    }
+
+    #[test]
+    fn schema_change_defaults() {
+        let rule = PairedChangeRule::schema_change(&make_config(None, vec![])).unwrap();
+        let violations = rule.check_diff(&make_diff(&["api/schema.graphql", "src/app.ts"]));
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].message.contains("1 schema file matching"));
+
+        let diff = make_diff(&["migrations/002_users.sql", "CHANGELOG.md"]);
+        assert!(rule.check_diff(&diff).is_empty());
+    }
+
+    #[test]
+    fn schema_change_custom_globs() {
+        let rule =
+            PairedChangeRule::schema_change(&make_config(Some("db/schema/**"), vec!["docs/db-changes.md"]))
+                .unwrap();
+        let violations = rule.check_diff(&make_diff(&["db/schema/users.prisma"]));
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].message.contains("docs/db-changes.md"));
+    }
+
+    #[test]
+    fn invalid_glob_error() {
+        let err = PairedChangeRule::new(&make_config(Some("[invalid"), vec!["x"])).unwrap_err();
+        assert!(matches!(err, RuleBuildError::InvalidGlob(_, _)));
+    }
+}
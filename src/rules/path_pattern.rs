@@ -0,0 +1,158 @@
+use crate::config::{RuleConfig, Severity};
+use crate::rules::patterns::PatternSet;
+use crate::rules::{PathRule, Rule, RuleBuildError, ScanContext, Violation};
+use std::path::Path;
+
+/// Matches `pattern` against file paths instead of file contents.
+///
+/// Useful for conventions like "no `utils` directories", "no spaces in
+/// filenames" or "kebab-case directories" (by banning what isn't). Paths are
+/// checked as the walk finds them, so files are never read. Paths use `/`
+/// separators and have no leading `./`.
+#[derive(Debug)]
+pub struct PathPatternRule {
+    id: String,
+    severity: Severity,
+    message: String,
+    suggest: Option<String>,
+    glob: Option<String>,
+    patterns: PatternSet,
+}
+
+impl PathPatternRule {
+    pub fn new(config: &RuleConfig) -> Result<Self, RuleBuildError> {
+        let patterns = PatternSet::new(config)?;
+
+        Ok(Self {
+            id: config.id.clone(),
+            severity: config.severity,
+            message: config.message.clone(),
+            suggest: config.suggest.clone(),
+            glob: config.glob.clone(),
+            patterns,
+        })
+    }
+}
+
+impl Rule for PathPatternRule {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    fn file_glob(&self) -> Option<&str> {
+        self.glob.as_deref()
+    }
+
+    fn check_file(&self, _ctx: &ScanContext) -> Vec<Violation> {
+        // Paths are checked during the walk via check_path, not per file
+        Vec::new()
+    }
+}
+
+impl PathRule for PathPatternRule {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    /// A violation if `path` matches any of the rule's patterns. One per
+    /// path, for the earliest match.
+    fn check_path(&self, path: &Path) -> Option<Violation> {
+        let normalized = path.to_string_lossy().replace('\\', "/");
+        let normalized = normalized.trim_start_matches("./");
+        let (_, _, index) = self.patterns.find_at(normalized, 0)?;
+        let default = if self.message.is_empty() {
+            "Path matches a banned pattern"
+        } else {
+            &self.message
+        };
+
+        Some(Violation {
+            rule_id: self.id.clone(),
+            severity: self.severity,
+            file: path.to_path_buf(),
+            line: None,
+            column: None,
+            message: self.patterns.message(index, default),
+            suggest: self.patterns.suggest(index, self.suggest.as_ref()),
+            source_line: None,
+            fix: None,
+            url: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RulePattern;
+
+    fn make_rule(pattern: &str, regex: bool) -> PathPatternRule {
+        let config = RuleConfig {
+            id: "test-path-pattern".into(),
+            severity: Severity::Error,
+            pattern: Some(pattern.into()),
+            regex,
+            ..Default::default()
+        };
+        PathPatternRule::new(&config).unwrap()
+    }
+
+    #[test]
+    fn flags_matching_paths() {
+        let rule = make_rule("(^|/)utils/", true);
+        let v = rule.check_path(Path::new("./src/utils/date.ts")).unwrap();
+        assert_eq!(v.file, Path::new("./src/utils/date.ts"));
+        assert_eq!(v.message, "Path matches a banned pattern");
+        assert!(v.line.is_none());
+
+        assert!(rule.check_path(Path::new("utils/date.ts")).is_some());
+        assert!(rule.check_path(Path::new("src/myutils/date.ts")).is_none());
+    }
+
+    #[test]
+    fn literal_patterns_match_anywhere_in_the_path() {
+        let rule = make_rule(" ", false);
+        assert!(rule.check_path(Path::new("docs/Release Notes.md")).is_some());
+        assert!(rule.check_path(Path::new("docs/release-notes.md")).is_none());
+    }
+
+    #[test]
+    fn non_kebab_case_directories() {
+        let rule = make_rule("[A-Z_ ][^/]*/", true);
+        assert!(rule.check_path(Path::new("src/UserProfile/index.ts")).is_some());
+        assert!(rule.check_path(Path::new("src/user_profile/index.ts")).is_some());
+        // Only directories are checked; file names are free
+        assert!(rule.check_path(Path::new("src/user-profile/UserCard.tsx")).is_none());
+    }
+
+    #[test]
+    fn per_pattern_messages() {
+        let config = RuleConfig {
+            id: "layout".into(),
+            message: "Banned path".into(),
+            patterns: vec![
+                RulePattern {
+                    pattern: "/helpers/".into(),
+                    ..Default::default()
+                },
+                RulePattern {
+                    pattern: ".spec.".into(),
+                    message: Some("Name tests *.test.ts".into()),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        let rule = PathPatternRule::new(&config).unwrap();
+        assert_eq!(rule.check_path(Path::new("src/helpers/a.ts")).unwrap().message, "Banned path: '/helpers/'");
+        assert_eq!(rule.check_path(Path::new("src/a.spec.ts")).unwrap().message, "Name tests *.test.ts");
+    }
+}
@@ -0,0 +1,469 @@
+use crate::config::RuleConfig;
+use crate::line_endings;
+use crate::rules::masking::Masking;
+use crate::rules::regex_pool::{self, RegexPool};
+use crate::rules::RuleBuildError;
+use aho_corasick::AhoCorasick;
+use regex::{Regex, RegexSet};
+use std::sync::{Arc, OnceLock};
+
+#[derive(Debug)]
+struct Entry {
+    pattern: String,
+    /// The regex compiled for `pattern`, with the rule's matching options
+    /// applied; a literal with options becomes a regex too.
+    expr: String,
+    /// Empty until first use when the rule's regexes are deferred.
+    regex: Option<OnceLock<Arc<Regex>>>,
+    message: Option<String>,
+    suggest: Option<String>,
+}
+
+/// A rule's `pattern` plus its `patterns`, searched for together.
+///
+/// Shared by the ratchet and banned-pattern rules. Each pattern is a literal
+/// or, with `regex`, a regular expression, and may carry its own message.
+/// `case_insensitive`, `word_boundary`, `multiline`, `skip_comments` and
+/// `skip_strings` apply to every pattern. With `defer_regex`, regexes are only
+/// parsed when the set is built and compiled on first use.
+#[derive(Debug)]
+pub struct PatternSet {
+    entries: Vec<Entry>,
+    /// Whether the whole file is searched at once, so matches can span lines.
+    multiline: bool,
+    /// Comments and strings in which matches don't count.
+    masking: Masking,
+    /// Every pattern, when all of them are literals.
+    literals: Option<Vec<String>>,
+    /// Where deferred regexes are compiled.
+    pool: Option<Arc<RegexPool>>,
+}
+
+impl PatternSet {
+    pub fn new(config: &RuleConfig) -> Result<Self, RuleBuildError> {
+        let mut entries = Vec::new();
+        if let Some(pattern) = config.pattern.as_ref().filter(|p| !p.is_empty()) {
+            entries.push(Self::entry(config, pattern, config.regex, None, None)?);
+        }
+        for extra in &config.patterns {
+            if extra.pattern.is_empty() {
+                return Err(RuleBuildError::InvalidField(
+                    config.id.clone(),
+                    "patterns",
+                    "patterns can't be empty".to_string(),
+                ));
+            }
+            entries.push(Self::entry(
+                config,
+                &extra.pattern,
+                extra.regex.unwrap_or(config.regex),
+                extra.message.clone(),
+                extra.suggest.clone(),
+            )?);
+        }
+        if entries.is_empty() {
+            return Err(RuleBuildError::MissingField(config.id.clone(), "pattern"));
+        }
+
+        let literals = entries
+            .iter()
+            .all(|e| e.regex.is_none())
+            .then(|| entries.iter().map(|e| e.pattern.clone()).collect());
+        Ok(Self {
+            entries,
+            multiline: config.multiline,
+            masking: Masking {
+                comments: config.skip_comments,
+                strings: config.skip_strings,
+            },
+            literals,
+            pool: config.regex_pool.clone(),
+        })
+    }
+
+    fn entry(
+        config: &RuleConfig,
+        pattern: &str,
+        regex: bool,
+        message: Option<String>,
+        suggest: Option<String>,
+    ) -> Result<Entry, RuleBuildError> {
+        let expr = expression(config, pattern, regex);
+        let regex = if expr.is_none() {
+            None
+        } else if config.defer_regex {
+            regex_pool::validate(config, expr.as_deref().unwrap())?;
+            Some(OnceLock::new())
+        } else {
+            Some(OnceLock::from(regex_pool::compile(config, expr.as_deref().unwrap())?))
+        };
+        Ok(Entry {
+            pattern: pattern.to_string(),
+            expr: expr.unwrap_or_default(),
+            regex,
+            message,
+            suggest,
+        })
+    }
+
+    /// The patterns as written in the config.
+    pub fn patterns(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().map(|e| e.pattern.as_str())
+    }
+
+    /// Whether patterns must be searched for in whole files with
+    /// [`PatternSet::find_in_content`], because they span lines or skip
+    /// comments or strings, so a rule can't hand them to a [`CombinedMatcher`].
+    pub fn whole_file(&self) -> bool {
+        self.multiline || !self.masking.is_empty()
+    }
+
+    /// Literals for [`Rule::prefilter`](crate::rules::Rule::prefilter). A
+    /// regex has no single literal every match must contain, so any regex
+    /// pattern disables the prefilter.
+    pub fn prefilter(&self) -> Option<&[String]> {
+        self.literals.as_deref()
+    }
+
+    /// The first match in `line` at or after byte `start`, as
+    /// `(start, end, pattern index)`. Ties go to the pattern listed first.
+    pub fn find_at(&self, line: &str, start: usize) -> Option<(usize, usize, usize)> {
+        let mut best: Option<(usize, usize, usize)> = None;
+        for (index, entry) in self.entries.iter().enumerate() {
+            let found = match entry.regex {
+                Some(ref re) => re
+                    .get_or_init(|| {
+                        match self.pool {
+                            Some(ref pool) => pool.get(&entry.expr),
+                            None => Regex::new(&entry.expr).map(Arc::new),
+                        }
+                        .expect("regex that parsed when the rule was built compiles")
+                    })
+                    .find_at(line, start)
+                    .map(|m| (m.start(), m.end())),
+                None => line[start..]
+                    .find(entry.pattern.as_str())
+                    .map(|pos| (start + pos, start + pos + entry.pattern.len())),
+            };
+            if let Some((s, e)) = found {
+                if best.is_none_or(|(best_start, _, _)| s < best_start) {
+                    best = Some((s, e, index));
+                }
+            }
+        }
+        best
+    }
+
+    /// Every match in `haystack` from the start, as `(start, end, pattern
+    /// index)`. A match overlapping an earlier one is skipped unless
+    /// `overlapping`.
+    pub fn find_all(&self, haystack: &str, overlapping: bool) -> Vec<(usize, usize, usize)> {
+        let mut found = Vec::new();
+        let mut search_start = 0;
+        while search_start <= haystack.len() {
+            let Some((start, end, index)) = self.find_at(haystack, search_start) else {
+                break;
+            };
+            found.push((start, end, index));
+            search_start = if overlapping || end == start {
+                // Resume at the next character so every starting position counts once
+                match haystack[start..].chars().next() {
+                    Some(c) => start + c.len_utf8(),
+                    None => break,
+                }
+            } else {
+                end
+            };
+        }
+        found
+    }
+
+    /// Every match in a file's `content` of type `file_type`, as `(0-based
+    /// line, line, byte column, pattern index)`, leaving out comments and
+    /// strings the rule skips. A match spanning lines is placed on its first
+    /// line.
+    pub fn find_in_content<'a>(
+        &self,
+        content: &'a str,
+        file_type: Option<&str>,
+        overlapping: bool,
+    ) -> Vec<(usize, &'a str, usize, usize)> {
+        let lines: Vec<&str> = line_endings::lines(content).collect();
+        let masked = self.masking.apply(content, file_type);
+        if !self.multiline {
+            return line_endings::lines(&masked)
+                .enumerate()
+                .flat_map(|(line_idx, text)| {
+                    let line = lines[line_idx];
+                    self.find_all(text, overlapping)
+                        .into_iter()
+                        .map(move |(start, _, index)| (line_idx, line, start, index))
+                })
+                .collect();
+        }
+        let starts = line_endings::starts(content);
+        self.find_all(&masked, overlapping)
+            .into_iter()
+            .map(|(start, _, index)| {
+                let line_idx = starts.partition_point(|&s| s <= start).saturating_sub(1);
+                let line = lines.get(line_idx).copied().unwrap_or_default();
+                let column = (start - starts.get(line_idx).copied().unwrap_or_default()).min(line.len());
+                (line_idx, line, column, index)
+            })
+            .collect()
+    }
+
+    /// The message for a match of pattern `index`: its own message, or else
+    /// `default` naming the pattern when the rule has more than one.
+    pub fn message(&self, index: usize, default: &str) -> String {
+        let entry = &self.entries[index];
+        match entry.message {
+            Some(ref message) => message.clone(),
+            None if self.entries.len() > 1 => format!("{}: '{}'", default, entry.pattern),
+            None => default.to_string(),
+        }
+    }
+
+    /// The suggestion for a match of pattern `index`.
+    pub fn suggest(&self, index: usize, default: Option<&String>) -> Option<String> {
+        self.entries[index].suggest.clone().or_else(|| default.cloned())
+    }
+}
+
+/// The regex to search for `pattern` with, or `None` to search for it as a
+/// literal: a literal stays one unless the rule's options need a regex.
+///
+/// `word_boundary` puts `\b` around a regex, and at each end of a literal
+/// that starts or ends with a word character. In `multiline` rules, `^` and
+/// `$` match at every line, and a newline in a literal matches CRLF too.
+fn expression(config: &RuleConfig, pattern: &str, regex: bool) -> Option<String> {
+    let newline = config.multiline && pattern.contains('\n');
+    if !regex && !config.case_insensitive && !config.word_boundary && !newline {
+        return None;
+    }
+    let mut expr = String::new();
+    match (config.case_insensitive, config.multiline) {
+        (true, true) => expr.push_str("(?imR)"),
+        (true, false) => expr.push_str("(?i)"),
+        (false, true) => expr.push_str("(?mR)"),
+        (false, false) => {}
+    }
+    let word = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() || c == '_');
+    if regex {
+        if config.word_boundary {
+            expr.push_str(&format!(r"\b(?:{})\b", pattern));
+        } else {
+            expr.push_str(pattern);
+        }
+    } else {
+        let bounded = config.word_boundary;
+        if bounded && word(pattern.chars().next()) {
+            expr.push_str(r"\b");
+        }
+        let escaped: Vec<String> = pattern.split('\n').map(regex::escape).collect();
+        expr.push_str(&escaped.join(r"\r?\n"));
+        if bounded && word(pattern.chars().next_back()) {
+            expr.push_str(r"\b");
+        }
+    }
+    Some(expr)
+}
+
+/// The [`PatternSet`]s of several rules, searched for together so that each
+/// line is scanned once however many rules share it: literals with one
+/// Aho-Corasick automaton, regexes with one `RegexSet`.
+///
+/// It only finds the lines each rule matches on; the rules then locate and
+/// report the matches on those lines themselves. The `RegexSet` is compiled
+/// on first use, like the rules' own regexes, so files no rule reaches never build it.
+#[derive(Debug)]
+pub struct CombinedMatcher {
+    members: usize,
+    literals: Option<AhoCorasick>,
+    /// The member each literal belongs to, by pattern id.
+    literal_owners: Vec<usize>,
+    regex_patterns: Vec<String>,
+    /// The member each regex belongs to.
+    regex_owners: Vec<usize>,
+    /// `None` when the regexes are too large to combine.
+    regexes: OnceLock<Option<RegexSet>>,
+    /// Members whose literals couldn't be combined, checked on every line.
+    unmatched: Vec<usize>,
+}
+
+impl CombinedMatcher {
+    /// Combine `sets`; member `i` is the `i`-th set.
+    pub fn new<'a>(sets: impl IntoIterator<Item = &'a PatternSet>) -> Self {
+        let mut members = 0;
+        let mut literal_patterns = Vec::new();
+        let mut literal_owners = Vec::new();
+        let mut regex_patterns = Vec::new();
+        let mut regex_owners = Vec::new();
+        for (member, set) in sets.into_iter().enumerate() {
+            members += 1;
+            for entry in &set.entries {
+                if entry.regex.is_some() {
+                    regex_patterns.push(entry.expr.clone());
+                    regex_owners.push(member);
+                } else {
+                    literal_patterns.push(entry.pattern.as_str());
+                    literal_owners.push(member);
+                }
+            }
+        }
+
+        let literals = (!literal_patterns.is_empty())
+            .then(|| AhoCorasick::new(&literal_patterns).ok())
+            .flatten();
+        let mut unmatched = Vec::new();
+        if literals.is_none() {
+            unmatched = literal_owners.clone();
+            unmatched.dedup();
+        }
+        Self {
+            members,
+            literals,
+            literal_owners,
+            regex_patterns,
+            regex_owners,
+            regexes: OnceLock::new(),
+            unmatched,
+        }
+    }
+
+    /// For each member, the indices of the `lines` on which one of its
+    /// patterns matches, in order.
+    pub fn candidates(&self, lines: &[&str]) -> Vec<Vec<usize>> {
+        let mut found: Vec<Vec<usize>> = vec![Vec::new(); self.members];
+        let mut mark = |member: usize, idx: usize| {
+            if found[member].last() != Some(&idx) {
+                found[member].push(idx);
+            }
+        };
+        let regexes = if self.regex_patterns.is_empty() {
+            None
+        } else {
+            self.regexes.get_or_init(|| RegexSet::new(&self.regex_patterns).ok()).as_ref()
+        };
+        let every_line: Vec<usize> = match regexes {
+            Some(_) => self.unmatched.clone(),
+            None => self.unmatched.iter().chain(&self.regex_owners).copied().collect(),
+        };
+
+        for (idx, line) in lines.iter().enumerate() {
+            if let Some(ref literals) = self.literals {
+                for m in literals.find_overlapping_iter(line) {
+                    mark(self.literal_owners[m.pattern().as_usize()], idx);
+                }
+            }
+            if let Some(regexes) = regexes {
+                for i in regexes.matches(line).iter() {
+                    mark(self.regex_owners[i], idx);
+                }
+            }
+            for &member in &every_line {
+                mark(member, idx);
+            }
+        }
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RulePattern;
+
+    fn config(pattern: Option<&str>, patterns: Vec<RulePattern>) -> RuleConfig {
+        RuleConfig {
+            id: "test".into(),
+            pattern: pattern.map(str::to_string),
+            patterns,
+            ..Default::default()
+        }
+    }
+
+    fn plain(pattern: &str) -> RulePattern {
+        RulePattern {
+            pattern: pattern.into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn finds_the_earliest_match_across_patterns() {
+        let set = PatternSet::new(&config(Some("b("), vec![plain("a("), plain("ab")])).unwrap();
+        assert_eq!(set.find_at("x ab( a(", 0), Some((2, 4, 2)));
+        assert_eq!(set.find_at("x ab( a(", 3), Some((3, 5, 0)));
+        assert_eq!(set.find_at("x ab( a(", 5), Some((6, 8, 1)));
+        assert_eq!(set.find_at("x ab( a(", 8), None);
+        assert_eq!(set.prefilter().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn regex_patterns_disable_the_prefilter() {
+        let mut regex = plain(r"eval\(");
+        regex.regex = Some(true);
+        let set = PatternSet::new(&config(Some("exec("), vec![regex])).unwrap();
+        assert!(set.prefilter().is_none());
+        assert_eq!(set.find_at("eval(x)", 0), Some((0, 5, 1)));
+    }
+
+    #[test]
+    fn deferred_regexes_compile_on_first_use() {
+        let deferred = |pattern: &str| RuleConfig {
+            regex: true,
+            defer_regex: true,
+            ..config(Some(pattern), Vec::new())
+        };
+        assert!(matches!(
+            PatternSet::new(&deferred("(unclosed")),
+            Err(RuleBuildError::InvalidRegex(..))
+        ));
+
+        let set = PatternSet::new(&deferred(r"eval\(")).unwrap();
+        assert!(set.entries[0].regex.as_ref().unwrap().get().is_none());
+        assert_eq!(set.find_at("x eval(y)", 0), Some((2, 7, 0)));
+        assert!(set.entries[0].regex.as_ref().unwrap().get().is_some());
+    }
+
+    #[test]
+    fn combined_matcher_finds_the_lines_of_each_set() {
+        let mut regex = plain(r"^\s*debugger;?$");
+        regex.regex = Some(true);
+        let logs = PatternSet::new(&config(Some("console.log("), vec![plain("console.debug(")])).unwrap();
+        let debugger = PatternSet::new(&config(None, vec![regex])).unwrap();
+        let todos = PatternSet::new(&config(Some("TODO"), Vec::new())).unwrap();
+        let matcher = CombinedMatcher::new([&logs, &debugger, &todos]);
+
+        let lines = ["console.log(1); console.debug(2);", "  debugger;", "// TODO", "ok", "console.log(TODO)"];
+        assert_eq!(matcher.candidates(&lines), [vec![0, 4], vec![1], vec![2, 4]]);
+        // The same patterns searched one rule at a time find the same lines
+        for (set, found) in [&logs, &debugger, &todos].iter().zip(matcher.candidates(&lines)) {
+            let expected: Vec<usize> = (0..lines.len()).filter(|&i| set.find_at(lines[i], 0).is_some()).collect();
+            assert_eq!(found, expected);
+        }
+    }
+
+    #[test]
+    fn messages_name_the_matched_pattern() {
+        let mut own = plain("b(");
+        own.message = Some("b() is gone".into());
+        own.suggest = Some("use c()".into());
+        let set = PatternSet::new(&config(Some("a("), vec![own])).unwrap();
+        assert_eq!(set.message(0, "banned call"), "banned call: 'a('");
+        assert_eq!(set.message(1, "banned call"), "b() is gone");
+        assert_eq!(set.suggest(1, None).as_deref(), Some("use c()"));
+
+        let single = PatternSet::new(&config(Some("a("), Vec::new())).unwrap();
+        assert_eq!(single.message(0, "banned call"), "banned call");
+    }
+
+    #[test]
+    fn rejects_missing_and_empty_patterns() {
+        let err = PatternSet::new(&config(None, Vec::new())).unwrap_err();
+        assert!(matches!(err, RuleBuildError::MissingField(_, "pattern")));
+        let err = PatternSet::new(&config(None, vec![plain("")])).unwrap_err();
+        assert!(matches!(err, RuleBuildError::InvalidField(_, "patterns", _)));
+    }
+}
@@ -0,0 +1,194 @@
+use crate::config::{RuleConfig, Severity};
+use crate::git_diff::DiffInfo;
+use crate::rules::{DiffRule, RuleBuildError, Violation};
+use crate::scan::compile_glob_set;
+use globset::GlobSet;
+use std::path::PathBuf;
+
+/// Keeps pull requests reviewable by capping their size.
+///
+/// Evaluated against the diff in `--changed-only` mode. Fires when the number
+/// of changed files exceeds `max_files` or the number of added/modified lines
+/// exceeds `max_lines`. Paths matching `exclude_glob` (lockfiles, generated
+/// code, snapshots) are left out of both counts.
+#[derive(Debug)]
+pub struct PrSizeRule {
+    id: String,
+    severity: Severity,
+    message: String,
+    suggest: Option<String>,
+    max_files: Option<usize>,
+    max_lines: Option<usize>,
+    exclude: Option<GlobSet>,
+}
+
+impl PrSizeRule {
+    /// Build a `pr-size` rule; at least one of `max_files` and `max_lines` is required.
+    pub fn new(config: &RuleConfig) -> Result<Self, RuleBuildError> {
+        if config.max_files.is_none() && config.max_lines.is_none() {
+            return Err(RuleBuildError::MissingOneOf(config.id.clone(), &["max_files", "max_lines"]));
+        }
+
+        let exclude = if config.exclude_glob.is_empty() {
+            None
+        } else {
+            Some(
+                compile_glob_set(&config.exclude_glob)
+                    .map_err(|e| RuleBuildError::InvalidGlob(config.id.clone(), e))?,
+            )
+        };
+
+        Ok(Self {
+            id: config.id.clone(),
+            severity: config.severity,
+            message: config.message.clone(),
+            suggest: config.suggest.clone(),
+            max_files: config.max_files,
+            max_lines: config.max_lines,
+            exclude,
+        })
+    }
+}
+
+impl DiffRule for PrSizeRule {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    fn check_diff(&self, diff: &DiffInfo) -> Vec<Violation> {
+        let counted: Vec<(&PathBuf, usize)> = diff
+            .changed_lines
+            .keys()
+            .filter(|p| !self.exclude.as_ref().is_some_and(|gs| gs.is_match(p)))
+            .map(|p| (p, diff.changed_line_count(p)))
+            .collect();
+
+        let files = counted.len();
+        let lines: usize = counted.iter().map(|(_, n)| n).sum();
+
+        let mut exceeded = Vec::new();
+        if let Some(max) = self.max_files {
+            if files > max {
+                exceeded.push(format!("{} files changed (max {})", files, max));
+            }
+        }
+        if let Some(max) = self.max_lines {
+            if lines > max {
+                exceeded.push(format!("{} lines added (max {})", lines, max));
+            }
+        }
+        if exceeded.is_empty() {
+            return Vec::new();
+        }
+
+        // Point at the largest change, the most likely candidate to split out.
+        let largest = counted
+            .iter()
+            .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(a.0)))
+            .map(|(p, _)| (*p).clone())
+            .unwrap_or_default();
+
+        let detail = exceeded.join(", ");
+        let msg = if self.message.is_empty() {
+            format!("Change is too large: {}", detail)
+        } else {
+            format!("{}: {}", self.message, detail)
+        };
+
+        vec![Violation {
+            rule_id: self.id.clone(),
+            severity: self.severity,
+            file: largest,
+            line: None,
+            column: None,
+            message: msg,
+            suggest: self.suggest.clone(),
+            source_line: None,
+            fix: None,
+            url: None,
+        }]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::ops::RangeInclusive;
+
+    fn make_diff(files: &[(&str, RangeInclusive<usize>)]) -> DiffInfo {
+        let mut changed_lines = HashMap::new();
+        for (f, range) in files {
+            changed_lines.insert(PathBuf::from(f), vec![range.clone()]);
+        }
+        DiffInfo {
+            changed_lines,
+            ..Default::default()
+        }
+    }
+
+    fn make_rule(max_files: Option<usize>, max_lines: Option<usize>, exclude: Vec<&str>) -> PrSizeRule {
+        let config = RuleConfig {
+            id: "pr-size".into(),
+            severity: Severity::Warning,
+            message: "PR too large".into(),
+            max_files,
+            max_lines,
+            exclude_glob: exclude.into_iter().map(|s| s.to_string()).collect(),
+            ..Default::default()
+        };
+        PrSizeRule::new(&config).unwrap()
+    }
+
+    #[test]
+    fn under_limits_passes() {
+        let rule = make_rule(Some(5), Some(100), vec![]);
+        let diff = make_diff(&[("a.ts", 1..=10), ("b.ts", 1..=10)]);
+        assert!(rule.check_diff(&diff).is_empty());
+    }
+
+    #[test]
+    fn too_many_files() {
+        let rule = make_rule(Some(1), None, vec![]);
+        let diff = make_diff(&[("a.ts", 1..=1), ("b.ts", 1..=3)]);
+        let violations = rule.check_diff(&diff);
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].message.contains("2 files changed (max 1)"));
+        assert_eq!(violations[0].file, PathBuf::from("b.ts"));
+    }
+
+    #[test]
+    fn too_many_lines() {
+        let rule = make_rule(None, Some(15), vec![]);
+        let diff = make_diff(&[("a.ts", 1..=10), ("b.ts", 1..=10)]);
+        let violations = rule.check_diff(&diff);
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].message.contains("20 lines added (max 15)"));
+    }
+
+    #[test]
+    fn excluded_paths_not_counted() {
+        let rule = make_rule(Some(1), Some(50), vec!["**/package-lock.json", "**/generated/**"]);
+        let diff = make_diff(&[
+            ("src/app.ts", 1..=10),
+            ("package-lock.json", 1..=5000),
+            ("src/generated/api.ts", 1..=900),
+        ]);
+        assert!(rule.check_diff(&diff).is_empty());
+    }
+
+    #[test]
+    fn missing_thresholds_error() {
+        let config = RuleConfig {
+            id: "pr-size".into(),
+            ..Default::default()
+        };
+        let err = PrSizeRule::new(&config).unwrap_err();
+        assert!(matches!(err, RuleBuildError::MissingOneOf(_, ["max_files", "max_lines"])));
+        assert_eq!(err.to_string(), "rule 'pr-size': needs at least one of 'max_files', 'max_lines'");
+    }
+}
@@ -1,12 +1,38 @@
 use crate::config::{RuleConfig, Severity};
+use crate::line_endings;
+use crate::ratchet_scopes::Scope;
+use crate::rules::patterns::PatternSet;
 use crate::rules::{Rule, RuleBuildError, ScanContext, Violation};
-use regex::Regex;
 
-/// A ratchet rule that counts literal pattern occurrences across all files.
+/// What counts toward a ratchet's `max_count`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CountBy {
+    /// Every match.
+    Match,
+    /// Each line with at least one match.
+    Line,
+    /// Each file with at least one match.
+    File,
+}
+
+/// A ratchet rule that counts pattern occurrences across all files.
+///
+/// Matches of every entry in `patterns` count toward the same `max_count`.
+/// With `scope = "new"`, only matches on lines the diff adds or modifies
+/// count, so `--changed-only` scans enforce "don't add more" without the
+/// repo-wide total; scans without a diff count nothing. With `scope =
+/// "directory"` or `"file"`, each directory or file has its own budget in
+/// the budgets file, and `max_count` is optional.
 ///
-/// Each match is reported as a violation. The scan layer post-processes:
-/// if total matches <= `max_count`, all violations are suppressed (the team
-/// is under budget). If over `max_count`, all violations are kept.
+/// Each counted occurrence is reported as a violation: every match, or only
+/// the first one per line or per file with `count_by = "line"` / `"file"`.
+/// Matches overlapping an earlier one are skipped unless `overlapping = true`.
+/// `case_insensitive`, `word_boundary`, `multiline`, `skip_comments` and
+/// `skip_strings` change how patterns match as for banned-pattern rules; a
+/// match spanning lines counts on the line it starts on.
+/// The scan layer post-processes: if total matches <= `max_count`, all
+/// violations are suppressed (the team is under budget). If over
+/// `max_count`, all violations are kept.
 #[derive(Debug)]
 pub struct RatchetRule {
     id: String,
@@ -14,30 +40,47 @@ pub struct RatchetRule {
     message: String,
     suggest: Option<String>,
     glob: Option<String>,
-    pattern: String,
+    patterns: PatternSet,
     max_count: usize,
-    compiled_regex: Option<Regex>,
+    count_by: CountBy,
+    overlapping: bool,
+    new_only: bool,
 }
 
 impl RatchetRule {
     pub fn new(config: &RuleConfig) -> Result<Self, RuleBuildError> {
-        let pattern = config
-            .pattern
-            .as_ref()
-            .filter(|p| !p.is_empty())
-            .ok_or_else(|| RuleBuildError::MissingField(config.id.clone(), "pattern"))?
-            .clone();
+        let patterns = PatternSet::new(config)?;
 
+        // Ratchets budgeted per directory or file need no total of their own
         let max_count = config
             .max_count
+            .or_else(|| Scope::parse(config.scope.as_deref()).map(|_| usize::MAX))
             .ok_or_else(|| RuleBuildError::MissingField(config.id.clone(), "max_count"))?;
 
-        let compiled_regex = if config.regex {
-            let re = Regex::new(&pattern)
-                .map_err(|e| RuleBuildError::InvalidRegex(config.id.clone(), e))?;
-            Some(re)
-        } else {
-            None
+        let count_by = match config.count_by.as_deref() {
+            None | Some("match") => CountBy::Match,
+            Some("line") => CountBy::Line,
+            Some("file") => CountBy::File,
+            Some(other) => {
+                return Err(RuleBuildError::InvalidField(
+                    config.id.clone(),
+                    "count_by",
+                    format!("expected \"match\", \"line\" or \"file\", got \"{}\"", other),
+                ))
+            }
+        };
+
+        // Per-directory and per-file budgets are applied by the scan
+        let new_only = match config.scope.as_deref() {
+            None | Some("repo" | "directory" | "file") => false,
+            Some("new") => true,
+            Some(other) => {
+                return Err(RuleBuildError::InvalidField(
+                    config.id.clone(),
+                    "scope",
+                    format!("expected \"repo\", \"new\", \"directory\" or \"file\", got \"{}\"", other),
+                ))
+            }
         };
 
         Ok(Self {
@@ -46,9 +89,11 @@ impl RatchetRule {
             message: config.message.clone(),
             suggest: config.suggest.clone(),
             glob: config.glob.clone(),
-            pattern,
+            patterns,
             max_count,
-            compiled_regex,
+            count_by,
+            overlapping: config.overlapping,
+            new_only,
         })
     }
 
@@ -56,8 +101,62 @@ impl RatchetRule {
         self.max_count
     }
 
-    pub fn pattern(&self) -> &str {
-        &self.pattern
+    pub fn patterns(&self) -> impl Iterator<Item = &str> {
+        self.patterns.patterns()
+    }
+
+    /// Violations for `matches`, given in file order as 0-based line index,
+    /// line, byte column and the index of the pattern matched.
+    fn check_matches<'a>(
+        &self,
+        ctx: &ScanContext,
+        matches: impl Iterator<Item = (usize, &'a str, usize, usize)>,
+    ) -> Vec<Violation> {
+        let mut violations = Vec::new();
+        if self.new_only && ctx.changed_ranges.is_none_or(<[_]>::is_empty) {
+            return violations;
+        }
+
+        let mut last_line = None;
+        for (line_idx, line, col, index) in matches {
+            if self.new_only && !ctx.in_diff(line_idx + 1) {
+                continue;
+            }
+            if self.count_by == CountBy::Line && last_line == Some(line_idx) {
+                continue;
+            }
+            last_line = Some(line_idx);
+            violations.push(Violation {
+                rule_id: self.id.clone(),
+                severity: self.severity,
+                file: ctx.file_path.to_path_buf(),
+                line: Some(line_idx + 1),
+                column: Some(col + 1),
+                message: self.patterns.message(index, &self.message),
+                suggest: self.patterns.suggest(index, self.suggest.as_ref()),
+                source_line: Some(line.to_string()),
+                fix: None,
+                url: None,
+            });
+            if self.count_by == CountBy::File {
+                break;
+            }
+        }
+
+        violations
+    }
+
+    /// The matches on each of `lines`, given as 0-based index and text.
+    fn line_matches<'a>(
+        &'a self,
+        lines: impl Iterator<Item = (usize, &'a str)> + 'a,
+    ) -> impl Iterator<Item = (usize, &'a str, usize, usize)> + 'a {
+        lines.flat_map(move |(line_idx, line)| {
+            self.patterns
+                .find_all(line, self.overlapping)
+                .into_iter()
+                .map(move |(start, _, index)| (line_idx, line, start, index))
+        })
     }
 }
 
@@ -74,55 +173,37 @@ impl Rule for RatchetRule {
         self.glob.as_deref()
     }
 
-    fn check_file(&self, ctx: &ScanContext) -> Vec<Violation> {
-        let mut violations = Vec::new();
+    /// `scope = "new"` counts depend on the diff.
+    fn cacheable(&self) -> bool {
+        !self.new_only
+    }
 
-        for (line_idx, line) in ctx.content.lines().enumerate() {
-            if let Some(ref re) = self.compiled_regex {
-                // Regex mode
-                for m in re.find_iter(line) {
-                    violations.push(Violation {
-                        rule_id: self.id.clone(),
-                        severity: self.severity,
-                        file: ctx.file_path.to_path_buf(),
-                        line: Some(line_idx + 1),
-                        column: Some(m.start() + 1),
-                        message: self.message.clone(),
-                        suggest: self.suggest.clone(),
-                        source_line: Some(line.to_string()),
-                        fix: None,
-                    });
-                }
-            } else {
-                // Literal mode
-                let pattern = self.pattern.as_str();
-                let pattern_len = pattern.len();
-                let mut search_start = 0;
-                while let Some(pos) = line[search_start..].find(pattern) {
-                    let col = search_start + pos;
-                    violations.push(Violation {
-                        rule_id: self.id.clone(),
-                        severity: self.severity,
-                        file: ctx.file_path.to_path_buf(),
-                        line: Some(line_idx + 1),
-                        column: Some(col + 1),
-                        message: self.message.clone(),
-                        suggest: self.suggest.clone(),
-                        source_line: Some(line.to_string()),
-                        fix: None,
-                    });
-                    search_start = col + pattern_len;
-                }
-            }
+    fn prefilter(&self) -> Option<&[String]> {
+        self.patterns.prefilter()
+    }
+
+    fn line_patterns(&self) -> Option<&PatternSet> {
+        (!self.patterns.whole_file()).then_some(&self.patterns)
+    }
+
+    fn check_file(&self, ctx: &ScanContext) -> Vec<Violation> {
+        if self.patterns.whole_file() {
+            let matches = self.patterns.find_in_content(ctx.content, ctx.file_type, self.overlapping);
+            return self.check_matches(ctx, matches.into_iter());
         }
+        self.check_matches(ctx, self.line_matches(line_endings::lines(ctx.content).enumerate()))
+    }
 
-        violations
+    fn check_lines(&self, ctx: &ScanContext, lines: &[&str], candidates: &[usize]) -> Vec<Violation> {
+        self.check_matches(ctx, self.line_matches(candidates.iter().map(|&idx| (idx, lines[idx]))))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::RulePattern;
+    use crate::path_class::PathClass;
     use std::path::Path;
 
     fn make_config(pattern: Option<&str>, max_count: Option<usize>) -> RuleConfig {
@@ -145,6 +226,9 @@ mod tests {
         let ctx = ScanContext {
             file_path: Path::new("test.ts"),
             content,
+            class: PathClass::FirstParty,
+            file_type: None,
+            changed_ranges: None,
         };
         let violations = rule.check_file(&ctx);
         assert_eq!(violations.len(), 1);
@@ -160,6 +244,9 @@ mod tests {
         let ctx = ScanContext {
             file_path: Path::new("test.ts"),
             content,
+            class: PathClass::FirstParty,
+            file_type: None,
+            changed_ranges: None,
         };
         let violations = rule.check_file(&ctx);
         assert_eq!(violations.len(), 3);
@@ -176,6 +263,9 @@ mod tests {
         let ctx = ScanContext {
             file_path: Path::new("test.ts"),
             content,
+            class: PathClass::FirstParty,
+            file_type: None,
+            changed_ranges: None,
         };
         let violations = rule.check_file(&ctx);
         assert!(violations.is_empty());
@@ -189,12 +279,114 @@ mod tests {
         let ctx = ScanContext {
             file_path: Path::new("test.ts"),
             content,
+            class: PathClass::FirstParty,
+            file_type: None,
+            changed_ranges: None,
         };
         let violations = rule.check_file(&ctx);
         assert_eq!(violations.len(), 1);
         assert_eq!(violations[0].column, Some(5)); // 1-indexed
     }
 
+    #[test]
+    fn overlapping_matches_count_when_enabled() {
+        let content = "aaaa\nxaax";
+        let ctx = ScanContext {
+            file_path: Path::new("test.ts"),
+            content,
+            class: PathClass::FirstParty,
+            file_type: None,
+            changed_ranges: None,
+        };
+        let literal = RatchetRule::new(&make_config(Some("aa"), Some(5))).unwrap();
+        assert_eq!(literal.check_file(&ctx).len(), 3);
+
+        let mut config = make_config(Some("aa"), Some(5));
+        config.overlapping = true;
+        let overlapping = RatchetRule::new(&config).unwrap();
+        let columns: Vec<_> = overlapping.check_file(&ctx).iter().map(|v| v.column).collect();
+        assert_eq!(columns, [Some(1), Some(2), Some(3), Some(2)]);
+
+        config.regex = true;
+        config.pattern = Some("a+".into());
+        let regex = RatchetRule::new(&config).unwrap();
+        assert_eq!(regex.check_file(&ctx).len(), 6);
+    }
+
+    #[test]
+    fn scope_new_counts_only_changed_lines() {
+        let mut config = make_config(Some("legacyFetch("), Some(0));
+        config.scope = Some("new".into());
+        let rule = RatchetRule::new(&config).unwrap();
+        let ctx = |changed_ranges| ScanContext {
+            file_path: Path::new("test.ts"),
+            content: "legacyFetch(a);\nlegacyFetch(b);\nlegacyFetch(c);",
+            class: PathClass::FirstParty,
+            file_type: None,
+            changed_ranges,
+        };
+
+        let changed = [2..=3];
+        let lines: Vec<_> = rule.check_file(&ctx(Some(&changed))).iter().map(|v| v.line).collect();
+        assert_eq!(lines, [Some(2), Some(3)]);
+        assert!(rule.check_file(&ctx(Some(&[]))).is_empty());
+        // Without a diff there's no new code to count
+        assert!(rule.check_file(&ctx(None)).is_empty());
+
+        config.scope = Some("everything".into());
+        assert!(matches!(
+            RatchetRule::new(&config),
+            Err(RuleBuildError::InvalidField(_, "scope", _))
+        ));
+    }
+
+    #[test]
+    fn count_by_line_or_file() {
+        let content = "TODO TODO\nok\nTODO";
+        let ctx = ScanContext {
+            file_path: Path::new("test.ts"),
+            content,
+            class: PathClass::FirstParty,
+            file_type: None,
+            changed_ranges: None,
+        };
+        let mut config = make_config(Some("TODO"), Some(5));
+        config.count_by = Some("line".into());
+        let lines: Vec<_> = RatchetRule::new(&config).unwrap().check_file(&ctx).iter().map(|v| v.line).collect();
+        assert_eq!(lines, [Some(1), Some(3)]);
+
+        config.count_by = Some("file".into());
+        let files = RatchetRule::new(&config).unwrap().check_file(&ctx);
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].line, Some(1));
+
+        config.count_by = Some("word".into());
+        let err = RatchetRule::new(&config).unwrap_err();
+        assert!(matches!(err, RuleBuildError::InvalidField(_, "count_by", _)));
+    }
+
+    #[test]
+    fn multiline_matches_count_on_their_first_line() {
+        let ctx = ScanContext {
+            file_path: Path::new("test.ts"),
+            content: "useEffect(() => {\n}, []); useEffect(() => {\n}, [])\nUSEEFFECT(() => {}, [])",
+            class: PathClass::FirstParty,
+            file_type: None,
+            changed_ranges: None,
+        };
+        let mut config = make_config(Some(r"useEffect\(\(\) => \{\s*\}, \[\]\)"), Some(5));
+        config.regex = true;
+        config.multiline = true;
+        config.case_insensitive = true;
+        let rule = RatchetRule::new(&config).unwrap();
+        assert!(rule.line_patterns().is_none());
+        let found: Vec<_> = rule.check_file(&ctx).iter().map(|v| (v.line, v.column)).collect();
+        assert_eq!(found, [(Some(1), Some(1)), (Some(2), Some(9)), (Some(4), Some(1))]);
+
+        config.count_by = Some("file".into());
+        assert_eq!(RatchetRule::new(&config).unwrap().check_file(&ctx).len(), 1);
+    }
+
     #[test]
     fn missing_pattern_error() {
         let config = make_config(None, Some(10));
@@ -231,8 +423,35 @@ mod tests {
     fn accessors() {
         let config = make_config(Some("legacyFetch("), Some(47));
         let rule = RatchetRule::new(&config).unwrap();
-        assert_eq!(rule.pattern(), "legacyFetch(");
+        assert_eq!(rule.patterns().collect::<Vec<_>>(), ["legacyFetch("]);
         assert_eq!(rule.max_count(), 47);
         assert_eq!(rule.id(), "test-ratchet");
     }
+
+    #[test]
+    fn patterns_share_one_budget() {
+        let mut config = make_config(Some("legacyFetch("), Some(10));
+        config.patterns = vec![RulePattern {
+            pattern: "legacyPost(".into(),
+            ..Default::default()
+        }];
+        let rule = RatchetRule::new(&config).unwrap();
+        let ctx = ScanContext {
+            file_path: Path::new("test.ts"),
+            content: "legacyPost(a); legacyFetch(b);\nlegacyFetch(c);",
+            class: PathClass::FirstParty,
+            file_type: None,
+            changed_ranges: None,
+        };
+        let violations = rule.check_file(&ctx);
+        let messages: Vec<_> = violations.iter().map(|v| v.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            [
+                "legacy pattern found: 'legacyPost('",
+                "legacy pattern found: 'legacyFetch('",
+                "legacy pattern found: 'legacyFetch('",
+            ]
+        );
+    }
 }
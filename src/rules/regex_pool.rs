@@ -0,0 +1,152 @@
+use crate::config::RuleConfig;
+use crate::rules::RuleBuildError;
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Compiled regexes shared by every rule built from one config.
+///
+/// Org presets often repeat a pattern across rules that differ only in globs
+/// or severity; each distinct pattern is compiled once and handed out behind
+/// an `Arc`.
+#[derive(Debug, Default)]
+pub struct RegexPool {
+    compiled: Mutex<HashMap<String, Arc<Regex>>>,
+    requested: AtomicUsize,
+    /// Patterns known to parse, from [`validate`] or the compile cache.
+    parsed: Mutex<HashSet<String>>,
+}
+
+/// How many regexes rules asked a [`RegexPool`] for, and how many it compiled.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RegexPoolStats {
+    pub requested: usize,
+    pub compiled: usize,
+}
+
+impl RegexPoolStats {
+    /// Requests per compiled regex; `1.0` when nothing was shared.
+    pub fn dedup_ratio(&self) -> f64 {
+        if self.compiled == 0 {
+            1.0
+        } else {
+            self.requested as f64 / self.compiled as f64
+        }
+    }
+}
+
+impl RegexPool {
+    /// The compiled regex for `pattern`, compiling it on the first request.
+    pub fn get(&self, pattern: &str) -> Result<Arc<Regex>, regex::Error> {
+        self.requested.fetch_add(1, Ordering::Relaxed);
+        let mut compiled = self.compiled.lock().unwrap();
+        if let Some(re) = compiled.get(pattern) {
+            return Ok(Arc::clone(re));
+        }
+        let re = Arc::new(Regex::new(pattern)?);
+        compiled.insert(pattern.to_string(), Arc::clone(&re));
+        Ok(re)
+    }
+
+    /// A pool that takes `patterns` as already validated.
+    pub fn with_parsed(patterns: Vec<String>) -> Self {
+        Self {
+            parsed: Mutex::new(patterns.into_iter().collect()),
+            ..Default::default()
+        }
+    }
+
+    /// The patterns known to parse, sorted.
+    pub fn parsed(&self) -> Vec<String> {
+        let mut parsed: Vec<String> = self.parsed.lock().unwrap().iter().cloned().collect();
+        parsed.sort();
+        parsed
+    }
+
+    pub fn stats(&self) -> RegexPoolStats {
+        RegexPoolStats {
+            requested: self.requested.load(Ordering::Relaxed),
+            compiled: self.compiled.lock().unwrap().len(),
+        }
+    }
+}
+
+/// Compile `pattern` for the rule `config` describes, through its pool when
+/// it has one.
+pub fn compile(config: &RuleConfig, pattern: &str) -> Result<Arc<Regex>, RuleBuildError> {
+    match config.regex_pool {
+        Some(ref pool) => pool.get(pattern),
+        None => Regex::new(pattern).map(Arc::new),
+    }
+    .map_err(|e| RuleBuildError::InvalidRegex(config.id.clone(), e))
+}
+
+/// Check that `pattern` parses without compiling it, for rules that compile
+/// their regexes on first use. Parsing is cheap next to compiling; only a
+/// pattern that fails to parse is compiled, for its error message. Patterns
+/// the rule's pool already knows to parse aren't parsed again.
+pub fn validate(config: &RuleConfig, pattern: &str) -> Result<(), RuleBuildError> {
+    let pool = config.regex_pool.as_deref();
+    if pool.is_some_and(|pool| pool.parsed.lock().unwrap().contains(pattern)) {
+        return Ok(());
+    }
+    if regex_syntax::Parser::new().parse(pattern).is_ok() {
+        if let Some(pool) = pool {
+            pool.parsed.lock().unwrap().insert(pattern.to_string());
+        }
+        return Ok(());
+    }
+    Regex::new(pattern)
+        .map(drop)
+        .map_err(|e| RuleBuildError::InvalidRegex(config.id.clone(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identical_patterns_share_one_regex() {
+        let pool = Arc::new(RegexPool::default());
+        let config = |id: &str| RuleConfig {
+            id: id.into(),
+            regex_pool: Some(Arc::clone(&pool)),
+            ..Default::default()
+        };
+
+        let a = compile(&config("a"), r"console\.log").unwrap();
+        let b = compile(&config("b"), r"console\.log").unwrap();
+        let c = compile(&config("c"), r"debugger").unwrap();
+        assert!(Arc::ptr_eq(&a, &b));
+        assert!(!Arc::ptr_eq(&a, &c));
+
+        let stats = pool.stats();
+        assert_eq!(stats, RegexPoolStats { requested: 3, compiled: 2 });
+        assert_eq!(stats.dedup_ratio(), 1.5);
+
+        let err = compile(&config("bad"), "(").unwrap_err();
+        assert!(matches!(err, RuleBuildError::InvalidRegex(ref id, _) if id == "bad"));
+    }
+
+    #[test]
+    fn validated_patterns_are_remembered() {
+        let pool = Arc::new(RegexPool::default());
+        let config = RuleConfig {
+            id: "r".into(),
+            regex_pool: Some(Arc::clone(&pool)),
+            ..Default::default()
+        };
+        validate(&config, r"eval\(").unwrap();
+        assert!(validate(&config, "(").is_err());
+        assert_eq!(pool.parsed(), [r"eval\("]);
+        assert_eq!(pool.stats().compiled, 0);
+
+        // A pool seeded from the compile cache doesn't parse its patterns again
+        let config = RuleConfig {
+            regex_pool: Some(Arc::new(RegexPool::with_parsed(vec!["(".into()]))),
+            ..config
+        };
+        assert!(validate(&config, "(").is_ok());
+    }
+}
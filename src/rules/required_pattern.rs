@@ -1,6 +1,8 @@
 use crate::config::{RuleConfig, Severity};
-use crate::rules::{Rule, RuleBuildError, ScanContext, Violation};
+use crate::line_endings;
+use crate::rules::{regex_pool, Fix, Rule, RuleBuildError, ScanContext, Violation};
 use regex::Regex;
+use std::sync::Arc;
 
 /// Ensures that files matching a glob contain a required pattern.
 ///
@@ -10,6 +12,9 @@ use regex::Regex;
 ///
 /// Optionally supports a `condition_pattern`: the required pattern is only
 /// enforced if the condition pattern is present in the file.
+///
+/// With an `insert` template, violations carry a fix adding it as the first
+/// lines of the file (after a `#!` line), in the file's line endings.
 #[derive(Debug)]
 pub struct RequiredPatternRule {
     id: String,
@@ -18,9 +23,10 @@ pub struct RequiredPatternRule {
     suggest: Option<String>,
     glob: Option<String>,
     pattern: String,
-    compiled_regex: Option<Regex>,
+    compiled_regex: Option<Arc<Regex>>,
     condition_pattern: Option<String>,
-    condition_regex: Option<Regex>,
+    condition_regex: Option<Arc<Regex>>,
+    insert: Option<String>,
 }
 
 impl RequiredPatternRule {
@@ -33,9 +39,7 @@ impl RequiredPatternRule {
             .clone();
 
         let compiled_regex = if config.regex {
-            let re = Regex::new(&pattern)
-                .map_err(|e| RuleBuildError::InvalidRegex(config.id.clone(), e))?;
-            Some(re)
+            Some(regex_pool::compile(config, &pattern)?)
         } else {
             None
         };
@@ -44,10 +48,7 @@ impl RequiredPatternRule {
             config
                 .condition_pattern
                 .as_ref()
-                .map(|p| {
-                    Regex::new(p)
-                        .map_err(|e| RuleBuildError::InvalidRegex(config.id.clone(), e))
-                })
+                .map(|p| regex_pool::compile(config, p))
                 .transpose()?
         } else {
             None
@@ -63,6 +64,7 @@ impl RequiredPatternRule {
             compiled_regex,
             condition_pattern: config.condition_pattern.clone(),
             condition_regex,
+            insert: config.insert.clone().filter(|insert| !insert.trim().is_empty()),
         })
     }
 
@@ -81,6 +83,26 @@ impl RequiredPatternRule {
             (None, _) => true, // no condition = always enforce
         }
     }
+
+    /// The fix inserting the `insert` template into `content`.
+    fn insert_fix(&self, content: &str) -> Option<Fix> {
+        let insert = self.insert.as_deref()?;
+        let (first, ending) = line_endings::split(content).next().unwrap_or(("", ""));
+        let ending = if ending.is_empty() { "\n" } else { ending };
+        let lines = insert.trim_end_matches(['\r', '\n']).replace("\r\n", "\n").replace('\n', ending);
+        // A shebang must stay first; `#![...]` is a Rust attribute, not one
+        let after_shebang = first.starts_with("#!") && !first.starts_with("#![");
+        let (at, new) = if after_shebang {
+            (first.len(), format!("{}{}", ending, lines))
+        } else {
+            (0, format!("{}{}", lines, ending))
+        };
+        Some(Fix {
+            old: String::new(),
+            new,
+            range: Some(at..at),
+        })
+    }
 }
 
 impl Rule for RequiredPatternRule {
@@ -116,8 +138,9 @@ impl Rule for RequiredPatternRule {
             column: Some(1),
             message: self.message.clone(),
             suggest: self.suggest.clone(),
-            source_line: ctx.content.lines().next().map(|l| l.to_string()),
-            fix: None,
+            source_line: line_endings::lines(ctx.content).next().map(|l| l.to_string()),
+            fix: self.insert_fix(ctx.content),
+            url: None,
         }]
     }
 }
@@ -125,6 +148,7 @@ impl Rule for RequiredPatternRule {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::path_class::PathClass;
     use std::path::Path;
 
     fn make_config(pattern: &str, glob: Option<&str>) -> RuleConfig {
@@ -143,6 +167,9 @@ mod tests {
         let ctx = ScanContext {
             file_path: Path::new("src/pages/Home.tsx"),
             content,
+            class: PathClass::FirstParty,
+            file_type: None,
+            changed_ranges: None,
         };
         rule.check_file(&ctx)
     }
@@ -209,6 +236,30 @@ mod tests {
         assert!(violations.is_empty());
     }
 
+    #[test]
+    fn insert_template_becomes_a_fix() {
+        let mut config = make_config("SPDX-License-Identifier", None);
+        config.insert = Some("// SPDX-License-Identifier: MIT\n// Copyright Acme\n".into());
+        let rule = RequiredPatternRule::new(&config).unwrap();
+        let fix = |content: &str| {
+            let fix = check(&rule, content).remove(0).fix.unwrap();
+            (fix.old, fix.new, fix.range.unwrap())
+        };
+
+        let header = "// SPDX-License-Identifier: MIT\n// Copyright Acme";
+        assert_eq!(fix("fn main() {}\n"), (String::new(), format!("{}\n", header), 0..0));
+        assert_eq!(
+            fix("#!/usr/bin/env node\r\nrun();\r\n"),
+            (String::new(), format!("\r\n{}", header.replace('\n', "\r\n")), 19..19)
+        );
+        // An inner attribute isn't a shebang
+        assert_eq!(fix("#![deny(unsafe_code)]\n").2, 0..0);
+        assert_eq!(fix("").1, format!("{}\n", header));
+
+        let rule = RequiredPatternRule::new(&make_config("x", None)).unwrap();
+        assert!(check(&rule, "y").remove(0).fix.is_none());
+    }
+
     #[test]
     fn missing_pattern_error() {
         let config = RuleConfig {
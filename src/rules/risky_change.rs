@@ -0,0 +1,230 @@
+use crate::config::{RuleConfig, Severity};
+use crate::git_diff::{self, DiffInfo};
+use crate::rules::{DiffRule, RuleBuildError, Violation};
+use crate::scan::compile_glob_set;
+use globset::GlobSet;
+use std::path::{Path, PathBuf};
+
+/// Asks for an explicit acknowledgement before large rewrites of critical code.
+///
+/// Evaluated against the diff in `--changed-only` mode. Reports every changed
+/// file matching one of the `critical_files` globs whose added/modified lines
+/// are more than `max_changed_percent` of its lines, unless the pull request
+/// description or a commit message in the range contains the `bypass_marker`
+/// (default `risk-ack:`).
+#[derive(Debug)]
+pub struct RiskyChangeRule {
+    id: String,
+    severity: Severity,
+    message: String,
+    suggest: Option<String>,
+    critical: GlobSet,
+    max_changed_percent: u32,
+    ack_marker: String,
+}
+
+const DEFAULT_ACK_MARKER: &str = "risk-ack:";
+
+impl RiskyChangeRule {
+    pub fn new(config: &RuleConfig) -> Result<Self, RuleBuildError> {
+        if config.critical_files.is_empty() {
+            return Err(RuleBuildError::MissingField(config.id.clone(), "critical_files"));
+        }
+        let critical = compile_glob_set(&config.critical_files)
+            .map_err(|e| RuleBuildError::InvalidGlob(config.id.clone(), e))?;
+
+        let max_changed_percent = config
+            .max_changed_percent
+            .ok_or_else(|| RuleBuildError::MissingField(config.id.clone(), "max_changed_percent"))?;
+        if max_changed_percent > 100 {
+            return Err(RuleBuildError::InvalidField(
+                config.id.clone(),
+                "max_changed_percent",
+                format!("expected 0 to 100, got {}", max_changed_percent),
+            ));
+        }
+
+        Ok(Self {
+            id: config.id.clone(),
+            severity: config.severity,
+            message: config.message.clone(),
+            suggest: config.suggest.clone(),
+            critical,
+            max_changed_percent,
+            ack_marker: config
+                .bypass_marker
+                .clone()
+                .unwrap_or_else(|| DEFAULT_ACK_MARKER.to_string()),
+        })
+    }
+
+    /// Check the diff, with `line_count` giving each changed file's current
+    /// number of lines (`None` when it can't be read).
+    fn check_with(&self, diff: &DiffInfo, line_count: impl Fn(&Path) -> Option<usize>) -> Vec<Violation> {
+        let acknowledged = diff
+            .pr_description
+            .iter()
+            .chain(&diff.commit_messages)
+            .any(|text| text.contains(&self.ack_marker));
+        if acknowledged {
+            return Vec::new();
+        }
+
+        let mut files: Vec<&PathBuf> = diff
+            .changed_lines
+            .keys()
+            .filter(|p| self.critical.is_match(p))
+            .collect();
+        files.sort();
+
+        let suggest = self.suggest.clone().or_else(|| {
+            Some(format!(
+                "Split the change or add '{}' with the reason to the PR description",
+                self.ack_marker
+            ))
+        });
+
+        files
+            .into_iter()
+            .filter_map(|file| {
+                let total = line_count(file)?.max(1);
+                let changed = diff.changed_line_count(file).min(total);
+                let percent = changed * 100 / total;
+                if percent <= self.max_changed_percent as usize {
+                    return None;
+                }
+                let detail = format!(
+                    "{}% of lines changed ({} of {}, limit {}%)",
+                    percent, changed, total, self.max_changed_percent
+                );
+                let message = if self.message.is_empty() {
+                    format!("Large change to a critical file: {}", detail)
+                } else {
+                    format!("{}: {}", self.message, detail)
+                };
+                Some(Violation {
+                    rule_id: self.id.clone(),
+                    severity: self.severity,
+                    file: file.clone(),
+                    line: None,
+                    column: None,
+                    message,
+                    suggest: suggest.clone(),
+                    source_line: None,
+                    fix: None,
+                    url: None,
+                })
+            })
+            .collect()
+    }
+}
+
+impl DiffRule for RiskyChangeRule {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    fn check_diff(&self, diff: &DiffInfo) -> Vec<Violation> {
+        let root = git_diff::repo_root().unwrap_or_default();
+        self.check_with(diff, |path| {
+            std::fs::read_to_string(root.join(path))
+                .ok()
+                .map(|content| content.lines().count())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn make_diff(files: &[(&str, usize)], commits: &[&str], description: Option<&str>) -> DiffInfo {
+        let mut changed_lines = HashMap::new();
+        for &(f, lines) in files {
+            changed_lines.insert(PathBuf::from(f), vec![1..=lines]);
+        }
+        DiffInfo {
+            changed_lines,
+            commit_messages: commits.iter().map(|s| s.to_string()).collect(),
+            pr_description: description.map(|s| s.to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn make_rule() -> RiskyChangeRule {
+        let config = RuleConfig {
+            id: "payments-rewrite".into(),
+            severity: Severity::Error,
+            critical_files: vec!["src/payments/**".into(), "src/auth/**".into()],
+            max_changed_percent: Some(30),
+            ..Default::default()
+        };
+        RiskyChangeRule::new(&config).unwrap()
+    }
+
+    fn hundred_lines(_: &Path) -> Option<usize> {
+        Some(100)
+    }
+
+    #[test]
+    fn flags_critical_files_over_the_limit() {
+        let rule = make_rule();
+        let diff = make_diff(
+            &[("src/payments/charge.ts", 64), ("src/auth/login.ts", 30), ("src/ui/button.tsx", 90)],
+            &["refactor: charge flow"],
+            None,
+        );
+        let violations = rule.check_with(&diff, hundred_lines);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].file, PathBuf::from("src/payments/charge.ts"));
+        assert_eq!(
+            violations[0].message,
+            "Large change to a critical file: 64% of lines changed (64 of 100, limit 30%)"
+        );
+    }
+
+    #[test]
+    fn ack_marker_in_description_or_commit_passes() {
+        let rule = make_rule();
+        let files = [("src/payments/charge.ts", 80)];
+        let described = make_diff(&files, &[], Some("Rewrite.\n\nrisk-ack: paired with @payments"));
+        assert!(rule.check_with(&described, hundred_lines).is_empty());
+        let committed = make_diff(&files, &["feat: new flow\n\nrisk-ack: approved in RFC-12"], None);
+        assert!(rule.check_with(&committed, hundred_lines).is_empty());
+    }
+
+    #[test]
+    fn unreadable_files_are_skipped() {
+        let rule = make_rule();
+        let diff = make_diff(&[("src/auth/session.ts", 50)], &[], None);
+        assert!(rule.check_with(&diff, |_| None).is_empty());
+    }
+
+    #[test]
+    fn requires_globs_and_a_valid_percent() {
+        let config = RuleConfig {
+            id: "r".into(),
+            max_changed_percent: Some(30),
+            ..Default::default()
+        };
+        assert!(matches!(
+            RiskyChangeRule::new(&config),
+            Err(RuleBuildError::MissingField(_, "critical_files"))
+        ));
+        let config = RuleConfig {
+            id: "r".into(),
+            critical_files: vec!["src/**".into()],
+            max_changed_percent: Some(150),
+            ..Default::default()
+        };
+        assert!(matches!(
+            RiskyChangeRule::new(&config),
+            Err(RuleBuildError::InvalidField(_, "max_changed_percent", _))
+        ));
+    }
+}
@@ -0,0 +1,193 @@
+use crate::config::{RuleConfig, Severity};
+use crate::line_endings;
+use crate::rules::{Rule, RuleBuildError, ScanContext, Violation};
+use tree_sitter::{Language, Parser, Query, QueryCursor, StreamingIterator};
+
+/// Matches a tree-sitter `query` against the syntax tree of JavaScript or
+/// TypeScript files, so comments and strings that merely look like code
+/// never match.
+///
+/// Each query match is a violation at the node captured as `@match`, or at
+/// its first capture. `language` is `"javascript"`, `"typescript"` or
+/// `"tsx"`; without a `glob`, the rule applies to that language's file
+/// extensions. Built only with the `syntax` feature.
+#[derive(Debug)]
+pub struct SyntaxQueryRule {
+    id: String,
+    severity: Severity,
+    message: String,
+    suggest: Option<String>,
+    glob: String,
+    language: Language,
+    query: Query,
+    capture: Option<u32>,
+}
+
+impl SyntaxQueryRule {
+    pub fn new(config: &RuleConfig) -> Result<Self, RuleBuildError> {
+        let source = config
+            .query
+            .as_deref()
+            .ok_or_else(|| RuleBuildError::MissingField(config.id.clone(), "query"))?;
+        let (language, default_glob): (Language, &str) = match config.language.as_deref() {
+            Some("javascript") => (tree_sitter_javascript::LANGUAGE.into(), "*.{js,jsx,mjs,cjs}"),
+            Some("typescript") => (tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(), "*.{ts,mts,cts}"),
+            Some("tsx") => (tree_sitter_typescript::LANGUAGE_TSX.into(), "*.tsx"),
+            Some(other) => {
+                return Err(RuleBuildError::InvalidField(
+                    config.id.clone(),
+                    "language",
+                    format!("expected \"javascript\", \"typescript\" or \"tsx\", got \"{}\"", other),
+                ))
+            }
+            None => return Err(RuleBuildError::MissingField(config.id.clone(), "language")),
+        };
+        let query = Query::new(&language, source)
+            .map_err(|e| RuleBuildError::InvalidField(config.id.clone(), "query", e.to_string()))?;
+
+        Ok(Self {
+            id: config.id.clone(),
+            severity: config.severity,
+            message: config.message.clone(),
+            suggest: config.suggest.clone(),
+            glob: config.glob.clone().unwrap_or_else(|| default_glob.to_string()),
+            capture: query.capture_index_for_name("match"),
+            language,
+            query,
+        })
+    }
+}
+
+impl Rule for SyntaxQueryRule {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    fn file_glob(&self) -> Option<&str> {
+        Some(&self.glob)
+    }
+
+    fn check_file(&self, ctx: &ScanContext) -> Vec<Violation> {
+        let mut parser = Parser::new();
+        if parser.set_language(&self.language).is_err() {
+            return Vec::new();
+        }
+        let Some(tree) = parser.parse(ctx.content, None) else {
+            return Vec::new();
+        };
+
+        let lines: Vec<&str> = line_endings::lines(ctx.content).collect();
+        let mut violations = Vec::new();
+        let mut cursor = QueryCursor::new();
+        let mut matches = cursor.matches(&self.query, tree.root_node(), ctx.content.as_bytes());
+        while let Some(m) = matches.next() {
+            let capture = match self.capture {
+                Some(index) => m.captures.iter().find(|c| c.index == index),
+                None => m.captures.first(),
+            };
+            let Some(capture) = capture else { continue };
+            let start = capture.node.start_position();
+            let text = capture.node.utf8_text(ctx.content.as_bytes()).unwrap_or_default();
+            violations.push(Violation {
+                rule_id: self.id.clone(),
+                severity: self.severity,
+                file: ctx.file_path.to_path_buf(),
+                line: Some(start.row + 1),
+                column: Some(start.column + 1),
+                message: if self.message.is_empty() {
+                    format!("'{}' matches a banned syntax pattern", text.lines().next().unwrap_or_default())
+                } else {
+                    self.message.clone()
+                },
+                suggest: self.suggest.clone(),
+                source_line: lines.get(start.row).map(|line| line.to_string()),
+                fix: None,
+                url: None,
+            });
+        }
+        violations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path_class::PathClass;
+    use std::path::Path;
+
+    fn make_rule(language: &str, query: &str) -> SyntaxQueryRule {
+        let config = RuleConfig {
+            id: "no-fetch".into(),
+            severity: Severity::Error,
+            language: Some(language.into()),
+            query: Some(query.into()),
+            ..Default::default()
+        };
+        SyntaxQueryRule::new(&config).unwrap()
+    }
+
+    fn check(rule: &SyntaxQueryRule, content: &str) -> Vec<Violation> {
+        let ctx = ScanContext {
+            file_path: Path::new("src/app.ts"),
+            content,
+            class: PathClass::FirstParty,
+            file_type: None,
+            changed_ranges: None,
+        };
+        rule.check_file(&ctx)
+    }
+
+    const FETCH_CALLS: &str = r#"(call_expression function: (identifier) @match (#eq? @match "fetch"))"#;
+
+    #[test]
+    fn matches_syntax_not_comments_or_strings() {
+        let rule = make_rule("typescript", FETCH_CALLS);
+        let content = "// fetch(url) is banned\nconst s = \"fetch(x)\";\nconst r: Response = await fetch(url);\napi.fetch(url);\n";
+        let violations = check(&rule, content);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].line, Some(3));
+        assert_eq!(violations[0].column, Some(27));
+        assert_eq!(violations[0].message, "'fetch' matches a banned syntax pattern");
+        assert_eq!(violations[0].source_line.as_deref(), Some("const r: Response = await fetch(url);"));
+    }
+
+    #[test]
+    fn reports_the_first_capture_without_match() {
+        let rule = make_rule("javascript", "(member_expression property: (property_identifier) @prop (#eq? @prop \"legacyFetch\"))");
+        let violations = check(&rule, "foo.legacyFetch();\n/* foo.legacyFetch() */\n");
+        assert_eq!(violations.len(), 1);
+        assert_eq!((violations[0].line, violations[0].column), (Some(1), Some(5)));
+    }
+
+    #[test]
+    fn defaults_the_glob_to_the_language() {
+        assert_eq!(make_rule("tsx", FETCH_CALLS).file_glob(), Some("*.tsx"));
+        assert_eq!(make_rule("javascript", FETCH_CALLS).file_glob(), Some("*.{js,jsx,mjs,cjs}"));
+    }
+
+    #[test]
+    fn rejects_bad_languages_and_queries() {
+        let config = |language: Option<&str>, query: &str| RuleConfig {
+            id: "q".into(),
+            language: language.map(String::from),
+            query: Some(query.into()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            SyntaxQueryRule::new(&config(None, FETCH_CALLS)),
+            Err(RuleBuildError::MissingField(_, "language"))
+        ));
+        assert!(matches!(
+            SyntaxQueryRule::new(&config(Some("python"), FETCH_CALLS)),
+            Err(RuleBuildError::InvalidField(_, "language", _))
+        ));
+        assert!(matches!(
+            SyntaxQueryRule::new(&config(Some("javascript"), "(not_a_node) @x")),
+            Err(RuleBuildError::InvalidField(_, "query", _))
+        ));
+    }
+}
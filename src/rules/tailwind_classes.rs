@@ -0,0 +1,241 @@
+use crate::line_endings;
+
+/// Attributes whose value is a class list.
+const CLASS_ATTRIBUTES: &[&str] = &["className", "class"];
+
+/// Functions whose string arguments are class lists, conditionals and
+/// variant maps included.
+const CLASS_FUNCTIONS: &[&str] = &["cn", "clsx", "classnames", "classNames", "cva", "cx", "tv", "twMerge", "twJoin"];
+
+/// Tags whose template literals are class lists (`` tw`...` ``, `` tw.div`...` ``).
+const CLASS_TAGS: &[&str] = &["tw"];
+
+/// One class of a class list, as written (variants included) and where.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClassToken<'a> {
+    pub text: &'a str,
+    /// Byte offset in the content.
+    pub offset: usize,
+    /// 1-based line.
+    pub line: usize,
+    /// 1-based byte column.
+    pub column: usize,
+}
+
+/// The class lists in `content`: the classes of each `className`/`class`
+/// attribute, class function call (`cn()`, `clsx()`, `cva()`, ...) and
+/// tagged template, in order.
+///
+/// Every string literal inside an attribute's `{...}` or a call's arguments
+/// counts, whichever branch of a conditional it's in, as do the static parts
+/// of template literals and the strings in their `${...}`. Lists may span
+/// lines.
+pub fn class_lists(content: &str) -> Vec<Vec<ClassToken<'_>>> {
+    let bytes = content.as_bytes();
+    let starts = line_endings::starts(content);
+    let mut lists = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        if !is_ident(bytes[i]) || (i > 0 && (is_ident(bytes[i - 1]) || bytes[i - 1] == b'.')) {
+            i += 1;
+            continue;
+        }
+        let end = i + bytes[i..].iter().take_while(|&&b| is_ident(b)).count();
+        let name = &content[i..end];
+        let mut spans = Vec::new();
+        i = match region(bytes, name, end) {
+            Some(Region::Attribute(at)) if matches!(bytes[at], b'"' | b'\'') => {
+                let close = bytes[at + 1..].iter().position(|&b| b == bytes[at]).map_or(bytes.len(), |p| at + 1 + p);
+                spans.push((at + 1, close));
+                close + 1
+            }
+            Some(Region::Attribute(at)) | Some(Region::Call(at)) => expression(bytes, at + 1, &mut spans) + 1,
+            Some(Region::Template(at)) => template(bytes, at + 1, &mut spans) + 1,
+            None => end,
+        };
+        let tokens: Vec<ClassToken> = spans
+            .into_iter()
+            .flat_map(|(from, to)| tokens(content, &starts, from, to.min(bytes.len())))
+            .collect();
+        if !tokens.is_empty() {
+            lists.push(tokens);
+        }
+    }
+    lists
+}
+
+/// Where a class list starts after an identifier: the attribute value's
+/// first byte, a call's `(` or a template's backtick.
+enum Region {
+    Attribute(usize),
+    Call(usize),
+    Template(usize),
+}
+
+fn region(bytes: &[u8], name: &str, end: usize) -> Option<Region> {
+    let skip_spaces = |from: usize| from + bytes[from..].iter().take_while(|b| b.is_ascii_whitespace()).count();
+    let at = skip_spaces(end);
+    let next = bytes.get(at).copied();
+    if CLASS_ATTRIBUTES.contains(&name) && next == Some(b'=') && bytes.get(at + 1) != Some(&b'=') {
+        let value = skip_spaces(at + 1);
+        return matches!(bytes.get(value), Some(b'"' | b'\'' | b'{')).then_some(Region::Attribute(value));
+    }
+    if CLASS_FUNCTIONS.contains(&name) && next == Some(b'(') {
+        return Some(Region::Call(at));
+    }
+    if CLASS_TAGS.contains(&name) {
+        // `tw.div` tags the template after the element name
+        let at = if next == Some(b'.') {
+            skip_spaces(at + 1 + bytes[at + 1..].iter().take_while(|&&b| is_ident(b)).count())
+        } else {
+            at
+        };
+        return (bytes.get(at) == Some(&b'`')).then_some(Region::Template(at));
+    }
+    None
+}
+
+/// Collect the string literals of the expression starting at `from` into
+/// `spans`, up to the bracket closing it. Returns that bracket's offset.
+fn expression(bytes: &[u8], from: usize, spans: &mut Vec<(usize, usize)>) -> usize {
+    let mut depth = 0;
+    let mut i = from;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'(' | b'[' | b'{' => depth += 1,
+            b')' | b']' | b'}' if depth == 0 => return i,
+            b')' | b']' | b'}' => depth -= 1,
+            quote @ (b'"' | b'\'') => {
+                let close = string_end(bytes, i + 1, quote);
+                spans.push((i + 1, close));
+                i = close;
+            }
+            b'`' => i = template(bytes, i + 1, spans),
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                i += bytes[i..].iter().position(|&b| b == b'\n').unwrap_or(bytes.len() - i);
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                i = bytes[i + 2..].windows(2).position(|w| w == b"*/").map_or(bytes.len(), |p| i + 3 + p);
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    bytes.len()
+}
+
+/// Collect the static parts of the template literal whose contents start at
+/// `from`, and the strings in its `${...}`, into `spans`. Returns the offset
+/// of the closing backtick.
+fn template(bytes: &[u8], from: usize, spans: &mut Vec<(usize, usize)>) -> usize {
+    let mut part = from;
+    let mut i = from;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 1,
+            b'`' => {
+                spans.push((part, i));
+                return i;
+            }
+            b'$' if bytes.get(i + 1) == Some(&b'{') => {
+                spans.push((part, i));
+                i = expression(bytes, i + 2, spans);
+                part = i + 1;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    spans.push((part, bytes.len()));
+    bytes.len()
+}
+
+/// The offset of the quote closing a string whose contents start at `from`,
+/// or of the end of its line if it's unterminated.
+fn string_end(bytes: &[u8], from: usize, quote: u8) -> usize {
+    let mut i = from;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 1,
+            b'\n' => return i,
+            b if b == quote => return i,
+            _ => {}
+        }
+        i += 1;
+    }
+    bytes.len()
+}
+
+/// The whitespace-separated classes of `content[from..to]`.
+fn tokens<'a>(content: &'a str, starts: &[usize], from: usize, to: usize) -> Vec<ClassToken<'a>> {
+    let mut tokens = Vec::new();
+    let mut offset = from;
+    for text in content[from..to].split(|c: char| c.is_ascii_whitespace()) {
+        if !text.is_empty() {
+            let (line, column) = line_endings::position(starts, offset);
+            tokens.push(ClassToken {
+                text,
+                offset,
+                line,
+                column,
+            });
+        }
+        offset += text.len() + 1;
+    }
+    tokens
+}
+
+fn is_ident(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_' || b == b'$'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lists(content: &str) -> Vec<Vec<(&str, usize, usize)>> {
+        class_lists(content)
+            .into_iter()
+            .map(|list| list.into_iter().map(|t| (t.text, t.line, t.column)).collect())
+            .collect()
+    }
+
+    #[test]
+    fn finds_attributes_calls_and_tagged_templates() {
+        let content = r#"<div className="p-4 bg-white" class='m-2'>
+const a = clsx("flex", { "bg-red-500": error }, active ? 'text-black' : "");
+const button = cva(
+  "rounded", // base: not a class
+  { variants: { intent: { primary: `bg-blue-600 ${dark ? "dark:bg-blue-400" : ""} px-2` } } },
+);
+const Box = tw.div`text-gray-900`;
+const label = "bg-white"; if (className == "x") {}
+"#;
+        assert_eq!(
+            lists(content),
+            [
+                vec![("p-4", 1, 17), ("bg-white", 1, 21)],
+                vec![("m-2", 1, 38)],
+                vec![("flex", 2, 17), ("bg-red-500", 2, 27), ("text-black", 2, 59)],
+                vec![
+                    ("rounded", 4, 4),
+                    ("bg-blue-600", 5, 37),
+                    ("dark:bg-blue-400", 5, 59),
+                    ("px-2", 5, 83),
+                ],
+                vec![("text-gray-900", 7, 20)],
+            ]
+        );
+    }
+
+    #[test]
+    fn attribute_expressions_and_multiline_strings() {
+        let content = "<p\n  className={cn(\n    \"text-sm\",\n    `bg-${tone}-50\n     border`,\n  )}\n/>";
+        assert_eq!(
+            lists(content),
+            [vec![("text-sm", 3, 6), ("bg-", 4, 6), ("-50", 4, 16), ("border", 5, 6)]]
+        );
+        // An unterminated call ends the list at the end of the file
+        assert_eq!(lists("x = cn('a', \"b"), [vec![("a", 1, 9), ("b", 1, 14)]]);
+    }
+}
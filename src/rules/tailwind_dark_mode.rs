@@ -1,5 +1,7 @@
 use crate::config::{RuleConfig, Severity};
-use crate::rules::{Rule, RuleBuildError, ScanContext, Violation};
+use crate::line_endings;
+use crate::rules::tailwind_classes::{self, ClassToken};
+use crate::rules::{Rule, RuleBuildError, ScanContext, Violation, TAILWIND_FILE_TYPES};
 use regex::Regex;
 use std::collections::HashSet;
 
@@ -10,8 +12,9 @@ use std::collections::HashSet;
 /// classes like `bg-background` and `text-foreground` are allowed by default
 /// because they resolve via CSS variables that already handle both themes.
 ///
-/// This rule scans JSX/TSX/HTML files for class attributes and analyzes the
-/// Tailwind classes within them.
+/// This rule scans JSX/TSX/HTML files for class attributes, class function
+/// calls and tagged templates (see [`tailwind_classes::class_lists`]) and
+/// analyzes the Tailwind classes within them.
 pub struct TailwindDarkModeRule {
     id: String,
     severity: Severity,
@@ -20,14 +23,8 @@ pub struct TailwindDarkModeRule {
     glob: Option<String>,
     /// Classes that are exempt (don't need a dark: variant).
     allowed: HashSet<String>,
-    /// Regex to extract className/class attribute values.
-    class_attr_re: Regex,
     /// Regex to identify color utility classes.
     color_utility_re: Regex,
-    /// Regex to find cn/clsx/classNames/cva/twMerge function calls.
-    cn_fn_re: Regex,
-    /// Regex to extract quoted strings inside function calls.
-    cn_str_re: Regex,
 }
 
 /// The Tailwind color utility prefixes that are theme-sensitive.
@@ -93,13 +90,6 @@ impl TailwindDarkModeRule {
             allowed.insert(cls.clone());
         }
 
-        // Regex to find className="..." or class="..." (handles multi-line with `)
-        // Also matches className={cn("...", "...")} and className={clsx(...)}
-        // We capture the full attribute value to extract classes from.
-        let class_attr_re = Regex::new(
-            r#"(?:className|class)\s*=\s*(?:"([^"]*?)"|'([^']*?)'|\{[^}]*?(?:`([^`]*?)`|"([^"]*?)"|'([^']*?)'))"#,
-        ).map_err(|e| RuleBuildError::InvalidRegex(config.id.clone(), e))?;
-
         // Build regex that matches color utility classes.
         // Pattern: (bg|text|border|...)-{color}(-{shade})?
         // Examples: bg-white, text-gray-900, border-slate-200/50
@@ -116,54 +106,32 @@ impl TailwindDarkModeRule {
         let color_utility_re = Regex::new(&color_re_str)
             .map_err(|e| RuleBuildError::InvalidRegex(config.id.clone(), e))?;
 
-        let cn_fn_re = Regex::new(r#"(?:cn|clsx|classNames|cva|twMerge)\s*\("#)
-            .map_err(|e| RuleBuildError::InvalidRegex(config.id.clone(), e))?;
-        let cn_str_re = Regex::new(r#"['"`]([^'"`]+?)['"`]"#)
-            .map_err(|e| RuleBuildError::InvalidRegex(config.id.clone(), e))?;
-
-        let default_glob = "**/*.{tsx,jsx,html}".to_string();
 
         Ok(Self {
             id: config.id.clone(),
             severity: config.severity,
             message: config.message.clone(),
             suggest: config.suggest.clone(),
-            glob: config.glob.clone().or(Some(default_glob)),
+            glob: config.glob.clone(),
             allowed,
-            class_attr_re,
             color_utility_re,
-            cn_fn_re,
-            cn_str_re,
         })
     }
 
-    /// Extract all class strings from a line of source code.
-    fn extract_class_strings<'a>(&self, line: &'a str) -> Vec<&'a str> {
-        let mut results = Vec::new();
-        for cap in self.class_attr_re.captures_iter(line) {
-            // Try each capture group (different quote styles)
-            for i in 1..=5 {
-                if let Some(m) = cap.get(i) {
-                    results.push(m.as_str());
-                }
-            }
-        }
-        results
-    }
-
     /// Check if a color utility class has a corresponding `dark:` variant in the same class list.
-    fn find_missing_dark_variants(&self, class_string: &str) -> Vec<(String, Option<String>)> {
-        let classes: Vec<&str> = class_string.split_whitespace().collect();
-
+    fn find_missing_dark_variants<'t, 'a>(
+        &self,
+        classes: &'t [ClassToken<'a>],
+    ) -> Vec<(&'t ClassToken<'a>, Option<String>)> {
         // Collect all dark: prefixed classes
-        let dark_classes: HashSet<String> = classes.iter()
-            .filter(|c| c.starts_with("dark:"))
-            .map(|c| c.strip_prefix("dark:").unwrap().to_string())
+        let dark_classes: HashSet<&str> = classes.iter()
+            .filter_map(|c| c.text.strip_prefix("dark:"))
             .collect();
 
         let mut violations = Vec::new();
 
-        for class in &classes {
+        for token in classes {
+            let class = &token.text;
             // Skip dark: prefixed classes themselves
             if class.starts_with("dark:") || class.starts_with("hover:") || class.starts_with("focus:") {
                 continue;
@@ -186,7 +154,7 @@ impl TailwindDarkModeRule {
 
             if !has_dark {
                 let suggestion = suggest_semantic_token(class);
-                violations.push((class.to_string(), suggestion));
+                violations.push((token, suggestion));
             }
         }
 
@@ -247,48 +215,43 @@ impl Rule for TailwindDarkModeRule {
     }
 
     fn check_file(&self, ctx: &ScanContext) -> Vec<Violation> {
+        if self.glob.is_none() && !ctx.file_type.is_some_and(|t| TAILWIND_FILE_TYPES.contains(&t)) {
+            return Vec::new();
+        }
         let mut violations = Vec::new();
-
-        for (line_num, line) in ctx.content.lines().enumerate() {
-            let class_strings = self.extract_class_strings(line);
-
-            // Also check for multi-word strings that look like class lists in
-            // cn(), clsx(), classNames() calls — common in shadcn projects.
-            // We do a broader scan for quoted strings inside these function calls.
-            let extra_strings = self.extract_cn_strings(line);
-
-            for class_str in class_strings.iter().copied().chain(extra_strings.iter().map(|s| s.as_str())) {
-                let missing = self.find_missing_dark_variants(class_str);
-
-                for (class, token_suggestion) in missing {
-                    let msg = if self.message.is_empty() {
-                        format!(
-                            "Class '{}' sets a color without a dark: variant",
-                            class
-                        )
-                    } else {
-                        format!("{}: '{}'", self.message, class)
-                    };
-
-                    let suggest = token_suggestion
-                        .or_else(|| self.suggest.clone())
-                        .or_else(|| Some(format!(
-                            "Add 'dark:{}' or replace with a semantic token class",
-                            suggest_dark_counterpart(&class)
-                        )));
-
-                    violations.push(Violation {
-                        rule_id: self.id.clone(),
-                        severity: self.severity,
-                        file: ctx.file_path.to_path_buf(),
-                        line: Some(line_num + 1),
-                        column: line.find(&class).map(|c| c + 1),
-                        message: msg,
-                        suggest,
-                        source_line: Some(line.to_string()),
-                        fix: None,
-                    });
-                }
+        let lines: Vec<&str> = line_endings::lines(ctx.content).collect();
+
+        for classes in tailwind_classes::class_lists(ctx.content) {
+            for (token, token_suggestion) in self.find_missing_dark_variants(&classes) {
+                let class = token.text;
+                let msg = if self.message.is_empty() {
+                    format!(
+                        "Class '{}' sets a color without a dark: variant",
+                        class
+                    )
+                } else {
+                    format!("{}: '{}'", self.message, class)
+                };
+
+                let suggest = token_suggestion
+                    .or_else(|| self.suggest.clone())
+                    .or_else(|| Some(format!(
+                        "Add 'dark:{}' or replace with a semantic token class",
+                        suggest_dark_counterpart(class)
+                    )));
+
+                violations.push(Violation {
+                    rule_id: self.id.clone(),
+                    severity: self.severity,
+                    file: ctx.file_path.to_path_buf(),
+                    line: Some(token.line),
+                    column: Some(token.column),
+                    message: msg,
+                    suggest,
+                    source_line: Some(lines[token.line - 1].to_string()),
+                    fix: None,
+                    url: None,
+                });
             }
         }
 
@@ -296,28 +259,6 @@ impl Rule for TailwindDarkModeRule {
     }
 }
 
-impl TailwindDarkModeRule {
-    /// Extract string arguments from cn(), clsx(), classNames() calls.
-    fn extract_cn_strings(&self, line: &str) -> Vec<String> {
-        let mut results = Vec::new();
-
-        if let Some(fn_match) = self.cn_fn_re.find(line) {
-            let remainder = &line[fn_match.end()..];
-            for cap in self.cn_str_re.captures_iter(remainder) {
-                if let Some(m) = cap.get(1) {
-                    let s = m.as_str();
-                    // Only include if it looks like Tailwind classes (has spaces or dashes)
-                    if s.contains('-') || s.contains(' ') {
-                        results.push(s.to_string());
-                    }
-                }
-            }
-        }
-
-        results
-    }
-}
-
 /// Suggest a dark mode counterpart for a color class.
 fn suggest_dark_counterpart(class: &str) -> String {
     let parts: Vec<&str> = class.splitn(2, '-').collect();
@@ -354,6 +295,7 @@ fn suggest_dark_counterpart(class: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::path_class::PathClass;
     use crate::config::{RuleConfig, Severity};
     use crate::rules::{Rule, ScanContext};
     use std::path::Path;
@@ -372,6 +314,9 @@ mod tests {
         let ctx = ScanContext {
             file_path: Path::new("test.tsx"),
             content,
+            class: PathClass::FirstParty,
+            file_type: Some("tsx"),
+            changed_ranges: None,
         };
         rule.check_file(&ctx)
     }
@@ -489,6 +434,17 @@ mod tests {
         assert!(violations.is_empty(), "semantic tokens inside cn() should pass");
     }
 
+    #[test]
+    fn multiline_calls_and_templates_report_each_token() {
+        let rule = make_rule();
+        let content = "const card = clsx(\n  \"p-4\",\n  active ? \"bg-white\" : `text-gray-900 ${muted ? 'dark:text-white' : ''}`,\n);\nconst Box = tw.div`border-slate-200`;\n";
+        let violations = check(&rule, content);
+        let found: Vec<_> = violations.iter().map(|v| (v.line, v.column)).collect();
+        // text-gray-900 has its dark: counterpart in the other branch
+        assert_eq!(found, [(Some(3), Some(13)), (Some(5), Some(20))]);
+        assert_eq!(violations[0].source_line.as_deref(), Some("  active ? \"bg-white\" : `text-gray-900 ${muted ? 'dark:text-white' : ''}`,"));
+    }
+
     // ── transparent / current always allowed ──
 
     #[test]
@@ -1,6 +1,7 @@
 use crate::config::{RuleConfig, Severity};
-use crate::rules::{Rule, RuleBuildError, ScanContext, Violation};
-use regex::Regex;
+use crate::line_endings;
+use crate::rules::tailwind_classes;
+use crate::rules::{Rule, RuleBuildError, ScanContext, Violation, TAILWIND_FILE_TYPES};
 use std::collections::HashMap;
 
 /// Enforces usage of shadcn/ui semantic token classes instead of raw Tailwind
@@ -21,10 +22,6 @@ pub struct TailwindThemeTokensRule {
     glob: Option<String>,
     /// Map from banned raw class → suggested semantic token class.
     token_map: HashMap<String, String>,
-    /// Regex to find color utility classes in source.
-    color_re: Regex,
-    /// Regex to extract class attribute values.
-    class_context_re: Regex,
 }
 
 /// Default mapping of raw Tailwind color classes → shadcn semantic tokens.
@@ -147,33 +144,14 @@ impl TailwindThemeTokensRule {
             token_map.remove(cls);
         }
 
-        // Build regex to detect any Tailwind color utility
-        let color_re = Regex::new(
-            r"\b(bg|text|border|ring|outline|shadow|divide|accent|caret|fill|stroke|decoration|placeholder|from|via|to)-(white|black|slate|gray|zinc|neutral|stone|red|orange|amber|yellow|lime|green|emerald|teal|cyan|sky|blue|indigo|violet|purple|fuchsia|pink|rose)(?:-(\d{2,3}))?(?:/\d+)?\b"
-        ).map_err(|e| RuleBuildError::InvalidRegex(config.id.clone(), e))?;
-
-        let class_context_re = Regex::new(
-            r#"(?:className|class)\s*=|(?:cn|clsx|classNames|cva|twMerge)\s*\("#,
-        ).map_err(|e| RuleBuildError::InvalidRegex(config.id.clone(), e))?;
-
-        let default_glob = "**/*.{tsx,jsx,html}".to_string();
-
         Ok(Self {
             id: config.id.clone(),
             severity: config.severity,
             message: config.message.clone(),
-            glob: config.glob.clone().or(Some(default_glob)),
+            glob: config.glob.clone(),
             token_map,
-            color_re,
-            class_context_re,
         })
     }
-
-    /// Check if a line appears to contain Tailwind classes
-    /// (within className, class, cn(), clsx(), etc.)
-    fn line_has_class_context(&self, line: &str) -> bool {
-        self.class_context_re.is_match(line)
-    }
 }
 
 impl Rule for TailwindThemeTokensRule {
@@ -190,53 +168,51 @@ impl Rule for TailwindThemeTokensRule {
     }
 
     fn check_file(&self, ctx: &ScanContext) -> Vec<Violation> {
+        if self.glob.is_none() && !ctx.file_type.is_some_and(|t| TAILWIND_FILE_TYPES.contains(&t)) {
+            return Vec::new();
+        }
         let mut violations = Vec::new();
 
-        for (line_num, line) in ctx.content.lines().enumerate() {
-            // Only check lines that are plausibly setting CSS classes
-            if !self.line_has_class_context(line) {
+        let lines: Vec<&str> = line_endings::lines(ctx.content).collect();
+
+        // Only classes in class attributes, cn()/clsx()/cva() calls and tagged templates
+        for token in tailwind_classes::class_lists(ctx.content).into_iter().flatten() {
+            // The utility after its variants (`hover:`, `md:`, ...)
+            let (variants, full_match) = token.text.rsplit_once(':').unwrap_or(("", token.text));
+            let start = token.text.len() - full_match.len();
+
+            // Skip classes that use dark: prefix — those are intentional overrides
+            if variants.split(':').any(|v| v == "dark") {
                 continue;
             }
 
-            // Find all color utility classes on this line
-            for cap in self.color_re.captures_iter(line) {
-                let full_match = cap.get(0).unwrap().as_str();
-
-                // Skip classes that use dark: prefix — those are intentional overrides
-                let match_start = cap.get(0).unwrap().start();
-                if match_start >= 5 {
-                    let prefix = &line[match_start.saturating_sub(5)..match_start];
-                    if prefix.ends_with("dark:") {
-                        continue;
-                    }
-                }
-
-                // Check if this raw class is in our ban map
-                if let Some(replacement) = self.token_map.get(full_match) {
-                    let msg = if self.message.is_empty() {
-                        format!(
-                            "Raw color class '{}' — use semantic token '{}' for theme support",
-                            full_match, replacement
-                        )
-                    } else {
-                        format!("{}: '{}' → '{}'", self.message, full_match, replacement)
-                    };
-
-                    violations.push(Violation {
-                        rule_id: self.id.clone(),
-                        severity: self.severity,
-                        file: ctx.file_path.to_path_buf(),
-                        line: Some(line_num + 1),
-                        column: Some(cap.get(0).unwrap().start() + 1),
-                        message: msg,
-                        suggest: Some(format!("Replace '{}' with '{}'", full_match, replacement)),
-                        source_line: Some(line.to_string()),
-                        fix: Some(crate::rules::Fix {
-                            old: full_match.to_string(),
-                            new: replacement.clone(),
-                        }),
-                    });
-                }
+            // Check if this raw class is in our ban map
+            if let Some(replacement) = self.token_map.get(full_match) {
+                let msg = if self.message.is_empty() {
+                    format!(
+                        "Raw color class '{}' — use semantic token '{}' for theme support",
+                        full_match, replacement
+                    )
+                } else {
+                    format!("{}: '{}' → '{}'", self.message, full_match, replacement)
+                };
+
+                violations.push(Violation {
+                    rule_id: self.id.clone(),
+                    severity: self.severity,
+                    file: ctx.file_path.to_path_buf(),
+                    line: Some(token.line),
+                    column: Some(token.column + start),
+                    message: msg,
+                    suggest: Some(format!("Replace '{}' with '{}'", full_match, replacement)),
+                    source_line: Some(lines[token.line - 1].to_string()),
+                    fix: Some(crate::rules::Fix {
+                        old: full_match.to_string(),
+                        new: replacement.clone(),
+                        range: Some(token.offset + start..token.offset + token.text.len()),
+                    }),
+                    url: None,
+                });
             }
         }
 
@@ -247,6 +223,7 @@ impl Rule for TailwindThemeTokensRule {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::path_class::PathClass;
     use crate::config::{RuleConfig, Severity};
     use crate::rules::{Rule, ScanContext};
     use std::path::Path;
@@ -265,6 +242,9 @@ mod tests {
         let ctx = ScanContext {
             file_path: Path::new("test.tsx"),
             content,
+            class: PathClass::FirstParty,
+            file_type: Some("tsx"),
+            changed_ranges: None,
         };
         rule.check_file(&ctx)
     }
@@ -404,6 +384,18 @@ mod tests {
         assert!(!violations.is_empty(), "raw colors inside cn() should be flagged");
     }
 
+    #[test]
+    fn cva_variants_report_the_utility_column() {
+        let rule = make_rule();
+        let content = "const button = cva(\"rounded\", {\n  variants: { ghost: \"hover:bg-white dark:bg-slate-900\" },\n});\n";
+        let violations = check(&rule, content);
+        assert_eq!(violations.len(), 1);
+        assert_eq!((violations[0].line, violations[0].column), (Some(2), Some(29)));
+        let fix = violations[0].fix.as_ref().unwrap();
+        assert_eq!(fix.old, "bg-white");
+        assert_eq!(&content[fix.range.clone().unwrap()], "bg-white");
+    }
+
     // ── Custom token_map overrides ──
 
     #[test]
@@ -0,0 +1,285 @@
+use crate::config::{RuleConfig, Severity};
+use crate::rules::{regex_pool, Rule, RuleBuildError, ScanContext, Violation};
+use regex::Regex;
+use std::sync::Arc;
+use std::collections::HashMap;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Markers matched when the rule sets no `pattern`.
+const DEFAULT_MARKERS: [&str; 2] = ["TODO", "FIXME"];
+
+/// Age after which a marker becomes an error when `max_age_days` is unset.
+const DEFAULT_MAX_AGE_DAYS: u64 = 90;
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// Reports TODO/FIXME comments with their age from `git blame`: a warning
+/// while they are young, an error once they are older than `max_age_days`.
+///
+/// The scanned content is blamed (`git blame --contents -`), so lines that
+/// aren't committed yet count as new. Outside a git repository every
+/// marker stays a warning.
+///
+/// Config fields:
+/// - `pattern` — marker to look for (default: `TODO` and `FIXME` as words)
+/// - `regex` — whether `pattern` is a regex
+/// - `max_age_days` — age in days at which a marker becomes an error (default: 90)
+#[derive(Debug)]
+pub struct TodoAgeRule {
+    id: String,
+    message: String,
+    suggest: Option<String>,
+    glob: Option<String>,
+    marker_re: Arc<Regex>,
+    literals: Option<Vec<String>>,
+    max_age_days: u64,
+}
+
+impl TodoAgeRule {
+    pub fn new(config: &RuleConfig) -> Result<Self, RuleBuildError> {
+        let (marker_re, literals) = match config.pattern.as_deref().filter(|p| !p.is_empty()) {
+            Some(pattern) if config.regex => (
+                regex_pool::compile(config, pattern)?,
+                None,
+            ),
+            Some(pattern) => (
+                regex_pool::compile(config, &regex::escape(pattern))?,
+                Some(vec![pattern.to_string()]),
+            ),
+            None => (
+                regex_pool::compile(config, &format!(r"\b(?:{})\b", DEFAULT_MARKERS.join("|")))?,
+                Some(DEFAULT_MARKERS.iter().map(|m| m.to_string()).collect()),
+            ),
+        };
+
+        let message = if config.message.is_empty() {
+            "resolve or remove this TODO".to_string()
+        } else {
+            config.message.clone()
+        };
+
+        Ok(Self {
+            id: config.id.clone(),
+            message,
+            suggest: config.suggest.clone(),
+            glob: config.glob.clone(),
+            marker_re,
+            literals,
+            max_age_days: config.max_age_days.unwrap_or(DEFAULT_MAX_AGE_DAYS),
+        })
+    }
+
+    fn violation(&self, ctx: &ScanContext, line: usize, column: usize, source: &str, age_days: Option<i64>) -> Violation {
+        let overdue = age_days.is_some_and(|days| days >= self.max_age_days as i64);
+        let message = match age_days {
+            Some(days) => format!(
+                "{} ({} day{} old{})",
+                self.message,
+                days,
+                if days == 1 { "" } else { "s" },
+                if overdue { format!(", limit {}", self.max_age_days) } else { String::new() }
+            ),
+            None => self.message.clone(),
+        };
+        Violation {
+            rule_id: self.id.clone(),
+            severity: if overdue { Severity::Error } else { Severity::Warning },
+            file: ctx.file_path.to_path_buf(),
+            line: Some(line),
+            column: Some(column),
+            message,
+            suggest: self.suggest.clone(),
+            source_line: Some(source.to_string()),
+            fix: None,
+            url: None,
+        }
+    }
+}
+
+impl Rule for TodoAgeRule {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Markers start as warnings; overdue ones are reported as errors.
+    fn severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn file_glob(&self) -> Option<&str> {
+        self.glob.as_deref()
+    }
+
+    /// Ages depend on git blame and today's date.
+    fn cacheable(&self) -> bool {
+        false
+    }
+
+    fn prefilter(&self) -> Option<&[String]> {
+        self.literals.as_deref()
+    }
+
+    fn check_file(&self, ctx: &ScanContext) -> Vec<Violation> {
+        let matches: Vec<(usize, usize, &str)> = ctx
+            .content
+            .lines()
+            .enumerate()
+            .filter_map(|(idx, line)| {
+                let m = self.marker_re.find(line)?;
+                Some((idx + 1, m.start() + 1, line))
+            })
+            .collect();
+        if matches.is_empty() {
+            return Vec::new();
+        }
+
+        let lines: Vec<usize> = matches.iter().map(|&(line, _, _)| line).collect();
+        let times = blame_times(ctx, &lines);
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0);
+
+        matches
+            .into_iter()
+            .map(|(line, column, source)| {
+                let age_days = times.get(&line).map(|&t| (now - t).max(0) / SECONDS_PER_DAY);
+                self.violation(ctx, line, column, source, age_days)
+            })
+            .collect()
+    }
+}
+
+/// Author time of each of `lines` in the scanned content, by line number.
+/// Empty when git or the repository isn't available.
+fn blame_times(ctx: &ScanContext, lines: &[usize]) -> HashMap<usize, i64> {
+    let dir = ctx
+        .file_path
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or(std::path::Path::new("."));
+    let Some(file_name) = ctx.file_path.file_name() else {
+        return HashMap::new();
+    };
+
+    let mut command = Command::new("git");
+    command
+        .arg("-C")
+        .arg(dir)
+        .args(["blame", "--line-porcelain", "--contents", "-"]);
+    for line in lines {
+        command.arg(format!("-L{},{}", line, line));
+    }
+    let child = command
+        .arg("--")
+        .arg(file_name)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn();
+    let Ok(mut child) = child else {
+        return HashMap::new();
+    };
+    if let Some(mut stdin) = child.stdin.take() {
+        let _ = stdin.write_all(ctx.content.as_bytes());
+    }
+    match child.wait_with_output() {
+        Ok(output) if output.status.success() => parse_line_porcelain(&String::from_utf8_lossy(&output.stdout)),
+        _ => HashMap::new(),
+    }
+}
+
+/// Map final line numbers to `author-time` in `git blame --line-porcelain` output.
+fn parse_line_porcelain(output: &str) -> HashMap<usize, i64> {
+    let mut times = HashMap::new();
+    let mut current: Option<usize> = None;
+    for line in output.lines() {
+        if line.starts_with('\t') {
+            current = None;
+            continue;
+        }
+        if current.is_none() {
+            // Header: <sha> <orig line> <final line> [<group size>]
+            let mut parts = line.split(' ');
+            let sha = parts.next().unwrap_or_default();
+            if sha.len() >= 40 && sha.bytes().all(|b| b.is_ascii_hexdigit()) {
+                current = parts.nth(1).and_then(|n| n.parse().ok());
+            }
+            continue;
+        }
+        if let (Some(line_no), Some(time)) = (current, line.strip_prefix("author-time ")) {
+            if let Ok(time) = time.parse() {
+                times.insert(line_no, time);
+            }
+        }
+    }
+    times
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path_class::PathClass;
+    use std::path::Path;
+
+    fn make_rule(max_age_days: Option<u64>) -> TodoAgeRule {
+        TodoAgeRule::new(&RuleConfig {
+            id: "todo-age".into(),
+            max_age_days,
+            ..Default::default()
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn parses_author_times_per_final_line() {
+        let output = "\
+4f2a1c9e8d7b6a5f4e3d2c1b0a9f8e7d6c5b4a39 3 7 1
+author Ada
+author-time 1700000000
+author-tz +0000
+filename src/a.ts
+\t// TODO: remove
+0000000000000000000000000000000000000000 9 12 1
+author Not Committed Yet
+author-time 1800000000
+filename src/a.ts
+\t// FIXME
+";
+        let times = parse_line_porcelain(output);
+        assert_eq!(times.get(&7), Some(&1_700_000_000));
+        assert_eq!(times.get(&12), Some(&1_800_000_000));
+        assert_eq!(times.len(), 2);
+    }
+
+    #[test]
+    fn escalates_markers_older_than_the_limit() {
+        let rule = make_rule(Some(30));
+        let ctx = ScanContext {
+            file_path: Path::new("a.ts"),
+            content: "// TODO: x\n",
+            class: PathClass::FirstParty,
+            file_type: None,
+            changed_ranges: None,
+        };
+        let young = rule.violation(&ctx, 1, 4, "// TODO: x", Some(3));
+        assert_eq!(young.severity, Severity::Warning);
+        assert_eq!(young.message, "resolve or remove this TODO (3 days old)");
+
+        let old = rule.violation(&ctx, 1, 4, "// TODO: x", Some(45));
+        assert_eq!(old.severity, Severity::Error);
+        assert_eq!(old.message, "resolve or remove this TODO (45 days old, limit 30)");
+
+        assert_eq!(rule.violation(&ctx, 1, 4, "// TODO: x", None).severity, Severity::Warning);
+    }
+
+    #[test]
+    fn default_markers_match_whole_words() {
+        let rule = make_rule(None);
+        assert!(rule.marker_re.is_match("// TODO: later"));
+        assert!(rule.marker_re.is_match("# FIXME"));
+        assert!(!rule.marker_re.is_match("let todoList = TODOS;"));
+        assert_eq!(rule.prefilter().unwrap(), ["TODO", "FIXME"]);
+    }
+}
@@ -0,0 +1,302 @@
+use crate::config::{RuleConfig, Severity};
+use crate::line_endings;
+use crate::rules::{Rule, RuleBuildError, ScanContext, Violation};
+use wasmtime::{Caller, Config, Engine, Extern, InstancePre, Linker, Memory, Module, Store, Trap};
+
+/// Instructions' worth of fuel a module may burn on one file before it's
+/// stopped, so a looping module can't hang the scan.
+const FUEL_PER_FILE: u64 = 1_000_000_000;
+
+/// Host functions modules import, from the `guardrails` namespace.
+const HOST_MODULE: &str = "guardrails";
+
+/// Runs a custom rule compiled to WebAssembly (or written as `.wat`).
+///
+/// The module exports its `memory` and a `check` function taking and
+/// returning nothing, which is called once per matching file. From the
+/// `guardrails` namespace it may import:
+///
+/// - `path_len() -> i32` and `read_path(ptr: i32)`: the file's path, copied
+///   into memory at `ptr`
+/// - `content_len() -> i32` and `read_content(ptr: i32)`: the file's
+///   contents, likewise
+/// - `emit(line: i32, column: i32, message_ptr: i32, message_len: i32)`:
+///   report a violation (1-based `line` and `column`; `0` for none, and an
+///   empty message for the rule's `message`)
+///
+/// Modules get nothing else: no filesystem, network or clock. A trap, or
+/// running out of fuel, is reported per `on_failure`. Built only with the
+/// `wasm` feature.
+pub struct WasmRule {
+    id: String,
+    severity: Severity,
+    message: String,
+    suggest: Option<String>,
+    glob: Option<String>,
+    module_path: String,
+    engine: Engine,
+    instance: InstancePre<FileState>,
+    /// Severity of the violation reporting a trap; `None` ignores traps.
+    on_failure: Option<Severity>,
+}
+
+/// What a module sees of the file it's checking, and what it reported.
+struct FileState {
+    path: String,
+    content: String,
+    emitted: Vec<(usize, usize, String)>,
+}
+
+impl WasmRule {
+    pub fn new(config: &RuleConfig) -> Result<Self, RuleBuildError> {
+        let module_path = config
+            .module
+            .clone()
+            .ok_or_else(|| RuleBuildError::MissingField(config.id.clone(), "module"))?;
+        let on_failure = match config.on_failure.as_deref() {
+            None | Some("error") => Some(Severity::Error),
+            Some("warning") => Some(Severity::Warning),
+            Some("ignore") => None,
+            Some(other) => {
+                return Err(RuleBuildError::InvalidField(
+                    config.id.clone(),
+                    "on_failure",
+                    format!("expected \"error\", \"warning\" or \"ignore\", got \"{}\"", other),
+                ))
+            }
+        };
+        let invalid = |reason: String| RuleBuildError::InvalidField(config.id.clone(), "module", reason);
+
+        let mut engine_config = Config::new();
+        engine_config.consume_fuel(true);
+        let engine = Engine::new(&engine_config).map_err(|e| invalid(e.to_string()))?;
+        let module = Module::from_file(&engine, &module_path).map_err(|e| invalid(format!("{}: {}", module_path, e)))?;
+        if !module.exports().any(|export| export.name() == "check") {
+            return Err(invalid(format!("{} doesn't export a `check` function", module_path)));
+        }
+        let instance = host_functions(&engine)
+            .and_then(|linker| linker.instantiate_pre(&module))
+            .map_err(|e| invalid(format!("{}: {}", module_path, e)))?;
+
+        Ok(Self {
+            id: config.id.clone(),
+            severity: config.severity,
+            message: config.message.clone(),
+            suggest: config.suggest.clone(),
+            glob: config.glob.clone(),
+            module_path,
+            engine,
+            instance,
+            on_failure,
+        })
+    }
+
+    /// Run the module's `check` on one file, returning what it emitted.
+    fn run(&self, state: FileState) -> wasmtime::Result<Vec<(usize, usize, String)>> {
+        let mut store = Store::new(&self.engine, state);
+        store.set_fuel(FUEL_PER_FILE)?;
+        let instance = self.instance.instantiate(&mut store)?;
+        instance.get_typed_func::<(), ()>(&mut store, "check")?.call(&mut store, ())?;
+        Ok(store.into_data().emitted)
+    }
+}
+
+fn host_functions(engine: &Engine) -> wasmtime::Result<Linker<FileState>> {
+    let mut linker = Linker::new(engine);
+    linker.func_wrap(HOST_MODULE, "path_len", |caller: Caller<'_, FileState>| {
+        caller.data().path.len() as i32
+    })?;
+    linker.func_wrap(HOST_MODULE, "read_path", |mut caller: Caller<'_, FileState>, ptr: i32| {
+        copy_to_guest(&mut caller, ptr, |state| &state.path)
+    })?;
+    linker.func_wrap(HOST_MODULE, "content_len", |caller: Caller<'_, FileState>| {
+        caller.data().content.len() as i32
+    })?;
+    linker.func_wrap(HOST_MODULE, "read_content", |mut caller: Caller<'_, FileState>, ptr: i32| {
+        copy_to_guest(&mut caller, ptr, |state| &state.content)
+    })?;
+    linker.func_wrap(
+        HOST_MODULE,
+        "emit",
+        |mut caller: Caller<'_, FileState>, line: i32, column: i32, ptr: i32, len: i32| -> wasmtime::Result<()> {
+            let memory = guest_memory(&mut caller)?;
+            let start = ptr as u32 as usize;
+            let bytes = memory
+                .data(&caller)
+                .get(start..start + len as u32 as usize)
+                .ok_or_else(|| wasmtime::Error::msg("emit: message out of bounds"))?;
+            let message = String::from_utf8_lossy(bytes).into_owned();
+            caller
+                .data_mut()
+                .emitted
+                .push((line as u32 as usize, column as u32 as usize, message));
+            Ok(())
+        },
+    )?;
+    Ok(linker)
+}
+
+fn guest_memory(caller: &mut Caller<'_, FileState>) -> wasmtime::Result<Memory> {
+    match caller.get_export("memory") {
+        Some(Extern::Memory(memory)) => Ok(memory),
+        _ => Err(wasmtime::Error::msg("module doesn't export its `memory`")),
+    }
+}
+
+/// Copy the part of the file state `field` picks into guest memory at `ptr`.
+fn copy_to_guest(
+    caller: &mut Caller<'_, FileState>,
+    ptr: i32,
+    field: fn(&FileState) -> &String,
+) -> wasmtime::Result<()> {
+    let memory = guest_memory(caller)?;
+    let (data, state) = memory.data_and_store_mut(caller);
+    let bytes = field(state).as_bytes();
+    let start = ptr as u32 as usize;
+    data.get_mut(start..start + bytes.len())
+        .ok_or_else(|| wasmtime::Error::msg("buffer out of bounds"))?
+        .copy_from_slice(bytes);
+    Ok(())
+}
+
+impl Rule for WasmRule {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    fn file_glob(&self) -> Option<&str> {
+        self.glob.as_deref()
+    }
+
+    fn check_file(&self, ctx: &ScanContext) -> Vec<Violation> {
+        let state = FileState {
+            path: ctx.file_path.to_string_lossy().into_owned(),
+            content: ctx.content.to_string(),
+            emitted: Vec::new(),
+        };
+        match self.run(state) {
+            Ok(emitted) => emitted
+                .into_iter()
+                .map(|(line, column, message)| Violation {
+                    rule_id: self.id.clone(),
+                    severity: self.severity,
+                    file: ctx.file_path.to_path_buf(),
+                    line: (line > 0).then_some(line),
+                    column: (column > 0).then_some(column),
+                    message: if message.is_empty() { self.message.clone() } else { message },
+                    suggest: self.suggest.clone(),
+                    source_line: line
+                        .checked_sub(1)
+                        .and_then(|i| line_endings::lines(ctx.content).nth(i))
+                        .map(String::from),
+                    fix: None,
+                    url: None,
+                })
+                .collect(),
+            Err(e) => {
+                let Some(severity) = self.on_failure else {
+                    return Vec::new();
+                };
+                let reason = match e.downcast_ref::<Trap>() {
+                    Some(Trap::OutOfFuel) => "ran out of fuel".to_string(),
+                    _ => format!("trapped: {}", e.to_string().lines().next().unwrap_or_default()),
+                };
+                vec![Violation {
+                    rule_id: self.id.clone(),
+                    severity,
+                    file: ctx.file_path.to_path_buf(),
+                    line: None,
+                    column: None,
+                    message: format!("wasm module '{}' {}", self.module_path, reason),
+                    suggest: None,
+                    source_line: None,
+                    fix: None,
+                    url: None,
+                }]
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path_class::PathClass;
+    use std::fs;
+    use std::path::Path;
+
+    /// Flags the first line of any file whose contents start with `TODO`.
+    const TODO_AT_START: &str = r#"
+(module
+  (import "guardrails" "content_len" (func $content_len (result i32)))
+  (import "guardrails" "read_content" (func $read_content (param i32)))
+  (import "guardrails" "emit" (func $emit (param i32 i32 i32 i32)))
+  (memory (export "memory") 1)
+  (data (i32.const 0) "no TODO up top")
+  (func (export "check")
+    (if (i32.lt_u (call $content_len) (i32.const 4)) (then (return)))
+    (call $read_content (i32.const 1024))
+    (if (i32.eq (i32.load (i32.const 1024)) (i32.const 0x4f444f54))
+      (then (call $emit (i32.const 1) (i32.const 1) (i32.const 0) (i32.const 14))))))
+"#;
+
+    fn make_rule(dir: &Path, wat: &str) -> Result<WasmRule, RuleBuildError> {
+        let module = dir.join("rule.wat");
+        fs::write(&module, wat).unwrap();
+        let config = RuleConfig {
+            id: "wasm-rule".into(),
+            severity: Severity::Warning,
+            module: Some(module.display().to_string()),
+            ..Default::default()
+        };
+        WasmRule::new(&config)
+    }
+
+    fn check(rule: &WasmRule, content: &str) -> Vec<Violation> {
+        let ctx = ScanContext {
+            file_path: Path::new("src/a.ts"),
+            content,
+            class: PathClass::FirstParty,
+            file_type: Some("ts"),
+            changed_ranges: None,
+        };
+        rule.check_file(&ctx)
+    }
+
+    #[test]
+    fn modules_read_the_file_and_emit_violations() {
+        let dir = tempfile::tempdir().unwrap();
+        let rule = make_rule(dir.path(), TODO_AT_START).unwrap();
+
+        let violations = check(&rule, "TODO: write this\nlet x = 1;\n");
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].message, "no TODO up top");
+        assert_eq!((violations[0].line, violations[0].column), (Some(1), Some(1)));
+        assert_eq!(violations[0].source_line.as_deref(), Some("TODO: write this"));
+        assert!(check(&rule, "let x = 1; // TODO\n").is_empty());
+        assert!(check(&rule, "").is_empty());
+    }
+
+    #[test]
+    fn traps_and_runaway_modules_are_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        let rule = make_rule(dir.path(), r#"(module (func (export "check") unreachable))"#).unwrap();
+        let violations = check(&rule, "x");
+        assert_eq!(violations[0].severity, Severity::Error);
+        assert!(violations[0].message.contains("trapped"));
+
+        let rule = make_rule(dir.path(), r#"(module (func (export "check") (loop (br 0))))"#).unwrap();
+        assert!(check(&rule, "x")[0].message.ends_with("ran out of fuel"));
+
+        let err = make_rule(dir.path(), "(module)").err().unwrap();
+        assert!(err.to_string().contains("doesn't export a `check` function"));
+        let err = make_rule(dir.path(), r#"(module (import "env" "now" (func)) (func (export "check")))"#)
+            .err()
+            .unwrap();
+        assert!(matches!(err, RuleBuildError::InvalidField(_, "module", _)));
+    }
+}
@@ -1,6 +1,8 @@
 use crate::config::{RuleConfig, Severity};
-use crate::rules::{Rule, RuleBuildError, ScanContext, Violation};
+use crate::line_endings;
+use crate::rules::{regex_pool, Rule, RuleBuildError, ScanContext, Violation};
 use regex::Regex;
+use std::sync::Arc;
 
 /// Enforces that when a trigger pattern appears, a required pattern
 /// must also appear within a configurable window of lines.
@@ -22,9 +24,9 @@ pub struct WindowPatternRule {
     suggest: Option<String>,
     glob: Option<String>,
     trigger: String,
-    trigger_re: Option<Regex>,
+    trigger_re: Option<Arc<Regex>>,
     required: String,
-    required_re: Option<Regex>,
+    required_re: Option<Arc<Regex>>,
     window_size: usize,
 }
 
@@ -49,19 +51,13 @@ impl WindowPatternRule {
         let window_size = config.max_count.unwrap_or(10);
 
         let trigger_re = if config.regex {
-            Some(
-                Regex::new(&trigger)
-                    .map_err(|e| RuleBuildError::InvalidRegex(config.id.clone(), e))?,
-            )
+            Some(regex_pool::compile(config, &trigger)?)
         } else {
             None
         };
 
         let required_re = if config.regex {
-            Some(
-                Regex::new(&required)
-                    .map_err(|e| RuleBuildError::InvalidRegex(config.id.clone(), e))?,
-            )
+            Some(regex_pool::compile(config, &required)?)
         } else {
             None
         };
@@ -110,7 +106,7 @@ impl Rule for WindowPatternRule {
 
     fn check_file(&self, ctx: &ScanContext) -> Vec<Violation> {
         let mut violations = Vec::new();
-        let lines: Vec<&str> = ctx.content.lines().collect();
+        let lines: Vec<&str> = line_endings::lines(ctx.content).collect();
         let total = lines.len();
 
         for (idx, line) in lines.iter().enumerate() {
@@ -136,6 +132,7 @@ impl Rule for WindowPatternRule {
                     suggest: self.suggest.clone(),
                     source_line: Some(line.to_string()),
                     fix: None,
+                    url: None,
                 });
             }
         }
@@ -147,6 +144,7 @@ impl Rule for WindowPatternRule {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::path_class::PathClass;
     use std::path::Path;
 
     fn make_config(
@@ -172,6 +170,9 @@ mod tests {
         let ctx = ScanContext {
             file_path: Path::new("test.ts"),
             content,
+            class: PathClass::FirstParty,
+            file_type: None,
+            changed_ranges: None,
         };
         rule.check_file(&ctx)
     }
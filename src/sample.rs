@@ -0,0 +1,299 @@
+use crate::config::Severity;
+use crate::fingerprint;
+use crate::rules::Violation;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+/// How `--sample` picks files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SampleStrategy {
+    /// Uniformly random over all files.
+    Random,
+    /// Proportional to the number of files in each directory, so every
+    /// part of the tree is represented.
+    Stratified,
+}
+
+/// Options for scanning a subset of files.
+#[derive(Debug, Clone, Copy)]
+pub struct SampleOptions {
+    pub size: usize,
+    pub strategy: SampleStrategy,
+    pub seed: u64,
+}
+
+/// Extrapolated violation count for one rule.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RuleEstimate {
+    pub rule_id: String,
+    /// Violations seen in the sampled files.
+    pub observed: usize,
+    /// Estimated violations across all files.
+    pub estimate: usize,
+    /// Lower bound of the 95% confidence range.
+    pub low: usize,
+    /// Upper bound of the 95% confidence range.
+    pub high: usize,
+}
+
+/// What a sampled scan covered and what it extrapolates to.
+#[derive(Debug, Clone, Serialize)]
+pub struct SampleSummary {
+    pub sampled_files: usize,
+    pub total_files: usize,
+    /// Seed that reproduces this sample.
+    pub seed: u64,
+    pub estimates: Vec<RuleEstimate>,
+}
+
+/// Pick up to `options.size` files. Returns all files when the sample is not smaller.
+pub fn select(files: Vec<PathBuf>, options: &SampleOptions) -> Vec<PathBuf> {
+    if options.size >= files.len() {
+        return files;
+    }
+
+    let mut rng = SplitMix64(options.seed);
+    let mut picked = match options.strategy {
+        SampleStrategy::Random => {
+            let mut files = files;
+            rng.shuffle(&mut files);
+            files.truncate(options.size);
+            files
+        }
+        SampleStrategy::Stratified => stratified(files, options.size, &mut rng),
+    };
+    picked.sort();
+    picked
+}
+
+/// Proportional allocation per parent directory (largest remainder method).
+fn stratified(files: Vec<PathBuf>, size: usize, rng: &mut SplitMix64) -> Vec<PathBuf> {
+    let total = files.len();
+    let mut strata: BTreeMap<PathBuf, Vec<PathBuf>> = BTreeMap::new();
+    for f in files {
+        let dir = f.parent().map(Path::to_path_buf).unwrap_or_default();
+        strata.entry(dir).or_default().push(f);
+    }
+
+    let mut quotas: Vec<(usize, usize, PathBuf)> = strata
+        .iter()
+        .map(|(dir, members)| {
+            let exact = members.len() * size;
+            (exact / total, exact % total, dir.clone())
+        })
+        .collect();
+
+    // Hand out the seats lost to rounding down, largest remainder first
+    let assigned: usize = quotas.iter().map(|q| q.0).sum();
+    let mut order: Vec<usize> = (0..quotas.len()).collect();
+    order.sort_by(|&a, &b| quotas[b].1.cmp(&quotas[a].1).then(a.cmp(&b)));
+    for &i in order.iter().take(size - assigned) {
+        quotas[i].0 += 1;
+    }
+
+    let mut picked = Vec::with_capacity(size);
+    for (quota, _, dir) in quotas {
+        let mut members = strata.remove(&dir).unwrap_or_default();
+        rng.shuffle(&mut members);
+        members.truncate(quota);
+        picked.extend(members);
+    }
+    picked
+}
+
+/// Scale per-rule counts from `sampled` files up to `total` files.
+///
+/// The range is a normal approximation over per-file counts with a finite
+/// population correction, clamped below at the observed count.
+pub fn estimate(violations: &[Violation], sampled: usize, total: usize) -> Vec<RuleEstimate> {
+    if sampled == 0 {
+        return Vec::new();
+    }
+
+    let mut per_file: HashMap<(&str, &Path), usize> = HashMap::new();
+    for v in violations {
+        *per_file.entry((v.rule_id.as_str(), v.file.as_path())).or_insert(0) += 1;
+    }
+
+    // rule -> (sum, sum of squares) over sampled files; files without hits add 0
+    let mut sums: BTreeMap<&str, (f64, f64)> = BTreeMap::new();
+    for ((rule_id, _), count) in per_file {
+        let x = count as f64;
+        let entry = sums.entry(rule_id).or_insert((0.0, 0.0));
+        entry.0 += x;
+        entry.1 += x * x;
+    }
+
+    let n = sampled as f64;
+    let big_n = total as f64;
+    sums.into_iter()
+        .map(|(rule_id, (sum, sum_sq))| {
+            let mean = sum / n;
+            let variance = if sampled > 1 {
+                ((sum_sq - n * mean * mean) / (n - 1.0)).max(0.0)
+            } else {
+                0.0
+            };
+            let fpc = (1.0 - n / big_n).max(0.0);
+            let margin = 1.96 * big_n * (variance / n * fpc).sqrt();
+            let point = mean * big_n;
+
+            RuleEstimate {
+                rule_id: rule_id.to_string(),
+                observed: sum as usize,
+                estimate: point.round() as usize,
+                low: ((point - margin).max(sum)).round() as usize,
+                high: (point + margin).round() as usize,
+            }
+        })
+        .collect()
+}
+
+/// Seconds in the week an `--audit-sample` stays the same for.
+const AUDIT_PERIOD_SECS: u64 = 7 * 24 * 60 * 60;
+
+/// Seed for `--audit-sample` at `now` (Unix seconds): the number of the
+/// week, so every run in a week shows the same violations.
+pub fn audit_seed(now: u64) -> u64 {
+    now / AUDIT_PERIOD_SECS
+}
+
+/// Up to `per_rule` of each rule's `hidden` violations, chosen at random
+/// but the same for the same `seed` whatever order they were found in, as
+/// warnings saying what hid them (`hidden_by`). Sorted by file and line.
+pub fn audit(hidden: Vec<(Violation, &str)>, per_rule: usize, seed: u64) -> Vec<Violation> {
+    let (violations, reasons): (Vec<Violation>, Vec<&str>) = hidden.into_iter().unzip();
+    let fingerprints = fingerprint::fingerprints(&violations);
+    let mut by_rule: BTreeMap<String, Vec<(String, Violation, &str)>> = BTreeMap::new();
+    for ((v, hidden_by), f) in violations.into_iter().zip(reasons).zip(fingerprints) {
+        by_rule.entry(v.rule_id.clone()).or_default().push((f, v, hidden_by));
+    }
+    let mut rng = SplitMix64(seed);
+    let mut picked = Vec::new();
+    for mut group in by_rule.into_values() {
+        group.sort_by(|a, b| a.0.cmp(&b.0));
+        rng.shuffle(&mut group);
+        picked.extend(group.into_iter().take(per_rule).map(|(_, mut v, hidden_by)| {
+            v.severity = Severity::Warning;
+            v.message = format!("{} (audit sample: {})", v.message, hidden_by);
+            v
+        }));
+    }
+    picked.sort_by(|a, b| (&a.file, a.line, a.column).cmp(&(&b.file, b.line, b.column)));
+    picked
+}
+
+/// Small deterministic PRNG so samples are reproducible with `--seed`.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Fisher–Yates shuffle.
+    fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = (self.next() % (i as u64 + 1)) as usize;
+            items.swap(i, j);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Severity;
+
+    fn files(spec: &[(&str, usize)]) -> Vec<PathBuf> {
+        spec.iter()
+            .flat_map(|(dir, n)| (0..*n).map(move |i| PathBuf::from(format!("{}/f{}.ts", dir, i))))
+            .collect()
+    }
+
+    #[test]
+    fn audit_picks_a_stable_sample_per_rule() {
+        let hidden: Vec<(Violation, &str)> = (0..10)
+            .map(|i| (Violation::test("a", &format!("src/{}.ts", i)), "in the baseline"))
+            .chain([(Violation::test("b", "src/x.ts"), "suppressed inline")])
+            .collect();
+        let mut reversed = hidden.clone();
+        reversed.reverse();
+
+        let picked = audit(hidden.clone(), 3, 2900);
+        assert_eq!(picked.iter().filter(|v| v.rule_id == "a").count(), 3);
+        assert_eq!(picked.iter().filter(|v| v.rule_id == "b").count(), 1);
+        assert!(picked.iter().all(|v| v.severity == Severity::Warning));
+        assert!(picked.iter().any(|v| v.message == "m (audit sample: suppressed inline)"));
+        // Same week, same sample, whatever order the violations came in
+        let files = |picked: &[Violation]| picked.iter().map(|v| v.file.clone()).collect::<Vec<_>>();
+        assert_eq!(files(&audit(reversed, 3, 2900)), files(&picked));
+        assert!((2901..2910).any(|week| files(&audit(hidden.clone(), 3, week)) != files(&picked)));
+    }
+
+    #[test]
+    fn audit_seed_changes_weekly() {
+        let week = 7 * 24 * 60 * 60;
+        assert_eq!(audit_seed(10 * week), audit_seed(10 * week + week - 1));
+        assert_ne!(audit_seed(10 * week), audit_seed(11 * week));
+    }
+
+    #[test]
+    fn random_sample_is_reproducible() {
+        let options = SampleOptions {
+            size: 5,
+            strategy: SampleStrategy::Random,
+            seed: 42,
+        };
+        let a = select(files(&[("src", 50)]), &options);
+        let b = select(files(&[("src", 50)]), &options);
+        assert_eq!(a.len(), 5);
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn stratified_sample_is_proportional() {
+        let options = SampleOptions {
+            size: 10,
+            strategy: SampleStrategy::Stratified,
+            seed: 7,
+        };
+        let picked = select(files(&[("a", 80), ("b", 15), ("c", 5)]), &options);
+        let in_dir = |d: &str| picked.iter().filter(|p| p.starts_with(d)).count();
+        assert_eq!(picked.len(), 10);
+        assert_eq!(in_dir("a"), 8);
+        assert_eq!(in_dir("b"), 2);
+        assert_eq!(in_dir("c"), 0);
+    }
+
+    #[test]
+    fn sample_larger_than_population_keeps_everything() {
+        let options = SampleOptions {
+            size: 100,
+            strategy: SampleStrategy::Random,
+            seed: 1,
+        };
+        assert_eq!(select(files(&[("src", 3)]), &options).len(), 3);
+    }
+
+    #[test]
+    fn estimate_scales_and_brackets() {
+        let violations = vec![
+            Violation::test("r", "a.ts"),
+            Violation::test("r", "a.ts"),
+            Violation::test("r", "b.ts"),
+        ];
+        let estimates = estimate(&violations, 10, 100);
+        assert_eq!(estimates.len(), 1);
+        let e = &estimates[0];
+        assert_eq!(e.observed, 3);
+        assert_eq!(e.estimate, 30);
+        assert!(e.low >= 3 && e.low < 30);
+        assert!(e.high > 30);
+    }
+}
@@ -0,0 +1,89 @@
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, WINDOWS_1252};
+
+/// How many leading bytes [`looks_binary`] inspects, as git does.
+const SNIFF_LEN: usize = 8000;
+
+/// A source file's contents as text.
+#[derive(Debug, PartialEq, Eq)]
+pub struct SourceText {
+    pub text: String,
+    /// The encoding the file was decoded from; `None` for UTF-8.
+    pub encoding: Option<&'static str>,
+}
+
+/// Why a file's bytes aren't scannable text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeError {
+    /// A NUL byte near the start (and no UTF-16 byte order mark).
+    Binary,
+    /// Text in another encoding, under `strict`.
+    NotUtf8,
+}
+
+/// Decode a source file's `bytes`.
+///
+/// Files with a UTF-16 byte order mark are decoded from UTF-16, and other
+/// files that [`looks_binary`] are rejected. UTF-8 is taken as is, byte
+/// order mark included; anything else is decoded as windows-1252 (a
+/// superset of Latin-1). `strict` rejects all but UTF-8. Decoding never
+/// fails on the text itself: bytes the encoding lacks become U+FFFD.
+pub fn decode(bytes: Vec<u8>, strict: bool) -> Result<SourceText, DecodeError> {
+    // UTF-16 text is full of NULs, so its byte order mark is checked first
+    if let Some((encoding, bom_len)) = Encoding::for_bom(&bytes).filter(|(e, _)| *e == UTF_16LE || *e == UTF_16BE) {
+        if strict {
+            return Err(DecodeError::NotUtf8);
+        }
+        let (text, _) = encoding.decode_without_bom_handling(&bytes[bom_len..]);
+        return Ok(SourceText {
+            text: text.into_owned(),
+            encoding: Some(encoding.name()),
+        });
+    }
+    if looks_binary(&bytes) {
+        return Err(DecodeError::Binary);
+    }
+    let bytes = match String::from_utf8(bytes) {
+        Ok(text) => return Ok(SourceText { text, encoding: None }),
+        Err(e) => e.into_bytes(),
+    };
+    if strict {
+        return Err(DecodeError::NotUtf8);
+    }
+    let (text, _) = WINDOWS_1252.decode_without_bom_handling(&bytes);
+    Ok(SourceText {
+        text: text.into_owned(),
+        encoding: Some(WINDOWS_1252.name()),
+    })
+}
+
+/// Whether `bytes` look like binary data: a NUL byte in the first
+/// `SNIFF_LEN` bytes.
+pub fn looks_binary(bytes: &[u8]) -> bool {
+    bytes[..bytes.len().min(SNIFF_LEN)].contains(&0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_utf16_and_latin1_but_not_binary() {
+        let utf8 = decode("café\n".as_bytes().to_vec(), true).unwrap();
+        assert_eq!(utf8, SourceText { text: "café\n".into(), encoding: None });
+
+        let mut utf16 = vec![0xff, 0xfe];
+        utf16.extend("café\n".encode_utf16().flat_map(u16::to_le_bytes));
+        let decoded = decode(utf16.clone(), false).unwrap();
+        assert_eq!(decoded.text, "café\n");
+        assert_eq!(decoded.encoding, Some("UTF-16LE"));
+        assert_eq!(decode(utf16, true), Err(DecodeError::NotUtf8));
+
+        let latin1 = b"caf\xe9 // \xa9 2001\n".to_vec();
+        let decoded = decode(latin1.clone(), false).unwrap();
+        assert_eq!(decoded.text, "café // © 2001\n");
+        assert_eq!(decoded.encoding, Some("windows-1252"));
+        assert_eq!(decode(latin1, true), Err(DecodeError::NotUtf8));
+
+        assert_eq!(decode(b"\x7fELF\x02\x01\x00\x00\xff".to_vec(), false), Err(DecodeError::Binary));
+    }
+}
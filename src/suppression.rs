@@ -0,0 +1,499 @@
+use crate::config::Severity;
+use crate::line_endings;
+use crate::rules::Violation;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Opens a suppressed region: `// guardrails-disable <rule>` (or `all`).
+pub const DISABLE_MARKER: &str = "guardrails-disable";
+/// Closes the matching region: `// guardrails-enable <rule>`.
+pub const ENABLE_MARKER: &str = "guardrails-enable";
+/// Suppresses its own line: `foo(); // guardrails-disable-line <rule>`.
+pub const DISABLE_LINE_MARKER: &str = "guardrails-disable-line";
+/// Suppresses the line below: `// guardrails-disable-next-line <rule>`.
+pub const DISABLE_NEXT_LINE_MARKER: &str = "guardrails-disable-next-line";
+/// Rule id reported for a `guardrails-disable` without a matching
+/// `guardrails-enable`, or the other way round.
+pub const UNBALANCED_RULE_ID: &str = "unbalanced-suppression";
+
+/// Rule id reported for a suppression or waiver that hid nothing
+/// (`--report-suppressions`).
+pub const UNUSED_RULE_ID: &str = "unused-suppression";
+
+/// `guardrails-disable` comments of one file: `guardrails-disable` /
+/// `guardrails-enable` regions, `guardrails-disable-line` and
+/// `guardrails-disable-next-line`.
+///
+/// Regions nest: each enable closes the most recent open disable of the same
+/// rule. A disable in the file's header (above the first line of code) that
+/// is never closed disables the rule for the whole file. Any other disable
+/// that is never closed still suppresses to the end of the file, but is
+/// reported as an `unbalanced-suppression` violation, as is an enable with
+/// nothing to close.
+#[derive(Debug, Default)]
+pub struct SuppressionBlocks {
+    /// (rule id or `all`, first line, last line), 1-indexed and inclusive.
+    regions: Vec<(String, usize, usize)>,
+    /// (kind, rule id or `all`, marker line, suppressed line) for
+    /// `guardrails-disable-line` and `guardrails-disable-next-line`.
+    lines: Vec<(SuppressionKind, String, usize, usize)>,
+    /// (rule id or `all`, marker line) for file-level disables.
+    whole_file: Vec<(String, usize)>,
+    /// (line, message) for each unbalanced marker.
+    unbalanced: Vec<(usize, String)>,
+}
+
+impl SuppressionBlocks {
+    /// Whether `content` has any disable markers worth parsing.
+    pub fn mentioned_in(content: &str) -> bool {
+        content.contains(DISABLE_MARKER) || content.contains(ENABLE_MARKER)
+    }
+
+    pub fn parse(content: &str) -> Self {
+        let mut blocks = Self::default();
+        let mut open: HashMap<String, Vec<usize>> = HashMap::new();
+        let mut last_line = 0;
+        let mut first_code_line = None;
+
+        for (index, line) in line_endings::lines(content).enumerate() {
+            let line_num = index + 1;
+            last_line = line_num;
+            if first_code_line.is_none() && is_code(line) {
+                first_code_line = Some(line_num);
+            }
+            if let Some(rule) = marker_rule(line, DISABLE_NEXT_LINE_MARKER) {
+                blocks
+                    .lines
+                    .push((SuppressionKind::DisableNextLine, rule, line_num, line_num + 1));
+            } else if let Some(rule) = marker_rule(line, DISABLE_LINE_MARKER) {
+                blocks
+                    .lines
+                    .push((SuppressionKind::DisableLine, rule, line_num, line_num));
+            } else if let Some(rule) = marker_rule(line, DISABLE_MARKER) {
+                open.entry(rule).or_default().push(line_num);
+            } else if let Some(rule) = marker_rule(line, ENABLE_MARKER) {
+                match open.get_mut(&rule).and_then(Vec::pop) {
+                    Some(start) => blocks.regions.push((rule, start, line_num)),
+                    None => blocks.unbalanced.push((
+                        line_num,
+                        format!(
+                            "`{} {}` has no matching `{} {}` before it",
+                            ENABLE_MARKER, rule, DISABLE_MARKER, rule
+                        ),
+                    )),
+                }
+            }
+        }
+
+        for (rule, starts) in open {
+            for start in starts {
+                if first_code_line.is_none_or(|code| start < code) {
+                    blocks.whole_file.push((rule.clone(), start));
+                    continue;
+                }
+                blocks.unbalanced.push((
+                    start,
+                    format!(
+                        "`{} {}` is never closed by `{} {}`",
+                        DISABLE_MARKER, rule, ENABLE_MARKER, rule
+                    ),
+                ));
+                blocks.regions.push((rule.clone(), start, last_line));
+            }
+        }
+        blocks.unbalanced.sort();
+        blocks.whole_file.sort_by_key(|(_, line)| *line);
+        blocks
+    }
+
+    /// Whether `line` of `rule_id` is disabled by a region, a line comment
+    /// or a file-level disable.
+    pub fn covers(&self, rule_id: &str, line: usize) -> bool {
+        let applies = |rule: &str| rule == rule_id || rule == "all";
+        self.covers_file(rule_id)
+            || self
+                .regions
+                .iter()
+                .any(|(rule, start, end)| applies(rule) && (*start..=*end).contains(&line))
+            || self
+                .lines
+                .iter()
+                .any(|(_, rule, _, target)| applies(rule) && *target == line)
+    }
+
+    /// Whether `rule_id` is disabled for the whole file, which also hides
+    /// violations without a line.
+    pub fn covers_file(&self, rule_id: &str) -> bool {
+        self.whole_file
+            .iter()
+            .any(|(rule, _)| rule == rule_id || rule == "all")
+    }
+
+    /// One error per unbalanced marker.
+    pub fn unbalanced_violations(&self, file: &Path, content: &str) -> Vec<Violation> {
+        let lines: Vec<&str> = line_endings::lines(content).collect();
+        self.unbalanced
+            .iter()
+            .map(|(line, message)| Violation {
+                rule_id: UNBALANCED_RULE_ID.to_string(),
+                severity: Severity::Error,
+                file: file.to_path_buf(),
+                line: Some(*line),
+                column: None,
+                message: message.clone(),
+                suggest: Some("pair every guardrails-disable with a guardrails-enable for the same rule".into()),
+                source_line: lines.get(line - 1).map(|l| l.to_string()),
+                fix: None,
+                url: None,
+            })
+            .collect()
+    }
+}
+
+/// How a violation was suppressed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SuppressionKind {
+    /// `guardrails:allow-<rule>` on the violating line.
+    Allow,
+    /// `guardrails:allow-next-line <rule>` on the line above.
+    AllowNextLine,
+    /// A `guardrails-disable` region.
+    Region,
+    /// A `guardrails-disable` for the whole file.
+    File,
+    /// `guardrails-disable-line <rule>` on the violating line.
+    DisableLine,
+    /// `guardrails-disable-next-line <rule>` on the line above.
+    DisableNextLine,
+    /// An entry in the waivers file.
+    Waiver,
+}
+
+impl SuppressionKind {
+    /// How the suppression is written, for messages.
+    fn syntax(self, rule: &str) -> String {
+        match self {
+            SuppressionKind::Allow => format!("guardrails:allow-{}", rule),
+            SuppressionKind::AllowNextLine => format!("guardrails:allow-next-line {}", rule),
+            SuppressionKind::Region | SuppressionKind::File => format!("{} {}", DISABLE_MARKER, rule),
+            SuppressionKind::DisableLine => format!("{} {}", DISABLE_LINE_MARKER, rule),
+            SuppressionKind::DisableNextLine => format!("{} {}", DISABLE_NEXT_LINE_MARKER, rule),
+            SuppressionKind::Waiver => format!("waiver for '{}'", rule),
+        }
+    }
+}
+
+/// One suppression and how many violations it hid, for `--report-suppressions`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SuppressionUse {
+    /// File holding the comment, or the waivers file.
+    pub file: PathBuf,
+    /// Line of the comment; `None` for waivers.
+    pub line: Option<usize>,
+    pub kind: SuppressionKind,
+    /// Rule id, or `all`.
+    pub rule: String,
+    /// The waiver's path glob.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    pub suppressed: usize,
+}
+
+impl SuppressionUse {
+    /// Whether the suppression hid nothing and can be removed.
+    pub fn is_unused(&self) -> bool {
+        self.suppressed == 0
+    }
+
+    /// A warning asking for the unused suppression to be removed.
+    pub fn unused_violation(&self) -> Violation {
+        let what = match self.path {
+            Some(ref path) => format!("{} on '{}'", self.kind.syntax(&self.rule), path),
+            None => format!("`{}`", self.kind.syntax(&self.rule)),
+        };
+        Violation {
+            rule_id: UNUSED_RULE_ID.to_string(),
+            severity: Severity::Warning,
+            file: self.file.clone(),
+            line: self.line,
+            column: None,
+            message: format!("{} no longer suppresses anything", what),
+            suggest: Some("remove it".into()),
+            source_line: None,
+            fix: None,
+            url: None,
+        }
+    }
+}
+
+/// Every inline suppression in one file, counting the violations each hides.
+#[derive(Debug)]
+pub struct SuppressionAudit {
+    /// (use, first covered line, last covered line)
+    entries: Vec<(SuppressionUse, usize, usize)>,
+    /// The violations the suppressions hid, in the order they were recorded.
+    hidden: Vec<Violation>,
+}
+
+impl SuppressionAudit {
+    pub fn new(file: &Path, content: &str) -> Self {
+        let mut entries = Vec::new();
+        let mut add = |line: usize, kind, rule: String, first: usize, last: usize| {
+            let entry = SuppressionUse {
+                file: file.to_path_buf(),
+                line: Some(line),
+                kind,
+                rule,
+                path: None,
+                suppressed: 0,
+            };
+            entries.push((entry, first, last));
+        };
+
+        for (index, line) in line_endings::lines(content).enumerate() {
+            let line_num = index + 1;
+            for (pos, _) in line.match_indices(ALLOW_PREFIX) {
+                let rest = &line[pos + ALLOW_PREFIX.len()..];
+                if let Some(after) = rest.strip_prefix("next-line") {
+                    let rule = rule_token(after.trim_start());
+                    add(line_num, SuppressionKind::AllowNextLine, rule, line_num + 1, line_num + 1);
+                } else {
+                    add(line_num, SuppressionKind::Allow, rule_token(rest), line_num, line_num);
+                }
+            }
+        }
+        if SuppressionBlocks::mentioned_in(content) {
+            let blocks = SuppressionBlocks::parse(content);
+            for (rule, start, end) in blocks.regions {
+                add(start, SuppressionKind::Region, rule, start, end);
+            }
+            for (kind, rule, line, target) in blocks.lines {
+                add(line, kind, rule, target, target);
+            }
+            // Line 0 stands for violations without a line
+            for (rule, line) in blocks.whole_file {
+                add(line, SuppressionKind::File, rule, 0, usize::MAX);
+            }
+        }
+        entries.sort_by_key(|(u, _, _)| u.line);
+
+        Self {
+            entries,
+            hidden: Vec::new(),
+        }
+    }
+
+    /// Credit a suppressed violation (of its rule, or a deprecated id it
+    /// replaces) on `line` (0 for none) to every suppression covering it.
+    pub fn record(&mut self, violation: &Violation, legacy_ids: &[String], line: usize) {
+        for (entry, first, last) in &mut self.entries {
+            let rule_matches = entry.rule == "all"
+                || entry.rule == violation.rule_id
+                || legacy_ids.contains(&entry.rule);
+            if rule_matches && (*first..=*last).contains(&line) {
+                entry.suppressed += 1;
+            }
+        }
+        self.hidden.push(violation.clone());
+    }
+
+    /// The violations recorded so far, for `--audit-sample`.
+    pub fn take_hidden(&mut self) -> Vec<Violation> {
+        std::mem::take(&mut self.hidden)
+    }
+
+    pub fn into_uses(self) -> Vec<SuppressionUse> {
+        self.entries.into_iter().map(|(u, _, _)| u).collect()
+    }
+}
+
+const ALLOW_PREFIX: &str = "guardrails:allow-";
+
+/// The rule id at the start of `text`, `all` when there is none.
+fn rule_token(text: &str) -> String {
+    let rule: String = text
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '_')
+        .collect();
+    if rule.is_empty() {
+        "all".to_string()
+    } else {
+        rule
+    }
+}
+
+/// Whether `line` holds code rather than nothing or only a comment. Lines
+/// above the first one that does form the file's header.
+fn is_code(line: &str) -> bool {
+    let line = line.trim_start();
+    !line.is_empty()
+        && !["//", "#", "/*", "*", "--", "<!--", "{/*"]
+            .iter()
+            .any(|prefix| line.starts_with(prefix))
+}
+
+/// The rule named after `marker` on `line` (`all` when none is given), if the
+/// line carries that marker. `guardrails-disable-next-line` and similar
+/// longer markers don't count.
+fn marker_rule(line: &str, marker: &str) -> Option<String> {
+    let pos = line.find(marker)?;
+    let rest = &line[pos + marker.len()..];
+    if rest.chars().next().is_some_and(|c| !c.is_whitespace() && c != '*') {
+        return None;
+    }
+    Some(rule_token(rest.trim_start()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hidden(rule_id: &str, line: usize) -> Violation {
+        Violation {
+            rule_id: rule_id.into(),
+            severity: Severity::Error,
+            file: PathBuf::from("a.ts"),
+            line: (line > 0).then_some(line),
+            column: None,
+            message: "m".into(),
+            suggest: None,
+            source_line: None,
+            fix: None,
+            url: None,
+        }
+    }
+
+    #[test]
+    fn nested_regions_cover_their_rules() {
+        let content = "\
+// guardrails-disable no-fetch
+fetch(a);
+// guardrails-disable no-alert
+alert(b);
+// guardrails-enable no-alert
+alert(c);
+// guardrails-enable no-fetch
+fetch(d);
+";
+        let blocks = SuppressionBlocks::parse(content);
+        assert!(blocks.covers("no-fetch", 2));
+        assert!(blocks.covers("no-fetch", 6));
+        assert!(!blocks.covers("no-fetch", 8));
+        assert!(blocks.covers("no-alert", 4));
+        assert!(!blocks.covers("no-alert", 6));
+        assert!(blocks.unbalanced_violations(Path::new("a.ts"), content).is_empty());
+    }
+
+    #[test]
+    fn unbalanced_markers_are_violations() {
+        let content = "\
+/* guardrails-enable no-fetch */
+init();
+// guardrails-disable
+fetch(a);
+";
+        let blocks = SuppressionBlocks::parse(content);
+        assert!(blocks.covers("anything", 4));
+        assert!(!blocks.covers("anything", 2));
+
+        let v = blocks.unbalanced_violations(Path::new("a.ts"), content);
+        assert_eq!(v.len(), 2);
+        assert_eq!(v[0].rule_id, UNBALANCED_RULE_ID);
+        assert_eq!(v[0].line, Some(1));
+        assert!(v[0].message.contains("no matching `guardrails-disable no-fetch`"));
+        assert_eq!(v[1].line, Some(3));
+        assert!(v[1].message.contains("never closed"));
+    }
+
+    #[test]
+    fn audit_counts_what_each_suppression_hides() {
+        let content = "\
+eval(a); // guardrails:allow-no-eval
+// guardrails:allow-next-line all
+eval(b);
+// guardrails:allow-next-line no-fetch
+ok();
+// guardrails-disable old-eval
+eval(c);
+// guardrails-enable old-eval
+";
+        let mut audit = SuppressionAudit::new(Path::new("a.ts"), content);
+        audit.record(&hidden("no-eval", 1), &[], 1);
+        audit.record(&hidden("no-eval", 3), &[], 3);
+        audit.record(&hidden("no-eval", 7), &["old-eval".to_string()], 7);
+        assert_eq!(audit.take_hidden().len(), 3);
+
+        let uses = audit.into_uses();
+        let summary: Vec<_> = uses
+            .iter()
+            .map(|u| (u.line, u.kind, u.rule.as_str(), u.suppressed))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (Some(1), SuppressionKind::Allow, "no-eval", 1),
+                (Some(2), SuppressionKind::AllowNextLine, "all", 1),
+                (Some(4), SuppressionKind::AllowNextLine, "no-fetch", 0),
+                (Some(6), SuppressionKind::Region, "old-eval", 1),
+            ]
+        );
+        let unused = uses[2].unused_violation();
+        assert_eq!(unused.rule_id, UNUSED_RULE_ID);
+        assert_eq!(
+            unused.message,
+            "`guardrails:allow-next-line no-fetch` no longer suppresses anything"
+        );
+    }
+
+    #[test]
+    fn line_and_file_disables() {
+        let content = "\
+#!/usr/bin/env node
+// guardrails-disable no-console
+/* guardrails-disable no-alert */
+
+eval(a); // guardrails-disable-line no-eval
+// guardrails-disable-next-line
+fetch(b);
+eval(c);
+";
+        let blocks = SuppressionBlocks::parse(content);
+        assert!(blocks.covers("no-eval", 5));
+        assert!(!blocks.covers("no-eval", 8));
+        assert!(blocks.covers("no-fetch", 7));
+        assert!(!blocks.covers("no-fetch", 6));
+        assert!(blocks.covers("no-console", 8));
+        assert!(blocks.covers_file("no-alert"));
+        assert!(!blocks.covers_file("no-eval"));
+        assert!(blocks.unbalanced_violations(Path::new("a.ts"), content).is_empty());
+
+        let mut audit = SuppressionAudit::new(Path::new("a.ts"), content);
+        audit.record(&hidden("no-eval", 5), &[], 5);
+        audit.record(&hidden("no-alert", 0), &[], 0);
+        let summary: Vec<_> = audit
+            .into_uses()
+            .into_iter()
+            .map(|u| (u.line, u.kind, u.rule, u.suppressed))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (Some(2), SuppressionKind::File, "no-console".to_string(), 0),
+                (Some(3), SuppressionKind::File, "no-alert".to_string(), 1),
+                (Some(5), SuppressionKind::DisableLine, "no-eval".to_string(), 1),
+                (Some(6), SuppressionKind::DisableNextLine, "all".to_string(), 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn longer_markers_are_not_regions() {
+        assert_eq!(marker_rule("// guardrails-disable-next-line x", DISABLE_MARKER), None);
+        assert_eq!(
+            marker_rule("{/* guardrails-disable no-raw-colors */}", DISABLE_MARKER).as_deref(),
+            Some("no-raw-colors")
+        );
+    }
+}
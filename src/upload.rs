@@ -0,0 +1,204 @@
+use serde::Serialize;
+use std::fmt;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+#[derive(Debug)]
+pub enum UploadError {
+    Read(std::io::Error),
+    Parse(serde_json::Error),
+    /// The file is JSON but neither `scan --format json` nor SARIF output.
+    NotAResult,
+    /// A `--header` that isn't `Name: value`.
+    InvalidHeader(String),
+    CurlNotFound,
+    /// curl ran but the request failed (network error or HTTP status >= 400).
+    RequestFailed(String),
+}
+
+impl fmt::Display for UploadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UploadError::Read(e) => write!(f, "failed to read result: {}", e),
+            UploadError::Parse(e) => write!(f, "result is not valid JSON: {}", e),
+            UploadError::NotAResult => write!(
+                f,
+                "not a guardrails result (expected output of scan --format json or --format sarif)"
+            ),
+            UploadError::InvalidHeader(h) => {
+                write!(f, "invalid header '{}' (expected \"Name: value\")", h)
+            }
+            UploadError::CurlNotFound => write!(f, "curl is not installed or not in PATH"),
+            UploadError::RequestFailed(msg) => write!(f, "upload failed: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for UploadError {}
+
+/// Where a result came from, sent alongside it.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct Metadata {
+    pub repo: Option<String>,
+    pub branch: Option<String>,
+    pub sha: Option<String>,
+}
+
+impl Metadata {
+    /// Repo, branch and commit from CI environment variables (GitHub
+    /// Actions, GitLab CI, Bitbucket Pipelines), falling back to git.
+    pub fn detect() -> Self {
+        let env = |names: &[&str]| {
+            names
+                .iter()
+                .find_map(|name| std::env::var(name).ok().filter(|v| !v.is_empty()))
+        };
+        Self {
+            repo: env(&["GITHUB_REPOSITORY", "CI_PROJECT_PATH", "BITBUCKET_REPO_FULL_NAME"])
+                .or_else(|| git(&["config", "--get", "remote.origin.url"])),
+            branch: env(&["GITHUB_HEAD_REF", "GITHUB_REF_NAME", "CI_COMMIT_REF_NAME", "BITBUCKET_BRANCH"])
+                .or_else(|| git(&["rev-parse", "--abbrev-ref", "HEAD"]).filter(|b| b != "HEAD")),
+            sha: env(&["GITHUB_SHA", "CI_COMMIT_SHA", "BITBUCKET_COMMIT"])
+                .or_else(|| git(&["rev-parse", "HEAD"])),
+        }
+    }
+}
+
+fn git(args: &[&str]) -> Option<String> {
+    let output = Command::new("git")
+        .args(args)
+        .output()
+        .ok()
+        .filter(|o| o.status.success())?;
+    let text = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!text.is_empty()).then_some(text)
+}
+
+/// The request body: the result wrapped with its format and metadata, e.g.
+/// `{"format": "sarif", "repo": …, "branch": …, "sha": …, "guardrails_version": …, "result": {…}}`.
+pub fn envelope(result_text: &str, metadata: &Metadata) -> Result<serde_json::Value, UploadError> {
+    let result: serde_json::Value = serde_json::from_str(result_text).map_err(UploadError::Parse)?;
+    let format = if result.get("runs").is_some_and(|r| r.is_array()) {
+        "sarif"
+    } else if result.get("violations").is_some() || result.get("counts").is_some() {
+        "json"
+    } else {
+        return Err(UploadError::NotAResult);
+    };
+    Ok(serde_json::json!({
+        "format": format,
+        "repo": metadata.repo,
+        "branch": metadata.branch,
+        "sha": metadata.sha,
+        "guardrails_version": env!("CARGO_PKG_VERSION"),
+        "result": result,
+    }))
+}
+
+/// POST `body` as JSON to `url` with extra `headers` (`"Name: value"`).
+///
+/// The request goes through curl, configured on stdin so tokens in headers
+/// never appear in the process list.
+pub fn post(url: &str, headers: &[String], body: &serde_json::Value) -> Result<(), UploadError> {
+    let config = curl_config(url, headers, &body.to_string())?;
+    let mut child = Command::new("curl")
+        .args(["--silent", "--show-error", "--fail-with-body", "--config", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|_| UploadError::CurlNotFound)?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(config.as_bytes()).map_err(UploadError::Read)?;
+    }
+    let output = child.wait_with_output().map_err(UploadError::Read)?;
+    if output.status.success() {
+        return Ok(());
+    }
+    let message = [output.stderr, output.stdout]
+        .iter()
+        .map(|bytes| String::from_utf8_lossy(bytes).trim().to_string())
+        .filter(|text| !text.is_empty())
+        .collect::<Vec<_>>()
+        .join(": ");
+    Err(UploadError::RequestFailed(message))
+}
+
+/// A curl config file for the request; see `curl --config`.
+fn curl_config(url: &str, headers: &[String], body: &str) -> Result<String, UploadError> {
+    let mut config = format!("url = {}\nrequest = \"POST\"\n", quote(url));
+    config.push_str("header = \"Content-Type: application/json\"\n");
+    for header in headers {
+        match header.split_once(':') {
+            Some((name, _)) if !name.trim().is_empty() => {
+                config.push_str(&format!("header = {}\n", quote(header)));
+            }
+            _ => return Err(UploadError::InvalidHeader(header.clone())),
+        }
+    }
+    config.push_str(&format!("data-binary = {}\n", quote(body)));
+    Ok(config)
+}
+
+/// A double-quoted curl config value.
+fn quote(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '\\' => quoted.push_str("\\\\"),
+            '"' => quoted.push_str("\\\""),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn envelope_detects_format_and_attaches_metadata() {
+        let metadata = Metadata {
+            repo: Some("acme/web".into()),
+            branch: Some("main".into()),
+            sha: Some("abc123".into()),
+        };
+        let body = envelope(r#"{"version": "2.1.0", "runs": []}"#, &metadata).unwrap();
+        assert_eq!(body["format"], "sarif");
+        assert_eq!(body["repo"], "acme/web");
+        assert_eq!(body["sha"], "abc123");
+        assert_eq!(body["result"]["version"], "2.1.0");
+
+        let body = envelope(r#"{"violations": [], "summary": {}}"#, &metadata).unwrap();
+        assert_eq!(body["format"], "json");
+
+        assert!(matches!(envelope(r#"{"a": 1}"#, &metadata), Err(UploadError::NotAResult)));
+        assert!(matches!(envelope("not json", &metadata), Err(UploadError::Parse(_))));
+    }
+
+    #[test]
+    fn curl_config_quotes_values_and_checks_headers() {
+        let headers = vec!["Authorization: Bearer t0k\"en".to_string()];
+        let config = curl_config("https://results.example/api", &headers, "{\"a\":\"x\\\\y\"}").unwrap();
+        assert_eq!(
+            config,
+            "url = \"https://results.example/api\"\n\
+             request = \"POST\"\n\
+             header = \"Content-Type: application/json\"\n\
+             header = \"Authorization: Bearer t0k\\\"en\"\n\
+             data-binary = \"{\\\"a\\\":\\\"x\\\\\\\\y\\\"}\"\n"
+        );
+
+        let bad = vec!["no colon".to_string()];
+        assert!(matches!(
+            curl_config("https://x", &bad, "{}"),
+            Err(UploadError::InvalidHeader(_))
+        ));
+    }
+}
@@ -0,0 +1,176 @@
+use crate::rules::freeze_window::parse_timestamp;
+use crate::rules::Violation;
+use crate::scan::compile_glob_set;
+use globset::GlobSet;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Default waivers file, used when `[guardrails] waivers` is not set.
+pub const DEFAULT_WAIVERS_FILE: &str = ".guardrails-waivers.toml";
+
+/// A time-boxed exception to one rule for the files matching `path`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Waiver {
+    pub rule: String,
+    /// Glob of the files the waiver covers.
+    pub path: String,
+    pub reason: String,
+    /// Last day (`YYYY-MM-DD`, UTC) the waiver applies.
+    pub expires: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct WaiversFile {
+    #[serde(default)]
+    waiver: Vec<Waiver>,
+}
+
+/// Read the waivers file at `path`; a missing file has no waivers.
+pub fn load(path: &Path) -> Result<Vec<Waiver>, String> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("failed to read {}: {}", path.display(), e)),
+    };
+    let file: WaiversFile = toml::from_str(&text)
+        .map_err(|e| format!("failed to parse {}: {}", path.display(), e))?;
+    Ok(file.waiver)
+}
+
+/// Append `waiver` as a `[[waiver]]` entry, creating the file if needed.
+pub fn append(path: &Path, waiver: &Waiver) -> io::Result<()> {
+    let entry = toml::to_string(&WaiversFile {
+        waiver: vec![waiver.clone()],
+    })
+    .map_err(io::Error::other)?;
+
+    let is_new = !path.exists();
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    if is_new {
+        writeln!(
+            file,
+            "# Temporary guardrails waivers. Add entries with `guardrails waive`."
+        )?;
+    }
+    write!(file, "\n{}", entry)
+}
+
+/// Unix seconds at which a waiver stops applying (the end of its expiry day).
+pub fn expiry(expires: &str) -> Option<i64> {
+    parse_timestamp(expires, true)
+}
+
+/// Compiled waivers that are still in effect.
+#[derive(Debug, Default)]
+pub struct WaiverSet {
+    /// Each waiver with its compiled path and how many violations it covered.
+    active: Vec<(Waiver, GlobSet, AtomicUsize)>,
+}
+
+impl WaiverSet {
+    /// Compile the waivers still in effect at `now` (Unix seconds). Expired
+    /// or undated waivers are skipped with a notice so they get cleaned up.
+    pub fn new(waivers: &[Waiver], now: i64) -> Result<(Self, Vec<String>), String> {
+        let mut active = Vec::new();
+        let mut notices = Vec::new();
+        for w in waivers {
+            match expiry(&w.expires) {
+                Some(end) if now < end => {
+                    let globs = compile_glob_set(std::slice::from_ref(&w.path))
+                        .map_err(|e| format!("waiver for '{}': invalid path: {}", w.rule, e))?;
+                    active.push((w.clone(), globs, AtomicUsize::new(0)));
+                }
+                Some(_) => notices.push(format!(
+                    "waiver for '{}' on '{}' expired on {}",
+                    w.rule, w.path, w.expires
+                )),
+                None => notices.push(format!(
+                    "waiver for '{}' on '{}' ignored: invalid expiry date '{}'",
+                    w.rule, w.path, w.expires
+                )),
+            }
+        }
+        Ok((Self { active }, notices))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.active.is_empty()
+    }
+
+    /// Whether an active waiver covers this violation. Every covering
+    /// waiver is credited with it.
+    pub fn covers(&self, v: &Violation) -> bool {
+        if self.active.is_empty() {
+            return false;
+        }
+        let file = v.file.to_string_lossy();
+        let mut covered = false;
+        for (w, globs, hits) in &self.active {
+            if w.rule == v.rule_id && globs.is_match(file.as_ref()) {
+                hits.fetch_add(1, Ordering::Relaxed);
+                covered = true;
+            }
+        }
+        covered
+    }
+
+    /// Each active waiver with the number of violations it covered so far.
+    pub fn usage(&self) -> Vec<(&Waiver, usize)> {
+        self.active
+            .iter()
+            .map(|(w, _, hits)| (w, hits.load(Ordering::Relaxed)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_waiver(rule: &str, path: &str, expires: &str) -> Waiver {
+        Waiver {
+            rule: rule.into(),
+            path: path.into(),
+            reason: "migration in progress".into(),
+            expires: expires.into(),
+        }
+    }
+
+    #[test]
+    fn active_waiver_covers_matching_rule_and_path() {
+        let now = expiry("2025-03-01").unwrap();
+        let (set, notices) =
+            WaiverSet::new(&[make_waiver("no-fetch", "src/legacy/**", "2025-04-01")], now).unwrap();
+        assert!(notices.is_empty());
+        assert!(set.covers(&Violation::test("no-fetch", "./src/legacy/api.ts")));
+        assert!(!set.covers(&Violation::test("no-fetch", "src/app/api.ts")));
+        assert!(!set.covers(&Violation::test("no-eval", "src/legacy/api.ts")));
+        assert_eq!(set.usage()[0].1, 1);
+    }
+
+    #[test]
+    fn expired_waiver_is_reported_not_applied() {
+        let now = expiry("2025-04-02").unwrap();
+        let (set, notices) =
+            WaiverSet::new(&[make_waiver("no-fetch", "src/**", "2025-04-01")], now).unwrap();
+        assert!(set.is_empty());
+        assert_eq!(notices, ["waiver for 'no-fetch' on 'src/**' expired on 2025-04-01"]);
+    }
+
+    #[test]
+    fn appended_waivers_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("waivers.toml");
+        assert!(load(&path).unwrap().is_empty());
+
+        let first = make_waiver("no-fetch", "src/legacy/**", "2025-04-01");
+        let second = make_waiver("no-eval", "scripts/\"odd\".js", "2025-05-01");
+        append(&path, &first).unwrap();
+        append(&path, &second).unwrap();
+
+        assert_eq!(load(&path).unwrap(), [first, second]);
+    }
+}